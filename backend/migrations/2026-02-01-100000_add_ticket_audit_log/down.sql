@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_ticket_audit_log_changed_at;
+DROP INDEX IF EXISTS idx_ticket_audit_log_ticket;
+DROP TABLE IF EXISTS ticket_audit_log;
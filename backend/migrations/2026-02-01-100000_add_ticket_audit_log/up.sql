@@ -0,0 +1,17 @@
+-- Audit trail for ticket field edits (status, priority, category)
+-- Assignment changes are already tracked in assignment_log
+
+CREATE TABLE ticket_audit_log (
+    id SERIAL PRIMARY KEY,
+    ticket_id INT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    field VARCHAR(50) NOT NULL,               -- 'status', 'priority', 'category_id'
+    old_value TEXT,
+    new_value TEXT,
+    changed_by UUID REFERENCES users(uuid) ON DELETE SET NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_ticket_audit_log_ticket ON ticket_audit_log(ticket_id);
+CREATE INDEX idx_ticket_audit_log_changed_at ON ticket_audit_log(changed_at);
+
+COMMENT ON TABLE ticket_audit_log IS 'History of status, priority and category changes made to tickets';
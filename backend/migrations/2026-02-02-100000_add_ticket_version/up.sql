@@ -0,0 +1,5 @@
+-- Optimistic concurrency: every ticket update bumps the version, and clients
+-- that send the version they last saw get a conflict if someone else got there first
+ALTER TABLE tickets ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+
+COMMENT ON COLUMN tickets.version IS 'Incremented on every update; used to detect concurrent edits';
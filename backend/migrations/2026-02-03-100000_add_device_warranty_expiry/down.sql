@@ -0,0 +1,4 @@
+DELETE FROM notification_types WHERE code = 'device_warranty_expiring';
+DROP TABLE IF EXISTS device_warranty_notifications;
+DROP INDEX IF EXISTS idx_devices_warranty_expiry_date;
+ALTER TABLE devices DROP COLUMN IF EXISTS warranty_expiry_date;
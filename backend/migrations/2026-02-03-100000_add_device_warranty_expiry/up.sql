@@ -0,0 +1,18 @@
+-- Warranty expiry tracking for devices
+
+ALTER TABLE devices ADD COLUMN warranty_expiry_date DATE;
+
+CREATE INDEX idx_devices_warranty_expiry_date ON devices(warranty_expiry_date)
+    WHERE warranty_expiry_date IS NOT NULL;
+
+-- One row per (device, expiry date) that has been notified, so the daily scan
+-- doesn't repeat itself. A renewed warranty gets a new date and notifies again.
+CREATE TABLE device_warranty_notifications (
+    device_id INT NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+    warranty_expiry_date DATE NOT NULL,
+    notified_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (device_id, warranty_expiry_date)
+);
+
+INSERT INTO notification_types (code, name, description, category, default_channels) VALUES
+    ('device_warranty_expiring', 'Device Warranty Expiring', 'When a device you own or manage is about to go out of warranty', 'device', '["in_app", "email"]');
@@ -0,0 +1,24 @@
+-- History of a device's primary user. The open row (unassigned_at IS NULL)
+-- mirrors devices.primary_user_uuid; reassigning closes it and opens a new one.
+
+CREATE TABLE device_assignment_history (
+    id SERIAL PRIMARY KEY,
+    device_id INT NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    assigned_by UUID REFERENCES users(uuid) ON DELETE SET NULL,
+    assigned_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    unassigned_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_device_assignment_history_device ON device_assignment_history(device_id);
+
+-- At most one open assignment per device
+CREATE UNIQUE INDEX idx_device_assignment_history_open
+    ON device_assignment_history(device_id) WHERE unassigned_at IS NULL;
+
+-- Open a row for existing assignments. When they started isn't known, so
+-- history begins now.
+INSERT INTO device_assignment_history (device_id, user_uuid)
+SELECT id, primary_user_uuid FROM devices WHERE primary_user_uuid IS NOT NULL;
+
+COMMENT ON TABLE device_assignment_history IS 'Who has been the primary user of each device, and when';
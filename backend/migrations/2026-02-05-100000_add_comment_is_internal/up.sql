@@ -0,0 +1,3 @@
+-- Internal comments are only visible to technicians and admins
+
+ALTER TABLE comments ADD COLUMN is_internal BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,18 @@
+-- Reusable reply templates for technicians. Bodies may contain placeholders
+-- like {{requester_name}} that are filled in from the ticket when applied.
+
+CREATE TABLE canned_responses (
+    id SERIAL PRIMARY KEY,
+    title VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    -- Only offered on tickets in this category; NULL means any ticket
+    category_id INT REFERENCES ticket_categories(id) ON DELETE SET NULL,
+    -- Shared templates are visible to every technician, personal ones only to their creator
+    is_shared BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by UUID REFERENCES users(uuid) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_canned_responses_category_id ON canned_responses(category_id);
+CREATE INDEX idx_canned_responses_created_by ON canned_responses(created_by);
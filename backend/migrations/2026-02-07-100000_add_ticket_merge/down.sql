@@ -0,0 +1,3 @@
+DELETE FROM notification_types WHERE code = 'ticket_merged';
+DROP INDEX IF EXISTS idx_tickets_merged_into_id;
+ALTER TABLE tickets DROP COLUMN IF EXISTS merged_into_id;
@@ -0,0 +1,10 @@
+-- Duplicate tickets can be merged into another ticket. The source is closed
+-- and keeps a pointer to the ticket it was merged into.
+
+ALTER TABLE tickets ADD COLUMN merged_into_id INT REFERENCES tickets(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_tickets_merged_into_id ON tickets(merged_into_id)
+    WHERE merged_into_id IS NOT NULL;
+
+INSERT INTO notification_types (code, name, description, category, default_channels) VALUES
+    ('ticket_merged', 'Ticket Merged', 'When a ticket you are involved with is merged into another ticket', 'ticket', '["in_app"]');
@@ -0,0 +1,11 @@
+-- Users who follow a ticket without being its requester or assignee.
+-- Watchers receive the same comment and status change notifications.
+
+CREATE TABLE ticket_watchers (
+    ticket_id INT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (ticket_id, user_uuid)
+);
+
+CREATE INDEX idx_ticket_watchers_user_uuid ON ticket_watchers(user_uuid);
@@ -0,0 +1,4 @@
+ALTER TABLE assignment_rules DROP COLUMN IF EXISTS trigger_on_sla_breach;
+DROP TABLE IF EXISTS ticket_sla_breaches;
+ALTER TABLE tickets DROP COLUMN IF EXISTS first_response_at;
+DROP TABLE IF EXISTS sla_targets;
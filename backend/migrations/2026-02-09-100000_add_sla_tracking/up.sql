@@ -0,0 +1,33 @@
+-- SLA targets per priority, the time of each ticket's first technician
+-- response, and a record of breaches so each one is escalated only once.
+
+CREATE TABLE sla_targets (
+    priority ticket_priority PRIMARY KEY,
+    first_response_minutes INT CHECK (first_response_minutes > 0),
+    resolution_minutes INT CHECK (resolution_minutes > 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE tickets ADD COLUMN first_response_at TIMESTAMPTZ;
+
+-- Backfill from the earliest public technician/admin comment
+UPDATE tickets t
+SET first_response_at = fr.responded_at
+FROM (
+    SELECT c.ticket_id, MIN(c.created_at) AS responded_at
+    FROM comments c
+    JOIN users u ON u.uuid = c.user_uuid
+    WHERE u.role IN ('admin', 'technician') AND NOT c.is_internal
+    GROUP BY c.ticket_id
+) fr
+WHERE fr.ticket_id = t.id;
+
+CREATE TABLE ticket_sla_breaches (
+    ticket_id INT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    metric VARCHAR(32) NOT NULL,              -- 'first_response', 'resolution'
+    breached_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (ticket_id, metric)
+);
+
+-- Assignment rules can reassign tickets that breach their SLA
+ALTER TABLE assignment_rules ADD COLUMN trigger_on_sla_breach BOOLEAN NOT NULL DEFAULT FALSE;
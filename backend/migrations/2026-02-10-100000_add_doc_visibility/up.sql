@@ -0,0 +1,13 @@
+-- Group-based visibility for documentation pages, mirroring category_group_visibility
+-- If a page has NO entries here -> visible to ALL users (public)
+-- If a page has entries here -> only visible to users in those groups (and admins)
+CREATE TABLE doc_group_visibility (
+    page_id INT NOT NULL REFERENCES documentation_pages(id) ON DELETE CASCADE,
+    group_id INT NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by UUID REFERENCES users(uuid) ON DELETE SET NULL,
+    PRIMARY KEY (page_id, group_id)
+);
+
+CREATE INDEX idx_doc_visibility_page ON doc_group_visibility(page_id);
+CREATE INDEX idx_doc_visibility_group ON doc_group_visibility(group_id);
@@ -0,0 +1,9 @@
+DELETE FROM plugin_data WHERE user_uuid IS NOT NULL;
+
+DROP INDEX IF EXISTS idx_plugin_data_user_key;
+DROP INDEX IF EXISTS idx_plugin_data_global_key;
+ALTER TABLE plugin_data ADD CONSTRAINT plugin_data_plugin_id_data_type_key_key UNIQUE (plugin_id, data_type, key);
+
+ALTER TABLE plugin_data
+    DROP CONSTRAINT IF EXISTS plugin_data_user_scope_check,
+    DROP COLUMN IF EXISTS user_uuid;
@@ -0,0 +1,12 @@
+-- Per-user plugin storage: entries with a user_uuid belong to that user,
+-- entries without one stay in the plugin's global namespace
+ALTER TABLE plugin_data
+    ADD COLUMN user_uuid UUID REFERENCES users(uuid) ON DELETE CASCADE,
+    ADD CONSTRAINT plugin_data_user_scope_check CHECK (user_uuid IS NULL OR data_type = 'storage');
+
+-- Keys are unique per plugin/type globally, and per user for user entries
+ALTER TABLE plugin_data DROP CONSTRAINT plugin_data_plugin_id_data_type_key_key;
+CREATE UNIQUE INDEX idx_plugin_data_global_key
+    ON plugin_data(plugin_id, data_type, key) WHERE user_uuid IS NULL;
+CREATE UNIQUE INDEX idx_plugin_data_user_key
+    ON plugin_data(plugin_id, data_type, key, user_uuid) WHERE user_uuid IS NOT NULL;
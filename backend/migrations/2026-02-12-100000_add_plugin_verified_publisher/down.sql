@@ -0,0 +1 @@
+ALTER TABLE plugins DROP COLUMN IF EXISTS verified_publisher;
@@ -0,0 +1,2 @@
+-- Publisher whose signature verified the plugin (official plugins only)
+ALTER TABLE plugins ADD COLUMN verified_publisher VARCHAR(100);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_notifications_unread_group;
+ALTER TABLE notifications DROP COLUMN IF EXISTS collapsed_count;
+ALTER TABLE notifications DROP COLUMN IF EXISTS group_key;
@@ -0,0 +1,9 @@
+-- Repeated notifications about the same entity collapse into one unread row.
+-- group_key identifies the entity notifications are grouped by (comments
+-- group by their ticket); collapsed_count is how many notifications were collapsed.
+ALTER TABLE notifications ADD COLUMN group_key VARCHAR(100);
+ALTER TABLE notifications ADD COLUMN collapsed_count INTEGER NOT NULL DEFAULT 1;
+
+CREATE INDEX idx_notifications_unread_group
+    ON notifications (user_uuid, notification_type_id, group_key)
+    WHERE is_read = false;
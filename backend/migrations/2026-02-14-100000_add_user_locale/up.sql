@@ -0,0 +1,2 @@
+-- Preferred language for notifications (NULL uses the default, English)
+ALTER TABLE users ADD COLUMN locale VARCHAR(10);
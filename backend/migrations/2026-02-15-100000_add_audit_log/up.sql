@@ -0,0 +1,16 @@
+-- Unified audit trail for security-relevant actions (logins, MFA and role
+-- changes, secret writes). Entries outlive the actor, so deleting a user
+-- keeps their history with a NULL actor.
+CREATE TABLE audit_log (
+    id SERIAL PRIMARY KEY,
+    actor_uuid UUID REFERENCES users(uuid) ON DELETE SET NULL,
+    action VARCHAR(50) NOT NULL,
+    target_type VARCHAR(50),
+    target_id VARCHAR(255),
+    metadata JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_log_created_at ON audit_log (created_at DESC);
+CREATE INDEX idx_audit_log_actor ON audit_log (actor_uuid, created_at DESC);
+CREATE INDEX idx_audit_log_action ON audit_log (action, created_at DESC);
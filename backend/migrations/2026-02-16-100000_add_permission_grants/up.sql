@@ -0,0 +1,14 @@
+-- Extra permissions granted to a user or to every member of a group, on top
+-- of the bundle their role carries. Grants are additive only.
+CREATE TABLE permission_grants (
+    id SERIAL PRIMARY KEY,
+    permission VARCHAR(50) NOT NULL,
+    user_uuid UUID REFERENCES users(uuid) ON DELETE CASCADE,
+    group_id INTEGER REFERENCES groups(id) ON DELETE CASCADE,
+    granted_by UUID REFERENCES users(uuid) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT permission_grants_one_subject CHECK ((user_uuid IS NULL) <> (group_id IS NULL))
+);
+
+CREATE UNIQUE INDEX idx_permission_grants_user ON permission_grants (user_uuid, permission) WHERE user_uuid IS NOT NULL;
+CREATE UNIQUE INDEX idx_permission_grants_group ON permission_grants (group_id, permission) WHERE group_id IS NOT NULL;
@@ -0,0 +1,5 @@
+-- Group managers get elevated rights over tickets in categories visible to
+-- their group (e.g. reassigning), without being technicians.
+ALTER TABLE user_groups ADD COLUMN is_manager BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_user_groups_managers ON user_groups (user_uuid) WHERE is_manager;
@@ -0,0 +1,3 @@
+-- Tickets on legal hold keep their attachments regardless of the
+-- attachment retention policy.
+ALTER TABLE tickets ADD COLUMN legal_hold BOOLEAN NOT NULL DEFAULT FALSE;
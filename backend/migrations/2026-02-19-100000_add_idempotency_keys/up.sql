@@ -0,0 +1,16 @@
+-- Idempotency keys for create endpoints. A retried POST carrying the same
+-- Idempotency-Key replays the stored response instead of creating a
+-- duplicate. Rows are short-lived and purged once past the replay window.
+CREATE TABLE idempotency_keys (
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    scope VARCHAR(255) NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL,
+    request_hash VARCHAR(64) NOT NULL,
+    resource_id INTEGER,
+    response_status INTEGER,
+    response_body TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_uuid, scope, idempotency_key)
+);
+
+CREATE INDEX idx_idempotency_keys_created_at ON idempotency_keys (created_at);
@@ -0,0 +1,3 @@
+ALTER TABLE ticket_categories
+    DROP COLUMN IF EXISTS default_group_id,
+    DROP COLUMN IF EXISTS default_assignee_uuid;
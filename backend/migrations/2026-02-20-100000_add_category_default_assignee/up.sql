@@ -0,0 +1,6 @@
+-- Default owner for each category. When no assignment rule matches a ticket,
+-- it is assigned to the category's default user, or else to a member of its
+-- default group.
+ALTER TABLE ticket_categories
+    ADD COLUMN default_assignee_uuid UUID REFERENCES users(uuid) ON DELETE SET NULL,
+    ADD COLUMN default_group_id INTEGER REFERENCES groups(id) ON DELETE SET NULL;
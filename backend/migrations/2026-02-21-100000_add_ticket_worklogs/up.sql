@@ -0,0 +1,15 @@
+-- Time logged against tickets by technicians. `logged_at` is when the work
+-- was done, which may be earlier than when the entry was recorded.
+
+CREATE TABLE ticket_worklogs (
+    id SERIAL PRIMARY KEY,
+    ticket_id INT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    minutes INT NOT NULL CHECK (minutes > 0),
+    note TEXT,
+    logged_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_ticket_worklogs_ticket_id ON ticket_worklogs(ticket_id);
+CREATE INDEX idx_ticket_worklogs_logged_at ON ticket_worklogs(logged_at);
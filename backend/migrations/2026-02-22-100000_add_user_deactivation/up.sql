@@ -0,0 +1,6 @@
+-- Deactivated users are kept as tombstones so tickets, comments and other
+-- history they're attributed to still resolve a name. They can't sign in.
+
+ALTER TABLE users ADD COLUMN deactivated_at TIMESTAMPTZ;
+
+CREATE INDEX idx_users_deactivated_at ON users(deactivated_at) WHERE deactivated_at IS NOT NULL;
@@ -0,0 +1,2 @@
+-- IANA zone calendar date filters are interpreted in (NULL uses DEFAULT_TIMEZONE)
+ALTER TABLE users ADD COLUMN timezone VARCHAR(64);
@@ -0,0 +1,2 @@
+ALTER TABLE user_emails DROP COLUMN IF EXISTS complained_at;
+ALTER TABLE user_emails DROP COLUMN IF EXISTS bounced_at;
@@ -0,0 +1,5 @@
+-- Addresses our email provider reported as hard-bounced or as having filed a
+-- spam complaint. Notification email skips them until an admin clears the flag.
+
+ALTER TABLE user_emails ADD COLUMN bounced_at TIMESTAMPTZ;
+ALTER TABLE user_emails ADD COLUMN complained_at TIMESTAMPTZ;
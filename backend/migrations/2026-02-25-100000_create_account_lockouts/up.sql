@@ -0,0 +1,10 @@
+-- Failed password logins per account. Once too many fail within the window
+-- the account is locked until locked_until; a successful login or an admin
+-- unlock deletes the row.
+CREATE TABLE account_lockouts (
+    user_uuid UUID PRIMARY KEY REFERENCES users(uuid) ON DELETE CASCADE,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    window_started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_until TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
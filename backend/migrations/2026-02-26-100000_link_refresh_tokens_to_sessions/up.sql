@@ -0,0 +1,7 @@
+-- Each refresh token belongs to the session its login created, so revoking
+-- the session also stops it being refreshed. Tokens issued before this start
+-- a new session on their next refresh.
+ALTER TABLE refresh_tokens
+    ADD COLUMN session_id INTEGER REFERENCES active_sessions(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_refresh_tokens_session_id ON refresh_tokens(session_id);
@@ -0,0 +1,2 @@
+DELETE FROM notification_types WHERE code = 'new_device_sign_in';
+DROP TABLE IF EXISTS known_devices;
@@ -0,0 +1,13 @@
+-- Coarse device fingerprints (browser family, OS and, when a geo lookup is
+-- configured, country and ASN) each user has signed in from. A sign-in from a
+-- fingerprint not listed here notifies the user.
+CREATE TABLE known_devices (
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    fingerprint VARCHAR(255) NOT NULL,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_uuid, fingerprint)
+);
+
+INSERT INTO notification_types (code, name, description, category, default_channels) VALUES
+    ('new_device_sign_in', 'New Device Sign-In', 'When your account signs in from a device or location it hasn''t used before', 'security', '["in_app", "email"]');
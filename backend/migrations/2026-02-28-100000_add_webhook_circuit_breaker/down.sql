@@ -0,0 +1,3 @@
+ALTER TABLE webhooks
+    DROP COLUMN IF EXISTS circuit_opened_at,
+    DROP COLUMN IF EXISTS consecutive_timeouts;
@@ -0,0 +1,6 @@
+-- Circuit breaker for slow webhook consumers. Consecutive timeouts open the
+-- circuit; while it is open deliveries fail fast, and once the cooldown has
+-- passed the next delivery tests whether the consumer has recovered.
+ALTER TABLE webhooks
+    ADD COLUMN consecutive_timeouts INT NOT NULL DEFAULT 0,
+    ADD COLUMN circuit_opened_at TIMESTAMPTZ;
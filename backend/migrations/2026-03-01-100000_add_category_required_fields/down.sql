@@ -0,0 +1,5 @@
+ALTER TABLE tickets
+    DROP COLUMN IF EXISTS custom_fields;
+
+ALTER TABLE ticket_categories
+    DROP COLUMN IF EXISTS required_fields;
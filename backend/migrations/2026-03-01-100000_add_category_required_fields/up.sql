@@ -0,0 +1,9 @@
+-- Fields a ticket must have to be created in a category, e.g.
+-- [{"key": "device", "label": "Affected device", "kind": "device"},
+--  {"key": "system_name", "label": "System name", "kind": "text"}].
+-- Values of text fields are kept in the ticket's custom_fields.
+ALTER TABLE ticket_categories
+    ADD COLUMN required_fields JSONB NOT NULL DEFAULT '[]';
+
+ALTER TABLE tickets
+    ADD COLUMN custom_fields JSONB NOT NULL DEFAULT '{}';
@@ -0,0 +1,15 @@
+ALTER TABLE tickets ADD COLUMN custom_fields JSONB NOT NULL DEFAULT '{}';
+
+UPDATE tickets
+SET custom_fields = fields.values
+FROM (
+    SELECT ticket_custom_field_values.ticket_id,
+           jsonb_object_agg(custom_field_definitions.key, ticket_custom_field_values.value) AS values
+    FROM ticket_custom_field_values
+    JOIN custom_field_definitions ON custom_field_definitions.id = ticket_custom_field_values.field_id
+    GROUP BY ticket_custom_field_values.ticket_id
+) AS fields
+WHERE tickets.id = fields.ticket_id;
+
+DROP TABLE IF EXISTS ticket_custom_field_values;
+DROP TABLE IF EXISTS custom_field_definitions;
@@ -0,0 +1,63 @@
+-- Custom ticket fields. A definition with a category_id only applies to
+-- tickets in that category; without one it applies to every ticket.
+-- Values are stored as text in a canonical form for their type (numbers
+-- without trailing zeros, dates as YYYY-MM-DD, checkboxes as true/false).
+CREATE TABLE custom_field_definitions (
+    id SERIAL PRIMARY KEY,
+    key VARCHAR(50) NOT NULL UNIQUE,
+    label VARCHAR(100) NOT NULL,
+    field_type VARCHAR(20) NOT NULL,
+    -- Choices of a select field
+    options JSONB NOT NULL DEFAULT '[]',
+    category_id INT REFERENCES ticket_categories(id) ON DELETE CASCADE,
+    display_order INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by UUID REFERENCES users(uuid) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_custom_field_definitions_category ON custom_field_definitions(category_id);
+
+CREATE TABLE ticket_custom_field_values (
+    ticket_id INT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    field_id INT NOT NULL REFERENCES custom_field_definitions(id) ON DELETE CASCADE,
+    value TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_by UUID REFERENCES users(uuid) ON DELETE SET NULL,
+    PRIMARY KEY (ticket_id, field_id)
+);
+
+-- Ticket list filters look tickets up by field value
+CREATE INDEX idx_ticket_custom_field_values_lookup ON ticket_custom_field_values(field_id, value);
+
+-- Categories' required text fields become text field definitions scoped to
+-- their category, and the values tickets stored for them move over. Keys are
+-- unique, so a key several categories require becomes one field for all
+-- tickets.
+WITH text_fields AS (
+    SELECT ticket_categories.id AS category_id, field->>'key' AS key, field->>'label' AS label
+    FROM ticket_categories, jsonb_array_elements(required_fields) AS field
+    WHERE field->>'kind' = 'text'
+),
+key_categories AS (
+    SELECT key, COUNT(DISTINCT category_id) AS categories
+    FROM text_fields
+    GROUP BY key
+)
+INSERT INTO custom_field_definitions (key, label, field_type, category_id)
+SELECT DISTINCT ON (text_fields.key)
+       text_fields.key,
+       text_fields.label,
+       'text',
+       CASE WHEN key_categories.categories = 1 THEN text_fields.category_id END
+FROM text_fields
+JOIN key_categories ON key_categories.key = text_fields.key
+ORDER BY text_fields.key, text_fields.category_id
+ON CONFLICT (key) DO NOTHING;
+
+INSERT INTO ticket_custom_field_values (ticket_id, field_id, value)
+SELECT tickets.id, custom_field_definitions.id, btrim(entry.value)
+FROM tickets, jsonb_each_text(tickets.custom_fields) AS entry, custom_field_definitions
+WHERE custom_field_definitions.key = entry.key AND btrim(entry.value) <> '';
+
+ALTER TABLE tickets DROP COLUMN custom_fields;
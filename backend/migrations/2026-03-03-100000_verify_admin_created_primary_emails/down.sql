@@ -0,0 +1,3 @@
+-- Data backfill; which addresses were verified by it isn't recorded, so
+-- there is nothing to undo.
+SELECT 1;
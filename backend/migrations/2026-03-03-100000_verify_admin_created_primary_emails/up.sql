@@ -0,0 +1,12 @@
+-- Users an admin created with a password got an unverified primary email,
+-- and notification email only goes to verified addresses. Invited users are
+-- verified when they accept, so only password holders need the backfill.
+UPDATE user_emails
+SET is_verified = TRUE, updated_at = NOW()
+WHERE is_primary
+  AND NOT is_verified
+  AND EXISTS (
+      SELECT 1 FROM user_auth_identities
+      WHERE user_auth_identities.user_uuid = user_emails.user_uuid
+        AND user_auth_identities.password_hash IS NOT NULL
+  );
@@ -5,8 +5,8 @@ use std::path::Path;
 // Import from the parent crate
 extern crate backend;
 use backend::db;
-use backend::models::TicketsJson;
 use backend::repository;
+use backend::services::ticket_json;
 
 fn main() {
     // Get the JSON file path from command line arguments
@@ -28,14 +28,24 @@ fn main() {
         }
     };
 
-    // Parse the JSON
-    let tickets_json: TicketsJson = match serde_json::from_str(&json_content) {
-        Ok(tickets) => tickets,
+    // Parse the JSON and check it against the ticket schema
+    let document: serde_json::Value = match serde_json::from_str(&json_content) {
+        Ok(document) => document,
         Err(e) => {
             println!("Failed to parse JSON: {e}");
             return;
         }
     };
+    let tickets_json = match ticket_json::parse(&document) {
+        Ok(tickets) => tickets,
+        Err(errors) => {
+            println!("Invalid ticket import:");
+            for error in errors {
+                println!("  {}: {}", error.field, error.message);
+            }
+            return;
+        }
+    };
 
     // Establish database connection
     let pool = db::establish_connection_pool();
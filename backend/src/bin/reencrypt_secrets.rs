@@ -0,0 +1,38 @@
+//! Re-encrypt stored plugin, webhook and user MFA secrets with the current ENCRYPTION_KEY.
+//! Also encrypts webhook secrets stored as plaintext by older versions.
+//!
+//! Usage after rotating the key:
+//!   ENCRYPTION_KEY=<new> ENCRYPTION_KEY_ID=k2 ENCRYPTION_RETIRED_KEYS=k1:<old> reencrypt_secrets
+
+extern crate backend;
+use backend::db;
+use backend::utils::encryption;
+
+fn main() {
+    dotenv::dotenv().ok();
+
+    let pool = db::establish_connection_pool();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(e) => {
+            eprintln!("Failed to get database connection: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    match encryption::reencrypt_all_secrets(&mut conn) {
+        Ok(summary) => {
+            println!(
+                "Re-encrypted {} secret(s), {} already current, {} failed",
+                summary.reencrypted, summary.skipped, summary.failed
+            );
+            if summary.failed > 0 {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("Re-encryption failed: {e}");
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 /// Configuration utilities for the application
 use std::env;
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub enum ConfigError {
@@ -42,7 +43,9 @@ pub fn get_microsoft_redirect_uri() -> Result<String, ConfigError> {
 
 /// Check if OIDC is enabled (minimum required vars are set)
 pub fn is_oidc_enabled() -> bool {
-    env::var("OIDC_CLIENT_ID").is_ok() && env::var("OIDC_CLIENT_SECRET").is_ok()
+    FeatureFlags::from_env().is_enabled(Feature::Oidc)
+        && env::var("OIDC_CLIENT_ID").is_ok()
+        && env::var("OIDC_CLIENT_SECRET").is_ok()
 }
 
 /// Get OIDC client ID
@@ -98,4 +101,682 @@ pub fn get_oidc_username_claim() -> String {
 /// Get OIDC logout URI (optional)
 pub fn get_oidc_logout_uri() -> Option<String> {
     env::var("OIDC_LOGOUT_URI").ok()
-} 
\ No newline at end of file
+} 
+
+/// Shared secret for email provider bounce/complaint callbacks. The feedback
+/// endpoint is disabled while unset.
+pub fn get_email_feedback_secret() -> Option<String> {
+    env_lookup("EMAIL_FEEDBACK_SECRET")
+}
+
+// ===== Startup Validation =====
+
+/// Reads a configuration value by name (the process environment in
+/// production, a map in tests)
+pub type EnvLookup<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// How serious a configuration problem is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The server would fail or be insecure; startup is aborted
+    Error,
+    /// Works, but probably not what was intended
+    Warning,
+}
+
+/// A configuration problem found at startup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    pub severity: Severity,
+    pub key: &'static str,
+    pub message: String,
+}
+
+impl ConfigProblem {
+    fn error(key: &'static str, message: impl Into<String>) -> Self {
+        Self { severity: Severity::Error, key, message: message.into() }
+    }
+
+    fn warning(key: &'static str, message: impl Into<String>) -> Self {
+        Self { severity: Severity::Warning, key, message: message.into() }
+    }
+}
+
+impl std::fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// Settings shown in the startup config dump, in display order
+const DUMPED_SETTINGS: &[&str] = &[
+    "ENVIRONMENT",
+    "HOST",
+    "PORT",
+    "FRONTEND_URL",
+    "DATABASE_URL",
+    "REDIS_URL",
+    "JWT_SECRET",
+    "ENCRYPTION_KEY",
+    "MFA_ENCRYPTION_KEY",
+    "ENCRYPTION_KEY_ID",
+    "ENCRYPTION_RETIRED_KEYS",
+    "WEBAUTHN_RP_ID",
+    "WEBAUTHN_RP_ORIGIN",
+    "STORAGE_PATH",
+    "UPLOAD_DIR",
+    "UPLOADS_DIR",
+    "PLUGINS_DIR",
+    "SEARCH_INDEX_PATH",
+    "SMTP_ENABLED",
+    "SMTP_HOST",
+    "SMTP_USERNAME",
+    "SMTP_PASSWORD",
+    "EMAIL_FEEDBACK_SECRET",
+    "LOGIN_LOCKOUT_MAX_ATTEMPTS",
+    "LOGIN_LOCKOUT_WINDOW_SECONDS",
+    "LOGIN_LOCKOUT_DURATION_SECONDS",
+    "LOGIN_LOCKOUT_SCOPE",
+    "GEO_COUNTRY_HEADER",
+    "GEO_ASN_HEADER",
+    "MICROSOFT_CLIENT_ID",
+    "MICROSOFT_TENANT_ID",
+    "MICROSOFT_CLIENT_SECRET",
+    "OIDC_CLIENT_ID",
+    "OIDC_CLIENT_SECRET",
+    "OIDC_ISSUER_URL",
+    "FEATURE_WEBHOOKS",
+    "FEATURE_PLUGINS",
+    "FEATURE_PASSKEYS",
+    "FEATURE_OIDC",
+];
+
+/// Settings holding a URL that may embed a password
+const URL_SETTINGS: &[&str] = &["DATABASE_URL", "REDIS_URL"];
+
+fn env_lookup(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+fn is_production(lookup: EnvLookup) -> bool {
+    lookup("ENVIRONMENT").is_some_and(|e| e.eq_ignore_ascii_case("production"))
+}
+
+/// Check all startup configuration, returning every problem found
+///
+/// Call once at startup, before anything reads configuration lazily, so a
+/// mis-set variable is reported up front instead of failing mid-request.
+pub fn validate_all() -> Vec<ConfigProblem> {
+    let mut problems = validate_with(&env_lookup);
+    problems.extend(check_storage(
+        &crate::utils::storage::get_storage_config(),
+        is_production(&env_lookup),
+    ));
+    problems
+}
+
+/// Check the configuration in `lookup` (everything except the filesystem)
+pub fn validate_with(lookup: EnvLookup) -> Vec<ConfigProblem> {
+    let production = is_production(lookup);
+    let mut problems = Vec::new();
+
+    match lookup("DATABASE_URL") {
+        None => problems.push(ConfigProblem::error("DATABASE_URL", "is required")),
+        Some(url) if !url.starts_with("postgres://") && !url.starts_with("postgresql://") => {
+            problems.push(ConfigProblem::error("DATABASE_URL", "must be a postgres:// URL"));
+        }
+        Some(_) => {}
+    }
+
+    match lookup("JWT_SECRET") {
+        None => problems.push(ConfigProblem::error(
+            "JWT_SECRET",
+            "is required (generate with: openssl rand -base64 32)",
+        )),
+        Some(secret) if secret.len() < 32 => {
+            let message = "must be at least 32 characters";
+            problems.push(if production {
+                ConfigProblem::error("JWT_SECRET", message)
+            } else {
+                ConfigProblem::warning("JWT_SECRET", format!("{message} (would be rejected in production)"))
+            });
+        }
+        Some(_) => {}
+    }
+
+    if let Some(url) = lookup("REDIS_URL") {
+        if let Err(e) = redis::Client::open(url.as_str()) {
+            problems.push(ConfigProblem::error("REDIS_URL", format!("is not a valid Redis URL: {e}")));
+        }
+    }
+
+    let has_key = lookup("ENCRYPTION_KEY").is_some() || lookup("MFA_ENCRYPTION_KEY").is_some();
+    if has_key {
+        if let Err(e) = crate::utils::encryption::validate_key_config(lookup) {
+            problems.push(ConfigProblem::error("ENCRYPTION_KEY", e.to_string()));
+        }
+    } else if production {
+        problems.push(ConfigProblem::error(
+            "ENCRYPTION_KEY",
+            "ENCRYPTION_KEY or MFA_ENCRYPTION_KEY is required in production (generate with: openssl rand -hex 32)",
+        ));
+    } else {
+        problems.push(ConfigProblem::warning(
+            "ENCRYPTION_KEY",
+            "not set - MFA and encrypted settings are disabled",
+        ));
+    }
+
+    let flags = FeatureFlags::from_lookup(lookup);
+    if production && flags.passkeys {
+        if let Err(e) = crate::utils::webauthn::WebAuthnConfig::from_lookup(lookup) {
+            problems.push(ConfigProblem::error("WEBAUTHN", e.to_string()));
+        }
+    }
+
+    for feature in Feature::ALL {
+        if let Some(value) = lookup(feature.env_var()) {
+            if parse_flag(&value).is_none() {
+                problems.push(ConfigProblem::warning(
+                    feature.env_var(),
+                    format!("'{value}' is not a boolean - {} stays enabled", feature.as_str()),
+                ));
+            }
+        }
+    }
+
+    for name in ["LOGIN_LOCKOUT_MAX_ATTEMPTS", "LOGIN_LOCKOUT_WINDOW_SECONDS", "LOGIN_LOCKOUT_DURATION_SECONDS"] {
+        if let Some(value) = lookup(name) {
+            if value.trim().parse::<u64>().is_err() {
+                problems.push(ConfigProblem::warning(name, format!("'{value}' is not a number - the default is used")));
+            }
+        }
+    }
+    if let Some(scope) = lookup("LOGIN_LOCKOUT_SCOPE") {
+        if !["password", "all"].contains(&scope.trim().to_ascii_lowercase().as_str()) {
+            problems.push(ConfigProblem::warning(
+                "LOGIN_LOCKOUT_SCOPE",
+                format!("'{scope}' is not 'password' or 'all' - passkeys stay available while locked"),
+            ));
+        }
+    }
+
+    problems
+}
+
+/// Check that the storage backend is usable
+pub fn check_storage(
+    config: &crate::utils::storage::StorageConfig,
+    production: bool,
+) -> Vec<ConfigProblem> {
+    use crate::utils::storage::StorageConfig;
+
+    let problem = match config {
+        StorageConfig::Local { base_path } => {
+            let path = std::path::Path::new(base_path);
+            if path.exists() && !path.is_dir() {
+                Some(format!("storage path {base_path} is not a directory"))
+            } else {
+                std::fs::create_dir_all(path)
+                    .err()
+                    .map(|e| format!("storage directory {base_path} cannot be created: {e}"))
+            }
+        }
+        StorageConfig::S3 { .. } => Some("S3 storage is not implemented yet".to_string()),
+    };
+
+    problem
+        .map(|message| {
+            if production {
+                ConfigProblem::error("STORAGE_PATH", message)
+            } else {
+                ConfigProblem::warning("STORAGE_PATH", message)
+            }
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Whether a setting holds a secret that must never be logged
+fn is_secret(name: &str) -> bool {
+    ["SECRET", "PASSWORD", "TOKEN", "KEY"].iter().any(|marker| name.contains(marker))
+        && name != "ENCRYPTION_KEY_ID"
+}
+
+/// A setting's value as it may appear in logs
+fn redact(name: &str, value: &str) -> String {
+    if is_secret(name) {
+        return "<set>".to_string();
+    }
+    if URL_SETTINGS.contains(&name) {
+        return match url::Url::parse(value) {
+            Ok(mut url) => {
+                if url.password().is_some() {
+                    let _ = url.set_password(Some("****"));
+                }
+                url.to_string()
+            }
+            Err(_) => "<invalid URL>".to_string(),
+        };
+    }
+    value.to_string()
+}
+
+/// The dumped settings with secrets redacted
+pub fn redacted_dump(lookup: EnvLookup) -> Vec<(&'static str, String)> {
+    DUMPED_SETTINGS
+        .iter()
+        .map(|name| {
+            let value = lookup(name)
+                .map(|v| redact(name, &v))
+                .unwrap_or_else(|| "<not set>".to_string());
+            (*name, value)
+        })
+        .collect()
+}
+
+/// Log the effective configuration at info level, secrets redacted
+pub fn log_config_dump() {
+    let dump = redacted_dump(&env_lookup)
+        .into_iter()
+        .map(|(name, value)| format!("  {name}={value}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    tracing::info!("Effective configuration:\n{dump}");
+}
+
+// ===== Feature Flags =====
+
+/// A subsystem that can be switched off per deployment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    Webhooks,
+    Plugins,
+    Passkeys,
+    Oidc,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 4] = [Feature::Webhooks, Feature::Plugins, Feature::Passkeys, Feature::Oidc];
+
+    /// Environment variable toggling the feature
+    pub fn env_var(self) -> &'static str {
+        match self {
+            Feature::Webhooks => "FEATURE_WEBHOOKS",
+            Feature::Plugins => "FEATURE_PLUGINS",
+            Feature::Passkeys => "FEATURE_PASSKEYS",
+            Feature::Oidc => "FEATURE_OIDC",
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Feature::Webhooks => "webhooks",
+            Feature::Plugins => "plugins",
+            Feature::Passkeys => "passkeys",
+            Feature::Oidc => "oidc",
+        }
+    }
+}
+
+/// Runtime toggles for optional subsystems, read from `FEATURE_*` variables
+///
+/// Every feature is enabled unless its variable is set to a false value
+/// (`false`, `0`, `no`, `off`). Registered as app data so handlers can call
+/// [`require_feature`] before touching the subsystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureFlags {
+    pub webhooks: bool,
+    pub plugins: bool,
+    pub passkeys: bool,
+    pub oidc: bool,
+}
+
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self { webhooks: true, plugins: true, passkeys: true, oidc: true }
+    }
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+impl FeatureFlags {
+    pub fn from_env() -> Self {
+        Self::from_lookup(&env_lookup)
+    }
+
+    /// Read the flags from `lookup`; unrecognised values keep the feature enabled
+    pub fn from_lookup(lookup: EnvLookup) -> Self {
+        let mut flags = Self::default();
+        for feature in Feature::ALL {
+            let enabled = lookup(feature.env_var())
+                .and_then(|v| parse_flag(&v))
+                .unwrap_or(true);
+            flags.set(feature, enabled);
+        }
+        flags
+    }
+
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        match feature {
+            Feature::Webhooks => self.webhooks,
+            Feature::Plugins => self.plugins,
+            Feature::Passkeys => self.passkeys,
+            Feature::Oidc => self.oidc,
+        }
+    }
+
+    pub fn set(&mut self, feature: Feature, enabled: bool) {
+        match feature {
+            Feature::Webhooks => self.webhooks = enabled,
+            Feature::Plugins => self.plugins = enabled,
+            Feature::Passkeys => self.passkeys = enabled,
+            Feature::Oidc => self.oidc = enabled,
+        }
+    }
+
+    /// Features switched off in this deployment
+    pub fn disabled(&self) -> Vec<Feature> {
+        Feature::ALL.into_iter().filter(|f| !self.is_enabled(*f)).collect()
+    }
+}
+
+/// Reject the request with 404 when `feature` is disabled
+/// Returns Err(HttpResponse) so handlers can bail out before using the subsystem.
+pub fn require_feature(flags: &FeatureFlags, feature: Feature) -> Result<(), actix_web::HttpResponse> {
+    if flags.is_enabled(feature) {
+        return Ok(());
+    }
+    Err(actix_web::HttpResponse::NotFound().json(serde_json::json!({
+        "error": "Not Found",
+        "message": format!("The {} feature is disabled", feature.as_str())
+    })))
+}
+
+// ===== Plugin Paths =====
+
+/// Where plugins are provisioned from and where their bundles are stored
+///
+/// Read once at startup and registered as app data. Defaults match the
+/// container layout; set `UPLOADS_DIR` (or `UPLOAD_DIR`, which backups also
+/// read) and `PLUGINS_DIR` for local development or other deployments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginPaths {
+    /// Base uploads directory; bundles are stored under `plugins/<uuid>/`
+    pub uploads_dir: PathBuf,
+    /// Directory scanned for plugins to provision at startup
+    pub plugins_dir: PathBuf,
+}
+
+impl Default for PluginPaths {
+    fn default() -> Self {
+        Self {
+            uploads_dir: PathBuf::from("/app/uploads"),
+            plugins_dir: PathBuf::from("/app/plugins"),
+        }
+    }
+}
+
+impl PluginPaths {
+    pub fn from_env() -> Self {
+        Self::from_lookup(&env_lookup)
+    }
+
+    /// Read the paths from `lookup`; unset or empty values keep the defaults
+    pub fn from_lookup(lookup: EnvLookup) -> Self {
+        let read = |name: &str| lookup(name).filter(|v| !v.trim().is_empty()).map(PathBuf::from);
+        let defaults = Self::default();
+        Self {
+            uploads_dir: read("UPLOADS_DIR").or_else(|| read("UPLOAD_DIR")).unwrap_or(defaults.uploads_dir),
+            plugins_dir: read("PLUGINS_DIR").unwrap_or(defaults.plugins_dir),
+        }
+    }
+
+    /// Directory holding a plugin's uploaded bundle
+    pub fn bundle_dir(&self, plugin_uuid: uuid::Uuid) -> PathBuf {
+        self.uploads_dir.join("plugins").join(plugin_uuid.to_string())
+    }
+}
+
+// ===== Login Lockout =====
+
+/// When repeated failed password logins lock an account
+///
+/// Read once at startup and registered as app data. `LOGIN_LOCKOUT_SCOPE`
+/// decides what a lock blocks: `password` (the default) leaves passkey login
+/// available, `all` blocks passkeys too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoginLockoutPolicy {
+    /// Failed attempts within the window that lock the account; 0 disables locking
+    pub max_attempts: u32,
+    /// How long failed attempts count towards a lock, in seconds
+    pub window_seconds: u64,
+    /// How long the account stays locked, in seconds
+    pub lockout_seconds: u64,
+    /// Whether a locked account can still sign in with a passkey
+    pub allow_passkeys: bool,
+}
+
+impl Default for LoginLockoutPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 5, window_seconds: 900, lockout_seconds: 900, allow_passkeys: true }
+    }
+}
+
+impl LoginLockoutPolicy {
+    pub fn from_env() -> Self {
+        Self::from_lookup(&env_lookup)
+    }
+
+    /// Read the policy from `lookup`; unset or unparseable values keep the defaults
+    pub fn from_lookup(lookup: EnvLookup) -> Self {
+        let number = |name: &str| lookup(name).and_then(|v| v.trim().parse::<u64>().ok());
+        let defaults = Self::default();
+        Self {
+            max_attempts: number("LOGIN_LOCKOUT_MAX_ATTEMPTS")
+                .and_then(|n| u32::try_from(n).ok())
+                .unwrap_or(defaults.max_attempts),
+            window_seconds: number("LOGIN_LOCKOUT_WINDOW_SECONDS")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.window_seconds),
+            lockout_seconds: number("LOGIN_LOCKOUT_DURATION_SECONDS")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.lockout_seconds),
+            allow_passkeys: match lookup("LOGIN_LOCKOUT_SCOPE").map(|v| v.trim().to_ascii_lowercase()) {
+                Some(scope) if scope == "all" => false,
+                _ => defaults.allow_passkeys,
+            },
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_attempts > 0
+    }
+}
+
+// ===== Sign-in Geo Headers =====
+
+/// Request headers carrying the client's country and ASN
+///
+/// Set these when a CDN or reverse proxy in front of the app adds geo headers
+/// (e.g. Cloudflare's `CF-IPCountry`); new-device sign-in detection then
+/// tells locations apart as well as browsers. Only trust headers the proxy
+/// overwrites, since clients can send them too.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GeoHeaders {
+    pub country: Option<String>,
+    pub asn: Option<String>,
+}
+
+impl GeoHeaders {
+    pub fn from_env() -> Self {
+        Self::from_lookup(&env_lookup)
+    }
+
+    pub fn from_lookup(lookup: EnvLookup) -> Self {
+        let header = |name: &str| lookup(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        Self {
+            country: header("GEO_COUNTRY_HEADER"),
+            asn: header("GEO_ASN_HEADER"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn lookup_from(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> =
+            vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    const KEY: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn missing_and_invalid_settings_are_reported_together() {
+        let lookup = lookup_from(&[
+            ("ENVIRONMENT", "production"),
+            ("JWT_SECRET", "short"),
+            ("WEBAUTHN_RP_ID", "example.com"),
+            ("WEBAUTHN_RP_ORIGIN", "https://helpdesk.other.org"),
+        ]);
+        let problems = validate_with(&lookup);
+        let errors: Vec<&str> = problems
+            .iter()
+            .filter(|p| p.severity == Severity::Error)
+            .map(|p| p.key)
+            .collect();
+        assert_eq!(errors, vec!["DATABASE_URL", "JWT_SECRET", "ENCRYPTION_KEY", "WEBAUTHN"]);
+
+        let lookup = lookup_from(&[
+            ("ENVIRONMENT", "production"),
+            ("DATABASE_URL", "postgres://nosdesk@db/helpdesk"),
+            ("JWT_SECRET", "a-secret-that-is-at-least-32-characters"),
+            ("ENCRYPTION_KEY", KEY),
+            ("WEBAUTHN_RP_ID", "example.com"),
+            ("WEBAUTHN_RP_ORIGIN", "https://helpdesk.example.com"),
+        ]);
+        assert_eq!(validate_with(&lookup), vec![]);
+
+        // Outside production a missing key only disables MFA
+        let lookup = lookup_from(&[
+            ("DATABASE_URL", "postgres://nosdesk@db/helpdesk"),
+            ("JWT_SECRET", "a-secret-that-is-at-least-32-characters"),
+        ]);
+        let problems = validate_with(&lookup);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn config_dump_redacts_secrets() {
+        let lookup = lookup_from(&[
+            ("DATABASE_URL", "postgres://nosdesk:hunter2@db:5432/helpdesk"),
+            ("JWT_SECRET", "a-secret-that-is-at-least-32-characters"),
+            ("ENCRYPTION_KEY", KEY),
+            ("ENCRYPTION_KEY_ID", "k2"),
+            ("SMTP_PASSWORD", "mail-password"),
+            ("HOST", "0.0.0.0"),
+        ]);
+        let dump: HashMap<&str, String> = redacted_dump(&lookup).into_iter().collect();
+
+        assert_eq!(dump["DATABASE_URL"], "postgres://nosdesk:****@db:5432/helpdesk");
+        assert_eq!(dump["JWT_SECRET"], "<set>");
+        assert_eq!(dump["ENCRYPTION_KEY"], "<set>");
+        assert_eq!(dump["SMTP_PASSWORD"], "<set>");
+        assert_eq!(dump["ENCRYPTION_KEY_ID"], "k2");
+        assert_eq!(dump["HOST"], "0.0.0.0");
+        assert_eq!(dump["OIDC_CLIENT_SECRET"], "<not set>");
+
+        let printed = format!("{dump:?}");
+        for secret in ["hunter2", "a-secret-that", KEY, "mail-password"] {
+            assert!(!printed.contains(secret), "dump leaks {secret}");
+        }
+    }
+
+    #[test]
+    fn plugin_paths_default_to_the_container_layout() {
+        assert_eq!(PluginPaths::from_lookup(&lookup_from(&[])), PluginPaths::default());
+
+        let paths = PluginPaths::from_lookup(&lookup_from(&[("UPLOAD_DIR", "/srv/uploads"), ("PLUGINS_DIR", "")]));
+        assert_eq!(paths.uploads_dir, PathBuf::from("/srv/uploads"));
+        assert_eq!(paths.plugins_dir, PathBuf::from("/app/plugins"));
+
+        let paths = PluginPaths::from_lookup(&lookup_from(&[
+            ("UPLOAD_DIR", "/srv/uploads"),
+            ("UPLOADS_DIR", "/home/dev/uploads"),
+            ("PLUGINS_DIR", "/home/dev/plugins"),
+        ]));
+        assert_eq!(paths.uploads_dir, PathBuf::from("/home/dev/uploads"));
+        assert_eq!(paths.plugins_dir, PathBuf::from("/home/dev/plugins"));
+    }
+
+    #[test]
+    fn login_lockout_policy_parses_from_env() {
+        assert_eq!(LoginLockoutPolicy::from_lookup(&lookup_from(&[])), LoginLockoutPolicy::default());
+
+        let lookup = lookup_from(&[
+            ("LOGIN_LOCKOUT_MAX_ATTEMPTS", "10"),
+            ("LOGIN_LOCKOUT_WINDOW_SECONDS", "600"),
+            ("LOGIN_LOCKOUT_DURATION_SECONDS", "soon"),
+            ("LOGIN_LOCKOUT_SCOPE", "All"),
+        ]);
+        let policy = LoginLockoutPolicy::from_lookup(&lookup);
+        assert_eq!(policy.max_attempts, 10);
+        assert_eq!(policy.window_seconds, 600);
+        assert_eq!(policy.lockout_seconds, 900, "unparseable values keep the default");
+        assert!(!policy.allow_passkeys);
+
+        let warnings: Vec<&str> = validate_with(&lookup).into_iter().map(|p| p.key).collect();
+        assert!(warnings.contains(&"LOGIN_LOCKOUT_DURATION_SECONDS"));
+
+        let disabled = LoginLockoutPolicy::from_lookup(&lookup_from(&[("LOGIN_LOCKOUT_MAX_ATTEMPTS", "0")]));
+        assert!(!disabled.is_enabled());
+    }
+
+    #[test]
+    fn feature_flags_parse_from_env() {
+        assert_eq!(FeatureFlags::from_lookup(&lookup_from(&[])), FeatureFlags::default());
+
+        let lookup = lookup_from(&[
+            ("FEATURE_WEBHOOKS", "false"),
+            ("FEATURE_PLUGINS", "0"),
+            ("FEATURE_PASSKEYS", "On"),
+            ("FEATURE_OIDC", "maybe"),
+        ]);
+        let flags = FeatureFlags::from_lookup(&lookup);
+        assert!(!flags.is_enabled(Feature::Webhooks));
+        assert!(!flags.is_enabled(Feature::Plugins));
+        assert!(flags.is_enabled(Feature::Passkeys));
+        assert!(flags.is_enabled(Feature::Oidc), "unparseable values keep the feature on");
+        assert_eq!(flags.disabled(), vec![Feature::Webhooks, Feature::Plugins]);
+
+        let warnings: Vec<&str> = validate_with(&lookup)
+            .into_iter()
+            .filter(|p| p.severity == Severity::Warning)
+            .map(|p| p.key)
+            .collect();
+        assert!(warnings.contains(&"FEATURE_OIDC"));
+    }
+
+    #[actix_web::test]
+    async fn disabled_feature_guard_returns_not_found() {
+        let flags = FeatureFlags { plugins: false, ..FeatureFlags::default() };
+        assert!(require_feature(&flags, Feature::Webhooks).is_ok());
+
+        let response = require_feature(&flags, Feature::Plugins).unwrap_err();
+        assert_eq!(response.status(), actix_web::http::StatusCode::NOT_FOUND);
+        let body = actix_web::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["message"], "The plugins feature is disabled");
+    }
+}
@@ -1,10 +1,11 @@
+use diesel::connection::{Instrumentation, InstrumentationEvent};
 use diesel::pg::PgConnection;
 use diesel::r2d2::{self, ConnectionManager};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
-use diesel::RunQueryDsl;
+use diesel::{Connection, RunQueryDsl};
 use dotenv::dotenv;
 use std::env;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::sync::atomic::{AtomicBool, Ordering};
 use tracing::{info, warn, error};
 
@@ -85,6 +86,174 @@ pub fn is_initialized() -> bool {
     INITIALIZED.load(Ordering::Acquire)
 }
 
+/// Connection pool settings, read from the environment
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// Maximum open connections (DB_MAX_CONNECTIONS, default 10)
+    pub max_size: u32,
+    /// Idle connections kept open (DB_MIN_CONNECTIONS, default: same as max_size)
+    pub min_idle: Option<u32>,
+    /// How long `pool.get()` waits for a free connection before failing
+    /// (DB_CONNECTION_TIMEOUT seconds, default 30)
+    pub connection_timeout: Duration,
+    /// Checkouts waiting longer than this are logged (DB_SLOW_CHECKOUT_MS, default 500)
+    pub slow_checkout: Duration,
+    /// Queries running longer than this are logged (DB_SLOW_QUERY_MS, default 1000, 0 disables)
+    pub slow_query: Option<Duration>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 10,
+            min_idle: None,
+            connection_timeout: Duration::from_secs(30),
+            slow_checkout: Duration::from_millis(500),
+            slow_query: Some(Duration::from_millis(1000)),
+        }
+    }
+}
+
+impl PoolConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let parse = |name: &str| env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+
+        let max_size = parse("DB_MAX_CONNECTIONS")
+            .map(|v| v.clamp(1, 1000) as u32)
+            .unwrap_or(defaults.max_size);
+
+        Self {
+            max_size,
+            min_idle: parse("DB_MIN_CONNECTIONS").map(|v| (v as u32).min(max_size)),
+            connection_timeout: parse("DB_CONNECTION_TIMEOUT")
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.connection_timeout),
+            slow_checkout: parse("DB_SLOW_CHECKOUT_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.slow_checkout),
+            slow_query: match parse("DB_SLOW_QUERY_MS") {
+                Some(0) => None,
+                Some(ms) => Some(Duration::from_millis(ms)),
+                None => defaults.slow_query,
+            },
+        }
+    }
+}
+
+/// Pool event handler: records checkout metrics and warns about slow or
+/// timed-out checkouts, which mean the pool is saturated
+#[derive(Debug)]
+struct PoolMonitor {
+    slow_checkout: Duration,
+}
+
+impl r2d2::HandleEvent for PoolMonitor {
+    fn handle_checkout(&self, event: r2d2::event::CheckoutEvent) {
+        crate::services::metrics::record_db_checkout(event.duration());
+        if event.duration() >= self.slow_checkout {
+            warn!(
+                wait_ms = event.duration().as_millis() as u64,
+                threshold_ms = self.slow_checkout.as_millis() as u64,
+                "Slow database connection checkout, pool may be saturated"
+            );
+        }
+    }
+
+    fn handle_timeout(&self, event: r2d2::event::TimeoutEvent) {
+        crate::services::metrics::record_db_checkout_timeout();
+        warn!(
+            timeout_ms = event.timeout().as_millis() as u64,
+            "Timed out waiting for a database connection"
+        );
+    }
+}
+
+/// Diesel instrumentation that logs queries slower than a threshold
+struct SlowQueryLogger {
+    threshold: Duration,
+    started: Option<Instant>,
+}
+
+impl Instrumentation for SlowQueryLogger {
+    fn on_connection_event(&mut self, event: InstrumentationEvent<'_>) {
+        match event {
+            InstrumentationEvent::StartQuery { .. } => self.started = Some(Instant::now()),
+            InstrumentationEvent::FinishQuery { query, .. } => {
+                let Some(started) = self.started.take() else { return };
+                let elapsed = started.elapsed();
+                if elapsed >= self.threshold {
+                    // Bind values may hold secrets, so only the SQL is logged
+                    let query = query.to_string();
+                    let sql = query.split(" -- binds:").next().unwrap_or_default();
+                    warn!(
+                        duration_ms = elapsed.as_millis() as u64,
+                        threshold_ms = self.threshold.as_millis() as u64,
+                        sql = %sql,
+                        "Slow database query"
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Installs `SlowQueryLogger` on every new pooled connection
+#[derive(Debug)]
+struct SlowQueryCustomizer {
+    threshold: Duration,
+}
+
+impl r2d2::CustomizeConnection<PgConnection, r2d2::Error> for SlowQueryCustomizer {
+    fn on_acquire(&self, conn: &mut PgConnection) -> Result<(), r2d2::Error> {
+        conn.set_instrumentation(SlowQueryLogger {
+            threshold: self.threshold,
+            started: None,
+        });
+        Ok(())
+    }
+}
+
+/// Build a pool with the given settings
+pub fn build_pool(database_url: &str, config: &PoolConfig) -> Result<Pool, r2d2::PoolError> {
+    let manager = ConnectionManager::<PgConnection>::new(database_url);
+    let mut builder = r2d2::Pool::builder()
+        .max_size(config.max_size)
+        .min_idle(config.min_idle)
+        .connection_timeout(config.connection_timeout)
+        .event_handler(Box::new(PoolMonitor {
+            slow_checkout: config.slow_checkout,
+        }));
+
+    if let Some(threshold) = config.slow_query {
+        builder = builder.connection_customizer(Box::new(SlowQueryCustomizer { threshold }));
+    }
+
+    builder.build(manager)
+}
+
+/// Current pool utilization, for metrics and diagnostics
+pub fn pool_utilization(pool: &Pool) -> PoolUtilization {
+    let state = pool.state();
+    PoolUtilization {
+        max_size: pool.max_size(),
+        connections: state.connections,
+        idle: state.idle_connections,
+        in_use: state.connections - state.idle_connections,
+    }
+}
+
+/// Snapshot of how many pool connections are open and in use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolUtilization {
+    pub max_size: u32,
+    pub connections: u32,
+    pub idle: u32,
+    pub in_use: u32,
+}
+
 pub fn establish_connection_pool() -> Pool {
     dotenv().ok();
 
@@ -99,10 +268,15 @@ pub fn establish_connection_pool() -> Pool {
         }
     };
 
-    info!("Attempting to create database connection pool");
-    let manager = ConnectionManager::<PgConnection>::new(database_url);
+    let config = PoolConfig::from_env();
+    info!(
+        max_size = config.max_size,
+        min_idle = ?config.min_idle,
+        connection_timeout_secs = config.connection_timeout.as_secs(),
+        "Attempting to create database connection pool"
+    );
 
-    match r2d2::Pool::builder().build(manager) {
+    match build_pool(&database_url, &config) {
         Ok(pool) => {
             info!("Database connection pool created successfully");
             pool
@@ -115,4 +289,41 @@ pub fn establish_connection_pool() -> Pool {
             std::process::exit(1);
         }
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exhausted_pool_times_out_instead_of_hanging() {
+        dotenv().ok();
+        let database_url = env::var("TEST_DATABASE_URL")
+            .or_else(|_| env::var("DATABASE_URL"))
+            .expect("DATABASE_URL or TEST_DATABASE_URL must be set for tests");
+
+        let pool = build_pool(&database_url, &PoolConfig {
+            max_size: 1,
+            min_idle: Some(0),
+            connection_timeout: Duration::from_millis(250),
+            ..PoolConfig::default()
+        })
+        .unwrap();
+
+        let held = pool.get().unwrap();
+        assert_eq!(
+            pool_utilization(&pool),
+            PoolUtilization { max_size: 1, connections: 1, idle: 0, in_use: 1 }
+        );
+
+        let started = Instant::now();
+        assert!(pool.get().is_err());
+        let waited = started.elapsed();
+        assert!(waited >= Duration::from_millis(250), "gave up early: {waited:?}");
+        assert!(waited < Duration::from_secs(5), "waited too long: {waited:?}");
+
+        // The connection is usable again once released
+        drop(held);
+        assert!(pool.get().is_ok());
+    }
+}
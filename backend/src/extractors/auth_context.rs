@@ -31,8 +31,12 @@ pub struct AuthContext {
     /// User's display name
     #[allow(dead_code)]
     pub name: String,
-    /// Group IDs the user belongs to (for future group-based permissions)
+    /// Group IDs the user belongs to
     pub group_ids: Vec<i32>,
+    /// Group IDs the user is a manager of (subset of `group_ids`)
+    pub managed_group_ids: Vec<i32>,
+    /// User's preferred IANA timezone, if they've set one
+    pub timezone: Option<String>,
     /// Original JWT claims (for access to other fields if needed)
     #[allow(dead_code)]
     claims: Claims,
@@ -70,7 +74,6 @@ impl AuthContext {
 
     /// Check if user can view a specific ticket
     /// Returns true if user is admin/tech, or is the requester/assignee
-    #[allow(dead_code)]
     pub fn can_view_ticket(&self, requester_uuid: Option<Uuid>, assignee_uuid: Option<Uuid>) -> bool {
         if self.is_technician_or_admin() {
             return true;
@@ -90,6 +93,26 @@ impl AuthContext {
         group_ids.iter().any(|id| self.group_ids.contains(id))
     }
 
+    /// Check if user manages any of the specified groups
+    pub fn is_manager_of_any_group(&self, group_ids: &[i32]) -> bool {
+        group_ids.iter().any(|id| self.managed_group_ids.contains(id))
+    }
+
+    /// Check if user can manage (update, reassign) a ticket
+    /// Technicians and admins manage every ticket; group managers only tickets
+    /// whose category is visible to one of their groups. `category_group_ids`
+    /// are the groups the ticket's category is visible to (empty if none).
+    pub fn can_manage_ticket(&self, category_group_ids: &[i32]) -> bool {
+        self.is_technician_or_admin() || self.is_manager_of_any_group(category_group_ids)
+    }
+
+    /// Grant manager rights over some of the user's groups (tests only).
+    #[cfg(test)]
+    pub fn with_managed_groups(mut self, managed_group_ids: Vec<i32>) -> Self {
+        self.managed_group_ids = managed_group_ids;
+        self
+    }
+
     /// Construct an AuthContext for tests.
     #[cfg(test)]
     pub fn test_context(user_uuid: Uuid, role: UserRole, group_ids: Vec<i32>) -> Self {
@@ -98,6 +121,8 @@ impl AuthContext {
             role,
             name: "test-user".into(),
             group_ids,
+            managed_group_ids: vec![],
+            timezone: None,
             claims: Claims {
                 sub: user_uuid.to_string(),
                 name: "test-user".into(),
@@ -106,6 +131,7 @@ impl AuthContext {
                 scope: "full".into(),
                 exp: 9999999999,
                 iat: 0,
+                impersonator: None,
             },
         }
     }
@@ -181,18 +207,25 @@ impl FromRequest for AuthContext {
                 .map_err(|e| AuthContextError::DatabaseError(e.to_string()))?;
 
             // Fetch user from database to get current role and groups
+            // Deactivated users are kept for attribution but can't act
             let user = crate::repository::users::get_user_by_uuid(&user_uuid, &mut conn)
-                .map_err(|_| AuthContextError::UserNotFound)?;
+                .ok()
+                .filter(|user| user.deactivated_at.is_none())
+                .ok_or(AuthContextError::UserNotFound)?;
 
             // Fetch user's group memberships
             let group_ids = crate::repository::groups::get_group_ids_for_user(&mut conn, &user_uuid)
                 .unwrap_or_default();
+            let managed_group_ids = crate::repository::groups::get_managed_group_ids_for_user(&mut conn, &user_uuid)
+                .unwrap_or_default();
 
             Ok(AuthContext {
                 user_uuid,
                 role: user.role,
                 name: user.name,
                 group_ids,
+                managed_group_ids,
+                timezone: user.timezone,
                 claims,
             })
         })
@@ -230,6 +263,8 @@ impl FromRequest for OptionalAuthContext {
                     role,
                     name: claims.name.clone(),
                     group_ids: vec![], // Not loaded for optional auth
+                    managed_group_ids: vec![],
+                    timezone: None,
                     claims: claims.clone(),
                 })
             });
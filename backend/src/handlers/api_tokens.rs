@@ -10,7 +10,7 @@ use uuid::Uuid;
 use crate::db::Pool;
 use crate::models::{Claims, CreateApiTokenRequest};
 use crate::repository::api_tokens;
-use crate::utils::rbac::require_admin;
+use crate::utils::rbac::{is_valid_scope, require_admin, require_scope, FULL_SCOPE};
 
 /// List all API tokens (admin only)
 pub async fn list_api_tokens(req: HttpRequest, pool: web::Data<Pool>) -> impl Responder {
@@ -51,6 +51,11 @@ pub async fn create_api_token(
         return e;
     }
 
+    // A scoped token must not be able to mint a broader one
+    if let Err(e) = require_scope(&req, FULL_SCOPE) {
+        return e;
+    }
+
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
         None => return HttpResponse::Unauthorized().json("Authentication required"),
@@ -70,6 +75,16 @@ pub async fn create_api_token(
         return HttpResponse::BadRequest().json("Token name must be 255 characters or less");
     }
 
+    // Validate requested scopes
+    if let Some(invalid) = body
+        .scopes
+        .iter()
+        .flatten()
+        .find(|scope| !is_valid_scope(scope))
+    {
+        return HttpResponse::BadRequest().json(format!("Invalid scope: {invalid}"));
+    }
+
     let mut conn = match pool.get() {
         Ok(conn) => conn,
         Err(e) => {
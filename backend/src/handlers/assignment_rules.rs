@@ -10,18 +10,19 @@ use crate::models::{
 };
 use crate::repository;
 use crate::services::assignment::AssignmentEngine;
-use crate::utils::rbac::require_admin;
+use crate::utils::permissions::Permission;
+use crate::utils::rbac::require_permission;
 
 // ============================================================================
 // List Rules
 // ============================================================================
 
-/// Get all assignment rules with details (admin only)
+/// Get all assignment rules with details (requires manage_assignment_rules)
 pub async fn get_all_rules(
     req: HttpRequest,
     pool: web::Data<Pool>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_permission(&req, Permission::ManageAssignmentRules) {
         return e;
     }
 
@@ -40,13 +41,13 @@ pub async fn get_all_rules(
 // Get Single Rule
 // ============================================================================
 
-/// Get a single rule by ID (admin only)
+/// Get a single rule by ID (requires manage_assignment_rules)
 pub async fn get_rule(
     req: HttpRequest,
     pool: web::Data<Pool>,
     path: web::Path<i32>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_permission(&req, Permission::ManageAssignmentRules) {
         return e;
     }
 
@@ -81,17 +82,18 @@ pub struct CreateAssignmentRuleRequest {
     pub target_group_id: Option<i32>,
     pub trigger_on_create: Option<bool>,
     pub trigger_on_category_change: Option<bool>,
+    pub trigger_on_sla_breach: Option<bool>,
     pub category_id: Option<i32>,
     pub conditions: Option<Value>,
 }
 
-/// Create a new assignment rule (admin only)
+/// Create a new assignment rule (requires manage_assignment_rules)
 pub async fn create_rule(
     req: HttpRequest,
     pool: web::Data<Pool>,
     body: web::Json<CreateAssignmentRuleRequest>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_permission(&req, Permission::ManageAssignmentRules) {
         return e;
     }
 
@@ -166,6 +168,7 @@ pub async fn create_rule(
         target_group_id: body.target_group_id,
         trigger_on_create: body.trigger_on_create.unwrap_or(true),
         trigger_on_category_change: body.trigger_on_category_change.unwrap_or(true),
+        trigger_on_sla_breach: body.trigger_on_sla_breach.unwrap_or(false),
         category_id: body.category_id,
         conditions: body.conditions.clone(),
         created_by,
@@ -199,18 +202,19 @@ pub struct UpdateAssignmentRuleRequest {
     pub target_group_id: Option<Option<i32>>,
     pub trigger_on_create: Option<bool>,
     pub trigger_on_category_change: Option<bool>,
+    pub trigger_on_sla_breach: Option<bool>,
     pub category_id: Option<Option<i32>>,
     pub conditions: Option<Value>,
 }
 
-/// Update an assignment rule (admin only)
+/// Update an assignment rule (requires manage_assignment_rules)
 pub async fn update_rule(
     req: HttpRequest,
     pool: web::Data<Pool>,
     path: web::Path<i32>,
     body: web::Json<UpdateAssignmentRuleRequest>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_permission(&req, Permission::ManageAssignmentRules) {
         return e;
     }
 
@@ -270,6 +274,7 @@ pub async fn update_rule(
         target_group_id: body.target_group_id,
         trigger_on_create: body.trigger_on_create,
         trigger_on_category_change: body.trigger_on_category_change,
+        trigger_on_sla_breach: body.trigger_on_sla_breach,
         category_id: body.category_id,
         conditions: body.conditions.clone(),
         updated_at: None,
@@ -292,13 +297,13 @@ pub async fn update_rule(
 // Delete Rule
 // ============================================================================
 
-/// Delete an assignment rule (admin only)
+/// Delete an assignment rule (requires manage_assignment_rules)
 pub async fn delete_rule(
     req: HttpRequest,
     pool: web::Data<Pool>,
     path: web::Path<i32>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_permission(&req, Permission::ManageAssignmentRules) {
         return e;
     }
 
@@ -331,13 +336,13 @@ pub struct RuleOrder {
     pub priority: i32,
 }
 
-/// Reorder rules by priority (admin only)
+/// Reorder rules by priority (requires manage_assignment_rules)
 pub async fn reorder_rules(
     req: HttpRequest,
     pool: web::Data<Pool>,
     body: web::Json<ReorderRulesRequest>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_permission(&req, Permission::ManageAssignmentRules) {
         return e;
     }
 
@@ -382,13 +387,13 @@ pub struct PreviewAssignmentResponse {
     pub message: String,
 }
 
-/// Preview what assignment would happen for a ticket (admin only)
+/// Preview what assignment would happen for a ticket (requires manage_assignment_rules)
 pub async fn preview_assignment(
     req: HttpRequest,
     pool: web::Data<Pool>,
     body: web::Json<PreviewAssignmentRequest>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_permission(&req, Permission::ManageAssignmentRules) {
         return e;
     }
 
@@ -401,6 +406,8 @@ pub async fn preview_assignment(
     let trigger = match body.trigger.as_str() {
         "ticket_created" => AssignmentTrigger::TicketCreated,
         "category_changed" => AssignmentTrigger::CategoryChanged,
+        "sla_breached" => AssignmentTrigger::SlaBreached,
+        "ticket_reopened" => AssignmentTrigger::TicketReopened,
         _ => return HttpResponse::BadRequest().json("Invalid trigger type"),
     };
 
@@ -426,7 +433,7 @@ pub async fn preview_assignment(
     match AssignmentEngine::evaluate_rules(&mut conn, &ticket, trigger) {
         Some(result) => HttpResponse::Ok().json(PreviewAssignmentResponse {
             would_assign: true,
-            rule_id: Some(result.rule_id),
+            rule_id: result.rule_id,
             rule_name: Some(result.rule_name),
             assigned_user_uuid: result.assigned_user_uuid,
             method: Some(result.method.to_string()),
@@ -447,12 +454,12 @@ pub async fn preview_assignment(
 // Get Assignment Logs
 // ============================================================================
 
-/// Get recent assignment logs (admin only)
+/// Get recent assignment logs (requires manage_assignment_rules)
 pub async fn get_assignment_logs(
     req: HttpRequest,
     pool: web::Data<Pool>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_permission(&req, Permission::ManageAssignmentRules) {
         return e;
     }
 
@@ -0,0 +1,85 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, NaiveDate};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::services::audit::{self, AuditAction, AuditFilter};
+use crate::utils::permissions::Permission;
+use crate::utils::rbac::{require_permission, require_scope};
+
+/// Query parameters for the audit log
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQuery {
+    /// Only entries by this user
+    pub actor: Option<Uuid>,
+    /// Only entries with this action (e.g. `role_changed`)
+    pub action: Option<String>,
+    /// First day included (YYYY-MM-DD)
+    pub from: Option<NaiveDate>,
+    /// Last day included (YYYY-MM-DD)
+    pub to: Option<NaiveDate>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Query the audit log (requires view_audit_log)
+pub async fn get_audit_log(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    query: web::Query<AuditLogQuery>,
+) -> impl Responder {
+    if let Err(e) = require_permission(&req, Permission::ViewAuditLog) {
+        return e;
+    }
+    if let Err(e) = require_scope(&req, "audit:read") {
+        return e;
+    }
+
+    let action = match query.action.as_deref() {
+        None => None,
+        Some(name) => match AuditAction::parse(name) {
+            Some(action) => Some(action),
+            None => {
+                let known: Vec<&str> = AuditAction::ALL.iter().map(|a| a.as_str()).collect();
+                return HttpResponse::BadRequest().json(json!({
+                    "error": "Invalid action",
+                    "message": format!("action must be one of: {}", known.join(", "))
+                }));
+            }
+        },
+    };
+
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return HttpResponse::BadRequest().json(json!({
+                "error": "Invalid range",
+                "message": "from must not be after to"
+            }));
+        }
+    }
+
+    // Whole days: from midnight on `from` up to midnight after `to`
+    let filter = AuditFilter {
+        actor: query.actor,
+        action,
+        from: query.from.and_then(|d| d.and_hms_opt(0, 0, 0)),
+        to: query.to.and_then(|d| (d + Duration::days(1)).and_hms_opt(0, 0, 0)),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    let limit = query.limit.unwrap_or(100);
+    let offset = query.offset.unwrap_or(0);
+    match audit::query(&mut conn, &filter, limit, offset) {
+        Ok(entries) => HttpResponse::Ok().json(entries),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to query audit log");
+            HttpResponse::InternalServerError().json("Failed to query audit log")
+        }
+    }
+}
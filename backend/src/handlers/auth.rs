@@ -12,10 +12,15 @@ use crate::models::{
     UserRegistration, UserResponse
 };
 use crate::repository;
+use crate::services::audit::{self, AuditAction, AuditTarget};
+use crate::services::notifications::NotificationService;
+use crate::services::sign_in_devices;
 use crate::utils::{self, ValidationError, parse_uuid};
 use crate::utils::auth::{hash_password, validate_password};
 use crate::utils::mfa;
 use crate::utils::rate_limit::{RateLimiter, get_redis_url};
+use crate::config_utils::LoginLockoutPolicy;
+use crate::repository::account_lockouts::FailedAttempt;
 
 // Import JWT utilities
 use crate::utils::jwt::{JwtUtils, helpers as jwt_helpers};
@@ -29,7 +34,7 @@ pub struct AdminPasswordResetRequest {
 }
 
 /// Helper function to get password hash from user_auth_identities for local auth
-fn get_local_password_hash(user_uuid: &Uuid, conn: &mut DbConnection) -> Result<String, String> {
+pub(crate) fn get_local_password_hash(user_uuid: &Uuid, conn: &mut DbConnection) -> Result<String, String> {
     use diesel::prelude::*;
     use crate::schema::user_auth_identities;
 
@@ -131,17 +136,40 @@ async fn log_password_change_event(
     Ok(())
 }
 
+/// Record a login attempt for a known account in the audit log
+pub(crate) fn audit_login(conn: &mut DbConnection, user_uuid: Uuid, action: AuditAction, method: &str, request: &HttpRequest) {
+    let ip = request.connection_info().realip_remote_addr().map(|s| s.to_string());
+    let _ = audit::record(
+        conn,
+        Some(user_uuid),
+        action,
+        Some(AuditTarget::user(user_uuid)),
+        json!({ "method": method, "ip": ip }),
+    );
+}
+
+/// How long a session lasts without a refresh (the access token lifetime)
+const SESSION_LIFETIME_HOURS: i64 = 24;
+
+/// Hash an access token the way sessions store it
+pub(crate) fn session_token_hash(token: &str) -> String {
+    use ring::digest;
+    hex::encode(digest::digest(&digest::SHA256, token.as_bytes()).as_ref())
+}
+
 /// Helper function to create a session record after successful login
+///
+/// Pass the login's refresh token to link it to the session, so revoking the
+/// session also stops it being refreshed.
 pub async fn create_session_record(
     user_uuid: &Uuid,
     token: &str,
+    refresh_token: Option<&str>,
     request: &HttpRequest,
     conn: &mut DbConnection,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<crate::models::ActiveSession, Box<dyn std::error::Error>> {
     // Hash the JWT token with SHA-256 for storage
-    use ring::digest;
-    let hash = digest::digest(&digest::SHA256, token.as_bytes());
-    let token_hash = hex::encode(hash.as_ref());
+    let token_hash = session_token_hash(token);
 
     // Extract IP address from request and convert to IpNetwork
     let ip_address = request.peer_addr()
@@ -153,28 +181,15 @@ pub async fn create_session_record(
         .and_then(|h| h.to_str().ok())
         .map(|s| s.to_string());
 
-    // Parse device name from user agent (simple parsing)
-    let device_name = user_agent.as_ref().map(|ua| {
-        // Simple device name extraction - can be enhanced later
-        if ua.contains("iPhone") {
-            "iPhone".to_string()
-        } else if ua.contains("iPad") {
-            "iPad".to_string()
-        } else if ua.contains("Android") {
-            "Android Device".to_string()
-        } else if ua.contains("Macintosh") || ua.contains("Mac OS") {
-            "Mac".to_string()
-        } else if ua.contains("Windows") {
-            "Windows PC".to_string()
-        } else if ua.contains("Linux") {
-            "Linux".to_string()
-        } else {
-            "Unknown Device".to_string()
-        }
-    });
+    let device_name = user_agent.as_deref().map(|ua| sign_in_devices::device_name(ua).to_string());
+
+    // Place the client when a geo lookup is configured
+    let location = request
+        .app_data::<web::Data<dyn sign_in_devices::GeoLookup>>()
+        .and_then(|lookup| lookup.locate(request));
 
-    // Set expiration to 24 hours from now (matching JWT expiration)
-    let expires_at = chrono::Utc::now().naive_utc() + chrono::Duration::hours(24);
+    // Set expiration to match the JWT expiration
+    let expires_at = chrono::Utc::now().naive_utc() + chrono::Duration::hours(SESSION_LIFETIME_HOURS);
 
     // Create new session record
     let new_session = crate::models::NewActiveSession {
@@ -183,7 +198,7 @@ pub async fn create_session_record(
         device_name,
         ip_address,
         user_agent,
-        location: None, // Could be derived from IP in the future
+        location: location.as_ref().and_then(|location| location.label()),
         expires_at,
         is_current: true,
     };
@@ -192,7 +207,21 @@ pub async fn create_session_record(
     match crate::repository::active_sessions::create_session(conn, new_session) {
         Ok(session) => {
             tracing::info!("Session created for user {}: session_id={}", user_uuid, session.id);
-            Ok(())
+            if let Some(refresh_token) = refresh_token {
+                let refresh_hash = JwtUtils::hash_refresh_token(refresh_token);
+                crate::repository::refresh_tokens::attach_to_session(conn, &refresh_hash, session.id)?;
+
+                // Only sign-ins issue a refresh token; impersonation and
+                // sessions rebuilt from legacy refresh tokens aren't new
+                // devices
+                let fingerprint = sign_in_devices::fingerprint(session.user_agent.as_deref(), location.as_ref());
+                let device = sign_in_devices::describe_user_agent(session.user_agent.as_deref());
+                let notifications = request.app_data::<web::Data<NotificationService>>().map(|service| service.get_ref());
+                if let Err(e) = sign_in_devices::check_sign_in(conn, &session, &fingerprint, &device, notifications).await {
+                    tracing::warn!("Failed to check sign-in device for user {}: {}", user_uuid, e);
+                }
+            }
+            Ok(session)
         },
         Err(e) => {
             tracing::error!("Failed to create session for user {}: {}", user_uuid, e);
@@ -201,44 +230,60 @@ pub async fn create_session_record(
     }
 }
 
-// Account lockout configuration (IP rate limiting handled by middleware)
-const MAX_LOGIN_ATTEMPTS: u32 = 5;
-const LOCKOUT_DURATION_SECONDS: u64 = 900; // 15 minutes
+/// Response for a login on a locked account
+pub(crate) fn account_locked_response(locked_until: chrono::NaiveDateTime) -> HttpResponse {
+    let retry_after = (locked_until - chrono::Utc::now().naive_utc()).num_seconds().max(0);
+    HttpResponse::TooManyRequests().json(json!({
+        "status": "error",
+        "message": format!("Account temporarily locked. Try again in {} minutes.", (retry_after / 60) + 1),
+        "retry_after": retry_after
+    }))
+}
 
 // Authentication handlers
+//
+// Failed password logins on an account count towards a lock in the database
+// (see `repository::account_lockouts`). Emails with no account, or with no
+// local password, are counted in Redis under the same policy instead, so a
+// lock doesn't reveal whether the account exists. IP rate limiting is handled
+// by middleware.
 pub async fn login(
     db_pool: web::Data<crate::db::Pool>,
+    lockout_policy: web::Data<LoginLockoutPolicy>,
     login_data: web::Json<LoginRequest>,
     request: HttpRequest,
 ) -> impl Responder {
     let redis_url = get_redis_url();
     let lockout_key = RateLimiter::login_attempt_key(&login_data.email);
+    let policy = *lockout_policy.get_ref();
 
     // Check if account is locked before any validation
     let is_production = std::env::var("ENVIRONMENT")
         .map(|v| v.to_lowercase() == "production")
         .unwrap_or(false);
 
-    match RateLimiter::check_lockout(&redis_url, &lockout_key, MAX_LOGIN_ATTEMPTS).await {
-        Ok(Some(remaining_seconds)) => {
-            warn!(email = %login_data.email, remaining_seconds, "Login attempt on locked account");
-            return HttpResponse::TooManyRequests().json(json!({
-                "status": "error",
-                "message": format!("Account temporarily locked. Try again in {} minutes.", (remaining_seconds / 60) + 1),
-                "retry_after": remaining_seconds
-            }));
-        }
-        Ok(None) => {} // Not locked, continue
-        Err(e) => {
-            error!(error = %e, "Redis error checking account lockout");
-            if is_production {
-                // Fail closed in production - deny login if we can't verify lockout status
-                return HttpResponse::ServiceUnavailable().json(json!({
+    if policy.is_enabled() {
+        match RateLimiter::check_lockout(&redis_url, &lockout_key, policy.max_attempts).await {
+            Ok(Some(remaining_seconds)) => {
+                warn!(email = %login_data.email, remaining_seconds, "Login attempt on locked account");
+                return HttpResponse::TooManyRequests().json(json!({
                     "status": "error",
-                    "message": "Authentication service temporarily unavailable. Please try again."
+                    "message": format!("Account temporarily locked. Try again in {} minutes.", (remaining_seconds / 60) + 1),
+                    "retry_after": remaining_seconds
                 }));
             }
-            // Fail open in development for convenience
+            Ok(None) => {} // Not locked, continue
+            Err(e) => {
+                error!(error = %e, "Redis error checking account lockout");
+                if is_production {
+                    // Fail closed in production - deny login if we can't verify lockout status
+                    return HttpResponse::ServiceUnavailable().json(json!({
+                        "status": "error",
+                        "message": "Authentication service temporarily unavailable. Please try again."
+                    }));
+                }
+                // Fail open in development for convenience
+            }
         }
     }
 
@@ -256,7 +301,9 @@ pub async fn login(
         Err(e) => {
             error!(error = ?e, "Error finding user by email");
             // Record failed attempt even for non-existent users (prevents enumeration)
-            let _ = RateLimiter::record_failed_attempt(&redis_url, &lockout_key, LOCKOUT_DURATION_SECONDS).await;
+            if policy.is_enabled() {
+                let _ = RateLimiter::record_failed_attempt(&redis_url, &lockout_key, policy.lockout_seconds).await;
+            }
             return HttpResponse::Unauthorized().json(json!({
                 "status": "error",
                 "message": "Invalid email or password"
@@ -264,12 +311,24 @@ pub async fn login(
         }
     };
 
+    let now = chrono::Utc::now().naive_utc();
+    match repository::account_lockouts::active_lock(&mut conn, &user.uuid, now) {
+        Ok(Some(locked_until)) => {
+            warn!(user_uuid = %user.uuid, %locked_until, "Login attempt on locked account");
+            return account_locked_response(locked_until);
+        }
+        Ok(None) => {}
+        Err(e) => error!(error = ?e, user_uuid = %user.uuid, "Failed to check account lockout"),
+    }
+
     // Get password hash from user_auth_identities for local authentication
     let password_hash = match get_local_password_hash(&user.uuid, &mut conn) {
         Ok(hash) => hash,
         Err(_) => {
             warn!(user_uuid = %user.uuid, "No local password found for user");
-            let _ = RateLimiter::record_failed_attempt(&redis_url, &lockout_key, LOCKOUT_DURATION_SECONDS).await;
+            if policy.is_enabled() {
+                let _ = RateLimiter::record_failed_attempt(&redis_url, &lockout_key, policy.lockout_seconds).await;
+            }
             return HttpResponse::Unauthorized().json(json!({
                 "status": "error",
                 "message": "Invalid email or password"
@@ -284,21 +343,33 @@ pub async fn login(
     };
 
     if !password_matches {
+        audit_login(&mut conn, user.uuid, AuditAction::LoginFailed, "password", &request);
+
+        if !policy.is_enabled() {
+            return HttpResponse::Unauthorized().json(json!({
+                "status": "error",
+                "message": "Invalid email or password"
+            }));
+        }
+
         // Record failed attempt
-        match RateLimiter::record_failed_attempt(&redis_url, &lockout_key, LOCKOUT_DURATION_SECONDS).await {
-            Ok(attempts) => {
-                let remaining = MAX_LOGIN_ATTEMPTS.saturating_sub(attempts);
-                if remaining == 0 {
-                    warn!(email = %login_data.email, "Account locked after {} failed attempts", MAX_LOGIN_ATTEMPTS);
-                    return HttpResponse::TooManyRequests().json(json!({
-                        "status": "error",
-                        "message": format!("Account locked after too many failed attempts. Try again in {} minutes.", LOCKOUT_DURATION_SECONDS / 60),
-                        "retry_after": LOCKOUT_DURATION_SECONDS
-                    }));
-                }
-                debug!(email = %login_data.email, attempts, remaining, "Failed login attempt");
+        match repository::account_lockouts::record_failure(&mut conn, &user.uuid, &policy, now) {
+            Ok(FailedAttempt::Locked { until, failed_attempts }) => {
+                warn!(user_uuid = %user.uuid, failed_attempts, "Account locked after too many failed attempts");
+                let ip = request.connection_info().realip_remote_addr().map(|s| s.to_string());
+                let _ = audit::record(
+                    &mut conn,
+                    None,
+                    AuditAction::AccountLocked,
+                    Some(AuditTarget::user(user.uuid)),
+                    json!({ "failed_attempts": failed_attempts, "locked_until": until, "ip": ip }),
+                );
+                return account_locked_response(until);
+            }
+            Ok(FailedAttempt::Counted { remaining }) => {
+                debug!(user_uuid = %user.uuid, remaining, "Failed login attempt");
             }
-            Err(e) => warn!(error = %e, "Failed to record login attempt"),
+            Err(e) => warn!(error = ?e, "Failed to record login attempt"),
         }
         return HttpResponse::Unauthorized().json(json!({
             "status": "error",
@@ -307,6 +378,9 @@ pub async fn login(
     }
 
     // Clear failed attempts on successful password verification
+    if let Err(e) = repository::account_lockouts::clear(&mut conn, &user.uuid, now) {
+        warn!(error = ?e, "Failed to clear account lockout after successful auth");
+    }
     if let Err(e) = RateLimiter::clear_attempts(&redis_url, &lockout_key).await {
         warn!(error = %e, "Failed to clear login attempts after successful auth");
     }
@@ -331,10 +405,11 @@ pub async fn login(
     match jwt_helpers::create_login_response(user, &mut conn) {
         Ok((response, tokens)) => {
             // Create session record after successful login
-            if let Err(e) = create_session_record(&user_uuid, &tokens.access_token, &request, &mut conn).await {
+            if let Err(e) = create_session_record(&user_uuid, &tokens.access_token, Some(&tokens.refresh_token), &request, &mut conn).await {
                 tracing::warn!("Failed to create session record for user {}: {}", user_uuid, e);
                 // Don't fail the login if session creation fails
             }
+            audit_login(&mut conn, user_uuid, AuditAction::LoginSucceeded, "password", &request);
 
             // Set httpOnly cookies for tokens
             HttpResponse::Ok()
@@ -428,6 +503,7 @@ pub async fn mfa_login(
     if !mfa_result.is_valid {
         // Log failed MFA attempt
         mfa::log_mfa_attempt(&user.uuid, false, "login", &request).await;
+        audit_login(&mut conn, user.uuid, AuditAction::LoginFailed, "password+mfa", &request);
         
         return HttpResponse::BadRequest().json(json!({
             "status": "error",
@@ -450,10 +526,11 @@ pub async fn mfa_login(
     ) {
         Ok((response, tokens)) => {
             // Create session record after successful MFA login
-            if let Err(e) = create_session_record(&user_uuid, &tokens.access_token, &request, &mut conn).await {
+            if let Err(e) = create_session_record(&user_uuid, &tokens.access_token, Some(&tokens.refresh_token), &request, &mut conn).await {
                 tracing::warn!("Failed to create session record for user {}: {}", user_uuid, e);
                 // Don't fail the login if session creation fails
             }
+            audit_login(&mut conn, user_uuid, AuditAction::LoginSucceeded, "password+mfa", &request);
 
             // Set httpOnly cookies for tokens
             HttpResponse::Ok()
@@ -467,15 +544,42 @@ pub async fn mfa_login(
 }
 
 /// Logout endpoint - clears all authentication cookies
-pub async fn logout() -> impl Responder {
-    use crate::utils::cookies::{delete_access_token_cookie, delete_refresh_token_cookie, delete_csrf_token_cookie};
+pub async fn logout(
+    db_pool: web::Data<crate::db::Pool>,
+    request: HttpRequest,
+) -> impl Responder {
+    use crate::utils::cookies::{
+        delete_access_token_cookie, delete_refresh_token_cookie, delete_csrf_token_cookie,
+        delete_impersonator_token_cookie, delete_impersonating_cookie,
+    };
 
     tracing::info!("🔓 User logging out");
 
+    // End the session server-side too, so copies of its tokens stop working
+    match db_pool.get() {
+        Ok(mut conn) => {
+            if let Some(cookie) = request.cookie(crate::utils::cookies::ACCESS_TOKEN_COOKIE) {
+                let token_hash = session_token_hash(cookie.value());
+                if let Err(e) = crate::repository::active_sessions::revoke_session_by_token(&mut conn, &token_hash) {
+                    tracing::warn!("Failed to revoke session on logout: {}", e);
+                }
+            }
+            if let Some(cookie) = request.cookie(crate::utils::cookies::REFRESH_TOKEN_COOKIE) {
+                let refresh_hash = JwtUtils::hash_refresh_token(cookie.value());
+                if let Err(e) = crate::repository::refresh_tokens::revoke_refresh_token(&mut conn, &refresh_hash) {
+                    tracing::warn!("Failed to revoke refresh token on logout: {}", e);
+                }
+            }
+        }
+        Err(_) => tracing::warn!("Could not get database connection to revoke session on logout"),
+    }
+
     HttpResponse::Ok()
         .cookie(delete_access_token_cookie())
         .cookie(delete_refresh_token_cookie())
         .cookie(delete_csrf_token_cookie())
+        .cookie(delete_impersonator_token_cookie())
+        .cookie(delete_impersonating_cookie())
         .json(json!({
             "success": true,
             "message": "Logged out successfully"
@@ -1382,6 +1486,7 @@ pub async fn mfa_enable(
     match repository::update_user_mfa(&user_uuid, mfa_update, &mut conn) {
         Ok(_) => {
             tracing::info!("MFA enabled successfully for user: {}", user_uuid);
+            let _ = audit::record(&mut conn, Some(user_uuid), AuditAction::MfaEnabled, Some(AuditTarget::user(user_uuid)), json!({}));
             // Return plaintext backup codes so the client can display them once
             HttpResponse::Ok().json(json!({
                 "status": "success",
@@ -1475,6 +1580,13 @@ pub async fn mfa_disable(
     match repository::update_user_mfa(&user_uuid, mfa_update, &mut conn) {
         Ok(_) => {
             tracing::info!("MFA disabled for user: {} (scope: {})", user_uuid, claims.scope);
+            let _ = audit::record(
+                &mut conn,
+                Some(user_uuid),
+                AuditAction::MfaDisabled,
+                Some(AuditTarget::user(user_uuid)),
+                json!({ "scope": claims.scope }),
+            );
             HttpResponse::Ok().json(json!({
                 "status": "success",
                 "message": "MFA disabled successfully"
@@ -1559,6 +1671,13 @@ pub async fn mfa_regenerate_backup_codes(
 
     match repository::update_user_mfa(&user_uuid, mfa_update, &mut conn) {
         Ok(_) => {
+            let _ = audit::record(
+                &mut conn,
+                Some(user_uuid),
+                AuditAction::MfaBackupCodesRegenerated,
+                Some(AuditTarget::user(user_uuid)),
+                json!({}),
+            );
             let response = crate::models::MfaRegenerateBackupCodesResponse {
                 backup_codes: backup_codes_plaintext,
             };
@@ -1853,6 +1972,7 @@ pub async fn mfa_enable_login(
     match repository::update_user_mfa(&user_uuid, mfa_update, &mut conn) {
         Ok(_) => {
             tracing::info!("MFA enabled successfully for user during login: {}", user_uuid);
+            let _ = audit::record(&mut conn, Some(user_uuid), AuditAction::MfaEnabled, Some(AuditTarget::user(user_uuid)), json!({}));
 
             // Generate JWT token and complete login
             match jwt_helpers::create_login_response(user, &mut conn) {
@@ -1861,7 +1981,7 @@ pub async fn mfa_enable_login(
                     response.backup_codes = Some(backup_codes_plaintext);
 
                     // Create session record after successful login (IMPORTANT!)
-                    if let Err(e) = create_session_record(&user_uuid, &tokens.access_token, &http_request, &mut conn).await {
+                    if let Err(e) = create_session_record(&user_uuid, &tokens.access_token, Some(&tokens.refresh_token), &http_request, &mut conn).await {
                         tracing::warn!("Failed to create session record for user {}: {}", user_uuid, e);
                         // Don't fail the login if session creation fails
                     }
@@ -1967,9 +2087,7 @@ pub async fn get_user_sessions(
         }
     };
 
-    use ring::digest;
-    let hash = digest::digest(&digest::SHA256, current_session_token.as_bytes());
-    let token_hash = hex::encode(hash.as_ref());
+    let token_hash = session_token_hash(&current_session_token);
 
     // Get all sessions for the user
     let sessions = match crate::repository::active_sessions::get_user_sessions(&mut conn, &user_uuid) {
@@ -2037,25 +2155,8 @@ pub async fn revoke_session(
         })),
     };
 
-    // Verify the session belongs to this user before revoking
-    match crate::repository::active_sessions::get_session_by_token(&mut conn, &session_id.to_string()) {
-        Ok(session) if session.user_uuid == user_uuid => {
-            // Session belongs to user, proceed with revocation
-        },
-        Ok(_) => {
-            return HttpResponse::Forbidden().json(json!({
-                "status": "error",
-                "message": "You can only revoke your own sessions"
-            }));
-        },
-        Err(_) => {
-            // Try to revoke anyway in case it's a valid session ID (not found by token lookup)
-            // The revoke_session function will handle if it doesn't exist
-        }
-    }
-
-    // Revoke the session
-    match crate::repository::active_sessions::revoke_session(&mut conn, session_id) {
+    // Only the user's own sessions match; its refresh tokens go with it
+    match crate::repository::active_sessions::revoke_user_session(&mut conn, &user_uuid, session_id) {
         Ok(count) if count > 0 => {
             tracing::info!("Session {} revoked for user {}", session_id, user_uuid);
             HttpResponse::Ok().json(json!({
@@ -2118,9 +2219,7 @@ pub async fn revoke_all_other_sessions(
         }
     };
 
-    use ring::digest;
-    let hash = digest::digest(&digest::SHA256, current_session_token.as_bytes());
-    let token_hash = hex::encode(hash.as_ref());
+    let token_hash = session_token_hash(&current_session_token);
 
     // Look up current session ID
     let current_session_id = match crate::repository::active_sessions::get_session_by_token(
@@ -2134,7 +2233,12 @@ pub async fn revoke_all_other_sessions(
         }
     };
 
-    // Revoke all other sessions
+    // Refresh tokens from before sessions were linked could start new sessions
+    if let Err(e) = crate::repository::refresh_tokens::revoke_unlinked_tokens(&mut conn, &user_uuid) {
+        tracing::warn!("Failed to revoke unlinked refresh tokens for user {}: {}", user_uuid, e);
+    }
+
+    // Revoke all other sessions (their refresh tokens go with them)
     match crate::repository::active_sessions::revoke_other_sessions(
         &mut conn,
         &user_uuid,
@@ -2225,6 +2329,45 @@ pub async fn refresh_token(
         }
     };
 
+    // Move the session to the new access token so it stays one revocable
+    // session; tokens from before sessions were linked start a new one
+    let session_id = match refresh_token.session_id {
+        Some(session_id) => {
+            let session_expires = chrono::Utc::now().naive_utc() + chrono::Duration::hours(SESSION_LIFETIME_HOURS);
+            match crate::repository::active_sessions::rotate_session_token(
+                &mut conn,
+                session_id,
+                &session_token_hash(&new_access_token),
+                session_expires,
+            ) {
+                Ok(0) => {
+                    return HttpResponse::Unauthorized().json(json!({
+                        "status": "error",
+                        "message": "Session has been revoked"
+                    }));
+                }
+                Ok(_) => session_id,
+                Err(e) => {
+                    tracing::error!("Failed to rotate session {}: {}", session_id, e);
+                    return HttpResponse::InternalServerError().json(json!({
+                        "status": "error",
+                        "message": "Failed to refresh session"
+                    }));
+                }
+            }
+        }
+        None => match create_session_record(&user.uuid, &new_access_token, None, &request, &mut conn).await {
+            Ok(session) => session.id,
+            Err(e) => {
+                tracing::error!("Failed to create session on refresh: {}", e);
+                return HttpResponse::InternalServerError().json(json!({
+                    "status": "error",
+                    "message": "Failed to refresh session"
+                }));
+            }
+        },
+    };
+
     // Generate new refresh token
     let new_refresh_token = JwtUtils::generate_refresh_token();
     let new_refresh_token_hash = JwtUtils::hash_refresh_token(&new_refresh_token);
@@ -2235,6 +2378,7 @@ pub async fn refresh_token(
         token_hash: new_refresh_token_hash,
         user_uuid: user.uuid,
         expires_at: new_refresh_expires,
+        session_id: Some(session_id),
     };
 
     if let Err(e) = crate::repository::refresh_tokens::create_refresh_token(&mut conn, new_refresh_record) {
@@ -2254,10 +2398,13 @@ pub async fn refresh_token(
         csrf_token: new_csrf_token.clone(),
     };
 
+    // The refresh token belongs to the real user, so refreshing ends any impersonation
     HttpResponse::Ok()
         .cookie(crate::utils::cookies::create_access_token_cookie(&new_access_token))
         .cookie(crate::utils::cookies::create_refresh_token_cookie(&new_refresh_token))
         .cookie(crate::utils::cookies::create_csrf_token_cookie(&new_csrf_token))
+        .cookie(crate::utils::cookies::delete_impersonator_token_cookie())
+        .cookie(crate::utils::cookies::delete_impersonating_cookie())
         .json(response)
 }
 
@@ -2280,6 +2427,7 @@ mod tests {
     > {
         App::new()
             .app_data(web::Data::new(pool))
+            .app_data(web::Data::new(LoginLockoutPolicy::default()))
             .route("/setup/status", web::get().to(check_setup_status))
             .route("/login", web::post().to(login))
             .route("/register", web::post().to(register))
@@ -2334,6 +2482,159 @@ mod tests {
         assert_eq!(json.get("status").and_then(|v| v.as_str()), Some("error"));
     }
 
+    #[actix_web::test]
+    async fn repeated_failed_logins_lock_the_account_until_the_cooldown_ends() {
+        use diesel::prelude::*;
+
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "lockoutuser", UserRole::User);
+        let email = format!("lockout_{}@example.com", uuid::Uuid::new_v4());
+        TestFixtures::create_user_email(&mut conn, user.uuid, &email, true);
+        repository::user_auth_identities::create_identity(
+            crate::models::NewUserAuthIdentity {
+                user_uuid: user.uuid,
+                provider_type: "local".to_string(),
+                external_id: user.uuid.to_string(),
+                email: Some(email.clone()),
+                metadata: None,
+                password_hash: Some(hash_password("CorrectHorse123!").unwrap()),
+            },
+            &mut conn,
+        )
+        .unwrap();
+
+        let policy = LoginLockoutPolicy { max_attempts: 2, ..LoginLockoutPolicy::default() };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(policy))
+                .route("/login", web::post().to(login)),
+        )
+        .await;
+        let attempt = |password: &str| {
+            test::TestRequest::post()
+                .uri("/login")
+                .set_json(json!({ "email": email, "password": password }))
+                .to_request()
+        };
+
+        assert_eq!(test::call_service(&app, attempt("wrong")).await.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(test::call_service(&app, attempt("wrong")).await.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // The right password doesn't get through while locked
+        let resp = test::call_service(&app, attempt("CorrectHorse123!")).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(body["message"].as_str().unwrap().starts_with("Account temporarily locked"));
+
+        let filter = audit::AuditFilter { action: Some(AuditAction::AccountLocked), ..audit::AuditFilter::default() };
+        let locks = audit::query(&mut conn, &filter, 50, 0).unwrap();
+        assert!(locks.iter().any(|entry| entry.target_id == Some(user.uuid.to_string())));
+
+        // Once the cooldown has passed the account unlocks by itself
+        diesel::update(crate::schema::account_lockouts::table.find(user.uuid))
+            .set(crate::schema::account_lockouts::locked_until.eq(chrono::Utc::now().naive_utc() - chrono::Duration::seconds(1)))
+            .execute(&mut conn)
+            .unwrap();
+        assert_eq!(test::call_service(&app, attempt("CorrectHorse123!")).await.status(), StatusCode::OK);
+        assert_eq!(repository::account_lockouts::active_lock(&mut conn, &user.uuid, chrono::Utc::now().naive_utc()).unwrap(), None);
+    }
+
+    /// Log a user in the way the login handlers do: an access token with a
+    /// session, and a refresh token linked to it
+    async fn signed_in_session(conn: &mut DbConnection, user: &crate::models::User) -> (String, String, crate::models::ActiveSession) {
+        let access_token = crate::test_helpers::create_test_token(user);
+        let refresh_token = JwtUtils::generate_refresh_token();
+        crate::repository::refresh_tokens::create_refresh_token(conn, crate::models::NewRefreshToken {
+            token_hash: JwtUtils::hash_refresh_token(&refresh_token),
+            user_uuid: user.uuid,
+            expires_at: chrono::Utc::now().naive_utc() + chrono::Duration::days(7),
+            session_id: None,
+        })
+        .unwrap();
+        let request = test::TestRequest::default().to_http_request();
+        let session = create_session_record(&user.uuid, &access_token, Some(&refresh_token), &request, conn)
+            .await
+            .unwrap();
+        (access_token, refresh_token, session)
+    }
+
+    #[actix_web::test]
+    async fn revoked_session_no_longer_authenticates_or_refreshes() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "sessionrevoke", UserRole::User);
+        let other = TestFixtures::create_user(&mut conn, "sessionintruder", UserRole::User);
+        let (access_token, refresh_token, session) = signed_in_session(&mut conn, &user).await;
+        assert!(JwtUtils::validate_token_with_user_check(&access_token, &mut conn).await.is_ok());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/sessions/{id}", web::delete().to(revoke_session))
+                .route("/refresh", web::post().to(super::refresh_token)),
+        )
+        .await;
+        let revoke = |claims: crate::models::Claims| {
+            let req = test::TestRequest::delete().uri(&format!("/sessions/{}", session.id)).to_request();
+            req.extensions_mut().insert(claims);
+            req
+        };
+
+        // Someone else's session looks like it doesn't exist
+        let resp = test::call_service(&app, revoke(create_test_claims(&other))).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert!(JwtUtils::validate_token_with_user_check(&access_token, &mut conn).await.is_ok());
+
+        let resp = test::call_service(&app, revoke(create_test_claims(&user))).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(matches!(
+            JwtUtils::validate_token_with_user_check(&access_token, &mut conn).await,
+            Err(crate::utils::jwt::JwtError::SessionRevoked)
+        ));
+
+        // The session's refresh token went with it
+        let req = test::TestRequest::post()
+            .uri("/refresh")
+            .cookie(actix_web::cookie::Cookie::new(crate::utils::cookies::REFRESH_TOKEN_COOKIE, refresh_token))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn refresh_keeps_the_same_session() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "sessionrefresh", UserRole::User);
+        let (_, refresh_token, session) = signed_in_session(&mut conn, &user).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/refresh", web::post().to(super::refresh_token)),
+        )
+        .await;
+        let req = test::TestRequest::post()
+            .uri("/refresh")
+            .cookie(actix_web::cookie::Cookie::new(crate::utils::cookies::REFRESH_TOKEN_COOKIE, refresh_token))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let new_access_token = resp
+            .response()
+            .cookies()
+            .find(|cookie| cookie.name() == crate::utils::cookies::ACCESS_TOKEN_COOKIE)
+            .unwrap()
+            .value()
+            .to_string();
+        assert!(JwtUtils::validate_token_with_user_check(&new_access_token, &mut conn).await.is_ok());
+
+        let sessions = crate::repository::active_sessions::get_user_sessions(&mut conn, &user.uuid).unwrap();
+        assert_eq!(sessions.iter().map(|s| s.id).collect::<Vec<_>>(), vec![session.id]);
+    }
+
     #[actix_web::test]
     async fn register_creates_user_when_allowed() {
         let pool = setup_test_pool();
@@ -2414,4 +2715,74 @@ mod tests {
         assert_eq!(json.get("uuid").and_then(|v| v.as_str()), Some(user.uuid.to_string().as_str()));
         assert_eq!(json.get("name").and_then(|v| v.as_str()), Some("authuser"));
     }
+    /// Sign `user` in from a browser with `user_agent`, with notifications on
+    async fn sign_in_from(
+        conn: &mut DbConnection,
+        user: &crate::models::User,
+        notifications: &web::Data<NotificationService>,
+        user_agent: &str,
+    ) -> crate::models::ActiveSession {
+        let request = test::TestRequest::default()
+            .insert_header(("User-Agent", user_agent))
+            .app_data(notifications.clone())
+            .to_http_request();
+        let access_token = format!("access-{}", Uuid::new_v4());
+        let refresh_token = JwtUtils::generate_refresh_token();
+        create_session_record(&user.uuid, &access_token, Some(&refresh_token), &request, conn)
+            .await
+            .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn only_sign_ins_from_unseen_devices_notify_the_user() {
+        use crate::services::notifications::channels::in_app::InAppChannel;
+        use std::sync::Arc;
+
+        const FIREFOX_LINUX: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+        const SAFARI_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1";
+
+        // The test holds a connection while the new-device notification takes more
+        let pool = crate::test_helpers::setup_test_pool_with_size(4);
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "newdevicesignin", UserRole::User);
+        let notifications = web::Data::new(NotificationService::new(pool.clone()));
+        notifications.register_channel(Arc::new(InAppChannel::new(Arc::new(crate::handlers::sse::SseState::new()))));
+
+        let new_device_notifications = || async {
+            notifications
+                .get_all(&user.uuid, 10, 0, false)
+                .await
+                .unwrap()
+                .into_iter()
+                .filter(|n| n.notification_type == "new_device_sign_in")
+                .collect::<Vec<_>>()
+        };
+        let audited_sign_ins = |conn: &mut DbConnection| {
+            let filter = audit::AuditFilter { action: Some(AuditAction::NewDeviceSignIn), ..Default::default() };
+            audit::query(conn, &filter, 50, 0)
+                .unwrap()
+                .into_iter()
+                .filter(|entry| entry.target_id == Some(user.uuid.to_string()))
+                .count()
+        };
+
+        // The first sign-in just records the device, and a browser update
+        // doesn't make it a new one
+        sign_in_from(&mut conn, &user, &notifications, FIREFOX_LINUX).await;
+        sign_in_from(&mut conn, &user, &notifications, &FIREFOX_LINUX.replace("128.0", "129.0")).await;
+        assert!(new_device_notifications().await.is_empty());
+        assert_eq!(audited_sign_ins(&mut conn), 0);
+
+        let session = sign_in_from(&mut conn, &user, &notifications, SAFARI_IPHONE).await;
+        let notified = new_device_notifications().await;
+        assert_eq!(notified.len(), 1);
+        assert_eq!(notified[0].entity_type, "session");
+        assert_eq!(notified[0].entity_id, session.id);
+        assert_eq!(audited_sign_ins(&mut conn), 1);
+
+        // Signing in from it again is nothing new
+        sign_in_from(&mut conn, &user, &notifications, SAFARI_IPHONE).await;
+        assert_eq!(new_device_notifications().await.len(), 1);
+        assert_eq!(audited_sign_ins(&mut conn), 1);
+    }
 }
\ No newline at end of file
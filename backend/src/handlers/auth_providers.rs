@@ -665,7 +665,7 @@ pub async fn oauth_callback(
                                     info!(user_uuid = %user_uuid, "OAuth: Created login response, creating session");
 
                                     // Create session record after successful OAuth login
-                                    if let Err(e) = crate::handlers::auth::create_session_record(&user_uuid, &tokens.access_token, &request, &mut conn).await {
+                                    if let Err(e) = crate::handlers::auth::create_session_record(&user_uuid, &tokens.access_token, Some(&tokens.refresh_token), &request, &mut conn).await {
                                         error!(user_uuid = %user_uuid, error = %e, "OAuth: Failed to create session record");
                                         // Return error instead of continuing without session
                                         return HttpResponse::InternalServerError().json(json!({
@@ -877,7 +877,7 @@ pub async fn oauth_callback(
                                 Ok((response, tokens)) => {
                                     info!(user_uuid = %user_uuid, "OIDC: Created login response, creating session");
 
-                                    if let Err(e) = crate::handlers::auth::create_session_record(&user_uuid, &tokens.access_token, &request, &mut conn).await {
+                                    if let Err(e) = crate::handlers::auth::create_session_record(&user_uuid, &tokens.access_token, Some(&tokens.refresh_token), &request, &mut conn).await {
                                         error!(user_uuid = %user_uuid, error = %e, "OIDC: Failed to create session record");
                                         return HttpResponse::InternalServerError().json(json!({
                                             "status": "error",
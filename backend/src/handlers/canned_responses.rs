@@ -0,0 +1,302 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use diesel::result::Error;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::models::{CannedResponse, CannedResponseUpdate, Claims, NewCannedResponse};
+use crate::repository;
+use crate::services::canned_responses;
+use crate::utils::rbac::{is_admin, require_technician_or_admin};
+
+/// Maximum length of a canned response body
+const MAX_BODY_LENGTH: usize = 20_000;
+
+/// Personal templates are only visible to their creator
+fn can_view(response: &CannedResponse, claims: &Claims, user_uuid: Uuid) -> bool {
+    response.is_shared || response.created_by == Some(user_uuid) || is_admin(claims)
+}
+
+/// Only the creator may change a template; admins may also change shared ones
+fn can_edit(response: &CannedResponse, claims: &Claims, user_uuid: Uuid) -> bool {
+    response.created_by == Some(user_uuid) || (response.is_shared && is_admin(claims))
+}
+
+// ============================================================================
+// List / Get
+// ============================================================================
+
+/// Query parameters for listing canned responses
+#[derive(Debug, Deserialize)]
+pub struct ListCannedResponsesQuery {
+    /// Only templates for this category (plus those without a category)
+    pub category_id: Option<i32>,
+}
+
+/// List shared canned responses and the caller's personal ones (technician/admin)
+pub async fn list_canned_responses(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    query: web::Query<ListCannedResponsesQuery>,
+) -> impl Responder {
+    let claims = match require_technician_or_admin(&req) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+
+    let user_uuid = match Uuid::parse_str(&claims.sub) {
+        Ok(uuid) => uuid,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user UUID"),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    match repository::canned_responses::list_canned_responses(&mut conn, user_uuid, query.category_id) {
+        Ok(responses) => HttpResponse::Ok().json(responses),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to get canned responses"),
+    }
+}
+
+/// Get a single canned response (technician/admin)
+pub async fn get_canned_response(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let claims = match require_technician_or_admin(&req) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+
+    let user_uuid = match Uuid::parse_str(&claims.sub) {
+        Ok(uuid) => uuid,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user UUID"),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    match repository::canned_responses::get_canned_response(&mut conn, path.into_inner()) {
+        Ok(response) if can_view(&response, &claims, user_uuid) => HttpResponse::Ok().json(response),
+        Ok(_) | Err(Error::NotFound) => HttpResponse::NotFound().json("Canned response not found"),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to get canned response"),
+    }
+}
+
+// ============================================================================
+// Create / Update / Delete
+// ============================================================================
+
+/// Request body for creating or replacing a canned response
+#[derive(Debug, Deserialize)]
+pub struct CannedResponseRequest {
+    pub title: String,
+    pub body: String,
+    pub category_id: Option<i32>,
+    pub is_shared: Option<bool>, // Defaults to shared
+}
+
+impl CannedResponseRequest {
+    fn validate(&self) -> Result<(), HttpResponse> {
+        if self.title.trim().is_empty() {
+            return Err(HttpResponse::BadRequest().json("Title is required"));
+        }
+        if self.title.len() > 255 {
+            return Err(HttpResponse::BadRequest().json("Title too long (max 255 characters)"));
+        }
+        if self.body.trim().is_empty() {
+            return Err(HttpResponse::BadRequest().json("Body is required"));
+        }
+        if self.body.len() > MAX_BODY_LENGTH {
+            return Err(HttpResponse::BadRequest().json("Body too long (max 20000 characters)"));
+        }
+        Ok(())
+    }
+}
+
+/// Create a canned response owned by the caller (technician/admin)
+pub async fn create_canned_response(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    body: web::Json<CannedResponseRequest>,
+) -> impl Responder {
+    let claims = match require_technician_or_admin(&req) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+
+    if let Err(e) = body.validate() {
+        return e;
+    }
+
+    let created_by = Uuid::parse_str(&claims.sub).ok();
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    let body = body.into_inner();
+    let new_response = NewCannedResponse {
+        title: body.title.trim().to_string(),
+        body: body.body,
+        category_id: body.category_id,
+        is_shared: body.is_shared.unwrap_or(true),
+        created_by,
+    };
+
+    match repository::canned_responses::create_canned_response(&mut conn, new_response) {
+        Ok(response) => HttpResponse::Created().json(response),
+        Err(Error::DatabaseError(diesel::result::DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            HttpResponse::BadRequest().json("Category does not exist")
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Failed to create canned response"),
+    }
+}
+
+/// Replace a canned response (creator, or admin for shared responses)
+pub async fn update_canned_response(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+    body: web::Json<CannedResponseRequest>,
+) -> impl Responder {
+    let claims = match require_technician_or_admin(&req) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+
+    let user_uuid = match Uuid::parse_str(&claims.sub) {
+        Ok(uuid) => uuid,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user UUID"),
+    };
+
+    if let Err(e) = body.validate() {
+        return e;
+    }
+
+    let response_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    let existing = match repository::canned_responses::get_canned_response(&mut conn, response_id) {
+        Ok(response) if can_view(&response, &claims, user_uuid) => response,
+        Ok(_) | Err(Error::NotFound) => return HttpResponse::NotFound().json("Canned response not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Database error"),
+    };
+
+    if !can_edit(&existing, &claims, user_uuid) {
+        return HttpResponse::Forbidden().json("Only the creator can change this canned response");
+    }
+
+    let body = body.into_inner();
+    let update = CannedResponseUpdate {
+        title: body.title.trim().to_string(),
+        body: body.body,
+        category_id: body.category_id,
+        is_shared: body.is_shared.unwrap_or(existing.is_shared),
+    };
+
+    match repository::canned_responses::update_canned_response(&mut conn, response_id, update) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(Error::DatabaseError(diesel::result::DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            HttpResponse::BadRequest().json("Category does not exist")
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Failed to update canned response"),
+    }
+}
+
+/// Delete a canned response (creator, or admin for shared responses)
+pub async fn delete_canned_response(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let claims = match require_technician_or_admin(&req) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+
+    let user_uuid = match Uuid::parse_str(&claims.sub) {
+        Ok(uuid) => uuid,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user UUID"),
+    };
+
+    let response_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    let existing = match repository::canned_responses::get_canned_response(&mut conn, response_id) {
+        Ok(response) if can_view(&response, &claims, user_uuid) => response,
+        Ok(_) | Err(Error::NotFound) => return HttpResponse::NotFound().json("Canned response not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Database error"),
+    };
+
+    if !can_edit(&existing, &claims, user_uuid) {
+        return HttpResponse::Forbidden().json("Only the creator can delete this canned response");
+    }
+
+    match repository::canned_responses::delete_canned_response(&mut conn, response_id) {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to delete canned response"),
+    }
+}
+
+// ============================================================================
+// Apply
+// ============================================================================
+
+/// Request body for applying a canned response to a ticket
+#[derive(Debug, Deserialize)]
+pub struct ApplyCannedResponseRequest {
+    pub ticket_id: i32,
+}
+
+/// Render a canned response for a ticket, filling in its placeholders
+pub async fn apply_canned_response(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+    body: web::Json<ApplyCannedResponseRequest>,
+) -> impl Responder {
+    let claims = match require_technician_or_admin(&req) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+
+    let user_uuid = match Uuid::parse_str(&claims.sub) {
+        Ok(uuid) => uuid,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user UUID"),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    let template = match repository::canned_responses::get_canned_response(&mut conn, path.into_inner()) {
+        Ok(response) if can_view(&response, &claims, user_uuid) => response,
+        Ok(_) | Err(Error::NotFound) => return HttpResponse::NotFound().json("Canned response not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Database error"),
+    };
+
+    let ticket = match repository::get_complete_ticket(&mut conn, body.ticket_id, true) {
+        Ok(ticket) => ticket,
+        Err(Error::NotFound) => return HttpResponse::NotFound().json("Ticket not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to get ticket"),
+    };
+
+    HttpResponse::Ok().json(json!({
+        "content": canned_responses::apply(&template.body, &ticket),
+    }))
+}
@@ -1,11 +1,13 @@
 use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
 use diesel::result::Error;
 use serde::Deserialize;
+use serde_json::json;
 use uuid::Uuid;
 
-use crate::db::Pool;
-use crate::models::{NewTicketCategory, TicketCategoryUpdate, Claims};
+use crate::db::{DbConnection, Pool};
+use crate::models::{NewTicketCategory, TicketCategoryUpdate, Claims, UserRole};
 use crate::repository;
+use crate::services::category_fields;
 use crate::utils::rbac::require_admin;
 
 // ============================================================================
@@ -89,6 +91,86 @@ pub async fn get_category_admin(
     }
 }
 
+/// Check that a category's default assignee and group exist, and that the
+/// assignee can be assigned tickets
+fn validate_category_defaults(
+    conn: &mut DbConnection,
+    default_assignee_uuid: Option<Uuid>,
+    default_group_id: Option<i32>,
+) -> Result<(), HttpResponse> {
+    if let Some(user_uuid) = default_assignee_uuid {
+        match repository::users::get_user_by_uuid(&user_uuid, conn) {
+            Ok(user) if user.role == UserRole::Technician || user.role == UserRole::Admin => {}
+            Ok(_) => {
+                return Err(HttpResponse::BadRequest().json(json!({
+                    "error": "Invalid default assignee",
+                    "message": "Only technicians and administrators can be assigned to tickets"
+                })))
+            }
+            Err(_) => {
+                return Err(HttpResponse::BadRequest().json(json!({
+                    "error": "User not found",
+                    "message": "The specified default assignee does not exist"
+                })))
+            }
+        }
+    }
+
+    if let Some(group_id) = default_group_id {
+        if repository::groups::get_group_by_id(conn, group_id).is_err() {
+            return Err(HttpResponse::BadRequest().json(json!({
+                "error": "Group not found",
+                "message": "The specified default group does not exist"
+            })));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check a category's color, icon and required field definitions,
+/// listing each problem. Required text fields must name a custom field.
+fn validate_category_fields(
+    conn: &mut DbConnection,
+    color: Option<&str>,
+    icon: Option<&str>,
+    required_fields: Option<&serde_json::Value>,
+) -> Result<(), HttpResponse> {
+    let mut errors = category_fields::validate_presentation(color, icon);
+    match required_fields.map(category_fields::parse_required_fields) {
+        Some(Ok(fields)) => {
+            let keys: Vec<String> = fields
+                .iter()
+                .filter(|field| field.kind == category_fields::RequiredFieldKind::Text)
+                .map(|field| field.key.clone())
+                .collect();
+            let defined: Vec<String> = match repository::custom_fields::get_definitions_by_keys(conn, &keys) {
+                Ok(definitions) => definitions.into_iter().map(|definition| definition.key).collect(),
+                Err(_) => return Err(HttpResponse::InternalServerError().json("Failed to load custom fields")),
+            };
+            for (i, field) in fields.iter().enumerate() {
+                if field.kind == category_fields::RequiredFieldKind::Text && !defined.contains(&field.key) {
+                    errors.push(category_fields::FieldError::new(
+                        format!("required_fields[{i}].key"),
+                        format!("no custom field with key '{}'", field.key),
+                    ));
+                }
+            }
+        }
+        Some(Err(field_errors)) => errors.extend(field_errors),
+        None => {}
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(HttpResponse::BadRequest().json(json!({
+            "error": "Invalid category",
+            "fields": errors,
+        })))
+    }
+}
+
 /// Request body for creating a category
 #[derive(Debug, Deserialize)]
 pub struct CreateCategoryRequest {
@@ -97,6 +179,12 @@ pub struct CreateCategoryRequest {
     pub color: Option<String>,
     pub icon: Option<String>,
     pub visible_to_group_ids: Option<Vec<i32>>, // If None or empty, category is public
+    /// Assignee when no assignment rule matches
+    pub default_assignee_uuid: Option<Uuid>,
+    /// Group to pick an assignee from when there is no default assignee
+    pub default_group_id: Option<i32>,
+    /// Fields tickets in the category must have; see `category_fields::RequiredField`
+    pub required_fields: Option<serde_json::Value>,
 }
 
 /// Create a new category (admin only)
@@ -121,6 +209,14 @@ pub async fn create_category(
         Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
     };
 
+    if let Err(e) = validate_category_fields(&mut conn, body.color.as_deref(), body.icon.as_deref(), body.required_fields.as_ref()) {
+        return e;
+    }
+
+    if let Err(e) = validate_category_defaults(&mut conn, body.default_assignee_uuid, body.default_group_id) {
+        return e;
+    }
+
     // Get next display order
     let display_order = repository::categories::get_next_display_order(&mut conn).unwrap_or_default();
 
@@ -132,6 +228,9 @@ pub async fn create_category(
         display_order,
         is_active: true,
         created_by,
+        default_assignee_uuid: body.default_assignee_uuid,
+        default_group_id: body.default_group_id,
+        required_fields: body.required_fields.clone().unwrap_or_else(|| json!([])),
     };
 
     match repository::categories::create_category(&mut conn, new_category) {
@@ -170,6 +269,10 @@ pub struct UpdateCategoryRequest {
     pub icon: Option<String>,
     pub is_active: Option<bool>,
     pub visible_to_group_ids: Option<Vec<i32>>, // If provided, replaces existing visibility
+    pub default_assignee_uuid: Option<Option<Uuid>>,
+    pub default_group_id: Option<Option<i32>>,
+    /// If provided, replaces the category's required fields
+    pub required_fields: Option<serde_json::Value>,
 }
 
 /// Update an existing category (admin only)
@@ -196,6 +299,18 @@ pub async fn update_category(
         Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
     };
 
+    if let Err(e) = validate_category_fields(&mut conn, body.color.as_deref(), body.icon.as_deref(), body.required_fields.as_ref()) {
+        return e;
+    }
+
+    if let Err(e) = validate_category_defaults(
+        &mut conn,
+        body.default_assignee_uuid.flatten(),
+        body.default_group_id.flatten(),
+    ) {
+        return e;
+    }
+
     let category_update = TicketCategoryUpdate {
         name: body.name.clone(),
         description: body.description.clone(),
@@ -203,6 +318,9 @@ pub async fn update_category(
         icon: body.icon.clone(),
         display_order: None,
         is_active: body.is_active,
+        default_assignee_uuid: body.default_assignee_uuid,
+        default_group_id: body.default_group_id,
+        required_fields: body.required_fields.clone(),
         updated_at: None,
     };
 
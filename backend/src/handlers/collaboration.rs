@@ -91,6 +91,29 @@ fn log_document_root_types(awareness: &Awareness, doc_id: &str) {
     let sv = txn.state_vector();
     trace!(doc_id = %doc_id, state_vector = ?sv, "Document state vector");
 }
+/// The cursor from an awareness message, if the sender included one
+fn awareness_cursor(msg: &[u8]) -> Option<serde_json::Value> {
+    use yrs::sync::Message as YMessage;
+
+    let YMessage::Awareness(update) = YMessage::decode_v1(msg).ok()? else {
+        return None;
+    };
+    update.clients.values().find_map(|entry| {
+        let state: serde_json::Value = serde_json::from_str(&entry.json).ok()?;
+        state.get("cursor").filter(|cursor| !cursor.is_null()).cloned()
+    })
+}
+
+/// The document update carried by a sync message (SyncStep2 or Update), if any
+fn sync_update_payload(msg: &[u8]) -> Option<Vec<u8>> {
+    use yrs::sync::{Message as YMessage, SyncMessage};
+
+    match YMessage::decode_v1(msg).ok()? {
+        YMessage::Sync(SyncMessage::SyncStep2(update)) | YMessage::Sync(SyncMessage::Update(update)) => Some(update),
+        _ => None,
+    }
+}
+
 use crate::models::{NewArticleContent, NewArticleContentRevision};
 use crate::utils::redis_yjs_cache::RedisYjsCache;
 
@@ -109,6 +132,10 @@ const EMPTY_ROOM_FINAL_SAVE_DELAY: Duration = Duration::from_secs(2);
 // How long to keep document state after room becomes empty
 #[allow(dead_code)]
 const EMPTY_ROOM_CLEANUP_DELAY: Duration = Duration::from_secs(300); // 5 minutes
+// Minimum time between cursor events broadcast for one editing session
+const CURSOR_BROADCAST_INTERVAL: Duration = Duration::from_secs(1);
+// How often a long editing session gets a save-point revision
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(600); // 10 minutes
 
 // Document type enum to distinguish between tickets and documentation
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -239,6 +266,7 @@ struct DocumentState {
     // Snapshot tracking (for version history)
     update_counter: u32,                    // Total updates since document creation
     last_snapshot_at: u32,                  // Update count when last snapshot created
+    last_snapshot_time: Instant,            // When the last snapshot was created
     contributors: std::collections::HashSet<Uuid>, // Contributors since last snapshot (only added on actual content changes)
 }
 
@@ -255,6 +283,7 @@ impl DocumentState {
             // Initialize snapshot tracking
             update_counter: 0,
             last_snapshot_at: 0,
+            last_snapshot_time: Instant::now(),
             contributors: std::collections::HashSet::new(),
         }
     }
@@ -347,10 +376,10 @@ impl DocumentState {
 
     // Snapshot management methods
     fn should_create_snapshot(&self) -> bool {
-        // Session-based revisions: snapshots are only created when editing sessions end
-        // (when room becomes empty), not based on update count thresholds.
-        // This provides more meaningful revision history based on actual editing sessions.
-        false
+        // Revisions are mostly created when editing sessions end (when the room becomes
+        // empty). Long sessions also get a save-point every SNAPSHOT_INTERVAL so an
+        // accidental deletion mid-session can be recovered.
+        !self.contributors.is_empty() && self.last_snapshot_time.elapsed() >= SNAPSHOT_INTERVAL
     }
 
     fn add_contributor(&mut self, user_uuid: Uuid) {
@@ -359,6 +388,7 @@ impl DocumentState {
 
     fn reset_snapshot_tracking(&mut self) {
         self.last_snapshot_at = self.update_counter;
+        self.last_snapshot_time = Instant::now();
         self.contributors.clear();
     }
 }
@@ -379,16 +409,23 @@ pub struct YjsAppState {
     pool: web::Data<crate::db::Pool>,
     redis_cache: Arc<RedisYjsCache>,
     sse_state: web::Data<crate::handlers::sse::SseState>,
+    search_service: Arc<crate::services::search::SearchService>,
 }
 
 impl YjsAppState {
-    pub fn new(pool: web::Data<crate::db::Pool>, redis_cache: Arc<RedisYjsCache>, sse_state: web::Data<crate::handlers::sse::SseState>) -> Self {
+    pub fn new(
+        pool: web::Data<crate::db::Pool>,
+        redis_cache: Arc<RedisYjsCache>,
+        sse_state: web::Data<crate::handlers::sse::SseState>,
+        search_service: Arc<crate::services::search::SearchService>,
+    ) -> Self {
         let state = YjsAppState {
             documents: Arc::new(RwLock::new(HashMap::new())),
             sessions: Arc::new(RwLock::new(HashMap::new())),
             pool,
             redis_cache,
             sse_state,
+            search_service,
         };
         // Start the periodic cleanup and save task
         let state_clone = state.clone();
@@ -398,6 +435,7 @@ impl YjsAppState {
             loop {
                 interval.tick().await;
                 state_clone.cleanup_stale_sessions().await;
+                state_clone.expire_presence().await;
                 state_clone.save_all_active_documents().await;
             }
         });
@@ -420,10 +458,10 @@ impl YjsAppState {
                 saved_count += 1;
             }
 
-            // Check for snapshot creation (every 500 updates)
+            // Save-point revision for long editing sessions
             if doc_state.should_create_snapshot() {
                 debug!(doc_id = %doc_id, updates_since_snapshot = doc_state.update_counter - doc_state.last_snapshot_at,
-                    "Snapshot threshold reached");
+                    "Snapshot interval reached");
 
                 // Clone contributors before passing to async function
                 let contributors = doc_state.contributors.clone();
@@ -448,6 +486,13 @@ impl YjsAppState {
                     doc_state.reset_snapshot_tracking();
                     snapshot_count += 1;
                 }
+
+                // The room is idle, so fold its update log into the snapshot
+                let state = self.clone();
+                let idle_doc_id = doc_id.clone();
+                actix::spawn(async move {
+                    state.compact_document(&idle_doc_id).await;
+                });
             }
 
             // YIJS BEST PRACTICE: Keep documents in memory indefinitely
@@ -695,6 +740,11 @@ impl YjsAppState {
 
         let awareness = Arc::new(awareness);
 
+        // Logged updates belong to the old document; start Redis over from the new state
+        let full_state = awareness.doc().transact().encode_state_as_update_v1(&StateVector::default());
+        self.redis_cache.clear_update_log(doc_id).await;
+        self.redis_cache.set_document(doc_id, &full_state).await;
+
         if let Some(doc_state) = documents.get_mut(doc_id) {
             // Replace the awareness with the new one
             doc_state.awareness = Arc::clone(&awareness);
@@ -708,6 +758,92 @@ impl YjsAppState {
         }
     }
 
+    // Log an incremental update to Redis, compacting the log once it grows
+    // past the configured thresholds
+    fn log_update(&self, doc_id: &str, update: Vec<u8>) {
+        let state = self.clone();
+        let doc_id = doc_id.to_string();
+        actix::spawn(async move {
+            if state.redis_cache.append_update(&doc_id, &update).await {
+                state.compact_document(&doc_id).await;
+            }
+        });
+    }
+
+    // Merge a document's Redis update log into a single snapshot and persist
+    // the compacted form to PostgreSQL
+    async fn compact_document(&self, doc_id: &str) {
+        let Some(doc_type) = DocumentType::from_doc_id(doc_id) else {
+            return;
+        };
+        let Some(compacted) = self.redis_cache.compact_document(doc_id).await else {
+            return;
+        };
+
+        let mut conn = match self.pool.get() {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(doc_id = %doc_id, error = ?e, "Database connection error when storing compacted document");
+                return;
+            }
+        };
+
+        let result = match doc_type {
+            DocumentType::Ticket(ticket_id) => repository::update_article_yjs_snapshot(
+                &mut conn, ticket_id, compacted.document, compacted.state_vector,
+            ).map(|_| ()),
+            DocumentType::Documentation(page_id) => repository::update_documentation_yjs_snapshot(
+                &mut conn, page_id, compacted.document, compacted.state_vector,
+            ).map(|_| ()),
+        };
+
+        if let Err(e) = result {
+            error!(doc_id = %doc_id, error = ?e, "Failed to store compacted document");
+        }
+    }
+
+    // Record a heartbeat from an editing session and announce joins/leaves
+    async fn presence_heartbeat(&self, doc_id: &str, session_id: &str, user_uuid: Uuid, name: &str) {
+        let changes = self.redis_cache.presence_heartbeat(doc_id, session_id, user_uuid, name).await;
+        if changes.joined {
+            crate::utils::sse::SseBroadcaster::broadcast_editor_joined(&self.sse_state, doc_id, user_uuid, name).await;
+        }
+        for left in changes.left {
+            crate::utils::sse::SseBroadcaster::broadcast_editor_left(&self.sse_state, doc_id, left).await;
+        }
+    }
+
+    // Remove an editing session from the document's presence
+    async fn presence_leave(&self, doc_id: &str, session_id: &str) {
+        for left in self.redis_cache.presence_leave(doc_id, session_id).await {
+            crate::utils::sse::SseBroadcaster::broadcast_editor_left(&self.sse_state, doc_id, left).await;
+        }
+    }
+
+    // Record and announce an editor's cursor
+    async fn presence_cursor(&self, doc_id: &str, session_id: &str, user_uuid: Uuid, cursor: serde_json::Value) {
+        if self.redis_cache.presence_cursor(doc_id, session_id, cursor.clone()).await {
+            crate::utils::sse::SseBroadcaster::broadcast_editor_cursor(&self.sse_state, doc_id, user_uuid, cursor).await;
+        }
+    }
+
+    // Expire editors whose heartbeats stopped (e.g. the backend instance
+    // serving them went away) and announce that they left
+    async fn expire_presence(&self) {
+        let doc_ids: Vec<String> = self.sessions.read().await.keys().cloned().collect();
+        for doc_id in doc_ids {
+            let (_, left) = self.redis_cache.presence(&doc_id).await;
+            for user_uuid in left {
+                crate::utils::sse::SseBroadcaster::broadcast_editor_left(&self.sse_state, &doc_id, user_uuid).await;
+            }
+        }
+    }
+
+    // Editors currently present on a document
+    pub async fn presence(&self, doc_id: &str) -> Vec<crate::utils::redis_yjs_cache::PresenceEntry> {
+        self.redis_cache.presence(doc_id).await.0
+    }
+
     // Track contributor for version history
     async fn add_contributor(&self, doc_id: &str, user_uuid: Uuid) {
         let mut documents = self.documents.write().await;
@@ -878,7 +1014,6 @@ impl YjsAppState {
     }
 
     // Helper method to save a document by ID
-    #[allow(dead_code)]
     async fn save_document_by_id(&self, doc_id: &str) {
         let mut documents = self.documents.write().await;
         if let Some(doc_state) = documents.get_mut(doc_id) {
@@ -972,6 +1107,7 @@ impl YjsAppState {
 
         // Save to database in a separate thread (cold storage - permanent backup)
         let pool = self.pool.clone();
+        let search_service = self.search_service.clone();
         let content = binary_content.clone(); // Already Vec<u8>
 
         match doc_type {
@@ -983,8 +1119,12 @@ impl YjsAppState {
                     match pool.get() {
                         Ok(mut conn) => {
                             match repository::update_article_yjs_state(&mut conn, ticket_id, content) {
-                                Ok(_) => {
+                                Ok(article) => {
                                     debug!(ticket_id, "Successfully saved Yjs snapshot for ticket");
+                                    // Reindex so the saved description is searchable
+                                    if let Ok(ticket) = repository::get_ticket_by_id(&mut conn, ticket_id) {
+                                        crate::services::search::indexing_tasks::spawn_index_ticket(search_service, ticket, Some(article));
+                                    }
                                 },
                                 Err(e) => error!(ticket_id, error = ?e, "Failed to save Yjs snapshot for ticket"),
                             }
@@ -1000,7 +1140,10 @@ impl YjsAppState {
                         Ok(mut conn) => {
                             // Update only the Yjs-related fields
                             match repository::update_documentation_yjs_state(&mut conn, doc_page_id, content) {
-                                Ok(_) => debug!(doc_page_id, "Successfully saved Yjs state for documentation page"),
+                                Ok(page) => {
+                                    debug!(doc_page_id, "Successfully saved Yjs state for documentation page");
+                                    crate::services::search::indexing_tasks::spawn_index_documentation(search_service, page);
+                                },
                                 Err(e) => error!(doc_page_id, error = ?e, "Failed to save Yjs state for documentation page"),
                             }
                         },
@@ -1168,6 +1311,8 @@ struct YjsWebSocket {
     #[allow(dead_code)]
     protocol: DefaultProtocol,
     user_uuid: Uuid, // User UUID for contributor tracking
+    user_name: String, // Display name shown to other editors
+    last_cursor_sent: Option<Instant>, // Throttles cursor presence events
     // Statistics for debugging
     messages_received: u32,
     pings_sent: u32,
@@ -1176,7 +1321,7 @@ struct YjsWebSocket {
 }
 
 impl YjsWebSocket {
-    fn new(doc_id: String, app_state: YjsAppState, user_uuid: Uuid) -> Self {
+    fn new(doc_id: String, app_state: YjsAppState, user_uuid: Uuid, user_name: String) -> Self {
         let id = Uuid::now_v7().to_string();
         let now = Instant::now();
 
@@ -1187,6 +1332,8 @@ impl YjsWebSocket {
             hb: now,
             protocol: DefaultProtocol,
             user_uuid,
+            user_name,
+            last_cursor_sent: None,
             messages_received: 0,
             pings_sent: 0,
             pongs_received: 0,
@@ -1227,6 +1374,16 @@ impl YjsWebSocket {
             act.pings_sent += 1;
             ctx.ping(b"");
 
+            // Keep this session's presence alive
+            let app_state = act.app_state.clone();
+            let doc_id = act.doc_id.clone();
+            let session_id = act.id.clone();
+            let user_uuid = act.user_uuid;
+            let user_name = act.user_name.clone();
+            actix::spawn(async move {
+                app_state.presence_heartbeat(&doc_id, &session_id, user_uuid, &user_name).await;
+            });
+
             if time_since_last_hb > CLIENT_TIMEOUT {
                 warn!(session_id = %act.id, idle_secs = time_since_last_hb.as_secs(),
                     "WebSocket Client heartbeat WARNING");
@@ -1245,6 +1402,20 @@ impl YjsWebSocket {
         // Otherwise the heartbeat checker thinks the connection is idle
         self.hb = Instant::now();
 
+        // Awareness messages carry the editor's cursor; share it (throttled) over SSE
+        if msg.first() == Some(&1) && self.last_cursor_sent.is_none_or(|sent| sent.elapsed() >= CURSOR_BROADCAST_INTERVAL) {
+            if let Some(cursor) = awareness_cursor(msg) {
+                self.last_cursor_sent = Some(Instant::now());
+                let app_state = self.app_state.clone();
+                let doc_id = self.doc_id.clone();
+                let session_id = self.id.clone();
+                let user_uuid = self.user_uuid;
+                actix::spawn(async move {
+                    app_state.presence_cursor(&doc_id, &session_id, user_uuid, cursor).await;
+                });
+            }
+        }
+
         let app_state = self.app_state.clone();
         let doc_id = self.doc_id.clone();
         let session_id = self.id.clone();
@@ -1338,6 +1509,11 @@ impl YjsWebSocket {
                     // This ensures revisions are only created for sessions with real edits
                     if content_changed {
                         app_state.add_contributor(&doc_id, user_uuid).await;
+
+                        // Keep the change in Redis until the next snapshot
+                        if let Some(update) = sync_update_payload(&msg_vec) {
+                            app_state.log_update(&doc_id, update);
+                        }
                     }
                 },
                 Err(e) => {
@@ -1364,8 +1540,11 @@ impl Actor for YjsWebSocket {
         let doc_id = self.doc_id.clone();
         let session_id = self.id.clone();
         let addr = ctx.address();
+        let user_uuid = self.user_uuid;
+        let user_name = self.user_name.clone();
         actix::spawn(async move {
             app_state.register_session(&doc_id, &session_id, addr).await;
+            app_state.presence_heartbeat(&doc_id, &session_id, user_uuid, &user_name).await;
         });
 
         debug!(doc_id = %self.doc_id, "Waiting for client sync request");
@@ -1391,6 +1570,7 @@ impl Actor for YjsWebSocket {
         actix::spawn(async move {
             // Remove the session first
             app_state.remove_session(&doc_id, &session_id).await;
+            app_state.presence_leave(&doc_id, &session_id).await;
 
             // Only force save if this was the last session in the room
             // The periodic save task will handle regular saves
@@ -1506,24 +1686,36 @@ pub async fn ws_handler(
     let token = req.cookie(crate::utils::cookies::ACCESS_TOKEN_COOKIE)
         .ok_or_else(|| actix_web::error::ErrorUnauthorized("No authentication cookie"))?;
 
-    // Validate the token and extract user UUID
-    let user_uuid = if let Some(pool) = req.app_data::<web::Data<crate::db::Pool>>() {
+    // Validate the token and extract the user
+    let (user_uuid, user_name) = if let Some(pool) = req.app_data::<web::Data<crate::db::Pool>>() {
         let mut conn = pool.get()
             .map_err(|_| actix_web::error::ErrorInternalServerError("Database connection failed"))?;
 
         // Use our centralized JWT validation
         use crate::utils::jwt::JwtUtils;
 
-        match JwtUtils::validate_token_with_user_check(token.value(), &mut conn).await {
-            Ok((_claims, user)) => user.uuid,
+        let user = match JwtUtils::validate_token_with_user_check(token.value(), &mut conn).await {
+            Ok((_claims, user)) => user,
             Err(_) => return Err(actix_web::error::ErrorUnauthorized("Invalid or expired token")),
+        };
+
+        // Documentation pages may be restricted to certain groups
+        if let Some(DocumentType::Documentation(page_id)) = DocumentType::from_doc_id(&doc_id) {
+            let is_admin = user.role == crate::models::UserRole::Admin;
+            match crate::repository::can_user_see_doc(&mut conn, &user.uuid, page_id, is_admin) {
+                Ok(true) => {}
+                Ok(false) => return Err(actix_web::error::ErrorForbidden("Access denied")),
+                Err(_) => return Err(actix_web::error::ErrorInternalServerError("Failed to check page visibility")),
+            }
         }
+
+        (user.uuid, user.name)
     } else {
         return Err(actix_web::error::ErrorInternalServerError("Database pool not available"));
     };
 
     debug!(doc_id = %doc_id, user_uuid = %user_uuid, "WebSocket authentication successful");
-    let actor = YjsWebSocket::new(doc_id, app_state.get_ref().clone(), user_uuid);
+    let actor = YjsWebSocket::new(doc_id, app_state.get_ref().clone(), user_uuid, user_name);
 
     // Use WsResponseBuilder to configure larger frame size for Yjs documents
     // Default is 64KB, but Yjs documents with history can grow larger
@@ -1532,6 +1724,39 @@ pub async fn ws_handler(
         .start()
 }
 
+/// GET /documents/:id/presence - Editors currently on a collaborative document
+/// ("ticket-N" or "doc-N")
+pub async fn get_document_presence(
+    path: web::Path<String>,
+    pool: web::Data<crate::db::Pool>,
+    app_state: web::Data<YjsAppState>,
+    auth: crate::extractors::AuthContext,
+) -> HttpResponse {
+    let doc_id = path.into_inner();
+    let doc_type = match DocumentType::from_doc_id(&doc_id) {
+        Some(doc_type) => doc_type,
+        None => return HttpResponse::BadRequest().json("Invalid document ID format (expected 'ticket-N' or 'doc-N')"),
+    };
+
+    if let DocumentType::Documentation(page_id) = doc_type {
+        let mut conn = match pool.get() {
+            Ok(conn) => conn,
+            Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+        };
+        match repository::can_user_see_doc(&mut conn, &auth.user_uuid, page_id, auth.is_admin()) {
+            Ok(true) => {}
+            Ok(false) => return HttpResponse::NotFound().json("Page not found"),
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to check page visibility"),
+        }
+    }
+
+    let editors = app_state.presence(&doc_id).await;
+    HttpResponse::Ok().json(json!({
+        "document_id": doc_id,
+        "editors": editors,
+    }))
+}
+
 // ============= Revision History API Endpoints =============
 
 /// GET /tickets/:id/revisions - List all revisions for a ticket
@@ -1700,8 +1925,23 @@ pub async fn restore_ticket_revision(
 
 // ============= Documentation Revision History API Endpoints =============
 
+// Check that the requesting user can see a documentation page. Hidden pages
+// are reported as not found.
+fn check_doc_access(req: &HttpRequest, conn: &mut crate::db::DbConnection, doc_id: i32) -> Result<(), HttpResponse> {
+    let claims = crate::utils::rbac::require_auth(req)?;
+    let user_uuid = crate::utils::parse_uuid(&claims.sub)
+        .map_err(|_| HttpResponse::BadRequest().json("Invalid user UUID in token"))?;
+
+    match crate::repository::can_user_see_doc(conn, &user_uuid, doc_id, claims.role == "admin") {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(HttpResponse::NotFound().json("Page not found")),
+        Err(_) => Err(HttpResponse::InternalServerError().json("Failed to check page visibility")),
+    }
+}
+
 /// GET /docs/:id/revisions - List all revisions for a documentation page
 pub async fn get_doc_revisions(
+    req: HttpRequest,
     doc_id: web::Path<i32>,
     pool: web::Data<crate::db::Pool>,
 ) -> HttpResponse {
@@ -1712,6 +1952,10 @@ pub async fn get_doc_revisions(
         Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
     };
 
+    if let Err(e) = check_doc_access(&req, &mut conn, doc_id) {
+        return e;
+    }
+
     // Get all revisions
     match crate::repository::documentation::get_documentation_revisions(&mut conn, doc_id) {
         Ok(revisions) => {
@@ -1723,6 +1967,7 @@ pub async fn get_doc_revisions(
 
 /// GET /docs/:id/revisions/:revision_number - Get a specific revision
 pub async fn get_doc_revision(
+    req: HttpRequest,
     path: web::Path<(i32, i32)>,
     pool: web::Data<crate::db::Pool>,
 ) -> HttpResponse {
@@ -1733,6 +1978,10 @@ pub async fn get_doc_revision(
         Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
     };
 
+    if let Err(e) = check_doc_access(&req, &mut conn, doc_id) {
+        return e;
+    }
+
     // Get the specific revision
     match crate::repository::documentation::get_documentation_revision(&mut conn, doc_id, revision_number) {
         Ok(revision) => {
@@ -1754,12 +2003,23 @@ pub async fn get_doc_revision(
     }
 }
 
-/// POST /docs/:id/restore/:revision_number - Restore documentation page to a specific revision
-pub async fn restore_doc_revision(
+#[derive(Debug, serde::Deserialize)]
+pub struct RevisionDiffQuery {
+    /// Revision to compare against; defaults to the page's current content
+    against: Option<i32>,
+}
+
+/// GET /docs/:id/revisions/:revision_number/diff - Block-level text diff from a
+/// revision to another revision or the current content
+pub async fn get_doc_revision_diff(
+    req: HttpRequest,
     path: web::Path<(i32, i32)>,
+    query: web::Query<RevisionDiffQuery>,
     pool: web::Data<crate::db::Pool>,
-    app_state: web::Data<YjsAppState>,
 ) -> HttpResponse {
+    use crate::services::search::extractors::extract_blocks_from_yjs;
+    use crate::utils::text_diff::diff_lines;
+
     let (doc_id, revision_number) = path.into_inner();
 
     let mut conn = match pool.get() {
@@ -1767,18 +2027,84 @@ pub async fn restore_doc_revision(
         Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
     };
 
-    // Get the revision to restore
+    if let Err(e) = check_doc_access(&req, &mut conn, doc_id) {
+        return e;
+    }
+
     let revision = match crate::repository::documentation::get_documentation_revision(&mut conn, doc_id, revision_number) {
         Ok(rev) => rev,
         Err(_) => return HttpResponse::NotFound().json("Revision not found"),
     };
 
+    let against_content = match query.against {
+        Some(against) => match crate::repository::documentation::get_documentation_revision(&mut conn, doc_id, against) {
+            Ok(rev) => rev.yjs_document_snapshot,
+            Err(_) => return HttpResponse::NotFound().json("Revision not found"),
+        },
+        None => match crate::repository::get_documentation_page(doc_id, &mut conn) {
+            Ok(page) => page.yjs_document.unwrap_or_default(),
+            Err(_) => return HttpResponse::NotFound().json("Documentation page not found"),
+        },
+    };
+
+    let old_blocks = extract_blocks_from_yjs(&revision.yjs_document_snapshot).unwrap_or_default();
+    let new_blocks = extract_blocks_from_yjs(&against_content).unwrap_or_default();
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "page_id": doc_id,
+        "revision_number": revision_number,
+        "against": query.against,
+        "lines": diff_lines(&old_blocks, &new_blocks),
+    }))
+}
+
+/// POST /docs/:id/restore/:revision_number - Restore documentation page to a specific revision
+pub async fn restore_doc_revision(
+    req: HttpRequest,
+    path: web::Path<(i32, i32)>,
+    pool: web::Data<crate::db::Pool>,
+    app_state: web::Data<YjsAppState>,
+    search_service: web::Data<Arc<crate::services::search::SearchService>>,
+) -> HttpResponse {
+    let claims = match crate::utils::rbac::require_technician_or_admin(&req) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+    let user_uuid = match crate::utils::parse_uuid(&claims.sub) {
+        Ok(uuid) => uuid,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user UUID in token"),
+    };
+
+    let (doc_id, revision_number) = path.into_inner();
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    if let Err(e) = check_doc_access(&req, &mut conn, doc_id) {
+        return e;
+    }
+
+    // Persist the restored content and record the restore as a new revision
+    let (page, new_revision_number) = match crate::repository::documentation::restore_documentation_revision(
+        &mut conn, doc_id, revision_number, user_uuid,
+    ) {
+        Ok(restored) => restored,
+        Err(diesel::result::Error::NotFound) => return HttpResponse::NotFound().json("Revision not found"),
+        Err(e) => {
+            error!(doc_id, revision_number, error = ?e, "Error restoring revision");
+            return HttpResponse::InternalServerError().json("Error restoring revision");
+        }
+    };
+    let snapshot = page.yjs_document.clone().unwrap_or_default();
+
     // Get the document ID string
     let doc_id_str = format!("doc-{doc_id}");
 
     // Decode the stored Yjs update (this is the full document state at that revision)
     use yrs::updates::decoder::Decode;
-    let update = match Update::decode_v1(&revision.yjs_document_snapshot) {
+    let update = match Update::decode_v1(&snapshot) {
         Ok(upd) => upd,
         Err(e) => {
             error!(doc_id, revision_number, error = ?e, "Error decoding revision update");
@@ -1826,8 +2152,9 @@ pub async fn restore_doc_revision(
     // Replace the document in app_state with the new one
     app_state.replace_document(&doc_id_str, new_doc).await;
 
-    // Mark document as changed to trigger save
+    // Save right away so Redis doesn't serve the old content on the next load
     app_state.mark_document_changed(&doc_id_str).await;
+    app_state.save_document_by_id(&doc_id_str).await;
 
     // Broadcast the full restored state to all connected clients
     use yrs::sync::Message;
@@ -1835,11 +2162,14 @@ pub async fn restore_doc_revision(
     let encoded = sync_message.encode_v1();
     app_state.broadcast(&doc_id_str, "", &encoded).await;
 
-    info!(doc_id, revision_number, "Restored documentation page to revision");
+    crate::services::search::indexing_tasks::spawn_index_documentation(search_service.get_ref().clone(), page);
+
+    info!(doc_id, revision_number, new_revision_number, "Restored documentation page to revision");
 
     HttpResponse::Ok().json(serde_json::json!({
         "success": true,
         "message": format!("Restored to revision {}", revision_number),
+        "revision_number": new_revision_number,
     }))
 }
 
@@ -1852,8 +2182,15 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/tickets/{ticket_id}/revisions", web::get().to(get_ticket_revisions))
             .route("/tickets/{ticket_id}/revisions/{revision_number}", web::get().to(get_ticket_revision))
             .route("/tickets/{ticket_id}/restore/{revision_number}", web::post().to(restore_ticket_revision))
-            .route("/docs/{doc_id}/revisions", web::get().to(get_doc_revisions))
-            .route("/docs/{doc_id}/revisions/{revision_number}", web::get().to(get_doc_revision))
-            .route("/docs/{doc_id}/restore/{revision_number}", web::post().to(restore_doc_revision))
+            .service(
+                // Documentation pages can be restricted to groups, so their
+                // history needs an authenticated user
+                web::scope("/docs")
+                    .wrap(actix_web::middleware::from_fn(crate::middleware::dual_auth_middleware))
+                    .route("/{doc_id}/revisions", web::get().to(get_doc_revisions))
+                    .route("/{doc_id}/revisions/{revision_number}", web::get().to(get_doc_revision))
+                    .route("/{doc_id}/revisions/{revision_number}/diff", web::get().to(get_doc_revision_diff))
+                    .route("/{doc_id}/restore/{revision_number}", web::post().to(restore_doc_revision))
+            )
     );
 }
\ No newline at end of file
@@ -0,0 +1,179 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use diesel::result::Error;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::models::{CustomFieldDefinitionUpdate, NewCustomFieldDefinition};
+use crate::repository;
+use crate::repository::custom_fields::CustomFieldError;
+use crate::utils::rbac::{require_admin, require_auth};
+
+fn error_response(e: CustomFieldError, action: &str) -> HttpResponse {
+    match e {
+        CustomFieldError::Invalid(errors) => HttpResponse::BadRequest().json(json!({
+            "error": "Invalid custom field",
+            "fields": errors,
+        })),
+        CustomFieldError::Database(Error::NotFound) => HttpResponse::NotFound().json("Custom field not found"),
+        CustomFieldError::Database(_) => HttpResponse::InternalServerError().json(format!("Failed to {action}")),
+    }
+}
+
+// ============================================================================
+// List
+// ============================================================================
+
+/// Query parameters for listing custom fields
+#[derive(Debug, Deserialize)]
+pub struct ListCustomFieldsQuery {
+    /// Only fields that apply to this category (plus those without a category)
+    pub category_id: Option<i32>,
+}
+
+/// List custom field definitions, for ticket forms and filters
+pub async fn list_custom_fields(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    query: web::Query<ListCustomFieldsQuery>,
+) -> impl Responder {
+    if let Err(e) = require_auth(&req) {
+        return e;
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    match repository::custom_fields::list_definitions(&mut conn, query.category_id) {
+        Ok(definitions) => HttpResponse::Ok().json(definitions),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to get custom fields"),
+    }
+}
+
+// ============================================================================
+// Admin Create / Update / Delete
+// ============================================================================
+
+/// Request body for defining a custom field
+#[derive(Debug, Deserialize)]
+pub struct CreateCustomFieldRequest {
+    pub key: String,
+    pub label: String,
+    /// text, number, date, select or checkbox
+    pub field_type: String,
+    /// Choices of a select field
+    pub options: Option<serde_json::Value>,
+    /// Limit the field to one category
+    pub category_id: Option<i32>,
+    pub display_order: Option<i32>,
+}
+
+/// Define a custom field (admin only)
+pub async fn create_custom_field(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    body: web::Json<CreateCustomFieldRequest>,
+) -> impl Responder {
+    let claims = match require_admin(&req) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    if let Some(category_id) = body.category_id {
+        if repository::categories::get_category_by_id(&mut conn, category_id).is_err() {
+            return HttpResponse::BadRequest().json("Category not found");
+        }
+    }
+
+    let body = body.into_inner();
+    let definition = NewCustomFieldDefinition {
+        key: body.key,
+        label: body.label,
+        field_type: body.field_type,
+        options: body.options.unwrap_or_else(|| json!([])),
+        category_id: body.category_id,
+        display_order: body.display_order.unwrap_or_default(),
+        created_by: Uuid::parse_str(&claims.sub).ok(),
+    };
+
+    match repository::custom_fields::create_definition(&mut conn, definition) {
+        Ok(definition) => HttpResponse::Created().json(definition),
+        Err(e) => error_response(e, "create custom field"),
+    }
+}
+
+/// Request body for changing a custom field; its key and type are fixed
+#[derive(Debug, Deserialize)]
+pub struct UpdateCustomFieldRequest {
+    pub label: Option<String>,
+    pub options: Option<serde_json::Value>,
+    pub category_id: Option<Option<i32>>,
+    pub display_order: Option<i32>,
+}
+
+/// Change a custom field's label, options, category or order (admin only)
+pub async fn update_custom_field(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+    body: web::Json<UpdateCustomFieldRequest>,
+) -> impl Responder {
+    if let Err(e) = require_admin(&req) {
+        return e;
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    if let Some(Some(category_id)) = body.category_id {
+        if repository::categories::get_category_by_id(&mut conn, category_id).is_err() {
+            return HttpResponse::BadRequest().json("Category not found");
+        }
+    }
+
+    let body = body.into_inner();
+    let update = CustomFieldDefinitionUpdate {
+        label: body.label,
+        options: body.options,
+        category_id: body.category_id,
+        display_order: body.display_order,
+        updated_at: None,
+    };
+
+    match repository::custom_fields::update_definition(&mut conn, path.into_inner(), update) {
+        Ok(definition) => HttpResponse::Ok().json(definition),
+        Err(e) => error_response(e, "update custom field"),
+    }
+}
+
+/// Delete a custom field and every ticket's value for it (admin only)
+pub async fn delete_custom_field(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    if let Err(e) = require_admin(&req) {
+        return e;
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    match repository::custom_fields::delete_definition(&mut conn, path.into_inner()) {
+        Ok(0) => HttpResponse::NotFound().json("Custom field not found"),
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to delete custom field"),
+    }
+}
@@ -2,6 +2,7 @@ use actix_web::{web, HttpResponse, HttpRequest, HttpMessage, Responder};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use diesel::result::Error;
+use diesel::Connection;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, error};
@@ -53,6 +54,7 @@ pub struct DeviceResponse {
     pub serial_number: String,
     pub model: String,
     pub warranty_status: String,
+    pub warranty_expiry_date: Option<String>,
     pub manufacturer: Option<String>,
     pub primary_user_uuid: Option<String>,
     pub intune_device_id: Option<String>,
@@ -75,6 +77,39 @@ pub struct UserInfo {
     pub avatar_thumb: Option<String>,
 }
 
+impl UserInfo {
+    fn from_user(user: User, conn: &mut crate::db::DbConnection) -> Self {
+        let role = match user.role {
+            crate::models::UserRole::Admin => "admin",
+            crate::models::UserRole::Technician => "technician",
+            crate::models::UserRole::User => "user",
+        }.to_string();
+
+        // Fetch primary email from user_emails table
+        let email = repository::user_helpers::get_primary_email(&user.uuid, conn)
+            .unwrap_or_else(|| user.name.clone());
+
+        Self {
+            uuid: utils::uuid_to_string(&user.uuid),
+            name: user.name,
+            email,
+            role,
+            avatar_url: user.avatar_url,
+            avatar_thumb: user.avatar_thumb,
+        }
+    }
+}
+
+// One entry in a device's primary user history
+#[derive(Debug, Serialize)]
+pub struct DeviceAssignmentResponse {
+    pub id: i32,
+    pub user: Option<UserInfo>,
+    pub assigned_by: Option<UserInfo>,
+    pub assigned_at: String,
+    pub unassigned_at: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct GroupInfo {
     pub id: i32,
@@ -107,6 +142,7 @@ impl DeviceResponse {
             serial_number: device.serial_number.unwrap_or_default(),
             model: device.model.unwrap_or_default(),
             warranty_status: device.warranty_status.unwrap_or_default(),
+            warranty_expiry_date: device.warranty_expiry_date.map(|d| d.format("%Y-%m-%d").to_string()),
             manufacturer: device.manufacturer,
             primary_user_uuid: device.primary_user_uuid.map(|uuid| utils::uuid_to_string(&uuid)),
             intune_device_id: device.intune_device_id.clone(),
@@ -115,27 +151,7 @@ impl DeviceResponse {
             updated_at: device.updated_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
             last_sync_time: device.last_sync_time.map(|t| t.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()),
             is_editable,
-            primary_user: user.map(|u| {
-                let name = u.name.clone();
-                let role = match u.role {
-                    crate::models::UserRole::Admin => "admin",
-                    crate::models::UserRole::Technician => "technician",
-                    crate::models::UserRole::User => "user",
-                }.to_string();
-
-                // Fetch primary email from user_emails table
-                let email = repository::user_helpers::get_primary_email(&u.uuid, conn)
-                    .unwrap_or_else(|| name.clone());
-
-                UserInfo {
-                    uuid: utils::uuid_to_string(&u.uuid),
-                    name,
-                    email,
-                    role,
-                    avatar_url: u.avatar_url,
-                    avatar_thumb: u.avatar_thumb,
-                }
-            }),
+            primary_user: user.map(|u| UserInfo::from_user(u, conn)),
             groups: groups.into_iter().map(GroupInfo::from).collect(),
         }
     }
@@ -259,6 +275,60 @@ pub async fn get_device_by_id(
     }
 }
 
+/// Get a device's primary user history, most recent first
+pub async fn get_device_assignment_history(
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let device_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    if let Err(e) = repository::get_device_by_id(&mut conn, device_id) {
+        return match e {
+            Error::NotFound => HttpResponse::NotFound().json(format!("Device {device_id} not found")),
+            _ => {
+                error!(device_id, error = ?e, "Database error getting device");
+                HttpResponse::InternalServerError().json(format!("Failed to get device {device_id}"))
+            }
+        };
+    }
+
+    let history = match repository::devices::get_device_assignment_history(&mut conn, device_id) {
+        Ok(history) => history,
+        Err(e) => {
+            error!(device_id, error = ?e, "Database error getting device assignment history");
+            return HttpResponse::InternalServerError().json(format!("Failed to get assignment history for device {device_id}"));
+        }
+    };
+
+    // The same few users tend to repeat, so look each one up once
+    let mut users: HashMap<Uuid, Option<User>> = HashMap::new();
+    let mut lookup = |conn: &mut crate::db::DbConnection, uuid: Option<Uuid>| {
+        let uuid = uuid?;
+        users
+            .entry(uuid)
+            .or_insert_with(|| get_user_by_uuid(conn, &uuid))
+            .clone()
+            .map(|user| UserInfo::from_user(user, conn))
+    };
+
+    let response: Vec<DeviceAssignmentResponse> = history
+        .into_iter()
+        .map(|entry| DeviceAssignmentResponse {
+            id: entry.id,
+            user: lookup(&mut conn, Some(entry.user_uuid)),
+            assigned_by: lookup(&mut conn, entry.assigned_by),
+            assigned_at: entry.assigned_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            unassigned_at: entry.unassigned_at.map(|t| t.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()),
+        })
+        .collect();
+
+    HttpResponse::Ok().json(response)
+}
+
 /// Get devices for a specific user
 pub async fn get_user_devices(
     pool: web::Data<Pool>,
@@ -317,7 +387,16 @@ pub async fn create_device(
         Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
     };
 
-    match repository::create_device(&mut conn, device.into_inner()) {
+    let created_by = Uuid::parse_str(&claims.sub).ok();
+    let result = conn.transaction(|conn| {
+        let device = repository::create_device(conn, device.into_inner())?;
+        match device.primary_user_uuid {
+            Some(user_uuid) => repository::devices::reassign_primary_user(conn, device.id, user_uuid, created_by),
+            None => Ok(device),
+        }
+    });
+
+    match result {
         Ok(device) => {
             let device_id = device.id;
 
@@ -403,12 +482,24 @@ pub async fn update_device(
         }));
     }
 
-    let update_data = device_update.into_inner();
+    let mut update_data = device_update.into_inner();
     
     // Convert to JSON before the move for SSE broadcasting
     let update_json = serde_json::to_value(&update_data).unwrap_or_default();
-    
-    match repository::update_device(&mut conn, device_id, update_data) {
+
+    // Primary user changes go through the assignment history
+    let new_primary_user = update_data.primary_user_uuid.take();
+    let changed_by = Uuid::parse_str(&user_info.sub).ok();
+
+    let result = conn.transaction(|conn| {
+        let device = repository::update_device(conn, device_id, update_data)?;
+        match new_primary_user {
+            Some(user_uuid) => repository::devices::reassign_primary_user(conn, device_id, user_uuid, changed_by),
+            None => Ok(device),
+        }
+    });
+
+    match result {
         Ok(device) => {
             // Re-index the updated device in search
             indexing_tasks::spawn_index_device(search_service.get_ref().clone(), device.clone());
@@ -575,6 +666,7 @@ pub async fn unmanage_device(
         os_version: None,
         is_managed: None,
         enrollment_date: None,
+        warranty_expiry_date: None,
         updated_at: None,
     };
 
@@ -649,6 +741,177 @@ pub async fn get_paginated_devices_excluding(
 }
 
 // Bulk device operations request
+// Per-row outcome of a device import
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DeviceImportResult {
+    row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    serial_number: Option<String>,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+impl DeviceImportResult {
+    fn saved(row: usize, device: &Device, created: bool) -> Self {
+        Self {
+            row,
+            serial_number: device.serial_number.clone(),
+            status: if created { "created" } else { "updated" },
+            device_id: Some(device.id),
+            reason: None,
+        }
+    }
+
+    fn failed(row: usize, serial_number: Option<String>, reason: impl Into<String>) -> Self {
+        Self { row, serial_number, status: "failed", device_id: None, reason: Some(reason.into()) }
+    }
+}
+
+// Upsert each row by serial number inside one transaction. Rows that can't be
+// imported are reported as failed without affecting the others. Returns the
+// per-row results and the devices that were written.
+fn import_device_rows(
+    conn: &mut crate::db::DbConnection,
+    rows: Vec<serde_json::Value>,
+    imported_by: Option<Uuid>,
+) -> Result<(Vec<DeviceImportResult>, Vec<Device>), Error> {
+    use diesel::result::DatabaseErrorKind;
+
+    conn.transaction(|conn| {
+        let mut results = Vec::with_capacity(rows.len());
+        let mut saved = Vec::new();
+
+        for (row, value) in rows.into_iter().enumerate() {
+            let serial_hint = value.get("serial_number").and_then(|v| v.as_str()).map(str::to_string);
+            let mut new_device: NewDevice = match serde_json::from_value(value) {
+                Ok(device) => device,
+                Err(e) => {
+                    results.push(DeviceImportResult::failed(row, serial_hint, format!("Invalid device data: {e}")));
+                    continue;
+                }
+            };
+
+            let serial = new_device.serial_number.as_deref().map(str::trim).unwrap_or_default().to_string();
+            if serial.is_empty() {
+                results.push(DeviceImportResult::failed(row, None, "Missing serial number"));
+                continue;
+            }
+            new_device.serial_number = Some(serial.clone());
+
+            // Devices synced from Microsoft Graph are managed there, same as manual edits
+            match repository::devices::get_device_by_serial(conn, &serial) {
+                Ok(existing) if existing.intune_device_id.is_some() || existing.entra_device_id.is_some() => {
+                    results.push(DeviceImportResult::failed(
+                        row,
+                        Some(serial),
+                        "Device is managed by Microsoft Intune/Entra and cannot be updated by import",
+                    ));
+                    continue;
+                }
+                Ok(_) | Err(Error::NotFound) => {}
+                Err(e) => return Err(e),
+            }
+
+            // Primary user changes go through the assignment history
+            let primary_user = new_device.primary_user_uuid.take();
+
+            // Savepoint per row so one bad row doesn't abort the whole import
+            let outcome = conn.transaction(|conn| {
+                let (device, created) = repository::devices::upsert_device_by_serial(conn, new_device)?;
+                let device = match primary_user {
+                    Some(user_uuid) => repository::devices::reassign_primary_user(conn, device.id, user_uuid, imported_by)?,
+                    None => device,
+                };
+                Ok::<_, Error>((device, created))
+            });
+
+            match outcome {
+                Ok((device, created)) => {
+                    results.push(DeviceImportResult::saved(row, &device, created));
+                    saved.push(device);
+                }
+                Err(Error::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+                    results.push(DeviceImportResult::failed(row, Some(serial), "Primary user does not exist"));
+                }
+                Err(Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                    results.push(DeviceImportResult::failed(row, Some(serial), "Conflicts with another device's identifiers"));
+                }
+                Err(e) => {
+                    error!(row, serial_number = %serial, error = ?e, "Database error importing device");
+                    results.push(DeviceImportResult::failed(row, Some(serial), "Failed to save device"));
+                }
+            }
+        }
+
+        Ok((results, saved))
+    })
+}
+
+/// Import devices in bulk, creating or updating by serial number
+/// (technician or admin only)
+pub async fn import_devices(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    search_service: web::Data<Arc<SearchService>>,
+    body: web::Json<Vec<serde_json::Value>>,
+) -> impl Responder {
+    let claims = match req.extensions().get::<Claims>() {
+        Some(claims) => claims.clone(),
+        None => return HttpResponse::Unauthorized().json(json!({
+            "error": "Unauthorized",
+            "message": "Authentication required"
+        })),
+    };
+
+    if !is_technician_or_admin(&claims) {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Only technicians and administrators can import devices"
+        }));
+    }
+
+    let rows = body.into_inner();
+    if rows.is_empty() {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Bad Request",
+            "message": "No devices provided"
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "error": "Database connection failed"
+        })),
+    };
+
+    let (results, saved) = match import_device_rows(&mut conn, rows, Uuid::parse_str(&claims.sub).ok()) {
+        Ok(imported) => imported,
+        Err(e) => {
+            error!(error = ?e, "Database error importing devices");
+            return HttpResponse::InternalServerError().json(json!({
+                "error": "Internal Server Error",
+                "message": "Failed to import devices"
+            }));
+        }
+    };
+
+    for device in saved {
+        indexing_tasks::spawn_index_device(search_service.get_ref().clone(), device);
+    }
+
+    let count = |status: &str| results.iter().filter(|r| r.status == status).count();
+    HttpResponse::Ok().json(json!({
+        "created": count("created"),
+        "updated": count("updated"),
+        "failed": count("failed"),
+        "results": results,
+    }))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BulkDeviceActionRequest {
     action: String,
@@ -720,4 +983,47 @@ pub async fn bulk_devices(
             "message": format!("Unknown action: {}", action)
         })),
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+    #[test]
+    fn import_reports_created_updated_and_failed_rows() {
+        let mut conn = setup_test_connection();
+        let tech = TestFixtures::create_user(&mut conn, "importtech", UserRole::Technician);
+
+        let existing = repository::devices::upsert_device_by_serial(&mut conn, serde_json::from_value(json!({
+            "name": "Old Name",
+            "serial_number": "IMPORT-SN-EXISTING",
+        })).unwrap()).unwrap().0;
+
+        let rows = vec![
+            json!({ "name": "New Laptop", "serial_number": " IMPORT-SN-NEW ", "primary_user_uuid": tech.uuid }),
+            json!({ "name": "Renamed Laptop", "serial_number": "IMPORT-SN-EXISTING" }),
+            json!({ "name": "No Serial" }),
+            json!({ "name": "Blank Serial", "serial_number": "  " }),
+            json!({ "serial_number": "IMPORT-SN-NONAME" }),
+        ];
+        let (results, saved) = import_device_rows(&mut conn, rows, Some(tech.uuid)).unwrap();
+
+        let statuses: Vec<&str> = results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, vec!["created", "updated", "failed", "failed", "failed"]);
+        assert_eq!(results[1].device_id, Some(existing.id));
+        assert_eq!(results[2].reason.as_deref(), Some("Missing serial number"));
+        assert_eq!(results[3].reason.as_deref(), Some("Missing serial number"));
+        assert!(results[4].reason.as_deref().unwrap().starts_with("Invalid device data"));
+        assert_eq!(results[4].serial_number.as_deref(), Some("IMPORT-SN-NONAME"));
+        assert_eq!(saved.len(), 2);
+
+        let created = repository::devices::get_device_by_serial(&mut conn, "IMPORT-SN-NEW").unwrap();
+        assert_eq!(created.primary_user_uuid, Some(tech.uuid));
+        let history = repository::devices::get_device_assignment_history(&mut conn, created.id).unwrap();
+        assert_eq!(history.len(), 1);
+
+        let renamed = repository::get_device_by_id(&mut conn, existing.id).unwrap();
+        assert_eq!(renamed.name, "Renamed Laptop");
+    }
+}
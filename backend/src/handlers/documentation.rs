@@ -10,10 +10,11 @@ use yrs::{Doc, Transact, ReadTxn, WriteTxn, GetString, Options, updates::decoder
 use regex::Regex;
 
 use crate::db::{Pool, DbConnection};
+use crate::extractors::AuthContext;
 use crate::models::{Claims, NewDocumentationPage, DocumentationPageWithChildren, DocumentationStatus, DocumentationPage, DocumentationPageResponse, UserInfoWithAvatar};
 use crate::repository;
 use crate::utils;
-use crate::utils::rbac::{is_admin, is_technician_or_admin};
+use crate::utils::rbac::{is_admin, is_technician_or_admin, require_admin};
 use crate::services::search::SearchService;
 use crate::services::search::indexing_tasks;
 
@@ -213,16 +214,24 @@ fn to_page_responses(
         .collect()
 }
 
+// Whether the requesting user may see a page. Hidden pages are reported as
+// not found so their existence isn't leaked.
+fn can_view(conn: &mut DbConnection, auth: &AuthContext, page_id: i32) -> bool {
+    repository::can_user_see_doc(conn, &auth.user_uuid, page_id, auth.is_admin()).unwrap_or(false)
+}
+
 // Get all documentation pages
 pub async fn get_documentation_pages(
     pool: web::Data<Pool>,
+    auth: AuthContext,
 ) -> impl Responder {
     let mut conn = match pool.get() {
         Ok(conn) => conn,
         Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
     };
 
-    match repository::get_documentation_pages(&mut conn) {
+    match repository::get_documentation_pages(&mut conn)
+        .and_then(|pages| repository::filter_visible_docs(&mut conn, pages, &auth.user_uuid, auth.is_admin())) {
         Ok(pages) => {
             match to_page_responses(pages, &mut conn) {
                 Ok(responses) => HttpResponse::Ok().json(responses),
@@ -233,10 +242,27 @@ pub async fn get_documentation_pages(
     }
 }
 
+// Get the documentation page tree for the sidebar table of contents
+pub async fn get_documentation_tree(
+    pool: web::Data<Pool>,
+    auth: AuthContext,
+) -> impl Responder {
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    match repository::get_doc_tree_for_user(&mut conn, &auth.user_uuid, auth.is_admin()) {
+        Ok(tree) => HttpResponse::Ok().json(tree),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch documentation tree"),
+    }
+}
+
 // Get a single documentation page by ID
 pub async fn get_documentation_page(
     id: web::Path<i32>,
     pool: web::Data<Pool>,
+    auth: AuthContext,
 ) -> impl Responder {
     let page_id = id.into_inner();
     let mut conn = match pool.get() {
@@ -245,6 +271,7 @@ pub async fn get_documentation_page(
     };
 
     match repository::get_documentation_page(page_id, &mut conn) {
+        Ok(page) if !can_view(&mut conn, &auth, page.id) => HttpResponse::NotFound().json("Page not found"),
         Ok(page) => {
             match to_page_response(page, &mut conn) {
                 Ok(response) => HttpResponse::Ok().json(response),
@@ -259,6 +286,7 @@ pub async fn get_documentation_page(
 pub async fn get_documentation_page_by_slug(
     slug: web::Path<String>,
     pool: web::Data<Pool>,
+    auth: AuthContext,
 ) -> impl Responder {
     let page_slug = slug.into_inner();
     let mut conn = match pool.get() {
@@ -267,6 +295,7 @@ pub async fn get_documentation_page_by_slug(
     };
 
     match repository::get_documentation_page_by_slug(&page_slug, &mut conn) {
+        Ok(page) if !can_view(&mut conn, &auth, page.id) => HttpResponse::NotFound().json("Page not found"),
         Ok(page) => {
             match to_page_response(page, &mut conn) {
                 Ok(response) => HttpResponse::Ok().json(response),
@@ -420,6 +449,17 @@ pub async fn update_documentation_page(
     // Check if the page exists and get its current state
     match repository::get_documentation_page(page_id, &mut conn) {
         Ok(_existing_page) => {
+            if let Some(new_parent_id) = page.parent_id {
+                match repository::creates_cycle(&mut conn, page_id, new_parent_id) {
+                    Ok(false) => {}
+                    Ok(true) => return HttpResponse::BadRequest().json(json!({
+                        "error": "Circular reference",
+                        "message": "A page cannot be a child of itself or its own descendant"
+                    })),
+                    Err(_) => return HttpResponse::InternalServerError().json("Failed to validate parent page"),
+                }
+            }
+
             // Get the user UUID for last_edited_by
             let user_uuid = match utils::parse_uuid(&claims.sub) {
                 Ok(uuid) => uuid,
@@ -553,13 +593,15 @@ pub async fn delete_documentation_page(
 // Get top-level documentation pages
 pub async fn get_top_level_documentation_pages(
     pool: web::Data<Pool>,
+    auth: AuthContext,
 ) -> impl Responder {
     let mut conn = match pool.get() {
         Ok(conn) => conn,
         Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
     };
 
-    match repository::get_top_level_pages(&mut conn) {
+    match repository::get_top_level_pages(&mut conn)
+        .and_then(|pages| repository::filter_visible_docs(&mut conn, pages, &auth.user_uuid, auth.is_admin())) {
         Ok(pages) => {
             match to_page_responses(pages, &mut conn) {
                 Ok(responses) => HttpResponse::Ok().json(responses),
@@ -574,6 +616,7 @@ pub async fn get_top_level_documentation_pages(
 pub async fn get_documentation_pages_by_parent_id(
     parent_id: web::Path<i32>,
     pool: web::Data<Pool>,
+    auth: AuthContext,
 ) -> impl Responder {
     let parent = parent_id.into_inner();
     let mut conn = match pool.get() {
@@ -581,7 +624,8 @@ pub async fn get_documentation_pages_by_parent_id(
         Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
     };
 
-    match repository::get_pages_by_parent_id(parent, &mut conn) {
+    match repository::get_pages_by_parent_id(parent, &mut conn)
+        .and_then(|pages| repository::filter_visible_docs(&mut conn, pages, &auth.user_uuid, auth.is_admin())) {
         Ok(pages) => {
             match to_page_responses(pages, &mut conn) {
                 Ok(responses) => HttpResponse::Ok().json(responses),
@@ -596,6 +640,7 @@ pub async fn get_documentation_pages_by_parent_id(
 pub async fn get_page_with_children_by_parent_id(
     id: web::Path<i32>,
     pool: web::Data<Pool>,
+    auth: AuthContext,
 ) -> impl Responder {
     let page_id = id.into_inner();
     let mut conn = match pool.get() {
@@ -608,10 +653,16 @@ pub async fn get_page_with_children_by_parent_id(
         Ok(page) => page,
         Err(_) => return HttpResponse::NotFound().json("Page not found"),
     };
+    if !can_view(&mut conn, &auth, page.id) {
+        return HttpResponse::NotFound().json("Page not found");
+    }
 
     // Then get its children
     let children = match repository::get_pages_by_parent_id(page_id, &mut conn) {
-        Ok(children) => children,
+        Ok(children) => match repository::filter_visible_docs(&mut conn, children, &auth.user_uuid, auth.is_admin()) {
+            Ok(children) => children,
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch children"),
+        },
         Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch children"),
     };
 
@@ -628,6 +679,7 @@ pub async fn get_page_with_children_by_parent_id(
 pub async fn get_page_with_ordered_children(
     id: web::Path<i32>,
     pool: web::Data<Pool>,
+    auth: AuthContext,
 ) -> impl Responder {
     let page_id = id.into_inner();
     let mut conn = match pool.get() {
@@ -635,8 +687,21 @@ pub async fn get_page_with_ordered_children(
         Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
     };
 
+    if !can_view(&mut conn, &auth, page_id) {
+        return HttpResponse::NotFound().json("Page not found");
+    }
+
     match repository::get_page_with_ordered_children(&mut conn, page_id) {
-        Ok(page_with_children) => HttpResponse::Ok().json(page_with_children),
+        Ok(mut page_with_children) => {
+            let children = std::mem::take(&mut page_with_children.children);
+            match repository::filter_visible_docs(&mut conn, children, &auth.user_uuid, auth.is_admin()) {
+                Ok(children) => {
+                    page_with_children.children = children;
+                    HttpResponse::Ok().json(page_with_children)
+                },
+                Err(_) => HttpResponse::InternalServerError().json("Failed to fetch children"),
+            }
+        },
         Err(_) => HttpResponse::NotFound().json("Page not found or error fetching children"),
     }
 }
@@ -645,6 +710,7 @@ pub async fn get_page_with_ordered_children(
 pub async fn get_ordered_pages_by_parent_id(
     parent_id: web::Path<i32>,
     pool: web::Data<Pool>,
+    auth: AuthContext,
 ) -> impl Responder {
     let parent = parent_id.into_inner();
     let mut conn = match pool.get() {
@@ -652,7 +718,8 @@ pub async fn get_ordered_pages_by_parent_id(
         Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
     };
 
-    match repository::get_ordered_pages_by_parent_id(&mut conn, parent) {
+    match repository::get_ordered_pages_by_parent_id(&mut conn, parent)
+        .and_then(|pages| repository::filter_visible_docs(&mut conn, pages, &auth.user_uuid, auth.is_admin())) {
         Ok(pages) => {
             match to_page_responses(pages, &mut conn) {
                 Ok(responses) => HttpResponse::Ok().json(responses),
@@ -665,7 +732,8 @@ pub async fn get_ordered_pages_by_parent_id(
 
 #[derive(Deserialize)]
 pub struct ReorderPagesRequest {
-    pub parent_id: i32,
+    /// Parent whose children are reordered; omitted or null for top-level pages
+    pub parent_id: Option<i32>,
     pub page_orders: Vec<crate::models::PageOrder>,
 }
 
@@ -696,10 +764,16 @@ pub async fn reorder_pages(
         }));
     }
 
-    match repository::reorder_pages(&mut conn, Some(request.parent_id), &request.page_orders) {
+    match repository::reorder_docs(&mut conn, request.parent_id, &request.page_orders) {
         Ok(updated_pages) => HttpResponse::Ok().json(updated_pages),
+        Err(diesel::result::Error::RollbackTransaction) => {
+            HttpResponse::BadRequest().json(json!({
+                "error": "Circular reference",
+                "message": "Cannot move a page to be a child of itself or its own descendant"
+            }))
+        }
         Err(e) => {
-            error!(parent_id = request.parent_id, error = ?e, "Error reordering pages");
+            error!(parent_id = ?request.parent_id, error = ?e, "Error reordering pages");
             HttpResponse::InternalServerError().json("Failed to reorder pages")
         }
     }
@@ -767,9 +841,80 @@ pub async fn move_page_to_parent(
     }
 }
 
+#[derive(Deserialize)]
+pub struct SetDocumentationVisibilityRequest {
+    pub group_ids: Vec<i32>, // Empty array = public (visible to all)
+}
+
+// Get the groups that can see a page (admin only)
+pub async fn get_documentation_visibility(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    if let Err(e) = require_admin(&req) {
+        return e;
+    }
+
+    let page_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    if repository::get_documentation_page(page_id, &mut conn).is_err() {
+        return HttpResponse::NotFound().json("Page not found");
+    }
+
+    match repository::get_visible_groups_for_doc(&mut conn, page_id) {
+        Ok(groups) => HttpResponse::Ok().json(json!({
+            "page_id": page_id,
+            "visible_to_groups": groups,
+        })),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to fetch page visibility"),
+    }
+}
+
+// Set which groups can see a page (admin only)
+pub async fn set_documentation_visibility(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+    body: web::Json<SetDocumentationVisibilityRequest>,
+) -> impl Responder {
+    let claims = match require_admin(&req) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+
+    let created_by = Uuid::parse_str(&claims.sub).ok();
+    let page_id = path.into_inner();
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    if repository::get_documentation_page(page_id, &mut conn).is_err() {
+        return HttpResponse::NotFound().json("Page not found");
+    }
+
+    match repository::set_doc_visibility(&mut conn, page_id, body.group_ids.clone(), created_by) {
+        Ok(_) => match repository::get_visible_groups_for_doc(&mut conn, page_id) {
+            Ok(groups) => HttpResponse::Ok().json(json!({
+                "page_id": page_id,
+                "visible_to_groups": groups,
+            })),
+            Err(_) => HttpResponse::InternalServerError().json("Failed to fetch page visibility"),
+        },
+        Err(_) => HttpResponse::InternalServerError().json("Failed to set page visibility"),
+    }
+}
+
 // Get top-level pages (with ordering)
 pub async fn get_ordered_top_level_pages(
     pool: web::Data<Pool>,
+    auth: AuthContext,
 ) -> impl Responder {
     let mut conn = match pool.get() {
         Ok(conn) => conn,
@@ -777,7 +922,8 @@ pub async fn get_ordered_top_level_pages(
     };
 
     // Get all top-level pages with appropriate ordering
-    match repository::get_ordered_top_level_pages(&mut conn) {
+    match repository::get_ordered_top_level_pages(&mut conn)
+        .and_then(|pages| repository::filter_visible_docs(&mut conn, pages, &auth.user_uuid, auth.is_admin())) {
         Ok(pages) => {
             match to_page_responses(pages, &mut conn) {
                 Ok(responses) => HttpResponse::Ok().json(responses),
@@ -792,6 +938,7 @@ pub async fn get_ordered_top_level_pages(
 pub async fn get_documentation_page_by_slug_with_children(
     slug: web::Path<String>,
     pool: web::Data<Pool>,
+    auth: AuthContext,
 ) -> impl Responder {
     let page_slug = slug.into_inner();
     let mut conn = match pool.get() {
@@ -804,10 +951,16 @@ pub async fn get_documentation_page_by_slug_with_children(
         Ok(page) => page,
         Err(_) => return HttpResponse::NotFound().json("Page not found"),
     };
+    if !can_view(&mut conn, &auth, page.id) {
+        return HttpResponse::NotFound().json("Page not found");
+    }
 
     // Then get its children
     let children = match repository::get_pages_by_parent_id(page.id, &mut conn) {
-        Ok(children) => children,
+        Ok(children) => match repository::filter_visible_docs(&mut conn, children, &auth.user_uuid, auth.is_admin()) {
+            Ok(children) => children,
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch children"),
+        },
         Err(_) => return HttpResponse::InternalServerError().json("Failed to fetch children"),
     };
 
@@ -824,6 +977,7 @@ pub async fn get_documentation_page_by_slug_with_children(
 pub async fn get_documentation_pages_by_ticket_id(
     pool: web::Data<Pool>,
     path: web::Path<i32>,
+    auth: AuthContext,
 ) -> impl Responder {
     let ticket_id = path.into_inner();
 
@@ -833,7 +987,8 @@ pub async fn get_documentation_pages_by_ticket_id(
         Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
     };
 
-    match repository::get_documentation_pages_by_ticket_id(&mut conn, ticket_id) {
+    match repository::get_documentation_pages_by_ticket_id(&mut conn, ticket_id)
+        .and_then(|pages| repository::filter_visible_docs(&mut conn, pages, &auth.user_uuid, auth.is_admin())) {
         Ok(pages) => {
             debug!(ticket_id = ticket_id, count = pages.len(), "Found documentation pages for ticket");
             match to_page_responses(pages, &mut conn) {
@@ -899,7 +1054,13 @@ pub async fn export_documentation_pages(
         }));
     }
 
-    match repository::get_documentation_pages(&mut conn) {
+    let viewer = match utils::parse_uuid(&claims.sub) {
+        Ok(uuid) => uuid,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user UUID in token"),
+    };
+
+    match repository::get_documentation_pages(&mut conn)
+        .and_then(|pages| repository::filter_visible_docs(&mut conn, pages, &viewer, is_admin(&claims))) {
         Ok(pages) => {
             let export_pages: Vec<DocumentationPageExport> = pages.into_iter().map(|page| {
                 DocumentationPageExport {
@@ -929,6 +1090,7 @@ pub async fn create_documentation_page_from_ticket(
     path: web::Path<i32>,
     page_data: web::Json<CreateDocPageFromTicket>,
     sse_state: web::Data<crate::handlers::sse::SseState>,
+    search_service: web::Data<Arc<SearchService>>,
 ) -> impl Responder {
     let ticket_id = path.into_inner();
     let mut conn = match pool.get() {
@@ -1005,6 +1167,8 @@ pub async fn create_documentation_page_from_ticket(
     // Create the documentation page
     match repository::create_documentation_page(new_page, &mut conn) {
         Ok(page) => {
+            indexing_tasks::spawn_index_documentation(search_service.get_ref().clone(), page.clone());
+
             // Broadcast SSE event for documentation creation
             use crate::utils::sse::SseBroadcaster;
             SseBroadcaster::broadcast_documentation_created(
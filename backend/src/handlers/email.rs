@@ -3,6 +3,8 @@ use serde::Deserialize;
 use serde_json::json;
 
 use crate::db::Pool;
+use crate::services::email_feedback::{self, Feedback};
+use crate::services::webhooks::signature::verify_signature;
 use crate::utils::email::{EmailService, EmailConfig};
 use crate::utils::email_branding::get_email_branding;
 
@@ -133,3 +135,92 @@ pub async fn send_test_email(
         })),
     }
 }
+
+/// Query parameters for the email feedback endpoint
+#[derive(Deserialize)]
+pub struct EmailFeedbackQuery {
+    /// Shared secret, for providers (like SNS) that can't sign requests
+    pub token: Option<String>,
+}
+
+/// Whether a feedback request carries the shared secret, either as an
+/// `X-Nosdesk-Signature` HMAC of the body or as the `token` query parameter
+fn is_authentic_feedback(req: &HttpRequest, body: &str, token: Option<&str>, secret: &str) -> bool {
+    if let Some(signature) = req.headers().get("X-Nosdesk-Signature").and_then(|h| h.to_str().ok()) {
+        return verify_signature(body, secret, signature);
+    }
+    token.is_some_and(|token| {
+        constant_time_eq::constant_time_eq(token.as_bytes(), secret.as_bytes())
+    })
+}
+
+/// Receive bounce and complaint reports from the email provider (public,
+/// verified with `EMAIL_FEEDBACK_SECRET`)
+///
+/// Hard-bounced and complained addresses are flagged so the notification
+/// email channel skips them; in-app notifications are unaffected.
+pub async fn receive_email_feedback(
+    db_pool: web::Data<Pool>,
+    req: HttpRequest,
+    query: web::Query<EmailFeedbackQuery>,
+    body: web::Bytes,
+) -> impl Responder {
+    let Some(secret) = crate::config_utils::get_email_feedback_secret() else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    let Ok(body) = std::str::from_utf8(&body) else {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "error",
+            "message": "Body must be UTF-8"
+        }));
+    };
+
+    if !is_authentic_feedback(&req, body, query.token.as_deref(), &secret) {
+        return HttpResponse::Unauthorized().json(json!({
+            "status": "error",
+            "message": "Invalid signature"
+        }));
+    }
+
+    let events = match email_feedback::parse_feedback(body) {
+        Ok(Feedback::Events(events)) => events,
+        Ok(Feedback::SubscriptionConfirmation { subscribe_url }) => {
+            // Not fetched automatically; an admin confirms it from the logs
+            tracing::info!(%subscribe_url, "Email feedback SNS subscription needs confirming");
+            return HttpResponse::Ok().json(json!({ "status": "success" }));
+        }
+        Err(message) => {
+            return HttpResponse::BadRequest().json(json!({
+                "status": "error",
+                "message": message
+            }))
+        }
+    };
+
+    let mut conn = match db_pool.get() {
+        Ok(conn) => conn,
+        Err(_) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": "Could not get database connection"
+            }))
+        }
+    };
+
+    match email_feedback::apply_feedback(&mut conn, &events) {
+        Ok(flagged) => {
+            if flagged > 0 {
+                tracing::info!(reported = events.len(), flagged, "Flagged email addresses from provider feedback");
+            }
+            HttpResponse::Ok().json(json!({ "status": "success", "flagged": flagged }))
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to record email feedback");
+            HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": "Failed to record email feedback"
+            }))
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Email Verification Handlers
+//!
+//! Addresses added to an account start unverified. A single-use link (a
+//! reset token of type `email_verification`, bound to the email's id and
+//! address) proves ownership. Unverified addresses get no notification email
+//! and can't be made primary.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{error, info, warn};
+
+use crate::db::DbConnection;
+use crate::models::{User, UserEmail};
+use crate::repository;
+use crate::utils;
+use crate::utils::email::EmailService;
+use crate::utils::email_branding::get_email_branding;
+use crate::utils::reset_tokens::{ResetTokenUtils, TokenType};
+
+/// Maximum verification emails per user per hour
+const MAX_VERIFICATION_EMAILS_PER_HOUR: i64 = 5;
+
+/// Request body for confirming an email address
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+/// Create a verification token for an email, returning the raw token to send
+pub fn issue_verification_token(conn: &mut DbConnection, email: &UserEmail) -> diesel::QueryResult<String> {
+    let token = ResetTokenUtils::create_reset_token(email.user_uuid, TokenType::EmailVerification);
+    repository::reset_tokens::create_reset_token(
+        conn,
+        &token.token_hash,
+        email.user_uuid,
+        TokenType::EmailVerification.as_str(),
+        None,
+        None,
+        token.expires_at,
+        Some(json!({ "email_id": email.id, "email": email.email })),
+    )?;
+    Ok(token.raw_token)
+}
+
+/// Consume a verification token and mark its email verified
+pub fn confirm_verification_token(conn: &mut DbConnection, raw_token: &str) -> Result<UserEmail, String> {
+    let user_uuid = repository::reset_tokens::validate_and_consume_token(
+        conn,
+        raw_token,
+        TokenType::EmailVerification.as_str(),
+    )
+    .map_err(|e| e.to_string())?;
+
+    let token = repository::reset_tokens::find_token_by_hash(conn, &ResetTokenUtils::hash_token(raw_token))
+        .map_err(|_| "Invalid or expired token".to_string())?;
+    let metadata = token.metadata.unwrap_or_default();
+    let (Some(email_id), Some(address)) = (
+        metadata["email_id"].as_i64().and_then(|id| i32::try_from(id).ok()),
+        metadata["email"].as_str(),
+    ) else {
+        return Err("Invalid or expired token".to_string());
+    };
+
+    // The address may have been removed or reassigned since the link was sent
+    repository::user_emails::mark_verified(conn, &user_uuid, email_id, address)
+        .map_err(|e| {
+            error!(error = ?e, "Failed to mark email verified");
+            "Failed to verify email".to_string()
+        })?
+        .ok_or_else(|| "This email address is no longer on the account".to_string())
+}
+
+/// Issue a token for an email and send the verification link. Failures are
+/// logged rather than returned; returns whether the email was sent.
+pub async fn send_verification_email(conn: &mut DbConnection, user: &User, email: &UserEmail) -> bool {
+    let since = Utc::now() - Duration::hours(1);
+    match repository::reset_tokens::count_recent_tokens(
+        conn,
+        user.uuid,
+        TokenType::EmailVerification.as_str(),
+        since,
+    ) {
+        Ok(count) if count >= MAX_VERIFICATION_EMAILS_PER_HOUR => {
+            warn!(user_uuid = %user.uuid, "Rate limit exceeded for email verification");
+            return false;
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!(error = ?e, "Failed to check rate limit for email verification");
+            return false;
+        }
+    }
+
+    let email_service = match EmailService::from_env() {
+        Ok(service) => service,
+        Err(e) => {
+            warn!("Email is not configured, cannot send verification link: {}", e);
+            return false;
+        }
+    };
+
+    let raw_token = match issue_verification_token(conn, email) {
+        Ok(token) => token,
+        Err(e) => {
+            error!(error = ?e, "Failed to create email verification token");
+            return false;
+        }
+    };
+
+    let base_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+    let branding = get_email_branding(conn, &base_url);
+
+    match email_service
+        .send_email_verification_email(&email.email, &user.name, &raw_token, &branding)
+        .await
+    {
+        Ok(_) => {
+            info!(email_id = email.id, user_uuid = %user.uuid, "Email verification link sent");
+            true
+        }
+        Err(e) => {
+            error!(email_id = email.id, "Failed to send email verification link: {}", e);
+            false
+        }
+    }
+}
+
+/// Confirm an email address from a verification link (public)
+pub async fn verify_email(
+    db_pool: web::Data<crate::db::Pool>,
+    request_data: web::Json<VerifyEmailRequest>,
+) -> impl Responder {
+    let mut conn = match db_pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": "Could not get database connection"
+        })),
+    };
+
+    match confirm_verification_token(&mut conn, &request_data.token) {
+        Ok(email) => {
+            info!(email_id = email.id, user_uuid = %email.user_uuid, "Email address verified");
+            HttpResponse::Ok().json(json!({
+                "status": "success",
+                "message": "Email address verified",
+                "email": email.email
+            }))
+        }
+        Err(e) => {
+            warn!("Invalid email verification token: {}", e);
+            HttpResponse::BadRequest().json(json!({
+                "status": "error",
+                "message": e
+            }))
+        }
+    }
+}
+
+/// Send (or resend) the verification link for one of a user's unverified
+/// emails (the user themselves or an admin)
+pub async fn resend_email_verification(
+    db_pool: web::Data<crate::db::Pool>,
+    req: HttpRequest,
+    path: web::Path<(String, i32)>,
+) -> impl Responder {
+    let (user_uuid, email_id) = path.into_inner();
+    let mut conn = match db_pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": "Database connection failed"
+        })),
+    };
+
+    let claims = match utils::jwt::JwtUtils::extract_claims(&req) {
+        Ok(claims) => claims,
+        Err(_) => return HttpResponse::Unauthorized().json(json!({
+            "status": "error",
+            "message": "Authentication required"
+        })),
+    };
+
+    if claims.sub != user_uuid && claims.role != "admin" {
+        return HttpResponse::Forbidden().json(json!({
+            "status": "error",
+            "message": "Not authorized"
+        }));
+    }
+
+    let uuid_parsed = match utils::parse_uuid(&user_uuid) {
+        Ok(uuid) => uuid,
+        Err(_) => return HttpResponse::BadRequest().json(json!({
+            "status": "error",
+            "message": "Invalid UUID format"
+        })),
+    };
+
+    let user = match repository::get_user_by_uuid(&uuid_parsed, &mut conn) {
+        Ok(user) => user,
+        Err(_) => return HttpResponse::NotFound().json(json!({
+            "status": "error",
+            "message": "User not found"
+        })),
+    };
+
+    let email = match repository::user_emails::get_user_emails_by_uuid(&mut conn, &user.uuid) {
+        Ok(emails) => emails.into_iter().find(|email| email.id == email_id),
+        Err(e) => {
+            error!(error = ?e, "Error loading user emails");
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": "Failed to load email"
+            }));
+        }
+    };
+    let Some(email) = email else {
+        return HttpResponse::NotFound().json(json!({
+            "status": "error",
+            "message": "Email not found"
+        }));
+    };
+
+    if email.is_verified {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "error",
+            "message": "Email address is already verified"
+        }));
+    }
+
+    if send_verification_email(&mut conn, &user, &email).await {
+        HttpResponse::Ok().json(json!({
+            "status": "success",
+            "message": format!("Verification link sent to {}", email.email)
+        }))
+    } else {
+        HttpResponse::ServiceUnavailable().json(json!({
+            "status": "error",
+            "message": "Could not send the verification link. Try again later."
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NewUserEmail, UserRole};
+    use crate::test_helpers::{setup_test_pool, TestFixtures};
+    use actix_web::{http::StatusCode, test, App};
+    use diesel::prelude::*;
+
+    fn unverified_email(conn: &mut DbConnection, user: &User, address: &str) -> UserEmail {
+        diesel::insert_into(crate::schema::user_emails::table)
+            .values(&NewUserEmail {
+                user_uuid: user.uuid,
+                email: address.to_string(),
+                email_type: "personal".to_string(),
+                is_primary: false,
+                is_verified: false,
+                source: Some("manual".to_string()),
+            })
+            .get_result(conn)
+            .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn verification_link_verifies_once() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "verifyflow", UserRole::User);
+        let email = unverified_email(&mut conn, &user, "second@example.com");
+        let raw_token = issue_verification_token(&mut conn, &email).unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/auth/email-verification/confirm", web::post().to(verify_email)),
+        )
+        .await;
+        let confirm = || {
+            test::TestRequest::post()
+                .uri("/auth/email-verification/confirm")
+                .set_json(json!({ "token": raw_token }))
+                .to_request()
+        };
+
+        let resp = test::call_service(&app, confirm()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let emails = repository::user_emails::get_user_emails_by_uuid(&mut conn, &user.uuid).unwrap();
+        assert!(emails.iter().find(|e| e.id == email.id).unwrap().is_verified);
+
+        // Tokens are single-use
+        let resp = test::call_service(&app, confirm()).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn token_for_a_removed_email_does_not_verify() {
+        let mut conn = setup_test_pool().get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "verifyremoved", UserRole::User);
+        let email = unverified_email(&mut conn, &user, "removed@example.com");
+        let raw_token = issue_verification_token(&mut conn, &email).unwrap();
+
+        diesel::delete(crate::schema::user_emails::table.find(email.id))
+            .execute(&mut conn)
+            .unwrap();
+        // Re-adding the address gets a new row the old link doesn't cover
+        let readded = unverified_email(&mut conn, &user, "removed@example.com");
+
+        assert!(confirm_verification_token(&mut conn, &raw_token).is_err());
+        let emails = repository::user_emails::get_user_emails_by_uuid(&mut conn, &user.uuid).unwrap();
+        assert!(!emails.iter().find(|e| e.id == readded.id).unwrap().is_verified);
+        assert!(confirm_verification_token(&mut conn, "not-a-token").is_err());
+    }
+}
@@ -7,14 +7,16 @@ use tracing::{debug, error, info, warn};
 
 use crate::db::DbConnection;
 use crate::models::NewAttachment;
+use crate::services::transcription::TranscriptionService;
 use crate::utils::storage::Storage;
-use crate::utils::file_validation::FileValidator;
+use crate::utils::file_validation::{self, FileValidator, UploadContext};
 
 // Upload files using the storage abstraction
 pub async fn upload_files(
     mut payload: Multipart,
     pool: web::Data<crate::db::Pool>,
     storage: web::Data<Arc<dyn Storage>>,
+    transcription: Option<web::Data<TranscriptionService>>,
 ) -> Result<HttpResponse, actix_web::Error> {
     info!("Received file upload request");
     
@@ -76,7 +78,11 @@ pub async fn upload_files(
 
         debug!(original_filename = %original_filename, sanitized_filename = %sanitized_filename, "Processing uploaded file");
 
+        // Client-declared type - only trusted after cross-checking against the content
+        let declared_mime = field.content_type().map(|m| m.to_string());
+
         // Read the field data with incremental size validation
+        let policy = UploadContext::Attachment.policy();
         let mut file_data = Vec::new();
         let mut total_size = 0usize;
 
@@ -88,7 +94,7 @@ pub async fn upload_files(
 
             // SECURITY: Validate chunk doesn't cause file to exceed max size
             // This prevents memory exhaustion attacks
-            FileValidator::validate_chunk_size(total_size, data.len())?;
+            policy.check_size(total_size + data.len())?;
 
             total_size += data.len();
             file_data.extend_from_slice(&data);
@@ -96,6 +102,14 @@ pub async fn upload_files(
 
         debug!(filename = %sanitized_filename, bytes = total_size, "File data read complete");
 
+        // Enforce the attachment policy's allowed types
+        policy
+            .validate(Some(&sanitized_filename), declared_mime.as_deref(), total_size)
+            .map_err(|e| {
+                warn!(error = ?e, filename = %sanitized_filename, "Upload policy rejected file");
+                actix_web::error::Error::from(e)
+            })?;
+
         // SECURITY: Validate file type using magic number detection AND extension check
         // This uses a blocklist approach - blocking dangerous types while allowing most files
         let detected_mime = FileValidator::validate_file(&file_data, Some(&sanitized_filename))
@@ -104,8 +118,18 @@ pub async fn upload_files(
                 actix_web::error::ErrorBadRequest(format!("Invalid file: {e}"))
             })?;
 
+        // SECURITY: The declared Content-Type must agree with the sniffed content
+        file_validation::verify_declared_mime(&file_data, declared_mime.as_deref())
+            .map_err(|e| {
+                warn!(error = ?e, filename = %sanitized_filename, "Declared content type mismatch");
+                actix_web::error::ErrorBadRequest(format!("Invalid file: {e}"))
+            })?;
+
         debug!(mime_type = %detected_mime, filename = %sanitized_filename, "File validated");
 
+        // SECURITY: Reject infected uploads (no-op when clamd isn't configured)
+        file_validation::ensure_not_malware(&file_data, &sanitized_filename).await?;
+
         // SECURITY: Compute SHA-256 checksum for file integrity verification
         use ring::digest;
         let checksum_bytes = digest::digest(&digest::SHA256, &file_data);
@@ -169,6 +193,13 @@ pub async fn upload_files(
                     "thumbnail_url": thumbnail_url
                 });
                 info!(attachment_id = attachment.id, filename = %sanitized_filename, "Attachment created successfully");
+
+                // Client-supplied transcriptions (voice notes) win over the pipeline
+                if attachment.transcription.is_none() {
+                    if let Some(transcription) = &transcription {
+                        transcription.enqueue(attachment.id, &detected_mime, &file_data);
+                    }
+                }
                 uploaded_attachments.push(attachment_json);
             },
             Err(e) => {
@@ -351,7 +382,11 @@ pub async fn upload_ticket_note_image(
 
         debug!(original_filename = %original_filename, sanitized_filename = %sanitized_filename, "Processing ticket note image");
 
+        // Client-declared type - only trusted after cross-checking against the content
+        let declared_mime = field.content_type().map(|m| m.to_string());
+
         // Read the field data with incremental size validation
+        let policy = UploadContext::NoteImage.policy();
         let mut file_data = Vec::new();
         let mut total_size = 0usize;
 
@@ -361,11 +396,8 @@ pub async fn upload_ticket_note_image(
                 actix_web::error::ErrorInternalServerError("Error reading chunk")
             })?;
 
-            // SECURITY: Validate chunk doesn't cause file to exceed max size (10MB for images)
-            const MAX_IMAGE_SIZE: usize = 10 * 1024 * 1024;
-            if total_size + data.len() > MAX_IMAGE_SIZE {
-                return Err(actix_web::error::ErrorBadRequest("File too large (max 10MB)"));
-            }
+            // SECURITY: Validate chunk doesn't cause file to exceed the note image limit
+            policy.check_size(total_size + data.len())?;
 
             total_size += data.len();
             file_data.extend_from_slice(&data);
@@ -373,6 +405,14 @@ pub async fn upload_ticket_note_image(
 
         debug!(filename = %sanitized_filename, bytes = total_size, "File data read complete");
 
+        // Enforce the note image policy's allowed types
+        policy
+            .validate(Some(&sanitized_filename), declared_mime.as_deref(), total_size)
+            .map_err(|e| {
+                warn!(error = ?e, filename = %sanitized_filename, "Upload policy rejected note image");
+                actix_web::error::Error::from(e)
+            })?;
+
         // SECURITY: Validate file type with extension check
         let detected_mime = FileValidator::validate_file(&file_data, Some(&sanitized_filename))
             .map_err(|e| {
@@ -380,6 +420,13 @@ pub async fn upload_ticket_note_image(
                 actix_web::error::ErrorBadRequest(format!("Invalid file: {e}"))
             })?;
 
+        // SECURITY: The declared Content-Type must agree with the sniffed content
+        file_validation::verify_declared_mime(&file_data, declared_mime.as_deref())
+            .map_err(|e| {
+                warn!(error = ?e, filename = %sanitized_filename, "Declared content type mismatch");
+                actix_web::error::ErrorBadRequest(format!("Invalid file: {e}"))
+            })?;
+
         // Only allow image types for ticket note images
         if !detected_mime.starts_with("image/") {
             return Err(actix_web::error::ErrorBadRequest("Only image files are allowed"));
@@ -387,6 +434,9 @@ pub async fn upload_ticket_note_image(
 
         debug!(mime_type = %detected_mime, filename = %sanitized_filename, "File validated");
 
+        // SECURITY: Reject infected uploads (no-op when clamd isn't configured)
+        file_validation::ensure_not_malware(&file_data, &sanitized_filename).await?;
+
         // Store in tickets/{ticket_id}/notes/ folder
         let folder = format!("tickets/{ticket_id}/notes");
         let stored_file = storage.store_file(&file_data, &sanitized_filename, &detected_mime, &folder)
@@ -282,6 +282,47 @@ pub async fn set_group_members(
     }
 }
 
+/// Request body for setting group managers
+#[derive(Debug, Deserialize)]
+pub struct SetGroupManagersRequest {
+    pub manager_uuids: Vec<Uuid>,
+}
+
+/// Set which members manage a group (replaces existing managers)
+/// Managers can reassign tickets in categories visible to the group. Works for
+/// externally synced groups too, since manager flags are kept across syncs.
+pub async fn set_group_managers(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+    body: web::Json<SetGroupManagersRequest>,
+) -> impl Responder {
+    if let Err(e) = require_admin(&req) {
+        return e;
+    }
+
+    let group_id = path.into_inner();
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    match repository::groups::get_group_by_id(&mut conn, group_id) {
+        Ok(_) => {}
+        Err(Error::NotFound) => return HttpResponse::NotFound().json("Group not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to get group"),
+    }
+
+    match repository::groups::set_group_managers(&mut conn, group_id, &body.manager_uuids) {
+        Ok(managers) => {
+            let manager_uuids: Vec<Uuid> = managers.iter().map(|m| m.user_uuid).collect();
+            HttpResponse::Ok().json(serde_json::json!({ "manager_uuids": manager_uuids }))
+        }
+        Err(_) => HttpResponse::InternalServerError().json("Failed to set group managers"),
+    }
+}
+
 /// Get groups for a specific user
 pub async fn get_user_groups(
     req: HttpRequest,
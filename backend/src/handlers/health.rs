@@ -0,0 +1,259 @@
+//! Liveness and readiness endpoints
+//!
+//! `/health` only says the process is serving requests and never touches a
+//! dependency, so orchestrators don't restart the backend because Postgres
+//! blipped. `/ready` checks the database and, unless disabled, Redis and the
+//! search index, and answers 503 while any of them is down.
+
+use actix_web::{web, HttpResponse, Responder};
+use async_trait::async_trait;
+use diesel::RunQueryDsl;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::db::Pool;
+use crate::services::search::SearchService;
+use crate::utils::redis_yjs_cache::RedisYjsCache;
+
+/// Upper bound on each dependency check
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A dependency that readiness can verify
+#[async_trait]
+pub trait HealthProbe: Send + Sync {
+    async fn check(&self) -> Result<(), String>;
+}
+
+#[async_trait]
+impl HealthProbe for RedisYjsCache {
+    async fn check(&self) -> Result<(), String> {
+        self.ping().await.map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl HealthProbe for SearchService {
+    async fn check(&self) -> Result<(), String> {
+        self.check_health()
+    }
+}
+
+/// Optional dependencies checked by `/ready`; `None` means the check is disabled
+#[derive(Clone, Default)]
+pub struct ReadinessChecks {
+    redis: Option<Arc<dyn HealthProbe>>,
+    search: Option<Arc<dyn HealthProbe>>,
+}
+
+impl ReadinessChecks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_redis(mut self, probe: Arc<dyn HealthProbe>) -> Self {
+        self.redis = Some(probe);
+        self
+    }
+
+    pub fn with_search(mut self, probe: Arc<dyn HealthProbe>) -> Self {
+        self.search = Some(probe);
+        self
+    }
+
+    /// Whether READINESS_CHECK_REDIS allows the Redis check (default on)
+    pub fn redis_enabled_from_env() -> bool {
+        env_flag("READINESS_CHECK_REDIS")
+    }
+
+    /// Whether READINESS_CHECK_SEARCH allows the search index check (default on)
+    pub fn search_enabled_from_env() -> bool {
+        env_flag("READINESS_CHECK_SEARCH")
+    }
+}
+
+fn env_flag(name: &str) -> bool {
+    !matches!(
+        std::env::var(name).map(|v| v.trim().to_ascii_lowercase()).as_deref(),
+        Ok("false" | "0" | "no" | "off")
+    )
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum CheckStatus {
+    Up,
+    Down,
+    Disabled,
+}
+
+#[derive(Debug, Serialize)]
+struct DependencyStatus {
+    status: CheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl DependencyStatus {
+    fn disabled() -> Self {
+        Self { status: CheckStatus::Disabled, latency_ms: None, error: None }
+    }
+
+    fn is_down(&self) -> bool {
+        matches!(self.status, CheckStatus::Down)
+    }
+}
+
+/// Run a check with a timeout, recording how long it took
+async fn run_check<F>(check: F) -> DependencyStatus
+where
+    F: std::future::Future<Output = Result<(), String>>,
+{
+    let start = Instant::now();
+    let result = match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(result) => result,
+        Err(_) => Err(format!("Timed out after {}s", CHECK_TIMEOUT.as_secs())),
+    };
+    let latency_ms = Some(start.elapsed().as_millis() as u64);
+
+    match result {
+        Ok(()) => DependencyStatus { status: CheckStatus::Up, latency_ms, error: None },
+        Err(error) => DependencyStatus { status: CheckStatus::Down, latency_ms, error: Some(error) },
+    }
+}
+
+async fn check_database(pool: Pool) -> Result<(), String> {
+    web::block(move || {
+        let mut conn = pool.get_timeout(CHECK_TIMEOUT).map_err(|e| e.to_string())?;
+        diesel::sql_query("SELECT 1")
+            .execute(&mut conn)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+async fn check_optional(probe: &Option<Arc<dyn HealthProbe>>) -> DependencyStatus {
+    match probe {
+        Some(probe) => run_check(probe.check()).await,
+        None => DependencyStatus::disabled(),
+    }
+}
+
+/// Liveness: the process is up and serving requests
+pub async fn liveness() -> impl Responder {
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(serde_json::json!({ "status": "ok" }))
+}
+
+/// Readiness: every enabled dependency answers
+pub async fn readiness(pool: web::Data<Pool>, checks: web::Data<ReadinessChecks>) -> impl Responder {
+    let (database, redis, search) = futures::join!(
+        run_check(check_database(pool.get_ref().clone())),
+        check_optional(&checks.redis),
+        check_optional(&checks.search),
+    );
+
+    let dependencies = BTreeMap::from([("database", database), ("redis", redis), ("search", search)]);
+    let ready = !dependencies.values().any(DependencyStatus::is_down);
+
+    if !ready {
+        for (name, dependency) in dependencies.iter().filter(|(_, d)| d.is_down()) {
+            warn!(dependency = %name, error = ?dependency.error, "Readiness check failed");
+        }
+    }
+
+    let body = serde_json::json!({
+        "status": if ready { "ready" } else { "not_ready" },
+        "checks": dependencies,
+    });
+
+    let mut response = if ready {
+        HttpResponse::Ok()
+    } else {
+        HttpResponse::ServiceUnavailable()
+    };
+    response.insert_header(("Cache-Control", "no-store")).json(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test, App};
+    use serde_json::Value;
+
+    struct StubProbe(Result<(), String>);
+
+    #[async_trait]
+    impl HealthProbe for StubProbe {
+        async fn check(&self) -> Result<(), String> {
+            self.0.clone()
+        }
+    }
+
+    async fn get_ready(checks: ReadinessChecks) -> (StatusCode, Value) {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(crate::test_helpers::setup_test_pool()))
+                .app_data(web::Data::new(checks))
+                .route("/ready", web::get().to(readiness)),
+        )
+        .await;
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/ready").to_request()).await;
+        let status = resp.status();
+        (status, test::read_body_json(resp).await)
+    }
+
+    #[actix_web::test]
+    async fn ready_when_all_dependencies_are_up() {
+        let checks = ReadinessChecks::new()
+            .with_redis(Arc::new(StubProbe(Ok(()))))
+            .with_search(Arc::new(StubProbe(Ok(()))));
+
+        let (status, body) = get_ready(checks).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ready");
+        for dependency in ["database", "redis", "search"] {
+            assert_eq!(body["checks"][dependency]["status"], "up", "{dependency}");
+        }
+    }
+
+    #[actix_web::test]
+    async fn not_ready_when_a_dependency_is_down() {
+        let checks = ReadinessChecks::new()
+            .with_redis(Arc::new(StubProbe(Err("Connection refused".to_string()))))
+            .with_search(Arc::new(StubProbe(Ok(()))));
+
+        let (status, body) = get_ready(checks).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "not_ready");
+        assert_eq!(body["checks"]["database"]["status"], "up");
+        assert_eq!(body["checks"]["redis"]["status"], "down");
+        assert_eq!(body["checks"]["redis"]["error"], "Connection refused");
+        assert_eq!(body["checks"]["search"]["status"], "up");
+    }
+
+    #[actix_web::test]
+    async fn disabled_checks_do_not_affect_readiness() {
+        let (status, body) = get_ready(ReadinessChecks::new()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["checks"]["redis"]["status"], "disabled");
+        assert_eq!(body["checks"]["search"]["status"], "disabled");
+    }
+
+    #[actix_web::test]
+    async fn unreachable_redis_is_reported_down() {
+        // Nothing listens on port 1
+        let redis = Arc::new(RedisYjsCache::new("redis://127.0.0.1:1").unwrap());
+        let (status, body) = get_ready(ReadinessChecks::new().with_redis(redis)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["checks"]["redis"]["status"], "down");
+    }
+}
@@ -0,0 +1,240 @@
+//! Impersonation Handlers
+//!
+//! Lets an admin sign in as another user to see exactly what they see. The
+//! admin re-enters their password (and MFA code, if enabled) to start; the
+//! resulting short-lived token names both the impersonated user and the real
+//! admin, and every state-changing request made with it is audit-logged.
+
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use bcrypt::verify;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db::{DbConnection, Pool};
+use crate::handlers::auth::{create_session_record, get_local_password_hash, session_token_hash};
+use crate::middleware::api_token::ApiTokenAuth;
+use crate::models::{User, UserResponse, UserRole};
+use crate::repository;
+use crate::services::audit::{self, AuditAction, AuditTarget};
+use crate::utils::cookies::{
+    create_access_token_cookie, create_impersonating_cookie, create_impersonator_token_cookie,
+    delete_access_token_cookie, delete_impersonating_cookie, delete_impersonator_token_cookie,
+    ACCESS_TOKEN_COOKIE, IMPERSONATOR_TOKEN_COOKIE,
+};
+use crate::utils::jwt::{JwtUtils, IMPERSONATION_TTL_SECONDS};
+use crate::utils::mfa;
+use crate::utils::rbac::{require_admin, require_auth};
+
+/// Step-up re-authentication for starting an impersonation
+#[derive(Debug, Deserialize)]
+pub struct StartImpersonationRequest {
+    pub password: String,
+    /// TOTP or backup code, required when the admin has MFA enabled
+    pub mfa_token: Option<String>,
+}
+
+/// Re-verify the admin's password and, if enabled, MFA code
+async fn verify_step_up(
+    admin: &User,
+    body: &StartImpersonationRequest,
+    conn: &mut DbConnection,
+) -> Result<(), HttpResponse> {
+    let password_hash = get_local_password_hash(&admin.uuid, conn).map_err(|_| {
+        HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Impersonation requires an account with a local password"
+        }))
+    })?;
+
+    if !verify(&body.password, &password_hash).unwrap_or(false) {
+        return Err(HttpResponse::Unauthorized().json(json!({
+            "error": "Unauthorized",
+            "message": "Invalid password"
+        })));
+    }
+
+    if mfa::user_has_mfa_enabled(admin) {
+        let token = body.mfa_token.as_deref().unwrap_or_default();
+        let verified = mfa::verify_mfa_token(&admin.uuid, token, conn)
+            .await
+            .is_ok_and(|result| result.is_valid);
+        if !verified {
+            return Err(HttpResponse::Unauthorized().json(json!({
+                "error": "Unauthorized",
+                "message": "A valid MFA code is required"
+            })));
+        }
+    }
+
+    Ok(())
+}
+
+/// Start impersonating a user (admin only, requires step-up re-auth)
+pub async fn start_impersonation(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    path: web::Path<Uuid>,
+    body: web::Json<StartImpersonationRequest>,
+) -> impl Responder {
+    let claims = match require_admin(&req) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+
+    if claims.is_impersonating() {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Already impersonating",
+            "message": "Stop the current impersonation first"
+        }));
+    }
+    // The admin's session is stashed in a cookie, so API tokens can't do this
+    if req.extensions().get::<ApiTokenAuth>().is_some() {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Impersonation requires a browser session"
+        }));
+    }
+    let admin_token = match req.cookie(ACCESS_TOKEN_COOKIE) {
+        Some(cookie) => cookie.value().to_string(),
+        None => return HttpResponse::Unauthorized().json("Authentication required"),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    let admin = match Uuid::parse_str(&claims.sub)
+        .ok()
+        .and_then(|uuid| repository::get_user_by_uuid(&uuid, &mut conn).ok())
+    {
+        Some(admin) => admin,
+        None => return HttpResponse::Unauthorized().json("Authentication required"),
+    };
+
+    if let Err(e) = verify_step_up(&admin, &body, &mut conn).await {
+        warn!(admin = %admin.uuid, "Impersonation step-up re-authentication failed");
+        return e;
+    }
+
+    let target_uuid = path.into_inner();
+    let target = match repository::get_user_by_uuid(&target_uuid, &mut conn) {
+        Ok(user) => user,
+        Err(_) => return HttpResponse::NotFound().json("User not found"),
+    };
+    if target.uuid == admin.uuid {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Invalid target",
+            "message": "You cannot impersonate yourself"
+        }));
+    }
+    if target.role == UserRole::Admin {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Administrators cannot be impersonated"
+        }));
+    }
+
+    let token = match JwtUtils::create_impersonation_token(&target, &admin.uuid) {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to create impersonation token");
+            return HttpResponse::InternalServerError().json("Failed to start impersonation");
+        }
+    };
+    // Sessions are required for token validation, and let the stop endpoint revoke it
+    if let Err(e) = create_session_record(&target.uuid, &token, None, &req, &mut conn).await {
+        tracing::error!(error = %e, "Failed to create impersonation session");
+        return HttpResponse::InternalServerError().json("Failed to start impersonation");
+    }
+
+    let _ = audit::record(
+        &mut conn,
+        Some(admin.uuid),
+        AuditAction::ImpersonationStarted,
+        Some(AuditTarget::user(target.uuid)),
+        json!({ "impersonated_name": target.name }),
+    );
+    info!(admin = %admin.uuid, user = %target.uuid, "Admin started impersonating user");
+
+    let target_uuid = target.uuid.to_string();
+    HttpResponse::Ok()
+        .cookie(create_access_token_cookie(&token))
+        .cookie(create_impersonator_token_cookie(&admin_token))
+        .cookie(create_impersonating_cookie(&target_uuid, IMPERSONATION_TTL_SECONDS as i64))
+        .json(json!({
+            "impersonating": true,
+            "user": UserResponse::from(target),
+            "impersonator": { "uuid": admin.uuid, "name": admin.name },
+            "expires_in": IMPERSONATION_TTL_SECONDS,
+        }))
+}
+
+/// Stop impersonating and return to the admin's own session
+pub async fn stop_impersonation(req: HttpRequest, pool: web::Data<Pool>) -> impl Responder {
+    let claims = match require_auth(&req) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+    let Some(impersonator) = claims.impersonator.clone() else {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Not impersonating",
+            "message": "This session is not an impersonation session"
+        }));
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    // End the impersonation session so its token stops working
+    if let Some(token) = req.cookie(ACCESS_TOKEN_COOKIE) {
+        let hash = session_token_hash(token.value());
+        if let Ok(session) = repository::active_sessions::get_session_by_token(&mut conn, &hash) {
+            let _ = repository::active_sessions::revoke_session(&mut conn, session.id);
+        }
+    }
+
+    let admin_uuid = Uuid::parse_str(&impersonator).ok();
+    let _ = audit::record(
+        &mut conn,
+        admin_uuid,
+        AuditAction::ImpersonationStopped,
+        Uuid::parse_str(&claims.sub).ok().map(AuditTarget::user),
+        json!({}),
+    );
+    info!(admin = %impersonator, user = %claims.sub, "Admin stopped impersonating user");
+
+    // Restore the admin's own session if it is still valid
+    let admin_token = req.cookie(IMPERSONATOR_TOKEN_COOKIE).map(|c| c.value().to_string());
+    let restored = match &admin_token {
+        Some(token) => JwtUtils::validate_token_with_user_check(token, &mut conn)
+            .await
+            .ok()
+            .filter(|(admin_claims, _)| admin_claims.sub == impersonator && !admin_claims.is_impersonating()),
+        None => None,
+    };
+
+    match (admin_token, restored) {
+        (Some(token), Some((_, admin))) => HttpResponse::Ok()
+            .cookie(create_access_token_cookie(&token))
+            .cookie(delete_impersonator_token_cookie())
+            .cookie(delete_impersonating_cookie())
+            .json(json!({
+                "impersonating": false,
+                "user": UserResponse::from(admin),
+            })),
+        // The admin session expired meanwhile; the refresh token can still restore it
+        _ => HttpResponse::Unauthorized()
+            .cookie(delete_access_token_cookie())
+            .cookie(delete_impersonator_token_cookie())
+            .cookie(delete_impersonating_cookie())
+            .json(json!({
+                "error": "Unauthorized",
+                "message": "Your admin session has expired, please sign in again"
+            })),
+    }
+}
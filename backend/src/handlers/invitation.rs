@@ -132,7 +132,7 @@ pub async fn accept_invitation(
             warn!("Invalid invitation token: {}", e);
             return HttpResponse::BadRequest().json(json!({
                 "status": "error",
-                "message": e
+                "message": e.to_string()
             }));
         }
     };
@@ -0,0 +1,30 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use crate::db::Pool;
+use crate::services::metrics;
+use crate::utils::rbac::require_admin;
+
+/// Content type of the Prometheus text exposition format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Prometheus metrics (admin only, e.g. scraped with an admin API token)
+pub async fn get_metrics(req: HttpRequest, pool: web::Data<Pool>) -> impl Responder {
+    if let Err(e) = require_admin(&req) {
+        return e;
+    }
+
+    metrics_response(&pool)
+}
+
+/// Prometheus metrics without authentication, for the internal listener
+/// bound by METRICS_BIND_ADDR
+pub async fn get_internal_metrics(pool: web::Data<Pool>) -> impl Responder {
+    metrics_response(&pool)
+}
+
+fn metrics_response(pool: &Pool) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(PROMETHEUS_CONTENT_TYPE)
+        .insert_header(("Cache-Control", "no-store"))
+        .body(metrics::render(pool))
+}
@@ -228,7 +228,7 @@ pub async fn complete_mfa_reset(
             tracing::warn!("Invalid MFA reset token attempt: {}", e);
             return HttpResponse::BadRequest().json(json!({
                 "status": "error",
-                "message": e
+                "message": e.to_string()
             }));
         }
     };
@@ -1,16 +1,20 @@
 // Reexport handlers
 pub mod api_tokens;
 pub mod assignment_rules;
+pub mod audit;
 pub mod collaboration;
 pub mod auth;
 pub mod users;
 pub mod files;
 pub mod tickets;
+pub mod ticket_export;
+pub mod ticket_status;
 pub mod projects;
 pub mod devices;
 pub mod documentation;
 pub mod auth_providers;
 pub mod email;
+pub mod email_verification;
 pub mod microsoft_graph;
 pub mod msgraph_integration;
 pub mod sse;
@@ -18,16 +22,25 @@ pub mod password_reset;
 pub mod mfa_reset;
 pub mod invitation;
 pub mod system;
+pub mod health;
+pub mod metrics;
 pub mod debug;
 pub mod branding;
 pub mod backup;
 pub mod groups;
+pub mod canned_responses;
+pub mod reports;
+pub mod sla;
 pub mod categories;
+pub mod custom_fields;
 pub mod notifications;
 pub mod webhooks;
 pub mod plugins;
 pub mod passkeys;
+pub mod impersonation;
+pub mod permissions;
 pub mod search;
+pub mod my_work;
 
 // Import all handlers from modules
 pub use auth::*;
@@ -36,10 +49,11 @@ pub use users::{
     get_users, get_paginated_users, get_users_batch, create_user,
     get_user_by_uuid, update_user_by_uuid, delete_user, upload_user_image,
     get_user_emails, get_user_with_emails, add_user_email, update_user_email, delete_user_email,
+    clear_user_email_delivery_flags, unlock_user_account,
     cleanup_stale_images,
     get_user_auth_identities, delete_user_auth_identity,
     get_user_auth_identities_by_uuid, delete_user_auth_identity_by_uuid,
-    resend_invitation, bulk_users
+    resend_invitation, bulk_users, update_user_timezone
 };
 pub use files::*;
 // Export specific items from tickets to avoid conflicts
@@ -48,14 +62,17 @@ pub use tickets::{
     create_empty_ticket, get_ticket, update_ticket, update_ticket_partial,
     delete_ticket, record_ticket_view, import_tickets_from_json,
     import_tickets_from_json_string, link_tickets, unlink_tickets,
-    add_device_to_ticket, remove_device_from_ticket, bulk_tickets
+    add_device_to_ticket, remove_device_from_ticket, bulk_tickets,
+    get_ticket_timeline, suggest_duplicate_tickets, merge_ticket, set_ticket_legal_hold, reopen_ticket,
+    get_ticket_watchers, add_ticket_watcher, remove_ticket_watcher,
+    get_ticket_worklogs, add_ticket_worklog, delete_ticket_worklog, update_ticket_custom_fields
 };
 pub use projects::*;
 // Export specific items from devices to avoid conflicts
 pub use devices::{
     get_all_devices, get_paginated_devices, get_paginated_devices_excluding,
-    create_device, get_device_by_id, update_device, delete_device,
-    get_user_devices, unmanage_device, bulk_devices
+    create_device, get_device_by_id, get_device_assignment_history, update_device, delete_device,
+    get_user_devices, unmanage_device, bulk_devices, import_devices
 };
 pub use documentation::*;
 pub use auth_providers::*;
@@ -68,7 +85,6 @@ use actix_web::{web, HttpResponse, HttpMessage, Responder};
 use serde_json::json;
 use std::sync::Arc;
 use tracing::{info, warn, error, debug};
-use uuid::Uuid;
 
 use crate::services::notifications::{
     NotificationService,
@@ -81,9 +97,6 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 
 // Pre-compiled regexes for performance (compiled once, reused)
-static MENTION_UUID_RE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"@\[[^\]]+\]\(([a-f0-9]{8}-[a-f0-9]{4}-[a-f0-9]{4}-[a-f0-9]{4}-[a-f0-9]{12})\)").unwrap()
-});
 static MENTION_DISPLAY_RE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"@\[([^\]]+)\]\([a-f0-9-]+\)").unwrap()
 });
@@ -94,24 +107,6 @@ static WHITESPACE_RE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"\s+").unwrap()
 });
 
-/// Parse @mentions from comment content
-/// Returns a list of unique UUIDs mentioned
-/// Supports format: @[Display Name](uuid)
-fn parse_mentions(content: &str) -> Vec<Uuid> {
-    let mut mentions: Vec<Uuid> = MENTION_UUID_RE
-        .captures_iter(content)
-        .filter_map(|cap| {
-            cap.get(1)
-                .and_then(|m| Uuid::parse_str(m.as_str()).ok())
-        })
-        .collect();
-
-    // Remove duplicates while preserving order
-    mentions.sort();
-    mentions.dedup();
-    mentions
-}
-
 /// Strip HTML tags and clean up text for notification previews
 /// Also removes @mention syntax: @[Name](uuid) -> @Name
 fn strip_html_for_preview(content: &str) -> String {
@@ -133,6 +128,18 @@ fn truncate_preview(text: &str, max_len: usize) -> String {
     }
 }
 
+/// Which of the given users are technicians or admins
+fn privileged_users(
+    conn: &mut crate::db::DbConnection,
+    uuids: &[uuid::Uuid],
+) -> Result<Vec<uuid::Uuid>, diesel::result::Error> {
+    Ok(crate::repository::users::get_users_by_uuids(uuids, conn)?
+        .into_iter()
+        .filter(|user| matches!(user.role, crate::models::UserRole::Admin | crate::models::UserRole::Technician))
+        .map(|user| user.uuid)
+        .collect())
+}
+
 // Re-export validation utilities
 // pub use crate::utils::validation;
 
@@ -141,7 +148,8 @@ fn truncate_preview(text: &str, max_len: usize) -> String {
 // Ticket comments and attachments
 pub async fn get_comments_by_ticket_id(
     path: web::Path<i32>,
-    pool: web::Data<crate::db::Pool>
+    pool: web::Data<crate::db::Pool>,
+    auth: crate::extractors::AuthContext,
 ) -> impl Responder {
     let ticket_id = path.into_inner();
     debug!(ticket_id, "Getting comments for ticket");
@@ -154,7 +162,8 @@ pub async fn get_comments_by_ticket_id(
         }
     };
 
-    match crate::repository::comments::get_comments_with_attachments_by_ticket_id(&mut conn, ticket_id) {
+    // Internal comments are only listed for technicians/admins
+    match crate::repository::comments::get_comments_with_attachments_by_ticket_id(&mut conn, ticket_id, auth.is_technician_or_admin()) {
         Ok(comments) => {
             // Format the comments for the frontend
             let formatted_comments: Vec<serde_json::Value> = comments.into_iter().map(|c| {
@@ -166,6 +175,7 @@ pub async fn get_comments_by_ticket_id(
                     "created_at": created_at,
                     "createdAt": created_at,
                     "ticket_id": c.comment.ticket_id,
+                    "is_internal": c.comment.is_internal,
                     "attachments": c.attachments,
                     "user": c.user
                 })
@@ -216,6 +226,14 @@ pub async fn add_comment_to_ticket(
         Err(_) => return HttpResponse::BadRequest().json(json!({"error": "Invalid user UUID in token"})),
     };
 
+    // Only technicians/admins can write internal comments
+    if comment_data.is_internal && !crate::utils::rbac::is_technician_or_admin(&claims) {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Only technicians can add internal comments"
+        }));
+    }
+
     // Get the authenticated user's full information for notifications
     let commenter_user = match crate::repository::users::get_user_by_uuid(&user_uuid_parsed, &mut conn) {
         Ok(user) => {
@@ -244,6 +262,7 @@ pub async fn add_comment_to_ticket(
         content: comment_data.content.clone(),
         user_uuid: user_uuid_parsed,  // Use the user_uuid from JWT token
         ticket_id,
+        is_internal: comment_data.is_internal,
     };
 
     // Insert the comment
@@ -409,6 +428,7 @@ pub async fn add_comment_to_ticket(
                 "created_at": created_at,
                 "createdAt": created_at,
                 "ticket_id": comment.ticket_id,
+                "is_internal": comment.is_internal,
                 "attachments": attachments,
                 "user": user
             });
@@ -422,9 +442,12 @@ pub async fn add_comment_to_ticket(
 
             debug!(ticket_id, "SSE: About to broadcast comment-added event");
             
-            // Use the centralized SSE broadcasting utility
+            // Use the centralized SSE broadcasting utility. SSE events go to every
+            // connected client, so internal comments aren't broadcast.
             use crate::utils::sse::SseBroadcaster;
-            SseBroadcaster::broadcast_comment_added(&sse_state, ticket_id, response.clone()).await;
+            if !comment.is_internal {
+                SseBroadcaster::broadcast_comment_added(&sse_state, ticket_id, response.clone()).await;
+            }
 
             // Also broadcast the ticket modified date update
             SseBroadcaster::broadcast_ticket_updated(
@@ -437,91 +460,131 @@ pub async fn add_comment_to_ticket(
 
             debug!(ticket_id, "SSE: Successfully broadcasted comment-added and modified events");
 
-            // Index the new comment in search
-            let ticket_title_for_search = ticket.as_ref().map(|t| t.title.clone()).unwrap_or_else(|| format!("Ticket #{}", ticket_id));
-            indexing_tasks::spawn_index_comment(
-                search_service.get_ref().clone(),
-                comment.clone(),
-                ticket_title_for_search,
-            );
+            // Index the new comment in search (internal comments are never indexed)
+            if !comment.is_internal {
+                let ticket_title_for_search = ticket.as_ref().map(|t| t.title.clone()).unwrap_or_else(|| format!("Ticket #{}", ticket_id));
+                // Attachments transcribed before being linked become searchable now
+                for attachment in attachments.iter().filter(|a| a.transcription.is_some()) {
+                    indexing_tasks::spawn_index_attachment(
+                        search_service.get_ref().clone(),
+                        attachment.clone(),
+                        ticket_id,
+                        ticket_title_for_search.clone(),
+                    );
+                }
+                indexing_tasks::spawn_index_comment(
+                    search_service.get_ref().clone(),
+                    comment.clone(),
+                    ticket_title_for_search,
+                );
+            }
+
+            // Commenters follow the ticket unless they opt out
+            if comment_data.watch != Some(false) {
+                if let Err(e) = crate::repository::ticket_watchers::add_watcher(&mut conn, ticket_id, commenter_user.uuid) {
+                    warn!(error = %e, ticket_id, "Failed to add commenter as ticket watcher");
+                }
+            }
+
+            // A public reply from staff is the ticket's first response
+            if !comment.is_internal
+                && matches!(commenter_user.role, crate::models::UserRole::Admin | crate::models::UserRole::Technician)
+            {
+                if let Err(e) = crate::repository::sla::record_first_response(&mut conn, ticket_id, comment.created_at) {
+                    warn!(error = %e, ticket_id, "Failed to record first response");
+                }
+            }
 
-            // Send notifications to ticket participants (requester, assignee, and @mentioned users)
+            // Send notifications to ticket participants (requester, assignee, watchers, and @mentioned users)
             if let Some(ref ticket_info) = ticket {
                 let commenter_uuid = commenter_user.uuid;
                 let commenter_name = commenter_user.name.clone();
                 let commenter_avatar = commenter_user.avatar_thumb.clone();
                 let ticket_title = ticket_info.title.clone();
-                let ticket_requester = ticket_info.requester_uuid;
-                let ticket_assignee = ticket_info.assignee_uuid;
                 let comment_id = comment.id;
                 // Strip HTML and clean up mentions for notification preview
                 let comment_preview = truncate_preview(&strip_html_for_preview(&comment_data.content), 100);
 
-                // Parse @mentions from comment content (now extracts UUIDs directly)
-                let mentioned_users: Vec<Uuid> = parse_mentions(&comment_data.content)
-                    .into_iter()
-                    .filter(|uuid| *uuid != commenter_uuid)
-                    .collect();
+                // Requester, assignee and watchers get CommentAdded (never the commenter)
+                let comment_recipients = crate::repository::ticket_watchers::notification_recipients(
+                    &mut conn,
+                    ticket_info,
+                    Some(commenter_uuid),
+                )
+                .unwrap_or_else(|e| {
+                    warn!(error = %e, ticket_id, "Failed to look up ticket watchers");
+                    [ticket_info.requester_uuid, ticket_info.assignee_uuid]
+                        .into_iter()
+                        .flatten()
+                        .filter(|participant| *participant != commenter_uuid)
+                        .collect()
+                });
+
+                // Everyone else @mentioned gets Mentioned; unknown users are dropped
+                let mentions = crate::utils::mentions::extract_mentions(&comment_data.content);
+                let mentioned_users = match crate::utils::mentions::resolve_mentions(&mut conn, &mentions) {
+                    Ok(resolved) => crate::utils::mentions::mention_recipients(&resolved, commenter_uuid, &comment_recipients),
+                    Err(e) => {
+                        warn!(error = %e, "Failed to resolve @mentions");
+                        Vec::new()
+                    }
+                };
                 debug!(mentioned_users = ?mentioned_users, "Parsed @mentions from comment");
 
+                // Internal comments only notify technicians/admins
+                let (comment_recipients, mentioned_users): (Vec<_>, Vec<_>) = if comment.is_internal {
+                    let candidates: Vec<_> = comment_recipients.iter().chain(&mentioned_users).copied().collect();
+                    let privileged = privileged_users(&mut conn, &candidates).unwrap_or_else(|e| {
+                        warn!(error = %e, "Failed to look up recipient roles for internal comment");
+                        Vec::new()
+                    });
+                    (
+                        comment_recipients.into_iter().filter(|u| privileged.contains(u)).collect(),
+                        mentioned_users.into_iter().filter(|u| privileged.contains(u)).collect(),
+                    )
+                } else {
+                    (comment_recipients, mentioned_users)
+                };
+
                 let notification_service = notification_service.clone();
-                tokio::spawn(async move {
+                crate::services::shutdown::notifications().spawn(async move {
                     let actor = NotificationActor {
                         uuid: commenter_uuid,
                         name: commenter_name,
                         avatar_thumb: commenter_avatar,
                     };
 
-                    // Collect recipients for CommentAdded (requester and assignee, excluding commenter and mentioned users)
-                    let mut comment_recipients = Vec::new();
-                    if let Some(requester) = ticket_requester {
-                        if requester != commenter_uuid && !mentioned_users.contains(&requester) {
-                            comment_recipients.push(requester);
-                        }
-                    }
-                    if let Some(assignee) = ticket_assignee {
-                        if assignee != commenter_uuid && !comment_recipients.contains(&assignee) && !mentioned_users.contains(&assignee) {
-                            comment_recipients.push(assignee);
-                        }
-                    }
+                    let entity = NotificationEntity::Comment {
+                        id: comment_id,
+                        ticket_id,
+                        ticket_title,
+                    };
 
-                    // Send CommentAdded notification to requester/assignee
-                    for recipient in comment_recipients {
-                        let payload = NotificationPayload::new(
-                            NotificationTypeCode::CommentAdded,
-                            recipient,
-                            actor.clone(),
-                            NotificationEntity::Comment {
-                                id: comment_id,
-                                ticket_id,
-                                ticket_title: ticket_title.clone(),
-                            },
-                        )
-                        .with_body(&comment_preview);
-
-                        if let Err(e) = notification_service.notify(payload).await {
-                            warn!(error = %e, recipient = %recipient, "Failed to send comment notification");
-                        }
-                    }
+                    // Send CommentAdded notification to requester/assignee/watchers
+                    notification_service
+                        .notify_many(&comment_recipients, |recipient| {
+                            NotificationPayload::new(
+                                NotificationTypeCode::CommentAdded,
+                                recipient,
+                                actor.clone(),
+                                entity.clone(),
+                            )
+                            .with_body(&comment_preview)
+                        })
+                        .await;
 
                     // Send Mentioned notification to @mentioned users
-                    for mentioned_uuid in mentioned_users {
-                        let payload = NotificationPayload::new(
-                            NotificationTypeCode::Mentioned,
-                            mentioned_uuid,
-                            actor.clone(),
-                            NotificationEntity::Comment {
-                                id: comment_id,
-                                ticket_id,
-                                ticket_title: ticket_title.clone(),
-                            },
-                        )
-                        .with_body(&comment_preview);
-
-                        if let Err(e) = notification_service.notify(payload).await {
-                            warn!(error = %e, recipient = %mentioned_uuid, "Failed to send mention notification");
-                        }
-                    }
+                    notification_service
+                        .notify_many(&mentioned_users, |recipient| {
+                            NotificationPayload::new(
+                                NotificationTypeCode::Mentioned,
+                                recipient,
+                                actor.clone(),
+                                entity.clone(),
+                            )
+                            .with_body(&comment_preview)
+                        })
+                        .await;
                 });
             }
 
@@ -64,7 +64,7 @@ fn get_user_sync_config() -> (usize, usize) {
 
 /// Creates a Microsoft Graph HTTP client with access token using client credentials flow.
 /// This is the shared helper to eliminate token acquisition duplication across sync functions.
-async fn get_msgraph_client_and_token() -> Result<(reqwest::Client, String), String> {
+pub(crate) async fn get_msgraph_client_and_token() -> Result<(reqwest::Client, String), String> {
     let client_id = std::env::var("MICROSOFT_CLIENT_ID")
         .map_err(|_| "MICROSOFT_CLIENT_ID not configured")?;
     let client_secret = std::env::var("MICROSOFT_CLIENT_SECRET")
@@ -3950,6 +3950,7 @@ async fn process_entra_device(
             os_version: entra_device.operating_system_version.clone(),
             is_managed: entra_device.is_managed,
             enrollment_date: registration_date,
+            warranty_expiry_date: None,
             updated_at: Some(chrono::Utc::now().naive_utc()),
         };
 
@@ -3980,6 +3981,7 @@ async fn process_entra_device(
             os_version: entra_device.operating_system_version.clone(),
             is_managed: entra_device.is_managed,
             enrollment_date: registration_date,
+            warranty_expiry_date: None,
         };
 
         device_repo::create_device(conn, new_device)
@@ -4183,7 +4185,7 @@ fn extract_smtp_address(proxy_address: &str) -> Option<String> {
 }
 
 /// Parse Microsoft Graph datetime string to NaiveDateTime
-fn parse_microsoft_datetime(datetime_str: &Option<String>) -> Option<chrono::NaiveDateTime> {
+pub(crate) fn parse_microsoft_datetime(datetime_str: &Option<String>) -> Option<chrono::NaiveDateTime> {
     datetime_str.as_ref().and_then(|s| {
         // Microsoft Graph typically returns ISO 8601 format: "2024-01-15T10:30:00Z"
         chrono::DateTime::parse_from_rfc3339(s)
@@ -0,0 +1,251 @@
+//! "My work" dashboard
+//!
+//! One call returning what the dashboard shows a technician about their own
+//! queue, so the page doesn't make four requests on load. Every part is
+//! scoped to the requesting user.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, NaiveDateTime, Utc};
+use serde::Serialize;
+use tracing::error;
+
+use crate::db::{DbConnection, Pool};
+use crate::extractors::AuthContext;
+use crate::models::{SlaMetric, SlaState, TicketPriority, TicketSla, TicketStatus};
+use crate::repository;
+use crate::repository::ticket_query::TicketQuery;
+use crate::services::notifications::NotificationService;
+use crate::utils::rbac::require_scope;
+
+/// How far ahead an SLA deadline counts as due soon
+const SLA_DUE_SOON_HOURS: i64 = 4;
+
+/// Watched tickets returned
+const WATCHED_TICKETS_LIMIT: i64 = 10;
+
+/// An SLA deadline on one of the user's tickets that hasn't passed yet
+#[derive(Debug, Serialize)]
+pub struct SlaDueSoon {
+    pub ticket_id: i32,
+    pub title: String,
+    pub priority: TicketPriority,
+    pub metric: SlaMetric,
+    pub due_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatchedTicket {
+    pub id: i32,
+    pub title: String,
+    pub status: TicketStatus,
+    pub priority: TicketPriority,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MyWork {
+    /// Unclosed tickets assigned to the user
+    pub open_assigned: i64,
+    /// Deadlines due within `SLA_DUE_SOON_HOURS`, soonest first
+    pub sla_due_soon: Vec<SlaDueSoon>,
+    pub unread_notifications: i64,
+    /// Watched tickets the user can still see, most recently updated first
+    pub watched_tickets: Vec<WatchedTicket>,
+}
+
+/// Everything but the notification count, which comes from the service
+fn load_my_work(
+    conn: &mut DbConnection,
+    auth: &AuthContext,
+    now: NaiveDateTime,
+) -> Result<MyWork, diesel::result::Error> {
+    let open_assigned = TicketQuery::new()
+        .visible_to(auth)
+        .assignee(Some(auth.user_uuid.to_string()))
+        .status(Some("open,in-progress".to_string()))
+        .count(conn)?;
+
+    let due_by = now + Duration::hours(SLA_DUE_SOON_HOURS);
+    let mut sla_due_soon = Vec::new();
+    for (ticket, target) in repository::sla::open_assigned_tickets_with_targets(conn, auth.user_uuid)? {
+        let sla = TicketSla::evaluate(&ticket, &target, now);
+        for (metric, status) in [
+            (SlaMetric::FirstResponse, sla.first_response),
+            (SlaMetric::Resolution, sla.resolution),
+        ] {
+            let Some(status) = status else { continue };
+            if status.state == SlaState::OnTrack && status.due_at <= due_by {
+                sla_due_soon.push(SlaDueSoon {
+                    ticket_id: ticket.id,
+                    title: ticket.title.clone(),
+                    priority: ticket.priority,
+                    metric,
+                    due_at: status.due_at,
+                });
+            }
+        }
+    }
+    sla_due_soon.sort_by_key(|due| due.due_at);
+
+    // Watching doesn't outlive losing access to the ticket's category
+    let watched = repository::ticket_watchers::watched_tickets(conn, auth.user_uuid, WATCHED_TICKETS_LIMIT)?;
+    let ids: Vec<i32> = watched.iter().map(|t| t.id).collect();
+    let visible = TicketQuery::new().visible_to(auth).matching_ids(conn, &ids)?;
+    let watched_tickets = watched
+        .into_iter()
+        .filter(|t| visible.contains(&t.id))
+        .map(|t| WatchedTicket {
+            id: t.id,
+            title: t.title,
+            status: t.status,
+            priority: t.priority,
+            updated_at: t.updated_at,
+        })
+        .collect();
+
+    Ok(MyWork {
+        open_assigned,
+        sla_due_soon,
+        unread_notifications: 0,
+        watched_tickets,
+    })
+}
+
+/// The requesting user's open assigned count, SLA deadlines due soon,
+/// unread notification count and recently updated watched tickets
+pub async fn get_my_work(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    notification_service: web::Data<NotificationService>,
+    auth: AuthContext,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:read") {
+        return e;
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    let mut my_work = match load_my_work(&mut conn, &auth, Utc::now().naive_utc()) {
+        Ok(my_work) => my_work,
+        Err(e) => {
+            error!(error = %e, "Failed to load my work");
+            return HttpResponse::InternalServerError().json("Failed to load my work");
+        }
+    };
+    drop(conn);
+
+    my_work.unread_notifications = match notification_service.get_unread_count(&auth.user_uuid).await {
+        Ok(count) => count,
+        Err(e) => {
+            error!(error = %e, "Failed to count unread notifications");
+            return HttpResponse::InternalServerError().json("Failed to load my work");
+        }
+    };
+
+    HttpResponse::Ok().json(my_work)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::sse::SseState;
+    use crate::models::{NewSlaTarget, UserRole};
+    use crate::schema::tickets;
+    use crate::services::notifications::channels::in_app::InAppChannel;
+    use crate::services::notifications::types::{
+        NotificationActor, NotificationEntity, NotificationPayload, NotificationTypeCode,
+    };
+    use crate::test_helpers::{create_test_claims, setup_test_pool, TestFixtures};
+    use actix_web::dev::Service;
+    use actix_web::{http::StatusCode, test, App, HttpMessage};
+    use diesel::prelude::*;
+    use std::sync::Arc;
+
+    #[actix_web::test]
+    async fn my_work_reflects_the_technicians_tickets() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let tech = TestFixtures::create_user(&mut conn, "myworktech", UserRole::Technician);
+        let other = TestFixtures::create_user(&mut conn, "myworkother", UserRole::Technician);
+
+        repository::sla::upsert_target(&mut conn, &NewSlaTarget {
+            priority: TicketPriority::High,
+            first_response_minutes: Some(60),
+            resolution_minutes: Some(24 * 60),
+        })
+        .unwrap();
+
+        let urgent = TestFixtures::create_ticket(&mut conn, "My work urgent", None, None);
+        let routine = TestFixtures::create_ticket(&mut conn, "My work routine", None, None);
+        let done = TestFixtures::create_ticket(&mut conn, "My work closed", None, None);
+        let theirs = TestFixtures::create_ticket(&mut conn, "My work theirs", None, None);
+        for (ticket, assignee) in [(&urgent, tech.uuid), (&routine, tech.uuid), (&done, tech.uuid), (&theirs, other.uuid)] {
+            diesel::update(tickets::table.find(ticket.id))
+                .set(tickets::assignee_uuid.eq(assignee))
+                .execute(&mut conn)
+                .unwrap();
+        }
+        diesel::update(tickets::table.find(urgent.id))
+            .set(tickets::priority.eq(TicketPriority::High))
+            .execute(&mut conn)
+            .unwrap();
+        diesel::update(tickets::table.find(done.id))
+            .set(tickets::status.eq(TicketStatus::Closed))
+            .execute(&mut conn)
+            .unwrap();
+
+        repository::ticket_watchers::add_watcher(&mut conn, theirs.id, tech.uuid).unwrap();
+        drop(conn);
+
+        let notification_service = NotificationService::new(pool.clone());
+        notification_service.register_channel(Arc::new(InAppChannel::new(Arc::new(SseState::new()))));
+        notification_service
+            .notify(NotificationPayload::new(
+                NotificationTypeCode::TicketAssigned,
+                tech.uuid,
+                NotificationActor { uuid: other.uuid, name: other.name.clone(), avatar_thumb: None },
+                NotificationEntity::Ticket { id: urgent.id, title: urgent.title.clone() },
+            ))
+            .await
+            .unwrap();
+
+        let claims = create_test_claims(&tech);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .app_data(web::Data::new(notification_service))
+                .wrap_fn(move |req, srv| {
+                    req.extensions_mut().insert(claims.clone());
+                    srv.call(req)
+                })
+                .route("/my-work", web::get().to(get_my_work)),
+        )
+        .await;
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/my-work").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+
+        assert_eq!(body["open_assigned"], 2);
+        assert_eq!(body["unread_notifications"], 1);
+
+        // The high priority ticket's first response is due within the window,
+        // its resolution isn't
+        let due: Vec<_> = body["sla_due_soon"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|d| d["ticket_id"] == urgent.id)
+            .collect();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0]["metric"], "first_response");
+        assert!(body["sla_due_soon"].as_array().unwrap().iter().all(|d| d["ticket_id"] != done.id));
+
+        let watched = body["watched_tickets"].as_array().unwrap();
+        assert_eq!(watched.len(), 1);
+        assert_eq!(watched[0]["id"], theirs.id);
+    }
+}
@@ -4,9 +4,12 @@
 
 use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
 use serde::Deserialize;
+use std::collections::HashMap;
 
 use crate::models::Claims;
-use crate::services::notifications::{NotificationChannel, NotificationService, NotificationTypeCode};
+use crate::services::notifications::preferences::PreferenceMatrix;
+use crate::services::notifications::{i18n, NotificationChannel, NotificationService, NotificationTypeCode};
+use crate::utils::etag::json_with_etag;
 
 /// Query parameters for fetching notifications
 #[derive(Debug, Deserialize)]
@@ -14,6 +17,8 @@ pub struct NotificationQuery {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
     pub unread_only: Option<bool>,
+    /// Mark the returned notifications read (ignored with `unread_only`)
+    pub auto_read_on_fetch: Option<bool>,
 }
 
 /// Request body for marking notifications as read
@@ -36,6 +41,27 @@ pub struct UpdatePreferenceRequest {
     pub enabled: bool,
 }
 
+/// One notification type's channels in a bulk preference update
+#[derive(Debug, Deserialize)]
+pub struct PreferenceMatrixEntry {
+    pub notification_type: String,
+    /// Channel code -> enabled
+    pub channels: HashMap<String, bool>,
+}
+
+/// Request body for updating many preferences at once
+#[derive(Debug, Deserialize)]
+pub struct BulkUpdatePreferencesRequest {
+    pub preferences: Vec<PreferenceMatrixEntry>,
+}
+
+/// Request body for setting the notification locale
+#[derive(Debug, Deserialize)]
+pub struct UpdateLocaleRequest {
+    /// Locale such as `fr` or `fr-CA`; None resets to the default
+    pub locale: Option<String>,
+}
+
 /// Get user's notifications
 ///
 /// GET /api/notifications
@@ -57,15 +83,16 @@ pub async fn get_notifications(
     let limit = query.limit.unwrap_or(20).min(100);
     let offset = query.offset.unwrap_or(0);
     let unread_only = query.unread_only.unwrap_or(false);
+    let auto_read = query.auto_read_on_fetch.unwrap_or(false);
 
     let result = if unread_only {
         notification_service.get_unread(&user_uuid, limit).await
     } else {
-        notification_service.get_all(&user_uuid, limit, offset).await
+        notification_service.get_all(&user_uuid, limit, offset, auto_read).await
     };
 
     match result {
-        Ok(notifications) => HttpResponse::Ok().json(notifications),
+        Ok(notifications) => json_with_etag(&req, &notifications),
         Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
             "error": e
         })),
@@ -234,6 +261,112 @@ pub async fn update_preference(
     }
 }
 
+/// Check a preference matrix, rejecting unknown notification types and channels
+fn parse_preference_matrix(
+    entries: Vec<PreferenceMatrixEntry>,
+) -> Result<PreferenceMatrix, String> {
+    entries
+        .into_iter()
+        .map(|entry| {
+            let notification_type = NotificationTypeCode::from_str(&entry.notification_type)
+                .ok_or_else(|| format!("Invalid notification type: {}", entry.notification_type))?;
+            let channels = entry
+                .channels
+                .into_iter()
+                .map(|(channel, enabled)| {
+                    NotificationChannel::from_str(&channel)
+                        .map(|c| (c, enabled))
+                        .ok_or_else(|| format!("Invalid channel: {channel}"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((notification_type, channels))
+        })
+        .collect()
+}
+
+/// Update the whole preference matrix in one request
+///
+/// PUT /api/notifications/preferences/bulk
+///
+/// Nothing is changed if any entry is invalid. Returns the full preference set.
+pub async fn update_preferences_bulk(
+    req: HttpRequest,
+    notification_service: web::Data<NotificationService>,
+    body: web::Json<BulkUpdatePreferencesRequest>,
+) -> HttpResponse {
+    let claims = match req.extensions().get::<Claims>() {
+        Some(c) => c.clone(),
+        None => return HttpResponse::Unauthorized().finish(),
+    };
+
+    let user_uuid = match uuid::Uuid::parse_str(&claims.sub) {
+        Ok(u) => u,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user UUID"),
+    };
+
+    let updates = match parse_preference_matrix(body.into_inner().preferences) {
+        Ok(updates) => updates,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({ "error": e })),
+    };
+
+    match notification_service
+        .preferences()
+        .set_preferences_bulk(&user_uuid, updates)
+        .await
+    {
+        Ok(prefs) => HttpResponse::Ok().json(prefs),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": e
+        })),
+    }
+}
+
+/// Set the language notifications are sent in
+///
+/// PUT /api/notifications/locale
+pub async fn update_locale(
+    req: HttpRequest,
+    pool: web::Data<crate::db::Pool>,
+    body: web::Json<UpdateLocaleRequest>,
+) -> HttpResponse {
+    let claims = match req.extensions().get::<Claims>() {
+        Some(c) => c.clone(),
+        None => return HttpResponse::Unauthorized().finish(),
+    };
+
+    let user_uuid = match uuid::Uuid::parse_str(&claims.sub) {
+        Ok(u) => u,
+        Err(_) => return HttpResponse::BadRequest().json("Invalid user UUID"),
+    };
+
+    let locale = body.locale.as_deref().map(str::trim).filter(|l| !l.is_empty());
+    if let Some(locale) = locale {
+        let language = locale.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+        if locale.len() > 10 || !i18n::supported_locales().contains(&language.as_str()) {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Unsupported locale: {locale}"),
+                "supported": i18n::supported_locales(),
+            }));
+        }
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {e}")
+            }))
+        }
+    };
+
+    match crate::repository::users::update_user_locale(&mut conn, &user_uuid, locale) {
+        Ok(user) => HttpResponse::Ok().json(serde_json::json!({ "locale": user.locale })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": e.to_string()
+        })),
+    }
+}
+
 /// Delete notifications
 ///
 /// POST /api/notifications/delete
@@ -265,3 +398,40 @@ pub async fn delete_notifications(
         })),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(notification_type: &str, channels: &[(&str, bool)]) -> PreferenceMatrixEntry {
+        PreferenceMatrixEntry {
+            notification_type: notification_type.to_string(),
+            channels: channels.iter().map(|(c, e)| (c.to_string(), *e)).collect(),
+        }
+    }
+
+    #[test]
+    fn preference_matrix_rejects_unknown_types_and_channels() {
+        let valid = parse_preference_matrix(vec![
+            entry("ticket_assigned", &[("email", true)]),
+            entry("mentioned", &[("in_app", false)]),
+        ])
+        .unwrap();
+        assert_eq!(
+            valid,
+            vec![
+                (NotificationTypeCode::TicketAssigned, vec![(NotificationChannel::Email, true)]),
+                (NotificationTypeCode::Mentioned, vec![(NotificationChannel::InApp, false)]),
+            ]
+        );
+
+        let unknown_type = parse_preference_matrix(vec![
+            entry("ticket_assigned", &[("email", true)]),
+            entry("ticket_exploded", &[("email", true)]),
+        ]);
+        assert_eq!(unknown_type, Err("Invalid notification type: ticket_exploded".to_string()));
+
+        let unknown_channel = parse_preference_matrix(vec![entry("mentioned", &[("sms", true)])]);
+        assert_eq!(unknown_channel, Err("Invalid channel: sms".to_string()));
+    }
+}
@@ -9,9 +9,11 @@ use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 use webauthn_rs::prelude::*;
 
+use crate::config_utils::{require_feature, Feature, FeatureFlags, LoginLockoutPolicy};
 use crate::db::Pool;
 use crate::models::Claims;
 use crate::repository;
+use crate::services::audit::{self, AuditAction, AuditTarget};
 use crate::utils::webauthn::{
     self, credential_id_to_string, get_user_passkey_data, save_user_passkey_data,
     StoredPasskeyCredential, WEBAUTHN,
@@ -99,8 +101,12 @@ pub struct PasskeyListResponse {
 pub async fn start_passkey_registration(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     _body: web::Json<StartRegistrationRequest>,
 ) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Passkeys) {
+        return e;
+    }
     // Get authenticated user
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
@@ -244,8 +250,12 @@ pub async fn start_passkey_registration(
 pub async fn finish_passkey_registration(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     body: web::Json<FinishRegistrationRequest>,
 ) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Passkeys) {
+        return e;
+    }
     // Get authenticated user
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
@@ -384,8 +394,12 @@ pub async fn finish_passkey_registration(
 pub async fn start_passkey_login(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     body: web::Json<StartLoginRequest>,
 ) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Passkeys) {
+        return e;
+    }
     // Rate limiting based on IP for discoverable auth, email for non-discoverable
     let redis_url = get_redis_url();
 
@@ -535,8 +549,13 @@ pub async fn start_passkey_login(
 pub async fn finish_passkey_login(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
+    lockout_policy: web::Data<LoginLockoutPolicy>,
     body: web::Json<FinishLoginRequest>,
 ) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Passkeys) {
+        return e;
+    }
     let mut conn = match pool.get() {
         Ok(conn) => conn,
         Err(_) => {
@@ -560,6 +579,15 @@ pub async fn finish_passkey_login(
         }
     };
 
+    // A lock from failed password logins only blocks passkeys if configured to
+    let now = chrono::Utc::now().naive_utc();
+    if !lockout_policy.allow_passkeys {
+        if let Ok(Some(locked_until)) = repository::account_lockouts::active_lock(&mut conn, &user.uuid, now) {
+            warn!(user_uuid = %user.uuid, %locked_until, "Passkey login attempt on locked account");
+            return super::auth::account_locked_response(locked_until);
+        }
+    }
+
     // Parse the authentication response
     let auth_response: PublicKeyCredential = match serde_json::from_value(json!({
         "id": body.id,
@@ -668,18 +696,23 @@ pub async fn finish_passkey_login(
         // Don't fail login for this
     }
 
+    if let Err(e) = repository::account_lockouts::clear(&mut conn, &user.uuid, now) {
+        warn!("Failed to clear account lockout after passkey auth: {:?}", e);
+    }
+
     // Create session and tokens using jwt_helpers (same as regular login)
     let user_uuid = user.uuid;
 
     match jwt_helpers::create_login_response(user, &mut conn) {
         Ok((response, tokens)) => {
             // Create session record after successful login
-            if let Err(e) = super::auth::create_session_record(&user_uuid, &tokens.access_token, &req, &mut conn).await {
+            if let Err(e) = super::auth::create_session_record(&user_uuid, &tokens.access_token, Some(&tokens.refresh_token), &req, &mut conn).await {
                 warn!("Failed to create session record for passkey login: {:?}", e);
                 // Don't fail the login if session creation fails
             }
 
             info!("Passkey login successful for user {}", user_uuid);
+            super::auth::audit_login(&mut conn, user_uuid, AuditAction::LoginSucceeded, "passkey", &req);
 
             // Set httpOnly cookies for tokens
             HttpResponse::Ok()
@@ -738,7 +771,14 @@ fn find_user_by_credential_id(
 // =============================================================================
 
 /// List all passkeys for the current user
-pub async fn list_passkeys(req: HttpRequest, pool: web::Data<Pool>) -> impl Responder {
+pub async fn list_passkeys(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
+) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Passkeys) {
+        return e;
+    }
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
         None => {
@@ -796,9 +836,13 @@ pub async fn list_passkeys(req: HttpRequest, pool: web::Data<Pool>) -> impl Resp
 pub async fn rename_passkey(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<String>,
     body: web::Json<RenamePasskeyRequest>,
 ) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Passkeys) {
+        return e;
+    }
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
         None => {
@@ -875,9 +919,13 @@ pub async fn rename_passkey(
 pub async fn delete_passkey(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<String>,
     body: web::Json<DeletePasskeyRequest>,
 ) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Passkeys) {
+        return e;
+    }
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
         None => {
@@ -949,6 +997,13 @@ pub async fn delete_passkey(
     }
 
     info!("Passkey {} deleted for user {}", credential_id, user_uuid);
+    let _ = audit::record(
+        &mut conn,
+        Some(user_uuid),
+        AuditAction::PasskeyDeleted,
+        Some(AuditTarget::passkey(credential_id.as_str())),
+        json!({}),
+    );
 
     HttpResponse::Ok().json(json!({
         "success": true
@@ -6,14 +6,48 @@ use tracing::{info, warn, error};
 use crate::db::DbConnection;
 use crate::models::{PasswordResetRequest, PasswordResetResponse, PasswordResetCompleteRequest};
 use crate::repository;
+use crate::repository::reset_tokens::TokenError;
+use crate::services::audit::{self, AuditAction, AuditTarget};
 use crate::utils::auth::hash_password;
-use crate::utils::reset_tokens::{TokenType, ResetTokenUtils};
+use crate::utils::rate_limit::{get_redis_url, RateLimiter};
+use crate::utils::reset_tokens::{ResetToken, TokenType, ResetTokenUtils};
 use crate::utils::email::EmailService;
 use crate::utils::email_branding::get_email_branding;
 
 /// Rate limiting: Maximum password reset requests per user within time window
 const MAX_RESET_REQUESTS_PER_HOUR: i64 = 3;
 
+/// Rate limiting: Maximum password reset requests per email address per hour,
+/// counted whether or not the address has an account
+const MAX_RESET_REQUESTS_PER_EMAIL_PER_HOUR: u32 = 3;
+
+/// Issue a password reset token, invalidating any outstanding ones so only
+/// the newest link works
+pub fn issue_reset_token(
+    conn: &mut DbConnection,
+    user_uuid: uuid::Uuid,
+    ip_address: Option<&str>,
+    user_agent: Option<&str>,
+) -> diesel::QueryResult<ResetToken> {
+    use diesel::Connection;
+
+    let reset_token = ResetTokenUtils::create_reset_token(user_uuid, TokenType::PasswordReset);
+    conn.transaction(|conn| {
+        repository::reset_tokens::invalidate_tokens_by_type(conn, user_uuid, TokenType::PasswordReset.as_str())?;
+        repository::reset_tokens::create_reset_token(
+            conn,
+            &reset_token.token_hash,
+            user_uuid,
+            TokenType::PasswordReset.as_str(),
+            ip_address,
+            user_agent,
+            reset_token.expires_at,
+            None, // No metadata needed for password reset
+        )?;
+        Ok(reset_token)
+    })
+}
+
 /// Request a password reset - sends email with reset link
 pub async fn request_password_reset(
     db_pool: web::Data<crate::db::Pool>,
@@ -37,6 +71,27 @@ pub async fn request_password_reset(
         }));
     }
 
+    // Limit per address so a mailbox can't be flooded with reset links. The
+    // limit applies to unknown addresses too, so it doesn't reveal accounts.
+    match RateLimiter::check_rate_limit(
+        &get_redis_url(),
+        &RateLimiter::password_reset_key(&email),
+        MAX_RESET_REQUESTS_PER_EMAIL_PER_HOUR,
+        3600,
+    ).await {
+        Ok(false) => {
+            return HttpResponse::TooManyRequests().json(json!({
+                "status": "error",
+                "message": "Too many password reset requests. Please try again later."
+            }));
+        }
+        Err(e) => {
+            // Fail open; the per-user limit below still applies
+            warn!("Rate limit check failed for password reset: {}", e);
+        }
+        Ok(true) => {}
+    }
+
     // Extract IP address and user agent for audit trail
     let ip_address = http_request.peer_addr()
         .map(|addr| addr.ip().to_string());
@@ -87,26 +142,17 @@ pub async fn request_password_reset(
         });
     }
 
-    // Generate reset token
-    let reset_token = ResetTokenUtils::create_reset_token(user.uuid, TokenType::PasswordReset);
-
-    // Store token hash in database
-    if let Err(e) = repository::reset_tokens::create_reset_token(
-        &mut conn,
-        &reset_token.token_hash,
-        user.uuid,
-        TokenType::PasswordReset.as_str(),
-        ip_address.as_deref(),
-        user_agent.as_deref(),
-        reset_token.expires_at,
-        None, // No metadata needed for password reset
-    ) {
-        error!("Failed to create password reset token: {}", e);
-        return HttpResponse::InternalServerError().json(json!({
-            "status": "error",
-            "message": "Failed to process reset request"
-        }));
-    }
+    // Generate reset token, invalidating any earlier links
+    let reset_token = match issue_reset_token(&mut conn, user.uuid, ip_address.as_deref(), user_agent.as_deref()) {
+        Ok(token) => token,
+        Err(e) => {
+            error!("Failed to create password reset token: {}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": "Failed to process reset request"
+            }));
+        }
+    };
 
     // Get base URL from environment or request
     let base_url = std::env::var("FRONTEND_URL")
@@ -206,9 +252,20 @@ pub async fn reset_password_with_token(
         Ok(uuid) => uuid,
         Err(e) => {
             warn!("Invalid password reset token: {}", e);
+            if let TokenError::AlreadyUsed { user_uuid } = e {
+                // A used or superseded link being replayed is worth an audit trail
+                let ip = http_request.connection_info().realip_remote_addr().map(|s| s.to_string());
+                let _ = audit::record(
+                    &mut conn,
+                    None,
+                    AuditAction::ResetTokenReused,
+                    Some(AuditTarget::user(user_uuid)),
+                    json!({ "token_type": TokenType::PasswordReset.as_str(), "ip": ip }),
+                );
+            }
             return HttpResponse::BadRequest().json(json!({
                 "status": "error",
-                "message": e
+                "message": e.to_string()
             }));
         }
     };
@@ -362,9 +419,92 @@ async fn log_password_reset_event(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{setup_test_pool, TestFixtures};
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{http::StatusCode, App};
 
     #[test]
     fn test_rate_limiting_constant() {
         assert_eq!(MAX_RESET_REQUESTS_PER_HOUR, 3);
     }
+
+    #[actix_web::test]
+    async fn reset_link_is_single_use_and_replay_is_audited() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "resetreplay", UserRole::User);
+        let token = issue_reset_token(&mut conn, user.uuid, None, None).unwrap();
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/password-reset/complete", web::post().to(reset_password_with_token)),
+        )
+        .await;
+        let complete = || {
+            TestRequest::post()
+                .uri("/password-reset/complete")
+                .set_json(json!({ "token": token.raw_token, "new_password": "a-new-password" }))
+                .to_request()
+        };
+
+        assert_eq!(call_service(&app, complete()).await.status(), StatusCode::OK);
+        assert_eq!(call_service(&app, complete()).await.status(), StatusCode::BAD_REQUEST);
+
+        let filter = audit::AuditFilter {
+            action: Some(AuditAction::ResetTokenReused),
+            ..audit::AuditFilter::default()
+        };
+        let replays = audit::query(&mut conn, &filter, 50, 0).unwrap();
+        assert!(replays.iter().any(|entry| entry.target_id == Some(user.uuid.to_string())));
+    }
+
+    #[actix_web::test]
+    async fn new_token_invalidates_outstanding_ones() {
+        let mut conn = setup_test_pool().get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "resetsuperseded", UserRole::User);
+        let first = issue_reset_token(&mut conn, user.uuid, None, None).unwrap();
+        let second = issue_reset_token(&mut conn, user.uuid, None, None).unwrap();
+
+        let kind = TokenType::PasswordReset.as_str();
+        assert_eq!(
+            repository::reset_tokens::validate_and_consume_token(&mut conn, &first.raw_token, kind),
+            Err(TokenError::AlreadyUsed { user_uuid: user.uuid })
+        );
+        assert_eq!(
+            repository::reset_tokens::validate_and_consume_token(&mut conn, &second.raw_token, kind),
+            Ok(user.uuid)
+        );
+    }
+
+    /// Needs Redis as well as the test database:
+    /// `REDIS_URL=redis://localhost:6379 cargo test --features redis-tests`
+    #[cfg(feature = "redis-tests")]
+    #[actix_web::test]
+    async fn reset_requests_are_rate_limited_per_email() {
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(setup_test_pool()))
+                .route("/password-reset/request", web::post().to(request_password_reset)),
+        )
+        .await;
+        let address = format!("flood-{}@example.com", uuid::Uuid::new_v4());
+        let request = |email: String| {
+            TestRequest::post()
+                .uri("/password-reset/request")
+                .set_json(json!({ "email": email }))
+                .to_request()
+        };
+
+        for _ in 0..MAX_RESET_REQUESTS_PER_EMAIL_PER_HOUR {
+            assert_eq!(call_service(&app, request(address.clone())).await.status(), StatusCode::OK);
+        }
+        // Same mailbox, different case
+        let resp = call_service(&app, request(address.to_uppercase())).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        // Other addresses are unaffected
+        let other = format!("other-{}@example.com", uuid::Uuid::new_v4());
+        assert_eq!(call_service(&app, request(other)).await.status(), StatusCode::OK);
+    }
 }
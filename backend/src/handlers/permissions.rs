@@ -0,0 +1,160 @@
+//! Permission Handlers
+//!
+//! Admin endpoints for viewing role permission bundles and granting extra
+//! permissions to users or groups.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::models::NewPermissionGrant;
+use crate::repository::permission_grants as grant_repo;
+use crate::services::audit::{self, AuditAction, AuditTarget};
+use crate::utils::permissions::{role_permissions, Permission};
+use crate::utils::rbac::{require_permission, require_scope, FULL_SCOPE};
+
+/// Request body for granting a permission to exactly one of a user or group
+#[derive(Debug, Deserialize)]
+pub struct CreateGrantRequest {
+    pub permission: Permission,
+    pub user_uuid: Option<Uuid>,
+    pub group_id: Option<i32>,
+}
+
+fn audit_target(user_uuid: Option<Uuid>, group_id: Option<i32>) -> Option<AuditTarget> {
+    user_uuid
+        .map(AuditTarget::user)
+        .or_else(|| group_id.map(AuditTarget::group))
+}
+
+/// List available permissions, role bundles and current grants (requires manage_permissions)
+pub async fn list_permissions(req: HttpRequest, pool: web::Data<Pool>) -> impl Responder {
+    if let Err(e) = require_permission(&req, Permission::ManagePermissions) {
+        return e;
+    }
+    if let Err(e) = require_scope(&req, "users:read") {
+        return e;
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    match grant_repo::list_grants(&mut conn) {
+        Ok(grants) => HttpResponse::Ok().json(json!({
+            "permissions": Permission::ALL,
+            "roles": {
+                "admin": role_permissions("admin"),
+                "technician": role_permissions("technician"),
+                "user": role_permissions("user"),
+            },
+            "grants": grants,
+        })),
+        Err(e) => {
+            error!(error = %e, "Failed to list permission grants");
+            HttpResponse::InternalServerError().json("Failed to list permission grants")
+        }
+    }
+}
+
+/// Grant a permission to a user or group (requires manage_permissions)
+pub async fn create_grant(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    body: web::Json<CreateGrantRequest>,
+) -> impl Responder {
+    let claims = match require_permission(&req, Permission::ManagePermissions) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+    // Grants can escalate privileges, so scoped API tokens may not change them
+    if let Err(e) = require_scope(&req, FULL_SCOPE) {
+        return e;
+    }
+
+    if body.user_uuid.is_some() == body.group_id.is_some() {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Invalid grant",
+            "message": "Exactly one of user_uuid or group_id is required"
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    let actor = Uuid::parse_str(&claims.sub).ok();
+    let new_grant = NewPermissionGrant {
+        permission: body.permission.as_str().to_string(),
+        user_uuid: body.user_uuid,
+        group_id: body.group_id,
+        granted_by: actor,
+    };
+
+    match grant_repo::create_grant(&mut conn, new_grant) {
+        Ok(grant) => {
+            let _ = audit::record(
+                &mut conn,
+                actor,
+                AuditAction::PermissionGranted,
+                audit_target(grant.user_uuid, grant.group_id),
+                json!({ "permission": grant.permission }),
+            );
+            HttpResponse::Created().json(grant)
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            HttpResponse::Conflict().json("Permission already granted")
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            HttpResponse::NotFound().json("User or group not found")
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to create permission grant");
+            HttpResponse::InternalServerError().json("Failed to create permission grant")
+        }
+    }
+}
+
+/// Revoke a permission grant (requires manage_permissions)
+pub async fn delete_grant(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let claims = match require_permission(&req, Permission::ManagePermissions) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+    if let Err(e) = require_scope(&req, FULL_SCOPE) {
+        return e;
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    match grant_repo::delete_grant(&mut conn, path.into_inner()) {
+        Ok(Some(grant)) => {
+            let _ = audit::record(
+                &mut conn,
+                Uuid::parse_str(&claims.sub).ok(),
+                AuditAction::PermissionRevoked,
+                audit_target(grant.user_uuid, grant.group_id),
+                json!({ "permission": grant.permission }),
+            );
+            HttpResponse::NoContent().finish()
+        }
+        Ok(None) => HttpResponse::NotFound().json("Permission grant not found"),
+        Err(e) => {
+            error!(error = %e, "Failed to delete permission grant");
+            HttpResponse::InternalServerError().json("Failed to delete permission grant")
+        }
+    }
+}
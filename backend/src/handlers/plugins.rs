@@ -5,7 +5,7 @@
 use actix_multipart::Multipart;
 use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
 use chrono::Utc;
-use diesel::result::Error as DieselError;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use futures::StreamExt;
 use hex;
 use ring::digest::{Context, SHA256};
@@ -17,15 +17,24 @@ use tracing::{error, info, warn};
 use uuid::Uuid;
 use zip;
 
+use crate::config_utils::{require_feature, Feature, FeatureFlags, PluginPaths};
 use crate::db::{DbConnection, Pool};
 use crate::models::{
     Claims, InstallPluginRequest, NewPlugin, PluginActivityResponse, PluginBundleUpdate,
-    PluginResponse, PluginSettingResponse, PluginStorageResponse, PluginUpdate,
-    SetPluginDataRequest, UpdatePluginRequest,
+    PluginResponse, PluginSettingResponse, PluginStorageResponse, PluginStorageScope,
+    PluginStorageScopeQuery, PluginUpdate, SetPluginDataRequest, SetPluginStorageRequest,
+    UpdatePluginRequest,
 };
-use crate::repository::plugins as plugin_repo;
+use crate::repository::{self, plugins as plugin_repo};
+use crate::repository::ticket_query::PaginatedResult;
+use crate::services::audit::{self, AuditAction, AuditTarget};
+use crate::services::plugins::signing;
+use crate::services::webhooks::WebhookEventType;
 use crate::utils::encryption;
-use crate::utils::rbac::require_admin;
+use crate::utils::etag::json_with_etag;
+use crate::utils::file_validation::{self, UploadContext};
+use crate::utils::permissions::Permission;
+use crate::utils::rbac::require_permission;
 
 /// Query parameters for pagination
 #[derive(Debug, Deserialize)]
@@ -66,6 +75,56 @@ fn validate_plugin_name(name: &str) -> Result<String, HttpResponse> {
     Ok(trimmed.to_string())
 }
 
+/// Reject a manifest with missing or malformed fields, listing each one
+fn validate_manifest(manifest: &crate::models::PluginManifest) -> Result<(), HttpResponse> {
+    manifest.validate().map_err(|errors| {
+        HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Invalid plugin manifest",
+            "fields": errors,
+        }))
+    })
+}
+
+/// Validate the server-side event subscriptions declared in a manifest
+fn validate_event_subscriptions(manifest: &crate::models::PluginManifest) -> Result<(), HttpResponse> {
+    if manifest.event_subscriptions.is_empty() {
+        return Ok(());
+    }
+    if manifest.event_handler.is_none() {
+        return Err(HttpResponse::BadRequest().json("event_subscriptions requires an event_handler"));
+    }
+    if let Some(unknown) = manifest
+        .event_subscriptions
+        .iter()
+        .find(|e| WebhookEventType::from_str(e).is_none())
+    {
+        return Err(HttpResponse::BadRequest().json(format!("Unknown event subscription: {unknown}")));
+    }
+    Ok(())
+}
+
+/// 409 for installing a plugin whose name is taken
+fn plugin_name_conflict(name: &str) -> HttpResponse {
+    HttpResponse::Conflict().json(format!(
+        "Plugin '{name}' already exists. Uninstall it first or use the update endpoint."
+    ))
+}
+
+/// Response for a failed plugin insert.
+///
+/// The unique constraint on the name is what prevents duplicates; the
+/// lookup before inserting only saves a write. An install racing another
+/// of the same name gets the same 409 as the lookup gives.
+fn create_plugin_error(e: DieselError, name: &str) -> HttpResponse {
+    match e {
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => plugin_name_conflict(name),
+        e => {
+            error!("Failed to install plugin: {}", e);
+            HttpResponse::InternalServerError().json("Failed to install plugin")
+        }
+    }
+}
+
 /// Get a plugin by UUID or return a 404/500 error response
 fn get_plugin_or_error(
     conn: &mut DbConnection,
@@ -85,9 +144,16 @@ fn get_plugin_or_error(
 // Plugin CRUD Handlers
 // =============================================================================
 
-/// List all plugins (admin only)
-pub async fn list_plugins(req: HttpRequest, pool: web::Data<Pool>) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+/// List all plugins (requires manage_plugins)
+pub async fn list_plugins(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
+) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManagePlugins) {
         return e;
     }
 
@@ -102,7 +168,7 @@ pub async fn list_plugins(req: HttpRequest, pool: web::Data<Pool>) -> impl Respo
                 .into_iter()
                 .filter_map(|p| PluginResponse::try_from(p).ok())
                 .collect();
-            HttpResponse::Ok().json(response)
+            json_with_etag(&req, &response)
         }
         Err(e) => {
             error!("Failed to list plugins: {}", e);
@@ -112,7 +178,14 @@ pub async fn list_plugins(req: HttpRequest, pool: web::Data<Pool>) -> impl Respo
 }
 
 /// List enabled plugins (for frontend plugin loader - authenticated users)
-pub async fn list_enabled_plugins(req: HttpRequest, pool: web::Data<Pool>) -> impl Responder {
+pub async fn list_enabled_plugins(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
+) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
+    }
     // Any authenticated user can get enabled plugins
     if req.extensions().get::<Claims>().is_none() {
         return HttpResponse::Unauthorized().json("Authentication required");
@@ -129,7 +202,7 @@ pub async fn list_enabled_plugins(req: HttpRequest, pool: web::Data<Pool>) -> im
                 .into_iter()
                 .filter_map(|p| PluginResponse::try_from(p).ok())
                 .collect();
-            HttpResponse::Ok().json(response)
+            json_with_etag(&req, &response)
         }
         Err(e) => {
             error!("Failed to list enabled plugins: {}", e);
@@ -138,13 +211,17 @@ pub async fn list_enabled_plugins(req: HttpRequest, pool: web::Data<Pool>) -> im
     }
 }
 
-/// Install a new plugin (admin only)
+/// Install a new plugin (requires manage_plugins)
 pub async fn install_plugin(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     body: web::Json<InstallPluginRequest>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManagePlugins) {
         return e;
     }
 
@@ -155,11 +232,17 @@ pub async fn install_plugin(
 
     let installed_by = Uuid::parse_str(&claims.sub).ok();
 
+    if let Err(e) = validate_manifest(&body.manifest) {
+        return e;
+    }
     // Validate plugin name
     let name = match validate_plugin_name(&body.manifest.name) {
         Ok(n) => n,
         Err(e) => return e,
     };
+    if let Err(e) = validate_event_subscriptions(&body.manifest) {
+        return e;
+    }
 
     let mut conn = match get_connection(&pool) {
         Ok(c) => c,
@@ -168,7 +251,7 @@ pub async fn install_plugin(
 
     // Check if plugin with same name already exists
     if plugin_repo::get_plugin_by_name(&mut conn, &name).is_ok() {
-        return HttpResponse::Conflict().json("Plugin with this name already exists");
+        return plugin_name_conflict(&name);
     }
 
     let manifest_json = match serde_json::to_value(&body.manifest) {
@@ -182,10 +265,16 @@ pub async fn install_plugin(
     let trust_level = body
         .trust_level
         .clone()
-        .unwrap_or_else(|| "community".to_string());
+        .unwrap_or_else(|| signing::TRUST_COMMUNITY.to_string());
+
+    // Official trust needs a publisher signature, which only zip installs carry
+    if trust_level == signing::TRUST_OFFICIAL {
+        return HttpResponse::BadRequest()
+            .json("Official plugins must be installed from a signed zip");
+    }
 
     let new_plugin = NewPlugin {
-        name,
+        name: name.clone(),
         display_name: body.manifest.display_name.clone(),
         version: body.manifest.version.clone(),
         description: body.manifest.description.clone(),
@@ -194,27 +283,32 @@ pub async fn install_plugin(
         trust_level,
         installed_by,
         source: "uploaded".to_string(),
+        verified_publisher: None,
     };
 
-    match plugin_repo::create_plugin(&mut conn, new_plugin) {
+    // The plugin and its install activity are written together
+    let result = repository::with_transaction(&mut conn, |conn| {
+        let plugin = plugin_repo::create_plugin(conn, new_plugin)?;
+        plugin_repo::log_plugin_activity(
+            conn,
+            plugin.id,
+            "installed".to_string(),
+            Some(serde_json::json!({
+                "version": plugin.version,
+                "installed_by": installed_by,
+            })),
+            installed_by,
+        )?;
+        Ok::<_, DieselError>(plugin)
+    });
+
+    match result {
         Ok(plugin) => {
             info!(
                 "Plugin installed: {} ({}) by {:?}",
                 plugin.uuid, plugin.name, installed_by
             );
 
-            // Log the installation activity
-            let _ = plugin_repo::log_plugin_activity(
-                &mut conn,
-                plugin.id,
-                "installed".to_string(),
-                Some(serde_json::json!({
-                    "version": plugin.version,
-                    "installed_by": installed_by,
-                })),
-                installed_by,
-            );
-
             match PluginResponse::try_from(plugin) {
                 Ok(response) => HttpResponse::Created().json(response),
                 Err(e) => {
@@ -223,20 +317,21 @@ pub async fn install_plugin(
                 }
             }
         }
-        Err(e) => {
-            error!("Failed to install plugin: {}", e);
-            HttpResponse::InternalServerError().json("Failed to install plugin")
-        }
+        Err(e) => create_plugin_error(e, &name),
     }
 }
 
-/// Get a single plugin by UUID (admin only)
+/// Get a single plugin by UUID (requires manage_plugins)
 pub async fn get_plugin(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<Uuid>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManagePlugins) {
         return e;
     }
 
@@ -261,14 +356,18 @@ pub async fn get_plugin(
     }
 }
 
-/// Update a plugin (admin only)
+/// Update a plugin (requires manage_plugins)
 pub async fn update_plugin(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<Uuid>,
     body: web::Json<UpdatePluginRequest>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManagePlugins) {
         return e;
     }
 
@@ -297,11 +396,18 @@ pub async fn update_plugin(
         update.enabled = Some(enabled);
     }
 
+    let mut trust_downgraded = false;
     if let Some(ref manifest) = body.manifest {
         update.display_name = Some(manifest.display_name.clone());
         update.version = Some(manifest.version.clone());
         update.description = manifest.description.clone();
         if let Ok(v) = serde_json::to_value(manifest) {
+            // The publisher's signature covered the old manifest, not this one
+            if v != plugin.manifest && plugin.trust_level != signing::TRUST_COMMUNITY {
+                update.trust_level = Some(signing::TRUST_COMMUNITY.to_string());
+                update.verified_publisher = Some(None);
+                trust_downgraded = true;
+            }
             update.manifest = Some(v);
         }
     }
@@ -318,6 +424,7 @@ pub async fn update_plugin(
                 Some(serde_json::json!({
                     "enabled": body.enabled,
                     "version_updated": body.manifest.is_some(),
+                    "trust_downgraded": trust_downgraded,
                 })),
                 user_uuid,
             );
@@ -338,13 +445,18 @@ pub async fn update_plugin(
     }
 }
 
-/// Uninstall a plugin (admin only)
+/// Uninstall a plugin (requires manage_plugins)
 pub async fn uninstall_plugin(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
+    paths: web::Data<PluginPaths>,
     path: web::Path<Uuid>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManagePlugins) {
         return e;
     }
 
@@ -355,12 +467,32 @@ pub async fn uninstall_plugin(
         Err(e) => return e,
     };
 
-    match plugin_repo::delete_plugin_by_uuid(&mut conn, plugin_uuid) {
-        Ok(count) if count > 0 => {
-            info!("Plugin uninstalled: {}", plugin_uuid);
+    match plugin_repo::uninstall_plugin(&mut conn, plugin_uuid) {
+        Ok(Some(removed)) => {
+            drop(conn);
+
+            // The plugin is gone either way; a leftover bundle is only disk space
+            let bundle_dir = paths.bundle_dir(plugin_uuid);
+            let bundle_removed = match fs::remove_dir_all(&bundle_dir).await {
+                Ok(()) => true,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+                Err(e) => {
+                    warn!("Failed to remove bundle directory {}: {}", bundle_dir.display(), e);
+                    false
+                }
+            };
+
+            info!(
+                "Plugin uninstalled: {} (removed {} settings, {} storage entries, {} activity entries{})",
+                plugin_uuid,
+                removed.settings,
+                removed.storage,
+                removed.activity,
+                if bundle_removed { " and its bundle" } else { "" }
+            );
             HttpResponse::NoContent().finish()
         }
-        Ok(_) => HttpResponse::NotFound().json("Plugin not found"),
+        Ok(None) => HttpResponse::NotFound().json("Plugin not found"),
         Err(e) => {
             error!("Failed to uninstall plugin: {}", e);
             HttpResponse::InternalServerError().json("Failed to uninstall plugin")
@@ -372,13 +504,17 @@ pub async fn uninstall_plugin(
 // Plugin Settings Handlers
 // =============================================================================
 
-/// Get all settings for a plugin (admin only)
+/// Get all settings for a plugin (requires manage_plugins)
 pub async fn get_plugin_settings(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<Uuid>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManagePlugins) {
         return e;
     }
 
@@ -407,16 +543,21 @@ pub async fn get_plugin_settings(
     }
 }
 
-/// Set a plugin setting (admin only)
+/// Set a plugin setting (requires manage_plugins)
 pub async fn set_plugin_setting(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<Uuid>,
     body: web::Json<SetPluginDataRequest>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
         return e;
     }
+    let claims = match require_permission(&req, Permission::ManagePlugins) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
 
     let plugin_uuid = path.into_inner();
 
@@ -473,6 +614,15 @@ pub async fn set_plugin_setting(
     ) {
         Ok(setting) => {
             info!("Plugin setting updated: {} / {}", plugin.name, body.key);
+            if is_secret {
+                let _ = audit::record(
+                    &mut conn,
+                    Uuid::parse_str(&claims.sub).ok(),
+                    AuditAction::PluginSecretSet,
+                    Some(AuditTarget::plugin(plugin.uuid)),
+                    serde_json::json!({ "plugin": plugin.name, "key": body.key }),
+                );
+            }
             HttpResponse::Ok().json(PluginSettingResponse::from(setting))
         }
         Err(e) => {
@@ -482,13 +632,17 @@ pub async fn set_plugin_setting(
     }
 }
 
-/// Delete a plugin setting (admin only)
+/// Delete a plugin setting (requires manage_plugins)
 pub async fn delete_plugin_setting(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<(Uuid, String)>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManagePlugins) {
         return e;
     }
 
@@ -518,15 +672,37 @@ pub async fn delete_plugin_setting(
 // Plugin Storage Handlers (for plugin runtime use)
 // =============================================================================
 
+/// Storage namespace for a request: the caller's own for user scope, else global
+fn storage_owner(req: &HttpRequest, scope: PluginStorageScope) -> Result<Option<Uuid>, HttpResponse> {
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or_else(|| HttpResponse::Unauthorized().json("Authentication required"))?;
+
+    match scope {
+        PluginStorageScope::Global => Ok(None),
+        PluginStorageScope::User => crate::utils::parse_uuid(&claims.sub)
+            .map(Some)
+            .map_err(|_| HttpResponse::BadRequest().json("Invalid user UUID")),
+    }
+}
+
 /// Get storage value for a plugin (authenticated users - for plugin use)
 pub async fn get_plugin_storage(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<(Uuid, String)>,
+    query: web::Query<PluginStorageScopeQuery>,
 ) -> impl Responder {
-    if req.extensions().get::<Claims>().is_none() {
-        return HttpResponse::Unauthorized().json("Authentication required");
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
     }
+    let owner = match storage_owner(&req, query.scope) {
+        Ok(o) => o,
+        Err(e) => return e,
+    };
 
     let (plugin_uuid, key) = path.into_inner();
 
@@ -540,7 +716,7 @@ pub async fn get_plugin_storage(
         Err(e) => return e,
     };
 
-    match plugin_repo::get_plugin_storage_entry(&mut conn, plugin.id, &key) {
+    match plugin_repo::get_plugin_storage_entry(&mut conn, plugin.id, &key, owner) {
         Ok(entry) => HttpResponse::Ok().json(PluginStorageResponse::from(entry)),
         Err(DieselError::NotFound) => HttpResponse::Ok().json(serde_json::json!({
             "key": key,
@@ -557,12 +733,17 @@ pub async fn get_plugin_storage(
 pub async fn set_plugin_storage(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<Uuid>,
-    body: web::Json<SetPluginDataRequest>,
+    body: web::Json<SetPluginStorageRequest>,
 ) -> impl Responder {
-    if req.extensions().get::<Claims>().is_none() {
-        return HttpResponse::Unauthorized().json("Authentication required");
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
     }
+    let owner = match storage_owner(&req, body.scope) {
+        Ok(o) => o,
+        Err(e) => return e,
+    };
 
     let plugin_uuid = path.into_inner();
 
@@ -581,6 +762,7 @@ pub async fn set_plugin_storage(
         plugin.id,
         body.key.clone(),
         Some(body.value.clone()),
+        owner,
     ) {
         Ok(entry) => HttpResponse::Ok().json(PluginStorageResponse::from(entry)),
         Err(e) => {
@@ -594,11 +776,17 @@ pub async fn set_plugin_storage(
 pub async fn delete_plugin_storage(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<(Uuid, String)>,
+    query: web::Query<PluginStorageScopeQuery>,
 ) -> impl Responder {
-    if req.extensions().get::<Claims>().is_none() {
-        return HttpResponse::Unauthorized().json("Authentication required");
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
     }
+    let owner = match storage_owner(&req, query.scope) {
+        Ok(o) => o,
+        Err(e) => return e,
+    };
 
     let (plugin_uuid, key) = path.into_inner();
 
@@ -612,7 +800,7 @@ pub async fn delete_plugin_storage(
         Err(e) => return e,
     };
 
-    match plugin_repo::delete_plugin_storage_entry(&mut conn, plugin.id, &key) {
+    match plugin_repo::delete_plugin_storage_entry(&mut conn, plugin.id, &key, owner) {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(e) => {
             error!("Failed to delete plugin storage: {}", e);
@@ -625,20 +813,24 @@ pub async fn delete_plugin_storage(
 // Plugin Activity Handlers
 // =============================================================================
 
-/// Get activity log for a plugin (admin only)
+/// Get activity log for a plugin (requires manage_plugins)
 pub async fn get_plugin_activity(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<Uuid>,
     query: web::Query<PaginationQuery>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManagePlugins) {
         return e;
     }
 
     let plugin_uuid = path.into_inner();
-    let limit = query.limit.unwrap_or(50).min(100);
-    let offset = query.offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
 
     let mut conn = match get_connection(&pool) {
         Ok(c) => c,
@@ -650,11 +842,17 @@ pub async fn get_plugin_activity(
         Err(e) => return e,
     };
 
-    match plugin_repo::get_plugin_activity(&mut conn, plugin.id, limit, offset) {
-        Ok(activity) => {
+    let activity = plugin_repo::get_plugin_activity(&mut conn, plugin.id, limit, offset)
+        .and_then(|activity| {
+            let total = plugin_repo::count_plugin_activity(&mut conn, plugin.id)?;
+            Ok((activity, total))
+        });
+
+    match activity {
+        Ok((activity, total)) => {
             let response: Vec<PluginActivityResponse> =
                 activity.into_iter().map(Into::into).collect();
-            HttpResponse::Ok().json(response)
+            HttpResponse::Ok().json(PaginatedResult::from_offset(response, total, limit, offset))
         }
         Err(e) => {
             error!("Failed to get plugin activity: {}", e);
@@ -671,13 +869,18 @@ pub async fn get_plugin_activity(
 pub async fn proxy_plugin_request(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     proxy_service: web::Data<crate::services::plugins::PluginProxyService>,
     path: web::Path<Uuid>,
     body: web::Json<crate::models::PluginProxyRequest>,
 ) -> impl Responder {
-    if req.extensions().get::<Claims>().is_none() {
-        return HttpResponse::Unauthorized().json("Authentication required");
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
     }
+    let claims = match req.extensions().get::<Claims>() {
+        Some(claims) => claims.clone(),
+        None => return HttpResponse::Unauthorized().json("Authentication required"),
+    };
 
     let plugin_uuid = path.into_inner();
 
@@ -705,70 +908,89 @@ pub async fn proxy_plugin_request(
         }
     };
 
-    // Fetch plugin settings for auth injection
-    let settings = match crate::repository::plugins::get_plugin_settings(&mut conn, plugin.id) {
-        Ok(s) => s,
-        Err(e) => {
-            error!("Failed to get plugin settings: {}", e);
-            vec![]
-        }
-    };
-
-    // Build secrets map for auth injection (decrypt encrypted secrets)
-    let mut secrets = std::collections::HashMap::new();
-    for setting in settings {
-        if setting.is_secret {
-            if let Some(value) = setting.value {
-                if let Some(encrypted) = value.as_str() {
-                    match encryption::decrypt(encrypted) {
-                        Ok(decrypted) => {
-                            secrets.insert(setting.key, decrypted);
-                        }
-                        Err(e) => {
-                            error!(
-                                "Failed to decrypt secret '{}' for plugin '{}': {}",
-                                setting.key, plugin.name, e
-                            );
-                            // Fail closed - don't use potentially compromised data
-                        }
-                    }
-                }
-            }
-        }
-    }
+    // Decrypt plugin secrets for auth injection
+    let secrets = crate::services::plugins::proxy::plugin_secrets(&mut conn, &plugin, |conn, key| {
+        log_secret_tamper_event(conn, &claims, &plugin, key);
+    });
 
     // Execute the proxied request with secrets for auth injection
     match proxy_service.proxy_request(&plugin.name, &manifest, body.into_inner(), &secrets).await {
         Ok(response) => HttpResponse::Ok().json(response),
         Err(e) => {
+            use crate::services::plugins::proxy::ProxyError;
+
             error!("Proxy request failed: {}", e);
-            HttpResponse::BadRequest().json(e)
+            let mut response = match e {
+                ProxyError::PermissionDenied(_) | ProxyError::InvalidRequest(_) => {
+                    HttpResponse::BadRequest()
+                }
+                ProxyError::RateLimited { .. } => HttpResponse::TooManyRequests(),
+                ProxyError::Timeout { .. } => HttpResponse::GatewayTimeout(),
+                ProxyError::ResponseTooLarge { .. }
+                | ProxyError::TooManyRedirects { .. }
+                | ProxyError::RequestFailed(_) => HttpResponse::BadGateway(),
+            };
+            response.json(serde_json::json!({
+                "error": e.code(),
+                "message": e.to_string(),
+            }))
         }
     }
 }
 
+/// Record a security event when a stored plugin secret fails its integrity check
+fn log_secret_tamper_event(
+    conn: &mut DbConnection,
+    claims: &Claims,
+    plugin: &crate::models::Plugin,
+    setting_key: &str,
+) {
+    let Ok(user_uuid) = crate::utils::parse_uuid(&claims.sub) else {
+        return;
+    };
+
+    let event = crate::models::NewSecurityEvent {
+        user_uuid,
+        event_type: crate::models::SecurityEventType::SecretTampered.to_string(),
+        ip_address: None,
+        user_agent: None,
+        location: None,
+        details: Some(serde_json::json!({
+            "plugin_uuid": plugin.uuid,
+            "plugin_name": plugin.name,
+            "setting_key": setting_key,
+        })),
+        severity: "critical".to_string(),
+        session_id: None,
+    };
+
+    if let Err(e) = crate::repository::security_events::create_security_event(conn, event) {
+        warn!("Failed to log plugin secret tamper event: {}", e);
+    }
+}
+
 // =============================================================================
 // Plugin Bundle Handlers
 // =============================================================================
 
 /// Get the bundle storage path for a plugin
-fn get_bundle_path(plugin_uuid: Uuid) -> PathBuf {
-    PathBuf::from("/app/uploads/plugins")
-        .join(plugin_uuid.to_string())
-        .join("bundle.js")
+fn get_bundle_path(paths: &PluginPaths, plugin_uuid: Uuid) -> PathBuf {
+    paths.bundle_dir(plugin_uuid).join("bundle.js")
 }
 
-/// Maximum bundle size (500 KB)
-const MAX_BUNDLE_SIZE: usize = 500 * 1024;
-
-/// Upload a plugin bundle (admin only)
+/// Upload a plugin bundle (requires manage_plugins)
 pub async fn upload_plugin_bundle(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
+    paths: web::Data<PluginPaths>,
     path: web::Path<Uuid>,
     mut payload: Multipart,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManagePlugins) {
         return e;
     }
 
@@ -802,14 +1024,14 @@ pub async fn upload_plugin_bundle(
             continue;
         }
 
-        // Check content type
+        // Check filename and content type against the bundle upload policy
         let content_type = field.content_type().map(|m| m.to_string());
-        if !matches!(
-            content_type.as_deref(),
-            Some("application/javascript") | Some("text/javascript") | Some("application/octet-stream")
-        ) {
-            warn!("Invalid content type for plugin bundle: {:?}", content_type);
-            // Still accept it, just log the warning
+        let filename = field.content_disposition().get_filename().map(|f| f.to_string());
+        let policy = UploadContext::PluginBundle.policy();
+        if let Err(e) = policy.validate(filename.as_deref(), content_type.as_deref(), 0) {
+            warn!("Invalid plugin bundle upload: {}", e);
+            return HttpResponse::BadRequest()
+                .json(format!("Invalid plugin bundle: {e}"));
         }
 
         // Read field data
@@ -822,11 +1044,9 @@ pub async fn upload_plugin_bundle(
                 }
             };
 
-            if bundle_data.len() + data.len() > MAX_BUNDLE_SIZE {
-                return HttpResponse::BadRequest().json(format!(
-                    "Bundle too large. Maximum size is {} KB",
-                    MAX_BUNDLE_SIZE / 1024
-                ));
+            if let Err(e) = policy.check_size(bundle_data.len() + data.len()) {
+                warn!("Bundle too large: {}", e);
+                return HttpResponse::BadRequest().json(e.to_string());
             }
 
             bundle_data.extend_from_slice(&data);
@@ -837,6 +1057,13 @@ pub async fn upload_plugin_bundle(
         return HttpResponse::BadRequest().json("No file data received");
     }
 
+    // JavaScript has no magic bytes - any recognised binary signature means this isn't a bundle
+    if let Some(detected) = file_validation::detect_true_mime(&bundle_data) {
+        warn!("Rejected plugin bundle with binary content: {}", detected);
+        return HttpResponse::BadRequest()
+            .json(format!("Invalid bundle: content detected as {detected}, expected JavaScript"));
+    }
+
     // Basic JavaScript validation - check for export
     let content = String::from_utf8_lossy(&bundle_data);
     if !content.contains("export") {
@@ -851,7 +1078,7 @@ pub async fn upload_plugin_bundle(
     let hash = hex::encode(digest.as_ref());
 
     // Create directory and write file
-    let bundle_path = get_bundle_path(plugin_uuid);
+    let bundle_path = get_bundle_path(&paths, plugin_uuid);
     if let Some(parent) = bundle_path.parent() {
         if let Err(e) = fs::create_dir_all(parent).await {
             error!("Failed to create plugin directory: {}", e);
@@ -904,8 +1131,13 @@ pub async fn upload_plugin_bundle(
 pub async fn serve_plugin_bundle(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
+    paths: web::Data<PluginPaths>,
     path: web::Path<Uuid>,
 ) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
+    }
     // Any authenticated user can request plugin bundles
     if req.extensions().get::<Claims>().is_none() {
         return HttpResponse::Unauthorized().json("Authentication required");
@@ -934,7 +1166,7 @@ pub async fn serve_plugin_bundle(
     }
 
     // Read and serve the bundle
-    let bundle_path = get_bundle_path(plugin_uuid);
+    let bundle_path = get_bundle_path(&paths, plugin_uuid);
 
     match fs::read(&bundle_path).await {
         Ok(data) => HttpResponse::Ok()
@@ -956,10 +1188,7 @@ pub async fn serve_plugin_bundle(
 // Plugin Zip Upload Handler
 // =============================================================================
 
-/// Maximum zip file size (2MB)
-const MAX_ZIP_SIZE: usize = 2 * 1024 * 1024;
-
-/// Install a plugin from a zip file (admin only)
+/// Install a plugin from a zip file (requires manage_plugins)
 ///
 /// The zip file should contain:
 /// - manifest.json (required)
@@ -967,10 +1196,15 @@ const MAX_ZIP_SIZE: usize = 2 * 1024 * 1024;
 pub async fn install_plugin_from_zip(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
+    paths: web::Data<PluginPaths>,
     mut payload: Multipart,
 ) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Plugins) {
+        return e;
+    }
     // Check admin permission
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_permission(&req, Permission::ManagePlugins) {
         return e;
     }
 
@@ -996,11 +1230,17 @@ pub async fn install_plugin_from_zip(
             }
         };
 
-        // Check content type
-        let content_type = field.content_type().map(|m| m.to_string()).unwrap_or_default();
-        if !content_type.contains("zip") && !content_type.contains("octet-stream") {
+        // Skip fields that aren't a zip upload under the plugin zip policy
+        let content_type = field.content_type().map(|m| m.to_string());
+        let policy = UploadContext::PluginZip.policy();
+        if !policy.allows_mime(content_type.as_deref()) {
             continue;
         }
+        let filename = field.content_disposition().get_filename().map(|f| f.to_string());
+        if let Err(e) = policy.validate(filename.as_deref(), content_type.as_deref(), 0) {
+            warn!("Invalid plugin zip upload: {}", e);
+            return HttpResponse::BadRequest().json(format!("Invalid plugin zip: {e}"));
+        }
 
         while let Some(chunk) = field.next().await {
             let data = match chunk {
@@ -1011,11 +1251,9 @@ pub async fn install_plugin_from_zip(
                 }
             };
 
-            if zip_data.len() + data.len() > MAX_ZIP_SIZE {
-                return HttpResponse::BadRequest().json(format!(
-                    "Zip file too large. Maximum size is {} MB",
-                    MAX_ZIP_SIZE / (1024 * 1024)
-                ));
+            if let Err(e) = policy.check_size(zip_data.len() + data.len()) {
+                warn!("Zip file too large: {}", e);
+                return HttpResponse::BadRequest().json(e.to_string());
             }
 
             zip_data.extend_from_slice(&data);
@@ -1026,6 +1264,16 @@ pub async fn install_plugin_from_zip(
         return HttpResponse::BadRequest().json("No zip file received");
     }
 
+    // Reject infected archives (no-op when clamd isn't configured)
+    match file_validation::ensure_not_malware(&zip_data, "plugin.zip").await {
+        Ok(()) => {}
+        Err(e @ file_validation::FileValidationError::ScanFailed(_)) => {
+            error!("Plugin zip malware scan failed: {}", e);
+            return HttpResponse::ServiceUnavailable().json(e.to_string());
+        }
+        Err(e) => return HttpResponse::BadRequest().json(e.to_string()),
+    }
+
     // Extract the zip file
     let cursor = std::io::Cursor::new(&zip_data);
     let mut archive = match zip::ZipArchive::new(cursor) {
@@ -1060,17 +1308,21 @@ pub async fn install_plugin_from_zip(
         }
     };
 
+    if let Err(e) = validate_manifest(&manifest) {
+        return e;
+    }
     // Validate plugin name
     let name = match validate_plugin_name(&manifest.name) {
         Ok(n) => n,
         Err(e) => return e,
     };
+    if let Err(e) = validate_event_subscriptions(&manifest) {
+        return e;
+    }
 
     // Check if plugin already exists
     if plugin_repo::get_plugin_by_name(&mut conn, &name).is_ok() {
-        return HttpResponse::Conflict().json(format!(
-            "Plugin '{name}' already exists. Uninstall it first or use the update endpoint."
-        ));
+        return plugin_name_conflict(&name);
     }
 
     // Read bundle.js if present
@@ -1086,6 +1338,29 @@ pub async fn install_plugin_from_zip(
         Err(_) => None,
     };
 
+    // Verify the signature; only signed plugins are official
+    let signature = match archive.by_name(signing::SIGNATURE_FILE) {
+        Ok(mut file) => {
+            let mut content = String::new();
+            if std::io::Read::read_to_string(&mut file, &mut content).is_err() {
+                return HttpResponse::BadRequest().json("Failed to read manifest.sig");
+            }
+            Some(content)
+        }
+        Err(_) => None,
+    };
+    let check = signing::verify_plugin(
+        &signing::PublisherKeys::from_env(),
+        manifest_content.as_bytes(),
+        bundle_data.as_deref(),
+        signature.as_deref(),
+    );
+    if check == signing::SignatureCheck::Invalid {
+        warn!("Rejected plugin {} with an invalid signature", name);
+        return HttpResponse::BadRequest().json("manifest.sig does not match any trusted publisher");
+    }
+    let (trust_level, verified_publisher) = check.trust();
+
     // Create the plugin
     let manifest_json = match serde_json::to_value(&manifest) {
         Ok(v) => v,
@@ -1096,27 +1371,45 @@ pub async fn install_plugin_from_zip(
     };
 
     let new_plugin = NewPlugin {
-        name,
+        name: name.clone(),
         display_name: manifest.display_name.clone(),
         version: manifest.version.clone(),
         description: manifest.description.clone(),
         manifest: manifest_json,
         enabled: true,
-        trust_level: "community".to_string(), // Uploaded plugins start as community
+        trust_level: trust_level.to_string(),
         installed_by: Uuid::parse_str(&claims.sub).ok(),
         source: "uploaded".to_string(),
+        verified_publisher: verified_publisher.clone(),
     };
 
-    let plugin = match plugin_repo::create_plugin(&mut conn, new_plugin) {
+    // The plugin and its install activity are written together; the bundle
+    // goes to disk only once they've committed
+    let has_bundle = bundle_data.is_some();
+    let user_uuid = Uuid::parse_str(&claims.sub).ok();
+    let result = repository::with_transaction(&mut conn, |conn| {
+        let plugin = plugin_repo::create_plugin(conn, new_plugin)?;
+        plugin_repo::log_plugin_activity(
+            conn,
+            plugin.id,
+            "installed".to_string(),
+            Some(serde_json::json!({
+                "version": manifest.version,
+                "source": "zip_upload",
+                "has_bundle": has_bundle,
+                "trust_level": trust_level,
+                "verified_publisher": verified_publisher,
+            })),
+            user_uuid,
+        )?;
+        Ok::<_, DieselError>(plugin)
+    });
+    let plugin = match result {
         Ok(p) => p,
-        Err(e) => {
-            error!("Failed to create plugin: {}", e);
-            return HttpResponse::InternalServerError().json("Failed to create plugin");
-        }
+        Err(e) => return create_plugin_error(e, &name),
     };
 
     // Store bundle if present
-    let has_bundle = bundle_data.is_some();
     if let Some(data) = bundle_data {
         // Validate bundle
         let content = String::from_utf8_lossy(&data);
@@ -1130,7 +1423,7 @@ pub async fn install_plugin_from_zip(
         let hash = hex::encode(context.finish().as_ref());
 
         // Store bundle
-        let bundle_path = get_bundle_path(plugin.uuid);
+        let bundle_path = get_bundle_path(&paths, plugin.uuid);
         if let Some(parent) = bundle_path.parent() {
             if let Err(e) = fs::create_dir_all(parent).await {
                 error!("Failed to create plugin directory: {}", e);
@@ -1150,20 +1443,6 @@ pub async fn install_plugin_from_zip(
         }
     }
 
-    // Log activity
-    let user_uuid = Uuid::parse_str(&claims.sub).ok();
-    let _ = plugin_repo::log_plugin_activity(
-        &mut conn,
-        plugin.id,
-        "installed".to_string(),
-        Some(serde_json::json!({
-            "version": manifest.version,
-            "source": "zip_upload",
-            "has_bundle": has_bundle,
-        })),
-        user_uuid,
-    );
-
     info!(
         "Plugin installed from zip: {} v{} by {}",
         manifest.name, manifest.version, claims.sub
@@ -1178,3 +1457,161 @@ pub async fn install_plugin_from_zip(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NewPlugin, UserRole};
+    use crate::test_helpers::{create_test_claims, setup_test_pool, TestFixtures};
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn bundle_path_honors_a_custom_uploads_dir() {
+        let paths = PluginPaths {
+            uploads_dir: PathBuf::from("/home/dev/nosdesk/uploads"),
+            plugins_dir: PathBuf::from("/home/dev/nosdesk/plugins"),
+        };
+        let plugin_uuid = Uuid::new_v4();
+        assert_eq!(
+            get_bundle_path(&paths, plugin_uuid),
+            PathBuf::from(format!("/home/dev/nosdesk/uploads/plugins/{plugin_uuid}/bundle.js"))
+        );
+    }
+
+    #[actix_web::test]
+    async fn duplicate_name_insert_is_a_conflict() {
+        let mut conn = setup_test_pool().get().unwrap();
+        let new_plugin = || NewPlugin {
+            name: "racing-install".to_string(),
+            display_name: "Racing install".to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            manifest: serde_json::json!({}),
+            enabled: true,
+            trust_level: signing::TRUST_COMMUNITY.to_string(),
+            installed_by: None,
+            source: "uploaded".to_string(),
+            verified_publisher: None,
+        };
+        plugin_repo::create_plugin(&mut conn, new_plugin()).unwrap();
+
+        // What the losing install of a race gets past the name lookup
+        let err = plugin_repo::create_plugin(&mut conn, new_plugin()).unwrap_err();
+        let resp = create_plugin_error(err, "racing-install");
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        let resp = create_plugin_error(DieselError::RollbackTransaction, "racing-install");
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[actix_web::test]
+    async fn changing_the_manifest_drops_signed_trust() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let admin = TestFixtures::create_user(&mut conn, "manifestadmin", UserRole::Admin);
+        let manifest = serde_json::json!({
+            "name": "signed-widget",
+            "displayName": "Signed widget",
+            "version": "1.0.0",
+        });
+        let plugin = plugin_repo::create_plugin(&mut conn, NewPlugin {
+            name: "signed-widget".to_string(),
+            display_name: "Signed widget".to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            manifest: serde_json::to_value(serde_json::from_value::<crate::models::PluginManifest>(manifest.clone()).unwrap()).unwrap(),
+            enabled: true,
+            trust_level: signing::TRUST_OFFICIAL.to_string(),
+            installed_by: None,
+            source: "uploaded".to_string(),
+            verified_publisher: Some("acme".to_string()),
+        })
+        .unwrap();
+        drop(conn);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(FeatureFlags::default()))
+                .route("/plugins/{uuid}", web::put().to(update_plugin)),
+        )
+        .await;
+        let put = |body: serde_json::Value| {
+            let req = test::TestRequest::put()
+                .uri(&format!("/plugins/{}", plugin.uuid))
+                .set_json(body)
+                .to_request();
+            req.extensions_mut().insert(create_test_claims(&admin));
+            req
+        };
+
+        // Toggling or re-sending the same manifest keeps the signature's trust
+        let resp = test::call_service(&app, put(serde_json::json!({ "enabled": false, "manifest": manifest }))).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let mut conn = pool.get().unwrap();
+        let unchanged = plugin_repo::get_plugin_by_uuid(&mut conn, plugin.uuid).unwrap();
+        assert_eq!(unchanged.trust_level, signing::TRUST_OFFICIAL);
+        assert_eq!(unchanged.verified_publisher.as_deref(), Some("acme"));
+
+        let mut changed = manifest.clone();
+        changed["permissions"] = serde_json::json!(["tickets:write"]);
+        let resp = test::call_service(&app, put(serde_json::json!({ "manifest": changed }))).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let downgraded = plugin_repo::get_plugin_by_uuid(&mut conn, plugin.uuid).unwrap();
+        assert_eq!(downgraded.trust_level, signing::TRUST_COMMUNITY);
+        assert_eq!(downgraded.verified_publisher, None);
+    }
+
+    #[actix_web::test]
+    async fn uninstall_removes_bundle_and_settings() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let admin = TestFixtures::create_user(&mut conn, "uninstalladmin", UserRole::Admin);
+        let plugin = plugin_repo::create_plugin(&mut conn, NewPlugin {
+            name: "uninstall-cleanup".to_string(),
+            display_name: "Uninstall cleanup".to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            manifest: serde_json::json!({}),
+            enabled: true,
+            trust_level: signing::TRUST_COMMUNITY.to_string(),
+            installed_by: None,
+            source: "uploaded".to_string(),
+            verified_publisher: None,
+        })
+        .unwrap();
+        plugin_repo::set_plugin_setting(&mut conn, plugin.id, "token".to_string(), Some(serde_json::json!("x")), true)
+            .unwrap();
+        drop(conn);
+
+        let paths = PluginPaths {
+            uploads_dir: std::env::temp_dir().join(format!("nosdesk-uploads-{}", Uuid::new_v4())),
+            plugins_dir: std::env::temp_dir(),
+        };
+        let bundle_path = get_bundle_path(&paths, plugin.uuid);
+        std::fs::create_dir_all(bundle_path.parent().unwrap()).unwrap();
+        std::fs::write(&bundle_path, "export default {}").unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(FeatureFlags::default()))
+                .app_data(web::Data::new(paths.clone()))
+                .route("/plugins/{uuid}", web::delete().to(uninstall_plugin)),
+        )
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/plugins/{}", plugin.uuid))
+            .to_request();
+        req.extensions_mut().insert(create_test_claims(&admin));
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        assert!(!bundle_path.exists());
+        let mut conn = pool.get().unwrap();
+        assert!(plugin_repo::get_plugin_settings(&mut conn, plugin.id).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&paths.uploads_dir);
+    }
+}
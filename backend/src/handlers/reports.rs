@@ -0,0 +1,108 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::db::Pool;
+use crate::services::reporting;
+use crate::utils::permissions::Permission;
+use crate::utils::rbac::require_permission;
+
+/// Longest range a single report may cover
+const MAX_RANGE_DAYS: i64 = 366;
+
+/// Query parameters for the ticket report
+#[derive(Debug, Deserialize)]
+pub struct TicketReportQuery {
+    /// First day included (YYYY-MM-DD), defaults to six days before `to`
+    pub from: Option<NaiveDate>,
+    /// Last day included (YYYY-MM-DD), defaults to today
+    pub to: Option<NaiveDate>,
+}
+
+/// Ticket metrics for a date range (requires view_reports)
+pub async fn get_ticket_report(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    query: web::Query<TicketReportQuery>,
+) -> impl Responder {
+    if let Err(e) = require_permission(&req, Permission::ViewReports) {
+        return e;
+    }
+
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - Duration::days(6));
+    if from > to {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Invalid range",
+            "message": "from must not be after to"
+        }));
+    }
+    if (to - from).num_days() >= MAX_RANGE_DAYS {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Invalid range",
+            "message": format!("Reports cover at most {MAX_RANGE_DAYS} days")
+        }));
+    }
+
+    // Whole days: from midnight on `from` up to midnight after `to`
+    let start = from.and_hms_opt(0, 0, 0).unwrap_or_default();
+    let end = (to + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap_or_default();
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    match reporting::ticket_report(&mut conn, start, end) {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to build ticket report");
+            HttpResponse::InternalServerError().json("Failed to build ticket report")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{create_test_claims, setup_test_pool, TestFixtures};
+    use actix_web::dev::Service;
+    use actix_web::{http::StatusCode, test, App, HttpMessage};
+
+    #[actix_web::test]
+    async fn report_is_limited_to_technicians_and_validates_range() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let tech = TestFixtures::create_user(&mut conn, "reporttech", UserRole::Technician);
+        let user = TestFixtures::create_user(&mut conn, "reportuser", UserRole::User);
+        drop(conn);
+
+        for (claims, uri, expected) in [
+            (create_test_claims(&user), "/reports/tickets", StatusCode::FORBIDDEN),
+            (create_test_claims(&tech), "/reports/tickets?from=2001-03-10&to=2001-03-01", StatusCode::BAD_REQUEST),
+            (create_test_claims(&tech), "/reports/tickets?from=2001-03-01&to=2001-03-07", StatusCode::OK),
+        ] {
+            let app = test::init_service(
+                App::new()
+                    .app_data(web::Data::new(pool.clone()))
+                    .wrap_fn(move |req, srv| {
+                        req.extensions_mut().insert(claims.clone());
+                        srv.call(req)
+                    })
+                    .route("/reports/tickets", web::get().to(get_ticket_report)),
+            )
+            .await;
+
+            let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+            assert_eq!(resp.status(), expected, "{uri}");
+            if expected == StatusCode::OK {
+                let body: serde_json::Value = test::read_body_json(resp).await;
+                assert_eq!(body["from"], "2001-03-01T00:00:00");
+                assert_eq!(body["to"], "2001-03-08T00:00:00");
+                assert!(body["open_by_priority"]["high"].is_number());
+            }
+        }
+    }
+}
@@ -5,62 +5,175 @@ use serde_json::json;
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
+use crate::extractors::AuthContext;
 use crate::models::Claims;
-use crate::services::search::{SearchQuery, SearchService};
+use crate::services::search::{
+    recent, GroupedSearchQuery, SearchError, SearchQuery, SearchService, SearchSuggestions, SuggestionsQuery,
+};
+
+/// Completions returned per suggestion request
+const SUGGESTION_LIMIT: usize = 5;
 
 /// Search across all indexed entities
 ///
+/// Results the user isn't allowed to see are left out.
+///
 /// GET /api/search?q=<query>&limit=20&types=ticket,documentation
 pub async fn search(
     query: web::Query<SearchQuery>,
+    pool: web::Data<crate::db::Pool>,
     search_service: web::Data<Arc<SearchService>>,
-    req: HttpRequest,
+    auth: AuthContext,
 ) -> impl Responder {
-    // Verify authentication
-    let claims = match req.extensions().get::<Claims>() {
-        Some(claims) => claims.clone(),
-        None => return HttpResponse::Unauthorized().json(json!({"error": "Authentication required"})),
-    };
-
+    // The query text is logged by the search service's query log, which
+    // may hash it
     debug!(
-        user = %claims.sub,
-        query = %query.q,
-        limit = query.limit,
+        user = %auth.user_uuid,
+        limit = ?query.limit,
         types = ?query.types,
         "Search request"
     );
 
-    // Validate query
-    let query_str = query.q.trim();
+    let query_str = match validate_query(&query.q) {
+        Ok(q) => q,
+        Err(response) => return response,
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!(error = ?e, "Database connection error");
+            return HttpResponse::InternalServerError().json(json!({
+                "error": "Database connection error"
+            }));
+        }
+    };
+
+    // Execute search
+    match search_service.search_visible(&mut conn, &auth, &query) {
+        Ok(response) => {
+            tokio::spawn(recent::record(auth.user_uuid, query_str.to_string()));
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => search_error_response(e, "Search failed"),
+    }
+}
+
+/// Search across all indexed entities, grouped by entity type
+///
+/// Returns the best few results of each type with per-type match counts,
+/// for rendering sections (e.g. in the command palette) directly.
+///
+/// GET /api/search/grouped?q=<query>&per_group=5&types=ticket,device
+pub async fn search_grouped(
+    query: web::Query<GroupedSearchQuery>,
+    pool: web::Data<crate::db::Pool>,
+    search_service: web::Data<Arc<SearchService>>,
+    auth: AuthContext,
+) -> impl Responder {
+    let query_str = match validate_query(&query.q) {
+        Ok(q) => q,
+        Err(response) => return response,
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!(error = ?e, "Database connection error");
+            return HttpResponse::InternalServerError().json(json!({
+                "error": "Database connection error"
+            }));
+        }
+    };
+
+    match search_service.search_grouped(&mut conn, &auth, &query) {
+        Ok(response) => {
+            tokio::spawn(recent::record(auth.user_uuid, query_str.to_string()));
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => search_error_response(e, "Grouped search failed"),
+    }
+}
+
+/// Response for a failed search: 400 when the query itself is at fault,
+/// 500 otherwise
+fn search_error_response(e: SearchError, context: &str) -> HttpResponse {
+    match e {
+        SearchError::ParseError(_) => HttpResponse::BadRequest().json(json!({
+            "error": e.to_string()
+        })),
+        e => {
+            error!(error = ?e, "{context}");
+            HttpResponse::InternalServerError().json(json!({
+                "error": "Search failed",
+                "details": e.to_string()
+            }))
+        }
+    }
+}
+
+/// Check a search query isn't empty or too long, returning it trimmed
+fn validate_query(q: &str) -> Result<&str, HttpResponse> {
+    let query_str = q.trim();
     if query_str.is_empty() {
-        return HttpResponse::BadRequest().json(json!({
+        return Err(HttpResponse::BadRequest().json(json!({
             "error": "Search query cannot be empty"
-        }));
+        })));
     }
 
     if query_str.len() > 500 {
-        return HttpResponse::BadRequest().json(json!({
+        return Err(HttpResponse::BadRequest().json(json!({
             "error": "Search query too long (max 500 characters)"
-        }));
+        })));
     }
 
-    // Execute search
-    match search_service.search(&query.into_inner()) {
-        Ok(response) => {
-            debug!(
-                query = %response.query,
-                results = response.results.len(),
-                total = response.total,
-                took_ms = response.took_ms,
-                "Search completed"
-            );
-            HttpResponse::Ok().json(response)
+    Ok(query_str)
+}
+
+/// Suggest searches for a partially typed query: the user's matching recent
+/// searches and completions of the last word from the index
+///
+/// GET /api/search/suggestions?q=<partial query>
+pub async fn get_suggestions(
+    query: web::Query<SuggestionsQuery>,
+    pool: web::Data<crate::db::Pool>,
+    search_service: web::Data<Arc<SearchService>>,
+    auth: AuthContext,
+) -> impl Responder {
+    let history = recent::list(&auth.user_uuid).await;
+    let recent = recent::matching(&history, &query.q, recent::MAX_RECENT_SEARCHES);
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!(error = ?e, "Database connection error");
+            return HttpResponse::InternalServerError().json(json!({
+                "error": "Database connection error"
+            }));
         }
+    };
+
+    let completions = match search_service.suggest_completions(&mut conn, &auth, &query.q, SUGGESTION_LIMIT) {
+        Ok(completions) => completions,
         Err(e) => {
-            error!(error = ?e, "Search failed");
+            warn!(error = ?e, "Failed to complete search terms");
+            Vec::new()
+        }
+    };
+
+    HttpResponse::Ok().json(SearchSuggestions { recent, completions })
+}
+
+/// Clear the current user's recent searches
+///
+/// DELETE /api/search/recent
+pub async fn clear_recent_searches(auth: AuthContext) -> impl Responder {
+    match recent::clear(&auth.user_uuid).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            error!(error = %e, "Failed to clear recent searches");
             HttpResponse::InternalServerError().json(json!({
-                "error": "Search failed",
-                "details": e.to_string()
+                "error": "Failed to clear recent searches"
             }))
         }
     }
@@ -141,6 +254,10 @@ pub async fn rebuild_index(
                 }
             }))
         }
+        // Another rebuild claimed the index since the check above
+        Err(SearchError::IndexBusy) => HttpResponse::Conflict().json(json!({
+            "error": "Index rebuild already in progress"
+        })),
         Err(e) => {
             error!(error = ?e, "Index rebuild failed");
             HttpResponse::InternalServerError().json(json!({
@@ -181,3 +298,88 @@ pub async fn get_stats(
         }
     }
 }
+
+/// Start rebuilding the search index in the background (admin only)
+///
+/// POST /api/admin/search/reindex
+pub async fn start_reindex(
+    pool: web::Data<crate::db::Pool>,
+    search_service: web::Data<Arc<SearchService>>,
+    req: HttpRequest,
+) -> impl Responder {
+    // Verify authentication and admin role
+    let claims = match req.extensions().get::<Claims>() {
+        Some(claims) => claims.clone(),
+        None => return HttpResponse::Unauthorized().json(json!({"error": "Authentication required"})),
+    };
+
+    if claims.role != "admin" {
+        warn!(user = %claims.sub, "Non-admin user attempted to reindex search");
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Admin access required"
+        }));
+    }
+
+    if !search_service.start_rebuild(pool.get_ref().clone()) {
+        return HttpResponse::Conflict().json(json!({
+            "error": "Index rebuild already in progress"
+        }));
+    }
+
+    info!(user = %claims.sub, "Started background search index rebuild");
+    HttpResponse::Accepted().json(search_service.rebuild_status())
+}
+
+/// Get progress of the current or most recent reindex (admin only)
+///
+/// GET /api/admin/search/reindex/status
+pub async fn get_reindex_status(
+    search_service: web::Data<Arc<SearchService>>,
+    req: HttpRequest,
+) -> impl Responder {
+    // Verify authentication and admin role
+    let claims = match req.extensions().get::<Claims>() {
+        Some(claims) => claims.clone(),
+        None => return HttpResponse::Unauthorized().json(json!({"error": "Authentication required"})),
+    };
+
+    if claims.role != "admin" {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Admin access required"
+        }));
+    }
+
+    HttpResponse::Ok().json(search_service.rebuild_status())
+}
+
+/// Query parameters for search insights
+#[derive(Debug, serde::Deserialize)]
+pub struct SearchInsightsQuery {
+    /// Zero-result queries to return (default 20, at most 100)
+    pub limit: Option<usize>,
+}
+
+/// Search activity since startup: search and slow search counts, and the
+/// most frequent queries that found nothing (admin only)
+///
+/// GET /api/admin/search/insights?limit=20
+pub async fn get_search_insights(
+    query: web::Query<SearchInsightsQuery>,
+    search_service: web::Data<Arc<SearchService>>,
+    req: HttpRequest,
+) -> impl Responder {
+    // Verify authentication and admin role
+    let claims = match req.extensions().get::<Claims>() {
+        Some(claims) => claims.clone(),
+        None => return HttpResponse::Unauthorized().json(json!({"error": "Authentication required"})),
+    };
+
+    if claims.role != "admin" {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Admin access required"
+        }));
+    }
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    HttpResponse::Ok().json(search_service.query_log().insights(limit))
+}
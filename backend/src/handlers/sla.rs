@@ -0,0 +1,65 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+
+use crate::db::Pool;
+use crate::models::NewSlaTarget;
+use crate::repository;
+use crate::utils::permissions::Permission;
+use crate::utils::rbac::require_permission;
+
+/// List SLA targets per priority (requires manage_sla_targets)
+pub async fn get_sla_targets(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+) -> impl Responder {
+    if let Err(e) = require_permission(&req, Permission::ManageSlaTargets) {
+        return e;
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    match repository::sla::list_targets(&mut conn) {
+        Ok(targets) => HttpResponse::Ok().json(targets),
+        Err(_) => HttpResponse::InternalServerError().json("Failed to get SLA targets"),
+    }
+}
+
+/// Set the SLA targets for one priority (requires manage_sla_targets). A null target turns
+/// that metric off for the priority.
+pub async fn update_sla_target(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    body: web::Json<NewSlaTarget>,
+) -> impl Responder {
+    if let Err(e) = require_permission(&req, Permission::ManageSlaTargets) {
+        return e;
+    }
+
+    let target = body.into_inner();
+    if [target.first_response_minutes, target.resolution_minutes]
+        .into_iter()
+        .flatten()
+        .any(|minutes| minutes <= 0)
+    {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Invalid target",
+            "message": "SLA targets must be a positive number of minutes"
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    match repository::sla::upsert_target(&mut conn, &target) {
+        Ok(target) => HttpResponse::Ok().json(target),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to save SLA target");
+            HttpResponse::InternalServerError().json("Failed to save SLA target")
+        }
+    }
+}
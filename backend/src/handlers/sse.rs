@@ -113,6 +113,26 @@ pub enum TicketEvent {
         count: usize,
         timestamp: chrono::DateTime<chrono::Utc>,
     },
+    /// A user started editing a collaborative document ("ticket-N" or "doc-N")
+    EditorJoined {
+        document_id: String,
+        user_uuid: String,
+        name: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// A user's last editing session on a document ended or timed out
+    EditorLeft {
+        document_id: String,
+        user_uuid: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// An editor moved their cursor or selection
+    EditorCursorMoved {
+        document_id: String,
+        user_uuid: String,
+        cursor: serde_json::Value,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
     UserUpdated {
         user_uuid: String,
         field: String,
@@ -138,6 +158,15 @@ pub enum TicketEvent {
         notification: serde_json::Value,
         timestamp: chrono::DateTime<chrono::Utc>,
     },
+    /// Notifications marked read (targeted to specific user, so their other
+    /// sessions can update the unread badge)
+    NotificationsRead {
+        recipient_uuid: String,
+        /// None when all of the user's notifications were marked read
+        notification_ids: Option<Vec<i32>>,
+        unread_count: i64,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
 }
 
 // Client connection info
@@ -298,11 +327,15 @@ impl Stream for SseStream {
                     TicketEvent::DocumentationCreated { .. } => "documentation-created",
                     TicketEvent::DocumentationUpdated { .. } => "documentation-updated",
                     TicketEvent::ViewerCountChanged { .. } => "viewer-count-changed",
+                    TicketEvent::EditorJoined { .. } => "editor-joined",
+                    TicketEvent::EditorLeft { .. } => "editor-left",
+                    TicketEvent::EditorCursorMoved { .. } => "editor-cursor-moved",
                     TicketEvent::UserUpdated { .. } => "user-updated",
                     TicketEvent::UserCreated { .. } => "user-created",
                     TicketEvent::UserDeleted { .. } => "user-deleted",
                     TicketEvent::Heartbeat { .. } => "heartbeat",
                     TicketEvent::NotificationReceived { .. } => "notification-received",
+                    TicketEvent::NotificationsRead { .. } => "notifications-read",
                 };
 
                 // Serialize event data
@@ -0,0 +1,454 @@
+//! Ticket list export
+//!
+//! Streams every ticket matching the ticket list filters as CSV or JSON.
+//! Rows are read in ID order a batch at a time and written out as they
+//! arrive, so large exports don't buffer in memory. PDF exports are rendered
+//! whole, with the site's letterhead, and capped at `PDF_MAX_ROWS` tickets.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use bytes::Bytes;
+use futures::stream;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::error;
+
+use crate::db::Pool;
+use crate::extractors::AuthContext;
+use crate::handlers::tickets::PaginationParams;
+use crate::repository::ticket_query::{TicketExport, TicketExportRow};
+use crate::utils::email_branding::get_pdf_branding;
+use crate::utils::pdf::render_text_pdf;
+use crate::utils::rbac::require_scope;
+
+/// Rows fetched per database round trip
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+/// Column headers, in the order `csv_row` writes them
+const CSV_HEADER: &str = "id,title,status,priority,requester,assignee,created_at,closed_at\r\n";
+
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Most tickets listed in a PDF export, which is buffered to render
+const PDF_MAX_ROWS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportFormatParam {
+    /// `csv` (default), `json` or `pdf`
+    format: Option<String>,
+}
+
+impl ExportFormat {
+    fn parse(format: Option<&str>) -> Option<Self> {
+        match format.map(str::to_ascii_lowercase).as_deref() {
+            None | Some("csv") => Some(Self::Csv),
+            Some("json") => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Json => "application/json",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Quote a CSV field when needed, and defuse values a spreadsheet would
+/// otherwise evaluate as a formula
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn csv_row(row: &TicketExportRow) -> String {
+    let enum_str = |value: serde_json::Value| value.as_str().unwrap_or_default().to_string();
+    let fields = [
+        row.id.to_string(),
+        csv_field(&row.title),
+        enum_str(json!(row.status)),
+        enum_str(json!(row.priority)),
+        csv_field(row.requester.as_deref().unwrap_or_default()),
+        csv_field(row.assignee.as_deref().unwrap_or_default()),
+        row.created_at.format(DATE_FORMAT).to_string(),
+        row.closed_at.map(|d| d.format(DATE_FORMAT).to_string()).unwrap_or_default(),
+    ];
+    format!("{}\r\n", fields.join(","))
+}
+
+/// PDF body text: a heading line per ticket with its details beneath
+fn pdf_body(rows: &[TicketExportRow], truncated: bool) -> String {
+    let enum_str = |value: serde_json::Value| value.as_str().unwrap_or_default().to_string();
+    let mut body = String::new();
+    for row in rows {
+        let closed = row
+            .closed_at
+            .map(|d| format!(" | Closed {}", d.format(DATE_FORMAT)))
+            .unwrap_or_default();
+        body.push_str(&format!("#{} {}\n", row.id, row.title));
+        body.push_str(&format!(
+            "    {} | {} | Requester: {} | Assignee: {} | Created {}{closed}\n\n",
+            enum_str(json!(row.status)),
+            enum_str(json!(row.priority)),
+            row.requester.as_deref().unwrap_or("-"),
+            row.assignee.as_deref().unwrap_or("-"),
+            row.created_at.format(DATE_FORMAT),
+        ));
+    }
+    if truncated {
+        body.push_str(&format!("Only the first {PDF_MAX_ROWS} tickets are listed; export as CSV for all of them.\n"));
+    }
+    body
+}
+
+/// Render the export as a branded PDF
+async fn pdf_export(pool: Pool, export: Arc<TicketExport>) -> HttpResponse {
+    let loaded = web::block(move || {
+        let mut conn = pool.get().map_err(|e| e.to_string())?;
+        let mut rows: Vec<TicketExportRow> = Vec::new();
+        let mut after_id = None;
+        // One row past the cap tells us the list was cut short
+        while rows.len() <= PDF_MAX_ROWS {
+            let batch = export
+                .next_batch(&mut conn, after_id, EXPORT_BATCH_SIZE)
+                .map_err(|e| e.to_string())?;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            after_id = Some(last.id);
+            rows.extend(batch);
+        }
+
+        let base_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+        let branding = get_pdf_branding(&mut conn, &base_url);
+        Ok::<_, String>((rows, branding))
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+
+    let (mut rows, branding) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            error!(error = %e, "Ticket PDF export failed");
+            return HttpResponse::InternalServerError().json("Database connection error");
+        }
+    };
+    let truncated = rows.len() > PDF_MAX_ROWS;
+    rows.truncate(PDF_MAX_ROWS);
+
+    match render_text_pdf("Tickets", &pdf_body(&rows, truncated), Some(branding)).await {
+        Ok(Some(bytes)) => {
+            let filename = format!("tickets-{}.pdf", chrono::Utc::now().format("%Y%m%d"));
+            HttpResponse::Ok()
+                .content_type("application/pdf")
+                .insert_header(("Content-Disposition", format!("attachment; filename=\"{filename}\"")))
+                .insert_header(("Cache-Control", "no-store"))
+                .body(bytes)
+        }
+        Ok(None) => HttpResponse::ServiceUnavailable().json(json!({
+            "error": "PDF unavailable",
+            "message": "PDF rendering is not available on this server"
+        })),
+        Err(e) => {
+            error!(error = %e, "Failed to render ticket PDF export");
+            HttpResponse::InternalServerError().json("Failed to render PDF")
+        }
+    }
+}
+
+/// Where the response stream is
+enum Stage {
+    Start,
+    Rows { after_id: Option<i32>, wrote_row: bool },
+    Done,
+}
+
+struct ExportStream {
+    pool: Pool,
+    export: Arc<TicketExport>,
+    format: ExportFormat,
+    stage: Stage,
+}
+
+impl ExportStream {
+    /// Produce the next chunk of the response body, or `None` when finished
+    async fn next_chunk(mut self) -> Option<(Result<Bytes, actix_web::Error>, Self)> {
+        match self.stage {
+            Stage::Start => {
+                self.stage = Stage::Rows { after_id: None, wrote_row: false };
+                let opening = match self.format {
+                    ExportFormat::Csv => CSV_HEADER,
+                    ExportFormat::Json => "[",
+                };
+                Some((Ok(Bytes::from_static(opening.as_bytes())), self))
+            }
+            Stage::Rows { after_id, wrote_row } => {
+                let pool = self.pool.clone();
+                let export = self.export.clone();
+                let batch = web::block(move || {
+                    let mut conn = pool.get().map_err(|e| e.to_string())?;
+                    export
+                        .next_batch(&mut conn, after_id, EXPORT_BATCH_SIZE)
+                        .map_err(|e| e.to_string())
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result);
+
+                let rows = match batch {
+                    Ok(rows) => rows,
+                    Err(e) => {
+                        // Headers are already sent; ending the stream with an
+                        // error aborts the download instead of truncating it
+                        error!(error = %e, "Ticket export failed");
+                        self.stage = Stage::Done;
+                        return Some((Err(actix_web::error::ErrorInternalServerError(e)), self));
+                    }
+                };
+
+                let Some(last) = rows.last() else {
+                    self.stage = Stage::Done;
+                    return match self.format {
+                        ExportFormat::Csv => None,
+                        ExportFormat::Json => Some((Ok(Bytes::from_static(b"]")), self)),
+                    };
+                };
+                self.stage = Stage::Rows { after_id: Some(last.id), wrote_row: true };
+
+                let mut chunk = String::new();
+                for (i, row) in rows.iter().enumerate() {
+                    match self.format {
+                        ExportFormat::Csv => chunk.push_str(&csv_row(row)),
+                        ExportFormat::Json => {
+                            if wrote_row || i > 0 {
+                                chunk.push(',');
+                            }
+                            chunk.push_str(&serde_json::to_string(row).unwrap_or_default());
+                        }
+                    }
+                }
+                Some((Ok(Bytes::from(chunk)), self))
+            }
+            Stage::Done => None,
+        }
+    }
+}
+
+/// Export the filtered ticket list. Takes the same filters as
+/// `/tickets/paginated`; pagination and sorting are ignored.
+pub async fn export_tickets(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    filters: web::Query<PaginationParams>,
+    format: web::Query<ExportFormatParam>,
+    auth: AuthContext,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:read") {
+        return e;
+    }
+
+    let pdf = format.format.as_deref().is_some_and(|f| f.eq_ignore_ascii_case("pdf"));
+    let format = ExportFormat::parse(format.format.as_deref());
+    if format.is_none() && !pdf {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Invalid format",
+            "message": "format must be csv, json or pdf"
+        }));
+    }
+
+    // Visibility is resolved once up front; batches reuse it
+    let query = filters.filtered_query(&auth);
+    let pool = pool.get_ref().clone();
+    let export_pool = pool.clone();
+    let export = web::block(move || {
+        let mut conn = export_pool.get().map_err(|e| e.to_string())?;
+        Ok::<_, String>(query.into_export(&mut conn))
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+
+    let export = match export {
+        Ok(export) => Arc::new(export),
+        Err(e) => {
+            error!(error = %e, "Failed to start ticket export");
+            return HttpResponse::InternalServerError().json("Database connection error");
+        }
+    };
+
+    // Only PDF exports get here without a streamed format
+    let Some(format) = format else {
+        return pdf_export(pool, export).await;
+    };
+
+    let body = stream::unfold(
+        ExportStream { pool, export, format, stage: Stage::Start },
+        ExportStream::next_chunk,
+    );
+
+    let filename = format!("tickets-{}.{}", chrono::Utc::now().format("%Y%m%d"), format.extension());
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{filename}\"")))
+        .insert_header(("Cache-Control", "no-store"))
+        .streaming(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{create_test_claims, setup_test_pool, TestFixtures};
+    use actix_web::dev::Service;
+    use actix_web::{http::StatusCode, test, App, HttpMessage};
+
+    async fn export(pool: Pool, claims: crate::models::Claims, uri: &str) -> (StatusCode, String) {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .wrap_fn(move |req, srv| {
+                    req.extensions_mut().insert(claims.clone());
+                    srv.call(req)
+                })
+                .route("/tickets/export", web::get().to(export_tickets)),
+        )
+        .await;
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        let status = resp.status();
+        let body = test::read_body(resp).await;
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[actix_web::test]
+    async fn csv_export_has_header_and_filtered_rows() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let requester = TestFixtures::create_user(&mut conn, "Export Requester", UserRole::User);
+        let tech = TestFixtures::create_user(&mut conn, "exporttech", UserRole::Technician);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Printer, \"jammed\"", Some(requester.uuid), None);
+        let other = TestFixtures::create_ticket(&mut conn, "Other export ticket", Some(requester.uuid), None);
+        TestFixtures::create_ticket(&mut conn, "Someone else's ticket", Some(tech.uuid), None);
+        drop(conn);
+
+        let uri = format!("/tickets/export?requester={}", requester.uuid);
+        let (status, body) = export(pool.clone(), create_test_claims(&tech), &uri).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let lines: Vec<&str> = body.split("\r\n").filter(|l| !l.is_empty()).collect();
+        assert_eq!(lines[0], CSV_HEADER.trim_end());
+        assert_eq!(lines.len(), 3);
+
+        let created = ticket.created_at.format(DATE_FORMAT);
+        assert_eq!(
+            lines[1],
+            format!("{},\"Printer, \"\"jammed\"\"\",open,medium,Export Requester,,{created},", ticket.id)
+        );
+        assert!(lines[2].starts_with(&format!("{},Other export ticket,", other.id)));
+        // Cells a spreadsheet would run as formulas are prefixed
+        assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+
+        // JSON carries the same rows
+        let (status, body) = export(pool, create_test_claims(&tech), &format!("{uri}&format=json")).await;
+        assert_eq!(status, StatusCode::OK);
+        let rows: Vec<serde_json::Value> = serde_json::from_str(&body).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["id"], ticket.id);
+        assert_eq!(rows[0]["requester"], "Export Requester");
+    }
+
+    #[actix_web::test]
+    async fn pdf_body_lists_each_ticket_and_notes_truncation() {
+        let created_at = chrono::NaiveDate::from_ymd_opt(2026, 3, 1).unwrap().and_hms_opt(9, 30, 0).unwrap();
+        let row = TicketExportRow {
+            id: 42,
+            title: "Printer jammed".to_string(),
+            status: crate::models::TicketStatus::Open,
+            priority: crate::models::TicketPriority::High,
+            requester: Some("Ada".to_string()),
+            assignee: None,
+            created_at,
+            closed_at: None,
+        };
+
+        let body = pdf_body(std::slice::from_ref(&row), false);
+        assert_eq!(
+            body,
+            "#42 Printer jammed\n    open | high | Requester: Ada | Assignee: - | Created 2026-03-01 09:30:00\n\n"
+        );
+        assert!(pdf_body(&[row], true).ends_with("export as CSV for all of them.\n"));
+    }
+
+    /// Requires the pdfium shared library: `cargo test --features pdfium-tests`
+    #[cfg(feature = "pdfium-tests")]
+    #[actix_web::test]
+    async fn pdf_export_is_rendered() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let tech = TestFixtures::create_user(&mut conn, "pdfexporttech", UserRole::Technician);
+        TestFixtures::create_ticket(&mut conn, "PDF export ticket", Some(tech.uuid), None);
+        drop(conn);
+
+        let uri = format!("/tickets/export?requester={}&format=pdf", tech.uuid);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .wrap_fn({
+                    let claims = create_test_claims(&tech);
+                    move |req, srv| {
+                        req.extensions_mut().insert(claims.clone());
+                        srv.call(req)
+                    }
+                })
+                .route("/tickets/export", web::get().to(export_tickets)),
+        )
+        .await;
+        let resp = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("Content-Type").unwrap(), "application/pdf");
+        assert!(test::read_body(resp).await.starts_with(b"%PDF"));
+    }
+
+    #[actix_web::test]
+    async fn export_applies_visibility_rules() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "exportviewer", UserRole::User);
+        let other = TestFixtures::create_user(&mut conn, "exportstranger", UserRole::User);
+        let secret = TestFixtures::create_category(&mut conn, "Export Secret");
+        let group = TestFixtures::create_group(&mut conn, "export_secret_group");
+        TestFixtures::set_category_visibility(&mut conn, secret.id, &[group.id]);
+        let hidden = TestFixtures::create_ticket(&mut conn, "Hidden export", Some(other.uuid), Some(secret.id));
+        drop(conn);
+
+        let uri = format!("/tickets/export?requester={}&format=json", other.uuid);
+        let (status, body) = export(pool, create_test_claims(&user), &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        let rows: Vec<serde_json::Value> = serde_json::from_str(&body).unwrap();
+        assert!(rows.iter().all(|r| r["id"] != hidden.id));
+    }
+}
@@ -0,0 +1,195 @@
+//! Public Ticket Status Handlers
+//!
+//! Requesters without an account can follow a ticket through a signed,
+//! expiring link (see `utils::status_links`). The public view shows the
+//! title, status, last update and public comments; internal comments,
+//! attachments and anything identifying staff beyond their name are left out.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::db::{DbConnection, Pool};
+use crate::extractors::AuthContext;
+use crate::models::TicketStatus;
+use crate::repository;
+use crate::utils::rbac::require_scope;
+use crate::utils::status_links::{self, StatusLinkError, DEFAULT_TTL_DAYS, MAX_TTL_DAYS};
+
+/// Request body for creating a status link
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateStatusLinkRequest {
+    /// Link lifetime in days (default 7, at most 30)
+    pub expires_in_days: Option<i64>,
+}
+
+/// A public comment as shown on the status page
+#[derive(Debug, Serialize)]
+pub struct StatusComment {
+    pub author: String,
+    pub content: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// Read-only ticket view for the public status page
+#[derive(Debug, Serialize)]
+pub struct TicketStatusView {
+    pub id: i32,
+    pub title: String,
+    pub status: TicketStatus,
+    pub last_updated: NaiveDateTime,
+    pub comments: Vec<StatusComment>,
+}
+
+/// Build the public view of a ticket, without internal comments or attachments
+pub fn build_status_view(conn: &mut DbConnection, ticket_id: i32) -> diesel::QueryResult<TicketStatusView> {
+    let ticket = repository::get_ticket_by_id(conn, ticket_id)?;
+    let comments = repository::get_comments_by_ticket_id(conn, ticket_id, false)?
+        .into_iter()
+        .map(|comment| {
+            let author = repository::get_user_by_uuid(&comment.user_uuid, conn)
+                .map(|user| user.name)
+                .unwrap_or_else(|_| "Unknown".to_string());
+            StatusComment {
+                author,
+                content: comment.content,
+                created_at: comment.created_at,
+            }
+        })
+        .collect();
+
+    Ok(TicketStatusView {
+        id: ticket.id,
+        title: ticket.title,
+        status: ticket.status,
+        last_updated: ticket.updated_at,
+        comments,
+    })
+}
+
+/// Create a signed status link for a ticket (requester, assignee or staff)
+pub async fn create_status_link(
+    req: HttpRequest,
+    auth: AuthContext,
+    pool: web::Data<Pool>,
+    path: web::Path<i32>,
+    body: Option<web::Json<CreateStatusLinkRequest>>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:read") {
+        return e;
+    }
+
+    let ticket_id = path.into_inner();
+    let days = body
+        .and_then(|body| body.into_inner().expires_in_days)
+        .unwrap_or(DEFAULT_TTL_DAYS);
+    if !(1..=MAX_TTL_DAYS).contains(&days) {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Invalid expiry",
+            "message": format!("Links expire after 1 to {MAX_TTL_DAYS} days")
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+    let ticket = match repository::get_ticket_by_id(&mut conn, ticket_id) {
+        Ok(ticket) => ticket,
+        Err(_) => return HttpResponse::NotFound().json("Ticket not found"),
+    };
+    if !auth.can_view_ticket(ticket.requester_uuid, ticket.assignee_uuid) {
+        return HttpResponse::NotFound().json("Ticket not found");
+    }
+
+    let expires_at = Utc::now() + Duration::days(days);
+    let token = match status_links::create_token(ticket.id, expires_at) {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to sign ticket status link");
+            return HttpResponse::InternalServerError().json("Failed to create status link");
+        }
+    };
+
+    let base_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| {
+        let info = req.connection_info();
+        format!("{}://{}", info.scheme(), info.host())
+    });
+    tracing::info!(ticket_id, user = %auth.user_uuid, days, "Created ticket status link");
+
+    HttpResponse::Ok().json(json!({
+        "url": format!("{}/status/{token}", base_url.trim_end_matches('/')),
+        "token": token,
+        "expires_at": expires_at,
+    }))
+}
+
+/// Public read-only ticket status for a signed link (no auth)
+pub async fn get_ticket_status(pool: web::Data<Pool>, path: web::Path<String>) -> impl Responder {
+    let ticket_id = match status_links::verify_token(&path, Utc::now()) {
+        Ok((ticket_id, _)) => ticket_id,
+        Err(StatusLinkError::Expired) => {
+            return HttpResponse::Gone().json(json!({
+                "error": "Link expired",
+                "message": "This status link has expired, ask for a new one"
+            }));
+        }
+        Err(_) => return HttpResponse::NotFound().json("Status link not found"),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json("Database connection error"),
+    };
+
+    match build_status_view(&mut conn, ticket_id) {
+        Ok(view) => HttpResponse::Ok()
+            .insert_header(("Cache-Control", "no-store"))
+            .json(view),
+        Err(diesel::result::Error::NotFound) => HttpResponse::NotFound().json("Status link not found"),
+        Err(e) => {
+            tracing::error!(ticket_id, error = %e, "Failed to load ticket status");
+            HttpResponse::InternalServerError().json("Failed to load ticket status")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NewComment, UserRole};
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+    #[test]
+    fn status_view_hides_internal_comments() {
+        let mut conn = setup_test_connection();
+        let requester = TestFixtures::create_user(&mut conn, "statusrequester", UserRole::User);
+        let tech = TestFixtures::create_user(&mut conn, "statustech", UserRole::Technician);
+        let ticket = TestFixtures::create_ticket(&mut conn, "VPN drops hourly", Some(requester.uuid), None);
+
+        let public = TestFixtures::create_comment(&mut conn, ticket.id, tech.uuid, "Replacing the router today");
+        TestFixtures::create_attachment(&mut conn, public.id, "router-log.txt");
+        repository::create_comment(
+            &mut conn,
+            NewComment {
+                content: "Requester's laptop is out of warranty".to_string(),
+                ticket_id: ticket.id,
+                user_uuid: tech.uuid,
+                is_internal: true,
+            },
+        )
+        .unwrap();
+
+        let view = build_status_view(&mut conn, ticket.id).unwrap();
+
+        assert_eq!(view.title, "VPN drops hourly");
+        assert_eq!(view.comments.len(), 1);
+        assert_eq!(view.comments[0].content, "Replacing the router today");
+        assert_eq!(view.comments[0].author, tech.name);
+
+        let json = serde_json::to_string(&view).unwrap();
+        assert!(!json.contains("out of warranty"));
+        assert!(!json.contains("router-log.txt"));
+    }
+}
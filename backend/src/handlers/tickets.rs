@@ -1,14 +1,16 @@
 use actix_web::{web, HttpResponse, Responder, HttpRequest, HttpMessage};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use once_cell::sync::Lazy;
 use std::sync::Arc;
 use tracing::{debug, error, warn, info};
 use uuid::Uuid;
 
 use crate::extractors::AuthContext;
-use crate::models::{AssignmentTrigger, Claims, NewTicket, TicketPriority, TicketStatus, TicketUpdate, TicketsJson, UserRole};
+use crate::models::{AssignmentTrigger, Claims, NewTicket, TicketPriority, TicketStatus, TicketUpdate, UserRole};
 use crate::repository;
 use crate::repository::ticket_query::TicketQuery;
 use crate::services::assignment::AssignmentEngine;
@@ -16,11 +18,26 @@ use crate::services::notifications::{
     NotificationService,
     types::{NotificationTypeCode, NotificationPayload, NotificationEntity, NotificationActor},
 };
+use crate::services::audit::{self, AuditAction, AuditTarget};
+use crate::services::category_fields;
+use crate::services::ticket_json;
 use crate::services::search::SearchService;
 use crate::services::search::indexing_tasks;
-use crate::utils::rbac::{is_admin, is_technician_or_admin};
+use crate::utils::rbac::{is_admin, is_technician_or_admin, require_scope};
 use crate::utils::sse::SseBroadcaster;
 
+/// Default age past which only admins can reopen a closed ticket
+const DEFAULT_REOPEN_MAX_AGE_DAYS: i64 = 30;
+
+/// Reopen age limit from TICKET_REOPEN_MAX_AGE_DAYS (default 30)
+static REOPEN_MAX_AGE_DAYS: Lazy<i64> = Lazy::new(|| {
+    std::env::var("TICKET_REOPEN_MAX_AGE_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_REOPEN_MAX_AGE_DAYS)
+});
+
 // Helper type for database operations with proper error handling
 type DbResult<T> = Result<T, HttpResponse>;
 
@@ -63,6 +80,36 @@ fn validate_assignee_role(
     }
 }
 
+// Helper function to check the user may manage tickets in each of the given
+// categories: technicians/admins always, group managers only where the
+// category is visible to a group they manage
+fn require_ticket_management(
+    auth: &AuthContext,
+    category_ids: &[Option<i32>],
+    conn: &mut crate::db::DbConnection,
+) -> Result<(), HttpResponse> {
+    if auth.is_technician_or_admin() {
+        return Ok(());
+    }
+
+    for category_id in category_ids {
+        let category_group_ids = match category_id {
+            Some(id) => repository::categories::get_visible_group_ids_for_category(conn, *id)
+                .map_err(|_| HttpResponse::InternalServerError().json("Failed to check category groups"))?,
+            None => Vec::new(),
+        };
+
+        if !auth.can_manage_ticket(&category_group_ids) {
+            return Err(HttpResponse::Forbidden().json(json!({
+                "error": "Forbidden",
+                "message": "Only technicians, administrators or managers of the ticket's group can do this"
+            })));
+        }
+    }
+
+    Ok(())
+}
+
 // Helper function to parse and validate assignee from string (for update operations)
 fn parse_and_validate_assignee_string(
     assignee_str: &str,
@@ -196,6 +243,9 @@ pub struct PaginationParams {
     category: Option<String>,
     assignee: Option<String>,
     requester: Option<String>,
+    /// Custom field values as comma-separated `key:value` pairs
+    #[serde(rename = "customFields")]
+    custom_fields: Option<String>,
     // Date filtering parameters
     #[serde(rename = "createdAfter")]
     created_after: Option<String>,
@@ -215,6 +265,33 @@ pub struct PaginationParams {
     closed_before: Option<String>,
     #[serde(rename = "closedOn")]
     closed_on: Option<String>,
+    /// IANA zone date filters are interpreted in; defaults to the user's
+    /// preference, then DEFAULT_TIMEZONE
+    timezone: Option<String>,
+}
+
+impl PaginationParams {
+    /// Ticket query with this request's filters and the caller's visibility
+    /// rules, without pagination or sorting
+    pub(crate) fn filtered_query(&self, auth: &AuthContext) -> TicketQuery {
+        let tz = crate::utils::timezone::resolve(self.timezone.as_deref(), auth.timezone.as_deref());
+        TicketQuery::new()
+            .visible_to(auth)
+            .timezone(tz)
+            .search(self.search.clone())
+            .status(self.status.clone())
+            .priority(self.priority.clone())
+            .category(self.category.clone())
+            .assignee(self.assignee.clone())
+            .requester(self.requester.clone())
+            .custom_fields(self.custom_fields.clone())
+            .created_between(self.created_after.clone(), self.created_before.clone())
+            .created_on(self.created_on.clone())
+            .modified_between(self.modified_after.clone(), self.modified_before.clone())
+            .modified_on(self.modified_on.clone())
+            .closed_between(self.closed_after.clone(), self.closed_before.clone())
+            .closed_on(self.closed_on.clone())
+    }
 }
 
 // Paginated response
@@ -231,9 +308,14 @@ pub struct PaginatedResponse<T> {
 
 // Get all tickets (technicians and admins only)
 pub async fn get_tickets(
+    req: HttpRequest,
     pool: web::Data<crate::db::Pool>,
     auth: AuthContext,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:read") {
+        return e;
+    }
+
     // Only technicians and admins can see all tickets via this endpoint
     if !auth.is_technician_or_admin() {
         return HttpResponse::Forbidden().json(json!({
@@ -255,30 +337,23 @@ pub async fn get_tickets(
 
 // Get paginated tickets
 pub async fn get_paginated_tickets(
+    req: HttpRequest,
     pool: web::Data<crate::db::Pool>,
     query: web::Query<PaginationParams>,
     auth: AuthContext,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:read") {
+        return e;
+    }
+
     let mut conn = match get_db_conn(&pool).await {
         Ok(conn) => conn,
         Err(e) => return e,
     };
 
     // Build query with automatic permission filtering via AuthContext
-    let result = TicketQuery::new()
-        .visible_to(&auth)
-        .search(query.search.clone())
-        .status(query.status.clone())
-        .priority(query.priority.clone())
-        .category(query.category.clone())
-        .assignee(query.assignee.clone())
-        .requester(query.requester.clone())
-        .created_between(query.created_after.clone(), query.created_before.clone())
-        .created_on(query.created_on.clone())
-        .modified_between(query.modified_after.clone(), query.modified_before.clone())
-        .modified_on(query.modified_on.clone())
-        .closed_between(query.closed_after.clone(), query.closed_before.clone())
-        .closed_on(query.closed_on.clone())
+    let result = query
+        .filtered_query(&auth)
         .paginate(query.page.unwrap_or(1), query.page_size.unwrap_or(10))
         .sort(query.sort_field.clone(), query.sort_direction.clone())
         .execute_with_users(&mut conn);
@@ -294,10 +369,16 @@ pub async fn get_paginated_tickets(
 
 // Get a ticket by ID with comments and related info
 pub async fn get_ticket(
+    req: HttpRequest,
+    auth: AuthContext,
     pool: web::Data<crate::db::Pool>,
     params: web::Path<i32>,
     claims: web::ReqData<Claims>,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:read") {
+        return e;
+    }
+
     use crate::repository::user_ticket_views::UserTicketViewsRepository;
 
     let ticket_id = params.into_inner();
@@ -308,8 +389,8 @@ pub async fn get_ticket(
         Err(e) => return e,
     };
 
-    // Get the ticket first
-    let complete_ticket = match repository::get_complete_ticket(&mut conn, ticket_id) {
+    // Get the ticket first (internal comments only for technicians/admins)
+    let complete_ticket = match repository::get_complete_ticket(&mut conn, ticket_id, auth.is_technician_or_admin()) {
         Ok(ticket) => ticket,
         Err(_) => return HttpResponse::NotFound().json("Ticket not found"),
     };
@@ -332,20 +413,139 @@ pub async fn get_ticket(
     HttpResponse::Ok().json(complete_ticket)
 }
 
+// Duplicate suggestion request for a ticket draft
+#[derive(Debug, serde::Deserialize)]
+pub struct SuggestDuplicatesRequest {
+    title: String,
+    description: Option<String>,
+}
+
+// Suggest existing tickets that look like duplicates of a draft
+pub async fn suggest_duplicate_tickets(
+    req: HttpRequest,
+    auth: AuthContext,
+    pool: web::Data<crate::db::Pool>,
+    search_service: web::Data<Arc<SearchService>>,
+    body: web::Json<SuggestDuplicatesRequest>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:read") {
+        return e;
+    }
+
+    let title = body.title.trim();
+    if title.is_empty() {
+        return HttpResponse::Ok().json(Vec::<crate::services::search::types::SearchResult>::new());
+    }
+    if title.len() > 500 {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Bad Request",
+            "message": "Title too long (max 500 characters)"
+        }));
+    }
+
+    let mut conn = match get_db_conn(&pool).await {
+        Ok(conn) => conn,
+        Err(e) => return e,
+    };
+
+    match crate::services::search::duplicates::find_similar_tickets(
+        &mut conn,
+        search_service.get_ref(),
+        &auth,
+        title,
+        body.description.as_deref(),
+    ) {
+        Ok(suggestions) => HttpResponse::Ok().json(suggestions),
+        Err(e) => {
+            error!(error = ?e, "Failed to find similar tickets");
+            HttpResponse::InternalServerError().json("Failed to find similar tickets")
+        }
+    }
+}
+
+// Get a ticket's activity timeline
+pub async fn get_ticket_timeline(
+    req: HttpRequest,
+    auth: AuthContext,
+    pool: web::Data<crate::db::Pool>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:read") {
+        return e;
+    }
+
+    let ticket_id = path.into_inner();
+    let mut conn = match get_db_conn(&pool).await {
+        Ok(conn) => conn,
+        Err(e) => return e,
+    };
+
+    match repository::get_ticket_timeline(&mut conn, ticket_id, auth.is_technician_or_admin()) {
+        Ok(timeline) => HttpResponse::Ok().json(timeline),
+        Err(diesel::result::Error::NotFound) => HttpResponse::NotFound().json("Ticket not found"),
+        Err(e) => {
+            error!(ticket_id, error = ?e, "Failed to build ticket timeline");
+            HttpResponse::InternalServerError().json("Failed to get ticket timeline")
+        }
+    }
+}
+
+// New ticket, with the devices to link to it
+#[derive(Debug, Deserialize)]
+pub struct CreateTicketRequest {
+    #[serde(flatten)]
+    pub ticket: NewTicket,
+    #[serde(default)]
+    pub device_ids: Vec<i32>,
+    /// Custom field values by field key
+    #[serde(default)]
+    pub custom_fields: Option<Value>,
+}
+
+/// Custom field values of a request body, when it's an object of text
+/// values (as checked by `category_fields::check_ticket`)
+fn custom_field_values_by_key(custom_fields: Option<&Value>) -> Option<HashMap<String, Option<String>>> {
+    let values = custom_fields?.as_object()?;
+    Some(
+        values
+            .iter()
+            .map(|(key, value)| (key.clone(), value.as_str().map(str::to_string)))
+            .collect(),
+    )
+}
+
+fn invalid_custom_fields_response(errors: Vec<category_fields::FieldError>) -> HttpResponse {
+    let errors: Vec<category_fields::FieldError> = errors
+        .into_iter()
+        .map(|e| category_fields::FieldError::new(format!("custom_fields.{}", e.field), e.message))
+        .collect();
+    HttpResponse::BadRequest().json(json!({
+        "error": "Invalid custom fields",
+        "fields": errors,
+    }))
+}
+
 // Create a new ticket
 pub async fn create_ticket(
+    req: HttpRequest,
     pool: web::Data<crate::db::Pool>,
     sse_state: web::Data<crate::handlers::sse::SseState>,
     notification_service: web::Data<NotificationService>,
     search_service: web::Data<Arc<SearchService>>,
     auth: AuthContext,
-    ticket: web::Json<NewTicket>,
+    body: web::Json<CreateTicketRequest>,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
     let mut conn = match get_db_conn(&pool).await {
         Ok(conn) => conn,
         Err(e) => return e,
     };
-    let new_ticket = ticket.into_inner();
+    let CreateTicketRequest { ticket: new_ticket, mut device_ids, custom_fields } = body.into_inner();
+    device_ids.sort_unstable();
+    device_ids.dedup();
 
     // Validate category visibility if category_id is set
     if let Some(category_id) = new_ticket.category_id {
@@ -368,6 +568,58 @@ pub async fn create_ticket(
         }
     }
 
+    // The category may require custom fields or a device
+    let required = match new_ticket.category_id {
+        Some(category_id) => match repository::categories::get_category_by_id(&mut conn, category_id) {
+            Ok(category) => category_fields::required_fields(&category),
+            Err(diesel::result::Error::NotFound) => {
+                return HttpResponse::BadRequest().json(json!({
+                    "error": "Bad Request",
+                    "message": "Category not found"
+                }));
+            }
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to load category"),
+        },
+        None => Vec::new(),
+    };
+    if let Err(errors) = category_fields::check_ticket(&required, custom_fields.as_ref(), &device_ids) {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Missing required fields",
+            "fields": errors,
+        }));
+    }
+
+    // Values must match their field definitions
+    let custom_field_values = match custom_field_values_by_key(custom_fields.as_ref()) {
+        Some(values) => {
+            match repository::custom_fields::validate_values(&mut conn, new_ticket.category_id, &values) {
+                Ok(validated) => validated,
+                Err(repository::custom_fields::CustomFieldError::Invalid(errors)) => {
+                    return invalid_custom_fields_response(errors);
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to validate custom fields");
+                    return HttpResponse::InternalServerError().json("Failed to validate custom fields");
+                }
+            }
+        }
+        None => Vec::new(),
+    };
+
+    // Technicians can link any device; other users only their own
+    for &device_id in &device_ids {
+        match repository::get_device_by_id(&mut conn, device_id) {
+            Ok(device) if auth.is_technician_or_admin() || device.primary_user_uuid == Some(auth.user_uuid) => {}
+            Ok(_) | Err(diesel::result::Error::NotFound) => {
+                return HttpResponse::BadRequest().json(json!({
+                    "error": "Bad Request",
+                    "message": format!("Device {device_id} not found")
+                }));
+            }
+            Err(_) => return HttpResponse::InternalServerError().json("Failed to load device"),
+        }
+    }
+
     // Validate assignee role if assignee is set
     if let Some(assignee_uuid) = new_ticket.assignee_uuid {
         if let Err(e) = validate_assignee_role(&assignee_uuid, &mut conn) {
@@ -375,7 +627,16 @@ pub async fn create_ticket(
         }
     }
 
-    match repository::create_ticket(&mut conn, new_ticket) {
+    let created = repository::with_transaction(&mut conn, |conn| {
+        let ticket = repository::create_ticket(conn, new_ticket)?;
+        for &device_id in &device_ids {
+            repository::add_device_to_ticket(conn, ticket.id, device_id)?;
+        }
+        repository::custom_fields::write_values(conn, ticket.id, &custom_field_values, Some(auth.user_uuid))?;
+        Ok::<_, diesel::result::Error>(ticket)
+    });
+
+    match created {
         Ok(mut ticket) => {
             // Run automatic assignment rules if no assignee
             if ticket.assignee_uuid.is_none() {
@@ -386,7 +647,7 @@ pub async fn create_ticket(
                             updated_at: Some(chrono::Utc::now().naive_utc()),
                             ..Default::default()
                         };
-                        if let Ok(updated) = repository::update_ticket_partial(&mut conn, ticket.id, assign_update) {
+                        if let Ok(updated) = repository::update_ticket_partial(&mut conn, ticket.id, assign_update, None, None) {
                             ticket = updated;
                             info!(
                                 ticket_id = ticket.id,
@@ -402,7 +663,7 @@ pub async fn create_ticket(
                             let ticket_title = ticket.title.clone();
                             let rule_name = result.rule_name.clone();
 
-                            tokio::spawn(async move {
+                            crate::services::shutdown::notifications().spawn(async move {
                                 let payload = NotificationPayload::new(
                                     NotificationTypeCode::TicketAssigned,
                                     assigned_uuid,
@@ -451,10 +712,17 @@ pub async fn create_ticket(
 
 // Update a ticket
 pub async fn update_ticket(
+    req: HttpRequest,
+    auth: AuthContext,
     pool: web::Data<crate::db::Pool>,
+    search_service: web::Data<Arc<SearchService>>,
     path: web::Path<i32>,
     ticket: web::Json<NewTicket>,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
     let ticket_id = path.into_inner();
     let mut conn = match get_db_conn(&pool).await {
         Ok(conn) => conn,
@@ -462,6 +730,16 @@ pub async fn update_ticket(
     };
     let new_ticket = ticket.into_inner();
 
+    // Replacing a ticket needs management rights over its current and new category
+    let current = match repository::get_ticket_by_id(&mut conn, ticket_id) {
+        Ok(ticket) => ticket,
+        Err(diesel::result::Error::NotFound) => return HttpResponse::NotFound().json("Ticket not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to load ticket"),
+    };
+    if let Err(e) = require_ticket_management(&auth, &[current.category_id, new_ticket.category_id], &mut conn) {
+        return e;
+    }
+
     // Validate assignee role if assignee is set
     if let Some(assignee_uuid) = new_ticket.assignee_uuid {
         if let Err(e) = validate_assignee_role(&assignee_uuid, &mut conn) {
@@ -470,13 +748,60 @@ pub async fn update_ticket(
     }
 
     match repository::update_ticket(&mut conn, ticket_id, new_ticket) {
-        Ok(ticket) => HttpResponse::Ok().json(ticket),
+        Ok(ticket) => {
+            let article_content = repository::get_article_content_by_ticket_id(&mut conn, ticket_id).ok();
+            indexing_tasks::spawn_index_ticket(search_service.get_ref().clone(), ticket.clone(), article_content);
+            HttpResponse::Ok().json(ticket)
+        }
         Err(e) => {
             HttpResponse::InternalServerError().json(format!("Failed to update ticket: {e}"))
         }
     }
 }
 
+/// Set a ticket's custom field values by field key; `null` or blank clears
+/// a value. Returns all of the ticket's values.
+pub async fn update_ticket_custom_fields(
+    req: HttpRequest,
+    auth: AuthContext,
+    pool: web::Data<crate::db::Pool>,
+    search_service: web::Data<Arc<SearchService>>,
+    path: web::Path<i32>,
+    body: web::Json<HashMap<String, Option<String>>>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
+    let ticket_id = path.into_inner();
+    let mut conn = match get_db_conn(&pool).await {
+        Ok(conn) => conn,
+        Err(e) => return e,
+    };
+
+    let ticket = match repository::get_ticket_by_id(&mut conn, ticket_id) {
+        Ok(ticket) => ticket,
+        Err(diesel::result::Error::NotFound) => return HttpResponse::NotFound().json("Ticket not found"),
+        Err(_) => return HttpResponse::InternalServerError().json("Failed to load ticket"),
+    };
+    if let Err(e) = require_ticket_management(&auth, &[ticket.category_id], &mut conn) {
+        return e;
+    }
+
+    match repository::custom_fields::set_values(&mut conn, ticket_id, ticket.category_id, &body, Some(auth.user_uuid)) {
+        Ok(values) => {
+            let article_content = repository::get_article_content_by_ticket_id(&mut conn, ticket_id).ok();
+            indexing_tasks::spawn_index_ticket(search_service.get_ref().clone(), ticket, article_content);
+            HttpResponse::Ok().json(values)
+        }
+        Err(repository::custom_fields::CustomFieldError::Invalid(errors)) => invalid_custom_fields_response(errors),
+        Err(e) => {
+            error!(ticket_id, error = %e, "Failed to update custom fields");
+            HttpResponse::InternalServerError().json("Failed to update custom fields")
+        }
+    }
+}
+
 // Delete a ticket with comprehensive cleanup
 pub async fn delete_ticket(
     req: HttpRequest,
@@ -485,6 +810,10 @@ pub async fn delete_ticket(
     search_service: web::Data<Arc<SearchService>>,
     path: web::Path<i32>,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
     // Extract claims and check role
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
@@ -528,8 +857,13 @@ pub async fn delete_ticket(
 pub async fn import_tickets_from_json(
     req: HttpRequest,
     pool: web::Data<crate::db::Pool>,
+    search_service: web::Data<Arc<SearchService>>,
     json_path: web::Path<String>,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
     // Extract claims and check role
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
@@ -558,8 +892,8 @@ pub async fn import_tickets_from_json(
     };
 
     // Parse the JSON
-    let tickets_json: TicketsJson = match serde_json::from_str(&json_content) {
-        Ok(tickets) => tickets,
+    let document: Value = match serde_json::from_str(&json_content) {
+        Ok(document) => document,
         Err(_) => return HttpResponse::BadRequest().json("Failed to parse JSON"),
     };
 
@@ -568,29 +902,20 @@ pub async fn import_tickets_from_json(
         Err(e) => return e,
     };
 
-    // Import each ticket
-    let mut imported_count = 0;
-    let mut failed_count = 0;
-
-    for ticket_json in tickets_json.tickets {
-        match repository::import_ticket_from_json(&mut conn, &ticket_json) {
-            Ok(_) => imported_count += 1,
-            Err(_) => failed_count += 1,
-        }
-    }
-
-    HttpResponse::Ok().json(json!({
-        "imported": imported_count,
-        "failed": failed_count
-    }))
+    import_ticket_document(&mut conn, search_service.get_ref(), &document)
 }
 
 // Import tickets from JSON string
 pub async fn import_tickets_from_json_string(
     req: HttpRequest,
     pool: web::Data<crate::db::Pool>,
-    tickets_json: web::Json<TicketsJson>,
+    search_service: web::Data<Arc<SearchService>>,
+    document: web::Json<Value>,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
     // Extract claims and check role
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
@@ -612,20 +937,54 @@ pub async fn import_tickets_from_json_string(
         Err(e) => return e,
     };
 
-    // Import each ticket
+    import_ticket_document(&mut conn, search_service.get_ref(), &document)
+}
+
+/// Import the tickets of a document in the ticket JSON format
+///
+/// A document that doesn't match the schema is rejected as a whole. Valid
+/// tickets are then imported one by one; those that can't be (e.g. an
+/// unknown requester) are counted as failed, with their errors.
+fn import_ticket_document(
+    conn: &mut crate::db::DbConnection,
+    search_service: &Arc<SearchService>,
+    document: &Value,
+) -> HttpResponse {
+    let tickets_json = match ticket_json::parse(document) {
+        Ok(tickets) => tickets,
+        Err(errors) => {
+            return HttpResponse::BadRequest().json(json!({
+                "error": "Invalid ticket import",
+                "fields": errors,
+            }));
+        }
+    };
+
     let mut imported_count = 0;
-    let mut failed_count = 0;
+    let mut errors = Vec::new();
 
-    for ticket_json in tickets_json.tickets.iter() {
-        match repository::import_ticket_from_json(&mut conn, ticket_json) {
-            Ok(_) => imported_count += 1,
-            Err(_) => failed_count += 1,
+    for (i, ticket_json) in tickets_json.tickets.iter().enumerate() {
+        match repository::import_ticket_from_json(conn, ticket_json) {
+            Ok(ticket) => {
+                indexing_tasks::spawn_index_ticket(search_service.clone(), ticket, None);
+                imported_count += 1;
+            }
+            Err(repository::tickets::TicketImportError::Invalid(field_errors)) => {
+                errors.extend(field_errors.into_iter().map(|e| {
+                    category_fields::FieldError::new(format!("tickets[{i}].{}", e.field), e.message)
+                }));
+            }
+            Err(e) => {
+                error!(index = i, error = %e, "Failed to import ticket");
+                errors.push(category_fields::FieldError::new(format!("tickets[{i}]"), "could not be saved"));
+            }
         }
     }
 
     HttpResponse::Ok().json(json!({
         "imported": imported_count,
-        "failed": failed_count
+        "failed": tickets_json.tickets.len() - imported_count,
+        "errors": errors,
     }))
 }
 
@@ -636,6 +995,10 @@ pub async fn create_empty_ticket(
     search_service: web::Data<Arc<SearchService>>,
     req: HttpRequest,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
     let mut conn = match get_db_conn(&pool).await {
         Ok(conn) => conn,
         Err(e) => return e,
@@ -683,7 +1046,7 @@ pub async fn create_empty_ticket(
                     updated_at: Some(chrono::Utc::now().naive_utc()),
                     ..Default::default()
                 };
-                if let Ok(updated) = repository::update_ticket_partial(&mut conn, ticket.id, assign_update) {
+                if let Ok(updated) = repository::update_ticket_partial(&mut conn, ticket.id, assign_update, None, None) {
                     ticket = updated;
                     info!(
                         ticket_id = ticket.id,
@@ -699,7 +1062,7 @@ pub async fn create_empty_ticket(
                     let ticket_title = ticket.title.clone();
                     let rule_name = result.rule_name.clone();
 
-                    tokio::spawn(async move {
+                    crate::services::shutdown::notifications().spawn(async move {
                         let payload = NotificationPayload::new(
                             NotificationTypeCode::TicketAssigned,
                             assigned_uuid,
@@ -745,14 +1108,92 @@ pub async fn create_empty_ticket(
     );
 
     // Return the complete ticket with article content
-    match repository::get_complete_ticket(&mut conn, ticket.id) {
+    match repository::get_complete_ticket(&mut conn, ticket.id, is_technician_or_admin(&claims)) {
         Ok(complete_ticket) => HttpResponse::Created().json(complete_ticket),
         Err(_) => HttpResponse::Created().json(ticket), // Fallback to just the ticket if getting complete ticket fails
     }
 }
 
+/// Requester, assignee and watchers of a ticket, minus the user who changed it
+fn status_change_recipients(conn: &mut crate::db::DbConnection, ticket: &crate::models::Ticket, actor: Uuid) -> Vec<Uuid> {
+    repository::ticket_watchers::notification_recipients(conn, ticket, Some(actor)).unwrap_or_else(|e| {
+        warn!(error = %e, ticket_id = ticket.id, "Failed to look up ticket watchers");
+        ticket.requester_uuid.into_iter().filter(|uuid| *uuid != actor).collect()
+    })
+}
+
+// Notify the new assignee about an assignment change and `status_recipients`
+// about a status change between two ticket states (runs async, doesn't block
+// the response)
+fn spawn_ticket_change_notifications(
+    notification_service: web::Data<NotificationService>,
+    actor: NotificationActor,
+    old: &crate::models::Ticket,
+    new: &crate::models::Ticket,
+    status_recipients: Vec<Uuid>,
+) {
+    let ticket_id = new.id;
+    let ticket_title = new.title.clone();
+    let new_assignee = new.assignee_uuid;
+    let old_assignee = old.assignee_uuid;
+    let new_status = new.status;
+    let old_status = old.status;
+
+    crate::services::shutdown::notifications().spawn(async move {
+        // Notify new assignee if assignment changed
+        if new_assignee != old_assignee {
+            if let Some(assignee_uuid) = new_assignee {
+                let payload = NotificationPayload::new(
+                    NotificationTypeCode::TicketAssigned,
+                    assignee_uuid,
+                    actor.clone(),
+                    NotificationEntity::Ticket {
+                        id: ticket_id,
+                        title: ticket_title.clone(),
+                    },
+                )
+                .with_body(format!("You have been assigned to ticket #{ticket_id}"));
+
+                if let Err(e) = notification_service.notify(payload).await {
+                    warn!(error = %e, "Failed to send assignment notification");
+                }
+            }
+        }
+
+        // Notify requester, assignee and watchers if status changed
+        if new_status != old_status {
+            let body = format!(
+                "Ticket #{} status changed to {}",
+                ticket_id,
+                match new_status {
+                    TicketStatus::Open => "open",
+                    TicketStatus::InProgress => "in-progress",
+                    TicketStatus::Closed => "closed",
+                }
+            );
+
+            notification_service
+                .notify_many(&status_recipients, |recipient| {
+                    NotificationPayload::new(
+                        NotificationTypeCode::TicketStatusChanged,
+                        recipient,
+                        actor.clone(),
+                        NotificationEntity::Ticket {
+                            id: ticket_id,
+                            title: ticket_title.clone(),
+                        },
+                    )
+                    .with_body(&body)
+                })
+                .await;
+        }
+    });
+}
+
 // Update ticket partially
+#[allow(clippy::too_many_arguments)]
 pub async fn update_ticket_partial(
+    auth: AuthContext,
     pool: web::Data<crate::db::Pool>,
     sse_state: web::Data<crate::handlers::sse::SseState>,
     notification_service: web::Data<NotificationService>,
@@ -761,6 +1202,10 @@ pub async fn update_ticket_partial(
     params: web::Path<i32>,
     body: web::Json<Value>,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
     let ticket_id = params.into_inner();
 
     let mut conn = match pool.get() {
@@ -774,6 +1219,8 @@ pub async fn update_ticket_partial(
         None => return HttpResponse::Unauthorized().json("Authentication required"),
     };
 
+    let actor_uuid = crate::utils::parse_uuid(&user_info.sub).ok();
+
     // Get the current ticket state for detecting changes (for notifications)
     let old_ticket = repository::get_ticket_by_id(&mut conn, ticket_id).ok();
 
@@ -786,9 +1233,16 @@ pub async fn update_ticket_partial(
         assignee_uuid: None,
         updated_at: Some(chrono::Utc::now().naive_utc()),
         closed_at: None,
+        closed_by: None,
         category_id: None,
     };
 
+    // Version the client last saw; omitted means last write wins
+    let expected_version = body
+        .get("version")
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32);
+
     // Handle simple string fields
     if let Some(title) = body.get("title").and_then(|v| v.as_str()) {
         ticket_update.title = Some(title.to_string());
@@ -862,6 +1316,19 @@ pub async fn update_ticket_partial(
         }
     }
 
+    // Reassigning needs management rights over the ticket's category, and over
+    // the new category too if it changes in the same request
+    if ticket_update.assignee_uuid.is_some() {
+        let current_category = old_ticket.as_ref().and_then(|t| t.category_id);
+        let mut category_ids = vec![current_category];
+        if let Some(new_category) = ticket_update.category_id {
+            category_ids.push(new_category);
+        }
+        if let Err(e) = require_ticket_management(&auth, &category_ids, &mut conn) {
+            return e;
+        }
+    }
+
     // Validate category visibility if category_id is being changed
     if let Some(Some(new_category_id)) = ticket_update.category_id {
         let user_uuid = match crate::utils::parse_uuid(&user_info.sub) {
@@ -892,7 +1359,7 @@ pub async fn update_ticket_partial(
     let category_changed = body.get("category_id").is_some();
 
     // Update the ticket
-    match repository::update_ticket_partial(&mut conn, ticket_id, ticket_update) {
+    match repository::update_ticket_partial(&mut conn, ticket_id, ticket_update, expected_version, actor_uuid) {
         Ok(updated_ticket) => {
             // Run automatic assignment rules if category changed and no assignee
             if category_changed && updated_ticket.assignee_uuid.is_none() {
@@ -908,7 +1375,7 @@ pub async fn update_ticket_partial(
                             updated_at: Some(chrono::Utc::now().naive_utc()),
                             ..Default::default()
                         };
-                        if repository::update_ticket_partial(&mut conn, ticket_id, assign_update).is_ok() {
+                        if repository::update_ticket_partial(&mut conn, ticket_id, assign_update, None, None).is_ok() {
                             info!(
                                 ticket_id,
                                 assignee = %assigned_uuid,
@@ -952,7 +1419,7 @@ pub async fn update_ticket_partial(
                                 let assignee_uuid = assignee.uuid;
                                 let rule_name = result.rule_name.clone();
 
-                                tokio::spawn(async move {
+                                crate::services::shutdown::notifications().spawn(async move {
                                     let payload = NotificationPayload::new(
                                         NotificationTypeCode::TicketAssigned,
                                         assignee_uuid,
@@ -999,7 +1466,7 @@ pub async fn update_ticket_partial(
 
             // Now fetch the complete ticket for the response
             // This happens after SSE broadcast so it doesn't delay real-time updates
-            let updated_ticket = match repository::get_complete_ticket(&mut conn, ticket_id) {
+            let updated_ticket = match repository::get_complete_ticket(&mut conn, ticket_id, is_technician_or_admin(&user_info)) {
                 Ok(ticket) => ticket,
                 Err(_) => {
                     return HttpResponse::InternalServerError()
@@ -1022,65 +1489,14 @@ pub async fn update_ticket_partial(
                 });
 
                 if let Some(actor) = actor {
-                    let notification_service = notification_service.clone();
-                    let ticket_title = updated_ticket.ticket.title.clone();
-                    let new_assignee = updated_ticket.ticket.assignee_uuid;
-                    let old_assignee = old.assignee_uuid;
-                    let new_status = updated_ticket.ticket.status;
-                    let old_status = old.status;
-                    let requester_uuid = updated_ticket.ticket.requester_uuid;
-                    let actor_clone = actor.clone();
-
-                    // Spawn async task for notifications to not block response
-                    tokio::spawn(async move {
-                        // Notify new assignee if assignment changed
-                        if new_assignee != old_assignee {
-                            if let Some(assignee_uuid) = new_assignee {
-                                let payload = NotificationPayload::new(
-                                    NotificationTypeCode::TicketAssigned,
-                                    assignee_uuid,
-                                    actor_clone.clone(),
-                                    NotificationEntity::Ticket {
-                                        id: ticket_id,
-                                        title: ticket_title.clone(),
-                                    },
-                                )
-                                .with_body(format!("You have been assigned to ticket #{ticket_id}"));
-
-                                if let Err(e) = notification_service.notify(payload).await {
-                                    warn!(error = %e, "Failed to send assignment notification");
-                                }
-                            }
-                        }
-
-                        // Notify requester if status changed to closed
-                        if new_status != old_status {
-                            if let Some(requester) = requester_uuid {
-                                let payload = NotificationPayload::new(
-                                    NotificationTypeCode::TicketStatusChanged,
-                                    requester,
-                                    actor_clone.clone(),
-                                    NotificationEntity::Ticket {
-                                        id: ticket_id,
-                                        title: ticket_title.clone(),
-                                    },
-                                )
-                                .with_body(format!(
-                                    "Ticket #{} status changed to {}",
-                                    ticket_id,
-                                    match new_status {
-                                        TicketStatus::Open => "open",
-                                        TicketStatus::InProgress => "in-progress",
-                                        TicketStatus::Closed => "closed",
-                                    }
-                                ));
-
-                                if let Err(e) = notification_service.notify(payload).await {
-                                    warn!(error = %e, "Failed to send status change notification");
-                                }
-                            }
-                        }
-                    });
+                    let status_recipients = status_change_recipients(&mut conn, &updated_ticket.ticket, actor.uuid);
+                    spawn_ticket_change_notifications(
+                        notification_service.clone(),
+                        actor,
+                        old,
+                        &updated_ticket.ticket,
+                        status_recipients,
+                    );
                 }
             }
 
@@ -1096,6 +1512,13 @@ pub async fn update_ticket_partial(
             // Return the updated complete ticket
             HttpResponse::Ok().json(updated_ticket)
         }
+        Err(repository::TicketUpdateError::Conflict) => HttpResponse::Conflict().json(json!({
+            "error": "Conflict",
+            "message": "Ticket was modified by someone else. Refresh and try again."
+        })),
+        Err(repository::TicketUpdateError::Database(diesel::result::Error::NotFound)) => {
+            HttpResponse::NotFound().json("Ticket not found")
+        }
         Err(e) => {
             error!(error = ?e, "Failed to update ticket");
             HttpResponse::InternalServerError().json("Failed to update ticket")
@@ -1110,6 +1533,10 @@ pub async fn link_tickets(
     path: web::Path<(i32, i32)>,
     sse_state: web::Data<crate::handlers::sse::SseState>,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
     // Extract claims and check role
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
@@ -1163,6 +1590,10 @@ pub async fn unlink_tickets(
     path: web::Path<(i32, i32)>,
     sse_state: web::Data<crate::handlers::sse::SseState>,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
     // Extract claims and check role
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
@@ -1216,6 +1647,10 @@ pub async fn add_device_to_ticket(
     path: web::Path<(i32, i32)>,
     sse_state: web::Data<crate::handlers::sse::SseState>,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
     // Extract claims and check role
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
@@ -1269,6 +1704,10 @@ pub async fn remove_device_from_ticket(
     path: web::Path<(i32, i32)>,
     sse_state: web::Data<crate::handlers::sse::SseState>,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
     // Extract claims and check role
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
@@ -1377,15 +1816,75 @@ pub struct BulkActionRequest {
     value: Option<String>,
 }
 
+// Per-ticket outcome of a bulk operation
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct BulkItemResult {
+    id: i32,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<&'static str>,
+}
+
+impl BulkItemResult {
+    fn updated(id: i32) -> Self {
+        Self { id, status: "updated", reason: None }
+    }
+
+    fn skipped(id: i32, reason: &'static str) -> Self {
+        Self { id, status: "skipped", reason: Some(reason) }
+    }
+}
+
+// Split requested ids into tickets the user may change and skipped results
+// (missing or not visible). Duplicate ids are only considered once.
+fn resolve_bulk_targets(
+    conn: &mut crate::db::DbConnection,
+    auth: &AuthContext,
+    ids: &[i32],
+) -> Result<(Vec<crate::models::Ticket>, Vec<BulkItemResult>), diesel::result::Error> {
+    use crate::schema::tickets;
+    use diesel::prelude::*;
+
+    let found: Vec<crate::models::Ticket> = tickets::table
+        .filter(tickets::id.eq_any(ids))
+        .load(conn)?;
+
+    let mut seen = std::collections::HashSet::new();
+    let mut allowed = Vec::new();
+    let mut skipped = Vec::new();
+
+    for &id in ids {
+        if !seen.insert(id) {
+            continue;
+        }
+        match found.iter().find(|t| t.id == id) {
+            None => skipped.push(BulkItemResult::skipped(id, "not_found")),
+            Some(ticket) if !auth.can_view_ticket(ticket.requester_uuid, ticket.assignee_uuid) => {
+                skipped.push(BulkItemResult::skipped(id, "forbidden"))
+            }
+            Some(ticket) => allowed.push(ticket.clone()),
+        }
+    }
+
+    Ok((allowed, skipped))
+}
+
 // Perform bulk operations on tickets
+#[allow(clippy::too_many_arguments)]
 pub async fn bulk_tickets(
     req: HttpRequest,
+    auth: AuthContext,
     pool: web::Data<crate::db::Pool>,
     storage: web::Data<std::sync::Arc<dyn crate::utils::storage::Storage>>,
     sse_state: web::Data<crate::handlers::sse::SseState>,
+    notification_service: web::Data<NotificationService>,
     search_service: web::Data<Arc<SearchService>>,
     body: web::Json<BulkActionRequest>,
 ) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
     // Extract claims and check authentication
     let claims = match req.extensions().get::<Claims>() {
         Some(claims) => claims.clone(),
@@ -1410,7 +1909,13 @@ pub async fn bulk_tickets(
         }));
     }
 
-    match action {
+    let mut update = TicketUpdate {
+        updated_at: Some(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    // Field name and value broadcast over SSE for each updated ticket
+    let (sse_key, sse_value) = match action {
         "delete" => {
             // Only admins can bulk delete
             if !is_admin(&claims) {
@@ -1434,7 +1939,7 @@ pub async fn bulk_tickets(
                 }
             }
 
-            HttpResponse::Ok().json(json!({ "affected": deleted }))
+            return HttpResponse::Ok().json(json!({ "affected": deleted }));
         }
 
         "set-status" => {
@@ -1446,7 +1951,8 @@ pub async fn bulk_tickets(
                 })),
             };
 
-            let status = match status_str {
+            // closed_at/closed_by are maintained by the repository
+            update.status = Some(match status_str {
                 "open" => crate::models::TicketStatus::Open,
                 "in-progress" => crate::models::TicketStatus::InProgress,
                 "closed" => crate::models::TicketStatus::Closed,
@@ -1454,39 +1960,8 @@ pub async fn bulk_tickets(
                     "error": "Bad Request",
                     "message": "Invalid status value"
                 })),
-            };
-
-            let mut updated = 0;
-            for id in ids {
-                let update = TicketUpdate {
-                    title: None,
-                    status: Some(status),
-                    priority: None,
-                    requester_uuid: None,
-                    assignee_uuid: None,
-                    updated_at: Some(chrono::Utc::now().naive_utc()),
-                    closed_at: if status == crate::models::TicketStatus::Closed {
-                        Some(Some(chrono::Utc::now().naive_utc()))
-                    } else {
-                        None
-                    },
-                    category_id: None,
-                };
-
-                if repository::update_ticket_partial(&mut conn, *id, update).is_ok() {
-                    updated += 1;
-                    // Send SSE update
-                    SseBroadcaster::broadcast_ticket_updated(
-                        &sse_state,
-                        *id,
-                        "status",
-                        json!(status_str),
-                        &claims.sub,
-                    ).await;
-                }
-            }
-
-            HttpResponse::Ok().json(json!({ "affected": updated }))
+            });
+            ("status", json!(status_str))
         }
 
         "set-priority" => {
@@ -1498,7 +1973,7 @@ pub async fn bulk_tickets(
                 })),
             };
 
-            let priority = match priority_str {
+            update.priority = Some(match priority_str {
                 "low" => crate::models::TicketPriority::Low,
                 "medium" => crate::models::TicketPriority::Medium,
                 "high" => crate::models::TicketPriority::High,
@@ -1506,35 +1981,8 @@ pub async fn bulk_tickets(
                     "error": "Bad Request",
                     "message": "Invalid priority value"
                 })),
-            };
-
-            let mut updated = 0;
-            for id in ids {
-                let update = TicketUpdate {
-                    title: None,
-                    status: None,
-                    priority: Some(priority),
-                    requester_uuid: None,
-                    assignee_uuid: None,
-                    updated_at: Some(chrono::Utc::now().naive_utc()),
-                    closed_at: None,
-                    category_id: None,
-                };
-
-                if repository::update_ticket_partial(&mut conn, *id, update).is_ok() {
-                    updated += 1;
-                    // Send SSE update
-                    SseBroadcaster::broadcast_ticket_updated(
-                        &sse_state,
-                        *id,
-                        "priority",
-                        json!(priority_str),
-                        &claims.sub,
-                    ).await;
-                }
-            }
-
-            HttpResponse::Ok().json(json!({ "affected": updated }))
+            });
+            ("priority", json!(priority_str))
         }
 
         "assign" => {
@@ -1558,39 +2006,656 @@ pub async fn bulk_tickets(
                 }
             };
 
-            let mut updated = 0;
-            for id in ids {
-                let update = TicketUpdate {
-                    title: None,
-                    status: None,
-                    priority: None,
-                    requester_uuid: None,
-                    assignee_uuid: Some(assignee_uuid),
-                    updated_at: Some(chrono::Utc::now().naive_utc()),
-                    closed_at: None,
-                    category_id: None,
-                };
+            update.assignee_uuid = Some(assignee_uuid);
+            ("assignee_uuid", json!(assignee_str))
+        }
+
+        _ => return HttpResponse::BadRequest().json(json!({
+            "error": "Bad Request",
+            "message": format!("Unknown action: {}", action)
+        })),
+    };
+
+    // Permission check per ticket before anything is written
+    let (targets, mut results) = match resolve_bulk_targets(&mut conn, &auth, ids) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            error!(error = ?e, "Failed to load tickets for bulk update");
+            return HttpResponse::InternalServerError().json("Failed to update tickets");
+        }
+    };
+
+    let target_ids: Vec<i32> = targets.iter().map(|t| t.id).collect();
+    let updated = match repository::bulk_update(&mut conn, &target_ids, update, Some(auth.user_uuid)) {
+        Ok(updated) => updated,
+        Err(e) => {
+            error!(error = ?e, "Bulk ticket update failed, no tickets were changed");
+            return HttpResponse::InternalServerError().json("Failed to update tickets");
+        }
+    };
+
+    let actor = repository::get_user_by_uuid(&auth.user_uuid, &mut conn)
+        .ok()
+        .map(|user| NotificationActor {
+            uuid: user.uuid,
+            name: user.name,
+            avatar_thumb: user.avatar_thumb,
+        });
+
+    for (old, ticket) in targets.iter().zip(&updated) {
+        results.push(BulkItemResult::updated(ticket.id));
+
+        SseBroadcaster::broadcast_ticket_updated(
+            &sse_state,
+            ticket.id,
+            sse_key,
+            sse_value.clone(),
+            &claims.sub,
+        ).await;
+
+        if let Some(actor) = &actor {
+            let status_recipients = status_change_recipients(&mut conn, ticket, actor.uuid);
+            spawn_ticket_change_notifications(notification_service.clone(), actor.clone(), old, ticket, status_recipients);
+        }
+
+        let article_content = repository::get_article_content_by_ticket_id(&mut conn, ticket.id).ok();
+        indexing_tasks::spawn_index_ticket(search_service.get_ref().clone(), ticket.clone(), article_content);
+    }
+
+    // Report results in request order
+    results.sort_by_key(|r| ids.iter().position(|id| *id == r.id));
+
+    HttpResponse::Ok().json(json!({
+        "affected": updated.len(),
+        "results": results,
+    }))
+}
+
+// Merge request body
+#[derive(Debug, Deserialize)]
+pub struct MergeTicketRequest {
+    pub target_id: i32,
+}
+
+// Merge a duplicate ticket into another ticket (technician/admin)
+#[allow(clippy::too_many_arguments)]
+pub async fn merge_ticket(
+    req: HttpRequest,
+    auth: AuthContext,
+    pool: web::Data<crate::db::Pool>,
+    sse_state: web::Data<crate::handlers::sse::SseState>,
+    notification_service: web::Data<NotificationService>,
+    search_service: web::Data<Arc<SearchService>>,
+    path: web::Path<i32>,
+    body: web::Json<MergeTicketRequest>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
+    if !auth.is_technician_or_admin() {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Only technicians can merge tickets"
+        }));
+    }
+
+    let source_id = path.into_inner();
+    let target_id = body.target_id;
+
+    let mut conn = match get_db_conn(&pool).await {
+        Ok(conn) => conn,
+        Err(e) => return e,
+    };
+
+    let merge = match repository::merge_ticket(&mut conn, source_id, target_id, Some(auth.user_uuid)) {
+        Ok(merge) => merge,
+        Err(repository::TicketMergeError::Update(repository::TicketUpdateError::Database(diesel::result::Error::NotFound))) => {
+            return HttpResponse::NotFound().json("Ticket not found");
+        }
+        Err(e @ (repository::TicketMergeError::SameTicket | repository::TicketMergeError::AlreadyMerged(_))) => {
+            return HttpResponse::BadRequest().json(json!({
+                "error": "Bad Request",
+                "message": e.to_string()
+            }));
+        }
+        Err(e) => {
+            error!(source_id, target_id, error = %e, "Failed to merge tickets");
+            return HttpResponse::InternalServerError().json("Failed to merge tickets");
+        }
+    };
+
+    info!(
+        source_id,
+        target_id,
+        moved_comments = merge.moved_comments.len(),
+        "Merged ticket"
+    );
+
+    let updated_by = auth.user_uuid.to_string();
+    SseBroadcaster::broadcast_ticket_updated(&sse_state, source_id, "status", json!("closed"), &updated_by).await;
+    SseBroadcaster::broadcast_ticket_updated(&sse_state, source_id, "merged_into_id", json!(target_id), &updated_by).await;
+    SseBroadcaster::broadcast_ticket_updated(
+        &sse_state,
+        target_id,
+        "modified",
+        json!(chrono::Utc::now()),
+        &updated_by,
+    ).await;
+
+    // The source's content now lives on the target
+    indexing_tasks::spawn_delete_ticket(search_service.get_ref().clone(), source_id);
+    let article_content = repository::get_article_content_by_ticket_id(&mut conn, target_id).ok();
+    indexing_tasks::spawn_index_ticket(search_service.get_ref().clone(), merge.target.clone(), article_content);
+    for comment in &merge.moved_comments {
+        indexing_tasks::spawn_index_comment(
+            search_service.get_ref().clone(),
+            comment.clone(),
+            merge.target.title.clone(),
+        );
+    }
+
+    // Notify everyone on either ticket, except whoever merged them
+    if let Ok(user) = repository::get_user_by_uuid(&auth.user_uuid, &mut conn) {
+        let actor = NotificationActor {
+            uuid: user.uuid,
+            name: user.name,
+            avatar_thumb: user.avatar_thumb,
+        };
+        // The source's watchers were moved onto the target by the merge
+        let mut recipients = Vec::new();
+        for ticket in [&merge.source, &merge.target] {
+            match repository::ticket_watchers::notification_recipients(&mut conn, ticket, Some(actor.uuid)) {
+                Ok(participants) => {
+                    for participant in participants {
+                        if !recipients.contains(&participant) {
+                            recipients.push(participant);
+                        }
+                    }
+                }
+                Err(e) => warn!(error = %e, ticket_id = ticket.id, "Failed to look up ticket watchers"),
+            }
+        }
+
+        let body = format!(
+            "Ticket #{} \"{}\" was merged into #{}",
+            merge.source.id, merge.source.title, merge.target.id
+        );
+        let target_title = merge.target.title.clone();
+        let notification_service = notification_service.clone();
+        crate::services::shutdown::notifications().spawn(async move {
+            for recipient in recipients {
+                let payload = NotificationPayload::new(
+                    NotificationTypeCode::TicketMerged,
+                    recipient,
+                    actor.clone(),
+                    NotificationEntity::Ticket {
+                        id: target_id,
+                        title: target_title.clone(),
+                    },
+                )
+                .with_body(&body);
+
+                if let Err(e) = notification_service.notify(payload).await {
+                    warn!(error = %e, recipient = %recipient, "Failed to send merge notification");
+                }
+            }
+        });
+    }
+
+    match repository::get_complete_ticket(&mut conn, target_id, true) {
+        Ok(complete_ticket) => HttpResponse::Ok().json(complete_ticket),
+        Err(_) => HttpResponse::Ok().json(merge.target),
+    }
+}
+
+// Legal hold request body
+#[derive(Debug, Deserialize)]
+pub struct LegalHoldRequest {
+    pub legal_hold: bool,
+}
+
+// Place a ticket on legal hold or release it (admin only). Held tickets keep
+// their attachments past the retention period.
+pub async fn set_ticket_legal_hold(
+    req: HttpRequest,
+    auth: AuthContext,
+    pool: web::Data<crate::db::Pool>,
+    path: web::Path<i32>,
+    body: web::Json<LegalHoldRequest>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
 
-                if repository::update_ticket_partial(&mut conn, *id, update).is_ok() {
-                    updated += 1;
-                    // Send SSE update
+    if !auth.is_admin() {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Only administrators can change legal holds"
+        }));
+    }
+
+    let ticket_id = path.into_inner();
+    let mut conn = match get_db_conn(&pool).await {
+        Ok(conn) => conn,
+        Err(e) => return e,
+    };
+
+    let ticket = match repository::tickets::set_legal_hold(&mut conn, ticket_id, body.legal_hold) {
+        Ok(ticket) => ticket,
+        Err(diesel::result::Error::NotFound) => return HttpResponse::NotFound().json("Ticket not found"),
+        Err(e) => {
+            error!(ticket_id, error = %e, "Failed to update legal hold");
+            return HttpResponse::InternalServerError().json("Failed to update legal hold");
+        }
+    };
+
+    let _ = audit::record(
+        &mut conn,
+        Some(auth.user_uuid),
+        AuditAction::LegalHoldChanged,
+        Some(AuditTarget::ticket(ticket_id)),
+        json!({ "legal_hold": ticket.legal_hold }),
+    );
+    info!(ticket_id, legal_hold = ticket.legal_hold, "Updated ticket legal hold");
+
+    HttpResponse::Ok().json(ticket)
+}
+
+// Reopen request body
+#[derive(Debug, Default, Deserialize)]
+pub struct ReopenTicketRequest {
+    /// Re-run assignment rules even if the ticket still has an assignee
+    #[serde(default)]
+    pub reassign: bool,
+}
+
+// Reopen a closed ticket (requester, assignee or staff). Tickets closed more
+// than TICKET_REOPEN_MAX_AGE_DAYS ago can only be reopened by an admin.
+// Unassigned tickets, or all of them with `reassign`, go through assignment
+// rules again.
+#[allow(clippy::too_many_arguments)]
+pub async fn reopen_ticket(
+    req: HttpRequest,
+    auth: AuthContext,
+    pool: web::Data<crate::db::Pool>,
+    sse_state: web::Data<crate::handlers::sse::SseState>,
+    notification_service: web::Data<NotificationService>,
+    search_service: web::Data<Arc<SearchService>>,
+    path: web::Path<i32>,
+    body: Option<web::Json<ReopenTicketRequest>>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
+    let ticket_id = path.into_inner();
+    let reassign = body.map(|b| b.reassign).unwrap_or_default();
+    let mut conn = match get_db_conn(&pool).await {
+        Ok(conn) => conn,
+        Err(e) => return e,
+    };
+
+    let closed = match repository::get_ticket_by_id(&mut conn, ticket_id) {
+        Ok(ticket) if auth.can_view_ticket(ticket.requester_uuid, ticket.assignee_uuid) => ticket,
+        _ => return HttpResponse::NotFound().json("Ticket not found"),
+    };
+
+    let max_age = (!auth.is_admin()).then(|| chrono::Duration::days(*REOPEN_MAX_AGE_DAYS));
+    let mut ticket = match repository::tickets::reopen_ticket(&mut conn, ticket_id, Some(auth.user_uuid), max_age) {
+        Ok(ticket) => ticket,
+        Err(repository::tickets::ReopenError::NotClosed) => {
+            return HttpResponse::Conflict().json(json!({
+                "error": "Ticket not closed",
+                "message": "Only closed tickets can be reopened"
+            }));
+        }
+        Err(repository::tickets::ReopenError::TooOld) => {
+            return HttpResponse::Forbidden().json(json!({
+                "error": "Reopen window passed",
+                "message": format!(
+                    "Tickets closed more than {} days ago can only be reopened by an administrator",
+                    *REOPEN_MAX_AGE_DAYS
+                )
+            }));
+        }
+        Err(e) => {
+            error!(ticket_id, error = %e, "Failed to reopen ticket");
+            return HttpResponse::InternalServerError().json("Failed to reopen ticket");
+        }
+    };
+    info!(ticket_id, user = %auth.user_uuid, "Reopened ticket");
+
+    let updated_by = auth.user_uuid.to_string();
+    SseBroadcaster::broadcast_ticket_updated(&sse_state, ticket_id, "status", json!("open"), &updated_by).await;
+
+    if reassign || ticket.assignee_uuid.is_none() {
+        let assigned = AssignmentEngine::evaluate_rules(&mut conn, &ticket, AssignmentTrigger::TicketReopened)
+            .and_then(|result| Some((result.assigned_user_uuid?, result.rule_name)))
+            .filter(|(assignee, _)| ticket.assignee_uuid != Some(*assignee));
+        if let Some((assignee_uuid, rule_name)) = assigned {
+            let update = TicketUpdate {
+                assignee_uuid: Some(Some(assignee_uuid)),
+                updated_at: Some(chrono::Utc::now().naive_utc()),
+                ..Default::default()
+            };
+            match repository::update_ticket_partial(&mut conn, ticket_id, update, None, None) {
+                Ok(updated) => {
+                    ticket = updated;
+                    info!(ticket_id, assignee = %assignee_uuid, rule = %rule_name, "Auto-assigned reopened ticket");
                     SseBroadcaster::broadcast_ticket_updated(
                         &sse_state,
-                        *id,
-                        "assignee_uuid",
-                        json!(assignee_str),
-                        &claims.sub,
-                    ).await;
+                        ticket_id,
+                        "assignee",
+                        json!(assignee_uuid.to_string()),
+                        "system",
+                    )
+                    .await;
                 }
+                Err(e) => warn!(ticket_id, error = %e, "Failed to auto-assign reopened ticket"),
             }
+        }
+    }
+
+    // Tell the requester, assignee and watchers (and any new assignee)
+    if let Ok(user) = repository::get_user_by_uuid(&auth.user_uuid, &mut conn) {
+        let actor = NotificationActor {
+            uuid: user.uuid,
+            name: user.name,
+            avatar_thumb: user.avatar_thumb,
+        };
+        let status_recipients = status_change_recipients(&mut conn, &ticket, actor.uuid);
+        spawn_ticket_change_notifications(notification_service.clone(), actor, &closed, &ticket, status_recipients);
+    }
+
+    let article_content = repository::get_article_content_by_ticket_id(&mut conn, ticket_id).ok();
+    indexing_tasks::spawn_index_ticket(search_service.get_ref().clone(), ticket.clone(), article_content);
+
+    match repository::get_complete_ticket(&mut conn, ticket_id, auth.is_technician_or_admin()) {
+        Ok(complete_ticket) => HttpResponse::Ok().json(complete_ticket),
+        Err(_) => HttpResponse::Ok().json(ticket),
+    }
+}
 
-            HttpResponse::Ok().json(json!({ "affected": updated }))
+// Watchers of a ticket
+pub async fn get_ticket_watchers(
+    req: HttpRequest,
+    pool: web::Data<crate::db::Pool>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:read") {
+        return e;
+    }
+
+    let ticket_id = path.into_inner();
+    let mut conn = match get_db_conn(&pool).await {
+        Ok(conn) => conn,
+        Err(e) => return e,
+    };
+
+    if let Err(e) = repository::get_ticket_by_id(&mut conn, ticket_id) {
+        return match e {
+            diesel::result::Error::NotFound => HttpResponse::NotFound().json("Ticket not found"),
+            _ => HttpResponse::InternalServerError().json("Failed to get ticket"),
+        };
+    }
+
+    match repository::ticket_watchers::list_watchers(&mut conn, ticket_id) {
+        Ok(watchers) => HttpResponse::Ok().json(
+            watchers
+                .into_iter()
+                .map(|user| crate::models::UserInfoWithAvatar {
+                    uuid: user.uuid,
+                    name: user.name,
+                    avatar_url: user.avatar_url,
+                    avatar_thumb: user.avatar_thumb,
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => {
+            error!(ticket_id, error = ?e, "Failed to list ticket watchers");
+            HttpResponse::InternalServerError().json("Failed to get ticket watchers")
         }
+    }
+}
 
-        _ => HttpResponse::BadRequest().json(json!({
-            "error": "Bad Request",
-            "message": format!("Unknown action: {}", action)
+// Watch request body; omitting the user watches the ticket yourself
+#[derive(Debug, Deserialize)]
+pub struct WatchTicketRequest {
+    pub user_uuid: Option<Uuid>,
+}
+
+// Start watching a ticket. Technicians/admins may add other users.
+pub async fn add_ticket_watcher(
+    req: HttpRequest,
+    auth: AuthContext,
+    pool: web::Data<crate::db::Pool>,
+    path: web::Path<i32>,
+    body: Option<web::Json<WatchTicketRequest>>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
+    let ticket_id = path.into_inner();
+    let watcher_uuid = body.and_then(|b| b.user_uuid).unwrap_or(auth.user_uuid);
+
+    if watcher_uuid != auth.user_uuid && !auth.is_technician_or_admin() {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Only technicians can add other users as watchers"
+        }));
+    }
+
+    let mut conn = match get_db_conn(&pool).await {
+        Ok(conn) => conn,
+        Err(e) => return e,
+    };
+
+    if let Err(e) = repository::get_ticket_by_id(&mut conn, ticket_id) {
+        return match e {
+            diesel::result::Error::NotFound => HttpResponse::NotFound().json("Ticket not found"),
+            _ => HttpResponse::InternalServerError().json("Failed to get ticket"),
+        };
+    }
+
+    match repository::ticket_watchers::add_watcher(&mut conn, ticket_id, watcher_uuid) {
+        Ok(added) => HttpResponse::Ok().json(json!({"success": true, "added": added})),
+        Err(diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            HttpResponse::BadRequest().json("User does not exist")
+        }
+        Err(e) => {
+            error!(ticket_id, user_uuid = %watcher_uuid, error = ?e, "Failed to add ticket watcher");
+            HttpResponse::InternalServerError().json("Failed to add ticket watcher")
+        }
+    }
+}
+
+// Stop a user watching a ticket. Users may remove themselves; technicians/admins anyone.
+pub async fn remove_ticket_watcher(
+    req: HttpRequest,
+    auth: AuthContext,
+    pool: web::Data<crate::db::Pool>,
+    path: web::Path<(i32, Uuid)>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+
+    let (ticket_id, watcher_uuid) = path.into_inner();
+
+    if watcher_uuid != auth.user_uuid && !auth.is_technician_or_admin() {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Only technicians can remove other watchers"
+        }));
+    }
+
+    let mut conn = match get_db_conn(&pool).await {
+        Ok(conn) => conn,
+        Err(e) => return e,
+    };
+
+    match repository::ticket_watchers::remove_watcher(&mut conn, ticket_id, watcher_uuid) {
+        Ok(removed) => HttpResponse::Ok().json(json!({"success": true, "removed": removed > 0})),
+        Err(e) => {
+            error!(ticket_id, user_uuid = %watcher_uuid, error = ?e, "Failed to remove ticket watcher");
+            HttpResponse::InternalServerError().json("Failed to remove ticket watcher")
+        }
+    }
+}
+
+// Time logged against a ticket (technicians/admins)
+pub async fn get_ticket_worklogs(
+    req: HttpRequest,
+    auth: AuthContext,
+    pool: web::Data<crate::db::Pool>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:read") {
+        return e;
+    }
+    if !auth.is_technician_or_admin() {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Only technicians can view worklogs"
+        }));
+    }
+
+    let ticket_id = path.into_inner();
+    let mut conn = match get_db_conn(&pool).await {
+        Ok(conn) => conn,
+        Err(e) => return e,
+    };
+
+    if let Err(e) = repository::get_ticket_by_id(&mut conn, ticket_id) {
+        return match e {
+            diesel::result::Error::NotFound => HttpResponse::NotFound().json("Ticket not found"),
+            _ => HttpResponse::InternalServerError().json("Failed to get ticket"),
+        };
+    }
+
+    let worklogs = repository::ticket_worklogs::list_worklogs(&mut conn, ticket_id);
+    let total = repository::ticket_worklogs::total_minutes_for_ticket(&mut conn, ticket_id);
+    match (worklogs, total) {
+        (Ok(worklogs), Ok(total_time_spent)) => HttpResponse::Ok().json(json!({
+            "worklogs": worklogs,
+            "total_time_spent": total_time_spent,
         })),
+        (Err(e), _) | (_, Err(e)) => {
+            error!(ticket_id, error = ?e, "Failed to list ticket worklogs");
+            HttpResponse::InternalServerError().json("Failed to get ticket worklogs")
+        }
+    }
+}
+
+// Worklog request body; `logged_at` defaults to now
+#[derive(Debug, Deserialize)]
+pub struct CreateWorklogRequest {
+    pub minutes: i32,
+    pub note: Option<String>,
+    pub logged_at: Option<chrono::NaiveDateTime>,
+}
+
+// Log time spent on a ticket (technicians/admins)
+pub async fn add_ticket_worklog(
+    req: HttpRequest,
+    auth: AuthContext,
+    pool: web::Data<crate::db::Pool>,
+    path: web::Path<i32>,
+    body: web::Json<CreateWorklogRequest>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+    if !auth.is_technician_or_admin() {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Only technicians can log time"
+        }));
+    }
+
+    let ticket_id = path.into_inner();
+    let body = body.into_inner();
+    if body.minutes <= 0 {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Invalid minutes",
+            "message": "Logged time must be at least one minute"
+        }));
+    }
+
+    let mut conn = match get_db_conn(&pool).await {
+        Ok(conn) => conn,
+        Err(e) => return e,
+    };
+
+    if let Err(e) = repository::get_ticket_by_id(&mut conn, ticket_id) {
+        return match e {
+            diesel::result::Error::NotFound => HttpResponse::NotFound().json("Ticket not found"),
+            _ => HttpResponse::InternalServerError().json("Failed to get ticket"),
+        };
+    }
+
+    let new_worklog = crate::models::NewTicketWorklog {
+        ticket_id,
+        user_uuid: auth.user_uuid,
+        minutes: body.minutes,
+        note: body.note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+        logged_at: body.logged_at.unwrap_or_else(|| chrono::Utc::now().naive_utc()),
+    };
+    match repository::ticket_worklogs::add_worklog(&mut conn, new_worklog) {
+        Ok(worklog) => HttpResponse::Created().json(worklog),
+        Err(e) => {
+            error!(ticket_id, user_uuid = %auth.user_uuid, error = ?e, "Failed to add ticket worklog");
+            HttpResponse::InternalServerError().json("Failed to add ticket worklog")
+        }
+    }
+}
+
+// Delete a worklog entry. Technicians may delete their own; admins any.
+pub async fn delete_ticket_worklog(
+    req: HttpRequest,
+    auth: AuthContext,
+    pool: web::Data<crate::db::Pool>,
+    path: web::Path<(i32, i32)>,
+) -> impl Responder {
+    if let Err(e) = require_scope(&req, "tickets:write") {
+        return e;
+    }
+    if !auth.is_technician_or_admin() {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Only technicians can delete worklogs"
+        }));
+    }
+
+    let (ticket_id, worklog_id) = path.into_inner();
+    let mut conn = match get_db_conn(&pool).await {
+        Ok(conn) => conn,
+        Err(e) => return e,
+    };
+
+    let worklog = match repository::ticket_worklogs::get_worklog(&mut conn, ticket_id, worklog_id) {
+        Ok(worklog) => worklog,
+        Err(diesel::result::Error::NotFound) => return HttpResponse::NotFound().json("Worklog not found"),
+        Err(e) => {
+            error!(ticket_id, worklog_id, error = ?e, "Failed to get ticket worklog");
+            return HttpResponse::InternalServerError().json("Failed to get ticket worklog");
+        }
+    };
+    if worklog.user_uuid != auth.user_uuid && !auth.is_admin() {
+        return HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": "Only admins can delete other users' worklogs"
+        }));
+    }
+
+    match repository::ticket_worklogs::delete_worklog(&mut conn, worklog_id) {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            error!(ticket_id, worklog_id, error = ?e, "Failed to delete ticket worklog");
+            HttpResponse::InternalServerError().json("Failed to delete ticket worklog")
+        }
     }
 }
 
@@ -1667,6 +2732,98 @@ mod tests {
         assert_eq!(claims.role, "admin");
     }
 
+    #[actix_web::test]
+    async fn read_only_token_is_rejected_from_write_handler() {
+        use crate::utils::rbac::TokenScopes;
+        use actix_web::dev::Service;
+
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let admin = TestFixtures::create_user(&mut conn, "scopedtokenadmin", UserRole::Admin);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Scoped Ticket", Some(admin.uuid), None);
+        let claims = create_test_claims(&admin);
+        let index_path = std::env::temp_dir().join(format!("nosdesk-scoped-token-{}", Uuid::new_v4()));
+        let search_service = Arc::new(SearchService::new(&index_path, &pool).unwrap());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .app_data(web::Data::new(search_service))
+                .wrap_fn(move |req, srv| {
+                    // Simulate dual_auth_middleware for a `tickets:read` API token
+                    req.extensions_mut().insert(claims.clone());
+                    req.extensions_mut()
+                        .insert(TokenScopes::new(vec!["tickets:read".to_string()]));
+                    srv.call(req)
+                })
+                .route("/tickets/{id}", web::put().to(update_ticket)),
+        )
+        .await;
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/tickets/{}", ticket.id))
+            .set_json(serde_json::json!({
+                "title": "Changed",
+                "status": "open",
+                "priority": "medium"
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let unchanged = repository::get_ticket_by_id(&mut conn, ticket.id).unwrap();
+        assert_eq!(unchanged.title, "Scoped Ticket");
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
+
+    #[actix_web::test]
+    async fn get_ticket_shows_internal_comments_only_to_technicians() {
+        use actix_web::dev::Service;
+
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let requester = TestFixtures::create_user(&mut conn, "internalviewrequester", UserRole::User);
+        let tech = TestFixtures::create_user(&mut conn, "internalviewtech", UserRole::Technician);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Internal View Ticket", Some(requester.uuid), None);
+        let public = TestFixtures::create_comment(&mut conn, ticket.id, tech.uuid, "Replacing the dock");
+        let internal = repository::comments::create_comment(&mut conn, crate::models::NewComment {
+            content: "Dock was dropped, not covered".to_string(),
+            ticket_id: ticket.id,
+            user_uuid: tech.uuid,
+            is_internal: true,
+        })
+        .unwrap();
+        drop(conn);
+
+        for (viewer, expected) in [(&requester, vec![public.id]), (&tech, vec![public.id, internal.id])] {
+            let claims = create_test_claims(viewer);
+            let app = test::init_service(
+                App::new()
+                    .app_data(web::Data::new(pool.clone()))
+                    .wrap_fn(move |req, srv| {
+                        req.extensions_mut().insert(claims.clone());
+                        srv.call(req)
+                    })
+                    .route("/tickets/{id}", web::get().to(get_ticket)),
+            )
+            .await;
+
+            let req = test::TestRequest::get()
+                .uri(&format!("/tickets/{}", ticket.id))
+                .to_request();
+            let body: Value = test::call_and_read_body_json(&app, req).await;
+            let mut ids: Vec<i64> = body["comments"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|c| c["id"].as_i64().unwrap())
+                .collect();
+            ids.sort();
+            assert_eq!(ids, expected.iter().map(|id| *id as i64).collect::<Vec<_>>());
+        }
+    }
+
     #[actix_web::test]
     async fn create_ticket_succeeds() {
         // This test verifies ticket creation via the repository layer directly
@@ -1699,7 +2856,7 @@ mod tests {
         let ticket = TestFixtures::create_ticket(&mut conn, "Get Me Ticket", Some(user.uuid), None);
 
         // Test via repository layer
-        let fetched = crate::repository::get_complete_ticket(&mut conn, ticket.id)
+        let fetched = crate::repository::get_complete_ticket(&mut conn, ticket.id, true)
             .expect("Should fetch ticket");
 
         assert_eq!(fetched.ticket.title, "Get Me Ticket");
@@ -1732,10 +2889,11 @@ mod tests {
             assignee_uuid: None,
             updated_at: Some(chrono::Utc::now().naive_utc()),
             closed_at: None,
+            closed_by: None,
             category_id: None,
         };
 
-        let updated = repository::update_ticket_partial(&mut conn, ticket.id, update)
+        let updated = repository::update_ticket_partial(&mut conn, ticket.id, update, None, Some(admin.uuid))
             .expect("Failed to update ticket");
 
         // Verify updates were applied
@@ -1811,10 +2969,266 @@ mod tests {
         assert_eq!(ticket.category_id, Some(category.id));
 
         // Fetch via repository
-        let fetched = crate::repository::get_complete_ticket(&mut conn, ticket.id)
+        let fetched = crate::repository::get_complete_ticket(&mut conn, ticket.id, true)
             .expect("Should fetch ticket");
 
         assert_eq!(fetched.ticket.category_id, Some(category.id));
         assert_eq!(fetched.ticket.title, "Categorized Ticket");
     }
+
+    #[actix_web::test]
+    async fn bulk_update_skips_tickets_user_cannot_see() {
+        let mut conn = crate::test_helpers::setup_test_connection();
+
+        let user = TestFixtures::create_user(&mut conn, "bulkregularuser", UserRole::User);
+        let other = TestFixtures::create_user(&mut conn, "bulkotheruser", UserRole::User);
+        let own = TestFixtures::create_ticket(&mut conn, "Bulk Own", Some(user.uuid), None);
+        let foreign = TestFixtures::create_ticket(&mut conn, "Bulk Foreign", Some(other.uuid), None);
+        let missing_id = foreign.id + 10_000;
+
+        let auth = AuthContext::test_context(user.uuid, UserRole::User, vec![]);
+        let ids = [foreign.id, own.id, missing_id, own.id];
+        let (targets, skipped) = resolve_bulk_targets(&mut conn, &auth, &ids).unwrap();
+
+        assert_eq!(targets.iter().map(|t| t.id).collect::<Vec<_>>(), vec![own.id]);
+        assert_eq!(
+            skipped,
+            vec![
+                BulkItemResult::skipped(foreign.id, "forbidden"),
+                BulkItemResult::skipped(missing_id, "not_found"),
+            ]
+        );
+
+        let update = TicketUpdate { status: Some(TicketStatus::Closed), ..Default::default() };
+        let target_ids: Vec<i32> = targets.iter().map(|t| t.id).collect();
+        let updated = repository::bulk_update(&mut conn, &target_ids, update, Some(user.uuid)).unwrap();
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].status, TicketStatus::Closed);
+
+        let untouched = repository::get_ticket_by_id(&mut conn, foreign.id).unwrap();
+        assert_eq!(untouched.status, TicketStatus::Open);
+    }
+
+    #[actix_web::test]
+    async fn group_manager_can_reassign_only_in_their_groups_categories() {
+        let mut conn = crate::test_helpers::setup_test_connection();
+
+        let lead = TestFixtures::create_user(&mut conn, "grouplead", UserRole::User);
+        let member = TestFixtures::create_user(&mut conn, "groupmember", UserRole::User);
+        let team = TestFixtures::create_group(&mut conn, "Lead Team");
+        let other_team = TestFixtures::create_group(&mut conn, "Other Team");
+        TestFixtures::add_user_to_group(&mut conn, lead.uuid, team.id);
+        TestFixtures::add_user_to_group(&mut conn, member.uuid, team.id);
+        repository::groups::set_group_managers(&mut conn, team.id, &[lead.uuid]).unwrap();
+
+        let own_category = TestFixtures::create_category(&mut conn, "Lead Team Category");
+        TestFixtures::set_category_visibility(&mut conn, own_category.id, &[team.id]);
+        let other_category = TestFixtures::create_category(&mut conn, "Other Team Category");
+        TestFixtures::set_category_visibility(&mut conn, other_category.id, &[other_team.id]);
+
+        let managed = repository::groups::get_managed_group_ids_for_user(&mut conn, &lead.uuid).unwrap();
+        assert_eq!(managed, vec![team.id]);
+        let lead_auth = AuthContext::test_context(lead.uuid, UserRole::User, vec![team.id])
+            .with_managed_groups(managed);
+
+        assert!(require_ticket_management(&lead_auth, &[Some(own_category.id)], &mut conn).is_ok());
+        let resp = require_ticket_management(&lead_auth, &[Some(other_category.id)], &mut conn).unwrap_err();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        // Moving a ticket out of their group's category is refused too
+        assert!(require_ticket_management(&lead_auth, &[Some(own_category.id), Some(other_category.id)], &mut conn).is_err());
+        assert!(require_ticket_management(&lead_auth, &[None], &mut conn).is_err());
+
+        // Plain members of the group get no elevated rights
+        let member_auth = AuthContext::test_context(member.uuid, UserRole::User, vec![team.id]);
+        assert!(require_ticket_management(&member_auth, &[Some(own_category.id)], &mut conn).is_err());
+    }
+
+    #[actix_web::test]
+    async fn bulk_update_is_all_or_nothing() {
+        let mut conn = crate::test_helpers::setup_test_connection();
+
+        let tech = TestFixtures::create_user(&mut conn, "bulkatomictech", UserRole::Technician);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Bulk Atomic", Some(tech.uuid), None);
+
+        let update = TicketUpdate { priority: Some(TicketPriority::High), ..Default::default() };
+        let result = repository::bulk_update(&mut conn, &[ticket.id, ticket.id + 10_000], update, Some(tech.uuid));
+        assert!(result.is_err());
+
+        let unchanged = repository::get_ticket_by_id(&mut conn, ticket.id).unwrap();
+        assert_eq!(unchanged.priority, TicketPriority::Medium);
+    }
+
+    #[actix_web::test]
+    async fn created_ticket_is_findable_via_search() {
+        use crate::services::search::{indexing_tasks, SearchQuery};
+        use actix_web::dev::Service;
+
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let tech = TestFixtures::create_user(&mut conn, "searchcreatetech", UserRole::Technician);
+        drop(conn);
+
+        let index_path = std::env::temp_dir().join(format!("nosdesk-create-search-{}", Uuid::new_v4()));
+        let search_service = Arc::new(SearchService::new(&index_path, &pool).unwrap());
+        indexing_tasks::spawn_commit_task(search_service.clone(), std::time::Duration::from_millis(20));
+
+        let claims = create_test_claims(&tech);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(crate::handlers::sse::SseState::new()))
+                .app_data(web::Data::new(NotificationService::new(pool.clone())))
+                .app_data(web::Data::new(search_service.clone()))
+                .wrap_fn(move |req, srv| {
+                    req.extensions_mut().insert(claims.clone());
+                    srv.call(req)
+                })
+                .route("/tickets", web::post().to(create_ticket)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/tickets")
+            .set_json(json!({
+                "title": "Quillaborn projector flickers",
+                "status": "open",
+                "priority": "medium",
+                "requester_uuid": tech.uuid,
+                "assignee_uuid": null,
+                "category_id": null
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let created: Value = test::read_body_json(resp).await;
+
+        // Indexing and the batched commit happen in the background
+        let query = SearchQuery {
+            q: "quillaborn".to_string(),
+            limit: Some(10),
+            types: Some("ticket".to_string()),
+        };
+        // The pool isn't rolled back, so earlier runs' tickets may match too
+        let created_id = created["id"].as_i64().unwrap();
+        let mut found = false;
+        for _ in 0..100 {
+            let hits = search_service.search(&query).unwrap().results;
+            if hits.iter().any(|hit| hit.entity_id == created_id) {
+                found = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        assert!(found);
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
+
+    #[actix_web::test]
+    async fn create_ticket_rejects_missing_required_fields() {
+        use crate::schema::ticket_categories;
+        use actix_web::dev::Service;
+        use diesel::prelude::*;
+
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let tech = TestFixtures::create_user(&mut conn, "requiredfieldstech", UserRole::Technician);
+        let hardware = TestFixtures::create_category(&mut conn, "Hardware");
+        let access = TestFixtures::create_category(&mut conn, "Access");
+        diesel::update(ticket_categories::table.find(hardware.id))
+            .set(ticket_categories::required_fields.eq(json!([
+                {"key": "device", "label": "Affected device", "kind": "device"},
+                {"key": "system_name", "label": "System name", "kind": "text"}
+            ])))
+            .execute(&mut conn)
+            .unwrap();
+        diesel::update(ticket_categories::table.find(access.id))
+            .set(ticket_categories::required_fields.eq(json!([
+                {"key": "system_name", "label": "System name", "kind": "text"}
+            ])))
+            .execute(&mut conn)
+            .unwrap();
+        repository::custom_fields::create_definition(
+            &mut conn,
+            crate::models::NewCustomFieldDefinition {
+                key: "system_name".to_string(),
+                label: "System name".to_string(),
+                field_type: "text".to_string(),
+                options: json!([]),
+                category_id: None,
+                display_order: 0,
+                created_by: None,
+            },
+        )
+        .unwrap();
+        drop(conn);
+
+        let index_path = std::env::temp_dir().join(format!("nosdesk-required-fields-{}", Uuid::new_v4()));
+        let search_service = Arc::new(SearchService::new(&index_path, &pool).unwrap());
+        let claims = create_test_claims(&tech);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(crate::handlers::sse::SseState::new()))
+                .app_data(web::Data::new(NotificationService::new(pool.clone())))
+                .app_data(web::Data::new(search_service))
+                .wrap_fn(move |req, srv| {
+                    req.extensions_mut().insert(claims.clone());
+                    srv.call(req)
+                })
+                .route("/tickets", web::post().to(create_ticket)),
+        )
+        .await;
+        let new_ticket = |category_id: i32, custom_fields: Value| {
+            json!({
+                "title": "Needs access",
+                "status": "open",
+                "priority": "medium",
+                "requester_uuid": tech.uuid,
+                "assignee_uuid": null,
+                "category_id": category_id,
+                "custom_fields": custom_fields
+            })
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/tickets")
+            .set_json(new_ticket(hardware.id, json!({"system_name": " "})))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body: Value = test::read_body_json(resp).await;
+        let fields: Vec<&str> = body["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["field"].as_str().unwrap())
+            .collect();
+        assert_eq!(fields, vec!["device", "system_name"]);
+
+        let req = test::TestRequest::post()
+            .uri("/tickets")
+            .set_json(new_ticket(access.id, json!({"system_name": "Payroll"})))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let created: Value = test::read_body_json(resp).await;
+        let mut conn = pool.get().unwrap();
+        let values = repository::custom_fields::get_values(&mut conn, created["id"].as_i64().unwrap() as i32).unwrap();
+        let values: Vec<(&str, &str)> = values.iter().map(|v| (v.key.as_str(), v.value.as_str())).collect();
+        assert_eq!(values, vec![("system_name", "Payroll")]);
+
+        // Values for undefined fields are rejected
+        let req = test::TestRequest::post()
+            .uri("/tickets")
+            .set_json(new_ticket(access.id, json!({"system_name": "Payroll", "colour": "red"})))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["fields"][0]["field"], "custom_fields.colour");
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
 }
@@ -15,10 +15,21 @@ use crate::repository;
 use crate::repository::user_emails as user_emails_repo;
 use crate::utils;
 use crate::utils::email_branding::get_email_branding;
+use crate::utils::file_validation::UploadContext;
 use crate::db::DbConnection;
+use crate::services::audit::{self, AuditAction, AuditTarget};
 use crate::services::search::SearchService;
 use crate::services::search::indexing_tasks;
 
+/// A role as stored and broadcast ("admin", "technician", "user")
+fn role_name(role: crate::models::UserRole) -> &'static str {
+    match role {
+        crate::models::UserRole::Admin => "admin",
+        crate::models::UserRole::Technician => "technician",
+        crate::models::UserRole::User => "user",
+    }
+}
+
 /// Result type for invitation sending operations
 pub enum SendInvitationResult {
     Success,
@@ -450,8 +461,10 @@ pub async fn create_user(
         .with_microsoft_uuid(user_data.microsoft_uuid)
         .build_with_email();
 
-    // Email starts as unverified - will be verified when user accepts invitation or verifies email
-    match repository::user_helpers::create_user_with_email(new_user, email.clone(), false, Some("manual".to_string()), &mut conn) {
+    // An admin who sets the password vouches for the address, as with self-registration.
+    // Invited users' email starts unverified and is verified when they accept the invitation.
+    let email_verified = user_data.password.is_some();
+    match repository::user_helpers::create_user_with_email(new_user, email.clone(), email_verified, Some("manual".to_string()), &mut conn) {
         Ok((user, _email_entry)) => {
             use bcrypt::hash;
             use crate::models::NewUserAuthIdentity;
@@ -594,6 +607,9 @@ pub async fn create_user(
 pub struct DeleteUserRequest {
     pub mfa_code: Option<String>,
     pub password: Option<String>,
+    /// Group whose technicians take over the user's open tickets; without
+    /// one they go back to the unassigned queue
+    pub reassign_group_id: Option<i32>,
 }
 
 pub async fn delete_user(
@@ -760,10 +776,24 @@ pub async fn delete_user(
         }));
     }
 
-    // Delete the user
-    match repository::delete_user(&target_user.uuid, &mut conn) {
-        Ok(count) if count > 0 => {
-            info!("User deleted successfully: {} (uuid={})", target_user.name, target_user.uuid);
+    // Users with history are deactivated rather than deleted, so nothing
+    // attributed to them is orphaned
+    match repository::deactivate_or_delete_user(&mut conn, &target_user.uuid, body.reassign_group_id, Some(admin_uuid)) {
+        Ok(removal) => {
+            if let repository::UserRemoval::Deactivated { reassigned_tickets } = &removal {
+                info!(
+                    "User deactivated: {} (uuid={}), {} open tickets reassigned",
+                    target_user.name, target_user.uuid, reassigned_tickets.len()
+                );
+                for &ticket_id in reassigned_tickets {
+                    if let Ok(ticket) = repository::get_ticket_by_id(&mut conn, ticket_id) {
+                        let article_content = repository::get_article_content_by_ticket_id(&mut conn, ticket_id).ok();
+                        indexing_tasks::spawn_index_ticket(search_service.get_ref().clone(), ticket, article_content);
+                    }
+                }
+            } else {
+                info!("User deleted successfully: {} (uuid={})", target_user.name, target_user.uuid);
+            }
 
             // Remove user from search index
             indexing_tasks::spawn_delete_user(search_service.get_ref().clone(), user_uuid.clone());
@@ -776,7 +806,7 @@ pub async fn delete_user(
 
             HttpResponse::NoContent().finish()
         },
-        Ok(_) => HttpResponse::NotFound().json(json!({
+        Err(diesel::result::Error::NotFound) => HttpResponse::NotFound().json(json!({
             "status": "error",
             "message": "User not found"
         })),
@@ -790,6 +820,66 @@ pub async fn delete_user(
     }
 }
 
+// Request body for setting the timezone date filters use
+#[derive(Debug, Deserialize)]
+pub struct UpdateTimezoneRequest {
+    /// IANA zone such as `Europe/Berlin`; null goes back to the default
+    pub timezone: Option<String>,
+}
+
+// Set the current user's timezone for calendar date filters
+pub async fn update_user_timezone(
+    req: HttpRequest,
+    pool: web::Data<crate::db::Pool>,
+    body: web::Json<UpdateTimezoneRequest>,
+) -> impl Responder {
+    let claims = match req.extensions().get::<crate::models::Claims>() {
+        Some(claims) => claims.clone(),
+        None => return HttpResponse::Unauthorized().json(json!({
+            "status": "error",
+            "message": "Authentication required"
+        })),
+    };
+    let user_uuid = match utils::parse_uuid(&claims.sub) {
+        Ok(uuid) => uuid,
+        Err(_) => return HttpResponse::BadRequest().json(json!({
+            "status": "error",
+            "message": "Invalid UUID in token"
+        })),
+    };
+
+    // Store the canonical name so later lookups can't disagree with validation
+    let timezone = match body.timezone.as_deref().map(str::trim).filter(|tz| !tz.is_empty()) {
+        Some(name) => match utils::timezone::parse(name) {
+            Some(tz) => Some(tz.name().to_string()),
+            None => return HttpResponse::BadRequest().json(json!({
+                "status": "error",
+                "message": format!("Unknown timezone: {name}")
+            })),
+        },
+        None => None,
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": "Database connection error"
+        })),
+    };
+
+    match repository::users::update_user_timezone(&mut conn, &user_uuid, timezone.as_deref()) {
+        Ok(user) => HttpResponse::Ok().json(json!({ "timezone": user.timezone })),
+        Err(e) => {
+            error!(error = ?e, "Failed to update user timezone");
+            HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": "Failed to update timezone"
+            }))
+        }
+    }
+}
+
 // Get user's authentication identities
 pub async fn get_user_auth_identities(
     db_pool: web::Data<crate::db::Pool>,
@@ -1145,11 +1235,13 @@ pub async fn upload_user_image(
         let content_type = field.content_type().map(|ct| ct.to_string()).unwrap_or_else(|| "application/octet-stream".to_string());
         debug!(content_type = %content_type, "Content type");
         
-        // Validate content type (only allow images)
-        if !content_type.starts_with("image/") {
+        // Validate content type against the avatar upload policy
+        let policy = UploadContext::Avatar.policy();
+        if let Err(e) = policy.validate(None, Some(&content_type), 0) {
+            warn!(error = %e, "Rejected image upload by upload policy");
             return HttpResponse::BadRequest().json(json!({
                 "status": "error",
-                "message": "Only image files are allowed"
+                "message": e.to_string()
             }));
         }
         
@@ -1198,9 +1290,34 @@ pub async fn upload_user_image(
                     }));
                 }
             };
+            if let Err(e) = policy.check_size(file_data.len() + data.len()) {
+                return HttpResponse::PayloadTooLarge().json(json!({
+                    "status": "error",
+                    "message": e.to_string()
+                }));
+            }
             file_data.extend_from_slice(&data);
         }
-        
+
+        // SECURITY: The declared image type must match the actual content
+        match crate::utils::file_validation::verify_declared_mime(&file_data, Some(&content_type)) {
+            Ok(Some(detected)) if matches!(detected.as_str(), "image/jpeg" | "image/png" | "image/gif" | "image/webp") => {}
+            Ok(detected) => {
+                warn!(declared = %content_type, detected = ?detected, "Rejected image upload with unsupported content");
+                return HttpResponse::BadRequest().json(json!({
+                    "status": "error",
+                    "message": "Uploaded file is not a supported image. Allowed: JPEG, PNG, GIF, WEBP"
+                }));
+            }
+            Err(e) => {
+                warn!(error = %e, "Rejected image upload with mismatched content");
+                return HttpResponse::BadRequest().json(json!({
+                    "status": "error",
+                    "message": e.to_string()
+                }));
+            }
+        }
+
         // Process the image based on type
         let (final_url, thumbnail_url) = if image_type == "avatar" {
             // For avatars, process and resize to WebP format with fixed dimensions (200x200 max)
@@ -1718,11 +1835,16 @@ pub async fn update_user_by_uuid(
                 ).await;
             }
             if user_data.role.is_some() {
-                let role_str = match updated_user.role {
-                    crate::models::UserRole::Admin => "admin",
-                    crate::models::UserRole::Technician => "technician",
-                    crate::models::UserRole::User => "user",
-                };
+                let role_str = role_name(updated_user.role);
+                if updated_user.role != user.role {
+                    let _ = audit::record(
+                        &mut conn,
+                        Uuid::parse_str(&claims.sub).ok(),
+                        AuditAction::RoleChanged,
+                        Some(AuditTarget::user(updated_user.uuid)),
+                        json!({ "from": role_name(user.role), "to": role_str }),
+                    );
+                }
                 crate::utils::sse::SseBroadcaster::broadcast_user_updated(
                     &sse_state,
                     &user_uuid,
@@ -1927,11 +2049,17 @@ pub async fn add_user_email(
         .values(&new_email)
         .get_result::<crate::models::UserEmail>(&mut conn)
     {
-        Ok(created_email) => HttpResponse::Created().json(json!({
-            "status": "success",
-            "message": "Email added successfully",
-            "email": created_email
-        })),
+        Ok(created_email) => {
+            // Unverified until the link is used; it can be resent later
+            let verification_sent =
+                super::email_verification::send_verification_email(&mut conn, &user, &created_email).await;
+            HttpResponse::Created().json(json!({
+                "status": "success",
+                "message": "Email added. Check the inbox for a verification link.",
+                "email": created_email,
+                "verification_sent": verification_sent
+            }))
+        }
         Err(e) => {
             error!(error = ?e, "Error adding email");
             HttpResponse::InternalServerError().json(json!({
@@ -1991,26 +2119,82 @@ pub async fn update_user_email(
         })),
     };
 
-    // If setting as primary, unset other primary emails first
-    if update_data.get("is_primary").and_then(|p| p.as_bool()).unwrap_or(false) {
-        use diesel::prelude::*;
-        let _ = diesel::update(crate::schema::user_emails::table)
-            .filter(crate::schema::user_emails::user_uuid.eq(&user.uuid))
-            .set(crate::schema::user_emails::is_primary.eq(false))
-            .execute(&mut conn);
+    let make_primary = update_data.get("is_primary").and_then(|p| p.as_bool());
+    let verified = update_data.get("is_verified").and_then(|v| v.as_bool());
+
+    // Users prove ownership through the emailed link instead
+    if verified.is_some() && claims.role != "admin" {
+        return HttpResponse::Forbidden().json(json!({
+            "status": "error",
+            "message": "Only administrators can change an email's verification status"
+        }));
     }
 
-    // Update the email
-    let email_update = crate::models::UserEmailUpdate {
-        is_primary: update_data.get("is_primary").and_then(|p| p.as_bool()),
-        is_verified: update_data.get("is_verified").and_then(|v| v.as_bool()),
-        updated_at: Some(chrono::Utc::now().naive_utc()),
-    };
+    let owned = user_emails_repo::get_user_emails_by_uuid(&mut conn, &user.uuid)
+        .map(|emails| emails.iter().any(|email| email.id == email_id));
+    match owned {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json(json!({
+            "status": "error",
+            "message": "Email not found"
+        })),
+        Err(e) => {
+            error!(error = ?e, "Error loading user emails");
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": "Failed to update email"
+            }));
+        }
+    }
 
-    use diesel::prelude::*;
-    match diesel::update(crate::schema::user_emails::table.find(email_id))
-        .set(&email_update)
-        .get_result::<crate::models::UserEmail>(&mut conn)
+    // Apply the verification change first so an admin can verify and promote at once
+    if verified.is_some() || make_primary == Some(false) {
+        let email_update = crate::models::UserEmailUpdate {
+            is_primary: make_primary.filter(|primary| !primary),
+            is_verified: verified,
+            updated_at: Some(chrono::Utc::now().naive_utc()),
+        };
+
+        if let Err(e) = diesel::update(crate::schema::user_emails::table.find(email_id))
+            .set(&email_update)
+            .execute(&mut conn)
+        {
+            error!(error = ?e, "Error updating email");
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": "Failed to update email"
+            }));
+        }
+    }
+
+    if make_primary == Some(true) {
+        match user_emails_repo::set_primary(&mut conn, &user.uuid, email_id) {
+            Ok(_) => {}
+            Err(user_emails_repo::SetPrimaryError::Unverified) => {
+                return HttpResponse::BadRequest().json(json!({
+                    "status": "error",
+                    "message": "Verify this email address before making it primary"
+                }))
+            }
+            Err(user_emails_repo::SetPrimaryError::NotFound) => {
+                return HttpResponse::NotFound().json(json!({
+                    "status": "error",
+                    "message": "Email not found"
+                }))
+            }
+            Err(user_emails_repo::SetPrimaryError::Database(e)) => {
+                error!(error = ?e, "Error setting primary email");
+                return HttpResponse::InternalServerError().json(json!({
+                    "status": "error",
+                    "message": "Failed to update email"
+                }));
+            }
+        }
+    }
+
+    match crate::schema::user_emails::table
+        .find(email_id)
+        .first::<crate::models::UserEmail>(&mut conn)
     {
         Ok(updated_email) => HttpResponse::Ok().json(json!({
             "status": "success",
@@ -2120,6 +2304,163 @@ pub async fn delete_user_email(
     }
 }
 
+/// Clear an address's bounce/complaint flags once the user has fixed their
+/// mailbox, so notification email resumes (admin only)
+pub async fn clear_user_email_delivery_flags(
+    db_pool: web::Data<crate::db::Pool>,
+    req: HttpRequest,
+    path: web::Path<(String, i32)>,
+) -> impl Responder {
+    let (user_uuid, email_id) = path.into_inner();
+    let mut conn = match db_pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": "Database connection failed"
+        })),
+    };
+
+    let claims = match crate::utils::jwt::JwtUtils::extract_claims(&req) {
+        Ok(claims) => claims,
+        Err(_) => return HttpResponse::Unauthorized().json(json!({
+            "status": "error",
+            "message": "Authentication required"
+        })),
+    };
+
+    if claims.role != "admin" {
+        return HttpResponse::Forbidden().json(json!({
+            "status": "error",
+            "message": "Only administrators can clear email delivery flags"
+        }));
+    }
+
+    let uuid_parsed = match utils::parse_uuid(&user_uuid) {
+        Ok(uuid) => uuid,
+        Err(_) => return HttpResponse::BadRequest().json(json!({
+            "status": "error",
+            "message": "Invalid UUID format"
+        })),
+    };
+
+    let email: crate::models::UserEmail = match crate::schema::user_emails::table
+        .find(email_id)
+        .first(&mut conn)
+    {
+        Ok(email) => email,
+        Err(_) => return HttpResponse::NotFound().json(json!({
+            "status": "error",
+            "message": "Email not found"
+        })),
+    };
+
+    if email.user_uuid != uuid_parsed {
+        return HttpResponse::Forbidden().json(json!({
+            "status": "error",
+            "message": "Email does not belong to this user"
+        }));
+    }
+
+    match user_emails_repo::clear_delivery_flags(&mut conn, email_id) {
+        Ok(updated_email) => {
+            info!(email_id, admin = %claims.sub, "Cleared email delivery flags");
+            HttpResponse::Ok().json(json!({
+                "status": "success",
+                "message": "Email delivery flags cleared",
+                "email": updated_email
+            }))
+        }
+        Err(e) => {
+            error!(error = ?e, "Error clearing email delivery flags");
+            HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": "Failed to clear email delivery flags"
+            }))
+        }
+    }
+}
+
+/// Unlock an account locked by failed password logins (admin only)
+pub async fn unlock_user_account(
+    db_pool: web::Data<crate::db::Pool>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_uuid = path.into_inner();
+    let mut conn = match db_pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": "Database connection failed"
+        })),
+    };
+
+    let claims = match crate::utils::jwt::JwtUtils::extract_claims(&req) {
+        Ok(claims) => claims,
+        Err(_) => return HttpResponse::Unauthorized().json(json!({
+            "status": "error",
+            "message": "Authentication required"
+        })),
+    };
+
+    if claims.role != "admin" {
+        return HttpResponse::Forbidden().json(json!({
+            "status": "error",
+            "message": "Only administrators can unlock accounts"
+        }));
+    }
+
+    let uuid_parsed = match utils::parse_uuid(&user_uuid) {
+        Ok(uuid) => uuid,
+        Err(_) => return HttpResponse::BadRequest().json(json!({
+            "status": "error",
+            "message": "Invalid UUID format"
+        })),
+    };
+
+    if repository::get_user_by_uuid(&uuid_parsed, &mut conn).is_err() {
+        return HttpResponse::NotFound().json(json!({
+            "status": "error",
+            "message": "User not found"
+        }));
+    }
+
+    let was_locked = match repository::account_lockouts::clear(&mut conn, &uuid_parsed, chrono::Utc::now().naive_utc()) {
+        Ok(was_locked) => was_locked,
+        Err(e) => {
+            error!(error = ?e, "Error unlocking account");
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": "Failed to unlock account"
+            }));
+        }
+    };
+
+    // Accounts without a local password are counted per email in Redis
+    let redis_url = utils::rate_limit::get_redis_url();
+    for email in user_emails_repo::get_user_emails_by_uuid(&mut conn, &uuid_parsed).unwrap_or_default() {
+        let key = utils::rate_limit::RateLimiter::login_attempt_key(&email.email);
+        if let Err(e) = utils::rate_limit::RateLimiter::clear_attempts(&redis_url, &key).await {
+            warn!(error = %e, "Failed to clear login attempts while unlocking account");
+        }
+    }
+
+    let _ = audit::record(
+        &mut conn,
+        Uuid::parse_str(&claims.sub).ok(),
+        AuditAction::AccountUnlocked,
+        Some(AuditTarget::user(uuid_parsed)),
+        json!({ "was_locked": was_locked }),
+    );
+    info!(user_uuid = %uuid_parsed, admin = %claims.sub, was_locked, "Account unlocked");
+
+    HttpResponse::Ok().json(json!({
+        "status": "success",
+        "message": if was_locked { "Account unlocked" } else { "Account was not locked" },
+        "was_locked": was_locked
+    }))
+}
+
 /// Resend invitation email to a user who hasn't set up their account yet
 pub async fn resend_invitation(
     db_pool: web::Data<crate::db::Pool>,
@@ -2457,6 +2798,7 @@ pub async fn bulk_users(
                     Err(_) => continue,
                 };
 
+                let previous_role = repository::get_user_by_uuid(&uuid, &mut conn).ok().map(|u| u.role);
                 let user_update = crate::models::UserUpdate {
                     name: None,
                     role: Some(role),
@@ -2471,6 +2813,15 @@ pub async fn bulk_users(
 
                 if repository::update_user(&uuid, user_update, &mut conn).is_ok() {
                     updated += 1;
+                    if previous_role != Some(role) {
+                        let _ = audit::record(
+                            &mut conn,
+                            Uuid::parse_str(&claims.sub).ok(),
+                            AuditAction::RoleChanged,
+                            Some(AuditTarget::user(uuid)),
+                            json!({ "from": previous_role.map(role_name), "to": role_str, "bulk": true }),
+                        );
+                    }
                     // Broadcast SSE event
                     crate::utils::sse::SseBroadcaster::broadcast_user_updated(
                         &sse_state,
@@ -2490,4 +2841,48 @@ pub async fn bulk_users(
             "message": format!("Unknown action: {}", action)
         })),
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::sse::SseState;
+    use crate::test_helpers::setup_test_pool;
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn admin_created_user_with_password_has_verified_primary_email() {
+        let pool = setup_test_pool();
+        let index_path = std::env::temp_dir().join(format!("nosdesk-create-user-{}", Uuid::new_v4()));
+        let search_service = Arc::new(SearchService::new(&index_path, &pool).unwrap());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(SseState::new()))
+                .app_data(web::Data::new(search_service))
+                .route("/users", web::post().to(create_user)),
+        )
+        .await;
+        let req = test::TestRequest::post()
+            .uri("/users")
+            .set_json(json!({
+                "name": "Admin Created",
+                "email": "admin-created@example.com",
+                "role": "user",
+                "password": "correct horse battery"
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let mut conn = pool.get().unwrap();
+        let user = repository::get_user_by_email("admin-created@example.com", &mut conn).unwrap();
+        let emails = user_emails_repo::get_user_emails_by_uuid(&mut conn, &user.uuid).unwrap();
+        let primary = emails.iter().find(|e| e.is_primary).unwrap();
+        // Notification email only goes to verified addresses
+        assert!(primary.is_verified);
+
+        let _ = std::fs::remove_dir_all(index_path);
+    }
+}
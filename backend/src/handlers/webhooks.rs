@@ -8,14 +8,20 @@ use serde::Deserialize;
 use tracing::{error, info};
 use uuid::Uuid;
 
+use crate::config_utils::{require_feature, Feature, FeatureFlags};
 use crate::db::{DbConnection, Pool};
 use crate::models::{
     Claims, CreateWebhookRequest, UpdateWebhookRequest, WebhookCreatedResponse,
     WebhookDeliveryResponse, WebhookResponse, WebhookUpdate,
 };
-use crate::repository::webhooks as webhook_repo;
+use crate::repository::ticket_query::PaginatedResult;
+use crate::repository::{self, webhooks as webhook_repo};
+use crate::services::audit::{self, AuditAction, AuditTarget};
+use crate::services::webhooks::signature::seal_secret;
 use crate::services::webhooks::{generate_secret, WebhookEventType, WebhookService};
-use crate::utils::rbac::require_admin;
+use crate::utils::encryption::open_secret;
+use crate::utils::permissions::Permission;
+use crate::utils::rbac::{require_permission, require_scope};
 
 /// Query parameters for pagination
 #[derive(Debug, Deserialize)]
@@ -36,6 +42,12 @@ fn get_connection(pool: &web::Data<Pool>) -> Result<DbConnection, HttpResponse>
     })
 }
 
+/// Response for a secret that couldn't be encrypted
+fn encryption_error(e: anyhow::Error) -> HttpResponse {
+    error!("Failed to encrypt webhook secret: {}", e);
+    HttpResponse::InternalServerError().json("Failed to encrypt secret. Ensure ENCRYPTION_KEY is configured.")
+}
+
 /// Validate webhook name
 fn validate_name(name: &str) -> Result<String, HttpResponse> {
     let trimmed = name.trim();
@@ -72,9 +84,19 @@ fn validate_events(events: &[String]) -> Result<(), HttpResponse> {
 // Handlers
 // =============================================================================
 
-/// List all webhooks (admin only)
-pub async fn list_webhooks(req: HttpRequest, pool: web::Data<Pool>) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+/// List all webhooks (requires manage_webhooks)
+pub async fn list_webhooks(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
+) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Webhooks) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManageWebhooks) {
+        return e;
+    }
+    if let Err(e) = require_scope(&req, "webhooks:read") {
         return e;
     }
 
@@ -95,13 +117,20 @@ pub async fn list_webhooks(req: HttpRequest, pool: web::Data<Pool>) -> impl Resp
     }
 }
 
-/// Create a new webhook (admin only)
+/// Create a new webhook (requires manage_webhooks)
 pub async fn create_webhook(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     body: web::Json<CreateWebhookRequest>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Webhooks) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManageWebhooks) {
+        return e;
+    }
+    if let Err(e) = require_scope(&req, "webhooks:write") {
         return e;
     }
 
@@ -130,16 +159,33 @@ pub async fn create_webhook(
     };
 
     let secret = generate_secret();
+    let sealed_secret = match seal_secret(&secret) {
+        Ok(sealed) => sealed,
+        Err(e) => return encryption_error(e),
+    };
 
-    match webhook_repo::create_webhook(
-        &mut conn,
-        name,
-        body.url.clone(),
-        secret.clone(),
-        body.events.clone(),
-        body.headers.clone(),
-        created_by,
-    ) {
+    // Creating a webhook writes a secret, so it's audited; both or neither
+    let result = repository::with_transaction(&mut conn, |conn| {
+        let webhook = webhook_repo::create_webhook(
+            conn,
+            name,
+            body.url.clone(),
+            sealed_secret,
+            body.events.clone(),
+            body.headers.clone(),
+            created_by,
+        )?;
+        audit::record(
+            conn,
+            created_by,
+            AuditAction::WebhookCreated,
+            Some(AuditTarget::webhook(webhook.uuid)),
+            serde_json::json!({ "name": webhook.name }),
+        )?;
+        Ok::<_, DieselError>(webhook)
+    });
+
+    match result {
         Ok(webhook) => {
             info!(
                 "Webhook created: {} ({}) by {:?}",
@@ -161,21 +207,34 @@ pub async fn create_webhook(
 }
 
 /// Get available event types
-pub async fn get_event_types(req: HttpRequest) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+pub async fn get_event_types(req: HttpRequest, flags: web::Data<FeatureFlags>) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Webhooks) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManageWebhooks) {
+        return e;
+    }
+    if let Err(e) = require_scope(&req, "webhooks:read") {
         return e;
     }
 
     HttpResponse::Ok().json(WebhookEventType::all())
 }
 
-/// Get a single webhook by UUID (admin only)
+/// Get a single webhook by UUID (requires manage_webhooks)
 pub async fn get_webhook(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<Uuid>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Webhooks) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManageWebhooks) {
+        return e;
+    }
+    if let Err(e) = require_scope(&req, "webhooks:read") {
         return e;
     }
 
@@ -196,14 +255,22 @@ pub async fn get_webhook(
     }
 }
 
-/// Update a webhook (admin only)
+/// Update a webhook (requires manage_webhooks)
 pub async fn update_webhook(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<Uuid>,
     body: web::Json<UpdateWebhookRequest>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Webhooks) {
+        return e;
+    }
+    let claims = match require_permission(&req, Permission::ManageWebhooks) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+    if let Err(e) = require_scope(&req, "webhooks:write") {
         return e;
     }
 
@@ -247,9 +314,13 @@ pub async fn update_webhook(
         update.events = Some(events.iter().map(|e| Some(e.clone())).collect());
     }
 
-    // Regenerate secret if requested
-    if body.regenerate_secret == Some(true) {
-        update.secret = Some(generate_secret());
+    // Regenerate secret if requested; the old one stops signing immediately
+    let regenerate_secret = body.regenerate_secret == Some(true);
+    if regenerate_secret {
+        match seal_secret(&generate_secret()) {
+            Ok(sealed) => update.secret = Some(sealed),
+            Err(e) => return encryption_error(e),
+        }
     }
 
     // Reset failure count if re-enabling
@@ -261,6 +332,15 @@ pub async fn update_webhook(
     match webhook_repo::update_webhook_by_uuid(&mut conn, webhook_uuid, update) {
         Ok(webhook) => {
             info!("Webhook updated: {} ({})", webhook.uuid, webhook.name);
+            if regenerate_secret {
+                let _ = audit::record(
+                    &mut conn,
+                    Uuid::parse_str(&claims.sub).ok(),
+                    AuditAction::WebhookSecretRegenerated,
+                    Some(AuditTarget::webhook(webhook.uuid)),
+                    serde_json::json!({ "name": webhook.name }),
+                );
+            }
             HttpResponse::Ok().json(WebhookResponse::from(webhook))
         }
         Err(DieselError::NotFound) => HttpResponse::NotFound().json("Webhook not found"),
@@ -271,13 +351,20 @@ pub async fn update_webhook(
     }
 }
 
-/// Delete a webhook (admin only)
+/// Delete a webhook (requires manage_webhooks)
 pub async fn delete_webhook(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<Uuid>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Webhooks) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManageWebhooks) {
+        return e;
+    }
+    if let Err(e) = require_scope(&req, "webhooks:write") {
         return e;
     }
 
@@ -301,20 +388,88 @@ pub async fn delete_webhook(
     }
 }
 
-/// Get delivery history for a webhook (admin only)
+/// Reveal a webhook's secret (requires manage_webhooks). Every reveal is
+/// recorded in the audit log.
+pub async fn reveal_webhook_secret(
+    req: HttpRequest,
+    pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    if let Err(e) = require_feature(&flags, Feature::Webhooks) {
+        return e;
+    }
+    let claims = match require_permission(&req, Permission::ManageWebhooks) {
+        Ok(claims) => claims,
+        Err(e) => return e,
+    };
+    if let Err(e) = require_scope(&req, "webhooks:write") {
+        return e;
+    }
+
+    let webhook_uuid = path.into_inner();
+
+    let mut conn = match get_connection(&pool) {
+        Ok(c) => c,
+        Err(e) => return e,
+    };
+
+    let webhook = match webhook_repo::get_webhook_by_uuid(&mut conn, webhook_uuid) {
+        Ok(w) => w,
+        Err(DieselError::NotFound) => return HttpResponse::NotFound().json("Webhook not found"),
+        Err(e) => {
+            error!("Failed to get webhook: {}", e);
+            return HttpResponse::InternalServerError().json("Failed to get webhook");
+        }
+    };
+
+    let secret = match open_secret(&webhook.secret) {
+        Ok(secret) => secret,
+        Err(e) => {
+            error!("Failed to decrypt secret for webhook {}: {}", webhook.uuid, e);
+            return HttpResponse::InternalServerError().json("Failed to decrypt webhook secret");
+        }
+    };
+
+    // Don't hand out the secret if the reveal can't be recorded
+    if let Err(e) = audit::record(
+        &mut conn,
+        Uuid::parse_str(&claims.sub).ok(),
+        AuditAction::WebhookSecretRevealed,
+        Some(AuditTarget::webhook(webhook.uuid)),
+        serde_json::json!({ "name": webhook.name }),
+    ) {
+        error!("Failed to audit webhook secret reveal: {}", e);
+        return HttpResponse::InternalServerError().json("Failed to reveal webhook secret");
+    }
+
+    info!("Webhook secret revealed: {} by {}", webhook.uuid, claims.sub);
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(serde_json::json!({ "secret": secret }))
+}
+
+/// Get delivery history for a webhook (requires manage_webhooks)
 pub async fn get_deliveries(
     req: HttpRequest,
     pool: web::Data<Pool>,
+    flags: web::Data<FeatureFlags>,
     path: web::Path<Uuid>,
     query: web::Query<PaginationQuery>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Webhooks) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManageWebhooks) {
+        return e;
+    }
+    if let Err(e) = require_scope(&req, "webhooks:read") {
         return e;
     }
 
     let webhook_uuid = path.into_inner();
-    let limit = query.limit.unwrap_or(50).min(100);
-    let offset = query.offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
 
     let mut conn = match get_connection(&pool) {
         Ok(c) => c,
@@ -331,8 +486,14 @@ pub async fn get_deliveries(
         }
     };
 
-    match webhook_repo::get_deliveries_for_webhook(&mut conn, webhook.id, limit, offset) {
-        Ok(deliveries) => {
+    let deliveries = webhook_repo::get_deliveries_for_webhook(&mut conn, webhook.id, limit, offset)
+        .and_then(|deliveries| {
+            let total = webhook_repo::count_deliveries_for_webhook(&mut conn, webhook.id)?;
+            Ok((deliveries, total))
+        });
+
+    match deliveries {
+        Ok((deliveries, total)) => {
             let response: Vec<WebhookDeliveryResponse> = deliveries
                 .into_iter()
                 .map(|d| WebhookDeliveryResponse {
@@ -346,7 +507,7 @@ pub async fn get_deliveries(
                     attempt_number: d.attempt_number,
                 })
                 .collect();
-            HttpResponse::Ok().json(response)
+            HttpResponse::Ok().json(PaginatedResult::from_offset(response, total, limit, offset))
         }
         Err(e) => {
             error!("Failed to get deliveries: {}", e);
@@ -355,16 +516,27 @@ pub async fn get_deliveries(
     }
 }
 
-/// Send a test event to a webhook (admin only)
+/// Send a test event to a webhook (requires manage_webhooks)
 pub async fn test_webhook(
     req: HttpRequest,
     pool: web::Data<Pool>,
-    webhook_service: web::Data<WebhookService>,
+    flags: web::Data<FeatureFlags>,
+    // Absent when webhooks are disabled; the feature guard answers first
+    webhook_service: Option<web::Data<WebhookService>>,
     path: web::Path<Uuid>,
 ) -> impl Responder {
-    if let Err(e) = require_admin(&req) {
+    if let Err(e) = require_feature(&flags, Feature::Webhooks) {
+        return e;
+    }
+    if let Err(e) = require_permission(&req, Permission::ManageWebhooks) {
+        return e;
+    }
+    if let Err(e) = require_scope(&req, "webhooks:write") {
         return e;
     }
+    let Some(webhook_service) = webhook_service else {
+        return HttpResponse::ServiceUnavailable().json("Webhook delivery is not running");
+    };
 
     let webhook_uuid = path.into_inner();
 
@@ -396,3 +568,96 @@ pub async fn test_webhook(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::services::audit::AuditFilter;
+    use crate::services::webhooks::signature::{sign_payload, verify_signature};
+    use crate::test_helpers::{create_test_claims, setup_test_pool, TestFixtures};
+    use actix_web::dev::Service;
+    use actix_web::{http::StatusCode, test, App};
+
+    #[actix_web::test]
+    async fn secrets_are_encrypted_revealed_with_audit_and_rotated() {
+        std::env::set_var(
+            "ENCRYPTION_KEY",
+            "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        );
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let admin = TestFixtures::create_user(&mut conn, "Webhook Admin", UserRole::Admin);
+        drop(conn);
+
+        let claims = create_test_claims(&admin);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(FeatureFlags::default()))
+                .wrap_fn(move |req, srv| {
+                    req.extensions_mut().insert(claims.clone());
+                    srv.call(req)
+                })
+                .route("/webhooks", web::post().to(create_webhook))
+                .route("/webhooks/{uuid}", web::put().to(update_webhook))
+                .route("/webhooks/{uuid}/secret/reveal", web::post().to(reveal_webhook_secret)),
+        )
+        .await;
+
+        let created: serde_json::Value = test::call_and_read_body_json(
+            &app,
+            test::TestRequest::post()
+                .uri("/webhooks")
+                .set_json(serde_json::json!({
+                    "name": "Reveal Hook",
+                    "url": "https://example.com/reveal",
+                    "events": ["ticket.created"],
+                }))
+                .to_request(),
+        )
+        .await;
+        let uuid: Uuid = created["uuid"].as_str().unwrap().parse().unwrap();
+        let original = created["secret"].as_str().unwrap().to_string();
+
+        // Only the ciphertext is stored
+        let mut conn = pool.get().unwrap();
+        let stored = webhook_repo::get_webhook_by_uuid(&mut conn, uuid).unwrap().secret;
+        assert_ne!(stored, original);
+        drop(conn);
+
+        let reveal = || test::TestRequest::post().uri(&format!("/webhooks/{uuid}/secret/reveal")).to_request();
+        let resp = test::call_service(&app, reveal()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let revealed: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(revealed["secret"], original);
+
+        let resp = test::call_service(
+            &app,
+            test::TestRequest::put()
+                .uri(&format!("/webhooks/{uuid}"))
+                .set_json(serde_json::json!({ "regenerate_secret": true }))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let rotated: serde_json::Value = test::call_and_read_body_json(&app, reveal()).await;
+        let rotated = rotated["secret"].as_str().unwrap();
+        assert_ne!(rotated, original);
+        let payload = r#"{"event":"ticket.created"}"#;
+        assert!(!verify_signature(payload, rotated, &sign_payload(payload, &original)));
+
+        // Creation, both reveals and the rotation name the admin and the webhook
+        let mut conn = pool.get().unwrap();
+        let entries = |conn: &mut DbConnection, action| {
+            let filter = AuditFilter { actor: Some(admin.uuid), action: Some(action), ..Default::default() };
+            audit::query(conn, &filter, 10, 0).unwrap()
+        };
+        let reveals = entries(&mut conn, AuditAction::WebhookSecretRevealed);
+        assert_eq!(reveals.len(), 2);
+        assert_eq!(reveals[0].target_id.as_deref(), Some(uuid.to_string().as_str()));
+        assert_eq!(entries(&mut conn, AuditAction::WebhookSecretRegenerated).len(), 1);
+        assert_eq!(entries(&mut conn, AuditAction::WebhookCreated).len(), 1);
+    }
+}
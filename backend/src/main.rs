@@ -4,8 +4,7 @@ use backend::middleware;
 use backend::services;
 use backend::utils;
 
-use actix_cors::Cors;
-use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer, Responder, Error, HttpMessage};
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer, Error, HttpMessage};
 use actix_web::dev::{ServiceRequest, ServiceResponse, fn_service};
 use actix_files::Files;
 use actix_limitation::{Limiter, RateLimiter};
@@ -17,10 +16,6 @@ use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 use utils::storage::{get_storage_config, create_storage};
 use utils::redis_yjs_cache::create_redis_cache;
 
-async fn health_check() -> impl Responder {
-    HttpResponse::Ok().body("Helpdesk API is running!")
-}
-
 /// Handle missing assets in development mode
 /// When frontend rebuilds, old asset hashes become invalid - this helps developers
 fn handle_missing_asset(path: &str) -> HttpResponse {
@@ -72,6 +67,10 @@ async fn serve_spa(_req: HttpRequest) -> HttpResponse {
     // Use no-cache so browsers always check for updated versions after deployments
     match tokio::fs::read("./public/index.html").await {
         Ok(content) => {
+            let content = crate::middleware::apply_csp_nonce(
+                &content,
+                crate::middleware::CspNonce::from_request(&_req).as_deref(),
+            );
             HttpResponse::Ok()
                 .content_type("text/html; charset=utf-8")
                 // no-cache: browser may cache but must revalidate with server before using
@@ -146,6 +145,11 @@ async fn cookie_auth_middleware(
 
     info!(user = %claims.sub, "Cookie auth: user authenticated successfully");
 
+    // Every state-changing request made while impersonating is audited
+    if claims.is_impersonating() && !req.method().is_safe() {
+        services::audit::record_impersonated_request(&mut conn, &claims, req.method().as_str(), req.path());
+    }
+
     // Insert claims into request extensions
     req.extensions_mut().insert(claims);
 
@@ -218,55 +222,42 @@ async fn main() -> std::io::Result<()> {
     let environment = env::var("ENVIRONMENT").unwrap_or("development".to_string());
     info!("Environment: {}", environment);
 
-    // Validate that JWT_SECRET is set and secure
-    let _jwt_secret = match std::env::var("JWT_SECRET") {
-        Ok(secret) => {
-            if secret.len() < 32 {
-                if environment == "production" {
-                    error!("JWT_SECRET must be at least 32 characters in production");
-                    error!("Generate a secure key with: openssl rand -base64 32");
-                    std::process::exit(1);
-                } else {
-                    warn!("JWT_SECRET is less than 32 characters - this would be rejected in production");
-                }
-            }
-            secret
-        },
-        Err(e) => {
-            error!(error = %e, "JWT_SECRET environment variable must be set");
-            error!("Generate a secure key with: openssl rand -base64 32");
-            std::process::exit(1);
-        }
-    };
-    info!("JWT_SECRET validated");
-    
-    // Validate that MFA_ENCRYPTION_KEY is set for production
-    if environment == "production" {
-        match std::env::var("MFA_ENCRYPTION_KEY") {
-            Ok(key) => {
-                if key.len() != 64 {
-                    error!("MFA_ENCRYPTION_KEY must be exactly 64 hex characters (32 bytes)");
-                    error!("Generate a secure key with: openssl rand -hex 32");
-                    std::process::exit(1);
-                }
-                // Validate it's valid hex
-                if hex::decode(&key).is_err() {
-                    error!("MFA_ENCRYPTION_KEY must be valid hexadecimal");
-                    error!("Generate a secure key with: openssl rand -hex 32");
-                    std::process::exit(1);
-                }
-            },
-            Err(e) => {
-                error!(error = %e, "MFA_ENCRYPTION_KEY environment variable must be set in production");
-                error!("Generate a secure key with: openssl rand -hex 32");
-                std::process::exit(1);
-            }
-        }
-    } else if std::env::var("MFA_ENCRYPTION_KEY").is_err() {
-        warn!("MFA_ENCRYPTION_KEY not set - MFA features will be disabled");
-        warn!("Generate with: openssl rand -hex 32");
+    // Validate all configuration up front and report every problem at once,
+    // rather than failing lazily on the first request that needs a setting
+    let config_problems = backend::config_utils::validate_all();
+    for problem in config_problems.iter().filter(|p| p.severity == backend::config_utils::Severity::Warning) {
+        warn!(key = problem.key, "Configuration: {}", problem.message);
     }
-    
+    let config_errors: Vec<String> = config_problems
+        .iter()
+        .filter(|p| p.severity == backend::config_utils::Severity::Error)
+        .map(|p| format!("  - {p}"))
+        .collect();
+    if !config_errors.is_empty() {
+        error!(
+            "Invalid configuration ({} problem(s)):\n{}",
+            config_errors.len(),
+            config_errors.join("\n")
+        );
+        std::process::exit(1);
+    }
+    backend::config_utils::log_config_dump();
+    info!("Configuration validated");
+
+    // Subsystems switched off for this deployment skip their background
+    // tasks, and their handlers answer 404
+    let feature_flags = backend::config_utils::FeatureFlags::from_env();
+    for feature in feature_flags.disabled() {
+        info!(feature = feature.as_str(), "Feature disabled");
+    }
+    let feature_flags_data = web::Data::new(feature_flags);
+    let lockout_policy_data = web::Data::new(backend::config_utils::LoginLockoutPolicy::from_env());
+    // Sign-ins are placed by headers from a trusted proxy, when configured
+    let geo_lookup: std::sync::Arc<dyn services::sign_in_devices::GeoLookup> = std::sync::Arc::new(
+        services::sign_in_devices::HeaderGeoLookup::new(backend::config_utils::GeoHeaders::from_env()),
+    );
+    let geo_lookup_data = web::Data::from(geo_lookup);
+
     // Security: Validate environment (already declared above)
     if environment == "production" {
         // Check for HTTPS in production URLs
@@ -407,13 +398,9 @@ async fn main() -> std::io::Result<()> {
         Err(_) => "http://localhost:3000".to_string(),
     };
 
-    // Parse additional CORS origins if provided
-    let additional_origins: Vec<String> = env::var("ADDITIONAL_CORS_ORIGINS")
-        .unwrap_or_default()
-        .split(',')
-        .filter(|s| !s.trim().is_empty())
-        .map(|s| s.trim().to_string())
-        .collect();
+    // CORS allowlist (CORS_ALLOWED_ORIGINS, or FRONTEND_URL + ADDITIONAL_CORS_ORIGINS)
+    let cors_config = crate::middleware::CorsConfig::from_env(&frontend_url);
+    info!(origins = ?cors_config.allowed_origins, credentials = cors_config.allow_credentials, "CORS configured");
 
     // Set up database connection pool
     let pool = match std::panic::catch_unwind(db::establish_connection_pool) {
@@ -439,6 +426,9 @@ async fn main() -> std::io::Result<()> {
         return Err(std::io::Error::other("Database initialization verification failed"));
     }
 
+    // Report whether uploads will be scanned for malware
+    utils::file_validation::log_malware_scanning_status();
+
     // Create uploads directory structure if it doesn't exist
     let uploads_dir = "/app/uploads";
     let directories = ["", "temp", "tickets", "users", "users/avatars", "users/banners", "users/thumbs", "plugins"];
@@ -453,10 +443,11 @@ async fn main() -> std::io::Result<()> {
         }
     }
 
-    // Provision plugins from /app/plugins/ directory
-    {
+    // Provision plugins from PLUGINS_DIR (/app/plugins/ by default)
+    let plugin_paths = backend::config_utils::PluginPaths::from_env();
+    if feature_flags.plugins {
         let mut conn = pool.get().expect("Failed to get connection for plugin provisioning");
-        let results = services::plugins::provision_plugins(&mut conn);
+        let results = services::plugins::provision_plugins(&mut conn, &plugin_paths);
         for result in results {
             match result {
                 services::plugins::provisioning::ProvisionResult::Created(name) => {
@@ -498,14 +489,39 @@ async fn main() -> std::io::Result<()> {
     // Initialize SSE state for real-time ticket updates (must be created before YjsAppState)
     let sse_state = web::Data::new(handlers::sse::SseState::new());
 
+    // Shared client and concurrency limit for webhook and plugin calls
+    let outbound_http = web::Data::new(services::outbound_http::OutboundHttp::new(
+        services::outbound_http::OutboundHttpConfig::from_env(),
+    ));
+
+    // Initialize webhook service for external integrations
+    let webhook_service = feature_flags.webhooks.then(|| {
+        use std::sync::Arc;
+        let sse_state_arc: Arc<handlers::sse::SseState> = sse_state.clone().into_inner();
+        web::Data::new(services::webhooks::WebhookService::new(
+            pool.clone(),
+            sse_state_arc,
+            outbound_http.clone().into_inner(),
+        ))
+    });
+
     // Initialize notification service for in-app and email notifications
     let notification_service = {
         use std::sync::Arc;
-        let service = services::notifications::NotificationService::new(pool.clone());
-
-        // Register in-app channel (SSE)
         // web::Data<T> wraps Arc<T>, so we can get the inner Arc directly
         let sse_state_arc: Arc<handlers::sse::SseState> = sse_state.clone().into_inner();
+
+        // Read-state changes are broadcast over SSE to keep other sessions in sync
+        // Deliveries that exhaust their retries are audited and sent to webhooks
+        let failure_sink = services::notifications::delivery_failures::AdminAlertSink::new(
+            pool.clone(),
+            webhook_service.as_ref().map(|service| service.clone().into_inner()),
+        );
+        let service = services::notifications::NotificationService::new(pool.clone())
+            .with_read_sync(sse_state_arc.clone())
+            .with_failure_sink(Arc::new(failure_sink));
+
+        // Register in-app channel (SSE)
         let in_app_channel = Arc::new(services::notifications::channels::in_app::InAppChannel::new(sse_state_arc));
         service.register_channel(in_app_channel);
 
@@ -529,15 +545,45 @@ async fn main() -> std::io::Result<()> {
         web::Data::new(service)
     };
 
-    // Initialize webhook service for external integrations
-    let webhook_service = {
-        use std::sync::Arc;
-        let sse_state_arc: Arc<handlers::sse::SseState> = sse_state.clone().into_inner();
-        web::Data::new(services::webhooks::WebhookService::new(pool.clone(), sse_state_arc))
-    };
+    // Periodically warn device owners and admins about expiring warranties
+    services::warranty::spawn(
+        pool.clone(),
+        notification_service.clone(),
+        services::warranty::WarrantyMonitorConfig::from_env(),
+    );
+
+    // Record SLA breaches and run escalation rules for them
+    services::sla::spawn(
+        pool.clone(),
+        notification_service.clone(),
+        services::sla::SlaMonitorConfig::from_env(),
+    );
+
+    // Close tickets left idle while waiting on the requester (opt-in via TICKET_AUTO_CLOSE_DAYS)
+    services::ticket_auto_close::spawn(
+        pool.clone(),
+        notification_service.clone(),
+        services::ticket_auto_close::AutoCloseConfig::from_env(),
+    );
+
+    // Scheduled Intune device sync (opt-in via DEVICE_SYNC_INTERVAL_MINUTES)
+    services::device_sync::spawn(pool.clone(), services::device_sync::DeviceSyncConfig::from_env());
+
+    // Periodic ticket report email (opt-in via REPORT_SUMMARY_RECIPIENTS)
+    services::reporting::spawn_summary(pool.clone(), services::reporting::SummaryConfig::from_env());
 
     // Initialize plugin proxy service for external requests
-    let plugin_proxy_service = web::Data::new(services::plugins::PluginProxyService::new());
+    let plugin_proxy_service = web::Data::new(services::plugins::PluginProxyService::new(outbound_http.clone().into_inner()));
+    let plugin_paths_data = web::Data::new(plugin_paths);
+
+    // Deliver subscribed events to plugin event handlers
+    if feature_flags.plugins {
+        services::plugins::events::spawn(
+            pool.clone(),
+            sse_state.clone().into_inner(),
+            plugin_proxy_service.clone().into_inner(),
+        );
+    }
 
     // Initialize search service for full-text search
     let search_service = {
@@ -562,8 +608,38 @@ async fn main() -> std::io::Result<()> {
         }
     };
 
+    // Commit incremental index updates in batches
+    services::search::indexing_tasks::spawn_commit_task(
+        search_service.get_ref().clone(),
+        services::search::indexing_tasks::commit_interval_from_env(),
+    );
+
+    // Transcribe uploaded audio and images for search, when an endpoint is configured
+    let transcription_service = services::transcription::TranscriptionService::from_env(
+        pool.clone(),
+        search_service.get_ref().clone(),
+    )
+    .map(web::Data::new);
+
     // Initialize WebSocket app state for collaborative editing (includes SseState for broadcasting)
-    let yjs_app_state = web::Data::new(handlers::collaboration::YjsAppState::new(web::Data::new(pool.clone()), redis_cache, sse_state.clone()));
+    let yjs_app_state = web::Data::new(handlers::collaboration::YjsAppState::new(web::Data::new(pool.clone()), redis_cache.clone(), sse_state.clone(), search_service.get_ref().clone()));
+
+    // Dependencies checked by /ready (Redis and search can be switched off)
+    let readiness_checks = {
+        use handlers::health::ReadinessChecks;
+        let mut checks = ReadinessChecks::new();
+        if ReadinessChecks::redis_enabled_from_env() {
+            checks = checks.with_redis(redis_cache.clone());
+        }
+        if ReadinessChecks::search_enabled_from_env() {
+            checks = checks.with_search(search_service.get_ref().clone());
+        }
+        web::Data::new(checks)
+    };
+
+    // Drains notifications, indexing and webhook deliveries once the server stops
+    let shutdown_coordinator = services::shutdown::ShutdownCoordinator::from_env()
+        .with_search(search_service.get_ref().clone());
 
     // Initialize system state for tracking uptime
     let system_state = web::Data::new(handlers::system::SystemState::new());
@@ -581,29 +657,33 @@ async fn main() -> std::io::Result<()> {
     let storage = create_storage(storage_config);
     let storage_data = web::Data::new(storage.clone());
 
+    // Purge attachments of tickets closed longer than the retention period
+    services::attachment_retention::spawn(
+        pool.clone(),
+        storage.clone(),
+        services::attachment_retention::AttachmentRetentionConfig::from_env(),
+    );
+
+    // Unauthenticated metrics on an internal address for Prometheus scrapers
+    if let Ok(metrics_addr) = env::var("METRICS_BIND_ADDR") {
+        let metrics_pool = web::Data::new(pool.clone());
+        let metrics_server = HttpServer::new(move || {
+            App::new()
+                .app_data(metrics_pool.clone())
+                .route("/metrics", web::get().to(handlers::metrics::get_internal_metrics))
+        })
+        .workers(1)
+        .bind(&metrics_addr)?
+        .run();
+        info!(address = %metrics_addr, "Metrics endpoint listening");
+        actix_web::rt::spawn(metrics_server);
+    }
+
     info!(host = %host, port = %port, environment = %environment, "Server starting");
     
     let server_result = HttpServer::new(move || {
         // Configure CORS with specific allowed origins
-        let mut cors = Cors::default()
-            .allowed_origin(&frontend_url)
-            .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "PATCH", "OPTIONS"])
-            .allowed_headers(vec![
-                "Authorization",
-                "Content-Type",
-                "Accept",
-                "Origin",
-                "X-Requested-With",
-                "X-CSRF-Token"
-            ])
-            .expose_headers(vec!["content-disposition"])
-            .supports_credentials()
-            .max_age(3600);
-
-        // Add additional allowed origins if specified
-        for origin in &additional_origins {
-            cors = cors.allowed_origin(origin);
-        }
+        let cors = cors_config.build();
 
         // Configure JSON payload limits for file uploads
         let json_config = web::JsonConfig::default()
@@ -616,23 +696,46 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .wrap(cors)
             .wrap(crate::middleware::SecurityHeaders) // Apply security headers globally
-            .wrap(crate::utils::csrf::CsrfProtection)
+            .wrap(crate::middleware::CsrfProtection)
+            // Request ids: tags every log line (and background task) from here in
+            .wrap(crate::middleware::RequestIdMiddleware)
+            // Compression: the policy marks small/pre-compressed responses so Compress skips them
+            .wrap(crate::middleware::CompressionPolicy::from_env())
+            .wrap(crate::middleware::compression::compress())
+            .wrap(crate::middleware::RequestMetrics) // Outermost: times the whole request
             .app_data(public_limiter_data.clone())
             .app_data(auth_limiter_data.clone())
             .app_data(web::Data::new(pool.clone()))
             .app_data(yjs_app_state.clone())
             .app_data(sse_state.clone())
             .app_data(system_state.clone())
+            .app_data(readiness_checks.clone())
             .app_data(storage_data.clone())
             .app_data(notification_service.clone())
-            .app_data(webhook_service.clone())
+            .app_data(feature_flags_data.clone())
+            .app_data(lockout_policy_data.clone())
+            .app_data(geo_lookup_data.clone())
+            .app_data(outbound_http.clone())
+            .configure(|cfg| {
+                // Only registered when webhooks are enabled
+                if let Some(webhook_service) = &webhook_service {
+                    cfg.app_data(webhook_service.clone());
+                }
+                // Only registered when a transcription endpoint is configured
+                if let Some(transcription_service) = &transcription_service {
+                    cfg.app_data(transcription_service.clone());
+                }
+            })
             .app_data(plugin_proxy_service.clone())
+            .app_data(plugin_paths_data.clone())
             .app_data(search_service.clone())
             .app_data(json_config)
+            .app_data(middleware::idempotency::MaxBodySize(max_payload_size))
             .app_data(multipart_config)
             
             // === PUBLIC ROUTES (NO AUTHENTICATION REQUIRED) ===
-            .route("/health", web::get().to(health_check))
+            .route("/health", web::get().to(handlers::health::liveness))
+            .route("/ready", web::get().to(handlers::health::readiness))
 
             // Debug endpoint for frontend log forwarding (dev mode only)
             .route("/api/debug/frontend-logs", web::post().to(handlers::debug::receive_frontend_logs))
@@ -645,6 +748,14 @@ async fn main() -> std::io::Result<()> {
 
             // Public branding config (needed for favicon/logo before login)
             .route("/api/branding", web::get().to(handlers::branding::get_public_branding))
+
+            // Public ticket status via signed link and email provider feedback (rate-limited, no auth)
+            .service(
+                web::scope("/api/public")
+                    .wrap(RateLimiter::default())
+                    .route("/ticket-status/{token}", web::get().to(handlers::ticket_status::get_ticket_status))
+                    .route("/email-feedback", web::post().to(handlers::email::receive_email_feedback))
+            )
             
             // Public WebSocket for collaboration (auth handled in WebSocket handler)
             .service(
@@ -659,7 +770,12 @@ async fn main() -> std::io::Result<()> {
             
             // SSE endpoints (with custom token-based auth)
             // Main event stream for all real-time updates (tickets, documentation, devices, etc.)
-            .route("/api/events/stream", web::get().to(handlers::sse::ticket_events_stream))
+            // SSE must stream unbuffered, so it opts out of compression
+            .service(
+                web::resource("/api/events/stream")
+                    .wrap(crate::middleware::compression::skip_compression())
+                    .route(web::get().to(handlers::sse::ticket_events_stream))
+            )
             .route("/api/events/status", web::get().to(handlers::sse::sse_status))
             
             // Authentication routes (public by design)
@@ -689,6 +805,8 @@ async fn main() -> std::io::Result<()> {
                     // Invitation routes (public)
                     .route("/invitation/validate", web::post().to(handlers::invitation::validate_invitation))
                     .route("/invitation/accept", web::post().to(handlers::invitation::accept_invitation))
+                    // Email verification link (public)
+                    .route("/email-verification/confirm", web::post().to(handlers::email_verification::verify_email))
                     .route("/providers", web::get().to(handlers::get_enabled_auth_providers))
                     .route("/oauth/authorize", web::post().to(handlers::oauth_authorize))
                     .route("/oauth/callback", web::get().to(handlers::oauth_callback))
@@ -697,13 +815,15 @@ async fn main() -> std::io::Result<()> {
                     .route("/me", web::get().to(handlers::get_current_user).wrap(actix_web::middleware::from_fn(cookie_auth_middleware)))
                     .route("/change-password", web::post().to(handlers::change_password).wrap(actix_web::middleware::from_fn(cookie_auth_middleware)))
                     .route("/oauth/connect", web::post().to(handlers::oauth_connect).wrap(actix_web::middleware::from_fn(cookie_auth_middleware)))
+                    .route("/impersonation/stop", web::post().to(handlers::impersonation::stop_impersonation).wrap(actix_web::middleware::from_fn(cookie_auth_middleware)))
                     // Session Management endpoints
                     .service(
                         web::scope("/sessions")
                             .wrap(actix_web::middleware::from_fn(cookie_auth_middleware))
                             .route("", web::get().to(handlers::get_user_sessions))
-                            .route("/{id}", web::delete().to(handlers::revoke_session))
+                            // Registered before /{id} so "others" isn't parsed as a session id
                             .route("/others", web::delete().to(handlers::revoke_all_other_sessions))
+                            .route("/{id}", web::delete().to(handlers::revoke_session))
                     )
                     // MFA (Multi-Factor Authentication) endpoints
                     .service(
@@ -748,6 +868,9 @@ async fn main() -> std::io::Result<()> {
                     .route("/admin/system/info", web::get().to(handlers::system::get_system_info))
                     .route("/admin/system/updates", web::get().to(handlers::system::check_system_updates))
 
+                    // Prometheus metrics (admin only)
+                    .route("/admin/metrics", web::get().to(handlers::metrics::get_metrics))
+
                     // Branding configuration (admin only)
                     .route("/admin/branding/config", web::get().to(handlers::branding::get_branding_config))
                     .route("/admin/branding/config", web::patch().to(handlers::branding::update_branding_config))
@@ -798,8 +921,14 @@ async fn main() -> std::io::Result<()> {
 
                     // ===== SEARCH =====
                     .route("/search", web::get().to(handlers::search::search))
+                    .route("/search/grouped", web::get().to(handlers::search::search_grouped))
+                    .route("/search/suggestions", web::get().to(handlers::search::get_suggestions))
+                    .route("/search/recent", web::delete().to(handlers::search::clear_recent_searches))
                     .route("/search/rebuild", web::post().to(handlers::search::rebuild_index))
                     .route("/search/stats", web::get().to(handlers::search::get_stats))
+                    .route("/admin/search/reindex", web::post().to(handlers::search::start_reindex))
+                    .route("/admin/search/reindex/status", web::get().to(handlers::search::get_reindex_status))
+                    .route("/admin/search/insights", web::get().to(handlers::search::get_search_insights))
 
                     // ===== NOTIFICATIONS =====
                     .route("/notifications", web::get().to(handlers::notifications::get_notifications))
@@ -808,19 +937,36 @@ async fn main() -> std::io::Result<()> {
                     .route("/notifications/read-all", web::post().to(handlers::notifications::mark_all_notifications_read))
                     .route("/notifications/preferences", web::get().to(handlers::notifications::get_preferences))
                     .route("/notifications/preferences", web::put().to(handlers::notifications::update_preference))
+                    .route("/notifications/preferences/bulk", web::put().to(handlers::notifications::update_preferences_bulk))
+                    .route("/notifications/locale", web::put().to(handlers::notifications::update_locale))
                     .route("/notifications/delete", web::post().to(handlers::notifications::delete_notifications))
 
                     // ===== TICKET MANAGEMENT =====
                     .route("/tickets", web::get().to(handlers::get_tickets))
                     .route("/tickets/paginated", web::get().to(handlers::get_paginated_tickets))
+                    .route("/tickets/export", web::get().to(handlers::ticket_export::export_tickets))
                     .route("/tickets/recent", web::get().to(handlers::get_recent_tickets))
-                    .route("/tickets", web::post().to(handlers::create_ticket))
+                    .route("/tickets/my-work", web::get().to(handlers::my_work::get_my_work))
+                    .route("/tickets", web::post().to(handlers::create_ticket).wrap(actix_web::middleware::from_fn(middleware::idempotency_middleware)))
                     .route("/tickets/empty", web::post().to(handlers::create_empty_ticket))
                     .route("/tickets/bulk", web::post().to(handlers::bulk_tickets))
+                    .route("/tickets/suggest-duplicates", web::post().to(handlers::suggest_duplicate_tickets))
                     .route("/tickets/{id}", web::get().to(handlers::get_ticket))
                     .route("/tickets/{id}", web::put().to(handlers::update_ticket))
                     .route("/tickets/{id}", web::patch().to(handlers::update_ticket_partial))
                     .route("/tickets/{id}", web::delete().to(handlers::delete_ticket))
+                    .route("/tickets/{id}/custom-fields", web::put().to(handlers::update_ticket_custom_fields))
+                    .route("/tickets/{id}/timeline", web::get().to(handlers::get_ticket_timeline))
+                    .route("/tickets/{id}/merge", web::post().to(handlers::merge_ticket))
+                    .route("/tickets/{id}/legal-hold", web::put().to(handlers::set_ticket_legal_hold))
+                    .route("/tickets/{id}/reopen", web::post().to(handlers::reopen_ticket))
+                    .route("/tickets/{id}/status-link", web::post().to(handlers::ticket_status::create_status_link))
+                    .route("/tickets/{id}/watchers", web::get().to(handlers::get_ticket_watchers))
+                    .route("/tickets/{id}/watchers", web::post().to(handlers::add_ticket_watcher))
+                    .route("/tickets/{id}/watchers/{user_uuid}", web::delete().to(handlers::remove_ticket_watcher))
+                    .route("/tickets/{id}/worklogs", web::get().to(handlers::get_ticket_worklogs))
+                    .route("/tickets/{id}/worklogs", web::post().to(handlers::add_ticket_worklog))
+                    .route("/tickets/{id}/worklogs/{worklog_id}", web::delete().to(handlers::delete_ticket_worklog))
                     .route("/tickets/{id}/view", web::post().to(handlers::record_ticket_view))
                     .route("/import/file", web::post().to(handlers::import_tickets_from_json))
                     .route("/import/json", web::post().to(handlers::import_tickets_from_json_string))
@@ -829,7 +975,7 @@ async fn main() -> std::io::Result<()> {
                     .route("/tickets/{ticket_id}/devices/{device_id}", web::post().to(handlers::add_device_to_ticket))
                     .route("/tickets/{ticket_id}/devices/{device_id}", web::delete().to(handlers::remove_device_from_ticket))
                     .route("/tickets/{ticket_id}/comments", web::get().to(handlers::get_comments_by_ticket_id))
-                    .route("/tickets/{ticket_id}/comments", web::post().to(handlers::add_comment_to_ticket))
+                    .route("/tickets/{ticket_id}/comments", web::post().to(handlers::add_comment_to_ticket).wrap(actix_web::middleware::from_fn(middleware::idempotency_middleware)))
                     .route("/tickets/{ticket_id}/notes/images", web::post().to(handlers::upload_ticket_note_image))
                     .route("/comments/{id}", web::delete().to(handlers::delete_comment))
                     .route("/comments/{comment_id}/attachments", web::post().to(handlers::add_attachment_to_comment))
@@ -861,6 +1007,7 @@ async fn main() -> std::io::Result<()> {
                     .route("/groups/{id}", web::put().to(handlers::groups::update_group))
                     .route("/groups/{id}", web::delete().to(handlers::groups::delete_group))
                     .route("/groups/{id}/members", web::put().to(handlers::groups::set_group_members))
+                    .route("/groups/{id}/managers", web::put().to(handlers::groups::set_group_managers))
                     .route("/groups/{id}/devices", web::put().to(handlers::groups::set_group_devices))
                     .route("/groups/{id}/unmanage", web::post().to(handlers::groups::unmanage_group))
                     .route("/users/{uuid}/groups", web::get().to(handlers::groups::get_user_groups))
@@ -877,6 +1024,20 @@ async fn main() -> std::io::Result<()> {
                     .route("/admin/categories/{id}", web::put().to(handlers::categories::update_category))
                     .route("/admin/categories/{id}", web::delete().to(handlers::categories::delete_category))
                     .route("/admin/categories/{id}/visibility", web::put().to(handlers::categories::set_category_visibility))
+                    // Custom ticket fields
+                    .route("/custom-fields", web::get().to(handlers::custom_fields::list_custom_fields))
+                    .route("/admin/custom-fields", web::post().to(handlers::custom_fields::create_custom_field))
+                    .route("/admin/custom-fields/{id}", web::put().to(handlers::custom_fields::update_custom_field))
+                    .route("/admin/custom-fields/{id}", web::delete().to(handlers::custom_fields::delete_custom_field))
+
+                    // ===== CANNED RESPONSES =====
+                    .route("/reports/tickets", web::get().to(handlers::reports::get_ticket_report))
+                    .route("/canned-responses", web::get().to(handlers::canned_responses::list_canned_responses))
+                    .route("/canned-responses", web::post().to(handlers::canned_responses::create_canned_response))
+                    .route("/canned-responses/{id}", web::get().to(handlers::canned_responses::get_canned_response))
+                    .route("/canned-responses/{id}", web::put().to(handlers::canned_responses::update_canned_response))
+                    .route("/canned-responses/{id}", web::delete().to(handlers::canned_responses::delete_canned_response))
+                    .route("/canned-responses/{id}/apply", web::post().to(handlers::canned_responses::apply_canned_response))
 
                     // ===== ASSIGNMENT RULES MANAGEMENT =====
                     .route("/admin/assignment-rules", web::get().to(handlers::assignment_rules::get_all_rules))
@@ -888,6 +1049,21 @@ async fn main() -> std::io::Result<()> {
                     .route("/admin/assignment-rules/{id}", web::patch().to(handlers::assignment_rules::update_rule))
                     .route("/admin/assignment-rules/{id}", web::delete().to(handlers::assignment_rules::delete_rule))
 
+                    // ===== SLA TARGETS =====
+                    .route("/admin/sla-targets", web::get().to(handlers::sla::get_sla_targets))
+                    .route("/admin/sla-targets", web::put().to(handlers::sla::update_sla_target))
+
+                    // ===== AUDIT LOG =====
+                    .route("/admin/audit-log", web::get().to(handlers::audit::get_audit_log))
+
+                    // ===== IMPERSONATION =====
+                    .route("/admin/impersonate/{user_uuid}", web::post().to(handlers::impersonation::start_impersonation))
+
+                    // ===== PERMISSIONS =====
+                    .route("/admin/permissions", web::get().to(handlers::permissions::list_permissions))
+                    .route("/admin/permissions/grants", web::post().to(handlers::permissions::create_grant))
+                    .route("/admin/permissions/grants/{id}", web::delete().to(handlers::permissions::delete_grant))
+
                     // ===== API TOKEN MANAGEMENT =====
                     .route("/admin/api-tokens", web::get().to(handlers::api_tokens::list_api_tokens))
                     .route("/admin/api-tokens", web::post().to(handlers::api_tokens::create_api_token))
@@ -903,6 +1079,7 @@ async fn main() -> std::io::Result<()> {
                     .route("/admin/webhooks/{uuid}", web::delete().to(handlers::webhooks::delete_webhook))
                     .route("/admin/webhooks/{uuid}/deliveries", web::get().to(handlers::webhooks::get_deliveries))
                     .route("/admin/webhooks/{uuid}/test", web::post().to(handlers::webhooks::test_webhook))
+                    .route("/admin/webhooks/{uuid}/secret/reveal", web::post().to(handlers::webhooks::reveal_webhook_secret))
 
                     // ===== PLUGIN MANAGEMENT (Admin) =====
                     .route("/admin/plugins", web::get().to(handlers::plugins::list_plugins))
@@ -935,6 +1112,7 @@ async fn main() -> std::io::Result<()> {
                     .route("/files/cleanup-temp", web::post().to(handlers::cleanup_temp_files))
                     .route("/users/auth-identities", web::get().to(handlers::get_user_auth_identities))
                     .route("/users/auth-identities/{id}", web::delete().to(handlers::delete_user_auth_identity))
+                    .route("/users/timezone", web::put().to(handlers::update_user_timezone))
                     .route("/users", web::post().to(handlers::create_user))
                     .route("/users/{uuid}", web::get().to(handlers::get_user_by_uuid))
                     .route("/users/{uuid}", web::put().to(handlers::update_user_by_uuid))
@@ -944,6 +1122,9 @@ async fn main() -> std::io::Result<()> {
                     .route("/users/{uuid}/emails", web::post().to(handlers::add_user_email))
                     .route("/users/{uuid}/emails/{email_id}", web::put().to(handlers::update_user_email))
                     .route("/users/{uuid}/emails/{email_id}", web::delete().to(handlers::delete_user_email))
+                    .route("/users/{uuid}/emails/{email_id}/clear-delivery-flags", web::post().to(handlers::clear_user_email_delivery_flags))
+                    .route("/users/{uuid}/emails/{email_id}/resend-verification", web::post().to(handlers::email_verification::resend_email_verification))
+                    .route("/users/{uuid}/unlock", web::post().to(handlers::unlock_user_account))
                     .route("/users/{uuid}/with-emails", web::get().to(handlers::get_user_with_emails))
                     .route("/users/{uuid}/auth-identities", web::get().to(handlers::get_user_auth_identities_by_uuid))
                     .route("/users/{uuid}/auth-identities/{id}", web::delete().to(handlers::delete_user_auth_identity_by_uuid))
@@ -954,15 +1135,19 @@ async fn main() -> std::io::Result<()> {
                     .route("/devices/paginated", web::get().to(handlers::get_paginated_devices))
                     .route("/devices/paginated/excluding", web::get().to(handlers::get_paginated_devices_excluding))
                     .route("/devices/bulk", web::post().to(handlers::bulk_devices))
+                    .route("/devices/import", web::post().to(handlers::import_devices))
                     .route("/devices", web::post().to(handlers::create_device))
                     .route("/devices/{id}", web::get().to(handlers::get_device_by_id))
                     .route("/devices/{id}", web::put().to(handlers::update_device))
                     .route("/devices/{id}", web::delete().to(handlers::delete_device))
                     .route("/devices/{id}/unmanage", web::post().to(handlers::unmanage_device))
+                    .route("/devices/{id}/assignment-history", web::get().to(handlers::get_device_assignment_history))
                     .route("/users/{uuid}/devices", web::get().to(handlers::get_user_devices))
                     
                     // ===== DOCUMENTATION SYSTEM =====
                     .route("/documentation/pages", web::get().to(handlers::get_documentation_pages))
+                    .route("/documentation/tree", web::get().to(handlers::get_documentation_tree))
+                    .route("/documents/{id}/presence", web::get().to(handlers::collaboration::get_document_presence))
                     .route("/documentation/pages/export", web::get().to(handlers::export_documentation_pages))
                     .route("/documentation/pages", web::post().to(handlers::create_documentation_page))
                     .route("/documentation/pages/{id}", web::get().to(handlers::get_documentation_page))
@@ -978,6 +1163,8 @@ async fn main() -> std::io::Result<()> {
                     .route("/documentation/pages/{id}/with-ordered-children", web::get().to(handlers::get_page_with_ordered_children))
                     .route("/documentation/pages/reorder", web::post().to(handlers::reorder_pages))
                     .route("/documentation/pages/move", web::post().to(handlers::move_page_to_parent))
+                    .route("/documentation/pages/{id}/visibility", web::get().to(handlers::get_documentation_visibility))
+                    .route("/documentation/pages/{id}/visibility", web::put().to(handlers::set_documentation_visibility))
                     .route("/tickets/{ticket_id}/documentation", web::get().to(handlers::get_documentation_pages_by_ticket_id))
                     .route("/tickets/{ticket_id}/documentation/create", web::post().to(handlers::create_documentation_page_from_ticket))
                     .route("/documentation/{id}", web::put().to(handlers::update_documentation_page))
@@ -1020,6 +1207,10 @@ async fn main() -> std::io::Result<()> {
                         // Use no-cache so browsers always check for updated frontend builds
                         match tokio::fs::read("./public/index.html").await {
                             Ok(content) => {
+                                let content = crate::middleware::apply_csp_nonce(
+                                    &content,
+                                    crate::middleware::CspNonce::from_request(&req).as_deref(),
+                                );
                                 let res = HttpResponse::Ok()
                                     .content_type("text/html; charset=utf-8")
                                     .insert_header(("Cache-Control", "no-cache"))
@@ -1061,5 +1252,9 @@ async fn main() -> std::io::Result<()> {
     .run()
     .await;
 
+    // The server has stopped accepting connections and finished in-flight
+    // requests; flush the background work they started before exiting
+    shutdown_coordinator.shutdown().await;
+
     server_result
 }
\ No newline at end of file
@@ -10,6 +10,7 @@ use tracing::{debug, error, info, warn};
 use crate::db::Pool;
 use crate::models::Claims;
 use crate::repository::api_tokens::{get_valid_api_token, hash_token, update_token_last_used};
+use crate::utils::rbac::{required_scope, TokenScopes};
 
 /// Marker struct to indicate request was authenticated via API token
 /// This is used by CSRF middleware to skip validation for API token requests
@@ -17,6 +18,7 @@ use crate::repository::api_tokens::{get_valid_api_token, hash_token, update_toke
 #[allow(dead_code)]
 pub struct ApiTokenAuth {
     pub token_uuid: uuid::Uuid,
+    pub scopes: Vec<String>,
 }
 
 /// Extract Bearer token from Authorization header
@@ -53,11 +55,11 @@ fn extract_client_ip(req: &ServiceRequest) -> Option<IpAddr> {
 }
 
 /// Try to authenticate request via Bearer token
-/// Returns Ok(Some(Claims)) if authenticated, Ok(None) if no Bearer token, Err on auth failure
+/// Returns Ok(Some((Claims, ApiTokenAuth))) if authenticated, Ok(None) if no Bearer token, Err on auth failure
 pub fn try_bearer_auth(
     req: &ServiceRequest,
     pool: &web::Data<Pool>,
-) -> Result<Option<Claims>, Error> {
+) -> Result<Option<(Claims, ApiTokenAuth)>, Error> {
     // Check for Bearer token
     let token = match extract_bearer_token(req) {
         Some(t) => t,
@@ -102,6 +104,10 @@ pub fn try_bearer_auth(
             return Err(actix_web::error::ErrorInternalServerError("Authentication error"));
         }
     };
+    if user.deactivated_at.is_some() {
+        warn!(path = %req.path(), "API token belongs to a deactivated user");
+        return Err(actix_web::error::ErrorUnauthorized("Invalid or expired API token"));
+    }
 
     // Get user's primary email
     let email = crate::repository::user_emails::get_user_emails_by_uuid(&mut conn, &api_token.user_uuid)
@@ -130,18 +136,33 @@ pub fn try_bearer_auth(
         name: user.name,
         email,
         role: format!("{:?}", user.role).to_lowercase(),
-        scope: "full".to_string(), // API tokens always have full scope
+        scope: "full".to_string(), // Session scope; API permissions come from ApiTokenAuth.scopes
         exp: (now + chrono::Duration::hours(24)).timestamp() as usize,
         iat: now.timestamp() as usize,
+        impersonator: None,
     };
 
+    let scopes: Vec<String> = api_token
+        .scopes
+        .unwrap_or_default()
+        .into_iter()
+        .flatten()
+        .collect();
+
     info!(
         user = %claims.sub,
         token_uuid = %api_token.uuid,
+        scopes = ?scopes,
         "API token authentication successful"
     );
 
-    Ok(Some(claims))
+    Ok(Some((
+        claims,
+        ApiTokenAuth {
+            token_uuid: api_token.uuid,
+            scopes,
+        },
+    )))
 }
 
 /// Middleware function that supports both Bearer token and cookie authentication
@@ -157,11 +178,21 @@ pub async fn dual_auth_middleware(
 
     // Try Bearer token authentication first
     match try_bearer_auth(&req, &pool)? {
-        Some(claims) => {
+        Some((claims, token_auth)) => {
+            // Every route needs the scope of its resource; unmapped routes need `full`
+            let scopes = TokenScopes::new(token_auth.scopes.clone());
+            let scope = required_scope(req.method(), req.path());
+            if !scopes.allows(&scope) {
+                warn!(path = %req.path(), scope = %scope, "API token is missing the required scope");
+                return Err(actix_web::error::ErrorForbidden(format!(
+                    "This API token is missing the required scope '{scope}'"
+                )));
+            }
+
+            // Attach the token's scopes so handlers can enforce finer checks
+            req.extensions_mut().insert(scopes);
             // Mark this request as authenticated via API token
-            req.extensions_mut().insert(ApiTokenAuth {
-                token_uuid: uuid::Uuid::parse_str(&claims.sub).unwrap_or_default(),
-            });
+            req.extensions_mut().insert(token_auth);
             // Insert claims for handler use
             req.extensions_mut().insert(claims);
             // Continue without cookie auth
@@ -197,8 +228,14 @@ pub async fn dual_auth_middleware(
 
     info!(user = %claims.sub, "Cookie auth: user authenticated successfully");
 
-    // Insert claims into request extensions
+    // Every state-changing request made while impersonating is audited
+    if claims.is_impersonating() && !req.method().is_safe() {
+        crate::services::audit::record_impersonated_request(&mut conn, &claims, req.method().as_str(), req.path());
+    }
+
+    // Insert claims into request extensions (sessions carry every scope)
     req.extensions_mut().insert(claims);
+    req.extensions_mut().insert(TokenScopes::all());
 
     // Continue to the handler
     next.call(req).await
@@ -0,0 +1,262 @@
+//! Response Compression Policy
+//!
+//! Actix's `Compress` middleware negotiates brotli/gzip from `Accept-Encoding`
+//! but compresses everything. `CompressionPolicy` sits inside it and marks
+//! responses that shouldn't be compressed with `Content-Encoding: identity`
+//! (which `Compress` leaves alone): bodies below the size threshold, content
+//! that is already compressed, event streams, and partial content (byte
+//! ranges refer to the unencoded file).
+
+use actix_web::{
+    body::{BodySize, MessageBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{
+        header::{self, HeaderValue},
+        StatusCode,
+    },
+    middleware::{Compress, DefaultHeaders},
+    Error,
+};
+use futures::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+
+/// Default minimum body size worth compressing
+const DEFAULT_MIN_SIZE: usize = 1024;
+
+/// Content types that are already compressed (or must stream unbuffered)
+const SKIP_CONTENT_TYPES: &[&str] = &[
+    "image/",
+    "video/",
+    "audio/",
+    "font/woff",
+    "application/zip",
+    "application/gzip",
+    "application/x-gzip",
+    "application/pdf",
+    "application/octet-stream",
+    "text/event-stream",
+];
+
+/// Compression layer: negotiates brotli (preferred) or gzip
+pub fn compress() -> Compress {
+    Compress::default()
+}
+
+/// Per-route opt-out, e.g. for SSE streams that must not be buffered
+pub fn skip_compression() -> DefaultHeaders {
+    DefaultHeaders::new().add((header::CONTENT_ENCODING, "identity"))
+}
+
+/// Decides which responses the outer `Compress` layer may encode
+#[derive(Clone, Copy, Debug)]
+pub struct CompressionPolicy {
+    min_size: usize,
+}
+
+impl CompressionPolicy {
+    pub fn new(min_size: usize) -> Self {
+        Self { min_size }
+    }
+
+    /// Threshold from COMPRESSION_MIN_SIZE (bytes, default 1024)
+    pub fn from_env() -> Self {
+        let min_size = std::env::var("COMPRESSION_MIN_SIZE")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .unwrap_or(DEFAULT_MIN_SIZE);
+        Self::new(min_size)
+    }
+
+    fn should_skip(&self, status: StatusCode, headers: &header::HeaderMap, size: BodySize) -> bool {
+        // Compressing a range response would break the offsets clients resume from
+        if status == StatusCode::PARTIAL_CONTENT || headers.contains_key(header::CONTENT_RANGE) {
+            return true;
+        }
+
+        let content_type = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok());
+        let skip_type = content_type.is_some_and(|ct| {
+            let ct = ct.to_ascii_lowercase();
+            SKIP_CONTENT_TYPES.iter().any(|skip| ct.starts_with(skip))
+        });
+
+        let too_small = matches!(size, BodySize::Sized(n) if n < self.min_size as u64);
+
+        skip_type || too_small
+    }
+}
+
+impl Default for CompressionPolicy {
+    fn default() -> Self {
+        Self::new(DEFAULT_MIN_SIZE)
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for CompressionPolicy
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = CompressionPolicyMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CompressionPolicyMiddleware {
+            service,
+            policy: *self,
+        }))
+    }
+}
+
+pub struct CompressionPolicyMiddleware<S> {
+    service: S,
+    policy: CompressionPolicy,
+}
+
+impl<S, B> Service<ServiceRequest> for CompressionPolicyMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let policy = self.policy;
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let mut res = fut.await?;
+
+            if !res.headers().contains_key(header::CONTENT_ENCODING) {
+                let size = res.response().body().size();
+
+                if policy.should_skip(res.status(), res.headers(), size) {
+                    res.headers_mut()
+                        .insert(header::CONTENT_ENCODING, HeaderValue::from_static("identity"));
+                }
+            }
+
+            Ok(res)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+
+    async fn encoding_for(path: &str) -> Option<String> {
+        let app = init_service(
+            App::new()
+                .wrap(CompressionPolicy::new(1024))
+                .wrap(compress())
+                .route(
+                    "/large",
+                    web::get().to(|| async {
+                        let tickets: Vec<_> = (0..200)
+                            .map(|i| serde_json::json!({ "id": i, "title": "Printer offline" }))
+                            .collect();
+                        HttpResponse::Ok().json(tickets)
+                    }),
+                )
+                .route(
+                    "/small",
+                    web::get().to(|| async { HttpResponse::Ok().json(serde_json::json!({ "ok": true })) }),
+                )
+                .route(
+                    "/image",
+                    web::get().to(|| async {
+                        HttpResponse::Ok().content_type("image/png").body(vec![0u8; 4096])
+                    }),
+                )
+                .route(
+                    "/partial",
+                    web::get().to(|| async {
+                        HttpResponse::PartialContent()
+                            .content_type("text/plain")
+                            .insert_header((header::CONTENT_RANGE, "bytes 0-4095/8192"))
+                            .body("z".repeat(4096))
+                    }),
+                )
+                .route(
+                    "/range-header",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("text/plain")
+                            .insert_header((header::CONTENT_RANGE, "bytes */8192"))
+                            .body("z".repeat(4096))
+                    }),
+                )
+                .service(
+                    web::resource("/stream")
+                        .wrap(skip_compression())
+                        .route(web::get().to(|| async {
+                            HttpResponse::Ok().content_type("text/plain").body("x".repeat(4096))
+                        })),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::get()
+            .uri(path)
+            .insert_header((header::ACCEPT_ENCODING, "gzip, br"))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        let encoding = resp
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .map(|v| v.to_str().unwrap().to_string());
+        // Drain the body so the encoder runs to completion
+        let _ = read_body(resp).await;
+        encoding
+    }
+
+    #[actix_web::test]
+    async fn large_json_is_compressed_with_brotli() {
+        assert_eq!(encoding_for("/large").await.as_deref(), Some("br"));
+    }
+
+    #[actix_web::test]
+    async fn small_json_is_not_compressed() {
+        assert_eq!(encoding_for("/small").await.as_deref(), Some("identity"));
+    }
+
+    #[actix_web::test]
+    async fn compressed_types_and_opted_out_routes_are_skipped() {
+        assert_eq!(encoding_for("/image").await.as_deref(), Some("identity"));
+        assert_eq!(encoding_for("/stream").await.as_deref(), Some("identity"));
+    }
+
+    #[actix_web::test]
+    async fn partial_content_is_not_compressed() {
+        assert_eq!(encoding_for("/partial").await.as_deref(), Some("identity"));
+        assert_eq!(encoding_for("/range-header").await.as_deref(), Some("identity"));
+    }
+
+    #[actix_web::test]
+    async fn gzip_is_used_when_brotli_is_not_accepted() {
+        let app = init_service(
+            App::new()
+                .wrap(CompressionPolicy::new(16))
+                .wrap(compress())
+                .route("/", web::get().to(|| async { HttpResponse::Ok().body("y".repeat(64)) })),
+        )
+        .await;
+        let req = TestRequest::get()
+            .uri("/")
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.headers().get(header::CONTENT_ENCODING).unwrap(), "gzip");
+    }
+}
@@ -0,0 +1,216 @@
+//! CORS Configuration
+//!
+//! Builds the `actix_cors::Cors` layer from environment settings so the SPA can
+//! be served from a different origin than the API. Only allowlisted origins are
+//! echoed back; a wildcard is never sent while credentials are allowed.
+
+use actix_cors::Cors;
+use actix_web::http::Method;
+use tracing::warn;
+
+use crate::middleware::csrf::CSRF_HEADER;
+
+const DEFAULT_METHODS: &[&str] = &["GET", "POST", "PUT", "DELETE", "PATCH", "OPTIONS"];
+
+const DEFAULT_HEADERS: &[&str] = &[
+    "Authorization",
+    "Content-Type",
+    "Accept",
+    "Origin",
+    "X-Requested-With",
+    CSRF_HEADER,
+];
+
+/// CORS settings loaded from the environment
+///
+/// * `CORS_ALLOWED_ORIGINS` - comma separated origins (falls back to
+///   `FRONTEND_URL` plus `ADDITIONAL_CORS_ORIGINS`)
+/// * `CORS_ALLOW_CREDENTIALS` - send `Access-Control-Allow-Credentials` (default true)
+/// * `CORS_ALLOWED_METHODS` / `CORS_ALLOWED_HEADERS` - comma separated overrides
+/// * `CORS_MAX_AGE` - preflight cache lifetime in seconds (default 3600)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    pub allow_credentials: bool,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub max_age: usize,
+}
+
+impl CorsConfig {
+    /// Config allowing a single origin with the default methods and headers
+    pub fn new(origins: Vec<String>) -> Self {
+        Self {
+            allowed_origins: origins,
+            allow_credentials: true,
+            allowed_methods: DEFAULT_METHODS.iter().map(|s| s.to_string()).collect(),
+            allowed_headers: DEFAULT_HEADERS.iter().map(|s| s.to_string()).collect(),
+            max_age: 3600,
+        }
+    }
+
+    /// Load the config from the environment, using `frontend_url` when no
+    /// explicit origin list is configured
+    pub fn from_env(frontend_url: &str) -> Self {
+        let origins = match std::env::var("CORS_ALLOWED_ORIGINS") {
+            Ok(list) if !list.trim().is_empty() => parse_list(&list),
+            _ => {
+                let mut origins = vec![frontend_url.to_string()];
+                origins.extend(parse_list(
+                    &std::env::var("ADDITIONAL_CORS_ORIGINS").unwrap_or_default(),
+                ));
+                origins
+            }
+        };
+
+        let mut config = Self::new(origins);
+
+        if let Ok(value) = std::env::var("CORS_ALLOW_CREDENTIALS") {
+            config.allow_credentials = !matches!(value.trim().to_lowercase().as_str(), "false" | "0" | "no");
+        }
+        if let Ok(methods) = std::env::var("CORS_ALLOWED_METHODS") {
+            let methods = parse_list(&methods);
+            if !methods.is_empty() {
+                config.allowed_methods = methods.into_iter().map(|m| m.to_uppercase()).collect();
+            }
+        }
+        if let Ok(headers) = std::env::var("CORS_ALLOWED_HEADERS") {
+            let headers = parse_list(&headers);
+            if !headers.is_empty() {
+                config.allowed_headers = headers;
+            }
+        }
+        if let Some(max_age) = std::env::var("CORS_MAX_AGE")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+        {
+            config.max_age = max_age;
+        }
+
+        config
+    }
+
+    /// Build the actix CORS layer
+    pub fn build(&self) -> Cors {
+        let mut cors = Cors::default()
+            .allowed_methods(
+                self.allowed_methods
+                    .iter()
+                    .filter_map(|m| Method::from_bytes(m.as_bytes()).ok()),
+            )
+            .allowed_headers(self.allowed_headers.iter().map(String::as_str))
+            .expose_headers(vec!["content-disposition", "etag", "x-request-id"])
+            .max_age(self.max_age);
+
+        // The CSRF header must always be allowed or cross-origin writes can't pass
+        if !self.allowed_headers.iter().any(|h| h.eq_ignore_ascii_case(CSRF_HEADER)) {
+            cors = cors.allowed_header(CSRF_HEADER);
+        }
+
+        if self.allow_credentials {
+            cors = cors.supports_credentials();
+        }
+
+        for origin in &self.allowed_origins {
+            if origin == "*" {
+                if self.allow_credentials {
+                    warn!("Ignoring wildcard CORS origin: not allowed together with credentials");
+                } else {
+                    cors = cors.allow_any_origin();
+                }
+                continue;
+            }
+            cors = cors.allowed_origin(origin);
+        }
+
+        cors
+    }
+}
+
+/// Split a comma separated list, dropping blanks and trailing slashes
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().trim_end_matches('/').to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::{header, StatusCode};
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+
+    const ALLOWED: &str = "https://app.example.com";
+
+    async fn allow_origin_for(config: &CorsConfig, origin: &str) -> Option<String> {
+        let app = init_service(
+            App::new()
+                .wrap(config.build())
+                .route("/api/tickets", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+        let req = TestRequest::get()
+            .uri("/api/tickets")
+            .insert_header((header::ORIGIN, origin))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        resp.headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[actix_web::test]
+    async fn allowed_origin_is_echoed() {
+        let config = CorsConfig::new(vec![ALLOWED.to_string()]);
+        assert_eq!(allow_origin_for(&config, ALLOWED).await.as_deref(), Some(ALLOWED));
+    }
+
+    #[actix_web::test]
+    async fn disallowed_origin_gets_no_header() {
+        let config = CorsConfig::new(vec![ALLOWED.to_string()]);
+        assert_eq!(allow_origin_for(&config, "https://evil.example.com").await, None);
+    }
+
+    #[actix_web::test]
+    async fn wildcard_is_ignored_with_credentials() {
+        let config = CorsConfig::new(vec!["*".to_string(), ALLOWED.to_string()]);
+        assert_eq!(allow_origin_for(&config, "https://evil.example.com").await, None);
+        assert_eq!(allow_origin_for(&config, ALLOWED).await.as_deref(), Some(ALLOWED));
+    }
+
+    #[actix_web::test]
+    async fn preflight_allows_csrf_header() {
+        let mut config = CorsConfig::new(vec![ALLOWED.to_string()]);
+        config.allowed_headers = vec!["Content-Type".to_string()];
+        let app = init_service(
+            App::new()
+                .wrap(config.build())
+                .route("/api/tickets", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+        let req = TestRequest::default()
+            .method(Method::OPTIONS)
+            .uri("/api/tickets")
+            .insert_header((header::ORIGIN, ALLOWED))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, "POST"))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_HEADERS, "x-csrf-token"))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS).unwrap(),
+            "true"
+        );
+    }
+
+    #[test]
+    fn list_parsing_trims_entries() {
+        assert_eq!(
+            parse_list(" https://a.example.com/, ,https://b.example.com"),
+            vec!["https://a.example.com".to_string(), "https://b.example.com".to_string()]
+        );
+    }
+}
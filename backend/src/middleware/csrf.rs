@@ -0,0 +1,274 @@
+//! CSRF Protection Middleware
+//!
+//! Enforces the double-submit cookie pattern for cookie-authenticated requests:
+//! every state-changing request must echo the `csrf_token` cookie back in the
+//! `X-CSRF-Token` header.
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
+    http::Method,
+    Error, HttpResponse,
+};
+use futures::future::LocalBoxFuture;
+use serde_json::json;
+use std::future::{ready, Ready};
+use tracing::{debug, warn};
+
+use crate::middleware::api_token::extract_bearer_token;
+use crate::utils::cookies::CSRF_TOKEN_COOKIE;
+use crate::utils::csrf::validate_csrf_token;
+
+/// Header the frontend uses to echo the CSRF cookie
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// Endpoints exempt from CSRF validation (exact match)
+/// These either run before a session exists or are the ones that set the cookie
+const CSRF_EXEMPT_PATHS: &[&str] = &[
+    "/api/auth/login",
+    "/api/auth/logout",
+    "/api/auth/mfa-login",
+    "/api/auth/mfa-setup-login",
+    "/api/auth/mfa-enable-login",
+    "/api/auth/microsoft",
+    "/api/auth/oauth/authorize",
+    "/api/auth/oauth/callback",
+    "/api/auth/oauth/logout",
+    "/api/auth/setup/admin",
+    "/api/auth/setup/status",
+    "/api/auth/register",
+    "/api/auth/passkeys/login/start",
+    "/api/auth/passkeys/login/finish",
+    "/api/debug/frontend-logs",
+    // Called by the email provider, verified with a shared secret
+    "/api/public/email-feedback",
+];
+
+/// Endpoints exempt from CSRF validation (prefix match)
+const CSRF_EXEMPT_PREFIXES: &[&str] = &[
+    "/api/auth/microsoft/callback",
+    "/api/auth/setup/restore/",
+    "/api/auth/password-reset/",
+    "/api/auth/mfa-reset/",
+    "/api/auth/invitation/",
+    "/api/auth/email-verification/",
+];
+
+/// Check whether a path is on the CSRF whitelist
+pub fn is_csrf_exempt(path: &str) -> bool {
+    CSRF_EXEMPT_PATHS.contains(&path)
+        || CSRF_EXEMPT_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+}
+
+/// Only state-changing methods need a CSRF token
+fn requires_csrf(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    )
+}
+
+/// API tokens are sent explicitly in the Authorization header, which a
+/// cross-site form or fetch can't attach, so they don't need CSRF protection
+fn is_api_token_request(req: &ServiceRequest) -> bool {
+    extract_bearer_token(req).is_some_and(|token| token.starts_with("nsk_"))
+}
+
+/// Build the 403 returned when validation fails
+fn csrf_error(reason: &'static str) -> Error {
+    InternalError::from_response(
+        reason,
+        HttpResponse::Forbidden().json(json!({
+            "status": "error",
+            "error": "csrf_validation_failed",
+            "message": reason
+        })),
+    )
+    .into()
+}
+
+/// CSRF protection middleware using the double-submit cookie pattern
+/// Validates CSRF tokens for state-changing requests (POST, PUT, PATCH, DELETE)
+pub struct CsrfProtection;
+
+impl<S, B> Transform<S, ServiceRequest> for CsrfProtection
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = CsrfProtectionMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CsrfProtectionMiddleware { service }))
+    }
+}
+
+pub struct CsrfProtectionMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for CsrfProtectionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let path = req.path().to_string();
+
+        // Safe methods (GET, HEAD, OPTIONS), API token requests and whitelisted
+        // endpoints pass straight through
+        if !requires_csrf(req.method()) {
+            return Box::pin(self.service.call(req));
+        }
+
+        if is_api_token_request(&req) {
+            debug!(path = %path, "CSRF: skipping validation for API token request");
+            return Box::pin(self.service.call(req));
+        }
+
+        if is_csrf_exempt(&path) {
+            return Box::pin(self.service.call(req));
+        }
+
+        let header_token = req
+            .headers()
+            .get(CSRF_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_string());
+
+        let cookie_token = req.cookie(CSRF_TOKEN_COOKIE).map(|c| c.value().to_string());
+
+        let failure = match (header_token, cookie_token) {
+            (Some(header), Some(cookie)) if !header.is_empty() => {
+                if validate_csrf_token(&header, &cookie) {
+                    None
+                } else {
+                    Some("Invalid CSRF token")
+                }
+            }
+            (Some(_), Some(_)) | (None, Some(_)) => Some("CSRF token required in header"),
+            (Some(_), None) => Some("CSRF token required in cookie"),
+            (None, None) => Some("CSRF token required"),
+        };
+
+        if let Some(reason) = failure {
+            warn!(path = %path, method = %req.method(), reason, "CSRF validation failed");
+            return Box::pin(async move { Err(csrf_error(reason)) });
+        }
+
+        debug!(path = %path, "CSRF validation passed");
+        Box::pin(self.service.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+    use actix_web::test::{call_service, init_service, try_call_service, TestRequest};
+    use actix_web::{cookie::Cookie, http::StatusCode, web, App};
+
+    const TOKEN: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn test_app() -> App<
+        impl actix_web::dev::ServiceFactory<
+            ServiceRequest,
+            Config = (),
+            Response = ServiceResponse<actix_web::body::BoxBody>,
+            Error = Error,
+            InitError = (),
+        >,
+    > {
+        App::new().wrap(CsrfProtection).service(
+            web::scope("/api")
+                .route("/tickets", web::get().to(HttpResponse::Ok))
+                .route("/tickets", web::post().to(HttpResponse::Created))
+                .route("/auth/passkeys/login/finish", web::post().to(HttpResponse::Ok)),
+        )
+    }
+
+    #[actix_web::test]
+    async fn valid_token_is_accepted() {
+        let app = init_service(test_app()).await;
+        let req = TestRequest::post()
+            .uri("/api/tickets")
+            .cookie(Cookie::new(CSRF_TOKEN_COOKIE, TOKEN))
+            .insert_header((CSRF_HEADER, TOKEN))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+    }
+
+    #[actix_web::test]
+    async fn missing_token_is_rejected_with_structured_body() {
+        let app = init_service(test_app()).await;
+        let req = TestRequest::post()
+            .uri("/api/tickets")
+            .cookie(Cookie::new(CSRF_TOKEN_COOKIE, TOKEN))
+            .to_request();
+        let resp = try_call_service(&app, req).await.unwrap_err().error_response();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"], "csrf_validation_failed");
+        assert_eq!(body["message"], "CSRF token required in header");
+    }
+
+    #[actix_web::test]
+    async fn mismatched_token_is_rejected() {
+        let app = init_service(test_app()).await;
+        let req = TestRequest::post()
+            .uri("/api/tickets")
+            .cookie(Cookie::new(CSRF_TOKEN_COOKIE, TOKEN))
+            .insert_header((CSRF_HEADER, "not-the-cookie-value"))
+            .to_request();
+        let resp = try_call_service(&app, req).await.unwrap_err().error_response();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    async fn get_is_method_exempt() {
+        let app = init_service(test_app()).await;
+        let req = TestRequest::get().uri("/api/tickets").to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn api_token_and_whitelisted_paths_skip_validation() {
+        let app = init_service(test_app()).await;
+
+        let req = TestRequest::post()
+            .uri("/api/tickets")
+            .insert_header(("Authorization", "Bearer nsk_example"))
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), StatusCode::CREATED);
+
+        let req = TestRequest::post()
+            .uri("/api/auth/passkeys/login/finish")
+            .to_request();
+        assert_eq!(call_service(&app, req).await.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn exempt_paths() {
+        assert!(is_csrf_exempt("/api/auth/login"));
+        assert!(is_csrf_exempt("/api/auth/password-reset/confirm"));
+        assert!(!is_csrf_exempt("/api/auth/login/extra"));
+        assert!(!is_csrf_exempt("/api/tickets"));
+        assert!(is_csrf_exempt("/api/public/email-feedback"));
+    }
+}
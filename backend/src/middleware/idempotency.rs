@@ -0,0 +1,352 @@
+//! Idempotency Keys
+//!
+//! Flaky clients retry POSTs, which would otherwise create duplicates. Create
+//! routes wrapped with `idempotency_middleware` accept an `Idempotency-Key`
+//! header: the first request with a key runs normally and its successful
+//! response is stored; a repeat of the same request with the same key inside
+//! the replay window gets the stored response back without running the
+//! handler again.
+//!
+//! Keys are per user and per route. Reusing a key for a different request
+//! body is rejected, and a repeat that arrives while the original is still
+//! running gets 409. Failed requests release their key so they can be retried.
+//!
+//! Wrap individual routes, inside the auth middleware:
+//! `web::post().to(handler).wrap(from_fn(idempotency_middleware))`
+//!
+//! Bodies are buffered up to the app's `MaxBodySize` (the JSON payload
+//! limit); larger ones get 413 before the handler runs.
+
+use actix_web::{
+    body::{to_bytes, BoxBody, MessageBody},
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    http::{header, StatusCode},
+    middleware::Next,
+    web, Error, HttpMessage, HttpResponse,
+};
+use chrono::{Duration, Utc};
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use ring::digest::{Context, SHA256};
+use serde_json::json;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::models::{Claims, IdempotencyKey, NewIdempotencyKey};
+use crate::repository::idempotency_keys;
+
+/// Header clients send the key in
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Header marking a response as a replay of the original
+pub const REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+/// Longest accepted key
+const MAX_KEY_LEN: usize = 255;
+
+/// Default time a key can be replayed for
+const DEFAULT_WINDOW_HOURS: i64 = 24;
+
+/// Body limit when no `MaxBodySize` is registered (actix's JSON default)
+const DEFAULT_MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+/// Largest request body buffered for fingerprinting, registered as app data
+/// alongside the `JsonConfig` limit
+#[derive(Debug, Clone, Copy)]
+pub struct MaxBodySize(pub usize);
+
+/// Replay window from IDEMPOTENCY_KEY_WINDOW_HOURS (default 24)
+static REPLAY_WINDOW: Lazy<Duration> = Lazy::new(|| {
+    let hours = std::env::var("IDEMPOTENCY_KEY_WINDOW_HOURS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|hours| *hours > 0)
+        .unwrap_or(DEFAULT_WINDOW_HOURS);
+    Duration::hours(hours)
+});
+
+/// Hash of what makes two requests "the same": method, path and body
+fn request_hash(method: &str, path: &str, body: &[u8]) -> String {
+    let mut context = Context::new(&SHA256);
+    context.update(method.as_bytes());
+    context.update(b"\0");
+    context.update(path.as_bytes());
+    context.update(b"\0");
+    context.update(body);
+    hex::encode(context.finish().as_ref())
+}
+
+/// Drop expired keys, then claim this one or return the record already holding it
+fn claim(pool: &Pool, new_key: NewIdempotencyKey) -> Result<Option<IdempotencyKey>, String> {
+    let mut conn = pool.get().map_err(|e| format!("DB error: {e}"))?;
+    let cutoff = Utc::now().naive_utc() - *REPLAY_WINDOW;
+    idempotency_keys::delete_keys_before(&mut conn, cutoff).map_err(|e| e.to_string())?;
+    idempotency_keys::claim_key(&mut conn, new_key).map_err(|e| e.to_string())
+}
+
+fn release(pool: &Pool, user_uuid: Uuid, scope: &str, key: &str) {
+    let result = pool
+        .get()
+        .map_err(|e| e.to_string())
+        .and_then(|mut conn| idempotency_keys::release_key(&mut conn, user_uuid, scope, key).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!(scope, error = %e, "Failed to release idempotency key");
+    }
+}
+
+/// The stored response for a repeated key
+fn replay(response_status: i32, body: Option<String>) -> HttpResponse {
+    let status = u16::try_from(response_status)
+        .ok()
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .unwrap_or(StatusCode::OK);
+    HttpResponse::build(status)
+        .content_type(header::ContentType::json())
+        .insert_header((REPLAYED_HEADER, "true"))
+        .body(body.unwrap_or_default())
+}
+
+/// Replays create responses for repeated `Idempotency-Key` headers
+pub async fn idempotency_middleware(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let Some(key) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.call(req).await.map(ServiceResponse::map_into_boxed_body);
+    };
+    let key = match key.to_str().map(str::trim) {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LEN => key.to_string(),
+        _ => {
+            return Ok(req.into_response(HttpResponse::BadRequest().json(json!({
+                "error": "Invalid Idempotency-Key",
+                "message": format!("{IDEMPOTENCY_KEY_HEADER} must be 1 to {MAX_KEY_LEN} visible characters")
+            }))));
+        }
+    };
+
+    let user_uuid = req
+        .extensions()
+        .get::<Claims>()
+        .and_then(|claims| crate::utils::parse_uuid(&claims.sub).ok());
+    let pool = req.app_data::<web::Data<Pool>>().map(|pool| pool.get_ref().clone());
+    let (Some(user_uuid), Some(pool)) = (user_uuid, pool) else {
+        return next.call(req).await.map(ServiceResponse::map_into_boxed_body);
+    };
+
+    // Buffer the body to fingerprint it, then hand it on to the handler
+    let max_body_size = req
+        .app_data::<MaxBodySize>()
+        .map_or(DEFAULT_MAX_BODY_SIZE, |limit| limit.0);
+    let mut body = web::BytesMut::new();
+    let mut payload = req.take_payload();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk?;
+        if body.len() + chunk.len() > max_body_size {
+            return Ok(req.into_response(HttpResponse::PayloadTooLarge().json(json!({
+                "error": "Payload too large",
+                "message": format!("Request body exceeds {max_body_size} bytes")
+            }))));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let body = body.freeze();
+    let hash = request_hash(req.method().as_str(), req.path(), &body);
+    req.set_payload(Payload::from(body));
+
+    let scope = format!(
+        "{} {}",
+        req.method(),
+        req.match_pattern().unwrap_or_else(|| req.path().to_string())
+    );
+    let new_key = NewIdempotencyKey {
+        user_uuid,
+        scope: &scope,
+        idempotency_key: &key,
+        request_hash: &hash,
+    };
+
+    match claim(&pool, new_key) {
+        Ok(None) => {}
+        Ok(Some(existing)) if existing.request_hash != hash => {
+            return Ok(req.into_response(HttpResponse::UnprocessableEntity().json(json!({
+                "error": "Idempotency-Key reused",
+                "message": "This Idempotency-Key was already used for a different request"
+            }))));
+        }
+        Ok(Some(existing)) => {
+            return Ok(match existing.response_status {
+                Some(status) => {
+                    debug!(scope, resource_id = ?existing.resource_id, "Replaying idempotent response");
+                    req.into_response(replay(status, existing.response_body))
+                }
+                None => req.into_response(HttpResponse::Conflict().json(json!({
+                    "error": "Request in progress",
+                    "message": "A request with this Idempotency-Key is still being processed"
+                }))),
+            });
+        }
+        Err(e) => {
+            warn!(scope, error = %e, "Idempotency key lookup failed");
+            return Ok(req.into_response(
+                HttpResponse::InternalServerError().json("Database connection error"),
+            ));
+        }
+    }
+
+    let res = match next.call(req).await {
+        Ok(res) if res.status().is_success() => res,
+        other => {
+            release(&pool, user_uuid, &scope, &key);
+            return other.map(ServiceResponse::map_into_boxed_body);
+        }
+    };
+
+    let (http_req, res) = res.into_parts();
+    let status = res.status();
+    let (res, body) = res.into_parts();
+    let body = match to_bytes(body).await {
+        Ok(body) => body,
+        Err(e) => {
+            release(&pool, user_uuid, &scope, &key);
+            let e: Box<dyn std::error::Error> = e.into();
+            return Err(actix_web::error::ErrorInternalServerError(e.to_string()));
+        }
+    };
+
+    let resource_id = serde_json::from_slice::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|value| value.get("id")?.as_i64())
+        .and_then(|id| i32::try_from(id).ok());
+    let stored = pool.get().map_err(|e| e.to_string()).and_then(|mut conn| {
+        idempotency_keys::complete_key(
+            &mut conn,
+            user_uuid,
+            &scope,
+            &key,
+            resource_id,
+            i32::from(status.as_u16()),
+            &String::from_utf8_lossy(&body),
+        )
+        .map_err(|e| e.to_string())
+    });
+    if let Err(e) = stored {
+        warn!(scope, error = %e, "Failed to store idempotent response");
+    }
+
+    Ok(ServiceResponse::new(http_req, res.set_body(body).map_into_boxed_body()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::schema::tickets;
+    use crate::test_helpers::{create_test_claims, setup_test_pool, TestFixtures};
+    use actix_web::dev::Service;
+    use actix_web::middleware::from_fn;
+    use actix_web::{test, App};
+    use diesel::prelude::*;
+
+    async fn create_test_ticket(pool: web::Data<Pool>, body: web::Json<serde_json::Value>) -> HttpResponse {
+        let mut conn = pool.get().unwrap();
+        let title = body["title"].as_str().unwrap();
+        HttpResponse::Created().json(TestFixtures::create_ticket(&mut conn, title, None, None))
+    }
+
+    #[actix_web::test]
+    async fn repeated_key_returns_original_response_without_duplicating() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "idempotentuser", UserRole::User);
+        drop(conn);
+
+        let claims = create_test_claims(&user);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap_fn(move |req, srv| {
+                    req.extensions_mut().insert(claims.clone());
+                    srv.call(req)
+                })
+                .route(
+                    "/tickets",
+                    web::post().to(create_test_ticket).wrap(from_fn(idempotency_middleware)),
+                ),
+        )
+        .await;
+
+        let title = format!("Retried ticket {}", Uuid::new_v4());
+        let key = Uuid::new_v4().to_string();
+        let create = |title: &str| {
+            test::TestRequest::post()
+                .uri("/tickets")
+                .insert_header((IDEMPOTENCY_KEY_HEADER, key.clone()))
+                .set_json(json!({ "title": title }))
+                .to_request()
+        };
+
+        let first = test::call_service(&app, create(&title)).await;
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert!(first.headers().get(REPLAYED_HEADER).is_none());
+        let first_body = test::read_body(first).await;
+
+        let second = test::call_service(&app, create(&title)).await;
+        assert_eq!(second.status(), StatusCode::CREATED);
+        assert_eq!(second.headers().get(REPLAYED_HEADER).unwrap(), "true");
+        assert_eq!(test::read_body(second).await, first_body);
+
+        let mut conn = pool.get().unwrap();
+        let created: i64 = tickets::table
+            .filter(tickets::title.eq(&title))
+            .count()
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(created, 1);
+
+        // The same key with a different body is refused
+        let reused = test::call_service(&app, create("Something else")).await;
+        assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[actix_web::test]
+    async fn oversized_body_is_rejected_before_the_handler() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "idempotentbig", UserRole::User);
+        drop(conn);
+
+        let claims = create_test_claims(&user);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(MaxBodySize(64))
+                .wrap_fn(move |req, srv| {
+                    req.extensions_mut().insert(claims.clone());
+                    srv.call(req)
+                })
+                .route(
+                    "/tickets",
+                    web::post().to(create_test_ticket).wrap(from_fn(idempotency_middleware)),
+                ),
+        )
+        .await;
+
+        let title = format!("Oversized ticket {}", "x".repeat(64));
+        let req = test::TestRequest::post()
+            .uri("/tickets")
+            .insert_header((IDEMPOTENCY_KEY_HEADER, Uuid::new_v4().to_string()))
+            .set_json(json!({ "title": title }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let mut conn = pool.get().unwrap();
+        let created: i64 = tickets::table
+            .filter(tickets::title.eq(&title))
+            .count()
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(created, 0);
+    }
+}
@@ -0,0 +1,113 @@
+//! Request Metrics
+//!
+//! Counts every request and times it, labelled by method, matched route
+//! pattern and response status. Errors returned by inner services are
+//! counted with the status they will be rendered as.
+//!
+//! Wrap it outermost so the timing covers the other middleware too.
+
+use actix_web::{
+    body::MessageBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    Error,
+};
+use futures::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::time::Instant;
+
+use crate::services::metrics::{self, UNMATCHED_ROUTE};
+
+/// Records `http_requests_total` and `http_request_duration_seconds`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RequestMetrics;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestMetricsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestMetricsMiddleware { service }))
+    }
+}
+
+pub struct RequestMetricsMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let start = Instant::now();
+        let method = req.method().to_string();
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let result = fut.await;
+
+            // Errors from inner middleware no longer carry the request, so
+            // their route is unknown
+            let (route, status) = match &result {
+                Ok(res) => (res.request().match_pattern(), res.status()),
+                Err(e) => (None, e.as_response_error().status_code()),
+            };
+            let route = route.unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+            metrics::record_http_request(&method, &route, status.as_u16(), start.elapsed());
+
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn requests_are_counted_by_route_pattern_and_status() {
+        let app = init_service(
+            App::new()
+                .wrap(RequestMetrics)
+                .route(
+                    "/metrics-test/tickets/{id}",
+                    web::get().to(|| async { HttpResponse::Ok().finish() }),
+                ),
+        )
+        .await;
+
+        let route = "/metrics-test/tickets/{id}";
+        let before = metrics::http_request_count("GET", route, 200);
+
+        for id in [1, 2] {
+            let req = TestRequest::get().uri(&format!("/metrics-test/tickets/{id}")).to_request();
+            assert!(call_service(&app, req).await.status().is_success());
+        }
+
+        assert_eq!(metrics::http_request_count("GET", route, 200), before + 2);
+
+        // Unmatched paths share one label instead of one per path
+        let unmatched_before = metrics::http_request_count("GET", UNMATCHED_ROUTE, 404);
+        let req = TestRequest::get().uri("/metrics-test/nope/123").to_request();
+        assert_eq!(call_service(&app, req).await.status(), 404);
+        assert_eq!(metrics::http_request_count("GET", UNMATCHED_ROUTE, 404), unmatched_before + 1);
+    }
+}
@@ -1,5 +1,17 @@
 pub mod api_token;
+pub mod compression;
+pub mod cors;
+pub mod csrf;
+pub mod idempotency;
+pub mod metrics;
+pub mod request_id;
 pub mod security_headers;
 
 pub use api_token::dual_auth_middleware;
-pub use security_headers::SecurityHeaders;
+pub use compression::CompressionPolicy;
+pub use cors::CorsConfig;
+pub use csrf::CsrfProtection;
+pub use idempotency::idempotency_middleware;
+pub use metrics::RequestMetrics;
+pub use request_id::{RequestId, RequestIdMiddleware};
+pub use security_headers::{apply_csp_nonce, CspNonce, SecurityHeaders};
@@ -0,0 +1,256 @@
+//! Request ID Middleware
+//!
+//! Gives every request an id so its log lines can be found together. An
+//! inbound `X-Request-Id` is kept if it looks sane, otherwise a UUID is
+//! generated. The id is:
+//!
+//! * recorded on a `request` tracing span wrapping the rest of the stack, so
+//!   every log line emitted while handling the request carries it. Background
+//!   work spawned through `services::shutdown` trackers inherits the span.
+//! * stored in request extensions as [`RequestId`]
+//! * echoed in the `X-Request-Id` response header, and added as `request_id`
+//!   to JSON error bodies that are objects
+
+use actix_web::{
+    body::{to_bytes, BoxBody, MessageBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
+    http::header::{self, HeaderName, HeaderValue},
+    Error, HttpMessage, HttpResponse,
+};
+use futures::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use tracing::Instrument;
+
+/// Header the id is read from and echoed in
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest inbound id that is honoured
+const MAX_ID_LEN: usize = 128;
+
+/// Largest error body that gets the id added
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// The id of the current request, available from request extensions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Use the inbound id if it's short and printable, otherwise make one
+    fn from_inbound(inbound: Option<&HeaderValue>) -> Self {
+        let valid = inbound
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|id| {
+                !id.is_empty()
+                    && id.len() <= MAX_ID_LEN
+                    && id.chars().all(|c| c.is_ascii_alphanumeric() || "-_.:".contains(c))
+            });
+        match valid {
+            Some(id) => Self(id.to_string()),
+            None => Self(uuid::Uuid::new_v4().to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Assigns request ids and opens the per-request tracing span
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RequestIdMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestIdService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdService { service }))
+    }
+}
+
+pub struct RequestIdService<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let request_id = RequestId::from_inbound(req.headers().get(&REQUEST_ID_HEADER));
+        let span = tracing::info_span!(
+            "request",
+            request_id = %request_id.as_str(),
+            method = %req.method(),
+            path = %req.path(),
+        );
+        req.extensions_mut().insert(request_id.clone());
+
+        let fut = span.in_scope(|| self.service.call(req));
+
+        Box::pin(
+            async move {
+                match fut.await {
+                    Ok(res) => {
+                        let (http_req, res) = res.map_into_boxed_body().into_parts();
+                        Ok(ServiceResponse::new(http_req, attach_request_id(res, &request_id).await))
+                    }
+                    // Errors are rendered further out, so render this one now
+                    // to get the id onto it
+                    Err(e) => {
+                        let res = attach_request_id(e.error_response(), &request_id).await;
+                        Err(InternalError::from_response(e.to_string(), res).into())
+                    }
+                }
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// Add the id to the response header, and to the body of JSON error responses
+async fn attach_request_id(res: HttpResponse<BoxBody>, request_id: &RequestId) -> HttpResponse<BoxBody> {
+    let mut res = if is_json_error(&res) {
+        let (res, body) = res.into_parts();
+        let body = match to_bytes(body).await {
+            Ok(body) => BoxBody::new(with_request_id(body, request_id)),
+            Err(_) => BoxBody::new(()),
+        };
+        res.set_body(body)
+    } else {
+        res
+    };
+
+    if let Ok(value) = HeaderValue::from_str(request_id.as_str()) {
+        res.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    res
+}
+
+fn is_json_error(res: &HttpResponse<BoxBody>) -> bool {
+    let is_json = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json"));
+    let small = matches!(
+        res.body().size(),
+        actix_web::body::BodySize::Sized(n) if n <= MAX_ERROR_BODY as u64
+    );
+    (res.status().is_client_error() || res.status().is_server_error())
+        && is_json
+        && small
+        && !res.headers().contains_key(header::CONTENT_ENCODING)
+}
+
+/// Insert `request_id` into a JSON object body; other bodies are unchanged
+fn with_request_id(body: actix_web::web::Bytes, request_id: &RequestId) -> actix_web::web::Bytes {
+    match serde_json::from_slice::<serde_json::Value>(&body) {
+        Ok(serde_json::Value::Object(mut object)) => {
+            object
+                .entry("request_id")
+                .or_insert_with(|| request_id.as_str().into());
+            serde_json::to_vec(&object).map(Into::into).unwrap_or(body)
+        }
+        _ => body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body, TestRequest};
+    use actix_web::{web, App};
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    /// Collects formatted log output for assertions
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn get(path: &str, request_id: &str) -> ServiceResponse<BoxBody> {
+        let app = init_service(
+            App::new()
+                .wrap(RequestIdMiddleware)
+                .route(
+                    "/tickets",
+                    web::get().to(|| async {
+                        tracing::info!("Listing tickets");
+                        HttpResponse::Ok().json(serde_json::json!([]))
+                    }),
+                )
+                .route(
+                    "/missing",
+                    web::get().to(|| async {
+                        HttpResponse::NotFound().json(serde_json::json!({ "error": "Ticket not found" }))
+                    }),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::get()
+            .uri(path)
+            .insert_header(("X-Request-Id", request_id))
+            .to_request();
+        call_service(&app, req).await
+    }
+
+    #[actix_web::test]
+    async fn inbound_id_is_echoed_and_carried_by_logs() {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let resp = get("/tickets", "req-7f3a").await;
+
+        assert_eq!(resp.headers().get("x-request-id").unwrap(), "req-7f3a");
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let line = logs.lines().find(|l| l.contains("Listing tickets")).unwrap();
+        assert!(line.contains("request_id=req-7f3a"), "log line without request id: {line}");
+    }
+
+    #[actix_web::test]
+    async fn ids_are_generated_and_added_to_error_bodies() {
+        // Ids that could forge log lines are replaced
+        let resp = get("/missing", "abc def").await;
+        let id = resp.headers().get("x-request-id").unwrap().to_str().unwrap().to_string();
+        assert!(uuid::Uuid::parse_str(&id).is_ok());
+
+        let body: serde_json::Value = serde_json::from_slice(&read_body(resp).await).unwrap();
+        assert_eq!(body["error"], "Ticket not found");
+        assert_eq!(body["request_id"], id);
+    }
+}
@@ -1,58 +1,134 @@
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    http::header,
-    Error,
+    http::header::{self, HeaderValue},
+    Error, HttpMessage, HttpRequest,
 };
+use base64::{engine::general_purpose, Engine as _};
+use rand::Rng;
 use futures::future::LocalBoxFuture;
 use std::future::{ready, Ready};
+use tracing::warn;
+
+/// Placeholder in the SPA shell (index.html) replaced with the per-response nonce
+pub const CSP_NONCE_PLACEHOLDER: &str = "__CSP_NONCE__";
+
+/// Per-response CSP nonce, stored in request extensions by `SecurityHeaders`
+///
+/// Handlers that render HTML read it to tag inline `<script>` elements.
+#[derive(Clone, Debug)]
+pub struct CspNonce(pub String);
+
+impl CspNonce {
+    fn generate() -> Self {
+        let bytes: [u8; 16] = rand::thread_rng().gen();
+        Self(general_purpose::STANDARD.encode(bytes))
+    }
+
+    /// Nonce for the current request, if the middleware ran
+    pub fn from_request(req: &HttpRequest) -> Option<String> {
+        req.extensions().get::<CspNonce>().map(|n| n.0.clone())
+    }
+}
+
+/// Substitute the CSP nonce placeholder in an HTML document
+pub fn apply_csp_nonce(html: &[u8], nonce: Option<&str>) -> Vec<u8> {
+    let html = String::from_utf8_lossy(html);
+    if !html.contains(CSP_NONCE_PLACEHOLDER) {
+        return html.into_owned().into_bytes();
+    }
+    html.replace(CSP_NONCE_PLACEHOLDER, nonce.unwrap_or_default())
+        .into_bytes()
+}
 
 /// Security headers middleware
 /// Adds essential security headers to all responses following OWASP best practices
 pub struct SecurityHeaders;
 
 impl SecurityHeaders {
-    /// Get Content-Security-Policy header value based on environment
-    /// Development has relaxed rules for hot reload, production is strict
-    fn get_csp_header() -> String {
-        let env = std::env::var("ENVIRONMENT")
-            .unwrap_or_else(|_| "development".to_string())
-            .to_lowercase();
+    /// Build the policy, adding `'nonce-…'` to script-src when given
+    ///
+    /// Plugin bundles are served from our own `/api/plugins/{uuid}/bundle` route and
+    /// loaded with a same-origin `import()`, so `'self'` covers them while external
+    /// script hosts stay blocked.
+    fn build_csp(production: bool, nonce: Option<&str>, report_uri: Option<&str>) -> String {
+        let nonce_source = nonce.map(|n| format!(" 'nonce-{n}'")).unwrap_or_default();
 
-        if env == "production" {
+        let mut directives = if production {
             // Strict CSP for production
-            concat!(
-                "default-src 'self'; ",
-                "script-src 'self'; ",
-                "worker-src 'self' blob:; ", // Allow web workers from blob URLs
-                "style-src 'self' 'unsafe-inline'; ", // unsafe-inline needed for some frameworks
-                "img-src 'self' data: https:; ",
-                "font-src 'self' data:; ",
-                "connect-src 'self' blob:; ", // blob: for voice note uploads
-                "media-src 'self' blob:; ", // blob: for audio/video playback (voice notes)
-                "frame-ancestors 'none'; ",
-                "base-uri 'self'; ",
-                "form-action 'self'"
-            )
-            .to_string()
+            vec![
+                "default-src 'self'".to_string(),
+                format!("script-src 'self'{nonce_source}"),
+                "worker-src 'self' blob:".to_string(), // Allow web workers from blob URLs
+                "style-src 'self' 'unsafe-inline'".to_string(), // unsafe-inline needed for some frameworks
+                "img-src 'self' data: https:".to_string(),
+                "font-src 'self' data:".to_string(),
+                "connect-src 'self' blob:".to_string(), // blob: for voice note uploads
+                "media-src 'self' blob:".to_string(), // blob: for audio/video playback (voice notes)
+                "object-src 'none'".to_string(),
+                "frame-ancestors 'none'".to_string(),
+                "base-uri 'self'".to_string(),
+                "form-action 'self'".to_string(),
+            ]
         } else {
             // Relaxed CSP for development (allows Vue dev server hot reload)
-            concat!(
-                "default-src 'self'; ",
-                "script-src 'self' 'unsafe-eval'; ", // unsafe-eval for Vue dev tools
-                "worker-src 'self' blob:; ", // Allow web workers from blob URLs
-                "style-src 'self' 'unsafe-inline'; ",
-                "img-src 'self' data: https:; ",
-                "font-src 'self' data:; ",
-                "connect-src 'self' ws: wss: blob:; ", // WebSocket for hot reload, blob: for voice notes
-                "media-src 'self' blob:; ", // blob: for audio/video playback (voice notes)
-                "frame-ancestors 'none'; ",
-                "base-uri 'self'; ",
-                "form-action 'self'"
-            )
-            .to_string()
+            vec![
+                "default-src 'self'".to_string(),
+                format!("script-src 'self' 'unsafe-eval'{nonce_source}"), // unsafe-eval for Vue dev tools
+                "worker-src 'self' blob:".to_string(), // Allow web workers from blob URLs
+                "style-src 'self' 'unsafe-inline'".to_string(),
+                "img-src 'self' data: https:".to_string(),
+                "font-src 'self' data:".to_string(),
+                "connect-src 'self' ws: wss: blob:".to_string(), // WebSocket for hot reload, blob: for voice notes
+                "media-src 'self' blob:".to_string(), // blob: for audio/video playback (voice notes)
+                "object-src 'none'".to_string(),
+                "frame-ancestors 'none'".to_string(),
+                "base-uri 'self'".to_string(),
+                "form-action 'self'".to_string(),
+            ]
+        };
+
+        if let Some(uri) = report_uri {
+            directives.push(format!("report-uri {uri}"));
+        }
+
+        directives.join("; ")
+    }
+
+    fn is_production() -> bool {
+        std::env::var("ENVIRONMENT")
+            .unwrap_or_else(|_| "development".to_string())
+            .to_lowercase()
+            == "production"
+    }
+
+    /// CSP_REPORT_ONLY=true sends Content-Security-Policy-Report-Only for rollout
+    fn csp_report_only() -> bool {
+        std::env::var("CSP_REPORT_ONLY")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "true" | "1" | "yes"))
+            .unwrap_or(false)
+    }
+
+    /// CSP_REPORT_URI receives violation reports. A value that isn't a single
+    /// header-safe URI is ignored rather than spliced into the policy.
+    fn csp_report_uri() -> Option<String> {
+        let uri = std::env::var("CSP_REPORT_URI")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())?;
+
+        if Self::is_valid_report_uri(&uri) {
+            Some(uri)
+        } else {
+            warn!(value = %uri, "Ignoring invalid CSP_REPORT_URI");
+            None
         }
     }
 
+    fn is_valid_report_uri(uri: &str) -> bool {
+        !uri.contains(|c: char| c.is_whitespace() || c == ';' || c == ',')
+            && HeaderValue::from_str(uri).is_ok()
+    }
+
     /// Check if HSTS should be enabled (production only)
     fn should_enable_hsts() -> bool {
         let env = std::env::var("ENVIRONMENT")
@@ -75,9 +151,17 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
+        // Built once; each response only substitutes its nonce
+        let csp_template = Self::build_csp(
+            Self::is_production(),
+            Some(CSP_NONCE_PLACEHOLDER),
+            Self::csp_report_uri().as_deref(),
+        );
+
         ready(Ok(SecurityHeadersMiddleware {
             service,
-            csp_header: Self::get_csp_header(),
+            csp_template,
+            csp_report_only: Self::csp_report_only(),
             enable_hsts: Self::should_enable_hsts(),
         }))
     }
@@ -85,7 +169,9 @@ where
 
 pub struct SecurityHeadersMiddleware<S> {
     service: S,
-    csp_header: String,
+    /// Policy with `CSP_NONCE_PLACEHOLDER` where the nonce goes
+    csp_template: String,
+    csp_report_only: bool,
     enable_hsts: bool,
 }
 
@@ -102,7 +188,17 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let csp_header = self.csp_header.clone();
+        // Fresh nonce per response, exposed to handlers via request extensions
+        let nonce = CspNonce::generate();
+        // The policy parts are fixed and the nonce is base64, so this only
+        // fails if the template itself is broken
+        let csp_header = HeaderValue::from_str(&self.csp_template.replace(CSP_NONCE_PLACEHOLDER, &nonce.0));
+        let csp_header_name = if self.csp_report_only {
+            header::CONTENT_SECURITY_POLICY_REPORT_ONLY
+        } else {
+            header::CONTENT_SECURITY_POLICY
+        };
+        req.extensions_mut().insert(nonce);
         let enable_hsts = self.enable_hsts;
 
         // Capture request path for cache control decisions
@@ -135,12 +231,16 @@ where
                 // For other paths, let the specific handlers set Cache-Control
             }
 
-            // Content-Security-Policy
-            if !headers.contains_key(header::CONTENT_SECURITY_POLICY) {
-                headers.insert(
-                    header::CONTENT_SECURITY_POLICY,
-                    csp_header.parse().unwrap(),
-                );
+            // Content-Security-Policy (or Report-Only during rollout)
+            if !headers.contains_key(header::CONTENT_SECURITY_POLICY)
+                && !headers.contains_key(header::CONTENT_SECURITY_POLICY_REPORT_ONLY)
+            {
+                match csp_header {
+                    Ok(value) => {
+                        headers.insert(csp_header_name, value);
+                    }
+                    Err(e) => warn!(error = %e, "Skipping invalid Content-Security-Policy header"),
+                }
             }
 
             // X-Frame-Options (prevents clickjacking)
@@ -197,10 +297,18 @@ mod tests {
 
     #[test]
     fn test_csp_header_generation() {
-        // Just test that it returns a non-empty string
-        let csp = SecurityHeaders::get_csp_header();
-        assert!(!csp.is_empty());
+        let csp = SecurityHeaders::build_csp(false, None, None);
         assert!(csp.contains("default-src 'self'"));
+        assert!(HeaderValue::from_str(&csp).is_ok());
+    }
+
+    #[test]
+    fn report_uri_that_would_break_the_policy_is_rejected() {
+        assert!(SecurityHeaders::is_valid_report_uri("/api/csp-report"));
+        assert!(SecurityHeaders::is_valid_report_uri("https://reports.example.com/csp?src=nosdesk"));
+        assert!(!SecurityHeaders::is_valid_report_uri("/api/csp-report; script-src *"));
+        assert!(!SecurityHeaders::is_valid_report_uri("/a /b"));
+        assert!(!SecurityHeaders::is_valid_report_uri("/api/csp-report\u{7f}"));
     }
 
     #[test]
@@ -210,4 +318,49 @@ mod tests {
         let should_enable = SecurityHeaders::should_enable_hsts();
         assert!(!should_enable || std::env::var("ENVIRONMENT").unwrap_or_default() == "production");
     }
+
+    #[actix_web::test]
+    async fn csp_header_contains_response_nonce() {
+        use actix_web::test::{call_service, init_service, read_body, TestRequest};
+        use actix_web::{web, App, HttpResponse};
+
+        let app = init_service(App::new().wrap(SecurityHeaders).route(
+            "/",
+            web::get().to(|req: HttpRequest| async move {
+                HttpResponse::Ok().body(CspNonce::from_request(&req).unwrap_or_default())
+            }),
+        ))
+        .await;
+
+        let resp = call_service(&app, TestRequest::get().uri("/").to_request()).await;
+        let csp = resp
+            .headers()
+            .get(header::CONTENT_SECURITY_POLICY)
+            .or_else(|| resp.headers().get(header::CONTENT_SECURITY_POLICY_REPORT_ONLY))
+            .expect("CSP header present")
+            .to_str()
+            .unwrap()
+            .to_string();
+        let nonce = String::from_utf8(read_body(resp).await.to_vec()).unwrap();
+
+        assert!(!nonce.is_empty());
+        assert!(csp.contains(&format!("'nonce-{nonce}'")));
+    }
+
+    #[test]
+    fn production_csp_blocks_external_scripts() {
+        let csp = SecurityHeaders::build_csp(true, Some("abc"), Some("/api/csp-report"));
+        assert!(csp.contains("script-src 'self' 'nonce-abc';"));
+        assert!(!csp.contains("unsafe-eval"));
+        assert!(csp.contains("object-src 'none'"));
+        assert!(csp.ends_with("report-uri /api/csp-report"));
+    }
+
+    #[test]
+    fn nonce_placeholder_is_replaced() {
+        let html = format!("<script nonce=\"{CSP_NONCE_PLACEHOLDER}\">init()</script>");
+        let out = apply_csp_nonce(html.as_bytes(), Some("xyz"));
+        assert_eq!(out, b"<script nonce=\"xyz\">init()</script>");
+        assert_eq!(apply_csp_nonce(b"<p>plain</p>", Some("xyz")), b"<p>plain</p>");
+    }
 }
@@ -106,6 +106,13 @@ pub struct Ticket {
     pub closed_at: Option<NaiveDateTime>,
     pub closed_by: Option<Uuid>,
     pub category_id: Option<i32>,
+    pub version: i32,
+    /// Set when this ticket was merged into another (and closed)
+    pub merged_into_id: Option<i32>,
+    /// When a technician first replied publicly, for SLA tracking
+    pub first_response_at: Option<NaiveDateTime>,
+    /// Exempts the ticket's attachments from the retention policy
+    pub legal_hold: bool,
 }
 
 // Ticket implementation removed - serialization now handled by serde attributes
@@ -122,7 +129,7 @@ pub struct NewTicket {
 }
 
 // Add a new struct for partial ticket updates
-#[derive(Debug, Default, Serialize, Deserialize, AsChangeset)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, AsChangeset)]
 #[diesel(table_name = crate::schema::tickets)]
 pub struct TicketUpdate {
     pub title: Option<String>,
@@ -132,6 +139,7 @@ pub struct TicketUpdate {
     pub assignee_uuid: Option<Option<Uuid>>,
     pub updated_at: Option<NaiveDateTime>,
     pub closed_at: Option<Option<NaiveDateTime>>,
+    pub closed_by: Option<Option<Uuid>>,
     pub category_id: Option<Option<i32>>,
 }
 
@@ -161,6 +169,7 @@ pub struct Device {
     pub os_version: Option<String>,
     pub is_managed: Option<bool>,
     pub enrollment_date: Option<NaiveDateTime>,
+    pub warranty_expiry_date: Option<NaiveDate>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Insertable, AsChangeset)]
@@ -185,6 +194,7 @@ pub struct NewDevice {
     pub os_version: Option<String>,
     pub is_managed: Option<bool>,
     pub enrollment_date: Option<NaiveDateTime>,
+    pub warranty_expiry_date: Option<NaiveDate>,
 }
 
 #[derive(Debug, Serialize, Deserialize, AsChangeset)]
@@ -209,9 +219,24 @@ pub struct DeviceUpdate {
     pub os_version: Option<String>,
     pub is_managed: Option<bool>,
     pub enrollment_date: Option<NaiveDateTime>,
+    pub warranty_expiry_date: Option<NaiveDate>,
     pub updated_at: Option<NaiveDateTime>,
 }
 
+/// One stint of a user as a device's primary user; `unassigned_at` is None
+/// for the current assignment
+#[derive(Debug, Clone, Serialize, Deserialize, Identifiable, Queryable, Associations)]
+#[diesel(table_name = crate::schema::device_assignment_history)]
+#[diesel(belongs_to(Device))]
+pub struct DeviceAssignmentHistory {
+    pub id: i32,
+    pub device_id: i32,
+    pub user_uuid: Uuid,
+    pub assigned_by: Option<Uuid>,
+    pub assigned_at: NaiveDateTime,
+    pub unassigned_at: Option<NaiveDateTime>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Identifiable, Queryable, Associations)]
 #[diesel(table_name = crate::schema::ticket_devices)]
 #[diesel(belongs_to(Ticket))]
@@ -231,6 +256,154 @@ pub struct NewTicketDevice {
     pub device_id: i32,
 }
 
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[diesel(table_name = crate::schema::ticket_watchers)]
+pub struct NewTicketWatcher {
+    pub ticket_id: i32,
+    pub user_uuid: Uuid,
+}
+
+/// Time a technician logged against a ticket
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[diesel(table_name = crate::schema::ticket_worklogs)]
+pub struct TicketWorklog {
+    pub id: i32,
+    pub ticket_id: i32,
+    pub user_uuid: Uuid,
+    pub minutes: i32,
+    pub note: Option<String>,
+    /// When the work was done
+    pub logged_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::ticket_worklogs)]
+pub struct NewTicketWorklog {
+    pub ticket_id: i32,
+    pub user_uuid: Uuid,
+    pub minutes: i32,
+    pub note: Option<String>,
+    pub logged_at: NaiveDateTime,
+}
+
+/// A worklog entry with the technician who logged it
+#[derive(Debug, Serialize)]
+pub struct TicketWorklogWithUser {
+    #[serde(flatten)]
+    pub worklog: TicketWorklog,
+    pub user: UserInfo,
+}
+
+/// SLA targets for one priority; `None` means that metric has no target
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+#[diesel(table_name = crate::schema::sla_targets)]
+pub struct SlaTarget {
+    pub priority: TicketPriority,
+    pub first_response_minutes: Option<i32>,
+    pub resolution_minutes: Option<i32>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Insertable, AsChangeset)]
+#[diesel(table_name = crate::schema::sla_targets)]
+#[diesel(treat_none_as_null = true)]
+pub struct NewSlaTarget {
+    pub priority: TicketPriority,
+    pub first_response_minutes: Option<i32>,
+    pub resolution_minutes: Option<i32>,
+}
+
+/// The two measured SLA metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaMetric {
+    FirstResponse,
+    Resolution,
+}
+
+impl SlaMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlaMetric::FirstResponse => "first_response",
+            SlaMetric::Resolution => "resolution",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaState {
+    /// Not done yet and still within the target
+    OnTrack,
+    /// Done within the target
+    Met,
+    /// Done late, or not done and past the target
+    Breached,
+}
+
+/// Where a ticket stands against one SLA target
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlaMetricStatus {
+    pub state: SlaState,
+    pub target_minutes: i32,
+    pub due_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+impl SlaMetricStatus {
+    fn evaluate(
+        started_at: NaiveDateTime,
+        target_minutes: i32,
+        completed_at: Option<NaiveDateTime>,
+        now: NaiveDateTime,
+    ) -> Self {
+        let due_at = started_at + chrono::Duration::minutes(i64::from(target_minutes));
+        let state = match completed_at {
+            Some(done) if done <= due_at => SlaState::Met,
+            Some(_) => SlaState::Breached,
+            None if now > due_at => SlaState::Breached,
+            None => SlaState::OnTrack,
+        };
+        Self { state, target_minutes, due_at, completed_at }
+    }
+}
+
+/// SLA status of a ticket; a metric is `None` when its priority has no target
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TicketSla {
+    pub first_response: Option<SlaMetricStatus>,
+    pub resolution: Option<SlaMetricStatus>,
+}
+
+impl TicketSla {
+    /// Measure a ticket against its priority's targets. Closing a ticket
+    /// counts as responding to it if nobody had.
+    pub fn evaluate(ticket: &Ticket, target: &SlaTarget, now: NaiveDateTime) -> Self {
+        let closed_at = ticket.closed_at.filter(|_| ticket.status == TicketStatus::Closed);
+        Self {
+            first_response: target.first_response_minutes.map(|minutes| {
+                SlaMetricStatus::evaluate(ticket.created_at, minutes, ticket.first_response_at.or(closed_at), now)
+            }),
+            resolution: target
+                .resolution_minutes
+                .map(|minutes| SlaMetricStatus::evaluate(ticket.created_at, minutes, closed_at, now)),
+        }
+    }
+
+    /// Metrics currently breached
+    pub fn breached(&self) -> Vec<SlaMetric> {
+        [
+            (SlaMetric::FirstResponse, &self.first_response),
+            (SlaMetric::Resolution, &self.resolution),
+        ]
+        .into_iter()
+        .filter(|(_, status)| status.as_ref().is_some_and(|s| s.state == SlaState::Breached))
+        .map(|(metric, _)| metric)
+        .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Identifiable, Queryable, Associations)]
 #[diesel(table_name = crate::schema::comments)]
 #[diesel(belongs_to(Ticket))]
@@ -244,6 +417,7 @@ pub struct Comment {
     pub updated_at: NaiveDateTime,
     pub is_edited: bool,
     pub edit_count: i32,
+    pub is_internal: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Insertable)]
@@ -252,6 +426,7 @@ pub struct NewComment {
     pub content: String,
     pub ticket_id: i32,
     pub user_uuid: Uuid,
+    pub is_internal: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Identifiable, Queryable, Associations, Clone)]
@@ -366,6 +541,71 @@ pub struct CompleteTicket {
     pub article_content: Option<String>,
     pub linked_tickets: Vec<i32>,
     pub projects: Vec<Project>,
+    /// `None` when the ticket's priority has no SLA targets
+    pub sla: Option<TicketSla>,
+    /// Minutes logged against the ticket in worklogs
+    pub total_time_spent: i64,
+    pub custom_fields: Vec<CustomFieldValue>,
+}
+
+// Unified ticket activity timeline entry (assignment log, comments, device links, ...)
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEvent {
+    pub timestamp: NaiveDateTime,
+    pub actor: Option<UserInfo>,
+    #[serde(flatten)]
+    pub kind: TimelineEventKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TimelineEventKind {
+    Created,
+    Assignment {
+        previous_assignee: Option<UserInfo>,
+        new_assignee: Option<UserInfo>,
+        method: AssignmentMethod,
+        trigger: String,
+        rule_name: Option<String>,
+    },
+    Comment {
+        comment_id: i32,
+        content: String,
+    },
+    DeviceLinked {
+        device_id: i32,
+        device_name: String,
+    },
+    FieldChanged {
+        field: String,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    },
+    Closed,
+}
+
+// Ticket field change audit trail (status, priority, category)
+#[derive(Debug, Clone, Serialize, Deserialize, Identifiable, Queryable, Associations)]
+#[diesel(table_name = crate::schema::ticket_audit_log)]
+#[diesel(belongs_to(Ticket))]
+pub struct TicketAuditLog {
+    pub id: i32,
+    pub ticket_id: i32,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_by: Option<Uuid>,
+    pub changed_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::ticket_audit_log)]
+pub struct NewTicketAuditLog {
+    pub ticket_id: i32,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_by: Option<Uuid>,
 }
 
 // Simplified ticket for lists - includes user info but not heavy data like comments
@@ -385,15 +625,19 @@ pub struct CommentWithAttachments {
     pub user: Option<UserInfoWithAvatar>,  // Use enhanced user info with avatar
 }
 
-// JSON import struct that matches the structure in tickets.json
+// Ticket in the import/export format; see services::ticket_json for the schema
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TicketJson {
     pub id: i32,
     pub title: String,
     pub status: String,
     pub priority: String,
+    /// RFC 3339 timestamps
     pub created: String,
     pub modified: String,
+    #[serde(default)]
+    pub closed_at: Option<String>,
+    /// User UUID, or empty when unassigned
     pub assignee: String,
     pub requester: String,
     pub device: Option<DeviceJson>,
@@ -420,6 +664,8 @@ pub struct CommentJson {
     pub user_uuid: String,
     #[serde(rename = "createdAt")]
     pub created_at: String,
+    #[serde(default)]
+    pub is_internal: bool,
     pub attachments: Vec<AttachmentJson>,
 }
 
@@ -504,6 +750,18 @@ pub struct DocumentationPageWithChildren {
     pub children: Vec<DocumentationPage>,
 }
 
+/// A page in the documentation table of contents
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DocumentationTreeNode {
+    pub id: i32,
+    pub title: String,
+    pub slug: Option<String>,
+    pub icon: Option<String>,
+    pub status: DocumentationStatus,
+    pub display_order: Option<i32>,
+    pub children: Vec<DocumentationTreeNode>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PageOrder {
     pub page_id: i32,
@@ -568,6 +826,12 @@ pub struct User {
     pub mfa_enabled: bool,
     pub mfa_backup_codes: Option<serde_json::Value>,
     pub passkey_credentials: Option<serde_json::Value>,
+    /// Preferred notification language; None uses the default (English)
+    pub locale: Option<String>,
+    /// Set when the account was deactivated; the record stays for attribution
+    pub deactivated_at: Option<NaiveDateTime>,
+    /// IANA zone for calendar date filters; None uses the default
+    pub timezone: Option<String>,
 }
 
 // New user for creation
@@ -659,13 +923,16 @@ pub struct UserResponse {
     pub banner_url: Option<String>,
     pub avatar_thumb: Option<String>,
     pub theme: Option<String>,
+    pub locale: Option<String>,
     pub microsoft_uuid: Option<Uuid>,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    pub deactivated_at: Option<chrono::NaiveDateTime>,
+    pub timezone: Option<String>,
 }
 
 // User info for comments - minimal user data to include with comments
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UserInfo {
     pub uuid: Uuid,
     pub name: String,
@@ -695,9 +962,12 @@ impl From<User> for UserResponse {
             banner_url: user.banner_url,
             avatar_thumb: user.avatar_thumb,
             theme: user.theme,
+            locale: user.locale,
             microsoft_uuid: user.microsoft_uuid,
             created_at: user.created_at,
             updated_at: user.updated_at,
+            deactivated_at: user.deactivated_at,
+            timezone: user.timezone,
         }
     }
 }
@@ -738,6 +1008,17 @@ pub struct UserEmail {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub created_by: Option<Uuid>,
+    /// Set when the email provider reports a hard bounce
+    pub bounced_at: Option<NaiveDateTime>,
+    /// Set when the recipient marks our email as spam
+    pub complained_at: Option<NaiveDateTime>,
+}
+
+impl UserEmail {
+    /// Whether notification email to this address is suppressed
+    pub fn is_suppressed(&self) -> bool {
+        self.bounced_at.is_some() || self.complained_at.is_some()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Insertable)]
@@ -912,6 +1193,10 @@ pub struct NewCommentWithAttachments {
     pub content: String,
     // user_id/user_uuid removed - extracted from JWT token for security
     pub attachments: Vec<AttachmentData>,
+    /// Only visible to technicians and admins
+    #[serde(default)]
+    pub is_internal: bool,
+    pub watch: Option<bool>, // Defaults to watching the ticket after commenting
 }
 
 // JWT Claims structure
@@ -925,6 +1210,15 @@ pub struct Claims {
     pub scope: String, // Token scope: "full" for normal sessions, "mfa_recovery" for limited MFA management
     pub exp: usize,   // Expiration time
     pub iat: usize,   // Issued at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonator: Option<String>, // Real admin's UUID when an admin is acting as `sub`
+}
+
+impl Claims {
+    /// Whether these claims belong to an admin impersonating `sub`
+    pub fn is_impersonating(&self) -> bool {
+        self.impersonator.is_some()
+    }
 }
 
 // Default scope for backward compatibility
@@ -1534,6 +1828,9 @@ pub struct RefreshToken {
     pub created_at: chrono::NaiveDateTime,
     pub expires_at: chrono::NaiveDateTime,
     pub revoked_at: Option<chrono::NaiveDateTime>,
+    /// The session this token refreshes; None for tokens issued before
+    /// sessions were linked
+    pub session_id: Option<i32>,
 }
 
 /// New refresh token for creation
@@ -1543,6 +1840,7 @@ pub struct NewRefreshToken {
     pub token_hash: String,
     pub user_uuid: Uuid,
     pub expires_at: chrono::NaiveDateTime,
+    pub session_id: Option<i32>,
 }
 
 // ===== API TOKEN MODELS =====
@@ -1643,6 +1941,109 @@ impl From<ActiveSession> for ActiveSessionResponse {
     }
 }
 
+// ===== AUDIT LOG MODELS =====
+
+/// An entry in the unified audit trail
+#[derive(Debug, Clone, Serialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = crate::schema::audit_log)]
+pub struct AuditLogEntry {
+    pub id: i32,
+    pub actor_uuid: Option<Uuid>,
+    pub action: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub metadata: serde_json::Value,
+    pub created_at: NaiveDateTime,
+}
+
+/// New audit log entry for insertion
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::audit_log)]
+pub struct NewAuditLogEntry {
+    pub actor_uuid: Option<Uuid>,
+    pub action: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub metadata: serde_json::Value,
+}
+
+// ===== PERMISSION GRANT MODELS =====
+
+/// A permission granted to a user or group beyond their role's bundle
+#[derive(Debug, Clone, Serialize, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = crate::schema::permission_grants)]
+pub struct PermissionGrant {
+    pub id: i32,
+    pub permission: String,
+    pub user_uuid: Option<Uuid>,
+    pub group_id: Option<i32>,
+    pub granted_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+}
+
+/// New permission grant for insertion
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::permission_grants)]
+pub struct NewPermissionGrant {
+    pub permission: String,
+    pub user_uuid: Option<Uuid>,
+    pub group_id: Option<i32>,
+    pub granted_by: Option<Uuid>,
+}
+
+// ===== IDEMPOTENCY KEY MODELS =====
+
+/// A create request seen under an Idempotency-Key, with its stored response.
+/// The response fields are None while the original request is still running.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = crate::schema::idempotency_keys)]
+pub struct IdempotencyKey {
+    pub user_uuid: Uuid,
+    pub scope: String,
+    pub idempotency_key: String,
+    pub request_hash: String,
+    pub resource_id: Option<i32>,
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// New idempotency key claim for insertion
+#[derive(Debug, Insertable)]
+#[diesel(table_name = crate::schema::idempotency_keys)]
+pub struct NewIdempotencyKey<'a> {
+    pub user_uuid: Uuid,
+    pub scope: &'a str,
+    pub idempotency_key: &'a str,
+    pub request_hash: &'a str,
+}
+
+// ===== ACCOUNT LOCKOUT MODELS =====
+
+/// Failed password logins for an account within the current window, and the
+/// lock they triggered, if any
+#[derive(Debug, Clone, PartialEq, Eq, Queryable, Selectable, Insertable, AsChangeset)]
+#[diesel(table_name = crate::schema::account_lockouts)]
+pub struct AccountLockout {
+    pub user_uuid: Uuid,
+    pub failed_attempts: i32,
+    pub window_started_at: NaiveDateTime,
+    pub locked_until: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+}
+
+// ===== KNOWN DEVICE MODELS =====
+
+/// A coarse device fingerprint a user has signed in from
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = crate::schema::known_devices)]
+pub struct KnownDevice {
+    pub user_uuid: Uuid,
+    pub fingerprint: String,
+    pub first_seen_at: NaiveDateTime,
+    pub last_seen_at: NaiveDateTime,
+}
+
 // ===== SECURITY EVENTS MODELS =====
 
 /// Security events for MFA and authentication monitoring
@@ -1702,6 +2103,8 @@ pub enum SecurityEventType {
     AccountLocked,
     #[serde(rename = "suspicious_activity")]
     SuspiciousActivity,
+    #[serde(rename = "secret_tampered")]
+    SecretTampered,
 }
 
 impl SecurityEventType {
@@ -1719,6 +2122,7 @@ impl SecurityEventType {
             Self::SessionRevoked => "session_revoked",
             Self::AccountLocked => "account_locked",
             Self::SuspiciousActivity => "suspicious_activity",
+            Self::SecretTampered => "secret_tampered",
         }
     }
 }
@@ -2265,6 +2669,7 @@ pub struct UserGroup {
     pub group_id: i32,
     pub created_at: NaiveDateTime,
     pub created_by: Option<Uuid>,
+    pub is_manager: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Insertable)]
@@ -2316,6 +2721,13 @@ pub struct TicketCategory {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub created_by: Option<Uuid>,
+    /// Assignee when no assignment rule matches a ticket in this category
+    pub default_assignee_uuid: Option<Uuid>,
+    /// Group to pick an assignee from when there is no default assignee
+    pub default_group_id: Option<i32>,
+    /// Fields a ticket needs to be created in this category, as a list of
+    /// `services::category_fields::RequiredField`
+    pub required_fields: serde_json::Value,
 }
 
 #[derive(Debug, Serialize, Deserialize, Insertable)]
@@ -2328,6 +2740,9 @@ pub struct NewTicketCategory {
     pub display_order: i32,
     pub is_active: bool,
     pub created_by: Option<Uuid>,
+    pub default_assignee_uuid: Option<Uuid>,
+    pub default_group_id: Option<i32>,
+    pub required_fields: serde_json::Value,
 }
 
 #[derive(Debug, Serialize, Deserialize, AsChangeset)]
@@ -2339,9 +2754,74 @@ pub struct TicketCategoryUpdate {
     pub icon: Option<String>,
     pub display_order: Option<i32>,
     pub is_active: Option<bool>,
+    pub default_assignee_uuid: Option<Option<Uuid>>,
+    pub default_group_id: Option<Option<i32>>,
+    pub required_fields: Option<serde_json::Value>,
     pub updated_at: Option<NaiveDateTime>,
 }
 
+// Custom ticket field; see services::custom_fields for types and values
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[diesel(table_name = crate::schema::custom_field_definitions)]
+pub struct CustomFieldDefinition {
+    pub id: i32,
+    pub key: String,
+    pub label: String,
+    /// text, number, date, select or checkbox
+    pub field_type: String,
+    /// Choices of a select field
+    pub options: serde_json::Value,
+    /// Category the field applies to; every category when None
+    pub category_id: Option<i32>,
+    pub display_order: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub created_by: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[diesel(table_name = crate::schema::custom_field_definitions)]
+pub struct NewCustomFieldDefinition {
+    pub key: String,
+    pub label: String,
+    pub field_type: String,
+    pub options: serde_json::Value,
+    pub category_id: Option<i32>,
+    pub display_order: i32,
+    pub created_by: Option<Uuid>,
+}
+
+// Key and type are fixed once defined, so existing values stay valid
+#[derive(Debug, Default, Serialize, Deserialize, AsChangeset)]
+#[diesel(table_name = crate::schema::custom_field_definitions)]
+pub struct CustomFieldDefinitionUpdate {
+    pub label: Option<String>,
+    pub options: Option<serde_json::Value>,
+    pub category_id: Option<Option<i32>>,
+    pub display_order: Option<i32>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = crate::schema::ticket_custom_field_values)]
+pub struct NewTicketCustomFieldValue {
+    pub ticket_id: i32,
+    pub field_id: i32,
+    pub value: String,
+    pub updated_at: NaiveDateTime,
+    pub updated_by: Option<Uuid>,
+}
+
+// A ticket's value for a custom field, with the field's definition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomFieldValue {
+    pub field_id: i32,
+    pub key: String,
+    pub label: String,
+    pub field_type: String,
+    pub value: String,
+}
+
 // Category with visibility information for admin views
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CategoryWithVisibility {
@@ -2372,6 +2852,62 @@ pub struct NewCategoryGroupVisibility {
     pub created_by: Option<Uuid>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+#[diesel(table_name = crate::schema::doc_group_visibility)]
+pub struct DocGroupVisibility {
+    pub page_id: i32,
+    pub group_id: i32,
+    pub created_at: NaiveDateTime,
+    pub created_by: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[diesel(table_name = crate::schema::doc_group_visibility)]
+pub struct NewDocGroupVisibility {
+    pub page_id: i32,
+    pub group_id: i32,
+    pub created_by: Option<Uuid>,
+}
+
+// ============================================================================
+// Canned Responses - Reply Templates
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, Identifiable, Queryable, Associations)]
+#[diesel(table_name = crate::schema::canned_responses)]
+#[diesel(belongs_to(TicketCategory, foreign_key = category_id))]
+pub struct CannedResponse {
+    pub id: i32,
+    pub title: String,
+    pub body: String,
+    pub category_id: Option<i32>,
+    pub is_shared: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[diesel(table_name = crate::schema::canned_responses)]
+pub struct NewCannedResponse {
+    pub title: String,
+    pub body: String,
+    pub category_id: Option<i32>,
+    pub is_shared: bool,
+    pub created_by: Option<Uuid>,
+}
+
+/// Full replacement of a canned response's editable fields
+#[derive(Debug, Serialize, Deserialize, AsChangeset)]
+#[diesel(table_name = crate::schema::canned_responses)]
+#[diesel(treat_none_as_null = true)]
+pub struct CannedResponseUpdate {
+    pub title: String,
+    pub body: String,
+    pub category_id: Option<i32>,
+    pub is_shared: bool,
+}
+
 // ============================================================================
 // Assignment Rules - Automatic Ticket Assignment
 // ============================================================================
@@ -2448,6 +2984,7 @@ pub struct AssignmentRule {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub created_by: Option<Uuid>,
+    pub trigger_on_sla_breach: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Insertable)]
@@ -2462,6 +2999,7 @@ pub struct NewAssignmentRule {
     pub target_group_id: Option<i32>,
     pub trigger_on_create: bool,
     pub trigger_on_category_change: bool,
+    pub trigger_on_sla_breach: bool,
     pub category_id: Option<i32>,
     pub conditions: Option<serde_json::Value>,
     pub created_by: Option<Uuid>,
@@ -2479,6 +3017,7 @@ pub struct AssignmentRuleUpdate {
     pub target_group_id: Option<Option<i32>>,
     pub trigger_on_create: Option<bool>,
     pub trigger_on_category_change: Option<bool>,
+    pub trigger_on_sla_breach: Option<bool>,
     pub category_id: Option<Option<i32>>,
     pub conditions: Option<serde_json::Value>,
     pub updated_at: Option<NaiveDateTime>,
@@ -2559,6 +3098,9 @@ pub struct AssignmentRuleWithDetails {
 pub enum AssignmentTrigger {
     TicketCreated,
     CategoryChanged,
+    SlaBreached,
+    /// A closed ticket was reopened; matches rules that run on create
+    TicketReopened,
 }
 
 impl AssignmentTrigger {
@@ -2566,6 +3108,8 @@ impl AssignmentTrigger {
         match self {
             AssignmentTrigger::TicketCreated => "ticket_created",
             AssignmentTrigger::CategoryChanged => "category_changed",
+            AssignmentTrigger::SlaBreached => "sla_breached",
+            AssignmentTrigger::TicketReopened => "ticket_reopened",
         }
     }
 }
@@ -2573,7 +3117,8 @@ impl AssignmentTrigger {
 /// Result of automatic assignment evaluation
 #[derive(Debug, Clone)]
 pub struct AssignmentResult {
-    pub rule_id: i32,
+    /// None when the ticket fell back to its category's default assignee
+    pub rule_id: Option<i32>,
     pub rule_name: String,
     pub assigned_user_uuid: Option<Uuid>,
     pub method: AssignmentMethod,
@@ -2646,6 +3191,10 @@ pub struct Notification {
     pub is_read: bool,
     pub read_at: Option<NaiveDateTime>,
     pub created_at: NaiveDateTime,
+    /// Entity that repeated notifications are collapsed by
+    pub group_key: Option<String>,
+    /// Number of notifications collapsed into this one
+    pub collapsed_count: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Insertable)]
@@ -2660,6 +3209,7 @@ pub struct NewNotification {
     pub body: Option<String>,
     pub metadata: Option<serde_json::Value>,
     pub channels_delivered: serde_json::Value,
+    pub group_key: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, AsChangeset)]
@@ -2716,6 +3266,8 @@ pub struct NotificationResponse {
     pub metadata: Option<serde_json::Value>,
     pub is_read: bool,
     pub created_at: NaiveDateTime,
+    /// Number of notifications collapsed into this one ("3 new comments")
+    pub count: i32,
 }
 
 // ===== WEBHOOK MODELS =====
@@ -2738,6 +3290,8 @@ pub struct Webhook {
     pub last_triggered_at: Option<NaiveDateTime>,
     pub failure_count: i32,
     pub disabled_reason: Option<String>,
+    pub consecutive_timeouts: i32,
+    pub circuit_opened_at: Option<NaiveDateTime>,
 }
 
 /// New webhook for insertion
@@ -2766,6 +3320,8 @@ pub struct WebhookUpdate {
     pub last_triggered_at: Option<NaiveDateTime>,
     pub failure_count: Option<i32>,
     pub disabled_reason: Option<Option<String>>,
+    pub consecutive_timeouts: Option<i32>,
+    pub circuit_opened_at: Option<Option<NaiveDateTime>>,
 }
 
 /// Webhook delivery record
@@ -2853,12 +3409,17 @@ pub struct WebhookResponse {
     pub last_triggered_at: Option<NaiveDateTime>,
     pub failure_count: i32,
     pub disabled_reason: Option<String>,
+    /// `closed`, `open` or `half_open`
+    pub circuit_state: &'static str,
+    /// When an open circuit lets a test delivery through
+    pub circuit_retry_at: Option<NaiveDateTime>,
 }
 
 impl Webhook {
-    /// Returns a preview of the secret (first 12 chars + "...")
+    /// Placeholder shown instead of the secret. Secrets are stored encrypted,
+    /// so there's nothing safe to preview; admins can reveal the full secret.
     pub fn secret_preview(&self) -> String {
-        format!("{}...", self.secret.chars().take(12).collect::<String>())
+        "whsec_...".to_string()
     }
 }
 
@@ -2866,6 +3427,7 @@ impl From<Webhook> for WebhookResponse {
     fn from(w: Webhook) -> Self {
         // Compute secret_preview before any moves
         let secret_preview = w.secret_preview();
+        let circuit = crate::services::webhooks::circuit_breaker::CircuitState::of(&w, chrono::Utc::now().naive_utc());
         WebhookResponse {
             uuid: w.uuid,
             name: w.name,
@@ -2879,6 +3441,8 @@ impl From<Webhook> for WebhookResponse {
             last_triggered_at: w.last_triggered_at,
             failure_count: w.failure_count,
             disabled_reason: w.disabled_reason,
+            circuit_state: circuit.as_str(),
+            circuit_retry_at: circuit.retry_at(),
         }
     }
 }
@@ -2963,6 +3527,8 @@ pub struct Plugin {
     pub bundle_size: Option<i32>,
     pub bundle_uploaded_at: Option<NaiveDateTime>,
     pub source: String,
+    /// Publisher whose signature verified the plugin
+    pub verified_publisher: Option<String>,
 }
 
 /// New plugin for insertion
@@ -2978,6 +3544,7 @@ pub struct NewPlugin {
     pub trust_level: String,
     pub installed_by: Option<Uuid>,
     pub source: String,
+    pub verified_publisher: Option<String>,
 }
 
 /// Plugin update changeset
@@ -2990,6 +3557,7 @@ pub struct PluginUpdate {
     pub manifest: Option<serde_json::Value>,
     pub enabled: Option<bool>,
     pub trust_level: Option<String>,
+    pub verified_publisher: Option<Option<String>>,
 }
 
 /// Plugin bundle update changeset
@@ -3031,6 +3599,8 @@ pub struct PluginData {
     pub is_secret: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    /// Owner of a per-user storage entry (None for global entries)
+    pub user_uuid: Option<Uuid>,
 }
 
 impl PluginData {
@@ -3054,6 +3624,7 @@ pub struct NewPluginData {
     pub key: String,
     pub value: Option<serde_json::Value>,
     pub is_secret: bool,
+    pub user_uuid: Option<Uuid>,
 }
 
 /// Plugin data update changeset
@@ -3108,6 +3679,24 @@ pub struct PluginManifest {
     pub events: Vec<String>,
     #[serde(default)]
     pub settings: Vec<PluginSettingDefinition>,
+    /// Server-side events delivered to `event_handler` (e.g. "ticket.created")
+    #[serde(default)]
+    pub event_subscriptions: Vec<String>,
+    /// Endpoint that receives subscribed events
+    #[serde(default)]
+    pub event_handler: Option<String>,
+    /// Resource limits for proxied requests (server defaults when unset)
+    #[serde(default)]
+    pub limits: PluginLimits,
+}
+
+/// Per-plugin limits on proxied requests, declared in the manifest
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginLimits {
+    pub requests_per_minute: Option<u32>,
+    pub timeout_secs: Option<u64>,
+    pub max_response_bytes: Option<usize>,
+    pub max_redirects: Option<usize>,
 }
 
 /// Plugin component configuration in manifest
@@ -3167,6 +3756,7 @@ pub struct PluginResponse {
     pub bundle_size: Option<i32>,
     pub bundle_uploaded_at: Option<NaiveDateTime>,
     pub source: String,
+    pub verified_publisher: Option<String>,
 }
 
 impl Plugin {
@@ -3197,6 +3787,7 @@ impl TryFrom<Plugin> for PluginResponse {
             bundle_size: p.bundle_size,
             bundle_uploaded_at: p.bundle_uploaded_at,
             source: p.source,
+            verified_publisher: p.verified_publisher,
         })
     }
 }
@@ -3227,6 +3818,33 @@ pub struct SetPluginDataRequest {
     pub value: serde_json::Value,
 }
 
+/// Namespace for plugin storage entries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginStorageScope {
+    /// Shared by all users
+    #[default]
+    Global,
+    /// Private to the authenticated user
+    User,
+}
+
+/// Query parameters selecting a plugin storage scope
+#[derive(Debug, Default, Deserialize)]
+pub struct PluginStorageScopeQuery {
+    #[serde(default)]
+    pub scope: PluginStorageScope,
+}
+
+/// Request to set a plugin storage value
+#[derive(Debug, Deserialize)]
+pub struct SetPluginStorageRequest {
+    pub key: String,
+    pub value: serde_json::Value,
+    #[serde(default)]
+    pub scope: PluginStorageScope,
+}
+
 /// Plugin storage response
 #[derive(Debug, Serialize)]
 pub struct PluginStorageResponse {
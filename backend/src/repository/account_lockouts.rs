@@ -0,0 +1,199 @@
+//! Account Lockout Repository
+//!
+//! Counts failed password logins per account and locks the account once
+//! `LoginLockoutPolicy::max_attempts` fail within the window. Locks expire
+//! on their own after the cooldown; a successful login or an admin unlock
+//! deletes the row.
+
+use chrono::{Duration, NaiveDateTime};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::config_utils::LoginLockoutPolicy;
+use crate::db::DbConnection;
+use crate::models::AccountLockout;
+use crate::schema::account_lockouts;
+
+/// What recording a failed attempt did to the account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailedAttempt {
+    /// Counted; this many more failures lock the account
+    Counted { remaining: u32 },
+    /// The account is locked until the given time
+    Locked { until: NaiveDateTime, failed_attempts: i32 },
+}
+
+/// Apply one failed attempt at `now` to the current state
+///
+/// A locked account stays as it is, so hammering it doesn't extend the lock.
+/// An expired lock or window starts a new count.
+pub fn next_state(
+    current: Option<&AccountLockout>,
+    user_uuid: Uuid,
+    policy: &LoginLockoutPolicy,
+    now: NaiveDateTime,
+) -> AccountLockout {
+    if let Some(state) = current.filter(|state| state.locked_until.is_some_and(|until| until > now)) {
+        return state.clone();
+    }
+
+    let window = Duration::seconds(policy.window_seconds as i64);
+    let mut state = match current {
+        Some(state) if state.locked_until.is_none() && state.window_started_at + window > now => state.clone(),
+        _ => AccountLockout {
+            user_uuid,
+            failed_attempts: 0,
+            window_started_at: now,
+            locked_until: None,
+            updated_at: now,
+        },
+    };
+
+    state.failed_attempts += 1;
+    state.updated_at = now;
+    if policy.is_enabled() && state.failed_attempts >= policy.max_attempts as i32 {
+        state.locked_until = Some(now + Duration::seconds(policy.lockout_seconds as i64));
+    }
+    state
+}
+
+/// When the account's lock ends, if it is locked at `now`
+pub fn active_lock(conn: &mut DbConnection, user_uuid: &Uuid, now: NaiveDateTime) -> QueryResult<Option<NaiveDateTime>> {
+    account_lockouts::table
+        .find(user_uuid)
+        .select(account_lockouts::locked_until)
+        .first::<Option<NaiveDateTime>>(conn)
+        .optional()
+        .map(|locked_until| locked_until.flatten().filter(|until| *until > now))
+}
+
+/// Record a failed password login at `now`
+pub fn record_failure(
+    conn: &mut DbConnection,
+    user_uuid: &Uuid,
+    policy: &LoginLockoutPolicy,
+    now: NaiveDateTime,
+) -> QueryResult<FailedAttempt> {
+    conn.transaction(|conn| {
+        // Make sure a row exists to lock, so concurrent failures count serially
+        diesel::insert_into(account_lockouts::table)
+            .values(&AccountLockout {
+                user_uuid: *user_uuid,
+                failed_attempts: 0,
+                window_started_at: now,
+                locked_until: None,
+                updated_at: now,
+            })
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+        let current = account_lockouts::table
+            .find(user_uuid)
+            .for_update()
+            .first::<AccountLockout>(conn)?;
+        let state = next_state(Some(&current), *user_uuid, policy, now);
+
+        diesel::update(account_lockouts::table.find(user_uuid))
+            .set(&state)
+            .execute(conn)?;
+
+        Ok(match state.locked_until {
+            Some(until) => FailedAttempt::Locked { until, failed_attempts: state.failed_attempts },
+            None => FailedAttempt::Counted {
+                remaining: policy.max_attempts.saturating_sub(state.failed_attempts as u32),
+            },
+        })
+    })
+}
+
+/// Forget failed attempts and any lock. Returns whether the account was
+/// locked at `now`.
+pub fn clear(conn: &mut DbConnection, user_uuid: &Uuid, now: NaiveDateTime) -> QueryResult<bool> {
+    diesel::delete(account_lockouts::table.find(user_uuid))
+        .returning(account_lockouts::locked_until)
+        .get_result::<Option<NaiveDateTime>>(conn)
+        .optional()
+        .map(|locked_until| locked_until.flatten().is_some_and(|until| until > now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+    use chrono::{SubsecRound, Utc};
+
+    fn policy() -> LoginLockoutPolicy {
+        LoginLockoutPolicy { max_attempts: 3, window_seconds: 600, lockout_seconds: 900, allow_passkeys: true }
+    }
+
+    fn fail_times(count: usize, now: NaiveDateTime) -> Option<AccountLockout> {
+        let uuid = Uuid::new_v4();
+        (0..count).fold(None, |state, _| Some(next_state(state.as_ref(), uuid, &policy(), now)))
+    }
+
+    #[test]
+    fn lock_trips_at_max_attempts_within_the_window() {
+        let now = Utc::now().naive_utc();
+        assert_eq!(fail_times(2, now).unwrap().locked_until, None);
+
+        let locked = fail_times(3, now).unwrap();
+        assert_eq!(locked.locked_until, Some(now + Duration::seconds(900)));
+
+        // Further failures while locked don't extend the lock
+        let again = next_state(Some(&locked), locked.user_uuid, &policy(), now + Duration::seconds(60));
+        assert_eq!(again, locked);
+    }
+
+    #[test]
+    fn failures_outside_the_window_start_a_new_count() {
+        let now = Utc::now().naive_utc();
+        let state = fail_times(2, now).unwrap();
+        let later = next_state(Some(&state), state.user_uuid, &policy(), now + Duration::seconds(601));
+        assert_eq!(later.failed_attempts, 1);
+        assert_eq!(later.locked_until, None);
+    }
+
+    #[test]
+    fn lock_expires_after_the_cooldown() {
+        let now = Utc::now().naive_utc();
+        let locked = fail_times(3, now).unwrap();
+        let after = next_state(Some(&locked), locked.user_uuid, &policy(), now + Duration::seconds(901));
+        assert_eq!(after.failed_attempts, 1, "an expired lock starts a new count");
+        assert_eq!(after.locked_until, None);
+    }
+
+    #[test]
+    fn disabled_policy_never_locks() {
+        let disabled = LoginLockoutPolicy { max_attempts: 0, ..policy() };
+        let now = Utc::now().naive_utc();
+        let state = (0..10).fold(None, |state, _| Some(next_state(state.as_ref(), Uuid::new_v4(), &disabled, now)));
+        assert_eq!(state.unwrap().locked_until, None);
+    }
+
+    #[test]
+    fn recorded_failures_lock_and_unlock_the_account() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "lockout", UserRole::User);
+        // Postgres stores microseconds; whole seconds compare cleanly
+        let now = Utc::now().naive_utc().trunc_subsecs(0);
+
+        assert_eq!(
+            record_failure(&mut conn, &user.uuid, &policy(), now).unwrap(),
+            FailedAttempt::Counted { remaining: 2 }
+        );
+        record_failure(&mut conn, &user.uuid, &policy(), now).unwrap();
+        let until = now + Duration::seconds(900);
+        assert_eq!(
+            record_failure(&mut conn, &user.uuid, &policy(), now).unwrap(),
+            FailedAttempt::Locked { until, failed_attempts: 3 }
+        );
+        assert_eq!(active_lock(&mut conn, &user.uuid, now).unwrap(), Some(until));
+
+        // Unlocks on its own once the cooldown has passed
+        assert_eq!(active_lock(&mut conn, &user.uuid, until + Duration::seconds(1)).unwrap(), None);
+
+        assert!(clear(&mut conn, &user.uuid, now).unwrap());
+        assert_eq!(active_lock(&mut conn, &user.uuid, now).unwrap(), None);
+        assert!(!clear(&mut conn, &user.uuid, now).unwrap());
+    }
+}
@@ -47,6 +47,46 @@ pub fn revoke_session(
         .execute(conn)
 }
 
+/// Revoke one of a user's sessions. Returns 0 if the session doesn't exist or
+/// belongs to someone else.
+pub fn revoke_user_session(
+    conn: &mut DbConnection,
+    user_uuid: &Uuid,
+    session_id: i32,
+) -> Result<usize, diesel::result::Error> {
+    diesel::delete(
+        active_sessions::table
+            .filter(active_sessions::id.eq(session_id))
+            .filter(active_sessions::user_uuid.eq(user_uuid))
+    )
+    .execute(conn)
+}
+
+/// Revoke the session for an access token hash (logout)
+pub fn revoke_session_by_token(
+    conn: &mut DbConnection,
+    token: &str,
+) -> Result<usize, diesel::result::Error> {
+    diesel::delete(active_sessions::table.filter(active_sessions::session_token.eq(token)))
+        .execute(conn)
+}
+
+/// Move a session to a newly issued access token (on refresh)
+pub fn rotate_session_token(
+    conn: &mut DbConnection,
+    session_id: i32,
+    token: &str,
+    expires_at: chrono::NaiveDateTime,
+) -> Result<usize, diesel::result::Error> {
+    diesel::update(active_sessions::table.find(session_id))
+        .set((
+            active_sessions::session_token.eq(token),
+            active_sessions::last_active.eq(Utc::now().naive_utc()),
+            active_sessions::expires_at.eq(expires_at),
+        ))
+        .execute(conn)
+}
+
 /// Revoke all sessions for a user except the current one
 pub fn revoke_other_sessions(
     conn: &mut DbConnection,
@@ -112,6 +152,33 @@ mod tests {
         assert!(get_session_by_token(&mut conn, "tok_revoke").is_err());
     }
 
+    #[test]
+    fn users_can_only_revoke_their_own_sessions() {
+        let mut conn = setup_test_connection();
+        let owner = TestFixtures::create_user(&mut conn, "sessowner", UserRole::User);
+        let other = TestFixtures::create_user(&mut conn, "sessother", UserRole::User);
+
+        let session = create_session(&mut conn, make_session(owner.uuid, "tok_owned")).unwrap();
+        assert_eq!(revoke_user_session(&mut conn, &other.uuid, session.id).unwrap(), 0);
+        assert!(get_session_by_token(&mut conn, "tok_owned").is_ok());
+
+        assert_eq!(revoke_user_session(&mut conn, &owner.uuid, session.id).unwrap(), 1);
+        assert!(get_session_by_token(&mut conn, "tok_owned").is_err());
+    }
+
+    #[test]
+    fn rotating_a_session_moves_it_to_the_new_token() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "rotateuser", UserRole::User);
+
+        let session = create_session(&mut conn, make_session(user.uuid, "tok_before")).unwrap();
+        let expires_at = (Utc::now() + chrono::Duration::hours(24)).naive_utc();
+        assert_eq!(rotate_session_token(&mut conn, session.id, "tok_after", expires_at).unwrap(), 1);
+
+        assert!(get_session_by_token(&mut conn, "tok_before").is_err());
+        assert_eq!(get_session_by_token(&mut conn, "tok_after").unwrap().id, session.id);
+    }
+
     #[test]
     fn get_user_sessions_test() {
         let mut conn = setup_test_connection();
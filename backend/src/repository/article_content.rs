@@ -106,6 +106,19 @@ pub fn update_article_yjs_state(
     }
 }
 
+// Store a compacted Yjs document together with its state vector
+pub fn update_article_yjs_snapshot(
+    conn: &mut DbConnection,
+    ticket_id: i32,
+    yjs_document: Vec<u8>,
+    yjs_state_vector: Vec<u8>,
+) -> QueryResult<ArticleContent> {
+    let article = update_article_yjs_state(conn, ticket_id, yjs_document)?;
+    diesel::update(article_contents::table.find(article.id))
+        .set(article_contents::yjs_state_vector.eq(Some(yjs_state_vector)))
+        .get_result(conn)
+}
+
 // Update parent ticket's updated_at timestamp (call only when content actually changes)
 pub fn update_ticket_modified_timestamp(
     conn: &mut DbConnection,
@@ -234,6 +234,7 @@ mod tests {
             target_group_id: None,
             trigger_on_create: true,
             trigger_on_category_change: false,
+            trigger_on_sla_breach: false,
             category_id: None,
             conditions: None,
             created_by: None,
@@ -0,0 +1,143 @@
+use diesel::prelude::*;
+use diesel::QueryResult;
+use uuid::Uuid;
+
+use crate::db::DbConnection;
+use crate::models::*;
+use crate::schema::*;
+
+// ============================================================================
+// Canned Response CRUD Operations
+// ============================================================================
+
+/// Canned responses visible to `viewer`: every shared template plus their own
+/// personal ones. With `category_id`, only templates for that category or
+/// without a category are returned.
+pub fn list_canned_responses(
+    conn: &mut DbConnection,
+    viewer: Uuid,
+    category_id: Option<i32>,
+) -> QueryResult<Vec<CannedResponse>> {
+    let mut query = canned_responses::table
+        .filter(
+            canned_responses::is_shared
+                .eq(true)
+                .or(canned_responses::created_by.eq(viewer)),
+        )
+        .order((canned_responses::title.asc(), canned_responses::id.asc()))
+        .into_boxed();
+
+    if let Some(category_id) = category_id {
+        query = query.filter(
+            canned_responses::category_id
+                .eq(category_id)
+                .or(canned_responses::category_id.is_null()),
+        );
+    }
+
+    query.load(conn)
+}
+
+/// Get a canned response by ID
+pub fn get_canned_response(conn: &mut DbConnection, id: i32) -> QueryResult<CannedResponse> {
+    canned_responses::table.find(id).first(conn)
+}
+
+/// Create a new canned response
+pub fn create_canned_response(
+    conn: &mut DbConnection,
+    new_response: NewCannedResponse,
+) -> QueryResult<CannedResponse> {
+    diesel::insert_into(canned_responses::table)
+        .values(&new_response)
+        .get_result(conn)
+}
+
+/// Replace a canned response's title, body, category and sharing
+pub fn update_canned_response(
+    conn: &mut DbConnection,
+    id: i32,
+    update: CannedResponseUpdate,
+) -> QueryResult<CannedResponse> {
+    diesel::update(canned_responses::table.find(id))
+        .set((&update, canned_responses::updated_at.eq(diesel::dsl::now)))
+        .get_result(conn)
+}
+
+/// Delete a canned response
+pub fn delete_canned_response(conn: &mut DbConnection, id: i32) -> QueryResult<usize> {
+    diesel::delete(canned_responses::table.find(id)).execute(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+    fn new_response(title: &str, category_id: Option<i32>, is_shared: bool, created_by: Uuid) -> NewCannedResponse {
+        NewCannedResponse {
+            title: title.to_string(),
+            body: format!("{title} body"),
+            category_id,
+            is_shared,
+            created_by: Some(created_by),
+        }
+    }
+
+    #[test]
+    fn list_returns_shared_and_own_personal_responses() {
+        let mut conn = setup_test_connection();
+        let alice = TestFixtures::create_user(&mut conn, "cannedalice", UserRole::Technician);
+        let bob = TestFixtures::create_user(&mut conn, "cannedbob", UserRole::Technician);
+        let category = TestFixtures::create_category(&mut conn, "Canned Hardware");
+
+        let shared = create_canned_response(&mut conn, new_response("Shared", None, true, alice.uuid)).unwrap();
+        let personal = create_canned_response(&mut conn, new_response("Alice only", None, false, alice.uuid)).unwrap();
+        let scoped = create_canned_response(&mut conn, new_response("Hardware", Some(category.id), true, bob.uuid)).unwrap();
+
+        let ids = |responses: Vec<CannedResponse>| -> Vec<i32> {
+            responses
+                .into_iter()
+                .map(|r| r.id)
+                .filter(|id| [shared.id, personal.id, scoped.id].contains(id))
+                .collect()
+        };
+
+        let for_alice = list_canned_responses(&mut conn, alice.uuid, None).unwrap();
+        assert_eq!(ids(for_alice), vec![personal.id, scoped.id, shared.id]);
+
+        let for_bob = list_canned_responses(&mut conn, bob.uuid, None).unwrap();
+        assert_eq!(ids(for_bob), vec![scoped.id, shared.id]);
+
+        // Category filter keeps unscoped templates
+        let other_category = TestFixtures::create_category(&mut conn, "Canned Software");
+        let for_software = list_canned_responses(&mut conn, bob.uuid, Some(other_category.id)).unwrap();
+        assert_eq!(ids(for_software), vec![shared.id]);
+        let for_hardware = list_canned_responses(&mut conn, bob.uuid, Some(category.id)).unwrap();
+        assert_eq!(ids(for_hardware), vec![scoped.id, shared.id]);
+    }
+
+    #[test]
+    fn update_replaces_fields_and_clears_category() {
+        let mut conn = setup_test_connection();
+        let tech = TestFixtures::create_user(&mut conn, "cannedupdater", UserRole::Technician);
+        let category = TestFixtures::create_category(&mut conn, "Canned Network");
+        let response = create_canned_response(&mut conn, new_response("VPN", Some(category.id), true, tech.uuid)).unwrap();
+
+        let updated = update_canned_response(&mut conn, response.id, CannedResponseUpdate {
+            title: "VPN reset".to_string(),
+            body: "Hi {{requester_name}}, your VPN profile was reset.".to_string(),
+            category_id: None,
+            is_shared: false,
+        })
+        .unwrap();
+
+        assert_eq!(updated.title, "VPN reset");
+        assert_eq!(updated.category_id, None);
+        assert!(!updated.is_shared);
+        assert_eq!(updated.created_by, Some(tech.uuid));
+
+        assert_eq!(delete_canned_response(&mut conn, response.id).unwrap(), 1);
+        assert!(get_canned_response(&mut conn, response.id).is_err());
+    }
+}
@@ -95,6 +95,117 @@ pub fn delete_category(conn: &mut DbConnection, category_id: i32) -> QueryResult
         .get_result(conn)
 }
 
+/// Error from merging one category into another
+#[derive(Debug)]
+pub enum CategoryMergeError {
+    /// Source and target are the same category
+    SameCategory,
+    /// The target has been deleted
+    TargetInactive,
+    Database(Error),
+}
+
+impl std::fmt::Display for CategoryMergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CategoryMergeError::SameCategory => write!(f, "A category can't be merged into itself"),
+            CategoryMergeError::TargetInactive => write!(f, "Can't merge into a deleted category"),
+            CategoryMergeError::Database(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<Error> for CategoryMergeError {
+    fn from(e: Error) -> Self {
+        CategoryMergeError::Database(e)
+    }
+}
+
+/// Result of a category merge
+#[derive(Debug)]
+pub struct CategoryMerge {
+    /// The source category, now inactive
+    pub source: TicketCategory,
+    pub target: TicketCategory,
+    /// Tickets moved from the source to the target
+    pub moved_ticket_ids: Vec<i32>,
+}
+
+/// Merge a category into another
+///
+/// Moves the source's tickets to the target, recording the category change
+/// in each ticket's audit log, and adds the source's visibility groups to
+/// a restricted target's. A public target (no visibility groups) stays
+/// public and the source's groups are dropped. Assignment rules aren't
+/// re-run on the moved tickets. The source is then soft-deleted. Runs in
+/// one transaction.
+///
+/// A public source doesn't make a restricted target public, so its tickets
+/// become visible only to the target's groups.
+pub fn merge_category(
+    conn: &mut DbConnection,
+    source_id: i32,
+    target_id: i32,
+    merged_by: Option<Uuid>,
+) -> Result<CategoryMerge, CategoryMergeError> {
+    if source_id == target_id {
+        return Err(CategoryMergeError::SameCategory);
+    }
+
+    crate::repository::with_transaction(conn, |conn| {
+        let target = get_category_by_id(conn, target_id)?;
+        get_category_by_id(conn, source_id)?;
+        if !target.is_active {
+            return Err(CategoryMergeError::TargetInactive);
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        let moved_ticket_ids: Vec<i32> = diesel::update(tickets::table.filter(tickets::category_id.eq(source_id)))
+            .set((tickets::category_id.eq(target_id), tickets::updated_at.eq(now)))
+            .returning(tickets::id)
+            .get_results(conn)?;
+
+        let audit_entries: Vec<NewTicketAuditLog> = moved_ticket_ids
+            .iter()
+            .map(|&ticket_id| NewTicketAuditLog {
+                ticket_id,
+                field: "category_id".to_string(),
+                old_value: Some(source_id.to_string()),
+                new_value: Some(target_id.to_string()),
+                changed_by: merged_by,
+            })
+            .collect();
+        diesel::insert_into(ticket_audit_log::table)
+            .values(&audit_entries)
+            .execute(conn)?;
+
+        // Groups already on the target are dropped from the source, and a
+        // public target keeps none so it stays public
+        let target_groups = get_visible_group_ids_for_category(conn, target_id)?;
+        if !target_groups.is_empty() {
+            diesel::update(
+                category_group_visibility::table
+                    .filter(category_group_visibility::category_id.eq(source_id))
+                    .filter(category_group_visibility::group_id.ne_all(&target_groups)),
+            )
+            .set(category_group_visibility::category_id.eq(target_id))
+            .execute(conn)?;
+        }
+        diesel::delete(
+            category_group_visibility::table.filter(category_group_visibility::category_id.eq(source_id)),
+        )
+        .execute(conn)?;
+
+        let source = delete_category(conn, source_id)?;
+
+        Ok(CategoryMerge {
+            source,
+            target,
+            moved_ticket_ids,
+        })
+    })
+}
+
 /// Get the next display order value
 pub fn get_next_display_order(conn: &mut DbConnection) -> QueryResult<i32> {
     let max_order: Option<i32> = ticket_categories::table
@@ -131,6 +242,14 @@ pub fn get_visible_groups_for_category(conn: &mut DbConnection, category_id: i32
         .load(conn)
 }
 
+/// Get IDs of the groups that can see a category
+pub fn get_visible_group_ids_for_category(conn: &mut DbConnection, category_id: i32) -> QueryResult<Vec<i32>> {
+    category_group_visibility::table
+        .filter(category_group_visibility::category_id.eq(category_id))
+        .select(category_group_visibility::group_id)
+        .load(conn)
+}
+
 /// Set which groups can see a category (replaces existing visibility)
 pub fn set_category_visibility(
     conn: &mut DbConnection,
@@ -361,4 +480,67 @@ mod tests {
         assert!(visible_ids.contains(&restricted_ok.id));
         assert!(!visible_ids.contains(&restricted_no.id));
     }
+
+    #[test]
+    fn merge_moves_tickets_and_deactivates_the_source() {
+        let mut conn = setup_test_connection();
+        let admin = TestFixtures::create_user(&mut conn, "mergeadmin", UserRole::Admin);
+        let source = TestFixtures::create_category(&mut conn, "Printers");
+        let target = TestFixtures::create_category(&mut conn, "Hardware");
+        let shared = TestFixtures::create_group(&mut conn, "Helpdesk");
+        let facilities = TestFixtures::create_group(&mut conn, "Facilities");
+        TestFixtures::set_category_visibility(&mut conn, source.id, &[shared.id, facilities.id]);
+        TestFixtures::set_category_visibility(&mut conn, target.id, &[shared.id]);
+        let first = TestFixtures::create_ticket(&mut conn, "Jammed", None, Some(source.id));
+        let second = TestFixtures::create_ticket(&mut conn, "Out of toner", None, Some(source.id));
+        let untouched = TestFixtures::create_ticket(&mut conn, "Laptop", None, Some(target.id));
+
+        let merge = merge_category(&mut conn, source.id, target.id, Some(admin.uuid)).unwrap();
+
+        let mut moved = merge.moved_ticket_ids.clone();
+        moved.sort();
+        assert_eq!(moved, vec![first.id, second.id]);
+        for ticket_id in [first.id, second.id, untouched.id] {
+            let ticket = crate::repository::get_ticket_by_id(&mut conn, ticket_id).unwrap();
+            assert_eq!(ticket.category_id, Some(target.id));
+        }
+        assert!(!merge.source.is_active);
+        assert!(!get_category_by_id(&mut conn, source.id).unwrap().is_active);
+
+        let mut groups = get_visible_group_ids_for_category(&mut conn, target.id).unwrap();
+        groups.sort();
+        let mut expected = vec![shared.id, facilities.id];
+        expected.sort();
+        assert_eq!(groups, expected);
+        assert!(get_visible_group_ids_for_category(&mut conn, source.id).unwrap().is_empty());
+
+        let audit = crate::repository::get_ticket_audit_log(&mut conn, first.id).unwrap();
+        assert!(audit.iter().any(|entry| entry.field == "category_id"
+            && entry.new_value == Some(target.id.to_string())
+            && entry.changed_by == Some(admin.uuid)));
+    }
+
+    #[test]
+    fn merge_into_a_public_category_keeps_it_public() {
+        let mut conn = setup_test_connection();
+        let source = TestFixtures::create_category(&mut conn, "Payroll");
+        let target = TestFixtures::create_category(&mut conn, "General");
+        let finance = TestFixtures::create_group(&mut conn, "Payroll Team");
+        TestFixtures::set_category_visibility(&mut conn, source.id, &[finance.id]);
+
+        merge_category(&mut conn, source.id, target.id, None).unwrap();
+
+        assert!(get_visible_group_ids_for_category(&mut conn, target.id).unwrap().is_empty());
+        assert!(get_visible_group_ids_for_category(&mut conn, source.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn merge_into_itself_is_refused() {
+        let mut conn = setup_test_connection();
+        let cat = TestFixtures::create_category(&mut conn, "Solo");
+        assert!(matches!(
+            merge_category(&mut conn, cat.id, cat.id, None),
+            Err(CategoryMergeError::SameCategory)
+        ));
+    }
 }
@@ -6,11 +6,20 @@ use crate::models::*;
 use crate::schema::*;
 
 // Comment operations
-pub fn get_comments_by_ticket_id(conn: &mut DbConnection, ticket_id: i32) -> QueryResult<Vec<Comment>> {
-    comments::table
+
+/// Comments on a ticket, newest first. Internal comments are only included
+/// when `include_internal` is set (technician/admin viewers).
+pub fn get_comments_by_ticket_id(conn: &mut DbConnection, ticket_id: i32, include_internal: bool) -> QueryResult<Vec<Comment>> {
+    let mut query = comments::table
         .filter(comments::ticket_id.eq(ticket_id))
         .order(comments::created_at.desc())
-        .load(conn)
+        .into_boxed();
+
+    if !include_internal {
+        query = query.filter(comments::is_internal.eq(false));
+    }
+
+    query.load(conn)
 }
 
 pub fn create_comment(conn: &mut DbConnection, new_comment: NewComment) -> QueryResult<Comment> {
@@ -45,8 +54,8 @@ pub fn get_comment_by_id(conn: &mut DbConnection, comment_id: i32) -> QueryResul
     comments::table.find(comment_id).first(conn)
 }
 
-pub fn get_comments_with_attachments_by_ticket_id(conn: &mut DbConnection, ticket_id: i32) -> QueryResult<Vec<CommentWithAttachments>> {
-    let comments = get_comments_by_ticket_id(conn, ticket_id)?;
+pub fn get_comments_with_attachments_by_ticket_id(conn: &mut DbConnection, ticket_id: i32, include_internal: bool) -> QueryResult<Vec<CommentWithAttachments>> {
+    let comments = get_comments_by_ticket_id(conn, ticket_id, include_internal)?;
     let mut comments_with_attachments = Vec::new();
 
     for comment in comments {
@@ -87,6 +96,33 @@ pub fn delete_attachment(conn: &mut DbConnection, attachment_id: i32) -> QueryRe
         .execute(conn)
 }
 
+/// Store the text transcribed from an attachment's audio or image
+pub fn set_attachment_transcription(conn: &mut DbConnection, attachment_id: i32, transcription: &str) -> QueryResult<Attachment> {
+    diesel::update(attachments::table.find(attachment_id))
+        .set(attachments::transcription.eq(transcription))
+        .get_result(conn)
+}
+
+/// Attachments on tickets closed before `closed_before` that aren't on
+/// legal hold, with their ticket id, oldest closures first
+///
+/// Reopening a ticket clears `closed_at`, so only tickets that are still
+/// closed are matched.
+pub fn get_attachments_past_retention(
+    conn: &mut DbConnection,
+    closed_before: chrono::NaiveDateTime,
+    limit: i64,
+) -> QueryResult<Vec<(Attachment, i32)>> {
+    attachments::table
+        .inner_join(comments::table.inner_join(tickets::table))
+        .filter(tickets::closed_at.lt(closed_before))
+        .filter(tickets::legal_hold.eq(false))
+        .order((tickets::closed_at.asc(), attachments::id.asc()))
+        .limit(limit)
+        .select((attachments::all_columns, tickets::id))
+        .load(conn)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,7 +139,7 @@ mod tests {
         assert_eq!(comment.content, "Hello world");
         assert_eq!(comment.ticket_id, ticket.id);
 
-        let comments = get_comments_by_ticket_id(&mut conn, ticket.id).unwrap();
+        let comments = get_comments_by_ticket_id(&mut conn, ticket.id, false).unwrap();
         assert_eq!(comments.len(), 1);
         assert_eq!(comments[0].id, comment.id);
     }
@@ -117,7 +153,7 @@ mod tests {
         let c1 = TestFixtures::create_comment(&mut conn, ticket.id, user.uuid, "First");
         let c2 = TestFixtures::create_comment(&mut conn, ticket.id, user.uuid, "Second");
 
-        let comments = get_comments_by_ticket_id(&mut conn, ticket.id).unwrap();
+        let comments = get_comments_by_ticket_id(&mut conn, ticket.id, false).unwrap();
         assert_eq!(comments.len(), 2);
         let ids: Vec<i32> = comments.iter().map(|c| c.id).collect();
         assert!(ids.contains(&c1.id));
@@ -138,6 +174,7 @@ mod tests {
             content: "bump".to_string(),
             ticket_id: ticket.id,
             user_uuid: user.uuid,
+            is_internal: false,
         };
         create_comment(&mut conn, new_comment).unwrap();
 
@@ -188,4 +225,53 @@ mod tests {
         // Comment should still exist
         assert!(get_comment_by_id(&mut conn, comment.id).is_ok());
     }
-}
\ No newline at end of file
+
+    /// Create a ticket closed at `closed_at` with one attachment
+    fn closed_ticket_with_attachment(
+        conn: &mut DbConnection,
+        user: &User,
+        title: &str,
+        closed_at: Option<chrono::NaiveDateTime>,
+        legal_hold: bool,
+    ) -> Attachment {
+        let ticket = TestFixtures::create_ticket(conn, title, Some(user.uuid), None);
+        diesel::update(tickets::table.find(ticket.id))
+            .set((
+                tickets::status.eq(TicketStatus::Closed),
+                // Backdated too, as a ticket can't close before it was created
+                tickets::created_at.eq(closed_at.map_or(ticket.created_at, |at| at - chrono::Duration::days(1))),
+                tickets::closed_at.eq(closed_at),
+                tickets::legal_hold.eq(legal_hold),
+            ))
+            .execute(conn)
+            .unwrap();
+        let comment = TestFixtures::create_comment(conn, ticket.id, user.uuid, "Log attached");
+        TestFixtures::create_attachment(conn, comment.id, &format!("{title}.log"))
+    }
+
+    #[test]
+    fn retention_selects_only_long_closed_tickets_without_legal_hold() {
+        use chrono::{Duration, Utc};
+
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "retentionuser", UserRole::User);
+        let cutoff = Utc::now().naive_utc() - Duration::days(90);
+
+        let expired = closed_ticket_with_attachment(&mut conn, &user, "expired", Some(cutoff - Duration::minutes(1)), false);
+        let at_cutoff = closed_ticket_with_attachment(&mut conn, &user, "atcutoff", Some(cutoff), false);
+        let recent = closed_ticket_with_attachment(&mut conn, &user, "recent", Some(cutoff + Duration::minutes(1)), false);
+        let on_hold = closed_ticket_with_attachment(&mut conn, &user, "onhold", Some(cutoff - Duration::days(30)), true);
+        let reopened = closed_ticket_with_attachment(&mut conn, &user, "reopened", None, false);
+
+        let selected: Vec<i32> = get_attachments_past_retention(&mut conn, cutoff, 100)
+            .unwrap()
+            .into_iter()
+            .map(|(attachment, _)| attachment.id)
+            .collect();
+
+        assert!(selected.contains(&expired.id));
+        for kept in [&at_cutoff, &recent, &on_hold, &reopened] {
+            assert!(!selected.contains(&kept.id), "{} should be kept", kept.name);
+        }
+    }
+}
@@ -0,0 +1,383 @@
+//! Custom field definitions and ticket values
+//!
+//! Values are checked against their definition (`services::custom_fields`)
+//! and stored in canonical form; clearing a value deletes its row.
+
+use std::collections::HashMap;
+
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error};
+use diesel::QueryResult;
+use uuid::Uuid;
+
+use crate::db::DbConnection;
+use crate::models::*;
+use crate::schema::*;
+use crate::services::category_fields::FieldError;
+use crate::services::custom_fields::{self, CustomFieldType, FieldSpec};
+
+/// Error from defining a field or setting values
+#[derive(Debug)]
+pub enum CustomFieldError {
+    /// Rejected input, with an error per field
+    Invalid(Vec<FieldError>),
+    Database(Error),
+}
+
+impl std::fmt::Display for CustomFieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CustomFieldError::Invalid(errors) => {
+                let errors: Vec<String> = errors.iter().map(|e| format!("{}: {}", e.field, e.message)).collect();
+                write!(f, "{}", errors.join("; "))
+            }
+            CustomFieldError::Database(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<Error> for CustomFieldError {
+    fn from(e: Error) -> Self {
+        CustomFieldError::Database(e)
+    }
+}
+
+// ============================================================================
+// Definitions
+// ============================================================================
+
+/// All definitions, or with `category_id` those that apply to tickets in it
+pub fn list_definitions(conn: &mut DbConnection, category_id: Option<i32>) -> QueryResult<Vec<CustomFieldDefinition>> {
+    let mut query = custom_field_definitions::table
+        .order((custom_field_definitions::display_order.asc(), custom_field_definitions::id.asc()))
+        .into_boxed();
+
+    if let Some(category_id) = category_id {
+        query = query.filter(
+            custom_field_definitions::category_id
+                .is_null()
+                .or(custom_field_definitions::category_id.eq(category_id)),
+        );
+    }
+
+    query.load(conn)
+}
+
+pub fn get_definition(conn: &mut DbConnection, field_id: i32) -> QueryResult<CustomFieldDefinition> {
+    custom_field_definitions::table.find(field_id).first(conn)
+}
+
+/// Definitions with the given keys; unknown keys are skipped
+pub fn get_definitions_by_keys(conn: &mut DbConnection, keys: &[String]) -> QueryResult<Vec<CustomFieldDefinition>> {
+    custom_field_definitions::table
+        .filter(custom_field_definitions::key.eq_any(keys))
+        .load(conn)
+}
+
+/// Define a field after checking its key, label, type and options
+pub fn create_definition(
+    conn: &mut DbConnection,
+    mut definition: NewCustomFieldDefinition,
+) -> Result<CustomFieldDefinition, CustomFieldError> {
+    custom_fields::validate_definition(
+        &definition.key,
+        &definition.label,
+        &definition.field_type,
+        &definition.options,
+    )
+    .map_err(CustomFieldError::Invalid)?;
+    definition.label = definition.label.trim().to_string();
+
+    diesel::insert_into(custom_field_definitions::table)
+        .values(&definition)
+        .get_result(conn)
+        .map_err(|e| match e {
+            Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => CustomFieldError::Invalid(vec![
+                FieldError::new("key", format!("'{}' is already used by another field", definition.key)),
+            ]),
+            e => e.into(),
+        })
+}
+
+/// Change a field's label, options, category or order
+///
+/// Values using a choice removed from a select field are kept; they're
+/// rejected only when set again.
+pub fn update_definition(
+    conn: &mut DbConnection,
+    field_id: i32,
+    mut update: CustomFieldDefinitionUpdate,
+) -> Result<CustomFieldDefinition, CustomFieldError> {
+    let existing = get_definition(conn, field_id)?;
+
+    let mut errors = Vec::new();
+    if let Some(label) = &update.label {
+        errors.extend(custom_fields::validate_label(label));
+        update.label = Some(label.trim().to_string());
+    }
+    if let Some(options) = &update.options {
+        if let Err(option_errors) = custom_fields::validate_options(FieldSpec::of(&existing).field_type, options) {
+            errors.extend(option_errors);
+        }
+    }
+    if !errors.is_empty() {
+        return Err(CustomFieldError::Invalid(errors));
+    }
+
+    update.updated_at = Some(chrono::Utc::now().naive_utc());
+    Ok(diesel::update(custom_field_definitions::table.find(field_id))
+        .set(&update)
+        .get_result(conn)?)
+}
+
+/// Delete a field along with every ticket's value for it
+pub fn delete_definition(conn: &mut DbConnection, field_id: i32) -> QueryResult<usize> {
+    diesel::delete(custom_field_definitions::table.find(field_id)).execute(conn)
+}
+
+// ============================================================================
+// Ticket values
+// ============================================================================
+
+/// A ticket's custom field values, in field order
+pub fn get_values(conn: &mut DbConnection, ticket_id: i32) -> QueryResult<Vec<CustomFieldValue>> {
+    let rows: Vec<(i32, String, String, String, String)> = ticket_custom_field_values::table
+        .inner_join(custom_field_definitions::table)
+        .filter(ticket_custom_field_values::ticket_id.eq(ticket_id))
+        .order((custom_field_definitions::display_order.asc(), custom_field_definitions::id.asc()))
+        .select((
+            custom_field_definitions::id,
+            custom_field_definitions::key,
+            custom_field_definitions::label,
+            custom_field_definitions::field_type,
+            ticket_custom_field_values::value,
+        ))
+        .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(field_id, key, label, field_type, value)| CustomFieldValue {
+            field_id,
+            key,
+            label,
+            field_type,
+            value,
+        })
+        .collect())
+}
+
+/// Check values, by field key, for a ticket in `category_id`
+///
+/// Returns each field's id with its canonical value; a missing or blank
+/// value clears the field. Unknown fields and fields of another category
+/// are rejected.
+pub fn validate_values(
+    conn: &mut DbConnection,
+    category_id: Option<i32>,
+    values: &HashMap<String, Option<String>>,
+) -> Result<Vec<(i32, Option<String>)>, CustomFieldError> {
+    let mut keys: Vec<String> = values.keys().cloned().collect();
+    keys.sort();
+    let definitions: HashMap<String, CustomFieldDefinition> = get_definitions_by_keys(conn, &keys)?
+        .into_iter()
+        .map(|definition| (definition.key.clone(), definition))
+        .collect();
+
+    let mut validated = Vec::new();
+    let mut errors = Vec::new();
+    for key in &keys {
+        let Some(definition) = definitions.get(key) else {
+            errors.push(FieldError::new(key.clone(), "unknown field"));
+            continue;
+        };
+        if definition.category_id.is_some() && definition.category_id != category_id {
+            errors.push(FieldError::new(key.clone(), "doesn't apply to this ticket's category"));
+            continue;
+        }
+
+        match values[key].as_deref().map(str::trim).filter(|raw| !raw.is_empty()) {
+            None => validated.push((definition.id, None)),
+            Some(raw) => match FieldSpec::of(definition).normalize(raw) {
+                Ok(value) => validated.push((definition.id, Some(value))),
+                Err(message) => errors.push(FieldError::new(key.clone(), message)),
+            },
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(validated)
+    } else {
+        Err(CustomFieldError::Invalid(errors))
+    }
+}
+
+/// Store values checked by [`validate_values`]
+pub fn write_values(
+    conn: &mut DbConnection,
+    ticket_id: i32,
+    values: &[(i32, Option<String>)],
+    updated_by: Option<Uuid>,
+) -> QueryResult<()> {
+    let now = chrono::Utc::now().naive_utc();
+    crate::repository::with_transaction(conn, |conn| {
+        for (field_id, value) in values {
+            match value {
+                Some(value) => {
+                    let row = NewTicketCustomFieldValue {
+                        ticket_id,
+                        field_id: *field_id,
+                        value: value.clone(),
+                        updated_at: now,
+                        updated_by,
+                    };
+                    diesel::insert_into(ticket_custom_field_values::table)
+                        .values(&row)
+                        .on_conflict((ticket_custom_field_values::ticket_id, ticket_custom_field_values::field_id))
+                        .do_update()
+                        .set(&row)
+                        .execute(conn)?;
+                }
+                None => {
+                    diesel::delete(ticket_custom_field_values::table.find((ticket_id, *field_id))).execute(conn)?;
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Check and store values, by field key, for a ticket in `category_id`,
+/// returning all of the ticket's values
+pub fn set_values(
+    conn: &mut DbConnection,
+    ticket_id: i32,
+    category_id: Option<i32>,
+    values: &HashMap<String, Option<String>>,
+    updated_by: Option<Uuid>,
+) -> Result<Vec<CustomFieldValue>, CustomFieldError> {
+    let validated = validate_values(conn, category_id, values)?;
+    write_values(conn, ticket_id, &validated, updated_by)?;
+    Ok(get_values(conn, ticket_id)?)
+}
+
+fn searchable_types() -> Vec<&'static str> {
+    CustomFieldType::ALL
+        .into_iter()
+        .filter(|t| t.is_searchable())
+        .map(CustomFieldType::as_str)
+        .collect()
+}
+
+/// A ticket's text and select values, for its search document
+pub fn searchable_values(conn: &mut DbConnection, ticket_id: i32) -> QueryResult<Vec<String>> {
+    ticket_custom_field_values::table
+        .inner_join(custom_field_definitions::table)
+        .filter(ticket_custom_field_values::ticket_id.eq(ticket_id))
+        .filter(custom_field_definitions::field_type.eq_any(searchable_types()))
+        .select(ticket_custom_field_values::value)
+        .load(conn)
+}
+
+/// Text and select values of every ticket, by ticket id
+pub fn all_searchable_values(conn: &mut DbConnection) -> QueryResult<HashMap<i32, Vec<String>>> {
+    let rows: Vec<(i32, String)> = ticket_custom_field_values::table
+        .inner_join(custom_field_definitions::table)
+        .filter(custom_field_definitions::field_type.eq_any(searchable_types()))
+        .select((ticket_custom_field_values::ticket_id, ticket_custom_field_values::value))
+        .load(conn)?;
+
+    let mut values: HashMap<i32, Vec<String>> = HashMap::new();
+    for (ticket_id, value) in rows {
+        values.entry(ticket_id).or_default().push(value);
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+    use serde_json::json;
+
+    fn define(
+        conn: &mut DbConnection,
+        key: &str,
+        field_type: &str,
+        options: serde_json::Value,
+        category_id: Option<i32>,
+    ) -> Result<CustomFieldDefinition, CustomFieldError> {
+        create_definition(
+            conn,
+            NewCustomFieldDefinition {
+                key: key.to_string(),
+                label: key.replace('_', " "),
+                field_type: field_type.to_string(),
+                options,
+                category_id,
+                display_order: 0,
+                created_by: None,
+            },
+        )
+    }
+
+    fn invalid_fields(result: Result<impl std::fmt::Debug, CustomFieldError>) -> Vec<String> {
+        match result {
+            Err(CustomFieldError::Invalid(errors)) => errors.into_iter().map(|e| e.field).collect(),
+            other => panic!("expected invalid input, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn definitions_are_validated_and_keys_unique() {
+        let mut conn = setup_test_connection();
+        define(&mut conn, "cost_center", "select", json!(["4410", "4420"]), None).unwrap();
+
+        assert_eq!(invalid_fields(define(&mut conn, "cost_center", "text", json!([]), None)), vec!["key"]);
+        assert_eq!(invalid_fields(define(&mut conn, "tier", "select", json!([]), None)), vec!["options"]);
+        assert_eq!(invalid_fields(define(&mut conn, "due", "datetime", json!([]), None)), vec!["field_type"]);
+    }
+
+    #[test]
+    fn values_are_checked_against_their_definition() {
+        let mut conn = setup_test_connection();
+        let access = TestFixtures::create_category(&mut conn, "Access");
+        let other = TestFixtures::create_category(&mut conn, "Other");
+        let ticket = TestFixtures::create_ticket(&mut conn, "Needs SAP access", None, Some(access.id));
+        define(&mut conn, "budget", "number", json!([]), None).unwrap();
+        define(&mut conn, "affected_system", "select", json!(["SAP", "Workday"]), Some(access.id)).unwrap();
+
+        let values = |pairs: &[(&str, Option<&str>)]| -> HashMap<String, Option<String>> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.map(str::to_string))).collect()
+        };
+
+        let stored = set_values(
+            &mut conn,
+            ticket.id,
+            Some(access.id),
+            &values(&[("budget", Some("1200.50")), ("affected_system", Some("SAP"))]),
+            None,
+        )
+        .unwrap();
+        let stored: Vec<(&str, &str)> = stored.iter().map(|v| (v.key.as_str(), v.value.as_str())).collect();
+        assert_eq!(stored, vec![("budget", "1200.5"), ("affected_system", "SAP")]);
+        assert_eq!(searchable_values(&mut conn, ticket.id).unwrap(), vec!["SAP".to_string()]);
+
+        let rejected = set_values(
+            &mut conn,
+            ticket.id,
+            Some(access.id),
+            &values(&[("budget", Some("a lot")), ("affected_system", Some("Oracle")), ("colour", Some("red"))]),
+            None,
+        );
+        assert_eq!(invalid_fields(rejected), vec!["affected_system", "budget", "colour"]);
+
+        // Fields of another category don't apply
+        let rejected = validate_values(&mut conn, Some(other.id), &values(&[("affected_system", Some("SAP"))]));
+        assert_eq!(invalid_fields(rejected), vec!["affected_system"]);
+
+        // A blank value clears the field
+        let stored = set_values(&mut conn, ticket.id, Some(access.id), &values(&[("budget", Some(" "))]), None).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].key, "affected_system");
+    }
+}
@@ -133,7 +133,6 @@ pub fn get_device_by_id(conn: &mut DbConnection, device_id: i32) -> QueryResult<
         .first(conn)
 }
 
-#[allow(dead_code)]
 pub fn get_device_by_intune_id(conn: &mut DbConnection, intune_device_id: &str) -> QueryResult<Device> {
     devices::table
         .filter(devices::intune_device_id.eq(intune_device_id))
@@ -152,6 +151,12 @@ pub fn get_device_by_microsoft_id(conn: &mut DbConnection, microsoft_device_id:
         .first(conn)
 }
 
+pub fn get_device_by_serial(conn: &mut DbConnection, serial_number: &str) -> QueryResult<Device> {
+    devices::table
+        .filter(devices::serial_number.eq(serial_number))
+        .first(conn)
+}
+
 #[allow(dead_code)]
 pub fn get_devices_by_user(conn: &mut DbConnection, user_uuid: &Uuid) -> QueryResult<Vec<Device>> {
     devices::table
@@ -233,6 +238,31 @@ pub fn upsert_device_by_entra_id(
         .get_result(conn)
 }
 
+/// Insert a device, or update the existing device with the same serial
+/// number. Fields left as None don't overwrite existing values.
+/// Returns the device and whether it was newly created.
+pub fn upsert_device_by_serial(conn: &mut DbConnection, device_data: NewDevice) -> QueryResult<(Device, bool)> {
+    let Some(serial) = device_data.serial_number.clone() else {
+        return Err(Error::QueryBuilderError("serial_number is required to upsert a device".into()));
+    };
+
+    let existing: Option<Device> = devices::table
+        .filter(devices::serial_number.eq(&serial))
+        .for_update()
+        .first(conn)
+        .optional()?;
+
+    match existing {
+        Some(device) => {
+            let updated = diesel::update(devices::table.find(device.id))
+                .set((&device_data, devices::updated_at.eq(Utc::now().naive_utc())))
+                .get_result(conn)?;
+            Ok((updated, false))
+        }
+        None => Ok((create_device(conn, device_data)?, true)),
+    }
+}
+
 pub fn get_devices_for_user(conn: &mut DbConnection, user_uuid: &Uuid) -> QueryResult<Vec<Device>> {
     use crate::schema::devices::dsl::*;
     
@@ -318,6 +348,98 @@ pub fn get_devices_by_entra_ids(
         .load::<(String, i32)>(conn)
 }
 
+/// Get devices whose warranty expires between today and `days` from today
+/// (both ends inclusive), soonest first
+pub fn get_devices_expiring_within(conn: &mut DbConnection, days: i64) -> QueryResult<Vec<Device>> {
+    let today = Utc::now().date_naive();
+    let until = today + chrono::Duration::days(days);
+
+    devices::table
+        .filter(devices::warranty_expiry_date.between(today, until))
+        .order((devices::warranty_expiry_date.asc(), devices::id.asc()))
+        .load(conn)
+}
+
+/// Record that a device's warranty expiry has been notified.
+/// Returns false if this device/expiry date was already notified.
+pub fn mark_warranty_notified(
+    conn: &mut DbConnection,
+    device_id: i32,
+    warranty_expiry_date: chrono::NaiveDate,
+) -> QueryResult<bool> {
+    let inserted = diesel::insert_into(device_warranty_notifications::table)
+        .values((
+            device_warranty_notifications::device_id.eq(device_id),
+            device_warranty_notifications::warranty_expiry_date.eq(warranty_expiry_date),
+        ))
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+
+    Ok(inserted == 1)
+}
+
+/// Make `new_user` the device's primary user, closing the previous
+/// assignment and opening a new one in the history. No-op if `new_user` is
+/// already the current primary user.
+pub fn reassign_primary_user(
+    conn: &mut DbConnection,
+    device_id: i32,
+    new_user: Uuid,
+    by: Option<Uuid>,
+) -> QueryResult<Device> {
+    use diesel::dsl::now;
+
+    conn.transaction(|conn| {
+        let device: Device = devices::table.find(device_id).for_update().first(conn)?;
+
+        let open: Option<Uuid> = device_assignment_history::table
+            .filter(device_assignment_history::device_id.eq(device_id))
+            .filter(device_assignment_history::unassigned_at.is_null())
+            .select(device_assignment_history::user_uuid)
+            .first(conn)
+            .optional()?;
+
+        if device.primary_user_uuid == Some(new_user) && open == Some(new_user) {
+            return Ok(device);
+        }
+
+        diesel::update(
+            device_assignment_history::table
+                .filter(device_assignment_history::device_id.eq(device_id))
+                .filter(device_assignment_history::unassigned_at.is_null()),
+        )
+        .set(device_assignment_history::unassigned_at.eq(now))
+        .execute(conn)?;
+
+        diesel::insert_into(device_assignment_history::table)
+            .values((
+                device_assignment_history::device_id.eq(device_id),
+                device_assignment_history::user_uuid.eq(new_user),
+                device_assignment_history::assigned_by.eq(by),
+                device_assignment_history::assigned_at.eq(now),
+            ))
+            .execute(conn)?;
+
+        diesel::update(devices::table.find(device_id))
+            .set((
+                devices::primary_user_uuid.eq(new_user),
+                devices::updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .get_result(conn)
+    })
+}
+
+/// Get a device's primary user history, most recent first
+pub fn get_device_assignment_history(
+    conn: &mut DbConnection,
+    device_id: i32,
+) -> QueryResult<Vec<DeviceAssignmentHistory>> {
+    device_assignment_history::table
+        .filter(device_assignment_history::device_id.eq(device_id))
+        .order((device_assignment_history::assigned_at.desc(), device_assignment_history::id.desc()))
+        .load(conn)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -345,6 +467,7 @@ mod tests {
             os_version: None,
             is_managed: None,
             enrollment_date: None,
+            warranty_expiry_date: None,
         }
     }
 
@@ -394,6 +517,7 @@ mod tests {
             os_version: None,
             is_managed: None,
             enrollment_date: None,
+            warranty_expiry_date: None,
             updated_at: None,
         };
 
@@ -412,4 +536,105 @@ mod tests {
         let result = get_device_by_id(&mut conn, dev.id);
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn warranty_window_includes_both_boundaries() {
+        let mut conn = setup_test_connection();
+        let today = Utc::now().date_naive();
+
+        let mut ids = Vec::new();
+        for (name, offset) in [("WarrantyPast", -1), ("WarrantyToday", 0), ("WarrantyEdge", 30), ("WarrantyLater", 31)] {
+            let mut device = minimal_device(name);
+            device.warranty_expiry_date = Some(today + chrono::Duration::days(offset));
+            ids.push(create_device(&mut conn, device).unwrap().id);
+        }
+        let no_date = create_device(&mut conn, minimal_device("WarrantyUnknown")).unwrap();
+
+        let expiring: Vec<i32> = get_devices_expiring_within(&mut conn, 30)
+            .unwrap()
+            .into_iter()
+            .map(|d| d.id)
+            .filter(|id| ids.contains(id) || *id == no_date.id)
+            .collect();
+
+        assert_eq!(expiring, vec![ids[1], ids[2]]);
+    }
+
+    #[test]
+    fn warranty_notification_is_recorded_once_per_expiry_date() {
+        let mut conn = setup_test_connection();
+        let today = Utc::now().date_naive();
+        let dev = create_device(&mut conn, minimal_device("WarrantyDedup")).unwrap();
+
+        assert!(mark_warranty_notified(&mut conn, dev.id, today).unwrap());
+        assert!(!mark_warranty_notified(&mut conn, dev.id, today).unwrap());
+
+        // A renewed warranty has a new date and is notified again
+        let renewed = today + chrono::Duration::days(365);
+        assert!(mark_warranty_notified(&mut conn, dev.id, renewed).unwrap());
+    }
+
+    #[test]
+    fn reassigning_twice_records_two_assignments() {
+        use crate::models::UserRole;
+        use crate::test_helpers::TestFixtures;
+
+        let mut conn = setup_test_connection();
+        let admin = TestFixtures::create_user(&mut conn, "assignadmin", UserRole::Admin);
+        let first = TestFixtures::create_user(&mut conn, "firstowner", UserRole::User);
+        let second = TestFixtures::create_user(&mut conn, "secondowner", UserRole::User);
+        let device = create_device(&mut conn, minimal_device("HandMeDown")).unwrap();
+
+        reassign_primary_user(&mut conn, device.id, first.uuid, Some(admin.uuid)).unwrap();
+        // Reassigning to the current owner doesn't add history
+        reassign_primary_user(&mut conn, device.id, first.uuid, Some(admin.uuid)).unwrap();
+        let device = reassign_primary_user(&mut conn, device.id, second.uuid, Some(admin.uuid)).unwrap();
+        assert_eq!(device.primary_user_uuid, Some(second.uuid));
+
+        let history = get_device_assignment_history(&mut conn, device.id).unwrap();
+        assert_eq!(history.len(), 2);
+
+        let (current, previous) = (&history[0], &history[1]);
+        assert_eq!(current.user_uuid, second.uuid);
+        assert_eq!(current.assigned_by, Some(admin.uuid));
+        assert_eq!(current.unassigned_at, None);
+        assert_eq!(previous.user_uuid, first.uuid);
+        assert_eq!(previous.unassigned_at, Some(current.assigned_at));
+        assert!(previous.assigned_at <= current.assigned_at);
+    }
+
+    #[test]
+    fn upsert_by_serial_creates_then_updates() {
+        let mut conn = setup_test_connection();
+
+        let mut existing = minimal_device("Imported Laptop");
+        existing.serial_number = Some("UPSERT-SN-1".to_string());
+        existing.model = Some("Old Model".to_string());
+        existing.location = Some("Storage".to_string());
+        let (original, created) = upsert_device_by_serial(&mut conn, existing).unwrap();
+        assert!(created);
+
+        let mut again = minimal_device("Imported Laptop (renamed)");
+        again.serial_number = Some("UPSERT-SN-1".to_string());
+        again.model = Some("New Model".to_string());
+        let (updated, created) = upsert_device_by_serial(&mut conn, again).unwrap();
+        assert!(!created);
+        assert_eq!(updated.id, original.id);
+        assert_eq!(updated.name, "Imported Laptop (renamed)");
+        assert_eq!(updated.model.as_deref(), Some("New Model"));
+        // Fields missing from the import are left alone
+        assert_eq!(updated.location.as_deref(), Some("Storage"));
+
+        let mut fresh = minimal_device("Other Laptop");
+        fresh.serial_number = Some("UPSERT-SN-2".to_string());
+        let (other, created) = upsert_device_by_serial(&mut conn, fresh).unwrap();
+        assert!(created);
+        assert_ne!(other.id, original.id);
+    }
+
+    #[test]
+    fn upsert_by_serial_requires_serial() {
+        let mut conn = setup_test_connection();
+        assert!(upsert_device_by_serial(&mut conn, minimal_device("No Serial")).is_err());
+    }
+}
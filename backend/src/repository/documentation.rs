@@ -1,13 +1,16 @@
+use std::collections::{HashMap, HashSet};
+
 use diesel::prelude::*;
 use diesel::result::Error;
 use diesel::sql_types::{Integer, Nullable};
 
 use crate::db::DbConnection;
 use crate::models::{
-    DocumentationPage, DocumentationPageWithChildren,
-    NewDocumentationPage, DocumentationPageUpdate, PageOrder
+    DocumentationPage, DocumentationPageWithChildren, DocumentationTreeNode,
+    NewDocumentationPage, NewDocumentationRevision, DocumentationPageUpdate, PageOrder,
+    DocGroupVisibility, NewDocGroupVisibility, Group,
 };
-use crate::schema::documentation_pages;
+use crate::schema::{doc_group_visibility, documentation_pages, groups};
 
 // Get all documentation pages
 pub fn get_documentation_pages(conn: &mut DbConnection) -> Result<Vec<DocumentationPage>, Error> {
@@ -106,17 +109,22 @@ pub fn get_ordered_pages_by_parent_id(
         .load::<DocumentationPage>(conn)
 }
 
-// Reorder documentation pages
-pub fn reorder_pages(
+// Reorder the pages under a parent (None = top level). The listed pages are
+// moved under that parent if they aren't already; a move that would make a
+// page its own ancestor fails with RollbackTransaction.
+pub fn reorder_docs(
     conn: &mut DbConnection,
     parent_id: Option<i32>,
     page_orders: &[PageOrder],
 ) -> Result<Vec<DocumentationPage>, Error> {
-    // Begin transaction
     conn.transaction(|conn| {
         let mut updated_pages = Vec::new();
-        
+
         for order in page_orders {
+            if creates_cycle(conn, order.page_id, parent_id)? {
+                return Err(Error::RollbackTransaction);
+            }
+
             // Update the page's display_order and ensure it has the correct parent_id
             let updated_page = diesel::update(documentation_pages::table.find(order.page_id))
                 .set((
@@ -124,14 +132,28 @@ pub fn reorder_pages(
                     documentation_pages::parent_id.eq(parent_id),
                 ))
                 .get_result::<DocumentationPage>(conn)?;
-                
+
             updated_pages.push(updated_page);
         }
-        
+
         Ok(updated_pages)
     })
 }
 
+// Whether giving `page_id` the parent `new_parent_id` would create a cycle:
+// the page would become its own parent or a child of one of its descendants
+pub fn creates_cycle(
+    conn: &mut DbConnection,
+    page_id: i32,
+    new_parent_id: Option<i32>,
+) -> Result<bool, Error> {
+    match new_parent_id {
+        None => Ok(false),
+        Some(parent_id) if parent_id == page_id => Ok(true),
+        Some(parent_id) => is_descendant_of(conn, parent_id, page_id),
+    }
+}
+
 // Check if a page is a descendant of another page (to prevent circular references)
 fn is_descendant_of(
     conn: &mut DbConnection,
@@ -153,21 +175,81 @@ fn get_all_descendant_ids(
 
     while !pages_to_check.is_empty() {
         // Get direct children of all pages in the current batch
-        let children: Vec<DocumentationPage> = documentation_pages::table
+        let children: Vec<i32> = documentation_pages::table
             .filter(documentation_pages::parent_id.eq_any(&pages_to_check))
+            .select(documentation_pages::id)
             .load(conn)?;
 
-        // Clear the pages to check and add the children's IDs
+        // Clear the pages to check and add the children's IDs. Skipping IDs
+        // already seen keeps a pre-existing cycle from looping forever.
         pages_to_check.clear();
-        for child in children {
-            all_descendants.push(child.id);
-            pages_to_check.push(child.id);
+        for child_id in children {
+            if child_id != page_id && !all_descendants.contains(&child_id) {
+                all_descendants.push(child_id);
+                pages_to_check.push(child_id);
+            }
         }
     }
 
     Ok(all_descendants)
 }
 
+// Build the page tree for the sidebar table of contents. Archived pages (and
+// anything under them) are left out; siblings are sorted by display order,
+// then title.
+pub fn get_doc_tree(conn: &mut DbConnection) -> Result<Vec<DocumentationTreeNode>, Error> {
+    build_doc_tree(conn, &HashSet::new())
+}
+
+// The page tree as seen by a user: pages they can't see are left out along
+// with everything under them
+pub fn get_doc_tree_for_user(
+    conn: &mut DbConnection,
+    user_uuid: &uuid::Uuid,
+    is_admin: bool,
+) -> Result<Vec<DocumentationTreeNode>, Error> {
+    let hidden = get_hidden_doc_ids(conn, user_uuid, is_admin)?;
+    build_doc_tree(conn, &hidden)
+}
+
+fn build_doc_tree(conn: &mut DbConnection, hidden: &HashSet<i32>) -> Result<Vec<DocumentationTreeNode>, Error> {
+    let pages: Vec<DocumentationPage> = documentation_pages::table
+        .filter(documentation_pages::archived_at.is_null())
+        .order_by((
+            coalesce(documentation_pages::display_order, 0).asc(),
+            documentation_pages::title.asc(),
+        ))
+        .load(conn)?;
+
+    let mut children_by_parent: HashMap<Option<i32>, Vec<DocumentationPage>> = HashMap::new();
+    for page in pages.into_iter().filter(|page| !hidden.contains(&page.id)) {
+        children_by_parent.entry(page.parent_id).or_default().push(page);
+    }
+
+    fn build(
+        parent_id: Option<i32>,
+        children_by_parent: &mut HashMap<Option<i32>, Vec<DocumentationPage>>,
+    ) -> Vec<DocumentationTreeNode> {
+        // Removing each level as it's visited means a cycle in the data can't
+        // recurse forever; its pages are never reached from a root anyway
+        let pages = children_by_parent.remove(&parent_id).unwrap_or_default();
+        pages
+            .into_iter()
+            .map(|page| DocumentationTreeNode {
+                children: build(Some(page.id), children_by_parent),
+                id: page.id,
+                title: page.title,
+                slug: page.slug,
+                icon: page.icon,
+                status: page.status,
+                display_order: page.display_order,
+            })
+            .collect()
+    }
+
+    Ok(build(None, &mut children_by_parent))
+}
+
 // Move a page to a new parent
 pub fn move_page_to_parent(
     conn: &mut DbConnection,
@@ -177,17 +259,10 @@ pub fn move_page_to_parent(
 ) -> Result<DocumentationPage, Error> {
     // Begin transaction
     conn.transaction(|conn| {
-        // Validation 1: Cannot move a page to be its own parent
-        if new_parent_id == Some(page_id) {
-            return Err(Error::RollbackTransaction);
-        }
-
-        // Validation 2: Cannot move a page to be a child of its own descendant
+        // Cannot move a page under itself or one of its own descendants
         // (this would create a circular reference)
-        if let Some(parent_id) = new_parent_id {
-            if is_descendant_of(conn, parent_id, page_id)? {
-                return Err(Error::RollbackTransaction);
-            }
+        if creates_cycle(conn, page_id, new_parent_id)? {
+            return Err(Error::RollbackTransaction);
         }
 
         // Update the page's parent_id and display_order
@@ -234,9 +309,24 @@ pub fn update_documentation_yjs_state(
         .get_result(conn)
 }
 
-// Create a documentation revision snapshot
-// Note: This is simplified - the schema doesn't have a revision number or contributed_by
-// Creates a basic revision with just the snapshot and metadata
+// Store a compacted Yjs document together with its state vector
+pub fn update_documentation_yjs_snapshot(
+    conn: &mut DbConnection,
+    page_id: i32,
+    yjs_document: Vec<u8>,
+    yjs_state_vector: Vec<u8>,
+) -> Result<DocumentationPage, Error> {
+    diesel::update(documentation_pages::table.find(page_id))
+        .set((
+            documentation_pages::yjs_document.eq(Some(yjs_document)),
+            documentation_pages::yjs_state_vector.eq(Some(yjs_state_vector)),
+            documentation_pages::updated_at.eq(diesel::dsl::now),
+        ))
+        .get_result(conn)
+}
+
+// Create a documentation revision snapshot, attributed to the first
+// contributor (or the page author if there were none)
 pub fn create_documentation_revision(
     conn: &mut DbConnection,
     page_id: i32,
@@ -245,41 +335,89 @@ pub fn create_documentation_revision(
     contributed_by: Vec<Option<uuid::Uuid>>,
 ) -> Result<i32, Error> {
     use crate::schema::documentation_pages::dsl as doc_dsl;
-    use crate::schema::documentation_revisions;
 
     conn.transaction(|conn| {
-        // Get current revision number from the page and created_by user
         let page: DocumentationPage = doc_dsl::documentation_pages
             .find(page_id)
             .first(conn)?;
 
-        // Get the latest revision number for this page
-        let latest_revision: i32 = documentation_revisions::table
-            .filter(documentation_revisions::page_id.eq(page_id))
-            .select(diesel::dsl::max(documentation_revisions::revision_number))
-            .first::<Option<i32>>(conn)?
-            .unwrap_or(0);
-
-        let new_revision_number = latest_revision + 1;
-
         // Use the first contributor or the created_by from the page
         let created_by = contributed_by.first()
             .and_then(|opt_uuid| *opt_uuid)
             .unwrap_or(page.created_by);
 
-        // Insert new revision (schema has different fields than article_content_revisions)
-        diesel::insert_into(documentation_revisions::table)
-            .values((
-                documentation_revisions::page_id.eq(page_id),
-                documentation_revisions::revision_number.eq(new_revision_number),
-                documentation_revisions::title.eq(&page.title), // Snapshot the title
-                documentation_revisions::yjs_document_snapshot.eq(yjs_document_content),
-                documentation_revisions::yjs_state_vector.eq(yjs_state_vector),
-                documentation_revisions::created_by.eq(created_by),
-            ))
-            .execute(conn)?;
+        insert_documentation_revision(conn, &page, yjs_state_vector, yjs_document_content, created_by, None)
+    })
+}
+
+// Append a revision with the next revision number. Must run inside a transaction.
+fn insert_documentation_revision(
+    conn: &mut DbConnection,
+    page: &DocumentationPage,
+    yjs_state_vector: Vec<u8>,
+    yjs_document_content: Vec<u8>,
+    created_by: uuid::Uuid,
+    change_summary: Option<String>,
+) -> Result<i32, Error> {
+    use crate::schema::documentation_revisions;
+
+    // Get the latest revision number for this page
+    let latest_revision: i32 = documentation_revisions::table
+        .filter(documentation_revisions::page_id.eq(page.id))
+        .select(diesel::dsl::max(documentation_revisions::revision_number))
+        .first::<Option<i32>>(conn)?
+        .unwrap_or(0);
+
+    let new_revision_number = latest_revision + 1;
+
+    diesel::insert_into(documentation_revisions::table)
+        .values(NewDocumentationRevision {
+            page_id: page.id,
+            revision_number: new_revision_number,
+            title: page.title.clone(), // Snapshot the title
+            yjs_document_snapshot: yjs_document_content,
+            yjs_state_vector,
+            created_by,
+            change_summary,
+        })
+        .execute(conn)?;
+
+    Ok(new_revision_number)
+}
 
-        Ok(new_revision_number)
+// Restore a page's content to an earlier revision. The restore is recorded
+// as a new revision, so it can itself be undone. The title is left as is.
+// Returns the updated page and the new revision number.
+pub fn restore_documentation_revision(
+    conn: &mut DbConnection,
+    page_id: i32,
+    revision_number: i32,
+    restored_by: uuid::Uuid,
+) -> Result<(DocumentationPage, i32), Error> {
+    use crate::schema::documentation_pages::dsl;
+
+    conn.transaction(|conn| {
+        let revision = get_documentation_revision(conn, page_id, revision_number)?;
+
+        let page: DocumentationPage = diesel::update(dsl::documentation_pages.find(page_id))
+            .set((
+                dsl::yjs_document.eq(Some(&revision.yjs_document_snapshot)),
+                dsl::yjs_state_vector.eq(Some(&revision.yjs_state_vector)),
+                dsl::last_edited_by.eq(restored_by),
+                dsl::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result(conn)?;
+
+        let new_revision_number = insert_documentation_revision(
+            conn,
+            &page,
+            revision.yjs_state_vector,
+            revision.yjs_document_snapshot,
+            restored_by,
+            Some(format!("Restored revision {revision_number}")),
+        )?;
+
+        Ok((page, new_revision_number))
     })
 }
 
@@ -323,6 +461,116 @@ pub fn get_latest_documentation_revision(
         .first(conn)
 }
 
+// ============= Page-Group Visibility =============
+// A page with no visibility entries is public; otherwise only members of the
+// listed groups (and admins) can see it.
+
+// Get groups that can see a page
+pub fn get_visible_groups_for_doc(conn: &mut DbConnection, page_id: i32) -> Result<Vec<Group>, Error> {
+    doc_group_visibility::table
+        .filter(doc_group_visibility::page_id.eq(page_id))
+        .inner_join(groups::table)
+        .select(groups::all_columns)
+        .order(groups::name.asc())
+        .load(conn)
+}
+
+// Set which groups can see a page (replaces existing visibility). An empty
+// list makes the page public.
+pub fn set_doc_visibility(
+    conn: &mut DbConnection,
+    page_id: i32,
+    group_ids: Vec<i32>,
+    created_by: Option<uuid::Uuid>,
+) -> Result<Vec<DocGroupVisibility>, Error> {
+    conn.transaction(|conn| {
+        diesel::delete(doc_group_visibility::table.filter(doc_group_visibility::page_id.eq(page_id)))
+            .execute(conn)?;
+
+        if group_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let new_entries: Vec<NewDocGroupVisibility> = group_ids
+            .iter()
+            .map(|group_id| NewDocGroupVisibility {
+                page_id,
+                group_id: *group_id,
+                created_by,
+            })
+            .collect();
+
+        diesel::insert_into(doc_group_visibility::table)
+            .values(&new_entries)
+            .get_results(conn)
+    })
+}
+
+// Check if a user can see a specific page
+pub fn can_user_see_doc(
+    conn: &mut DbConnection,
+    user_uuid: &uuid::Uuid,
+    page_id: i32,
+    is_admin: bool,
+) -> Result<bool, Error> {
+    if is_admin {
+        return Ok(true);
+    }
+
+    // Get group IDs that can see this page
+    let page_group_ids: Vec<i32> = doc_group_visibility::table
+        .filter(doc_group_visibility::page_id.eq(page_id))
+        .select(doc_group_visibility::group_id)
+        .load(conn)?;
+
+    // If no groups specified, the page is public
+    if page_group_ids.is_empty() {
+        return Ok(true);
+    }
+
+    let user_group_ids: Vec<i32> = crate::repository::groups::get_group_ids_for_user(conn, user_uuid)?;
+    Ok(user_group_ids.iter().any(|id| page_group_ids.contains(id)))
+}
+
+// IDs of the restricted pages a user can't see (always empty for admins)
+pub fn get_hidden_doc_ids(
+    conn: &mut DbConnection,
+    user_uuid: &uuid::Uuid,
+    is_admin: bool,
+) -> Result<HashSet<i32>, Error> {
+    if is_admin {
+        return Ok(HashSet::new());
+    }
+
+    let restrictions: Vec<(i32, i32)> = doc_group_visibility::table
+        .select((doc_group_visibility::page_id, doc_group_visibility::group_id))
+        .load(conn)?;
+    if restrictions.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let user_group_ids: Vec<i32> = crate::repository::groups::get_group_ids_for_user(conn, user_uuid)?;
+    let restricted: HashSet<i32> = restrictions.iter().map(|(page_id, _)| *page_id).collect();
+    let allowed: HashSet<i32> = restrictions
+        .iter()
+        .filter(|(_, group_id)| user_group_ids.contains(group_id))
+        .map(|(page_id, _)| *page_id)
+        .collect();
+
+    Ok(restricted.difference(&allowed).copied().collect())
+}
+
+// Drop the pages a user can't see from a list
+pub fn filter_visible_docs(
+    conn: &mut DbConnection,
+    pages: Vec<DocumentationPage>,
+    user_uuid: &uuid::Uuid,
+    is_admin: bool,
+) -> Result<Vec<DocumentationPage>, Error> {
+    let hidden = get_hidden_doc_ids(conn, user_uuid, is_admin)?;
+    Ok(pages.into_iter().filter(|page| !hidden.contains(&page.id)).collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -429,4 +677,192 @@ mod tests {
         assert!(top.iter().all(|p| p.parent_id.is_none()));
         assert!(top.iter().any(|p| p.id == parent.id));
     }
+
+    fn make_child(conn: &mut DbConnection, created_by: Uuid, title: &str, parent_id: Option<i32>, order: i32) -> DocumentationPage {
+        let mut page = make_page(created_by);
+        page.title = title.to_string();
+        page.slug = Some(title.to_lowercase().replace(' ', "-"));
+        page.parent_id = parent_id;
+        page.display_order = Some(order);
+        create_documentation_page(page, conn).unwrap()
+    }
+
+    #[test]
+    fn doc_tree_nests_and_orders_pages() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "tocuser", UserRole::Admin);
+
+        let root = make_child(&mut conn, user.uuid, "Toc Root", None, 0);
+        let second = make_child(&mut conn, user.uuid, "Toc Second", Some(root.id), 2);
+        let first = make_child(&mut conn, user.uuid, "Toc First", Some(root.id), 1);
+        let grandchild = make_child(&mut conn, user.uuid, "Toc Grandchild", Some(second.id), 0);
+        let archived = make_child(&mut conn, user.uuid, "Toc Archived", Some(root.id), 0);
+        diesel::update(documentation_pages::table.find(archived.id))
+            .set(documentation_pages::archived_at.eq(diesel::dsl::now))
+            .execute(&mut conn)
+            .unwrap();
+
+        let tree = get_doc_tree(&mut conn).unwrap();
+        let node = tree.iter().find(|n| n.id == root.id).expect("root is top level");
+        let child_ids: Vec<i32> = node.children.iter().map(|n| n.id).collect();
+        assert_eq!(child_ids, vec![first.id, second.id]);
+        assert!(node.children[0].children.is_empty());
+        assert_eq!(node.children[1].children.len(), 1);
+        assert_eq!(node.children[1].children[0].id, grandchild.id);
+        assert!(tree.iter().all(|n| n.id != second.id));
+    }
+
+    #[test]
+    fn reparenting_under_a_descendant_is_rejected() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "cycleuser", UserRole::Admin);
+
+        let root = make_child(&mut conn, user.uuid, "Cycle Root", None, 0);
+        let child = make_child(&mut conn, user.uuid, "Cycle Child", Some(root.id), 0);
+        let grandchild = make_child(&mut conn, user.uuid, "Cycle Grandchild", Some(child.id), 0);
+
+        assert!(creates_cycle(&mut conn, root.id, Some(root.id)).unwrap());
+        assert!(creates_cycle(&mut conn, root.id, Some(grandchild.id)).unwrap());
+        assert!(!creates_cycle(&mut conn, grandchild.id, Some(root.id)).unwrap());
+        assert!(!creates_cycle(&mut conn, child.id, None).unwrap());
+
+        assert!(matches!(
+            move_page_to_parent(&mut conn, root.id, Some(grandchild.id), 0),
+            Err(Error::RollbackTransaction)
+        ));
+        let orders = [PageOrder { page_id: root.id, display_order: 0 }];
+        assert!(matches!(
+            reorder_docs(&mut conn, Some(child.id), &orders),
+            Err(Error::RollbackTransaction)
+        ));
+        assert_eq!(get_documentation_page(root.id, &mut conn).unwrap().parent_id, None);
+
+        // Moving a grandchild to the top level is fine
+        let orders = [PageOrder { page_id: grandchild.id, display_order: 5 }];
+        let moved = reorder_docs(&mut conn, None, &orders).unwrap();
+        assert_eq!(moved[0].parent_id, None);
+        assert_eq!(moved[0].display_order, Some(5));
+    }
+
+    /// Encode a Yjs document with one paragraph per entry
+    fn yjs_doc(paragraphs: &[&str]) -> Vec<u8> {
+        use yrs::{Doc, ReadTxn, StateVector, Transact, WriteTxn, XmlElementPrelim, XmlFragment, XmlTextPrelim};
+
+        let doc = Doc::new();
+        let mut txn = doc.transact_mut();
+        let fragment = txn.get_or_insert_xml_fragment("prosemirror");
+        for text in paragraphs {
+            let paragraph = fragment.push_back(&mut txn, XmlElementPrelim::empty("paragraph"));
+            paragraph.push_back(&mut txn, XmlTextPrelim::new(*text));
+        }
+        txn.encode_state_as_update_v1(&StateVector::default())
+    }
+
+    fn page_text(conn: &mut DbConnection, page_id: i32) -> Option<Vec<String>> {
+        let page = get_documentation_page(page_id, conn).unwrap();
+        crate::services::search::extractors::extract_blocks_from_yjs(&page.yjs_document.unwrap_or_default())
+    }
+
+    #[test]
+    fn saving_creates_revision_and_restore_reverts_content() {
+        let mut conn = setup_test_connection();
+        let author = TestFixtures::create_user(&mut conn, "revauthor", UserRole::Technician);
+        let restorer = TestFixtures::create_user(&mut conn, "revrestorer", UserRole::Admin);
+        let page = create_documentation_page(make_page(author.uuid), &mut conn).unwrap();
+
+        // First save point
+        let original = yjs_doc(&["Reset the router", "Wait a minute"]);
+        update_documentation_yjs_state(&mut conn, page.id, original.clone()).unwrap();
+        let first = create_documentation_revision(&mut conn, page.id, Vec::new(), original, vec![Some(author.uuid)]).unwrap();
+        assert_eq!(first, 1);
+
+        // Content accidentally wiped and saved
+        let wiped = yjs_doc(&["oops"]);
+        update_documentation_yjs_state(&mut conn, page.id, wiped.clone()).unwrap();
+        assert_eq!(create_documentation_revision(&mut conn, page.id, Vec::new(), wiped, vec![]).unwrap(), 2);
+
+        let revisions = get_documentation_revisions(&mut conn, page.id).unwrap();
+        assert_eq!(revisions.iter().map(|r| r.revision_number).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(revisions[1].created_by, author.uuid);
+        // No contributors falls back to the page author
+        assert_eq!(revisions[0].created_by, author.uuid);
+        assert_eq!(page_text(&mut conn, page.id).unwrap(), vec!["oops"]);
+
+        let (restored, revision_number) = restore_documentation_revision(&mut conn, page.id, first, restorer.uuid).unwrap();
+        assert_eq!(revision_number, 3);
+        assert_eq!(restored.last_edited_by, restorer.uuid);
+        assert_eq!(page_text(&mut conn, page.id).unwrap(), vec!["Reset the router", "Wait a minute"]);
+
+        let latest = get_latest_documentation_revision(&mut conn, page.id).unwrap();
+        assert_eq!(latest.created_by, restorer.uuid);
+        assert_eq!(latest.change_summary.as_deref(), Some("Restored revision 1"));
+
+        assert!(matches!(
+            restore_documentation_revision(&mut conn, page.id, 42, restorer.uuid),
+            Err(Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn public_doc_visible_to_any_user() {
+        let mut conn = setup_test_connection();
+        let author = TestFixtures::create_user(&mut conn, "docauthor", UserRole::Technician);
+        let user = TestFixtures::create_user(&mut conn, "docreader", UserRole::User);
+        let page = make_child(&mut conn, author.uuid, "Public Doc", None, 0);
+        // No group restrictions → public
+        assert!(can_user_see_doc(&mut conn, &user.uuid, page.id, false).unwrap());
+    }
+
+    #[test]
+    fn restricted_doc_visible_to_allowed_group() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "docmember", UserRole::User);
+        let group = TestFixtures::create_group(&mut conn, "Doc Support");
+        TestFixtures::add_user_to_group(&mut conn, user.uuid, group.id);
+        let page = make_child(&mut conn, user.uuid, "Support Runbook", None, 0);
+        set_doc_visibility(&mut conn, page.id, vec![group.id], None).unwrap();
+
+        assert!(can_user_see_doc(&mut conn, &user.uuid, page.id, false).unwrap());
+        assert!(!get_hidden_doc_ids(&mut conn, &user.uuid, false).unwrap().contains(&page.id));
+    }
+
+    #[test]
+    fn restricted_doc_hidden_from_non_member() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "docoutsider", UserRole::User);
+        let group = TestFixtures::create_group(&mut conn, "Doc Finance");
+        // user is NOT added to the group
+        let root = make_child(&mut conn, user.uuid, "Finance Root", None, 0);
+        let restricted = make_child(&mut conn, user.uuid, "Finance Payroll", Some(root.id), 0);
+        make_child(&mut conn, user.uuid, "Finance Payroll Detail", Some(restricted.id), 0);
+        set_doc_visibility(&mut conn, restricted.id, vec![group.id], None).unwrap();
+
+        assert!(!can_user_see_doc(&mut conn, &user.uuid, restricted.id, false).unwrap());
+
+        let pages = get_pages_by_parent_id(root.id, &mut conn).unwrap();
+        assert!(filter_visible_docs(&mut conn, pages, &user.uuid, false).unwrap().is_empty());
+
+        // The restricted page and everything under it is left out of the tree
+        let tree = get_doc_tree_for_user(&mut conn, &user.uuid, false).unwrap();
+        let node = tree.iter().find(|n| n.id == root.id).unwrap();
+        assert!(node.children.is_empty());
+
+        // Clearing the restriction makes it public again
+        set_doc_visibility(&mut conn, restricted.id, vec![], None).unwrap();
+        assert!(can_user_see_doc(&mut conn, &user.uuid, restricted.id, false).unwrap());
+    }
+
+    #[test]
+    fn admin_sees_restricted_doc() {
+        let mut conn = setup_test_connection();
+        let admin = TestFixtures::create_user(&mut conn, "docadmin", UserRole::Admin);
+        let group = TestFixtures::create_group(&mut conn, "Doc Secret");
+        let page = make_child(&mut conn, admin.uuid, "Secret Doc", None, 0);
+        set_doc_visibility(&mut conn, page.id, vec![group.id], Some(admin.uuid)).unwrap();
+        assert_eq!(get_visible_groups_for_doc(&mut conn, page.id).unwrap()[0].id, group.id);
+
+        // admin not in group but passes is_admin=true
+        assert!(can_user_see_doc(&mut conn, &admin.uuid, page.id, true).unwrap());
+        assert!(get_hidden_doc_ids(&mut conn, &admin.uuid, true).unwrap().is_empty());
+    }
 }
@@ -191,15 +191,18 @@ pub fn remove_user_from_group(
 }
 
 /// Set all members of a group (replaces existing members)
+/// Members who stay in the group keep their manager flag.
 pub fn set_group_members(
     conn: &mut DbConnection,
     group_id: i32,
     member_uuids: Vec<Uuid>,
     created_by: Option<Uuid>,
 ) -> QueryResult<Vec<UserGroup>> {
-    // Delete all existing members
+    // Remove members no longer in the list
     diesel::delete(
-        user_groups::table.filter(user_groups::group_id.eq(group_id))
+        user_groups::table
+            .filter(user_groups::group_id.eq(group_id))
+            .filter(user_groups::user_uuid.ne_all(&member_uuids))
     ).execute(conn)?;
 
     // Add new members
@@ -218,19 +221,27 @@ pub fn set_group_members(
 
     diesel::insert_into(user_groups::table)
         .values(&new_memberships)
-        .get_results(conn)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+
+    user_groups::table
+        .filter(user_groups::group_id.eq(group_id))
+        .load(conn)
 }
 
 /// Set all groups for a user (replaces existing group memberships)
+/// Groups the user stays in keep their manager flag.
 pub fn set_user_groups(
     conn: &mut DbConnection,
     user_uuid: Uuid,
     group_ids: Vec<i32>,
     created_by: Option<Uuid>,
 ) -> QueryResult<Vec<UserGroup>> {
-    // Delete all existing memberships for this user
+    // Remove memberships no longer in the list
     diesel::delete(
-        user_groups::table.filter(user_groups::user_uuid.eq(user_uuid))
+        user_groups::table
+            .filter(user_groups::user_uuid.eq(user_uuid))
+            .filter(user_groups::group_id.ne_all(&group_ids))
     ).execute(conn)?;
 
     // Add new memberships
@@ -249,7 +260,29 @@ pub fn set_user_groups(
 
     diesel::insert_into(user_groups::table)
         .values(&new_memberships)
-        .get_results(conn)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+
+    user_groups::table
+        .filter(user_groups::user_uuid.eq(user_uuid))
+        .load(conn)
+}
+
+/// Set which members of a group are managers (replaces existing managers).
+/// UUIDs that are not members of the group are ignored.
+pub fn set_group_managers(
+    conn: &mut DbConnection,
+    group_id: i32,
+    manager_uuids: &[Uuid],
+) -> QueryResult<Vec<UserGroup>> {
+    diesel::update(user_groups::table.filter(user_groups::group_id.eq(group_id)))
+        .set(user_groups::is_manager.eq(user_groups::user_uuid.eq_any(manager_uuids)))
+        .execute(conn)?;
+
+    user_groups::table
+        .filter(user_groups::group_id.eq(group_id))
+        .filter(user_groups::is_manager.eq(true))
+        .load(conn)
 }
 
 /// Get group IDs for a user
@@ -260,6 +293,15 @@ pub fn get_group_ids_for_user(conn: &mut DbConnection, user_uuid: &Uuid) -> Quer
         .load(conn)
 }
 
+/// Get IDs of the groups a user manages
+pub fn get_managed_group_ids_for_user(conn: &mut DbConnection, user_uuid: &Uuid) -> QueryResult<Vec<i32>> {
+    user_groups::table
+        .filter(user_groups::user_uuid.eq(user_uuid))
+        .filter(user_groups::is_manager.eq(true))
+        .select(user_groups::group_id)
+        .load(conn)
+}
+
 // ============================================================================
 // External Group Sync Operations (Microsoft Graph, etc.)
 // ============================================================================
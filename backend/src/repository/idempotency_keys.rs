@@ -0,0 +1,61 @@
+//! Idempotency Key Repository
+//!
+//! Provides database operations for the keys that let clients safely retry
+//! create requests.
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::db::DbConnection;
+use crate::models::{IdempotencyKey, NewIdempotencyKey};
+use crate::schema::idempotency_keys;
+
+/// Claim a key for a new request. Returns None if the key was claimed, or
+/// the existing record if this key was already used.
+pub fn claim_key(
+    conn: &mut DbConnection,
+    new_key: NewIdempotencyKey,
+) -> QueryResult<Option<IdempotencyKey>> {
+    let inserted = diesel::insert_into(idempotency_keys::table)
+        .values(&new_key)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    if inserted == 1 {
+        return Ok(None);
+    }
+
+    idempotency_keys::table
+        .find((new_key.user_uuid, new_key.scope, new_key.idempotency_key))
+        .first(conn)
+        .optional()
+}
+
+/// Store the response of the request that claimed a key
+pub fn complete_key(
+    conn: &mut DbConnection,
+    user_uuid: Uuid,
+    scope: &str,
+    key: &str,
+    resource_id: Option<i32>,
+    response_status: i32,
+    response_body: &str,
+) -> QueryResult<usize> {
+    diesel::update(idempotency_keys::table.find((user_uuid, scope, key)))
+        .set((
+            idempotency_keys::resource_id.eq(resource_id),
+            idempotency_keys::response_status.eq(Some(response_status)),
+            idempotency_keys::response_body.eq(Some(response_body)),
+        ))
+        .execute(conn)
+}
+
+/// Release a key whose request failed, so a retry can run it again
+pub fn release_key(conn: &mut DbConnection, user_uuid: Uuid, scope: &str, key: &str) -> QueryResult<usize> {
+    diesel::delete(idempotency_keys::table.find((user_uuid, scope, key))).execute(conn)
+}
+
+/// Delete keys created before the cutoff
+pub fn delete_keys_before(conn: &mut DbConnection, cutoff: NaiveDateTime) -> QueryResult<usize> {
+    diesel::delete(idempotency_keys::table.filter(idempotency_keys::created_at.lt(cutoff))).execute(conn)
+}
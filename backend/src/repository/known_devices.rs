@@ -0,0 +1,80 @@
+//! Known Devices Repository
+//!
+//! The coarse device fingerprints each user has signed in from (see
+//! `services::sign_in_devices`). Rows are only ever added or touched; they go
+//! away with the user.
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::db::DbConnection;
+use crate::models::KnownDevice;
+use crate::schema::known_devices;
+
+/// Whether a sign-in's fingerprint had been seen before
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sighting {
+    /// Signed in from this fingerprint before
+    Known,
+    /// First sign-in from this fingerprint. `first_device` is set when the
+    /// user had no known devices at all, i.e. their first sign-in.
+    New { first_device: bool },
+}
+
+/// Record a sign-in from `fingerprint` at `now`
+pub fn remember(
+    conn: &mut DbConnection,
+    user_uuid: &Uuid,
+    fingerprint: &str,
+    now: NaiveDateTime,
+) -> QueryResult<Sighting> {
+    conn.transaction(|conn| {
+        let inserted = diesel::insert_into(known_devices::table)
+            .values(&KnownDevice {
+                user_uuid: *user_uuid,
+                fingerprint: fingerprint.to_string(),
+                first_seen_at: now,
+                last_seen_at: now,
+            })
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        if inserted == 0 {
+            diesel::update(known_devices::table.find((user_uuid, fingerprint)))
+                .set(known_devices::last_seen_at.eq(now))
+                .execute(conn)?;
+            return Ok(Sighting::Known);
+        }
+
+        let others: i64 = known_devices::table
+            .filter(known_devices::user_uuid.eq(user_uuid))
+            .filter(known_devices::fingerprint.ne(fingerprint))
+            .count()
+            .get_result(conn)?;
+        Ok(Sighting::New { first_device: others == 0 })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+    use chrono::Utc;
+
+    #[test]
+    fn only_unseen_fingerprints_are_new() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "knowndevices", UserRole::User);
+        let now = Utc::now().naive_utc();
+
+        assert_eq!(remember(&mut conn, &user.uuid, "Firefox on Linux", now).unwrap(), Sighting::New { first_device: true });
+        assert_eq!(remember(&mut conn, &user.uuid, "Firefox on Linux", now).unwrap(), Sighting::Known);
+        assert_eq!(remember(&mut conn, &user.uuid, "Safari on iPhone", now).unwrap(), Sighting::New { first_device: false });
+
+        // Fingerprints are per user
+        let other = TestFixtures::create_user(&mut conn, "knowndevicesother", UserRole::User);
+        assert_eq!(remember(&mut conn, &other.uuid, "Safari on iPhone", now).unwrap(), Sighting::New { first_device: true });
+    }
+}
@@ -1,26 +1,37 @@
 // Domain-specific modules
 pub mod article_content;
 pub mod assignment_rules;
+pub mod canned_responses;
 pub mod categories;
 pub mod comments;
+pub mod custom_fields;
 pub mod devices;
 pub mod documentation;
 pub mod groups;
 pub mod linked_tickets;
 pub mod projects;
+pub mod sla;
 pub mod sync_history;
 pub mod ticket_query;
+pub mod ticket_watchers;
+pub mod ticket_worklogs;
 pub mod tickets;
+pub mod transaction;
 pub mod user_auth_identities;
 pub mod user_emails;
 pub mod user_helpers; // Helper functions for user/email operations
 pub mod users;
 
 // Security and session management repositories
+pub mod account_lockouts;
 pub mod active_sessions;
 pub mod api_tokens;
+pub mod idempotency_keys;
+pub mod known_devices;
+pub mod permission_grants;
 pub mod refresh_tokens;
 pub mod reset_tokens;
+pub mod security_events;
 pub mod user_ticket_views;
 
 // Site configuration
@@ -43,6 +54,7 @@ pub use documentation::*;
 pub use linked_tickets::*;
 pub use projects::*;
 pub use tickets::*;
+pub use transaction::with_transaction;
 pub use users::*;
 
 // Note: We've completed the transition to a fully modular structure
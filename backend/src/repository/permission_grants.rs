@@ -0,0 +1,56 @@
+//! Permission Grant Repository
+//!
+//! Provides database operations for per-user and per-group permission grants.
+
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::db::DbConnection;
+use crate::models::{NewPermissionGrant, PermissionGrant};
+use crate::schema::{permission_grants, user_groups};
+
+/// List all permission grants
+pub fn list_grants(conn: &mut DbConnection) -> QueryResult<Vec<PermissionGrant>> {
+    permission_grants::table
+        .order(permission_grants::created_at.desc())
+        .load::<PermissionGrant>(conn)
+}
+
+/// Create a permission grant
+pub fn create_grant(
+    conn: &mut DbConnection,
+    new_grant: NewPermissionGrant,
+) -> QueryResult<PermissionGrant> {
+    diesel::insert_into(permission_grants::table)
+        .values(&new_grant)
+        .get_result(conn)
+}
+
+/// Delete a permission grant, returning it if it existed
+pub fn delete_grant(conn: &mut DbConnection, grant_id: i32) -> QueryResult<Option<PermissionGrant>> {
+    diesel::delete(permission_grants::table.find(grant_id))
+        .get_result(conn)
+        .optional()
+}
+
+/// Check whether a user holds a permission directly or through one of their groups
+pub fn user_has_grant(
+    conn: &mut DbConnection,
+    user_uuid: Uuid,
+    permission: &str,
+) -> QueryResult<bool> {
+    let member_of = user_groups::table
+        .filter(user_groups::user_uuid.eq(user_uuid))
+        .select(user_groups::group_id.nullable());
+
+    diesel::select(diesel::dsl::exists(
+        permission_grants::table
+            .filter(permission_grants::permission.eq(permission))
+            .filter(
+                permission_grants::user_uuid
+                    .eq(user_uuid)
+                    .or(permission_grants::group_id.eq_any(member_of)),
+            ),
+    ))
+    .get_result(conn)
+}
@@ -80,6 +80,52 @@ pub fn delete_plugin_by_uuid(
     diesel::delete(plugins::table.filter(plugins::uuid.eq(plugin_uuid))).execute(conn)
 }
 
+/// Rows removed along with an uninstalled plugin
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RemovedPluginData {
+    pub settings: usize,
+    pub storage: usize,
+    pub activity: usize,
+}
+
+/// Delete a plugin with its settings, storage and activity log.
+///
+/// The foreign keys cascade, but deleting the rows explicitly reports what
+/// was removed. Returns None if there is no such plugin.
+pub fn uninstall_plugin(
+    conn: &mut DbConnection,
+    plugin_uuid: Uuid,
+) -> Result<Option<RemovedPluginData>, diesel::result::Error> {
+    conn.transaction(|conn| {
+        let plugin_id: Option<i32> = plugins::table
+            .filter(plugins::uuid.eq(plugin_uuid))
+            .select(plugins::id)
+            .first(conn)
+            .optional()?;
+        let Some(plugin_id) = plugin_id else {
+            return Ok(None);
+        };
+
+        let delete_data = |conn: &mut DbConnection, data_type: &str| {
+            diesel::delete(
+                plugin_data::table
+                    .filter(plugin_data::plugin_id.eq(plugin_id))
+                    .filter(plugin_data::data_type.eq(data_type)),
+            )
+            .execute(conn)
+        };
+        let removed = RemovedPluginData {
+            settings: delete_data(conn, "setting")?,
+            storage: delete_data(conn, "storage")?,
+            activity: diesel::delete(plugin_activity::table.filter(plugin_activity::plugin_id.eq(plugin_id)))
+                .execute(conn)?,
+        };
+        diesel::delete(plugins::table.find(plugin_id)).execute(conn)?;
+
+        Ok(Some(removed))
+    })
+}
+
 /// Update a plugin's bundle metadata
 pub fn update_plugin_bundle(
     conn: &mut DbConnection,
@@ -95,7 +141,7 @@ pub fn update_plugin_bundle(
 // Plugin Data (Settings + Storage consolidated)
 // =============================================================================
 
-/// Get all data entries of a specific type for a plugin
+/// Get all global data entries of a specific type for a plugin
 pub fn get_plugin_data(
     conn: &mut DbConnection,
     plugin_id: i32,
@@ -104,22 +150,39 @@ pub fn get_plugin_data(
     plugin_data::table
         .filter(plugin_data::plugin_id.eq(plugin_id))
         .filter(plugin_data::data_type.eq(data_type))
+        .filter(plugin_data::user_uuid.is_null())
         .order(plugin_data::key.asc())
         .load::<PluginData>(conn)
 }
 
+/// Query for a data entry in the global namespace (`user_uuid` None) or a user's
+fn data_entry_query<'a>(
+    plugin_id: i32,
+    data_type: &'a str,
+    key: &'a str,
+    user_uuid: Option<Uuid>,
+) -> plugin_data::BoxedQuery<'a, diesel::pg::Pg> {
+    let query = plugin_data::table
+        .filter(plugin_data::plugin_id.eq(plugin_id))
+        .filter(plugin_data::data_type.eq(data_type))
+        .filter(plugin_data::key.eq(key))
+        .into_boxed();
+
+    match user_uuid {
+        Some(user_uuid) => query.filter(plugin_data::user_uuid.eq(user_uuid)),
+        None => query.filter(plugin_data::user_uuid.is_null()),
+    }
+}
+
 /// Get a specific data entry for a plugin
 pub fn get_plugin_data_entry(
     conn: &mut DbConnection,
     plugin_id: i32,
     data_type: &str,
     key: &str,
+    user_uuid: Option<Uuid>,
 ) -> Result<PluginData, diesel::result::Error> {
-    plugin_data::table
-        .filter(plugin_data::plugin_id.eq(plugin_id))
-        .filter(plugin_data::data_type.eq(data_type))
-        .filter(plugin_data::key.eq(key))
-        .first::<PluginData>(conn)
+    data_entry_query(plugin_id, data_type, key, user_uuid).first::<PluginData>(conn)
 }
 
 /// Set a plugin data entry (upsert)
@@ -130,13 +193,10 @@ pub fn set_plugin_data(
     key: String,
     value: Option<serde_json::Value>,
     is_secret: bool,
+    user_uuid: Option<Uuid>,
 ) -> Result<PluginData, diesel::result::Error> {
     // Try to update existing
-    let existing = plugin_data::table
-        .filter(plugin_data::plugin_id.eq(plugin_id))
-        .filter(plugin_data::data_type.eq(data_type))
-        .filter(plugin_data::key.eq(&key))
-        .first::<PluginData>(conn);
+    let existing = data_entry_query(plugin_id, data_type, &key, user_uuid).first::<PluginData>(conn);
 
     match existing {
         Ok(entry) => {
@@ -155,6 +215,7 @@ pub fn set_plugin_data(
                 key,
                 value,
                 is_secret,
+                user_uuid,
             };
             diesel::insert_into(plugin_data::table)
                 .values(&new_entry)
@@ -170,14 +231,39 @@ pub fn delete_plugin_data_entry(
     plugin_id: i32,
     data_type: &str,
     key: &str,
+    user_uuid: Option<Uuid>,
 ) -> Result<usize, diesel::result::Error> {
-    diesel::delete(
-        plugin_data::table
-            .filter(plugin_data::plugin_id.eq(plugin_id))
-            .filter(plugin_data::data_type.eq(data_type))
-            .filter(plugin_data::key.eq(key)),
-    )
-    .execute(conn)
+    let entries = plugin_data::table
+        .filter(plugin_data::plugin_id.eq(plugin_id))
+        .filter(plugin_data::data_type.eq(data_type))
+        .filter(plugin_data::key.eq(key));
+
+    match user_uuid {
+        Some(user_uuid) => {
+            diesel::delete(entries.filter(plugin_data::user_uuid.eq(user_uuid))).execute(conn)
+        }
+        None => diesel::delete(entries.filter(plugin_data::user_uuid.is_null())).execute(conn),
+    }
+}
+
+/// Get every secret data entry across all plugins (used for key rotation)
+pub fn get_all_secret_data(conn: &mut DbConnection) -> Result<Vec<PluginData>, diesel::result::Error> {
+    plugin_data::table
+        .filter(plugin_data::is_secret.eq(true))
+        .order(plugin_data::id.asc())
+        .load::<PluginData>(conn)
+}
+
+/// Replace the value of a data entry by id
+pub fn update_plugin_data_value(
+    conn: &mut DbConnection,
+    data_id: i32,
+    value: Option<serde_json::Value>,
+) -> Result<PluginData, diesel::result::Error> {
+    let update = PluginDataUpdate { value: Some(value) };
+    diesel::update(plugin_data::table.filter(plugin_data::id.eq(data_id)))
+        .set(&update)
+        .get_result(conn)
 }
 
 // =============================================================================
@@ -200,7 +286,7 @@ pub fn set_plugin_setting(
     value: Option<serde_json::Value>,
     is_secret: bool,
 ) -> Result<PluginData, diesel::result::Error> {
-    set_plugin_data(conn, plugin_id, "setting", key, value, is_secret)
+    set_plugin_data(conn, plugin_id, "setting", key, value, is_secret, None)
 }
 
 /// Delete a plugin setting
@@ -209,7 +295,7 @@ pub fn delete_plugin_setting(
     plugin_id: i32,
     key: &str,
 ) -> Result<usize, diesel::result::Error> {
-    delete_plugin_data_entry(conn, plugin_id, "setting", key)
+    delete_plugin_data_entry(conn, plugin_id, "setting", key, None)
 }
 
 // =============================================================================
@@ -217,12 +303,15 @@ pub fn delete_plugin_setting(
 // =============================================================================
 
 /// Get a specific storage entry for a plugin
+///
+/// `user_uuid` selects a user's private namespace; None is the global one.
 pub fn get_plugin_storage_entry(
     conn: &mut DbConnection,
     plugin_id: i32,
     key: &str,
+    user_uuid: Option<Uuid>,
 ) -> Result<PluginData, diesel::result::Error> {
-    get_plugin_data_entry(conn, plugin_id, "storage", key)
+    get_plugin_data_entry(conn, plugin_id, "storage", key, user_uuid)
 }
 
 /// Set a plugin storage entry (upsert)
@@ -231,8 +320,9 @@ pub fn set_plugin_storage(
     plugin_id: i32,
     key: String,
     value: Option<serde_json::Value>,
+    user_uuid: Option<Uuid>,
 ) -> Result<PluginData, diesel::result::Error> {
-    set_plugin_data(conn, plugin_id, "storage", key, value, false)
+    set_plugin_data(conn, plugin_id, "storage", key, value, false, user_uuid)
 }
 
 /// Delete a plugin storage entry
@@ -240,8 +330,9 @@ pub fn delete_plugin_storage_entry(
     conn: &mut DbConnection,
     plugin_id: i32,
     key: &str,
+    user_uuid: Option<Uuid>,
 ) -> Result<usize, diesel::result::Error> {
-    delete_plugin_data_entry(conn, plugin_id, "storage", key)
+    delete_plugin_data_entry(conn, plugin_id, "storage", key, user_uuid)
 }
 
 // =============================================================================
@@ -278,11 +369,23 @@ pub fn get_plugin_activity(
     plugin_activity::table
         .filter(plugin_activity::plugin_id.eq(plugin_id))
         .order(plugin_activity::created_at.desc())
+        .then_order_by(plugin_activity::id.desc())
         .limit(limit)
         .offset(offset)
         .load::<PluginActivity>(conn)
 }
 
+/// Count activity entries for a plugin
+pub fn count_plugin_activity(
+    conn: &mut DbConnection,
+    plugin_id: i32,
+) -> Result<i64, diesel::result::Error> {
+    plugin_activity::table
+        .filter(plugin_activity::plugin_id.eq(plugin_id))
+        .count()
+        .get_result(conn)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,6 +402,7 @@ mod tests {
             trust_level: "sandbox".to_string(),
             installed_by: None,
             source: "test".to_string(),
+            verified_publisher: None,
         }
     }
 
@@ -345,6 +449,22 @@ mod tests {
         assert!(get_plugin_by_uuid(&mut conn, plugin.uuid).is_err());
     }
 
+    #[test]
+    fn uninstall_removes_settings_storage_and_activity() {
+        let mut conn = setup_test_connection();
+        let plugin = create_plugin(&mut conn, make_new_plugin("uninstall-plug", true)).unwrap();
+        set_plugin_setting(&mut conn, plugin.id, "token".to_string(), Some(serde_json::json!("x")), true).unwrap();
+        set_plugin_storage(&mut conn, plugin.id, "cursor".to_string(), Some(serde_json::json!(5)), None).unwrap();
+        log_plugin_activity(&mut conn, plugin.id, "installed".to_string(), None, None).unwrap();
+
+        let removed = uninstall_plugin(&mut conn, plugin.uuid).unwrap();
+        assert_eq!(removed, Some(RemovedPluginData { settings: 1, storage: 1, activity: 1 }));
+        assert!(get_plugin_by_uuid(&mut conn, plugin.uuid).is_err());
+        assert!(get_plugin_settings(&mut conn, plugin.id).unwrap().is_empty());
+
+        assert_eq!(uninstall_plugin(&mut conn, plugin.uuid).unwrap(), None);
+    }
+
     #[test]
     fn plugin_data_crud() {
         let mut conn = setup_test_connection();
@@ -358,17 +478,76 @@ mod tests {
             "api_key".to_string(),
             Some(serde_json::json!("secret123")),
             true,
+            None,
         )
         .unwrap();
         assert_eq!(entry.key, "api_key");
 
         // Get data entry
-        let fetched = get_plugin_data_entry(&mut conn, plugin.id, "setting", "api_key").unwrap();
+        let fetched = get_plugin_data_entry(&mut conn, plugin.id, "setting", "api_key", None).unwrap();
         assert_eq!(fetched.value, Some(serde_json::json!("secret123")));
 
         // Delete data entry
-        let deleted = delete_plugin_data_entry(&mut conn, plugin.id, "setting", "api_key").unwrap();
+        let deleted = delete_plugin_data_entry(&mut conn, plugin.id, "setting", "api_key", None).unwrap();
         assert_eq!(deleted, 1);
-        assert!(get_plugin_data_entry(&mut conn, plugin.id, "setting", "api_key").is_err());
+        assert!(get_plugin_data_entry(&mut conn, plugin.id, "setting", "api_key", None).is_err());
+    }
+
+    #[test]
+    fn per_user_storage_does_not_collide() {
+        use crate::models::UserRole;
+        use crate::test_helpers::TestFixtures;
+
+        let mut conn = setup_test_connection();
+        let plugin = create_plugin(&mut conn, make_new_plugin("scoped-plug", true)).unwrap();
+        let alice = TestFixtures::create_user(&mut conn, "Alice", UserRole::User);
+        let bob = TestFixtures::create_user(&mut conn, "Bob", UserRole::User);
+
+        let set = |conn: &mut DbConnection, user: Option<Uuid>, value: &str| {
+            set_plugin_storage(conn, plugin.id, "draft".to_string(), Some(serde_json::json!(value)), user)
+                .unwrap();
+        };
+        set(&mut conn, None, "shared");
+        set(&mut conn, Some(alice.uuid), "alice");
+        set(&mut conn, Some(bob.uuid), "bob");
+        // Upserts stay within the namespace
+        set(&mut conn, Some(alice.uuid), "alice v2");
+
+        let value = |conn: &mut DbConnection, user: Option<Uuid>| {
+            get_plugin_storage_entry(conn, plugin.id, "draft", user).unwrap().value
+        };
+        assert_eq!(value(&mut conn, None), Some(serde_json::json!("shared")));
+        assert_eq!(value(&mut conn, Some(alice.uuid)), Some(serde_json::json!("alice v2")));
+        assert_eq!(value(&mut conn, Some(bob.uuid)), Some(serde_json::json!("bob")));
+
+        // Deleting one user's entry leaves the others
+        assert_eq!(delete_plugin_storage_entry(&mut conn, plugin.id, "draft", Some(bob.uuid)).unwrap(), 1);
+        assert!(get_plugin_storage_entry(&mut conn, plugin.id, "draft", Some(bob.uuid)).is_err());
+        assert_eq!(value(&mut conn, Some(alice.uuid)), Some(serde_json::json!("alice v2")));
+        assert_eq!(value(&mut conn, None), Some(serde_json::json!("shared")));
+    }
+
+    #[test]
+    fn plugin_activity_is_counted_and_paged() {
+        let mut conn = setup_test_connection();
+        let plugin = create_plugin(&mut conn, make_new_plugin("busy-plug", true)).unwrap();
+        let other = create_plugin(&mut conn, make_new_plugin("quiet-plug", true)).unwrap();
+
+        let ids: Vec<i32> = (0..5)
+            .map(|i| log_plugin_activity(&mut conn, plugin.id, format!("action-{i}"), None, None).unwrap().id)
+            .collect();
+        log_plugin_activity(&mut conn, other.id, "elsewhere".to_string(), None, None).unwrap();
+
+        assert_eq!(count_plugin_activity(&mut conn, plugin.id).unwrap(), 5);
+        assert_eq!(count_plugin_activity(&mut conn, other.id).unwrap(), 1);
+
+        // Newest first
+        let page: Vec<i32> = get_plugin_activity(&mut conn, plugin.id, 2, 2)
+            .unwrap()
+            .iter()
+            .map(|a| a.id)
+            .collect();
+        assert_eq!(page, vec![ids[2], ids[1]]);
+        assert!(get_plugin_activity(&mut conn, plugin.id, 10, 5).unwrap().is_empty());
     }
 }
@@ -39,6 +39,35 @@ pub fn revoke_refresh_token(
     .execute(conn)
 }
 
+/// Link a refresh token to the session its login created
+pub fn attach_to_session(
+    conn: &mut DbConnection,
+    token_hash: &str,
+    session_id: i32,
+) -> Result<usize, diesel::result::Error> {
+    diesel::update(
+        refresh_tokens::table.filter(refresh_tokens::token_hash.eq(token_hash))
+    )
+    .set(refresh_tokens::session_id.eq(Some(session_id)))
+    .execute(conn)
+}
+
+/// Revoke a user's refresh tokens that aren't linked to a session. Linked
+/// tokens go with their session when it is revoked.
+pub fn revoke_unlinked_tokens(
+    conn: &mut DbConnection,
+    user_uuid: &uuid::Uuid,
+) -> Result<usize, diesel::result::Error> {
+    diesel::update(
+        refresh_tokens::table
+            .filter(refresh_tokens::user_uuid.eq(user_uuid))
+            .filter(refresh_tokens::session_id.is_null())
+            .filter(refresh_tokens::revoked_at.is_null())
+    )
+    .set(refresh_tokens::revoked_at.eq(Utc::now().naive_utc()))
+    .execute(conn)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,6 +84,7 @@ mod tests {
             token_hash: "testhash123".to_string(),
             user_uuid: user.uuid,
             expires_at: (Utc::now() + Duration::hours(1)).naive_utc(),
+            session_id: None,
         };
 
         let created = create_refresh_token(&mut conn, new_token).unwrap();
@@ -73,6 +103,7 @@ mod tests {
             token_hash: "revokeme".to_string(),
             user_uuid: user.uuid,
             expires_at: (Utc::now() + Duration::hours(1)).naive_utc(),
+            session_id: None,
         };
 
         create_refresh_token(&mut conn, new_token).unwrap();
@@ -81,4 +112,40 @@ mod tests {
         let result = get_valid_refresh_token(&mut conn, "revokeme");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn revoking_a_session_invalidates_its_refresh_tokens() {
+        use crate::repository::active_sessions;
+
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "SessionTokenUser", UserRole::User);
+        let session = active_sessions::create_session(&mut conn, crate::models::NewActiveSession {
+            session_token: "linked_session".to_string(),
+            user_uuid: user.uuid,
+            device_name: None,
+            ip_address: None,
+            user_agent: None,
+            location: None,
+            expires_at: (Utc::now() + Duration::hours(1)).naive_utc(),
+            is_current: false,
+        })
+        .unwrap();
+
+        for (hash, session_id) in [("linked_refresh", Some(session.id)), ("unlinked_refresh", None)] {
+            create_refresh_token(&mut conn, NewRefreshToken {
+                token_hash: hash.to_string(),
+                user_uuid: user.uuid,
+                expires_at: (Utc::now() + Duration::hours(1)).naive_utc(),
+                session_id,
+            })
+            .unwrap();
+        }
+
+        active_sessions::revoke_user_session(&mut conn, &user.uuid, session.id).unwrap();
+        assert!(get_valid_refresh_token(&mut conn, "linked_refresh").is_err());
+        assert!(get_valid_refresh_token(&mut conn, "unlinked_refresh").is_ok());
+
+        assert_eq!(revoke_unlinked_tokens(&mut conn, &user.uuid).unwrap(), 1);
+        assert!(get_valid_refresh_token(&mut conn, "unlinked_refresh").is_err());
+    }
 }
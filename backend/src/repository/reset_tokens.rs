@@ -93,39 +93,79 @@ pub fn invalidate_tokens_by_type(
     .execute(conn)
 }
 
+/// Why a reset token was rejected
+#[derive(Debug, PartialEq, Eq)]
+pub enum TokenError {
+    /// No token with this value
+    Invalid,
+    /// The token was issued for a different purpose
+    WrongType,
+    /// The token was already consumed, or superseded by a newer one
+    AlreadyUsed { user_uuid: Uuid },
+    Expired,
+    Database,
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid => write!(f, "Invalid or expired token"),
+            Self::WrongType => write!(f, "Invalid token type"),
+            Self::AlreadyUsed { .. } => write!(f, "Token has already been used"),
+            Self::Expired => write!(f, "Token has expired"),
+            Self::Database => write!(f, "Failed to mark token as used"),
+        }
+    }
+}
+
 /// Validate and consume a reset token
 /// Returns Ok(user_uuid) if token is valid, unused, and not expired
+///
+/// Consuming is a conditional update, so of two concurrent requests with the
+/// same token only one succeeds; the other gets `AlreadyUsed`.
 pub fn validate_and_consume_token(
     conn: &mut DbConnection,
     raw_token: &str,
     expected_token_type: &str,
-) -> Result<Uuid, String> {
+) -> Result<Uuid, TokenError> {
     // Hash the raw token to look it up
     let token_hash_value = ResetTokenUtils::hash_token(raw_token);
 
     // Find the token
-    let token = find_token_by_hash(conn, &token_hash_value)
-        .map_err(|_| "Invalid or expired token".to_string())?;
+    let token = find_token_by_hash(conn, &token_hash_value).map_err(|_| TokenError::Invalid)?;
 
     // Verify token type
     if token.token_type != expected_token_type {
-        return Err("Invalid token type".to_string());
+        return Err(TokenError::WrongType);
     }
 
     // Check if already used
     if token.is_used {
-        return Err("Token has already been used".to_string());
+        return Err(TokenError::AlreadyUsed { user_uuid: token.user_uuid });
     }
 
     // Check if expired (convert NaiveDateTime to DateTime<Utc>)
     let expires_at_utc = DateTime::<Utc>::from_naive_utc_and_offset(token.expires_at, Utc);
     if ResetTokenUtils::is_token_expired(expires_at_utc) {
-        return Err("Token has expired".to_string());
+        return Err(TokenError::Expired);
     }
 
-    // Mark as used
-    mark_token_as_used(conn, &token_hash_value)
-        .map_err(|_| "Failed to mark token as used".to_string())?;
+    // Mark as used, unless another request got there first
+    let consumed = diesel::update(
+        reset_tokens::table
+            .filter(reset_tokens::token_hash.eq(&token_hash_value))
+            .filter(reset_tokens::is_used.eq(false)),
+    )
+    .set((
+        reset_tokens::used_at.eq(Some(Utc::now())),
+        reset_tokens::is_used.eq(true),
+    ))
+    .execute(conn)
+    .map_err(|_| TokenError::Database)?;
+
+    if consumed == 0 {
+        return Err(TokenError::AlreadyUsed { user_uuid: token.user_uuid });
+    }
 
     Ok(token.user_uuid)
 }
@@ -133,8 +173,54 @@ pub fn validate_and_consume_token(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+    use crate::utils::reset_tokens::TokenType;
     use chrono::Duration;
 
-    // Note: These tests require a test database connection
-    // They are here as documentation of the expected behavior
+    fn issue(conn: &mut DbConnection, user_uuid: Uuid, expires_at: DateTime<Utc>) -> String {
+        let raw = ResetTokenUtils::generate_token();
+        create_reset_token(
+            conn,
+            &ResetTokenUtils::hash_token(&raw),
+            user_uuid,
+            TokenType::PasswordReset.as_str(),
+            None,
+            None,
+            expires_at,
+            None,
+        )
+        .unwrap();
+        raw
+    }
+
+    #[test]
+    fn tokens_are_single_use() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "singleuse", UserRole::User);
+        let raw = issue(&mut conn, user.uuid, Utc::now() + Duration::hours(1));
+        let kind = TokenType::PasswordReset.as_str();
+
+        assert_eq!(validate_and_consume_token(&mut conn, &raw, kind), Ok(user.uuid));
+        assert_eq!(
+            validate_and_consume_token(&mut conn, &raw, kind),
+            Err(TokenError::AlreadyUsed { user_uuid: user.uuid })
+        );
+    }
+
+    #[test]
+    fn expired_wrong_type_and_unknown_tokens_are_rejected() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "expiredtoken", UserRole::User);
+        let expired = issue(&mut conn, user.uuid, Utc::now() - Duration::minutes(1));
+        let valid = issue(&mut conn, user.uuid, Utc::now() + Duration::hours(1));
+
+        let reset = TokenType::PasswordReset.as_str();
+        assert_eq!(validate_and_consume_token(&mut conn, &expired, reset), Err(TokenError::Expired));
+        assert_eq!(
+            validate_and_consume_token(&mut conn, &valid, TokenType::MfaReset.as_str()),
+            Err(TokenError::WrongType)
+        );
+        assert_eq!(validate_and_consume_token(&mut conn, "unknown", reset), Err(TokenError::Invalid));
+    }
 }
@@ -0,0 +1,62 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::db::DbConnection;
+use crate::models::{NewSecurityEvent, SecurityEvent};
+use crate::schema::security_events;
+
+/// Record a security event
+pub fn create_security_event(
+    conn: &mut DbConnection,
+    new_event: NewSecurityEvent,
+) -> QueryResult<SecurityEvent> {
+    diesel::insert_into(security_events::table)
+        .values(&new_event)
+        .get_result(conn)
+}
+
+/// Get the most recent security events for a user
+pub fn get_user_security_events(
+    conn: &mut DbConnection,
+    user_uuid: Uuid,
+    limit: i64,
+) -> QueryResult<Vec<SecurityEvent>> {
+    security_events::table
+        .filter(security_events::user_uuid.eq(user_uuid))
+        .order(security_events::created_at.desc())
+        .limit(limit)
+        .load(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SecurityEventType, UserRole};
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+    #[test]
+    fn create_and_list_security_events() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "Event User", UserRole::User);
+
+        let event = create_security_event(
+            &mut conn,
+            NewSecurityEvent {
+                user_uuid: user.uuid,
+                event_type: SecurityEventType::SecretTampered.to_string(),
+                ip_address: None,
+                user_agent: None,
+                location: None,
+                details: Some(serde_json::json!({"plugin": "example"})),
+                severity: "critical".to_string(),
+                session_id: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(event.event_type, "secret_tampered");
+
+        let events = get_user_security_events(&mut conn, user.uuid, 10).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, event.id);
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::QueryResult;
+use uuid::Uuid;
+
+use crate::db::DbConnection;
+use crate::models::*;
+use crate::schema::*;
+
+// ============================================================================
+// SLA Targets
+// ============================================================================
+
+/// All configured targets
+pub fn list_targets(conn: &mut DbConnection) -> QueryResult<Vec<SlaTarget>> {
+    sla_targets::table.into_boxed().load(conn)
+}
+
+/// Targets for one priority, if configured
+pub fn get_target(conn: &mut DbConnection, priority: TicketPriority) -> QueryResult<Option<SlaTarget>> {
+    // Boxed because the TicketPriority SQL type has no QueryId
+    sla_targets::table
+        .into_boxed()
+        .filter(sla_targets::priority.eq(priority))
+        .first(conn)
+        .optional()
+}
+
+/// Create or replace the targets for a priority
+pub fn upsert_target(conn: &mut DbConnection, target: &NewSlaTarget) -> QueryResult<SlaTarget> {
+    diesel::insert_into(sla_targets::table)
+        .values(target)
+        .on_conflict(sla_targets::priority)
+        .do_update()
+        .set((target, sla_targets::updated_at.eq(diesel::dsl::now)))
+        .get_result(conn)
+}
+
+// ============================================================================
+// Ticket SLA Status
+// ============================================================================
+
+/// A ticket's SLA status at `now`, or `None` when its priority has no targets
+pub fn ticket_sla_status(conn: &mut DbConnection, ticket: &Ticket, now: NaiveDateTime) -> QueryResult<Option<TicketSla>> {
+    Ok(get_target(conn, ticket.priority)?.map(|target| TicketSla::evaluate(ticket, &target, now)))
+}
+
+/// Record the first technician response on a ticket. Later responses don't
+/// move it; returns false if one was already recorded.
+pub fn record_first_response(conn: &mut DbConnection, ticket_id: i32, responded_at: NaiveDateTime) -> QueryResult<bool> {
+    let updated = diesel::update(
+        tickets::table
+            .filter(tickets::id.eq(ticket_id))
+            .filter(tickets::first_response_at.is_null()),
+    )
+    .set(tickets::first_response_at.eq(responded_at))
+    .execute(conn)?;
+    Ok(updated > 0)
+}
+
+/// Unclosed tickets whose priority has SLA targets, with those targets
+pub fn open_tickets_with_targets(conn: &mut DbConnection) -> QueryResult<Vec<(Ticket, SlaTarget)>> {
+    load_open_with_targets(conn, None)
+}
+
+/// Like [`open_tickets_with_targets`], limited to tickets assigned to one user
+pub fn open_assigned_tickets_with_targets(
+    conn: &mut DbConnection,
+    assignee_uuid: Uuid,
+) -> QueryResult<Vec<(Ticket, SlaTarget)>> {
+    load_open_with_targets(conn, Some(assignee_uuid))
+}
+
+fn load_open_with_targets(
+    conn: &mut DbConnection,
+    assignee_uuid: Option<Uuid>,
+) -> QueryResult<Vec<(Ticket, SlaTarget)>> {
+    let targets = list_targets(conn)?;
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let priorities: Vec<TicketPriority> = targets.iter().map(|t| t.priority).collect();
+    let mut query = tickets::table
+        .into_boxed()
+        .filter(tickets::status.ne(TicketStatus::Closed))
+        .filter(tickets::priority.eq_any(priorities));
+    if let Some(assignee) = assignee_uuid {
+        query = query.filter(tickets::assignee_uuid.eq(assignee));
+    }
+    let tickets: Vec<Ticket> = query.order(tickets::id.asc()).load(conn)?;
+
+    Ok(tickets
+        .into_iter()
+        .filter_map(|ticket| {
+            let target = targets.iter().find(|t| t.priority == ticket.priority)?.clone();
+            Some((ticket, target))
+        })
+        .collect())
+}
+
+/// Record that a ticket breached a metric. Returns false if that breach was
+/// already recorded, so each breach is acted on once.
+pub fn record_breach(conn: &mut DbConnection, ticket_id: i32, metric: SlaMetric) -> QueryResult<bool> {
+    let inserted = diesel::insert_into(ticket_sla_breaches::table)
+        .values((
+            ticket_sla_breaches::ticket_id.eq(ticket_id),
+            ticket_sla_breaches::metric.eq(metric.as_str()),
+        ))
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    Ok(inserted > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+    use chrono::Duration;
+
+    fn set_targets(conn: &mut DbConnection, priority: TicketPriority, first_response: Option<i32>, resolution: Option<i32>) {
+        upsert_target(conn, &NewSlaTarget {
+            priority,
+            first_response_minutes: first_response,
+            resolution_minutes: resolution,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn first_response_met_vs_missed() {
+        let mut conn = setup_test_connection();
+        set_targets(&mut conn, TicketPriority::Medium, Some(60), Some(480));
+
+        let created = TestFixtures::create_ticket(&mut conn, "SLA ticket", None, None);
+        let start = created.created_at;
+
+        // Answered after 30 minutes: met, and stays met later on
+        assert!(record_first_response(&mut conn, created.id, start + Duration::minutes(30)).unwrap());
+        // A later response doesn't move the first one
+        assert!(!record_first_response(&mut conn, created.id, start + Duration::minutes(90)).unwrap());
+        let ticket = crate::repository::get_ticket_by_id(&mut conn, created.id).unwrap();
+        let sla = ticket_sla_status(&mut conn, &ticket, start + Duration::hours(2)).unwrap().unwrap();
+        let first_response = sla.first_response.unwrap();
+        assert_eq!(first_response.state, SlaState::Met);
+        assert_eq!(first_response.due_at, start + Duration::minutes(60));
+        assert_eq!(sla.resolution.unwrap().state, SlaState::OnTrack);
+
+        // Another ticket nobody answered in time
+        let missed = TestFixtures::create_ticket(&mut conn, "Missed SLA ticket", None, None);
+        let start = missed.created_at;
+        let sla = ticket_sla_status(&mut conn, &missed, start + Duration::minutes(59)).unwrap().unwrap();
+        assert_eq!(sla.first_response.as_ref().unwrap().state, SlaState::OnTrack);
+        let sla = ticket_sla_status(&mut conn, &missed, start + Duration::minutes(61)).unwrap().unwrap();
+        assert_eq!(sla.first_response.as_ref().unwrap().state, SlaState::Breached);
+        assert_eq!(sla.breached(), vec![SlaMetric::FirstResponse]);
+
+        // Answering late keeps it breached
+        record_first_response(&mut conn, missed.id, start + Duration::minutes(75)).unwrap();
+        let missed = crate::repository::get_ticket_by_id(&mut conn, missed.id).unwrap();
+        let sla = ticket_sla_status(&mut conn, &missed, start + Duration::minutes(80)).unwrap().unwrap();
+        assert_eq!(sla.first_response.unwrap().state, SlaState::Breached);
+    }
+
+    #[test]
+    fn no_targets_means_no_status_and_breaches_are_recorded_once() {
+        let mut conn = setup_test_connection();
+        diesel::delete(sla_targets::table).execute(&mut conn).unwrap();
+        let ticket = TestFixtures::create_ticket(&mut conn, "Untracked ticket", None, None);
+        assert_eq!(ticket_sla_status(&mut conn, &ticket, ticket.created_at).unwrap(), None);
+
+        assert!(record_breach(&mut conn, ticket.id, SlaMetric::Resolution).unwrap());
+        assert!(!record_breach(&mut conn, ticket.id, SlaMetric::Resolution).unwrap());
+        assert!(record_breach(&mut conn, ticket.id, SlaMetric::FirstResponse).unwrap());
+    }
+}
@@ -3,6 +3,9 @@
 //! Provides a fluent, type-safe API for building ticket queries with
 //! automatic permission filtering.
 
+use std::collections::HashSet;
+
+use chrono_tz::Tz;
 use diesel::prelude::*;
 use uuid::Uuid;
 
@@ -10,6 +13,7 @@ use crate::db::DbConnection;
 use crate::extractors::AuthContext;
 use crate::models::{Ticket, TicketListItem, TicketPriority, TicketStatus, UserInfoWithAvatar};
 use crate::schema::tickets;
+use crate::utils::timezone;
 
 /// Parse comma-separated status filter into enums
 fn parse_status_filter(status_str: &str) -> Vec<TicketStatus> {
@@ -49,6 +53,8 @@ pub struct TicketQuery {
     assignee_uuid: Option<Uuid>,
     assignee_unassigned: bool,
     requester_uuid: Option<Uuid>,
+    /// (field key, value) pairs a ticket must all have
+    custom_fields: Vec<(String, String)>,
 
     // Date filters
     created_after: Option<chrono::NaiveDateTime>,
@@ -57,6 +63,8 @@ pub struct TicketQuery {
     modified_before: Option<chrono::NaiveDateTime>,
     closed_after: Option<chrono::NaiveDateTime>,
     closed_before: Option<chrono::NaiveDateTime>,
+    /// Zone date filters are interpreted in; `None` is UTC
+    timezone: Option<Tz>,
 
     // Pagination & sorting
     page: i64,
@@ -138,17 +146,48 @@ impl TicketQuery {
         self
     }
 
+    /// Filter by custom field values, as comma-separated `key:value` pairs
+    ///
+    /// Values are matched against the stored canonical form (see
+    /// `services::custom_fields`), e.g. `2026-03-31` for dates.
+    pub fn custom_fields(mut self, filter: Option<String>) -> Self {
+        if let Some(filter) = filter {
+            self.custom_fields = filter
+                .split(',')
+                .filter_map(|pair| pair.split_once(':'))
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+                .collect();
+        }
+        self
+    }
+
+    /// Zone date filters are interpreted in (default UTC). Call before the
+    /// date filters, which convert their dates to UTC as they're added.
+    pub fn timezone(mut self, tz: Tz) -> Self {
+        self.timezone = Some(tz);
+        self
+    }
+
+    /// UTC start of the `%Y-%m-%d` day in the query's zone
+    fn day_start(&self, date_str: &str) -> Option<chrono::NaiveDateTime> {
+        let date = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok()?;
+        Some(timezone::start_of_day_utc(date, self.timezone.unwrap_or(Tz::UTC)))
+    }
+
+    /// UTC end (last second) of the `%Y-%m-%d` day in the query's zone
+    fn day_end(&self, date_str: &str) -> Option<chrono::NaiveDateTime> {
+        let date = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok()?;
+        Some(timezone::end_of_day_utc(date, self.timezone.unwrap_or(Tz::UTC)))
+    }
+
     /// Filter by creation date range
     pub fn created_between(mut self, after: Option<String>, before: Option<String>) -> Self {
-        if let Some(date_str) = after {
-            if let Ok(date) = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
-                self.created_after = date.and_hms_opt(0, 0, 0);
-            }
+        if let Some(start) = after.and_then(|d| self.day_start(&d)) {
+            self.created_after = Some(start);
         }
-        if let Some(date_str) = before {
-            if let Ok(date) = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
-                self.created_before = date.and_hms_opt(23, 59, 59);
-            }
+        if let Some(end) = before.and_then(|d| self.day_end(&d)) {
+            self.created_before = Some(end);
         }
         self
     }
@@ -156,9 +195,9 @@ impl TicketQuery {
     /// Filter by creation date (exact day)
     pub fn created_on(mut self, date: Option<String>) -> Self {
         if let Some(date_str) = date {
-            if let Ok(date) = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
-                self.created_after = date.and_hms_opt(0, 0, 0);
-                self.created_before = date.and_hms_opt(23, 59, 59);
+            if let (Some(start), Some(end)) = (self.day_start(&date_str), self.day_end(&date_str)) {
+                self.created_after = Some(start);
+                self.created_before = Some(end);
             }
         }
         self
@@ -166,15 +205,11 @@ impl TicketQuery {
 
     /// Filter by modification date range
     pub fn modified_between(mut self, after: Option<String>, before: Option<String>) -> Self {
-        if let Some(date_str) = after {
-            if let Ok(date) = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
-                self.modified_after = date.and_hms_opt(0, 0, 0);
-            }
+        if let Some(start) = after.and_then(|d| self.day_start(&d)) {
+            self.modified_after = Some(start);
         }
-        if let Some(date_str) = before {
-            if let Ok(date) = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
-                self.modified_before = date.and_hms_opt(23, 59, 59);
-            }
+        if let Some(end) = before.and_then(|d| self.day_end(&d)) {
+            self.modified_before = Some(end);
         }
         self
     }
@@ -182,9 +217,9 @@ impl TicketQuery {
     /// Filter by modification date (exact day)
     pub fn modified_on(mut self, date: Option<String>) -> Self {
         if let Some(date_str) = date {
-            if let Ok(date) = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
-                self.modified_after = date.and_hms_opt(0, 0, 0);
-                self.modified_before = date.and_hms_opt(23, 59, 59);
+            if let (Some(start), Some(end)) = (self.day_start(&date_str), self.day_end(&date_str)) {
+                self.modified_after = Some(start);
+                self.modified_before = Some(end);
             }
         }
         self
@@ -192,15 +227,11 @@ impl TicketQuery {
 
     /// Filter by closed date range
     pub fn closed_between(mut self, after: Option<String>, before: Option<String>) -> Self {
-        if let Some(date_str) = after {
-            if let Ok(date) = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
-                self.closed_after = date.and_hms_opt(0, 0, 0);
-            }
+        if let Some(start) = after.and_then(|d| self.day_start(&d)) {
+            self.closed_after = Some(start);
         }
-        if let Some(date_str) = before {
-            if let Ok(date) = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
-                self.closed_before = date.and_hms_opt(23, 59, 59);
-            }
+        if let Some(end) = before.and_then(|d| self.day_end(&d)) {
+            self.closed_before = Some(end);
         }
         self
     }
@@ -208,9 +239,9 @@ impl TicketQuery {
     /// Filter by closed date (exact day)
     pub fn closed_on(mut self, date: Option<String>) -> Self {
         if let Some(date_str) = date {
-            if let Ok(date) = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
-                self.closed_after = date.and_hms_opt(0, 0, 0);
-                self.closed_before = date.and_hms_opt(23, 59, 59);
+            if let (Some(start), Some(end)) = (self.day_start(&date_str), self.day_end(&date_str)) {
+                self.closed_after = Some(start);
+                self.closed_before = Some(end);
             }
         }
         self
@@ -347,6 +378,18 @@ impl TicketQuery {
             query = query.filter(tickets::requester_uuid.eq(Some(requester)));
         }
 
+        // Custom field filters
+        for (key, value) in &self.custom_fields {
+            use crate::schema::{custom_field_definitions, ticket_custom_field_values};
+
+            let matching = ticket_custom_field_values::table
+                .inner_join(custom_field_definitions::table)
+                .filter(custom_field_definitions::key.eq(key.clone()))
+                .filter(ticket_custom_field_values::value.eq(value.clone()))
+                .select(ticket_custom_field_values::ticket_id);
+            query = query.filter(tickets::id.eq_any(matching));
+        }
+
         // Date filters
         if let Some(dt) = self.created_after {
             query = query.filter(tickets::created_at.ge(dt));
@@ -443,6 +486,105 @@ impl TicketQuery {
         })
     }
 
+    /// Number of tickets matching the query, ignoring pagination
+    pub fn count(mut self, conn: &mut DbConnection) -> Result<i64, diesel::result::Error> {
+        self.resolve_visibility(conn);
+        self.apply_filters(tickets::table.into_boxed()).count().get_result(conn)
+    }
+
+    /// Of the given ticket IDs, return the ones that pass the query's filters
+    pub fn matching_ids(
+        mut self,
+        conn: &mut DbConnection,
+        ids: &[i32],
+    ) -> Result<HashSet<i32>, diesel::result::Error> {
+        if ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        self.resolve_visibility(conn);
+        let matching: Vec<i32> = self
+            .apply_filters(tickets::table.into_boxed())
+            .filter(tickets::id.eq_any(ids))
+            .select(tickets::id)
+            .load(conn)?;
+
+        Ok(matching.into_iter().collect())
+    }
+
+    /// Turn the query into an unpaginated export. Pagination and sorting are
+    /// ignored: exports are read in ID order, one batch at a time.
+    pub fn into_export(mut self, conn: &mut DbConnection) -> TicketExport {
+        self.resolve_visibility(conn);
+        TicketExport { query: self }
+    }
+}
+
+/// A filtered ticket list read in ID-ordered batches, so large exports
+/// never hold every row in memory
+pub struct TicketExport {
+    query: TicketQuery,
+}
+
+impl TicketExport {
+    /// Up to `limit` rows with an ID greater than `after_id`
+    pub fn next_batch(
+        &self,
+        conn: &mut DbConnection,
+        after_id: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<TicketExportRow>, diesel::result::Error> {
+        use crate::schema::users;
+
+        let mut query = self.query.apply_filters(tickets::table.into_boxed());
+        if let Some(after_id) = after_id {
+            query = query.filter(tickets::id.gt(after_id));
+        }
+        let tickets: Vec<Ticket> = query.order(tickets::id.asc()).limit(limit).load(conn)?;
+
+        // One lookup for every user referenced in the batch
+        let mut user_uuids: Vec<Uuid> = tickets
+            .iter()
+            .flat_map(|t| [t.requester_uuid, t.assignee_uuid])
+            .flatten()
+            .collect();
+        user_uuids.sort();
+        user_uuids.dedup();
+        let names: std::collections::HashMap<Uuid, String> = users::table
+            .filter(users::uuid.eq_any(&user_uuids))
+            .select((users::uuid, users::name))
+            .load::<(Uuid, String)>(conn)?
+            .into_iter()
+            .collect();
+        let name_of = |uuid: Option<Uuid>| uuid.and_then(|u| names.get(&u).cloned());
+
+        Ok(tickets
+            .into_iter()
+            .map(|ticket| TicketExportRow {
+                id: ticket.id,
+                requester: name_of(ticket.requester_uuid),
+                assignee: name_of(ticket.assignee_uuid),
+                title: ticket.title,
+                status: ticket.status,
+                priority: ticket.priority,
+                created_at: ticket.created_at,
+                closed_at: ticket.closed_at,
+            })
+            .collect())
+    }
+}
+
+/// One exported ticket, with requester and assignee resolved to names
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TicketExportRow {
+    pub id: i32,
+    pub title: String,
+    pub status: TicketStatus,
+    pub priority: TicketPriority,
+    pub requester: Option<String>,
+    pub assignee: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub closed_at: Option<chrono::NaiveDateTime>,
 }
 
 /// Paginated query result
@@ -457,6 +599,19 @@ pub struct PaginatedResult<T> {
     pub total_pages: i64,
 }
 
+impl<T> PaginatedResult<T> {
+    /// Page info for a limit/offset query (`limit` must be positive)
+    pub fn from_offset(data: Vec<T>, total: i64, limit: i64, offset: i64) -> Self {
+        Self {
+            data,
+            total,
+            page: offset / limit + 1,
+            page_size: limit,
+            total_pages: (total + limit - 1) / limit,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -464,6 +619,45 @@ mod tests {
     use crate::models::UserRole;
     use crate::test_helpers::{setup_test_connection, TestFixtures};
 
+    #[test]
+    fn offset_pages_are_numbered_from_one() {
+        let result = PaginatedResult::from_offset(vec![1, 2], 5, 2, 2);
+        assert_eq!((result.page, result.page_size, result.total_pages), (2, 2, 3));
+
+        let empty = PaginatedResult::<i32>::from_offset(vec![], 0, 50, 0);
+        assert_eq!((empty.page, empty.total_pages), (1, 0));
+    }
+
+    #[test]
+    fn date_filters_use_the_query_timezone() {
+        let day = |tz: Option<Tz>| {
+            let query = match tz {
+                Some(tz) => TicketQuery::new().timezone(tz),
+                None => TicketQuery::new(),
+            };
+            let query = query.created_on(Some("2026-03-04".to_string()));
+            (query.created_after.unwrap().to_string(), query.created_before.unwrap().to_string())
+        };
+
+        let utc = ("2026-03-04 00:00:00".to_string(), "2026-03-04 23:59:59".to_string());
+        assert_eq!(day(None), utc);
+        assert_eq!(day(Some(Tz::UTC)), utc);
+        assert_eq!(
+            day(Some(chrono_tz::Europe::Berlin)),
+            ("2026-03-03 23:00:00".to_string(), "2026-03-04 22:59:59".to_string())
+        );
+        assert_eq!(
+            day(Some(chrono_tz::America::Los_Angeles)),
+            ("2026-03-04 08:00:00".to_string(), "2026-03-05 07:59:59".to_string())
+        );
+
+        let range = TicketQuery::new()
+            .timezone(chrono_tz::America::Los_Angeles)
+            .closed_between(Some("2026-03-01".to_string()), Some("not a date".to_string()));
+        assert_eq!(range.closed_after.unwrap().to_string(), "2026-03-01 08:00:00");
+        assert!(range.closed_before.is_none());
+    }
+
     #[test]
     fn resolve_visibility_computes_correct_ids() {
         let mut conn = setup_test_connection();
@@ -592,4 +786,86 @@ mod tests {
         }
         assert!(result.total >= 1);
     }
+
+    #[test]
+    fn custom_field_filters_match_all_pairs() {
+        use crate::models::NewCustomFieldDefinition;
+        use crate::repository::custom_fields;
+        use std::collections::HashMap;
+
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "cf_user", UserRole::Admin);
+        for (key, field_type, options) in [
+            ("cf_system", "select", serde_json::json!(["SAP", "Workday"])),
+            ("cf_urgent", "checkbox", serde_json::json!([])),
+        ] {
+            custom_fields::create_definition(
+                &mut conn,
+                NewCustomFieldDefinition {
+                    key: key.to_string(),
+                    label: key.to_string(),
+                    field_type: field_type.to_string(),
+                    options,
+                    category_id: None,
+                    display_order: 0,
+                    created_by: None,
+                },
+            )
+            .unwrap();
+        }
+
+        let mut ticket_with = |title: &str, system: &str, urgent: &str| {
+            let ticket = TestFixtures::create_ticket(&mut conn, title, Some(user.uuid), None);
+            let values = HashMap::from([
+                ("cf_system".to_string(), Some(system.to_string())),
+                ("cf_urgent".to_string(), Some(urgent.to_string())),
+            ]);
+            custom_fields::set_values(&mut conn, ticket.id, None, &values, None).unwrap();
+            ticket.id
+        };
+        let urgent_sap = ticket_with("SAP down", "SAP", "true");
+        ticket_with("SAP slow", "SAP", "false");
+        ticket_with("Workday down", "Workday", "true");
+
+        let auth = AuthContext::test_context(user.uuid, UserRole::Admin, vec![]);
+        let result = TicketQuery::new()
+            .visible_to(&auth)
+            .custom_fields(Some("cf_system:SAP, cf_urgent:true".into()))
+            .paginate(1, 50)
+            .execute_with_users(&mut conn)
+            .unwrap();
+
+        let ids: Vec<i32> = result.data.iter().map(|item| item.ticket.id).collect();
+        assert_eq!(ids, vec![urgent_sap]);
+    }
+
+    #[test]
+    fn export_batches_follow_id_order_and_visibility() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "exp_user", UserRole::User);
+        let other = TestFixtures::create_user(&mut conn, "exp_other", UserRole::User);
+        let secret_cat = TestFixtures::create_category(&mut conn, "ExpSecret");
+        let sg = TestFixtures::create_group(&mut conn, "exp_sg");
+        TestFixtures::set_category_visibility(&mut conn, secret_cat.id, &[sg.id]);
+
+        let first = TestFixtures::create_ticket(&mut conn, "Batchexport 1", Some(user.uuid), None);
+        TestFixtures::create_ticket(&mut conn, "Batchexport hidden", Some(other.uuid), Some(secret_cat.id));
+        let second = TestFixtures::create_ticket(&mut conn, "Batchexport 2", Some(user.uuid), None);
+        let third = TestFixtures::create_ticket(&mut conn, "Batchexport 3", Some(user.uuid), None);
+
+        let auth = AuthContext::test_context(user.uuid, UserRole::User, vec![]);
+        let export = TicketQuery::new()
+            .visible_to(&auth)
+            .search(Some("Batchexport".into()))
+            .into_export(&mut conn);
+
+        let batch = export.next_batch(&mut conn, None, 2).unwrap();
+        assert_eq!(batch.iter().map(|r| r.id).collect::<Vec<_>>(), vec![first.id, second.id]);
+        assert_eq!(batch[0].requester.as_deref(), Some(user.name.as_str()));
+        assert_eq!(batch[0].assignee, None);
+
+        let batch = export.next_batch(&mut conn, Some(second.id), 2).unwrap();
+        assert_eq!(batch.iter().map(|r| r.id).collect::<Vec<_>>(), vec![third.id]);
+        assert!(export.next_batch(&mut conn, Some(third.id), 2).unwrap().is_empty());
+    }
 }
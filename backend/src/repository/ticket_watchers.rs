@@ -0,0 +1,120 @@
+use diesel::prelude::*;
+use diesel::QueryResult;
+use uuid::Uuid;
+
+use crate::db::DbConnection;
+use crate::models::*;
+use crate::schema::*;
+
+// ============================================================================
+// Ticket Watcher Operations
+// ============================================================================
+
+/// Start watching a ticket. Returns false if the user was already watching.
+pub fn add_watcher(conn: &mut DbConnection, ticket_id: i32, user_uuid: Uuid) -> QueryResult<bool> {
+    let inserted = diesel::insert_into(ticket_watchers::table)
+        .values(&NewTicketWatcher { ticket_id, user_uuid })
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    Ok(inserted > 0)
+}
+
+/// Stop watching a ticket
+pub fn remove_watcher(conn: &mut DbConnection, ticket_id: i32, user_uuid: Uuid) -> QueryResult<usize> {
+    diesel::delete(
+        ticket_watchers::table
+            .filter(ticket_watchers::ticket_id.eq(ticket_id))
+            .filter(ticket_watchers::user_uuid.eq(user_uuid)),
+    )
+    .execute(conn)
+}
+
+/// Users watching a ticket, in the order they started watching
+pub fn list_watchers(conn: &mut DbConnection, ticket_id: i32) -> QueryResult<Vec<User>> {
+    ticket_watchers::table
+        .inner_join(users::table)
+        .filter(ticket_watchers::ticket_id.eq(ticket_id))
+        .order((ticket_watchers::created_at.asc(), users::name.asc()))
+        .select(users::all_columns)
+        .load(conn)
+}
+
+/// Tickets a user watches, most recently updated first
+pub fn watched_tickets(conn: &mut DbConnection, user_uuid: Uuid, limit: i64) -> QueryResult<Vec<Ticket>> {
+    ticket_watchers::table
+        .inner_join(tickets::table)
+        .filter(ticket_watchers::user_uuid.eq(user_uuid))
+        .order((tickets::updated_at.desc(), tickets::id.desc()))
+        .limit(limit)
+        .select(tickets::all_columns)
+        .load(conn)
+}
+
+/// Everyone to notify about activity on a ticket: the requester, the
+/// assignee and all watchers, without duplicates and excluding `actor`
+pub fn notification_recipients(
+    conn: &mut DbConnection,
+    ticket: &Ticket,
+    actor: Option<Uuid>,
+) -> QueryResult<Vec<Uuid>> {
+    let watchers: Vec<Uuid> = ticket_watchers::table
+        .filter(ticket_watchers::ticket_id.eq(ticket.id))
+        .order(ticket_watchers::created_at.asc())
+        .select(ticket_watchers::user_uuid)
+        .load(conn)?;
+
+    let mut recipients = Vec::new();
+    for user_uuid in [ticket.requester_uuid, ticket.assignee_uuid]
+        .into_iter()
+        .flatten()
+        .chain(watchers)
+    {
+        if Some(user_uuid) != actor && !recipients.contains(&user_uuid) {
+            recipients.push(user_uuid);
+        }
+    }
+    Ok(recipients)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+    #[test]
+    fn add_list_and_remove_watchers() {
+        let mut conn = setup_test_connection();
+        let requester = TestFixtures::create_user(&mut conn, "watchrequester", UserRole::User);
+        let manager = TestFixtures::create_user(&mut conn, "watchmanager", UserRole::Technician);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Watched Ticket", Some(requester.uuid), None);
+
+        assert!(add_watcher(&mut conn, ticket.id, manager.uuid).unwrap());
+        // Watching twice is a no-op
+        assert!(!add_watcher(&mut conn, ticket.id, manager.uuid).unwrap());
+
+        let watchers = list_watchers(&mut conn, ticket.id).unwrap();
+        assert_eq!(watchers.iter().map(|u| u.uuid).collect::<Vec<_>>(), vec![manager.uuid]);
+
+        assert_eq!(remove_watcher(&mut conn, ticket.id, manager.uuid).unwrap(), 1);
+        assert!(list_watchers(&mut conn, ticket.id).unwrap().is_empty());
+        assert_eq!(remove_watcher(&mut conn, ticket.id, manager.uuid).unwrap(), 0);
+    }
+
+    #[test]
+    fn recipients_include_watchers_without_duplicates_or_actor() {
+        let mut conn = setup_test_connection();
+        let requester = TestFixtures::create_user(&mut conn, "reciprequester", UserRole::User);
+        let manager = TestFixtures::create_user(&mut conn, "recipmanager", UserRole::Technician);
+        let tech = TestFixtures::create_user(&mut conn, "reciptech", UserRole::Technician);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Recipient Ticket", Some(requester.uuid), None);
+
+        add_watcher(&mut conn, ticket.id, manager.uuid).unwrap();
+        add_watcher(&mut conn, ticket.id, requester.uuid).unwrap();
+        add_watcher(&mut conn, ticket.id, tech.uuid).unwrap();
+
+        assert_eq!(
+            notification_recipients(&mut conn, &ticket, Some(tech.uuid)).unwrap(),
+            vec![requester.uuid, manager.uuid]
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use diesel::prelude::*;
+use diesel::QueryResult;
+
+use crate::db::DbConnection;
+use crate::models::*;
+use crate::schema::*;
+
+// ============================================================================
+// Ticket Worklog Operations
+// ============================================================================
+
+/// Record time spent on a ticket
+pub fn add_worklog(conn: &mut DbConnection, new_worklog: NewTicketWorklog) -> QueryResult<TicketWorklog> {
+    diesel::insert_into(ticket_worklogs::table)
+        .values(&new_worklog)
+        .get_result(conn)
+}
+
+/// A single worklog entry on a ticket
+pub fn get_worklog(conn: &mut DbConnection, ticket_id: i32, worklog_id: i32) -> QueryResult<TicketWorklog> {
+    ticket_worklogs::table
+        .filter(ticket_worklogs::ticket_id.eq(ticket_id))
+        .find(worklog_id)
+        .first(conn)
+}
+
+/// Worklog entries on a ticket with who logged them, most recent work first
+pub fn list_worklogs(conn: &mut DbConnection, ticket_id: i32) -> QueryResult<Vec<TicketWorklogWithUser>> {
+    let rows: Vec<(TicketWorklog, User)> = ticket_worklogs::table
+        .inner_join(users::table)
+        .filter(ticket_worklogs::ticket_id.eq(ticket_id))
+        .order((ticket_worklogs::logged_at.desc(), ticket_worklogs::id.desc()))
+        .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(worklog, user)| TicketWorklogWithUser {
+            worklog,
+            user: UserInfo::from(user),
+        })
+        .collect())
+}
+
+/// Delete a worklog entry
+pub fn delete_worklog(conn: &mut DbConnection, worklog_id: i32) -> QueryResult<usize> {
+    diesel::delete(ticket_worklogs::table.find(worklog_id)).execute(conn)
+}
+
+/// Total minutes logged against a ticket
+pub fn total_minutes_for_ticket(conn: &mut DbConnection, ticket_id: i32) -> QueryResult<i64> {
+    let total: Option<i64> = ticket_worklogs::table
+        .filter(ticket_worklogs::ticket_id.eq(ticket_id))
+        .select(diesel::dsl::sum(ticket_worklogs::minutes))
+        .first(conn)?;
+    Ok(total.unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+    use chrono::{Duration, Utc};
+
+    fn worklog(ticket_id: i32, user: &User, minutes: i32, hours_ago: i64) -> NewTicketWorklog {
+        NewTicketWorklog {
+            ticket_id,
+            user_uuid: user.uuid,
+            minutes,
+            note: Some(format!("{minutes} minutes")),
+            logged_at: Utc::now().naive_utc() - Duration::hours(hours_ago),
+        }
+    }
+
+    #[test]
+    fn add_and_list_worklogs() {
+        let mut conn = setup_test_connection();
+        let tech = TestFixtures::create_user(&mut conn, "worklogtech", UserRole::Technician);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Replace keyboard", None, None);
+
+        let earlier = add_worklog(&mut conn, worklog(ticket.id, &tech, 30, 5)).unwrap();
+        let later = add_worklog(&mut conn, worklog(ticket.id, &tech, 15, 1)).unwrap();
+        assert_eq!(earlier.minutes, 30);
+        assert_eq!(earlier.note.as_deref(), Some("30 minutes"));
+
+        let entries = list_worklogs(&mut conn, ticket.id).unwrap();
+        let ids: Vec<i32> = entries.iter().map(|e| e.worklog.id).collect();
+        assert_eq!(ids, vec![later.id, earlier.id]);
+        assert_eq!(entries[0].user.uuid, tech.uuid);
+
+        // Entries are scoped to their ticket
+        let other = TestFixtures::create_ticket(&mut conn, "Other ticket", None, None);
+        assert!(get_worklog(&mut conn, other.id, earlier.id).is_err());
+
+        assert_eq!(delete_worklog(&mut conn, earlier.id).unwrap(), 1);
+        assert_eq!(list_worklogs(&mut conn, ticket.id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn total_minutes_is_summed_per_ticket() {
+        let mut conn = setup_test_connection();
+        let tech = TestFixtures::create_user(&mut conn, "worklogtotals", UserRole::Technician);
+        let admin = TestFixtures::create_user(&mut conn, "worklogadmin", UserRole::Admin);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Migrate mailbox", None, None);
+        let other = TestFixtures::create_ticket(&mut conn, "Reset password", None, None);
+
+        assert_eq!(total_minutes_for_ticket(&mut conn, ticket.id).unwrap(), 0);
+
+        add_worklog(&mut conn, worklog(ticket.id, &tech, 45, 3)).unwrap();
+        add_worklog(&mut conn, worklog(ticket.id, &admin, 20, 2)).unwrap();
+        add_worklog(&mut conn, worklog(other.id, &tech, 10, 1)).unwrap();
+
+        assert_eq!(total_minutes_for_ticket(&mut conn, ticket.id).unwrap(), 65);
+        assert_eq!(total_minutes_for_ticket(&mut conn, other.id).unwrap(), 10);
+
+        let complete = crate::repository::get_complete_ticket(&mut conn, ticket.id, true).unwrap();
+        assert_eq!(complete.total_time_spent, 65);
+    }
+}
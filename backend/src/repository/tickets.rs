@@ -3,7 +3,7 @@ use diesel::result::Error;
 use diesel::QueryResult;
 use uuid::Uuid;
 use std::sync::Arc;
-use tracing::{debug, warn};
+use tracing::{debug, warn, Instrument};
 
 use crate::db::DbConnection;
 use crate::models::*;
@@ -13,22 +13,22 @@ use crate::utils::storage::Storage;
 // ============= Helper Functions for Enum Parsing =============
 
 /// Parse a status string into a TicketStatus enum
-fn parse_ticket_status(status: &str) -> TicketStatus {
+fn parse_ticket_status(status: &str) -> Option<TicketStatus> {
     match status {
-        "open" => TicketStatus::Open,
-        "in-progress" => TicketStatus::InProgress,
-        "closed" => TicketStatus::Closed,
-        _ => TicketStatus::Open, // Default to open if unknown
+        "open" => Some(TicketStatus::Open),
+        "in-progress" => Some(TicketStatus::InProgress),
+        "closed" => Some(TicketStatus::Closed),
+        _ => None,
     }
 }
 
 /// Parse a priority string into a TicketPriority enum
-fn parse_ticket_priority(priority: &str) -> TicketPriority {
+fn parse_ticket_priority(priority: &str) -> Option<TicketPriority> {
     match priority {
-        "low" => TicketPriority::Low,
-        "medium" => TicketPriority::Medium,
-        "high" => TicketPriority::High,
-        _ => TicketPriority::Medium, // Default to medium if unknown
+        "low" => Some(TicketPriority::Low),
+        "medium" => Some(TicketPriority::Medium),
+        "high" => Some(TicketPriority::High),
+        _ => None,
     }
 }
 
@@ -43,6 +43,39 @@ pub fn get_ticket_by_id(conn: &mut DbConnection, ticket_id: i32) -> QueryResult<
         .first(conn)
 }
 
+/// Tickets with the given IDs, in the order given (e.g. search relevance),
+/// with requester and assignee info. IDs of tickets that no longer exist
+/// are skipped.
+pub fn get_tickets_by_ids_ordered(conn: &mut DbConnection, ids: &[i32]) -> QueryResult<Vec<TicketListItem>> {
+    let mut found: std::collections::HashMap<i32, Ticket> = tickets::table
+        .filter(tickets::id.eq_any(ids))
+        .load::<Ticket>(conn)?
+        .into_iter()
+        .map(|ticket| (ticket.id, ticket))
+        .collect();
+
+    let user_uuids: Vec<Uuid> = found
+        .values()
+        .flat_map(|ticket| [ticket.requester_uuid, ticket.assignee_uuid])
+        .flatten()
+        .collect();
+    let users: std::collections::HashMap<Uuid, User> = crate::repository::get_users_by_uuids(&user_uuids, conn)?
+        .into_iter()
+        .map(|user| (user.uuid, user))
+        .collect();
+    let user_info = |uuid: Option<Uuid>| uuid.and_then(|uuid| users.get(&uuid)).cloned().map(UserInfoWithAvatar::from);
+
+    Ok(ids
+        .iter()
+        .filter_map(|id| found.remove(id))
+        .map(|ticket| TicketListItem {
+            requester_user: user_info(ticket.requester_uuid),
+            assignee_user: user_info(ticket.assignee_uuid),
+            ticket,
+        })
+        .collect())
+}
+
 pub fn create_ticket(conn: &mut DbConnection, new_ticket: NewTicket) -> QueryResult<Ticket> {
     diesel::insert_into(tickets::table)
         .values(&new_ticket)
@@ -51,17 +84,457 @@ pub fn create_ticket(conn: &mut DbConnection, new_ticket: NewTicket) -> QueryRes
 
 pub fn update_ticket(conn: &mut DbConnection, ticket_id: i32, ticket: NewTicket) -> QueryResult<Ticket> {
     diesel::update(tickets::table.find(ticket_id))
-        .set(&ticket)
+        .set((&ticket, tickets::version.eq(tickets::version + 1)))
         .get_result(conn)
 }
 
-// Add a new function for partial ticket updates
-pub fn update_ticket_partial(conn: &mut DbConnection, ticket_id: i32, ticket_update: crate::models::TicketUpdate) -> QueryResult<Ticket> {
-    debug!(ticket_id, update = ?ticket_update, "Updating ticket");
-    
-    diesel::update(tickets::table.find(ticket_id))
-        .set(&ticket_update)
+/// Error from a partial ticket update
+#[derive(Debug)]
+pub enum TicketUpdateError {
+    /// The ticket was modified since the client read it (version mismatch)
+    Conflict,
+    Database(Error),
+}
+
+impl std::fmt::Display for TicketUpdateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TicketUpdateError::Conflict => write!(f, "Ticket was modified"),
+            TicketUpdateError::Database(e) => write!(f, "Database error: {e}"),
+        }
+    }
+}
+
+impl From<Error> for TicketUpdateError {
+    fn from(e: Error) -> Self {
+        TicketUpdateError::Database(e)
+    }
+}
+
+/// Partially update a ticket, recording status/priority/category changes in
+/// the audit log and keeping `closed_at`/`closed_by` in step with the status
+///
+/// Every update bumps the ticket's `version`. When `expected_version` is given
+/// the update only applies if it still matches, otherwise
+/// `TicketUpdateError::Conflict` is returned.
+pub fn update_ticket_partial(
+    conn: &mut DbConnection,
+    ticket_id: i32,
+    mut ticket_update: crate::models::TicketUpdate,
+    expected_version: Option<i32>,
+    changed_by: Option<Uuid>,
+) -> Result<Ticket, TicketUpdateError> {
+    debug!(ticket_id, update = ?ticket_update, expected_version, "Updating ticket");
+
+    conn.transaction(|conn| {
+        let old_ticket: Ticket = tickets::table.find(ticket_id).for_update().first(conn)?;
+        let version = expected_version.unwrap_or(old_ticket.version);
+
+        match ticket_update.status {
+            Some(TicketStatus::Closed) if old_ticket.status != TicketStatus::Closed => {
+                if ticket_update.closed_at.is_none() {
+                    ticket_update.closed_at = Some(Some(chrono::Utc::now().naive_utc()));
+                }
+                ticket_update.closed_by = Some(changed_by);
+            }
+            Some(status) if status != TicketStatus::Closed && old_ticket.status == TicketStatus::Closed => {
+                ticket_update.closed_at = Some(None);
+                ticket_update.closed_by = Some(None);
+            }
+            _ => {}
+        }
+
+        let updated: Ticket = diesel::update(
+            tickets::table
+                .filter(tickets::id.eq(ticket_id))
+                .filter(tickets::version.eq(version)),
+        )
+        .set((&ticket_update, tickets::version.eq(tickets::version + 1)))
         .get_result(conn)
+        .optional()?
+        .ok_or(TicketUpdateError::Conflict)?;
+
+        let changes = audit_changes(&old_ticket, &updated, changed_by);
+        if !changes.is_empty() {
+            diesel::insert_into(ticket_audit_log::table)
+                .values(&changes)
+                .execute(conn)?;
+        }
+
+        Ok(updated)
+    })
+}
+
+/// Why a ticket couldn't be reopened
+#[derive(Debug)]
+pub enum ReopenError {
+    /// The ticket isn't closed
+    NotClosed,
+    /// The ticket was closed longer ago than the allowed age
+    TooOld,
+    Database(Error),
+}
+
+impl std::fmt::Display for ReopenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReopenError::NotClosed => write!(f, "Ticket is not closed"),
+            ReopenError::TooOld => write!(f, "Ticket was closed too long ago to reopen"),
+            ReopenError::Database(e) => write!(f, "Database error: {e}"),
+        }
+    }
+}
+
+impl From<Error> for ReopenError {
+    fn from(e: Error) -> Self {
+        ReopenError::Database(e)
+    }
+}
+
+/// Reopen a closed ticket, setting it back to open
+///
+/// The cleared `closed_at`/`closed_by` are kept in the audit log. With
+/// `max_age` set, tickets closed longer ago than that are refused with
+/// `ReopenError::TooOld`.
+pub fn reopen_ticket(
+    conn: &mut DbConnection,
+    ticket_id: i32,
+    reopened_by: Option<Uuid>,
+    max_age: Option<chrono::Duration>,
+) -> Result<Ticket, ReopenError> {
+    conn.transaction(|conn| {
+        let ticket: Ticket = tickets::table.find(ticket_id).for_update().first(conn)?;
+        if ticket.status != TicketStatus::Closed {
+            return Err(ReopenError::NotClosed);
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        if let (Some(max_age), Some(closed_at)) = (max_age, ticket.closed_at) {
+            if now - closed_at > max_age {
+                return Err(ReopenError::TooOld);
+            }
+        }
+
+        let update = TicketUpdate {
+            status: Some(TicketStatus::Open),
+            updated_at: Some(now),
+            ..Default::default()
+        };
+        // The row is locked, so the update can't hit a version conflict
+        let reopened = match update_ticket_partial(conn, ticket_id, update, None, reopened_by) {
+            Ok(ticket) => ticket,
+            Err(TicketUpdateError::Database(e)) => return Err(e.into()),
+            Err(TicketUpdateError::Conflict) => return Err(Error::RollbackTransaction.into()),
+        };
+
+        // Keep the closure the reopen cleared
+        let archived = [
+            ("closed_at", ticket.closed_at.map(|at| at.and_utc().to_rfc3339())),
+            ("closed_by", ticket.closed_by.map(|uuid| uuid.to_string())),
+        ];
+        let archived: Vec<NewTicketAuditLog> = archived
+            .into_iter()
+            .filter(|(_, old_value)| old_value.is_some())
+            .map(|(field, old_value)| NewTicketAuditLog {
+                ticket_id,
+                field: field.to_string(),
+                old_value,
+                new_value: None,
+                changed_by: reopened_by,
+            })
+            .collect();
+        if !archived.is_empty() {
+            diesel::insert_into(ticket_audit_log::table)
+                .values(&archived)
+                .execute(conn)?;
+        }
+
+        Ok(reopened)
+    })
+}
+
+/// Tickets in `status` that haven't been touched since `idle_since`, oldest first
+pub fn get_idle_tickets(
+    conn: &mut DbConnection,
+    status: TicketStatus,
+    idle_since: chrono::NaiveDateTime,
+    limit: i64,
+) -> QueryResult<Vec<Ticket>> {
+    tickets::table
+        .into_boxed()
+        .filter(tickets::status.eq(status))
+        .filter(tickets::updated_at.lt(idle_since))
+        .filter(tickets::merged_into_id.is_null())
+        .order((tickets::updated_at.asc(), tickets::id.asc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Apply the same partial update to several tickets in one transaction
+///
+/// Either every ticket is updated or none are.
+pub fn bulk_update(
+    conn: &mut DbConnection,
+    ids: &[i32],
+    update: crate::models::TicketUpdate,
+    changed_by: Option<Uuid>,
+) -> Result<Vec<Ticket>, TicketUpdateError> {
+    conn.transaction(|conn| {
+        ids.iter()
+            .map(|&id| update_ticket_partial(conn, id, update.clone(), None, changed_by))
+            .collect()
+    })
+}
+
+/// Error from merging one ticket into another
+#[derive(Debug)]
+pub enum TicketMergeError {
+    /// Source and target are the same ticket
+    SameTicket,
+    /// The given ticket has already been merged into another one
+    AlreadyMerged(i32),
+    Update(TicketUpdateError),
+}
+
+impl std::fmt::Display for TicketMergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TicketMergeError::SameTicket => write!(f, "A ticket can't be merged into itself"),
+            TicketMergeError::AlreadyMerged(id) => write!(f, "Ticket #{id} has already been merged"),
+            TicketMergeError::Update(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<Error> for TicketMergeError {
+    fn from(e: Error) -> Self {
+        TicketMergeError::Update(TicketUpdateError::Database(e))
+    }
+}
+
+impl From<TicketUpdateError> for TicketMergeError {
+    fn from(e: TicketUpdateError) -> Self {
+        TicketMergeError::Update(e)
+    }
+}
+
+/// Result of a ticket merge
+#[derive(Debug)]
+pub struct TicketMerge {
+    /// The source ticket, now closed and pointing at the target
+    pub source: Ticket,
+    pub target: Ticket,
+    /// Comments moved from the source onto the target
+    pub moved_comments: Vec<Comment>,
+}
+
+/// Merge a duplicate ticket into another
+///
+/// Moves the source's comments (with their attachments), devices, projects
+/// and ticket links onto the target, then closes the source and marks it as
+/// merged. Both tickets get an audit entry. Runs in one transaction.
+pub fn merge_ticket(
+    conn: &mut DbConnection,
+    source_id: i32,
+    target_id: i32,
+    merged_by: Option<Uuid>,
+) -> Result<TicketMerge, TicketMergeError> {
+    if source_id == target_id {
+        return Err(TicketMergeError::SameTicket);
+    }
+
+    crate::repository::with_transaction(conn, |conn| {
+        // Lock both tickets in id order so concurrent merges can't deadlock
+        let locked: Vec<Ticket> = tickets::table
+            .filter(tickets::id.eq_any([source_id, target_id]))
+            .order(tickets::id.asc())
+            .for_update()
+            .load(conn)?;
+        if locked.len() != 2 {
+            return Err(Error::NotFound.into());
+        }
+        for ticket in &locked {
+            if ticket.merged_into_id.is_some() {
+                return Err(TicketMergeError::AlreadyMerged(ticket.id));
+            }
+        }
+
+        let moved_comments: Vec<Comment> = diesel::update(comments::table.filter(comments::ticket_id.eq(source_id)))
+            .set(comments::ticket_id.eq(target_id))
+            .get_results(conn)?;
+
+        // Devices and projects already on the target are dropped from the source
+        let target_devices: Vec<i32> = ticket_devices::table
+            .filter(ticket_devices::ticket_id.eq(target_id))
+            .select(ticket_devices::device_id)
+            .load(conn)?;
+        diesel::update(
+            ticket_devices::table
+                .filter(ticket_devices::ticket_id.eq(source_id))
+                .filter(ticket_devices::device_id.ne_all(&target_devices)),
+        )
+        .set(ticket_devices::ticket_id.eq(target_id))
+        .execute(conn)?;
+        diesel::delete(ticket_devices::table.filter(ticket_devices::ticket_id.eq(source_id))).execute(conn)?;
+
+        let target_projects: Vec<i32> = project_tickets::table
+            .filter(project_tickets::ticket_id.eq(target_id))
+            .select(project_tickets::project_id)
+            .load(conn)?;
+        diesel::update(
+            project_tickets::table
+                .filter(project_tickets::ticket_id.eq(source_id))
+                .filter(project_tickets::project_id.ne_all(&target_projects)),
+        )
+        .set(project_tickets::ticket_id.eq(target_id))
+        .execute(conn)?;
+        diesel::delete(project_tickets::table.filter(project_tickets::ticket_id.eq(source_id))).execute(conn)?;
+
+        let target_watchers: Vec<Uuid> = ticket_watchers::table
+            .filter(ticket_watchers::ticket_id.eq(target_id))
+            .select(ticket_watchers::user_uuid)
+            .load(conn)?;
+        diesel::update(
+            ticket_watchers::table
+                .filter(ticket_watchers::ticket_id.eq(source_id))
+                .filter(ticket_watchers::user_uuid.ne_all(&target_watchers)),
+        )
+        .set(ticket_watchers::ticket_id.eq(target_id))
+        .execute(conn)?;
+        diesel::delete(ticket_watchers::table.filter(ticket_watchers::ticket_id.eq(source_id))).execute(conn)?;
+
+        // Links are stored in both directions; a link between source and
+        // target just disappears
+        let mut target_links = crate::repository::linked_tickets::get_linked_tickets(conn, target_id)?;
+        target_links.push(target_id);
+        diesel::update(
+            linked_tickets::table
+                .filter(linked_tickets::ticket_id.eq(source_id))
+                .filter(linked_tickets::linked_ticket_id.ne_all(&target_links)),
+        )
+        .set(linked_tickets::ticket_id.eq(target_id))
+        .execute(conn)?;
+        diesel::update(
+            linked_tickets::table
+                .filter(linked_tickets::linked_ticket_id.eq(source_id))
+                .filter(linked_tickets::ticket_id.ne_all(&target_links)),
+        )
+        .set(linked_tickets::linked_ticket_id.eq(target_id))
+        .execute(conn)?;
+        diesel::delete(
+            linked_tickets::table.filter(
+                linked_tickets::ticket_id
+                    .eq(source_id)
+                    .or(linked_tickets::linked_ticket_id.eq(source_id)),
+            ),
+        )
+        .execute(conn)?;
+
+        // Close the source (audited like any status change), then mark it merged
+        let now = chrono::Utc::now().naive_utc();
+        update_ticket_partial(
+            conn,
+            source_id,
+            crate::models::TicketUpdate {
+                status: Some(TicketStatus::Closed),
+                updated_at: Some(now),
+                ..Default::default()
+            },
+            None,
+            merged_by,
+        )?;
+        let source: Ticket = diesel::update(tickets::table.find(source_id))
+            .set(tickets::merged_into_id.eq(target_id))
+            .get_result(conn)?;
+
+        // Bump the target's version so stale clients refetch the moved content
+        let target = update_ticket_partial(
+            conn,
+            target_id,
+            crate::models::TicketUpdate {
+                updated_at: Some(now),
+                ..Default::default()
+            },
+            None,
+            merged_by,
+        )?;
+
+        diesel::insert_into(ticket_audit_log::table)
+            .values(&vec![
+                NewTicketAuditLog {
+                    ticket_id: source_id,
+                    field: "merged_into".to_string(),
+                    old_value: None,
+                    new_value: Some(target_id.to_string()),
+                    changed_by: merged_by,
+                },
+                NewTicketAuditLog {
+                    ticket_id: target_id,
+                    field: "merged_from".to_string(),
+                    old_value: None,
+                    new_value: Some(source_id.to_string()),
+                    changed_by: merged_by,
+                },
+            ])
+            .execute(conn)?;
+
+        Ok(TicketMerge {
+            source,
+            target,
+            moved_comments,
+        })
+    })
+}
+
+/// Diff the audited fields of a ticket before and after an update
+fn audit_changes(old: &Ticket, new: &Ticket, changed_by: Option<Uuid>) -> Vec<NewTicketAuditLog> {
+    let fields = [
+        ("status", Some(status_str(old.status)), Some(status_str(new.status))),
+        ("priority", Some(priority_str(old.priority)), Some(priority_str(new.priority))),
+        (
+            "category_id",
+            old.category_id.map(|id| id.to_string()),
+            new.category_id.map(|id| id.to_string()),
+        ),
+    ];
+
+    fields
+        .into_iter()
+        .filter(|(_, old_value, new_value)| old_value != new_value)
+        .map(|(field, old_value, new_value)| NewTicketAuditLog {
+            ticket_id: new.id,
+            field: field.to_string(),
+            old_value,
+            new_value,
+            changed_by,
+        })
+        .collect()
+}
+
+fn status_str(status: TicketStatus) -> String {
+    match status {
+        TicketStatus::Open => "open",
+        TicketStatus::InProgress => "in-progress",
+        TicketStatus::Closed => "closed",
+    }
+    .to_string()
+}
+
+fn priority_str(priority: TicketPriority) -> String {
+    match priority {
+        TicketPriority::Low => "low",
+        TicketPriority::Medium => "medium",
+        TicketPriority::High => "high",
+    }
+    .to_string()
+}
+
+/// Get the audit trail for a ticket, oldest first
+pub fn get_ticket_audit_log(conn: &mut DbConnection, ticket_id: i32) -> QueryResult<Vec<TicketAuditLog>> {
+    ticket_audit_log::table
+        .filter(ticket_audit_log::ticket_id.eq(ticket_id))
+        .order((ticket_audit_log::changed_at.asc(), ticket_audit_log::id.asc()))
+        .load(conn)
 }
 
 /// Comprehensive ticket deletion that cleans up all associated data and files
@@ -73,7 +546,7 @@ pub async fn delete_ticket_with_cleanup(
     // Start a transaction to ensure all operations succeed or fail together
     conn.transaction(|conn| {
         // 1. First, get all comments for this ticket to find attachments
-        let comments = crate::repository::comments::get_comments_by_ticket_id(conn, ticket_id)?;
+        let comments = crate::repository::comments::get_comments_by_ticket_id(conn, ticket_id, true)?;
         
         // 2. Collect all attachment paths for file cleanup
         let mut attachment_paths = Vec::new();
@@ -121,20 +594,31 @@ pub async fn delete_ticket_with_cleanup(
     }).map(|(result, attachment_paths)| {
         // Clean up files after successful database transaction
         // This is done outside the transaction to avoid blocking the database
-        tokio::spawn(async move {
-            for path in attachment_paths {
-                if let Err(e) = storage.delete_file(&path).await {
-                    warn!(path, error = ?e, "Failed to delete file during ticket cleanup");
+        tokio::spawn(
+            async move {
+                for path in attachment_paths {
+                    if let Err(e) = storage.delete_file(&path).await {
+                        warn!(path, error = ?e, "Failed to delete file during ticket cleanup");
+                    }
                 }
             }
-        });
+            .instrument(tracing::Span::current()),
+        );
         result
     })
 }
 
+/// Place a ticket on legal hold, or release it, exempting its attachments
+/// from the retention policy while held
+pub fn set_legal_hold(conn: &mut DbConnection, ticket_id: i32, legal_hold: bool) -> QueryResult<Ticket> {
+    diesel::update(tickets::table.find(ticket_id))
+        .set(tickets::legal_hold.eq(legal_hold))
+        .get_result(conn)
+}
+
 /// Extract storage path from attachment URL
 /// Converts /uploads/tickets/123/filename.ext to tickets/123/filename.ext
-fn extract_storage_path_from_url(url: &str) -> Option<String> {
+pub(crate) fn extract_storage_path_from_url(url: &str) -> Option<String> {
     if url.starts_with("/uploads/tickets/") {
         Some(url.trim_start_matches("/uploads/").to_string())
     } else if url.starts_with("/uploads/temp/") {
@@ -145,7 +629,10 @@ fn extract_storage_path_from_url(url: &str) -> Option<String> {
 }
 
 // Composite operations for tickets
-pub fn get_complete_ticket(conn: &mut DbConnection, ticket_id: i32) -> Result<CompleteTicket, Error> {
+
+/// Ticket with users, devices, comments, links and projects. Internal
+/// comments are left out unless `include_internal` is set.
+pub fn get_complete_ticket(conn: &mut DbConnection, ticket_id: i32, include_internal: bool) -> Result<CompleteTicket, Error> {
     // Get the main ticket first
     let ticket = get_ticket_by_id(conn, ticket_id)?;
     debug!(id = ticket.id, title = %ticket.title, "Found ticket");
@@ -169,7 +656,7 @@ pub fn get_complete_ticket(conn: &mut DbConnection, ticket_id: i32) -> Result<Co
     let devices = get_devices_for_ticket(conn, ticket_id).unwrap_or_default();
     
     // Get comments for this ticket
-    let comments = crate::repository::comments::get_comments_by_ticket_id(conn, ticket_id)?;
+    let comments = crate::repository::comments::get_comments_by_ticket_id(conn, ticket_id, include_internal)?;
     let mut comments_with_attachments = Vec::new();
     
     for comment in comments {
@@ -198,7 +685,14 @@ pub fn get_complete_ticket(conn: &mut DbConnection, ticket_id: i32) -> Result<Co
     // Get projects for this ticket
     let projects = crate::repository::projects::get_projects_for_ticket(conn, ticket_id).unwrap_or_default();
     debug!(ticket_id, count = projects.len(), "Found projects for ticket");
-    
+
+    let sla = crate::repository::sla::ticket_sla_status(conn, &ticket, chrono::Utc::now().naive_utc())
+        .unwrap_or_default();
+
+    let total_time_spent = crate::repository::ticket_worklogs::total_minutes_for_ticket(conn, ticket_id)?;
+
+    let custom_fields = crate::repository::custom_fields::get_values(conn, ticket_id)?;
+
     Ok(CompleteTicket {
         ticket,
         requester_user,
@@ -208,74 +702,182 @@ pub fn get_complete_ticket(conn: &mut DbConnection, ticket_id: i32) -> Result<Co
         article_content,
         linked_tickets,
         projects,
+        sla,
+        total_time_spent,
+        custom_fields,
     })
 }
 
-// Import from JSON
-pub fn import_ticket_from_json(conn: &mut DbConnection, ticket_json: &TicketJson) -> Result<Ticket, Error> {
-    // Use helper functions for enum parsing
-    let status = parse_ticket_status(&ticket_json.status);
-    let priority = parse_ticket_priority(&ticket_json.priority);
+// ============= JSON Import/Export =============
 
-    // Create the ticket
-    let new_ticket = NewTicket {
-        title: ticket_json.title.clone(),
-        status,
-        priority,
-        requester_uuid: Some(Uuid::parse_str(&ticket_json.requester).unwrap_or_else(|_| Uuid::now_v7())),
-        assignee_uuid: if ticket_json.assignee.is_empty() {
-            None
-        } else {
-            Uuid::parse_str(&ticket_json.assignee).ok()
-        },
-        category_id: None,
-    };
+/// Error from importing a ticket
+#[derive(Debug)]
+pub enum TicketImportError {
+    /// Fields that can't be imported, like an unknown requester, with an
+    /// error per field (e.g. `comments[0].user_uuid`)
+    Invalid(Vec<crate::services::category_fields::FieldError>),
+    Database(Error),
+}
+
+impl std::fmt::Display for TicketImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TicketImportError::Invalid(errors) => {
+                let errors: Vec<String> = errors.iter().map(|e| format!("{}: {}", e.field, e.message)).collect();
+                write!(f, "{}", errors.join("; "))
+            }
+            TicketImportError::Database(e) => write!(f, "Database error: {e}"),
+        }
+    }
+}
+
+impl From<Error> for TicketImportError {
+    fn from(e: Error) -> Self {
+        TicketImportError::Database(e)
+    }
+}
 
-    let ticket = create_ticket(conn, new_ticket)?;
-
-    // Create device if present (without ticket association)
-    if let Some(device_json) = &ticket_json.device {
-        let new_device = NewDevice {
-            name: device_json.name.clone(),
-            hostname: Some(device_json.hostname.clone()),
-            device_type: None,
-            serial_number: Some(device_json.serial_number.clone()),
-            manufacturer: None, // Will be populated during Microsoft Entra sync
-            model: Some(device_json.model.clone()),
-            warranty_status: Some(device_json.warranty_status.clone()),
-            location: None,
-            notes: None,
-            primary_user_uuid: None, // Will be populated during Microsoft Entra sync
-            microsoft_device_id: None,
-            intune_device_id: None,
-            entra_device_id: None,
-            compliance_state: None,
-            last_sync_time: None,
-            operating_system: None,
-            os_version: None,
-            is_managed: None,
-            enrollment_date: None,
+/// Checks the fields of an imported ticket, collecting an error per field
+struct ImportCheck<'a> {
+    conn: &'a mut DbConnection,
+    errors: Vec<crate::services::category_fields::FieldError>,
+}
+
+impl ImportCheck<'_> {
+    fn error(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.errors.push(crate::services::category_fields::FieldError::new(field, message));
+    }
+
+    fn timestamp(&mut self, field: &str, value: &str) -> Option<chrono::NaiveDateTime> {
+        let parsed = crate::services::ticket_json::parse_timestamp(value);
+        if parsed.is_none() {
+            self.error(field, format!("'{value}' is not an RFC 3339 timestamp"));
+        }
+        parsed
+    }
+
+    /// The existing user `value` names
+    fn user(&mut self, field: &str, value: &str) -> Result<Option<Uuid>, Error> {
+        if value.trim().is_empty() {
+            self.error(field, "is required");
+            return Ok(None);
+        }
+        let Ok(uuid) = Uuid::parse_str(value.trim()) else {
+            self.error(field, format!("'{value}' is not a user UUID"));
+            return Ok(None);
         };
+        match crate::repository::users::get_user_by_uuid(&uuid, self.conn) {
+            Ok(_) => Ok(Some(uuid)),
+            Err(Error::NotFound) => {
+                self.error(field, format!("no user with UUID {uuid}"));
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Import a ticket with its comments, attachments and device, keeping its
+/// timestamps
+///
+/// The ticket is checked before anything is written: statuses, priorities
+/// and timestamps must parse, and the requester, assignee and comment
+/// authors must be existing users. It's then written in one transaction,
+/// under a new id.
+pub fn import_ticket_from_json(conn: &mut DbConnection, ticket_json: &TicketJson) -> Result<Ticket, TicketImportError> {
+    let mut check = ImportCheck { conn: &mut *conn, errors: Vec::new() };
+
+    let status = parse_ticket_status(&ticket_json.status);
+    if status.is_none() {
+        check.error("status", format!("unknown status '{}'", ticket_json.status));
+    }
+    let priority = parse_ticket_priority(&ticket_json.priority);
+    if priority.is_none() {
+        check.error("priority", format!("unknown priority '{}'", ticket_json.priority));
+    }
+    let created_at = check.timestamp("created", &ticket_json.created);
+    let updated_at = check.timestamp("modified", &ticket_json.modified);
+    let closed_at = match &ticket_json.closed_at {
+        Some(closed_at) => check.timestamp("closed_at", closed_at),
+        None => None,
+    };
+    let requester_uuid = check.user("requester", &ticket_json.requester)?;
+    let assignee_uuid = if ticket_json.assignee.is_empty() {
+        None
+    } else {
+        check.user("assignee", &ticket_json.assignee)?
+    };
 
-        crate::repository::devices::create_device(conn, new_device)?;
+    let mut comments = Vec::new();
+    for (i, comment_json) in ticket_json.comments.iter().flatten().enumerate() {
+        let user_uuid = check.user(&format!("comments[{i}].user_uuid"), &comment_json.user_uuid)?;
+        let comment_created_at = check.timestamp(&format!("comments[{i}].createdAt"), &comment_json.created_at);
+        if let (Some(user_uuid), Some(comment_created_at)) = (user_uuid, comment_created_at) {
+            comments.push((comment_json, user_uuid, comment_created_at));
+        }
     }
 
-    // Create comments and attachments if present
-    if let Some(comments_json) = &ticket_json.comments {
-        // Default system user UUID for imported comments
-        let default_user_uuid = Uuid::parse_str("00000000-0000-0000-0000-000000000001")
-            .unwrap_or_else(|_| Uuid::now_v7());
+    let (Some(status), Some(priority), Some(created_at), Some(updated_at), Some(requester_uuid), true) =
+        (status, priority, created_at, updated_at, requester_uuid, check.errors.is_empty())
+    else {
+        return Err(TicketImportError::Invalid(check.errors));
+    };
 
-        for comment_json in comments_json {
-            let new_comment = NewComment {
-                content: comment_json.content.clone(),
-                ticket_id: ticket.id,
-                user_uuid: default_user_uuid,
+    crate::repository::with_transaction(conn, |conn| {
+        let ticket = create_ticket(
+            conn,
+            NewTicket {
+                title: ticket_json.title.clone(),
+                status,
+                priority,
+                requester_uuid: Some(requester_uuid),
+                assignee_uuid,
+                category_id: None,
+            },
+        )?;
+
+        // Create device if present (without ticket association)
+        if let Some(device_json) = &ticket_json.device {
+            let new_device = NewDevice {
+                name: device_json.name.clone(),
+                hostname: Some(device_json.hostname.clone()),
+                device_type: None,
+                serial_number: Some(device_json.serial_number.clone()),
+                manufacturer: None, // Will be populated during Microsoft Entra sync
+                model: Some(device_json.model.clone()),
+                warranty_status: Some(device_json.warranty_status.clone()),
+                location: None,
+                notes: None,
+                primary_user_uuid: None, // Will be populated during Microsoft Entra sync
+                microsoft_device_id: None,
+                intune_device_id: None,
+                entra_device_id: None,
+                compliance_state: None,
+                last_sync_time: None,
+                operating_system: None,
+                os_version: None,
+                is_managed: None,
+                enrollment_date: None,
+                warranty_expiry_date: None,
             };
 
-            let comment = crate::repository::comments::create_comment(conn, new_comment)?;
+            crate::repository::devices::create_device(conn, new_device)?;
+        }
+
+        for (comment_json, user_uuid, comment_created_at) in comments {
+            let comment = crate::repository::comments::create_comment(
+                conn,
+                NewComment {
+                    content: comment_json.content.clone(),
+                    ticket_id: ticket.id,
+                    user_uuid,
+                    is_internal: comment_json.is_internal,
+                },
+            )?;
+            diesel::update(comments::table.find(comment.id))
+                .set((comments::created_at.eq(comment_created_at), comments::updated_at.eq(comment_created_at)))
+                .execute(conn)?;
 
-            // Create attachments for this comment
             for attachment_json in &comment_json.attachments {
                 let new_attachment = NewAttachment {
                     url: attachment_json.url.clone(),
@@ -291,21 +893,69 @@ pub fn import_ticket_from_json(conn: &mut DbConnection, ticket_json: &TicketJson
                 crate::repository::comments::create_attachment(conn, new_attachment)?;
             }
         }
-    }
 
-    // Create article content if present
-    if ticket_json.article_content.is_some() {
-        let new_article_content = NewArticleContent {
-            ticket_id: ticket.id,
-            yjs_state_vector: None,
-            yjs_document: None,
-            yjs_client_id: None,
-        };
+        // Create article content if present
+        if ticket_json.article_content.is_some() {
+            let new_article_content = NewArticleContent {
+                ticket_id: ticket.id,
+                yjs_state_vector: None,
+                yjs_document: None,
+                yjs_client_id: None,
+            };
+
+            crate::repository::article_content::create_article_content(conn, new_article_content)?;
+        }
+
+        // Last, since adding comments bumps updated_at
+        Ok(diesel::update(tickets::table.find(ticket.id))
+            .set((
+                tickets::created_at.eq(created_at),
+                tickets::updated_at.eq(updated_at),
+                tickets::closed_at.eq(closed_at),
+            ))
+            .get_result(conn)?)
+    })
+}
+
+/// Export a ticket with its comments and attachments in the import format
+///
+/// Devices are separate records and article content lives in the
+/// collaborative editor, so neither is exported.
+pub fn export_ticket_to_json(conn: &mut DbConnection, ticket_id: i32) -> QueryResult<TicketJson> {
+    use crate::services::ticket_json::format_timestamp;
+
+    let ticket = get_ticket_by_id(conn, ticket_id)?;
 
-        crate::repository::article_content::create_article_content(conn, new_article_content)?;
+    let mut comments = Vec::new();
+    for comment in crate::repository::comments::get_comments_by_ticket_id(conn, ticket_id, true)? {
+        let attachments = crate::repository::comments::get_attachments_by_comment_id(conn, comment.id)?
+            .into_iter()
+            .map(|attachment| AttachmentJson { url: attachment.url, name: attachment.name })
+            .collect();
+        comments.push(CommentJson {
+            id: comment.id,
+            content: comment.content,
+            user_uuid: comment.user_uuid.to_string(),
+            created_at: format_timestamp(comment.created_at),
+            is_internal: comment.is_internal,
+            attachments,
+        });
     }
 
-    Ok(ticket)
+    Ok(TicketJson {
+        id: ticket.id,
+        title: ticket.title,
+        status: status_str(ticket.status),
+        priority: priority_str(ticket.priority),
+        created: format_timestamp(ticket.created_at),
+        modified: format_timestamp(ticket.updated_at),
+        closed_at: ticket.closed_at.map(format_timestamp),
+        assignee: ticket.assignee_uuid.map(|uuid| uuid.to_string()).unwrap_or_default(),
+        requester: ticket.requester_uuid.map(|uuid| uuid.to_string()).unwrap_or_default(),
+        device: None,
+        comments: Some(comments),
+        article_content: None,
+    })
 }
 
 // Ticket-Device relationship functions
@@ -336,34 +986,156 @@ pub fn get_devices_for_ticket(conn: &mut DbConnection, ticket_id: i32) -> QueryR
         .load(conn)
 }
 
+// ============= Activity Timeline =============
+
+/// Resolve user info, caching lookups since the same actors repeat across events
+fn resolve_user(
+    conn: &mut DbConnection,
+    cache: &mut std::collections::HashMap<Uuid, Option<UserInfo>>,
+    uuid: Option<Uuid>,
+) -> Option<UserInfo> {
+    let uuid = uuid?;
+    cache
+        .entry(uuid)
+        .or_insert_with(|| {
+            crate::repository::get_user_by_uuid(&uuid, conn)
+                .ok()
+                .map(|user| UserInfo { uuid: user.uuid, name: user.name })
+        })
+        .clone()
+}
+
+/// Get a ticket's activity (creation, assignments, comments, device links,
+/// field changes, closure) merged into one chronologically ordered list.
+/// Internal comments are left out unless `include_internal` is set.
+pub fn get_ticket_timeline(conn: &mut DbConnection, ticket_id: i32, include_internal: bool) -> QueryResult<Vec<TimelineEvent>> {
+    let ticket = get_ticket_by_id(conn, ticket_id)?;
+    let mut users = std::collections::HashMap::new();
+    let mut events = Vec::new();
+
+    events.push(TimelineEvent {
+        timestamp: ticket.created_at,
+        actor: resolve_user(conn, &mut users, ticket.created_by),
+        kind: TimelineEventKind::Created,
+    });
+
+    let assignments: Vec<AssignmentLog> = assignment_log::table
+        .filter(assignment_log::ticket_id.eq(ticket_id))
+        .order(assignment_log::assigned_at.asc())
+        .load(conn)?;
+
+    for log in assignments {
+        let context = log.context.as_ref();
+        let actor_uuid = context
+            .and_then(|c| c.get("assigned_by"))
+            .and_then(|v| v.as_str())
+            .and_then(|v| Uuid::parse_str(v).ok());
+        let rule_name = context
+            .and_then(|c| c.get("rule_name"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        events.push(TimelineEvent {
+            timestamp: log.assigned_at,
+            actor: resolve_user(conn, &mut users, actor_uuid),
+            kind: TimelineEventKind::Assignment {
+                previous_assignee: resolve_user(conn, &mut users, log.previous_assignee_uuid),
+                new_assignee: resolve_user(conn, &mut users, log.new_assignee_uuid),
+                method: log.method,
+                trigger: log.trigger_type,
+                rule_name,
+            },
+        });
+    }
+
+    for comment in crate::repository::comments::get_comments_by_ticket_id(conn, ticket_id, include_internal)? {
+        events.push(TimelineEvent {
+            timestamp: comment.created_at,
+            actor: resolve_user(conn, &mut users, Some(comment.user_uuid)),
+            kind: TimelineEventKind::Comment {
+                comment_id: comment.id,
+                content: comment.content,
+            },
+        });
+    }
+
+    let device_links: Vec<(TicketDevice, String)> = ticket_devices::table
+        .inner_join(devices::table)
+        .filter(ticket_devices::ticket_id.eq(ticket_id))
+        .select((ticket_devices::all_columns, devices::name))
+        .load(conn)?;
+
+    for (link, device_name) in device_links {
+        events.push(TimelineEvent {
+            timestamp: link.created_at,
+            actor: resolve_user(conn, &mut users, link.created_by),
+            kind: TimelineEventKind::DeviceLinked {
+                device_id: link.device_id,
+                device_name,
+            },
+        });
+    }
+
+    let audit_entries = get_ticket_audit_log(conn, ticket_id)?;
+    let close_audited = audit_entries
+        .iter()
+        .any(|entry| entry.field == "status" && entry.new_value.as_deref() == Some("closed"));
+
+    for entry in audit_entries {
+        events.push(TimelineEvent {
+            timestamp: entry.changed_at,
+            actor: resolve_user(conn, &mut users, entry.changed_by),
+            kind: TimelineEventKind::FieldChanged {
+                field: entry.field,
+                old_value: entry.old_value,
+                new_value: entry.new_value,
+            },
+        });
+    }
+
+    // Tickets closed before the audit log existed only have closed_at/closed_by
+    if let (Some(closed_at), false) = (ticket.closed_at, close_audited) {
+        events.push(TimelineEvent {
+            timestamp: closed_at,
+            actor: resolve_user(conn, &mut users, ticket.closed_by),
+            kind: TimelineEventKind::Closed,
+        });
+    }
+
+    // Stable sort keeps insertion order (creation first) for equal timestamps
+    events.sort_by_key(|event| event.timestamp);
+
+    Ok(events)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn parse_status_known_values() {
-        assert_eq!(parse_ticket_status("open"), TicketStatus::Open);
-        assert_eq!(parse_ticket_status("in-progress"), TicketStatus::InProgress);
-        assert_eq!(parse_ticket_status("closed"), TicketStatus::Closed);
+        assert_eq!(parse_ticket_status("open"), Some(TicketStatus::Open));
+        assert_eq!(parse_ticket_status("in-progress"), Some(TicketStatus::InProgress));
+        assert_eq!(parse_ticket_status("closed"), Some(TicketStatus::Closed));
     }
 
     #[test]
-    fn parse_status_unknown_defaults_to_open() {
-        assert_eq!(parse_ticket_status("unknown"), TicketStatus::Open);
-        assert_eq!(parse_ticket_status(""), TicketStatus::Open);
+    fn parse_status_unknown_is_rejected() {
+        assert_eq!(parse_ticket_status("unknown"), None);
+        assert_eq!(parse_ticket_status(""), None);
     }
 
     #[test]
     fn parse_priority_known_values() {
-        assert_eq!(parse_ticket_priority("low"), TicketPriority::Low);
-        assert_eq!(parse_ticket_priority("medium"), TicketPriority::Medium);
-        assert_eq!(parse_ticket_priority("high"), TicketPriority::High);
+        assert_eq!(parse_ticket_priority("low"), Some(TicketPriority::Low));
+        assert_eq!(parse_ticket_priority("medium"), Some(TicketPriority::Medium));
+        assert_eq!(parse_ticket_priority("high"), Some(TicketPriority::High));
     }
 
     #[test]
-    fn parse_priority_unknown_defaults_to_medium() {
-        assert_eq!(parse_ticket_priority("critical"), TicketPriority::Medium);
-        assert_eq!(parse_ticket_priority(""), TicketPriority::Medium);
+    fn parse_priority_unknown_is_rejected() {
+        assert_eq!(parse_ticket_priority("critical"), None);
+        assert_eq!(parse_ticket_priority(""), None);
     }
 
     #[test]
@@ -387,5 +1159,542 @@ mod tests {
         assert_eq!(extract_storage_path_from_url("/other/path.pdf"), None);
         assert_eq!(extract_storage_path_from_url("https://example.com/file"), None);
     }
-}
 
+    #[test]
+    fn tickets_by_ids_keep_the_given_order() {
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+        let mut conn = setup_test_connection();
+        let requester = TestFixtures::create_user(&mut conn, "orderedrequester", UserRole::User);
+        let first = TestFixtures::create_ticket(&mut conn, "First", Some(requester.uuid), None);
+        let second = TestFixtures::create_ticket(&mut conn, "Second", None, None);
+        let third = TestFixtures::create_ticket(&mut conn, "Third", None, None);
+        let missing = third.id + 1000;
+
+        let items = get_tickets_by_ids_ordered(&mut conn, &[third.id, missing, first.id, second.id]).unwrap();
+        let ids: Vec<i32> = items.iter().map(|item| item.ticket.id).collect();
+        assert_eq!(ids, vec![third.id, first.id, second.id]);
+        assert_eq!(items[1].requester_user.as_ref().map(|user| user.uuid), Some(requester.uuid));
+        assert!(items[0].requester_user.is_none());
+
+        assert!(get_tickets_by_ids_ordered(&mut conn, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn timeline_merges_events_in_order() {
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+        use chrono::{Duration, Utc};
+
+        let mut conn = setup_test_connection();
+        let tech = TestFixtures::create_user(&mut conn, "timelinetech", UserRole::Technician);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Timeline Ticket", Some(tech.uuid), None);
+
+        // Everything inside the test transaction shares now(), so pin explicit times
+        let base = Utc::now().naive_utc() - Duration::hours(4);
+        let at = |minutes: i64| base + Duration::minutes(minutes);
+
+        diesel::update(tickets::table.find(ticket.id))
+            .set((
+                tickets::created_at.eq(at(0)),
+                tickets::created_by.eq(Some(tech.uuid)),
+                tickets::status.eq(TicketStatus::Closed),
+                tickets::closed_at.eq(Some(at(40))),
+                tickets::closed_by.eq(Some(tech.uuid)),
+            ))
+            .execute(&mut conn)
+            .unwrap();
+
+        let comment = TestFixtures::create_comment(&mut conn, ticket.id, tech.uuid, "Looking into it");
+        diesel::update(comments::table.find(comment.id))
+            .set(comments::created_at.eq(at(20)))
+            .execute(&mut conn)
+            .unwrap();
+
+        diesel::insert_into(assignment_log::table)
+            .values(NewAssignmentLog {
+                ticket_id: ticket.id,
+                rule_id: None,
+                trigger_type: "ticket_created".to_string(),
+                previous_assignee_uuid: None,
+                new_assignee_uuid: Some(tech.uuid),
+                method: AssignmentMethod::DirectUser,
+                context: Some(serde_json::json!({ "rule_name": "Default" })),
+            })
+            .execute(&mut conn)
+            .unwrap();
+        diesel::update(assignment_log::table.filter(assignment_log::ticket_id.eq(ticket.id)))
+            .set(assignment_log::assigned_at.eq(at(10)))
+            .execute(&mut conn)
+            .unwrap();
+
+        let device_id: i32 = diesel::insert_into(devices::table)
+            .values(devices::name.eq("Timeline Laptop"))
+            .returning(devices::id)
+            .get_result(&mut conn)
+            .unwrap();
+        add_device_to_ticket(&mut conn, ticket.id, device_id).unwrap();
+        diesel::update(ticket_devices::table.filter(ticket_devices::ticket_id.eq(ticket.id)))
+            .set(ticket_devices::created_at.eq(at(30)))
+            .execute(&mut conn)
+            .unwrap();
+
+        let timeline = get_ticket_timeline(&mut conn, ticket.id, true).unwrap();
+
+        let kinds: Vec<&str> = timeline
+            .iter()
+            .map(|e| match e.kind {
+                TimelineEventKind::Created => "created",
+                TimelineEventKind::Assignment { .. } => "assignment",
+                TimelineEventKind::Comment { .. } => "comment",
+                TimelineEventKind::DeviceLinked { .. } => "device_linked",
+                TimelineEventKind::FieldChanged { .. } => "field_changed",
+                TimelineEventKind::Closed => "closed",
+            })
+            .collect();
+        assert_eq!(kinds, vec!["created", "assignment", "comment", "device_linked", "closed"]);
+        assert!(timeline.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+
+        // Actors and referenced users are resolved
+        assert_eq!(timeline[2].actor.as_ref().map(|u| u.uuid), Some(tech.uuid));
+        match &timeline[1].kind {
+            TimelineEventKind::Assignment { new_assignee, rule_name, .. } => {
+                assert_eq!(new_assignee.as_ref().map(|u| u.name.as_str()), Some(tech.name.as_str()));
+                assert_eq!(rule_name.as_deref(), Some("Default"));
+            }
+            other => panic!("expected assignment, got {other:?}"),
+        }
+        match &timeline[3].kind {
+            TimelineEventKind::DeviceLinked { device_name, .. } => assert_eq!(device_name, "Timeline Laptop"),
+            other => panic!("expected device link, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn status_change_writes_single_audit_row() {
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+        let mut conn = setup_test_connection();
+        let tech = TestFixtures::create_user(&mut conn, "audittech", UserRole::Technician);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Audit Ticket", Some(tech.uuid), None);
+
+        let update = TicketUpdate {
+            title: Some("Audit Ticket (renamed)".to_string()),
+            status: Some(TicketStatus::Closed),
+            ..Default::default()
+        };
+        let closed = update_ticket_partial(&mut conn, ticket.id, update, None, Some(tech.uuid)).unwrap();
+
+        assert!(closed.closed_at.is_some());
+        assert_eq!(closed.closed_by, Some(tech.uuid));
+
+        let audit = get_ticket_audit_log(&mut conn, ticket.id).unwrap();
+        assert_eq!(audit.len(), 1, "title edits are not audited, status is");
+        assert_eq!(audit[0].field, "status");
+        assert_eq!(audit[0].old_value.as_deref(), Some("open"));
+        assert_eq!(audit[0].new_value.as_deref(), Some("closed"));
+        assert_eq!(audit[0].changed_by, Some(tech.uuid));
+
+        // Setting the same status again is not a change
+        let update = TicketUpdate { status: Some(TicketStatus::Closed), ..Default::default() };
+        update_ticket_partial(&mut conn, ticket.id, update, None, Some(tech.uuid)).unwrap();
+        assert_eq!(get_ticket_audit_log(&mut conn, ticket.id).unwrap().len(), 1);
+
+        // Reopening clears the closure and shows up in the timeline
+        let update = TicketUpdate { status: Some(TicketStatus::Open), ..Default::default() };
+        let reopened = update_ticket_partial(&mut conn, ticket.id, update, None, Some(tech.uuid)).unwrap();
+        assert_eq!(reopened.closed_at, None);
+        assert_eq!(reopened.closed_by, None);
+
+        let timeline = get_ticket_timeline(&mut conn, ticket.id, true).unwrap();
+        let status_changes = timeline
+            .iter()
+            .filter(|e| matches!(&e.kind, TimelineEventKind::FieldChanged { field, .. } if field == "status"))
+            .count();
+        assert_eq!(status_changes, 2);
+        assert!(!timeline.iter().any(|e| e.kind == TimelineEventKind::Closed));
+    }
+
+    #[test]
+    fn reopen_clears_closure_and_archives_it() {
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+        let mut conn = setup_test_connection();
+        let tech = TestFixtures::create_user(&mut conn, "reopentech", UserRole::Technician);
+        let requester = TestFixtures::create_user(&mut conn, "reopenrequester", UserRole::User);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Printer offline", Some(requester.uuid), None);
+
+        // Only closed tickets can be reopened
+        assert!(matches!(
+            reopen_ticket(&mut conn, ticket.id, Some(requester.uuid), None),
+            Err(ReopenError::NotClosed)
+        ));
+
+        let update = TicketUpdate { status: Some(TicketStatus::Closed), ..Default::default() };
+        let closed = update_ticket_partial(&mut conn, ticket.id, update, None, Some(tech.uuid)).unwrap();
+
+        let reopened = reopen_ticket(&mut conn, ticket.id, Some(requester.uuid), None).unwrap();
+        assert_eq!(reopened.status, TicketStatus::Open);
+        assert_eq!(reopened.closed_at, None);
+        assert_eq!(reopened.closed_by, None);
+        assert_eq!(reopened.version, closed.version + 1);
+
+        let audit = get_ticket_audit_log(&mut conn, ticket.id).unwrap();
+        let reopen = audit
+            .iter()
+            .find(|e| e.field == "status" && e.new_value.as_deref() == Some("open"))
+            .unwrap();
+        assert_eq!(reopen.changed_by, Some(requester.uuid));
+        let closed_by = audit.iter().find(|e| e.field == "closed_by").unwrap();
+        assert_eq!(closed_by.old_value, Some(tech.uuid.to_string()));
+        let closed_at = audit.iter().find(|e| e.field == "closed_at").unwrap();
+        assert_eq!(
+            closed_at.old_value,
+            closed.closed_at.map(|at| at.and_utc().to_rfc3339())
+        );
+    }
+
+    #[test]
+    fn reopen_refuses_tickets_closed_too_long_ago() {
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+        let mut conn = setup_test_connection();
+        let ticket = TestFixtures::create_ticket(&mut conn, "Old request", None, None);
+        let closed_at = chrono::Utc::now().naive_utc() - chrono::Duration::days(45);
+        diesel::update(tickets::table.find(ticket.id))
+            .set((
+                tickets::status.eq(TicketStatus::Closed),
+                tickets::created_at.eq(closed_at - chrono::Duration::days(1)),
+                tickets::closed_at.eq(Some(closed_at)),
+            ))
+            .execute(&mut conn)
+            .unwrap();
+
+        assert!(matches!(
+            reopen_ticket(&mut conn, ticket.id, None, Some(chrono::Duration::days(30))),
+            Err(ReopenError::TooOld)
+        ));
+        assert_eq!(get_ticket_by_id(&mut conn, ticket.id).unwrap().status, TicketStatus::Closed);
+
+        // Without an age limit (admins) it reopens
+        let reopened = reopen_ticket(&mut conn, ticket.id, None, None).unwrap();
+        assert_eq!(reopened.status, TicketStatus::Open);
+    }
+
+    #[test]
+    fn stale_version_update_is_rejected() {
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+        let mut conn = setup_test_connection();
+        let tech = TestFixtures::create_user(&mut conn, "versiontech", UserRole::Technician);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Versioned Ticket", Some(tech.uuid), None);
+        assert_eq!(ticket.version, 1);
+
+        // First technician saves against the version they loaded
+        let update = TicketUpdate { title: Some("First edit".to_string()), ..Default::default() };
+        let first = update_ticket_partial(&mut conn, ticket.id, update, Some(1), Some(tech.uuid)).unwrap();
+        assert_eq!(first.version, 2);
+
+        // Second technician still holds version 1
+        let update = TicketUpdate {
+            title: Some("Second edit".to_string()),
+            status: Some(TicketStatus::Closed),
+            ..Default::default()
+        };
+        let result = update_ticket_partial(&mut conn, ticket.id, update, Some(1), Some(tech.uuid));
+        assert!(matches!(result, Err(TicketUpdateError::Conflict)));
+
+        let current = get_ticket_by_id(&mut conn, ticket.id).unwrap();
+        assert_eq!(current.title, "First edit");
+        assert_eq!(current.status, TicketStatus::Open);
+        assert_eq!(current.version, 2);
+        assert!(get_ticket_audit_log(&mut conn, ticket.id).unwrap().is_empty());
+
+        // Unversioned (internal) updates still apply and bump the version
+        let update = TicketUpdate { priority: Some(TicketPriority::High), ..Default::default() };
+        let bumped = update_ticket_partial(&mut conn, ticket.id, update, None, None).unwrap();
+        assert_eq!(bumped.version, 3);
+    }
+
+    #[test]
+    fn internal_comments_are_hidden_from_regular_users() {
+        use crate::extractors::AuthContext;
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+        let mut conn = setup_test_connection();
+        let requester = TestFixtures::create_user(&mut conn, "internalrequester", UserRole::User);
+        let tech = TestFixtures::create_user(&mut conn, "internaltech", UserRole::Technician);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Internal notes", Some(requester.uuid), None);
+
+        let public = TestFixtures::create_comment(&mut conn, ticket.id, tech.uuid, "We're looking into it");
+        let internal = crate::repository::comments::create_comment(&mut conn, NewComment {
+            content: "Probably the vendor's fault".to_string(),
+            ticket_id: ticket.id,
+            user_uuid: tech.uuid,
+            is_internal: true,
+        })
+        .unwrap();
+
+        let comment_ids = |include_internal: bool, conn: &mut DbConnection| -> Vec<i32> {
+            let mut ids: Vec<i32> = get_complete_ticket(conn, ticket.id, include_internal)
+                .unwrap()
+                .comments
+                .iter()
+                .map(|c| c.comment.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        let requester_view = AuthContext::test_context(requester.uuid, UserRole::User, vec![]);
+        let tech_view = AuthContext::test_context(tech.uuid, UserRole::Technician, vec![]);
+
+        assert_eq!(comment_ids(requester_view.is_technician_or_admin(), &mut conn), vec![public.id]);
+        assert_eq!(comment_ids(tech_view.is_technician_or_admin(), &mut conn), vec![public.id, internal.id]);
+
+        let timeline_comments = |include_internal: bool, conn: &mut DbConnection| -> usize {
+            get_ticket_timeline(conn, ticket.id, include_internal)
+                .unwrap()
+                .iter()
+                .filter(|e| matches!(e.kind, TimelineEventKind::Comment { .. }))
+                .count()
+        };
+        assert_eq!(timeline_comments(requester_view.is_technician_or_admin(), &mut conn), 1);
+        assert_eq!(timeline_comments(tech_view.is_technician_or_admin(), &mut conn), 2);
+    }
+
+    #[test]
+    fn merge_moves_content_and_closes_source() {
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+        let mut conn = setup_test_connection();
+        let requester = TestFixtures::create_user(&mut conn, "mergerequester", UserRole::User);
+        let tech = TestFixtures::create_user(&mut conn, "mergetech", UserRole::Technician);
+        let source = TestFixtures::create_ticket(&mut conn, "Email down (dup)", Some(requester.uuid), None);
+        let target = TestFixtures::create_ticket(&mut conn, "Email down", Some(requester.uuid), None);
+        let other = TestFixtures::create_ticket(&mut conn, "Related outage", Some(requester.uuid), None);
+
+        TestFixtures::create_comment(&mut conn, source.id, requester.uuid, "Still broken");
+        let with_file = TestFixtures::create_comment(&mut conn, source.id, tech.uuid, "Log attached");
+        let attachment = TestFixtures::create_attachment(&mut conn, with_file.id, "mail.log");
+        TestFixtures::create_comment(&mut conn, target.id, tech.uuid, "Looking into it");
+
+        // One device only on the source, one on both
+        let device_ids: Vec<i32> = diesel::insert_into(devices::table)
+            .values(&vec![devices::name.eq("Merge Laptop"), devices::name.eq("Merge Phone")])
+            .returning(devices::id)
+            .get_results(&mut conn)
+            .unwrap();
+        add_device_to_ticket(&mut conn, source.id, device_ids[0]).unwrap();
+        add_device_to_ticket(&mut conn, source.id, device_ids[1]).unwrap();
+        add_device_to_ticket(&mut conn, target.id, device_ids[1]).unwrap();
+
+        let project = TestFixtures::create_project(&mut conn, "Merge Project");
+        crate::repository::projects::add_ticket_to_project(&mut conn, project.id, source.id).unwrap();
+
+        // One watcher only on the source, one on both
+        let manager = TestFixtures::create_user(&mut conn, "mergemanager", UserRole::Technician);
+        crate::repository::ticket_watchers::add_watcher(&mut conn, source.id, manager.uuid).unwrap();
+        crate::repository::ticket_watchers::add_watcher(&mut conn, source.id, tech.uuid).unwrap();
+        crate::repository::ticket_watchers::add_watcher(&mut conn, target.id, tech.uuid).unwrap();
+
+        crate::repository::linked_tickets::link_tickets(&mut conn, source.id, other.id).unwrap();
+        crate::repository::linked_tickets::link_tickets(&mut conn, source.id, target.id).unwrap();
+
+        let merge = merge_ticket(&mut conn, source.id, target.id, Some(tech.uuid)).unwrap();
+        assert_eq!(merge.moved_comments.len(), 2);
+
+        // Comments (and their attachments) moved
+        assert!(crate::repository::comments::get_comments_by_ticket_id(&mut conn, source.id, true).unwrap().is_empty());
+        assert_eq!(crate::repository::comments::get_comments_by_ticket_id(&mut conn, target.id, true).unwrap().len(), 3);
+        let moved_attachment = crate::repository::comments::get_attachment_by_id(&mut conn, attachment.id).unwrap();
+        assert_eq!(moved_attachment.comment_id, Some(with_file.id));
+
+        // Devices, projects, watchers and links re-pointed without duplicates or self-links
+        let mut target_devices: Vec<i32> = get_devices_for_ticket(&mut conn, target.id).unwrap().iter().map(|d| d.id).collect();
+        target_devices.sort();
+        assert_eq!(target_devices, device_ids);
+        assert!(get_devices_for_ticket(&mut conn, source.id).unwrap().is_empty());
+        let projects: Vec<i32> = crate::repository::projects::get_projects_for_ticket(&mut conn, target.id)
+            .unwrap()
+            .iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(projects, vec![project.id]);
+        let mut watchers: Vec<Uuid> = crate::repository::ticket_watchers::list_watchers(&mut conn, target.id)
+            .unwrap()
+            .iter()
+            .map(|u| u.uuid)
+            .collect();
+        watchers.sort();
+        let mut expected_watchers = vec![tech.uuid, manager.uuid];
+        expected_watchers.sort();
+        assert_eq!(watchers, expected_watchers);
+        assert!(crate::repository::ticket_watchers::list_watchers(&mut conn, source.id).unwrap().is_empty());
+        // Merge notifications reach the moved watchers
+        assert!(crate::repository::ticket_watchers::notification_recipients(&mut conn, &target, Some(tech.uuid))
+            .unwrap()
+            .contains(&manager.uuid));
+        assert_eq!(crate::repository::linked_tickets::get_linked_tickets(&mut conn, target.id).unwrap(), vec![other.id]);
+        assert_eq!(crate::repository::linked_tickets::get_linked_tickets(&mut conn, other.id).unwrap(), vec![target.id]);
+        assert!(crate::repository::linked_tickets::get_linked_tickets(&mut conn, source.id).unwrap().is_empty());
+
+        // Source is closed and marked merged, with an audit trail on both
+        let closed = get_ticket_by_id(&mut conn, source.id).unwrap();
+        assert_eq!(closed.status, TicketStatus::Closed);
+        assert_eq!(closed.closed_by, Some(tech.uuid));
+        assert_eq!(closed.merged_into_id, Some(target.id));
+        assert!(get_ticket_audit_log(&mut conn, source.id)
+            .unwrap()
+            .iter()
+            .any(|e| e.field == "merged_into" && e.new_value.as_deref() == Some(target.id.to_string().as_str())));
+        assert!(get_ticket_audit_log(&mut conn, target.id)
+            .unwrap()
+            .iter()
+            .any(|e| e.field == "merged_from" && e.new_value.as_deref() == Some(source.id.to_string().as_str())));
+        assert_eq!(merge.target.version, target.version + 1);
+    }
+
+    #[test]
+    fn merge_rejects_self_and_already_merged_tickets() {
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "mergeguard", UserRole::Technician);
+        let first = TestFixtures::create_ticket(&mut conn, "First", Some(user.uuid), None);
+        let second = TestFixtures::create_ticket(&mut conn, "Second", Some(user.uuid), None);
+        let third = TestFixtures::create_ticket(&mut conn, "Third", Some(user.uuid), None);
+
+        assert!(matches!(merge_ticket(&mut conn, first.id, first.id, None), Err(TicketMergeError::SameTicket)));
+
+        merge_ticket(&mut conn, first.id, second.id, None).unwrap();
+        assert!(matches!(
+            merge_ticket(&mut conn, first.id, third.id, None),
+            Err(TicketMergeError::AlreadyMerged(id)) if id == first.id
+        ));
+        assert!(matches!(
+            merge_ticket(&mut conn, third.id, first.id, None),
+            Err(TicketMergeError::AlreadyMerged(id)) if id == first.id
+        ));
+        assert!(matches!(
+            merge_ticket(&mut conn, third.id, i32::MAX, None),
+            Err(TicketMergeError::Update(TicketUpdateError::Database(Error::NotFound)))
+        ));
+    }
+
+    fn import_json(requester: &str, author: &str) -> TicketJson {
+        serde_json::from_value(serde_json::json!({
+            "id": 4120,
+            "title": "VPN drops every hour",
+            "status": "closed",
+            "priority": "high",
+            "created": "2025-11-03T08:30:00Z",
+            "modified": "2025-11-05T17:45:12.250Z",
+            "closed_at": "2025-11-05T17:45:12.250Z",
+            "assignee": "",
+            "requester": requester,
+            "comments": [{
+                "id": 1,
+                "content": "Happens on the office Wi-Fi only",
+                "user_uuid": author,
+                "createdAt": "2025-11-03T09:00:00Z",
+                "is_internal": true,
+                "attachments": [{"url": "/uploads/tickets/vpn.log", "name": "vpn.log"}]
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn import_keeps_timestamps_and_authors() {
+        use crate::services::ticket_json::parse_timestamp;
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+        let mut conn = setup_test_connection();
+        let requester = TestFixtures::create_user(&mut conn, "importrequester", UserRole::User);
+        let tech = TestFixtures::create_user(&mut conn, "importtech", UserRole::Technician);
+
+        let ticket = import_ticket_from_json(
+            &mut conn,
+            &import_json(&requester.uuid.to_string(), &tech.uuid.to_string()),
+        )
+        .unwrap();
+        assert_ne!(ticket.id, 4120);
+        assert_eq!(ticket.status, TicketStatus::Closed);
+        assert_eq!(ticket.requester_uuid, Some(requester.uuid));
+        assert_eq!(ticket.created_at, parse_timestamp("2025-11-03T08:30:00Z").unwrap());
+        assert_eq!(ticket.updated_at, parse_timestamp("2025-11-05T17:45:12.250Z").unwrap());
+        assert_eq!(ticket.closed_at, parse_timestamp("2025-11-05T17:45:12.250Z"));
+
+        let comments = crate::repository::comments::get_comments_by_ticket_id(&mut conn, ticket.id, true).unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].user_uuid, tech.uuid);
+        assert!(comments[0].is_internal);
+        assert_eq!(comments[0].created_at, parse_timestamp("2025-11-03T09:00:00Z").unwrap());
+        let attachments = crate::repository::comments::get_attachments_by_comment_id(&mut conn, comments[0].id).unwrap();
+        assert_eq!(attachments[0].name, "vpn.log");
+    }
+
+    #[test]
+    fn import_rejects_bad_requesters_without_writing() {
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+        let mut conn = setup_test_connection();
+        let tech = TestFixtures::create_user(&mut conn, "rejecttech", UserRole::Technician);
+        let before: i64 = tickets::table.count().get_result(&mut conn).unwrap();
+
+        let rejected = |conn: &mut DbConnection, requester: &str, author: &str| {
+            match import_ticket_from_json(conn, &import_json(requester, author)) {
+                Err(TicketImportError::Invalid(errors)) => errors,
+                other => panic!("expected an invalid import, got {other:?}"),
+            }
+        };
+
+        let errors = rejected(&mut conn, "", &tech.uuid.to_string());
+        assert_eq!(errors, vec![crate::services::category_fields::FieldError::new("requester", "is required")]);
+
+        let errors = rejected(&mut conn, "not-a-uuid", &tech.uuid.to_string());
+        assert_eq!(errors[0].message, "'not-a-uuid' is not a user UUID");
+
+        let unknown = Uuid::now_v7().to_string();
+        let errors = rejected(&mut conn, &unknown, &unknown);
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["requester", "comments[0].user_uuid"]);
+        assert_eq!(errors[0].message, format!("no user with UUID {unknown}"));
+
+        let after: i64 = tickets::table.count().get_result(&mut conn).unwrap();
+        assert_eq!(after, before);
+    }
+
+    #[test]
+    fn exported_tickets_import_unchanged() {
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+        let mut conn = setup_test_connection();
+        let requester = TestFixtures::create_user(&mut conn, "roundtriprequester", UserRole::User);
+        let tech = TestFixtures::create_user(&mut conn, "roundtriptech", UserRole::Technician);
+        let original = import_ticket_from_json(
+            &mut conn,
+            &import_json(&requester.uuid.to_string(), &tech.uuid.to_string()),
+        )
+        .unwrap();
+        diesel::update(tickets::table.find(original.id))
+            .set(tickets::assignee_uuid.eq(Some(tech.uuid)))
+            .execute(&mut conn)
+            .unwrap();
+
+        let exported = export_ticket_to_json(&mut conn, original.id).unwrap();
+        let document = serde_json::json!({ "tickets": [exported] });
+        let parsed = crate::services::ticket_json::parse(&document).unwrap();
+        let copy = import_ticket_from_json(&mut conn, &parsed.tickets[0]).unwrap();
+        assert_ne!(copy.id, original.id);
+
+        // Everything but the ids matches
+        let mut reexported = serde_json::to_value(export_ticket_to_json(&mut conn, copy.id).unwrap()).unwrap();
+        let mut exported = document["tickets"][0].clone();
+        for json in [&mut exported, &mut reexported] {
+            json["id"] = serde_json::Value::Null;
+            json["comments"][0]["id"] = serde_json::Value::Null;
+        }
+        assert_eq!(reexported, exported);
+        assert_eq!(exported["assignee"], tech.uuid.to_string());
+    }
+}
@@ -0,0 +1,80 @@
+//! Transaction Helper
+//!
+//! Multi-step writes go through [`with_transaction`] so a failure part way
+//! through rolls back everything before it.
+//!
+//! Only database work belongs inside the closure. Notifications, SSE events,
+//! search indexing, webhooks and file writes can't be rolled back and would
+//! announce changes that never committed, so run them after
+//! `with_transaction` returns `Ok`.
+
+use diesel::prelude::*;
+use tracing::debug;
+
+use crate::db::DbConnection;
+
+/// Run `f` in a transaction, committing if it returns `Ok` and rolling back
+/// if it returns `Err`
+///
+/// `E` is the caller's own error type; database errors from the closure and
+/// from `BEGIN`/`COMMIT` convert into it through `From<diesel::result::Error>`.
+/// Nested calls become savepoints.
+pub fn with_transaction<T, E, F>(conn: &mut DbConnection, f: F) -> Result<T, E>
+where
+    F: FnOnce(&mut DbConnection) -> Result<T, E>,
+    E: From<diesel::result::Error> + std::fmt::Display,
+{
+    conn.transaction(f).inspect_err(|e| debug!(error = %e, "Transaction rolled back"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NewComment, UserRole};
+    use crate::repository;
+    use crate::schema::{comments, tickets};
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+    #[test]
+    fn failure_mid_transaction_rolls_back_all_writes() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "txnrollback", UserRole::Technician);
+        let title = format!("Rolled back {}", uuid::Uuid::new_v4());
+
+        let result: Result<(), diesel::result::Error> = with_transaction(&mut conn, |conn| {
+            let ticket = TestFixtures::create_ticket(conn, &title, None, None);
+            repository::create_comment(
+                conn,
+                NewComment {
+                    content: "Written before the failure".to_string(),
+                    ticket_id: ticket.id,
+                    user_uuid: user.uuid,
+                    is_internal: false,
+                },
+            )?;
+            // A later step fails
+            Err(diesel::result::Error::NotFound)
+        });
+        assert!(matches!(result, Err(diesel::result::Error::NotFound)));
+
+        let tickets_left: i64 = tickets::table
+            .filter(tickets::title.eq(&title))
+            .count()
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(tickets_left, 0);
+        let comments_left: i64 = comments::table
+            .filter(comments::user_uuid.eq(user.uuid))
+            .count()
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(comments_left, 0);
+
+        // A successful run commits
+        let ticket = with_transaction::<_, diesel::result::Error, _>(&mut conn, |conn| {
+            Ok(TestFixtures::create_ticket(conn, &title, None, None))
+        })
+        .unwrap();
+        assert!(repository::get_ticket_by_id(&mut conn, ticket.id).is_ok());
+    }
+}
@@ -105,7 +105,6 @@ pub fn get_user_uuid_by_external_id(
 }
 
 /// Get user UUID by external ID for a specific provider type
-#[allow(dead_code)]
 pub fn get_user_uuid_by_external_id_and_provider(
     external_id: &str,
     provider_type: &str,
@@ -105,6 +105,121 @@ pub fn find_user_by_any_of_emails(
     Ok(result)
 }
 
+/// Why an email couldn't be made primary
+#[derive(Debug)]
+pub enum SetPrimaryError {
+    /// No such email for this user
+    NotFound,
+    /// Only verified addresses can be primary
+    Unverified,
+    Database(diesel::result::Error),
+}
+
+impl From<diesel::result::Error> for SetPrimaryError {
+    fn from(e: diesel::result::Error) -> Self {
+        Self::Database(e)
+    }
+}
+
+/// Make one of a user's verified emails their primary address
+pub fn set_primary(
+    conn: &mut DbConnection,
+    user_uuid: &Uuid,
+    email_id: i32,
+) -> Result<UserEmail, SetPrimaryError> {
+    conn.transaction(|conn| {
+        let email: UserEmail = user_emails::table
+            .find(email_id)
+            .filter(user_emails::user_uuid.eq(user_uuid))
+            .first(conn)
+            .optional()?
+            .ok_or(SetPrimaryError::NotFound)?;
+
+        if !email.is_verified {
+            return Err(SetPrimaryError::Unverified);
+        }
+
+        diesel::update(
+            user_emails::table
+                .filter(user_emails::user_uuid.eq(user_uuid))
+                .filter(user_emails::id.ne(email_id)),
+        )
+        .set(user_emails::is_primary.eq(false))
+        .execute(conn)?;
+
+        Ok(diesel::update(user_emails::table.find(email_id))
+            .set((
+                user_emails::is_primary.eq(true),
+                user_emails::updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .get_result(conn)?)
+    })
+}
+
+/// Mark an email verified, provided it still belongs to the user and has the
+/// address the verification link was sent to. Returns `None` otherwise.
+pub fn mark_verified(
+    conn: &mut DbConnection,
+    user_uuid: &Uuid,
+    email_id: i32,
+    address: &str,
+) -> Result<Option<UserEmail>, diesel::result::Error> {
+    diesel::update(
+        user_emails::table
+            .find(email_id)
+            .filter(user_emails::user_uuid.eq(user_uuid))
+            .filter(lower(user_emails::email).eq(address.to_lowercase())),
+    )
+    .set((
+        user_emails::is_verified.eq(true),
+        user_emails::updated_at.eq(Utc::now().naive_utc()),
+    ))
+    .get_result(conn)
+    .optional()
+}
+
+diesel::define_sql_function! {
+    fn lower(x: diesel::sql_types::Text) -> diesel::sql_types::Text;
+}
+
+/// Flag an address as hard-bounced (case-insensitive). Addresses already
+/// flagged keep their original timestamp. Returns the number of rows flagged.
+pub fn mark_bounced(conn: &mut DbConnection, address: &str) -> Result<usize, diesel::result::Error> {
+    diesel::update(
+        user_emails::table
+            .filter(lower(user_emails::email).eq(address.trim().to_lowercase()))
+            .filter(user_emails::bounced_at.is_null()),
+    )
+    .set(user_emails::bounced_at.eq(Utc::now().naive_utc()))
+    .execute(conn)
+}
+
+/// Flag an address as having reported our email as spam (case-insensitive).
+/// Returns the number of rows flagged.
+pub fn mark_complained(conn: &mut DbConnection, address: &str) -> Result<usize, diesel::result::Error> {
+    diesel::update(
+        user_emails::table
+            .filter(lower(user_emails::email).eq(address.trim().to_lowercase()))
+            .filter(user_emails::complained_at.is_null()),
+    )
+    .set(user_emails::complained_at.eq(Utc::now().naive_utc()))
+    .execute(conn)
+}
+
+/// Clear the bounce and complaint flags so email delivery resumes
+pub fn clear_delivery_flags(
+    conn: &mut DbConnection,
+    email_id: i32,
+) -> Result<UserEmail, diesel::result::Error> {
+    diesel::update(user_emails::table.find(email_id))
+        .set((
+            user_emails::bounced_at.eq(None::<chrono::NaiveDateTime>),
+            user_emails::complained_at.eq(None::<chrono::NaiveDateTime>),
+            user_emails::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .get_result(conn)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,4 +260,60 @@ mod tests {
         let found = find_user_by_any_email(&mut conn, "test@example.com").unwrap();
         assert_eq!(found.uuid, user.uuid);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn mark_bounced_matches_case_insensitively_and_clears() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "bounceuser", UserRole::User);
+        let email = TestFixtures::create_user_email(&mut conn, user.uuid, "Bounce_Me@Example.com", true);
+        TestFixtures::create_user_email(&mut conn, user.uuid, "bouncexme@example.com", false);
+
+        assert_eq!(mark_bounced(&mut conn, "bounce_me@example.COM").unwrap(), 1);
+        // Already flagged, so the original timestamp is kept
+        assert_eq!(mark_bounced(&mut conn, "bounce_me@example.com").unwrap(), 0);
+
+        let emails = get_user_emails_by_uuid(&mut conn, &user.uuid).unwrap();
+        let flagged: Vec<&str> = emails.iter().filter(|e| e.is_suppressed()).map(|e| e.email.as_str()).collect();
+        assert_eq!(flagged, vec!["Bounce_Me@Example.com"]);
+
+        assert_eq!(mark_complained(&mut conn, "bounce_me@example.com").unwrap(), 1);
+        let cleared = clear_delivery_flags(&mut conn, email.id).unwrap();
+        assert!(cleared.bounced_at.is_none());
+        assert!(cleared.complained_at.is_none());
+    }
+
+    #[test]
+    fn only_verified_emails_can_be_made_primary() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "primaryguard", UserRole::User);
+        let other = TestFixtures::create_user(&mut conn, "primaryother", UserRole::User);
+        TestFixtures::create_user_email(&mut conn, user.uuid, "original@example.com", true);
+        let verified = TestFixtures::create_user_email(&mut conn, user.uuid, "verified@example.com", false);
+        let unverified: UserEmail = diesel::insert_into(user_emails::table)
+            .values(&NewUserEmail {
+                user_uuid: user.uuid,
+                email: "unverified@example.com".to_string(),
+                email_type: "personal".to_string(),
+                is_primary: false,
+                is_verified: false,
+                source: Some("manual".to_string()),
+            })
+            .get_result(&mut conn)
+            .unwrap();
+
+        assert!(matches!(
+            set_primary(&mut conn, &user.uuid, unverified.id),
+            Err(SetPrimaryError::Unverified)
+        ));
+        assert!(matches!(
+            set_primary(&mut conn, &other.uuid, verified.id),
+            Err(SetPrimaryError::NotFound)
+        ));
+
+        let promoted = set_primary(&mut conn, &user.uuid, verified.id).unwrap();
+        assert!(promoted.is_primary);
+        let emails = get_user_emails_by_uuid(&mut conn, &user.uuid).unwrap();
+        let primaries: Vec<i32> = emails.iter().filter(|e| e.is_primary).map(|e| e.id).collect();
+        assert_eq!(primaries, vec![verified.id]);
+    }
+}
@@ -81,9 +81,12 @@ pub fn get_user_with_primary_email(
         banner_url: user.banner_url,
         avatar_thumb: user.avatar_thumb,
         theme: user.theme,
+        locale: user.locale,
         microsoft_uuid: user.microsoft_uuid,
         created_at: user.created_at,
         updated_at: user.updated_at,
+        deactivated_at: user.deactivated_at,
+        timezone: user.timezone,
     }
 }
 
@@ -129,9 +132,12 @@ pub fn get_users_with_primary_emails(
             banner_url: user.banner_url,
             avatar_thumb: user.avatar_thumb,
             theme: user.theme,
+            locale: user.locale,
             microsoft_uuid: user.microsoft_uuid,
             created_at: user.created_at,
             updated_at: user.updated_at,
+            deactivated_at: user.deactivated_at,
+            timezone: user.timezone,
         }
     }).collect()
 }
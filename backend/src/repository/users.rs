@@ -325,6 +325,138 @@ pub fn delete_user(user_uuid: &Uuid, conn: &mut DbConnection) -> Result<usize, E
     })
 }
 
+/// What `deactivate_or_delete_user` did with a user
+#[derive(Debug, PartialEq, Eq)]
+pub enum UserRemoval {
+    /// Nothing referred to the user, so they were deleted outright
+    Deleted,
+    /// The user was kept as a tombstone; these open tickets were reassigned
+    Deactivated { reassigned_tickets: Vec<i32> },
+}
+
+/// Whether tickets, comments, worklogs, attachments, docs or the ticket
+/// audit log attribute anything to this user
+fn has_history(conn: &mut DbConnection, user_uuid: &Uuid) -> Result<bool, Error> {
+    use diesel::dsl::exists;
+
+    let checks = [
+        diesel::select(exists(comments::table.filter(comments::user_uuid.eq(user_uuid)))).get_result::<bool>(conn)?,
+        diesel::select(exists(
+            tickets::table.filter(
+                tickets::requester_uuid
+                    .eq(user_uuid)
+                    .or(tickets::assignee_uuid.eq(user_uuid))
+                    .or(tickets::created_by.eq(user_uuid))
+                    .or(tickets::closed_by.eq(user_uuid)),
+            ),
+        ))
+        .get_result(conn)?,
+        diesel::select(exists(ticket_worklogs::table.filter(ticket_worklogs::user_uuid.eq(user_uuid)))).get_result(conn)?,
+        diesel::select(exists(ticket_audit_log::table.filter(ticket_audit_log::changed_by.eq(user_uuid)))).get_result(conn)?,
+        diesel::select(exists(attachments::table.filter(attachments::uploaded_by.eq(user_uuid)))).get_result(conn)?,
+        diesel::select(exists(
+            documentation_pages::table.filter(
+                documentation_pages::created_by
+                    .eq(user_uuid)
+                    .or(documentation_pages::last_edited_by.eq(user_uuid)),
+            ),
+        ))
+        .get_result(conn)?,
+    ];
+    Ok(checks.contains(&true))
+}
+
+/// Remove a user without orphaning anything attributed to them
+///
+/// A user nothing refers to is deleted outright. Otherwise the account is
+/// deactivated instead: the record stays as a tombstone so requesters,
+/// comment authors, `created_by`/`closed_by` and audit entries keep resolving
+/// a name, while sign-in methods, sessions, tokens, group memberships and
+/// watches are removed. Their open tickets go to a random technician in
+/// `reassign_group_id` when given, or back to the unassigned queue.
+///
+/// Runs in one transaction. Reindex the reassigned tickets after it returns.
+pub fn deactivate_or_delete_user(
+    conn: &mut DbConnection,
+    user_uuid: &Uuid,
+    reassign_group_id: Option<i32>,
+    changed_by: Option<Uuid>,
+) -> Result<UserRemoval, Error> {
+    use rand::seq::SliceRandom;
+
+    crate::repository::with_transaction(conn, |conn| {
+        let user: User = users::table.find(user_uuid).for_update().first(conn)?;
+
+        if !has_history(conn, user_uuid)? {
+            delete_user(user_uuid, conn)?;
+            return Ok(UserRemoval::Deleted);
+        }
+
+        // Open tickets still waiting on them go to the group or the queue
+        let candidates: Vec<Uuid> = match reassign_group_id {
+            Some(group_id) => crate::repository::groups::get_users_in_group(conn, group_id)?
+                .into_iter()
+                .filter(|member| member.uuid != user.uuid && member.deactivated_at.is_none())
+                .filter(|member| member.role != UserRole::User)
+                .map(|member| member.uuid)
+                .collect(),
+            None => Vec::new(),
+        };
+        let open_tickets: Vec<i32> = tickets::table
+            .filter(tickets::assignee_uuid.eq(user_uuid))
+            .filter(tickets::status.ne(TicketStatus::Closed))
+            .select(tickets::id)
+            .into_boxed()
+            .load(conn)?;
+        let mut rng = rand::thread_rng();
+        for &ticket_id in &open_tickets {
+            let update = TicketUpdate {
+                assignee_uuid: Some(candidates.choose(&mut rng).copied()),
+                updated_at: Some(chrono::Utc::now().naive_utc()),
+                ..Default::default()
+            };
+            match crate::repository::update_ticket_partial(conn, ticket_id, update, None, changed_by) {
+                Ok(_) => {}
+                Err(crate::repository::TicketUpdateError::Database(e)) => return Err(e),
+                // No version is expected, so there's nothing to conflict with
+                Err(crate::repository::TicketUpdateError::Conflict) => return Err(Error::RollbackTransaction),
+            }
+        }
+
+        // Nothing can sign in as them any more
+        diesel::delete(user_auth_identities::table.filter(user_auth_identities::user_uuid.eq(user_uuid)))
+            .execute(conn)?;
+        diesel::delete(active_sessions::table.filter(active_sessions::user_uuid.eq(user_uuid))).execute(conn)?;
+        diesel::delete(refresh_tokens::table.filter(refresh_tokens::user_uuid.eq(user_uuid))).execute(conn)?;
+        diesel::delete(reset_tokens::table.filter(reset_tokens::user_uuid.eq(user_uuid))).execute(conn)?;
+        let now = chrono::Utc::now().naive_utc();
+        diesel::update(
+            api_tokens::table
+                .filter(api_tokens::user_uuid.eq(user_uuid))
+                .filter(api_tokens::revoked_at.is_null()),
+        )
+        .set(api_tokens::revoked_at.eq(Some(now)))
+        .execute(conn)?;
+
+        // Or be picked for or notified about new work
+        diesel::delete(user_groups::table.filter(user_groups::user_uuid.eq(user_uuid))).execute(conn)?;
+        diesel::delete(ticket_watchers::table.filter(ticket_watchers::user_uuid.eq(user_uuid))).execute(conn)?;
+
+        diesel::update(users::table.find(user_uuid))
+            .set((
+                users::deactivated_at.eq(Some(now)),
+                users::mfa_secret.eq::<Option<String>>(None),
+                users::mfa_enabled.eq(false),
+                users::mfa_backup_codes.eq::<Option<serde_json::Value>>(None),
+                users::passkey_credentials.eq::<Option<serde_json::Value>>(None),
+                users::updated_at.eq(now),
+            ))
+            .execute(conn)?;
+
+        Ok(UserRemoval::Deactivated { reassigned_tickets: open_tickets })
+    })
+}
+
 // Batch get users by UUIDs
 pub fn get_users_by_uuids(uuids: &[Uuid], conn: &mut DbConnection) -> Result<Vec<User>, Error> {
     users::table
@@ -333,6 +465,24 @@ pub fn get_users_by_uuids(uuids: &[Uuid], conn: &mut DbConnection) -> Result<Vec
         .load::<User>(conn)
 }
 
+/// User that automated actions (auto-close, imports) are attributed to
+pub const SYSTEM_USER_UUID: Uuid = Uuid::from_u128(1);
+
+/// Get the system user, creating it on first use. It has no login identity,
+/// so it can't sign in.
+pub fn ensure_system_user(conn: &mut DbConnection) -> Result<User, Error> {
+    diesel::insert_into(users::table)
+        .values((
+            users::uuid.eq(SYSTEM_USER_UUID),
+            users::name.eq("System"),
+            users::role.eq(UserRole::User),
+        ))
+        .on_conflict(users::uuid)
+        .do_nothing()
+        .execute(conn)?;
+    get_user_by_uuid(&SYSTEM_USER_UUID, conn)
+}
+
 // Count total users in the database (for onboarding check)
 pub fn count_users(conn: &mut DbConnection) -> Result<i64, Error> {
     users::table.count().get_result(conn)
@@ -349,6 +499,22 @@ pub fn update_user_mfa(
         .get_result(conn)
 }
 
+/// Users' stored (encrypted) MFA secrets, for re-encryption after a key rotation
+pub fn get_mfa_secrets(conn: &mut DbConnection) -> Result<Vec<(Uuid, String)>, Error> {
+    users::table
+        .filter(users::mfa_secret.is_not_null())
+        .select((users::uuid, users::mfa_secret.assume_not_null()))
+        .order(users::uuid.asc())
+        .load(conn)
+}
+
+/// Replace a user's stored MFA secret with a re-encrypted one
+pub fn update_mfa_secret(conn: &mut DbConnection, uuid: &Uuid, encrypted: &str) -> Result<usize, Error> {
+    diesel::update(users::table.filter(users::uuid.eq(uuid)))
+        .set(users::mfa_secret.eq(Some(encrypted)))
+        .execute(conn)
+}
+
 /// Update user passkey credentials by UUID
 pub fn update_user_passkey_credentials(
     conn: &mut DbConnection,
@@ -367,6 +533,34 @@ pub fn update_user_passkey_credentials(
         .get_result(conn)
 }
 
+/// Update user's preferred notification locale by UUID
+pub fn update_user_locale(
+    conn: &mut DbConnection,
+    uuid: &Uuid,
+    locale: Option<&str>,
+) -> Result<User, Error> {
+    diesel::update(users::table.filter(users::uuid.eq(uuid)))
+        .set((
+            users::locale.eq(locale),
+            users::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .get_result(conn)
+}
+
+/// Update the zone a user's calendar date filters are interpreted in
+pub fn update_user_timezone(
+    conn: &mut DbConnection,
+    uuid: &Uuid,
+    timezone: Option<&str>,
+) -> Result<User, Error> {
+    diesel::update(users::table.filter(users::uuid.eq(uuid)))
+        .set((
+            users::timezone.eq(timezone),
+            users::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .get_result(conn)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -459,4 +653,59 @@ mod tests {
         let results = get_users_by_uuids(&[u1.uuid, u2.uuid], &mut conn).unwrap();
         assert_eq!(results.len(), 2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn deactivated_user_still_authors_their_comments() {
+        let mut conn = setup_test_connection();
+        let tech = TestFixtures::create_user(&mut conn, "Departing Tech", UserRole::Technician);
+        let colleague = TestFixtures::create_user(&mut conn, "Queue Tech", UserRole::Technician);
+        let group = TestFixtures::create_group(&mut conn, "Deactivation Queue");
+        crate::repository::groups::add_user_to_group(&mut conn, colleague.uuid, group.id, None).unwrap();
+        crate::repository::groups::add_user_to_group(&mut conn, tech.uuid, group.id, None).unwrap();
+
+        let open = TestFixtures::create_ticket(&mut conn, "Still open", None, None);
+        let closed = TestFixtures::create_ticket(&mut conn, "Already done", None, None);
+        for ticket_id in [open.id, closed.id] {
+            diesel::update(tickets::table.find(ticket_id))
+                .set((tickets::assignee_uuid.eq(tech.uuid), tickets::created_by.eq(tech.uuid)))
+                .execute(&mut conn)
+                .unwrap();
+        }
+        diesel::update(tickets::table.find(closed.id))
+            .set((tickets::status.eq(TicketStatus::Closed), tickets::closed_by.eq(tech.uuid)))
+            .execute(&mut conn)
+            .unwrap();
+        let comment = TestFixtures::create_comment(&mut conn, open.id, tech.uuid, "Swapped the toner");
+
+        let removal = deactivate_or_delete_user(&mut conn, &tech.uuid, Some(group.id), None).unwrap();
+        assert_eq!(removal, UserRemoval::Deactivated { reassigned_tickets: vec![open.id] });
+
+        let tombstone = get_user_by_uuid(&tech.uuid, &mut conn).unwrap();
+        assert!(tombstone.deactivated_at.is_some());
+        assert!(crate::repository::groups::get_users_in_group(&mut conn, group.id)
+            .unwrap()
+            .iter()
+            .all(|member| member.uuid != tech.uuid));
+
+        // History keeps resolving to them; open work moved on
+        let complete = crate::repository::get_complete_ticket(&mut conn, open.id, true).unwrap();
+        let authored = complete.comments.iter().find(|c| c.comment.id == comment.id).unwrap();
+        assert_eq!(authored.user.as_ref().map(|u| u.name.as_str()), Some("Departing Tech"));
+        assert_eq!(complete.ticket.assignee_uuid, Some(colleague.uuid));
+        assert_eq!(complete.ticket.created_by, Some(tech.uuid));
+
+        let closed = crate::repository::get_ticket_by_id(&mut conn, closed.id).unwrap();
+        assert_eq!(closed.assignee_uuid, Some(tech.uuid));
+        assert_eq!(closed.closed_by, Some(tech.uuid));
+    }
+
+    #[test]
+    fn user_without_history_is_deleted() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "Never Used", UserRole::User);
+
+        let removal = deactivate_or_delete_user(&mut conn, &user.uuid, None, None).unwrap();
+        assert_eq!(removal, UserRemoval::Deleted);
+        assert!(get_user_by_uuid(&user.uuid, &mut conn).is_err());
+    }
+}
@@ -143,11 +143,23 @@ pub fn get_deliveries_for_webhook(
     webhook_deliveries::table
         .filter(webhook_deliveries::webhook_id.eq(webhook_id))
         .order(webhook_deliveries::created_at.desc())
+        .then_order_by(webhook_deliveries::id.desc())
         .limit(limit)
         .offset(offset)
         .load::<WebhookDelivery>(conn)
 }
 
+/// Count deliveries for a webhook
+pub fn count_deliveries_for_webhook(
+    conn: &mut DbConnection,
+    webhook_id: i32,
+) -> Result<i64, diesel::result::Error> {
+    webhook_deliveries::table
+        .filter(webhook_deliveries::webhook_id.eq(webhook_id))
+        .count()
+        .get_result(conn)
+}
+
 /// Get pending retries (deliveries with next_retry_at in the past and not yet delivered)
 pub fn get_pending_retries(conn: &mut DbConnection) -> Result<Vec<WebhookDelivery>, String> {
     let now = Utc::now().naive_utc();
@@ -281,4 +293,43 @@ mod tests {
         assert!(!hooks.is_empty());
         assert!(hooks.iter().any(|w| w.name == "Event Hook"));
     }
+
+    #[test]
+    fn deliveries_are_counted_and_paged() {
+        let mut conn = setup_test_connection();
+
+        let wh = create_webhook(
+            &mut conn,
+            "Paged Hook".into(),
+            "https://example.com/paged".into(),
+            "s".into(),
+            vec!["ticket.created".into()],
+            None,
+            None,
+        )
+        .unwrap();
+        let ids: Vec<i32> = (1..=5)
+            .map(|attempt| {
+                let delivery = NewWebhookDelivery {
+                    webhook_id: wh.id,
+                    event_type: "ticket.created".into(),
+                    payload: serde_json::json!({ "attempt": attempt }),
+                    request_headers: None,
+                    attempt_number: attempt,
+                };
+                create_delivery(&mut conn, delivery).unwrap().id
+            })
+            .collect();
+
+        assert_eq!(count_deliveries_for_webhook(&mut conn, wh.id).unwrap(), 5);
+
+        // Newest first: offset 1, limit 2 skips the last insert
+        let page: Vec<i32> = get_deliveries_for_webhook(&mut conn, wh.id, 2, 1)
+            .unwrap()
+            .iter()
+            .map(|d| d.id)
+            .collect();
+        assert_eq!(page, vec![ids[3], ids[2]]);
+        assert_eq!(get_deliveries_for_webhook(&mut conn, wh.id, 10, 4).unwrap().len(), 1);
+    }
 }
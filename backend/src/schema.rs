@@ -26,6 +26,16 @@ pub mod sql_types {
     pub struct UserRole;
 }
 
+diesel::table! {
+    account_lockouts (user_uuid) {
+        user_uuid -> Uuid,
+        failed_attempts -> Int4,
+        window_started_at -> Timestamptz,
+        locked_until -> Nullable<Timestamptz>,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     active_sessions (id) {
         id -> Int4,
@@ -143,6 +153,7 @@ diesel::table! {
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         created_by -> Nullable<Uuid>,
+        trigger_on_sla_breach -> Bool,
     }
 }
 
@@ -165,6 +176,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    audit_log (id) {
+        id -> Int4,
+        actor_uuid -> Nullable<Uuid>,
+        #[max_length = 50]
+        action -> Varchar,
+        #[max_length = 50]
+        target_type -> Nullable<Varchar>,
+        #[max_length = 255]
+        target_id -> Nullable<Varchar>,
+        metadata -> Jsonb,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     backup_jobs (id) {
         id -> Uuid,
@@ -182,6 +208,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    canned_responses (id) {
+        id -> Int4,
+        #[max_length = 255]
+        title -> Varchar,
+        body -> Text,
+        category_id -> Nullable<Int4>,
+        is_shared -> Bool,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     category_group_visibility (category_id, group_id) {
         category_id -> Int4,
@@ -201,6 +241,36 @@ diesel::table! {
         updated_at -> Timestamptz,
         is_edited -> Bool,
         edit_count -> Int4,
+        is_internal -> Bool,
+    }
+}
+
+diesel::table! {
+    custom_field_definitions (id) {
+        id -> Int4,
+        #[max_length = 50]
+        key -> Varchar,
+        #[max_length = 100]
+        label -> Varchar,
+        #[max_length = 20]
+        field_type -> Varchar,
+        options -> Jsonb,
+        category_id -> Nullable<Int4>,
+        display_order -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        created_by -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    device_assignment_history (id) {
+        id -> Int4,
+        device_id -> Int4,
+        user_uuid -> Uuid,
+        assigned_by -> Nullable<Uuid>,
+        assigned_at -> Timestamptz,
+        unassigned_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -215,6 +285,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    device_warranty_notifications (device_id, warranty_expiry_date) {
+        device_id -> Int4,
+        warranty_expiry_date -> Date,
+        notified_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     devices (id) {
         id -> Int4,
@@ -254,6 +332,16 @@ diesel::table! {
         os_version -> Nullable<Varchar>,
         is_managed -> Nullable<Bool>,
         enrollment_date -> Nullable<Timestamptz>,
+        warranty_expiry_date -> Nullable<Date>,
+    }
+}
+
+diesel::table! {
+    doc_group_visibility (page_id, group_id) {
+        page_id -> Int4,
+        group_id -> Int4,
+        created_at -> Timestamptz,
+        created_by -> Nullable<Uuid>,
     }
 }
 
@@ -330,6 +418,32 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    idempotency_keys (user_uuid, scope, idempotency_key) {
+        user_uuid -> Uuid,
+        #[max_length = 255]
+        scope -> Varchar,
+        #[max_length = 255]
+        idempotency_key -> Varchar,
+        #[max_length = 64]
+        request_hash -> Varchar,
+        resource_id -> Nullable<Int4>,
+        response_status -> Nullable<Int4>,
+        response_body -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    known_devices (user_uuid, fingerprint) {
+        user_uuid -> Uuid,
+        #[max_length = 255]
+        fingerprint -> Varchar,
+        first_seen_at -> Timestamptz,
+        last_seen_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     linked_tickets (ticket_id, linked_ticket_id) {
         ticket_id -> Int4,
@@ -399,6 +513,21 @@ diesel::table! {
         is_read -> Bool,
         read_at -> Nullable<Timestamptz>,
         created_at -> Timestamptz,
+        #[max_length = 100]
+        group_key -> Nullable<Varchar>,
+        collapsed_count -> Int4,
+    }
+}
+
+diesel::table! {
+    permission_grants (id) {
+        id -> Int4,
+        #[max_length = 50]
+        permission -> Varchar,
+        user_uuid -> Nullable<Uuid>,
+        group_id -> Nullable<Int4>,
+        granted_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
     }
 }
 
@@ -428,6 +557,7 @@ diesel::table! {
         is_secret -> Bool,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        user_uuid -> Nullable<Uuid>,
     }
 }
 
@@ -455,6 +585,8 @@ diesel::table! {
         bundle_uploaded_at -> Nullable<Timestamptz>,
         #[max_length = 20]
         source -> Varchar,
+        #[max_length = 100]
+        verified_publisher -> Nullable<Varchar>,
     }
 }
 
@@ -496,6 +628,7 @@ diesel::table! {
         created_at -> Timestamptz,
         expires_at -> Timestamptz,
         revoked_at -> Nullable<Timestamptz>,
+        session_id -> Nullable<Int4>,
     }
 }
 
@@ -553,6 +686,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::TicketPriority;
+
+    sla_targets (priority) {
+        priority -> TicketPriority,
+        first_response_minutes -> Nullable<Int4>,
+        resolution_minutes -> Nullable<Int4>,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     sync_delta_tokens (id) {
         id -> Int4,
@@ -587,6 +732,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    ticket_audit_log (id) {
+        id -> Int4,
+        ticket_id -> Int4,
+        #[max_length = 50]
+        field -> Varchar,
+        old_value -> Nullable<Text>,
+        new_value -> Nullable<Text>,
+        changed_by -> Nullable<Uuid>,
+        changed_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     ticket_categories (id) {
         id -> Int4,
@@ -603,6 +761,19 @@ diesel::table! {
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         created_by -> Nullable<Uuid>,
+        default_assignee_uuid -> Nullable<Uuid>,
+        default_group_id -> Nullable<Int4>,
+        required_fields -> Jsonb,
+    }
+}
+
+diesel::table! {
+    ticket_custom_field_values (ticket_id, field_id) {
+        ticket_id -> Int4,
+        field_id -> Int4,
+        value -> Text,
+        updated_at -> Timestamptz,
+        updated_by -> Nullable<Uuid>,
     }
 }
 
@@ -615,6 +786,35 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    ticket_sla_breaches (ticket_id, metric) {
+        ticket_id -> Int4,
+        #[max_length = 32]
+        metric -> Varchar,
+        breached_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    ticket_watchers (ticket_id, user_uuid) {
+        ticket_id -> Int4,
+        user_uuid -> Uuid,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    ticket_worklogs (id) {
+        id -> Int4,
+        ticket_id -> Int4,
+        user_uuid -> Uuid,
+        minutes -> Int4,
+        note -> Nullable<Text>,
+        logged_at -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::TicketStatus;
@@ -634,6 +834,10 @@ diesel::table! {
         closed_at -> Nullable<Timestamptz>,
         closed_by -> Nullable<Uuid>,
         category_id -> Nullable<Int4>,
+        version -> Int4,
+        merged_into_id -> Nullable<Int4>,
+        first_response_at -> Nullable<Timestamptz>,
+        legal_hold -> Bool,
     }
 }
 
@@ -671,6 +875,8 @@ diesel::table! {
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         created_by -> Nullable<Uuid>,
+        bounced_at -> Nullable<Timestamptz>,
+        complained_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -680,6 +886,7 @@ diesel::table! {
         group_id -> Int4,
         created_at -> Timestamptz,
         created_by -> Nullable<Uuid>,
+        is_manager -> Bool,
     }
 }
 
@@ -722,6 +929,11 @@ diesel::table! {
         mfa_enabled -> Bool,
         mfa_backup_codes -> Nullable<Jsonb>,
         passkey_credentials -> Nullable<Jsonb>,
+        #[max_length = 10]
+        locale -> Nullable<Varchar>,
+        deactivated_at -> Nullable<Timestamptz>,
+        #[max_length = 64]
+        timezone -> Nullable<Varchar>,
     }
 }
 
@@ -764,9 +976,12 @@ diesel::table! {
         last_triggered_at -> Nullable<Timestamptz>,
         failure_count -> Int4,
         disabled_reason -> Nullable<Text>,
+        consecutive_timeouts -> Int4,
+        circuit_opened_at -> Nullable<Timestamptz>,
     }
 }
 
+diesel::joinable!(account_lockouts -> users (user_uuid));
 diesel::joinable!(active_sessions -> users (user_uuid));
 diesel::joinable!(article_content_revisions -> article_contents (article_content_id));
 diesel::joinable!(article_contents -> tickets (ticket_id));
@@ -774,23 +989,35 @@ diesel::joinable!(assignment_log -> assignment_rules (rule_id));
 diesel::joinable!(assignment_log -> tickets (ticket_id));
 diesel::joinable!(assignment_rule_state -> assignment_rules (rule_id));
 diesel::joinable!(assignment_rule_state -> users (last_assigned_user_uuid));
+diesel::joinable!(audit_log -> users (actor_uuid));
 diesel::joinable!(assignment_rules -> groups (target_group_id));
 diesel::joinable!(assignment_rules -> ticket_categories (category_id));
 diesel::joinable!(attachments -> comments (comment_id));
 diesel::joinable!(attachments -> users (uploaded_by));
 diesel::joinable!(backup_jobs -> users (created_by));
+diesel::joinable!(canned_responses -> ticket_categories (category_id));
+diesel::joinable!(canned_responses -> users (created_by));
 diesel::joinable!(category_group_visibility -> groups (group_id));
 diesel::joinable!(category_group_visibility -> ticket_categories (category_id));
 diesel::joinable!(category_group_visibility -> users (created_by));
 diesel::joinable!(comments -> tickets (ticket_id));
 diesel::joinable!(comments -> users (user_uuid));
+diesel::joinable!(custom_field_definitions -> ticket_categories (category_id));
+diesel::joinable!(custom_field_definitions -> users (created_by));
+diesel::joinable!(device_assignment_history -> devices (device_id));
 diesel::joinable!(device_groups -> devices (device_id));
 diesel::joinable!(device_groups -> groups (group_id));
 diesel::joinable!(device_groups -> users (created_by));
+diesel::joinable!(device_warranty_notifications -> devices (device_id));
+diesel::joinable!(doc_group_visibility -> documentation_pages (page_id));
+diesel::joinable!(doc_group_visibility -> groups (group_id));
+diesel::joinable!(doc_group_visibility -> users (created_by));
 diesel::joinable!(documentation_pages -> tickets (ticket_id));
 diesel::joinable!(documentation_revisions -> documentation_pages (page_id));
 diesel::joinable!(documentation_revisions -> users (created_by));
 diesel::joinable!(groups -> users (created_by));
+diesel::joinable!(idempotency_keys -> users (user_uuid));
+diesel::joinable!(known_devices -> users (user_uuid));
 diesel::joinable!(linked_tickets -> users (created_by));
 diesel::joinable!(notification_preferences -> notification_types (notification_type_id));
 diesel::joinable!(notification_preferences -> users (user_uuid));
@@ -798,23 +1025,37 @@ diesel::joinable!(notification_rate_limits -> notification_types (notification_t
 diesel::joinable!(notification_rate_limits -> users (user_uuid));
 diesel::joinable!(notifications -> notification_types (notification_type_id));
 diesel::joinable!(notifications -> users (user_uuid));
+diesel::joinable!(permission_grants -> groups (group_id));
 diesel::joinable!(plugin_activity -> plugins (plugin_id));
 diesel::joinable!(plugin_activity -> users (user_uuid));
 diesel::joinable!(plugin_data -> plugins (plugin_id));
+diesel::joinable!(plugin_data -> users (user_uuid));
 diesel::joinable!(plugins -> users (installed_by));
 diesel::joinable!(project_tickets -> projects (project_id));
 diesel::joinable!(project_tickets -> tickets (ticket_id));
 diesel::joinable!(project_tickets -> users (created_by));
+diesel::joinable!(refresh_tokens -> active_sessions (session_id));
 diesel::joinable!(refresh_tokens -> users (user_uuid));
 diesel::joinable!(reset_tokens -> users (user_uuid));
 diesel::joinable!(security_events -> active_sessions (session_id));
 diesel::joinable!(security_events -> users (user_uuid));
 diesel::joinable!(site_settings -> users (updated_by));
 diesel::joinable!(sync_history -> users (initiated_by));
+diesel::joinable!(ticket_audit_log -> tickets (ticket_id));
+diesel::joinable!(ticket_audit_log -> users (changed_by));
+diesel::joinable!(ticket_categories -> groups (default_group_id));
 diesel::joinable!(ticket_categories -> users (created_by));
+diesel::joinable!(ticket_custom_field_values -> custom_field_definitions (field_id));
+diesel::joinable!(ticket_custom_field_values -> tickets (ticket_id));
+diesel::joinable!(ticket_custom_field_values -> users (updated_by));
 diesel::joinable!(ticket_devices -> devices (device_id));
 diesel::joinable!(ticket_devices -> tickets (ticket_id));
 diesel::joinable!(ticket_devices -> users (created_by));
+diesel::joinable!(ticket_sla_breaches -> tickets (ticket_id));
+diesel::joinable!(ticket_watchers -> tickets (ticket_id));
+diesel::joinable!(ticket_watchers -> users (user_uuid));
+diesel::joinable!(ticket_worklogs -> tickets (ticket_id));
+diesel::joinable!(ticket_worklogs -> users (user_uuid));
 diesel::joinable!(tickets -> ticket_categories (category_id));
 diesel::joinable!(user_groups -> groups (group_id));
 diesel::joinable!(user_ticket_views -> tickets (ticket_id));
@@ -823,4 +1064,4 @@ diesel::joinable!(webhook_deliveries -> webhooks (webhook_id));
 diesel::joinable!(webhooks -> users (created_by));
 
 diesel::allow_tables_to_appear_in_same_query!(
-    active_sessions,api_tokens,article_content_revisions,article_contents,assignment_log,assignment_rule_state,assignment_rules,attachments,backup_jobs,category_group_visibility,comments,device_groups,devices,documentation_pages,documentation_revisions,groups,linked_tickets,notification_preferences,notification_rate_limits,notification_types,notifications,plugin_activity,plugin_data,plugins,project_tickets,projects,refresh_tokens,reset_tokens,security_events,site_settings,sync_delta_tokens,sync_history,ticket_categories,ticket_devices,tickets,user_auth_identities,user_emails,user_groups,user_ticket_views,users,webhook_deliveries,webhooks,);
+    account_lockouts,active_sessions,api_tokens,article_content_revisions,article_contents,assignment_log,assignment_rule_state,assignment_rules,attachments,audit_log,backup_jobs,canned_responses,category_group_visibility,comments,custom_field_definitions,device_assignment_history,device_groups,device_warranty_notifications,devices,doc_group_visibility,documentation_pages,documentation_revisions,groups,idempotency_keys,known_devices,linked_tickets,notification_preferences,notification_rate_limits,notification_types,notifications,permission_grants,plugin_activity,plugin_data,plugins,project_tickets,projects,refresh_tokens,reset_tokens,security_events,site_settings,sla_targets,sync_delta_tokens,sync_history,ticket_audit_log,ticket_categories,ticket_custom_field_values,ticket_devices,ticket_sla_breaches,ticket_watchers,ticket_worklogs,tickets,user_auth_identities,user_emails,user_groups,user_ticket_views,users,webhook_deliveries,webhooks,);
@@ -2,6 +2,8 @@
 //!
 //! Handles automatic ticket assignment based on configurable rules.
 //! Supports multiple assignment methods: direct user, round-robin, random, and group queue.
+//! When no rule matches, a ticket falls back to its category's default
+//! assignee (or a member of the category's default group).
 
 use chrono::Utc;
 use diesel::prelude::*;
@@ -13,6 +15,9 @@ use crate::db::DbConnection;
 use crate::models::*;
 use crate::schema::*;
 
+/// Rule name recorded for assignments made from a category default
+pub const CATEGORY_DEFAULT_RULE: &str = "category_default";
+
 /// Assignment Engine for automatic ticket routing
 pub struct AssignmentEngine;
 
@@ -20,7 +25,8 @@ impl AssignmentEngine {
     /// Evaluate all active rules for a ticket and return the first matching assignment
     ///
     /// Rules are evaluated in priority order (lower priority number = higher priority).
-    /// The first matching rule wins.
+    /// The first matching rule wins. If none matches on create or category
+    /// change, the ticket's category default is used.
     pub fn evaluate_rules(
         conn: &mut DbConnection,
         ticket: &Ticket,
@@ -64,7 +70,7 @@ impl AssignmentEngine {
                 );
 
                 return Some(AssignmentResult {
-                    rule_id: rule.id,
+                    rule_id: Some(rule.id),
                     rule_name: rule.name.clone(),
                     assigned_user_uuid: assigned_user,
                     method: rule.method,
@@ -72,14 +78,65 @@ impl AssignmentEngine {
             }
         }
 
-        None
+        // SLA escalation should move a ticket on, not back to its usual owner
+        if matches!(trigger, AssignmentTrigger::SlaBreached) {
+            return None;
+        }
+        Self::category_default(conn, ticket, &trigger)
+    }
+
+    /// Assign from the ticket category's default user or group
+    fn category_default(
+        conn: &mut DbConnection,
+        ticket: &Ticket,
+        trigger: &AssignmentTrigger,
+    ) -> Option<AssignmentResult> {
+        let category_id = ticket.category_id?;
+        let category = match crate::repository::categories::get_category_by_id(conn, category_id) {
+            Ok(c) => c,
+            Err(e) => {
+                log::error!("Failed to get category {category_id} for default assignment: {e:?}");
+                return None;
+            }
+        };
+
+        let (assigned_user, method) = match (category.default_assignee_uuid, category.default_group_id) {
+            (Some(user), _) => (user, AssignmentMethod::DirectUser),
+            (None, Some(group_id)) => (Self::random_member(conn, group_id)?, AssignmentMethod::GroupRandom),
+            (None, None) => return None,
+        };
+
+        let new_log = NewAssignmentLog {
+            ticket_id: ticket.id,
+            rule_id: None,
+            trigger_type: trigger.as_str().to_string(),
+            previous_assignee_uuid: ticket.assignee_uuid,
+            new_assignee_uuid: Some(assigned_user),
+            method,
+            context: Some(json!({
+                "rule_name": CATEGORY_DEFAULT_RULE,
+                "category_id": category.id,
+                "category_name": category.name,
+            })),
+        };
+        let _ = diesel::insert_into(assignment_log::table)
+            .values(&new_log)
+            .execute(conn);
+
+        Some(AssignmentResult {
+            rule_id: None,
+            rule_name: CATEGORY_DEFAULT_RULE.to_string(),
+            assigned_user_uuid: Some(assigned_user),
+            method,
+        })
     }
 
     /// Check if the rule applies to the given trigger type
     fn matches_trigger(rule: &AssignmentRule, trigger: &AssignmentTrigger) -> bool {
         match trigger {
-            AssignmentTrigger::TicketCreated => rule.trigger_on_create,
+            AssignmentTrigger::TicketCreated | AssignmentTrigger::TicketReopened => rule.trigger_on_create,
             AssignmentTrigger::CategoryChanged => rule.trigger_on_category_change,
+            AssignmentTrigger::SlaBreached => rule.trigger_on_sla_breach,
         }
     }
 
@@ -203,7 +260,16 @@ impl AssignmentEngine {
     /// Random assignment from group members
     fn random_assignment(conn: &mut DbConnection, rule: &AssignmentRule) -> Option<Option<Uuid>> {
         let group_id = rule.target_group_id?;
+        let selected_user = Self::random_member(conn, group_id)?;
 
+        // Update state for tracking
+        let _ = Self::update_state(conn, rule.id, 0, Some(selected_user));
+
+        Some(Some(selected_user))
+    }
+
+    /// Pick a random member of a group
+    fn random_member(conn: &mut DbConnection, group_id: i32) -> Option<Uuid> {
         // Get group members
         let members = match crate::repository::groups::get_users_in_group(conn, group_id) {
             Ok(m) if !m.is_empty() => m,
@@ -219,12 +285,7 @@ impl AssignmentEngine {
 
         // Select random member
         let mut rng = rand::thread_rng();
-        let selected_user = members.choose(&mut rng)?;
-
-        // Update state for tracking
-        let _ = Self::update_state(conn, rule.id, 0, Some(selected_user.uuid));
-
-        Some(Some(selected_user.uuid))
+        members.choose(&mut rng).map(|user| user.uuid)
     }
 
     /// Get active rules ordered by priority (lower number = higher priority)
@@ -336,6 +397,7 @@ mod tests {
             created_at: now,
             updated_at: now,
             created_by: None,
+            trigger_on_sla_breach: false,
         };
         overrides(&mut rule);
         rule
@@ -357,6 +419,10 @@ mod tests {
             closed_at: None,
             closed_by: None,
             category_id: None,
+            version: 1,
+            merged_into_id: None,
+            first_response_at: None,
+            legal_hold: false,
         };
         overrides(&mut ticket);
         ticket
@@ -376,12 +442,26 @@ mod tests {
         assert!(!AssignmentEngine::matches_trigger(&rule, &AssignmentTrigger::TicketCreated));
     }
 
+    #[test]
+    fn trigger_on_create_matches_ticket_reopened() {
+        let rule = make_rule(|r| r.trigger_on_create = true);
+        assert!(AssignmentEngine::matches_trigger(&rule, &AssignmentTrigger::TicketReopened));
+    }
+
     #[test]
     fn trigger_on_category_change_matches() {
         let rule = make_rule(|r| r.trigger_on_category_change = true);
         assert!(AssignmentEngine::matches_trigger(&rule, &AssignmentTrigger::CategoryChanged));
     }
 
+    #[test]
+    fn trigger_on_sla_breach_matches_only_when_enabled() {
+        let rule = make_rule(|r| r.trigger_on_sla_breach = true);
+        assert!(AssignmentEngine::matches_trigger(&rule, &AssignmentTrigger::SlaBreached));
+        let rule = make_rule(|r| r.trigger_on_create = true);
+        assert!(!AssignmentEngine::matches_trigger(&rule, &AssignmentTrigger::SlaBreached));
+    }
+
     #[test]
     fn trigger_on_category_change_false_does_not_match() {
         let rule = make_rule(|r| r.trigger_on_category_change = false);
@@ -499,4 +579,45 @@ mod tests {
         });
         assert!(!AssignmentEngine::evaluate_conditions(&rule, &ticket2));
     }
+
+    // ── category default ─────────────────────────────────────────────
+
+    #[test]
+    fn category_default_assigns_when_no_rule_matches() {
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+        let mut conn = setup_test_connection();
+        diesel::update(assignment_rules::table)
+            .set(assignment_rules::is_active.eq(false))
+            .execute(&mut conn)
+            .unwrap();
+
+        let owner = TestFixtures::create_user(&mut conn, "printerowner", UserRole::Technician);
+        let category = TestFixtures::create_category(&mut conn, "Printers");
+        diesel::update(ticket_categories::table.find(category.id))
+            .set(ticket_categories::default_assignee_uuid.eq(owner.uuid))
+            .execute(&mut conn)
+            .unwrap();
+        let ticket = TestFixtures::create_ticket(&mut conn, "Printer jammed", None, Some(category.id));
+
+        let result =
+            AssignmentEngine::evaluate_rules(&mut conn, &ticket, AssignmentTrigger::TicketCreated).unwrap();
+        assert_eq!(result.rule_id, None);
+        assert_eq!(result.rule_name, CATEGORY_DEFAULT_RULE);
+        assert_eq!(result.assigned_user_uuid, Some(owner.uuid));
+
+        let log: AssignmentLog = assignment_log::table
+            .filter(assignment_log::ticket_id.eq(ticket.id))
+            .first(&mut conn)
+            .unwrap();
+        assert_eq!(log.rule_id, None);
+        assert_eq!(log.new_assignee_uuid, Some(owner.uuid));
+        assert_eq!(log.context.unwrap()["rule_name"], CATEGORY_DEFAULT_RULE);
+
+        // A ticket without a category stays unassigned
+        let uncategorized = TestFixtures::create_ticket(&mut conn, "Printer jammed again", None, None);
+        assert!(
+            AssignmentEngine::evaluate_rules(&mut conn, &uncategorized, AssignmentTrigger::TicketCreated).is_none()
+        );
+    }
 }
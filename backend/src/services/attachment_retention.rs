@@ -0,0 +1,134 @@
+//! Attachment Retention
+//!
+//! Background task that purges attachments from tickets that have been closed
+//! longer than the retention period. The file is deleted from storage, then
+//! its database row, and each purge is recorded in the audit log. Tickets on
+//! legal hold are skipped. Retention is off unless a period is configured.
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use serde_json::json;
+
+use crate::db::Pool;
+use crate::repository;
+use crate::repository::tickets::extract_storage_path_from_url;
+use crate::services::audit::{self, AuditAction, AuditTarget};
+use crate::utils::storage::Storage;
+
+/// Default time between sweeps
+const DEFAULT_SWEEP_INTERVAL_HOURS: u64 = 24;
+
+/// Attachments purged per query, so one sweep doesn't load everything at once
+const PURGE_BATCH_SIZE: i64 = 200;
+
+/// Retention settings loaded from the environment
+///
+/// * `ATTACHMENT_RETENTION_DAYS` - days after a ticket closes before its
+///   attachments are purged (unset or 0 disables retention)
+/// * `ATTACHMENT_RETENTION_SWEEP_INTERVAL_HOURS` - time between sweeps (default 24)
+#[derive(Debug, Clone, Copy)]
+pub struct AttachmentRetentionConfig {
+    pub retention_days: Option<i64>,
+    pub sweep_interval: std::time::Duration,
+}
+
+impl AttachmentRetentionConfig {
+    pub fn from_env() -> Self {
+        let retention_days = std::env::var("ATTACHMENT_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|days| *days > 0);
+        let hours = std::env::var("ATTACHMENT_RETENTION_SWEEP_INTERVAL_HOURS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|hours| *hours > 0)
+            .unwrap_or(DEFAULT_SWEEP_INTERVAL_HOURS);
+
+        Self {
+            retention_days,
+            sweep_interval: std::time::Duration::from_secs(hours * 3600),
+        }
+    }
+}
+
+/// Start the background sweep loop, if a retention period is configured
+pub fn spawn(pool: Pool, storage: Arc<dyn Storage>, config: AttachmentRetentionConfig) {
+    let Some(retention_days) = config.retention_days else {
+        tracing::debug!("Attachment retention disabled");
+        return;
+    };
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.sweep_interval);
+
+        tracing::info!(
+            retention_days,
+            interval_hours = config.sweep_interval.as_secs() / 3600,
+            "Attachment retention sweeper started"
+        );
+
+        loop {
+            interval.tick().await;
+
+            match purge_expired(&pool, storage.as_ref(), retention_days).await {
+                Ok(0) => tracing::debug!("No attachments past retention"),
+                Ok(count) => tracing::info!(attachments = count, "Purged attachments past retention"),
+                Err(e) => tracing::error!(error = %e, "Attachment retention sweep failed"),
+            }
+        }
+    });
+}
+
+/// Purge attachments on tickets closed more than `retention_days` ago.
+/// Returns the number of attachments purged.
+pub async fn purge_expired(pool: &Pool, storage: &dyn Storage, retention_days: i64) -> Result<usize, String> {
+    let mut conn = pool.get().map_err(|e| format!("DB error: {e}"))?;
+    let closed_before = Utc::now().naive_utc() - Duration::days(retention_days);
+
+    let mut purged = 0;
+    loop {
+        let batch = repository::comments::get_attachments_past_retention(&mut conn, closed_before, PURGE_BATCH_SIZE)
+            .map_err(|e| format!("Failed to load attachments past retention: {e}"))?;
+        let batch_len = batch.len();
+        let mut batch_purged = 0;
+
+        for (attachment, ticket_id) in batch {
+            // Keep the row if the file couldn't be removed, so the next sweep retries it
+            if let Some(path) = extract_storage_path_from_url(&attachment.url) {
+                if let Err(e) = storage.delete_file(&path).await {
+                    tracing::warn!(attachment_id = attachment.id, path, error = ?e, "Failed to delete attachment file");
+                    continue;
+                }
+            }
+
+            if let Err(e) = repository::comments::delete_attachment(&mut conn, attachment.id) {
+                tracing::warn!(attachment_id = attachment.id, error = %e, "Failed to delete attachment record");
+                continue;
+            }
+
+            let _ = audit::record(
+                &mut conn,
+                None,
+                AuditAction::AttachmentPurged,
+                Some(AuditTarget::attachment(attachment.id)),
+                json!({
+                    "ticket_id": ticket_id,
+                    "name": attachment.name,
+                    "url": attachment.url,
+                    "file_size": attachment.file_size,
+                    "retention_days": retention_days,
+                }),
+            );
+            batch_purged += 1;
+        }
+
+        purged += batch_purged;
+        // A short batch is the last one; a batch with nothing purged would repeat forever
+        if batch_len < PURGE_BATCH_SIZE as usize || batch_purged == 0 {
+            break;
+        }
+    }
+
+    Ok(purged)
+}
@@ -0,0 +1,297 @@
+//! Audit Log Service
+//!
+//! A single audit trail for security-relevant actions across entities:
+//! logins, MFA and passkey changes, role changes, secret writes and reveals,
+//! legal holds and data purges, and notifications that could not be
+//! delivered. Ticket edits, assignments and plugin activity keep their own
+//! logs; this one answers "who did what to which account or secret, and when".
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::db::DbConnection;
+use crate::models::{AuditLogEntry, Claims, NewAuditLogEntry};
+use crate::schema::audit_log;
+
+/// Maximum entries returned by one query
+pub const MAX_QUERY_LIMIT: i64 = 500;
+
+/// Security-relevant actions recorded in the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    LoginSucceeded,
+    LoginFailed,
+    MfaEnabled,
+    MfaDisabled,
+    MfaBackupCodesRegenerated,
+    PasskeyDeleted,
+    RoleChanged,
+    PluginSecretSet,
+    WebhookCreated,
+    WebhookSecretRegenerated,
+    WebhookSecretRevealed,
+    PermissionGranted,
+    PermissionRevoked,
+    ImpersonationStarted,
+    ImpersonationStopped,
+    ImpersonatedRequest,
+    NotificationDeliveryFailed,
+    LegalHoldChanged,
+    AttachmentPurged,
+    ResetTokenReused,
+    AccountLocked,
+    AccountUnlocked,
+    NewDeviceSignIn,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 23] = [
+        AuditAction::LoginSucceeded,
+        AuditAction::LoginFailed,
+        AuditAction::MfaEnabled,
+        AuditAction::MfaDisabled,
+        AuditAction::MfaBackupCodesRegenerated,
+        AuditAction::PasskeyDeleted,
+        AuditAction::RoleChanged,
+        AuditAction::PluginSecretSet,
+        AuditAction::WebhookCreated,
+        AuditAction::WebhookSecretRegenerated,
+        AuditAction::WebhookSecretRevealed,
+        AuditAction::PermissionGranted,
+        AuditAction::PermissionRevoked,
+        AuditAction::ImpersonationStarted,
+        AuditAction::ImpersonationStopped,
+        AuditAction::ImpersonatedRequest,
+        AuditAction::NotificationDeliveryFailed,
+        AuditAction::LegalHoldChanged,
+        AuditAction::AttachmentPurged,
+        AuditAction::ResetTokenReused,
+        AuditAction::AccountLocked,
+        AuditAction::AccountUnlocked,
+        AuditAction::NewDeviceSignIn,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::LoginSucceeded => "login_succeeded",
+            AuditAction::LoginFailed => "login_failed",
+            AuditAction::MfaEnabled => "mfa_enabled",
+            AuditAction::MfaDisabled => "mfa_disabled",
+            AuditAction::MfaBackupCodesRegenerated => "mfa_backup_codes_regenerated",
+            AuditAction::PasskeyDeleted => "passkey_deleted",
+            AuditAction::RoleChanged => "role_changed",
+            AuditAction::PluginSecretSet => "plugin_secret_set",
+            AuditAction::WebhookCreated => "webhook_created",
+            AuditAction::WebhookSecretRegenerated => "webhook_secret_regenerated",
+            AuditAction::WebhookSecretRevealed => "webhook_secret_revealed",
+            AuditAction::PermissionGranted => "permission_granted",
+            AuditAction::PermissionRevoked => "permission_revoked",
+            AuditAction::ImpersonationStarted => "impersonation_started",
+            AuditAction::ImpersonationStopped => "impersonation_stopped",
+            AuditAction::ImpersonatedRequest => "impersonated_request",
+            AuditAction::NotificationDeliveryFailed => "notification_delivery_failed",
+            AuditAction::LegalHoldChanged => "legal_hold_changed",
+            AuditAction::AttachmentPurged => "attachment_purged",
+            AuditAction::ResetTokenReused => "reset_token_reused",
+            AuditAction::AccountLocked => "account_locked",
+            AuditAction::AccountUnlocked => "account_unlocked",
+            AuditAction::NewDeviceSignIn => "new_device_sign_in",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.as_str() == s)
+    }
+}
+
+/// The entity an audited action applied to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditTarget {
+    pub kind: &'static str,
+    pub id: String,
+}
+
+impl AuditTarget {
+    pub fn user(uuid: Uuid) -> Self {
+        Self { kind: "user", id: uuid.to_string() }
+    }
+
+    pub fn passkey(credential_id: impl Into<String>) -> Self {
+        Self { kind: "passkey", id: credential_id.into() }
+    }
+
+    pub fn plugin(uuid: Uuid) -> Self {
+        Self { kind: "plugin", id: uuid.to_string() }
+    }
+
+    pub fn webhook(uuid: Uuid) -> Self {
+        Self { kind: "webhook", id: uuid.to_string() }
+    }
+
+    pub fn group(id: i32) -> Self {
+        Self { kind: "group", id: id.to_string() }
+    }
+
+    pub fn ticket(id: i32) -> Self {
+        Self { kind: "ticket", id: id.to_string() }
+    }
+
+    pub fn attachment(id: i32) -> Self {
+        Self { kind: "attachment", id: id.to_string() }
+    }
+}
+
+/// Record an audited action
+///
+/// `actor` is the user who performed the action (`None` for anonymous
+/// attempts). Failures are logged and returned; callers on request paths
+/// usually ignore them rather than fail the action being audited.
+pub fn record(
+    conn: &mut DbConnection,
+    actor: Option<Uuid>,
+    action: AuditAction,
+    target: Option<AuditTarget>,
+    metadata: Value,
+) -> QueryResult<AuditLogEntry> {
+    let (target_type, target_id) = match target {
+        Some(target) => (Some(target.kind.to_string()), Some(target.id)),
+        None => (None, None),
+    };
+
+    diesel::insert_into(audit_log::table)
+        .values(&NewAuditLogEntry {
+            actor_uuid: actor,
+            action: action.as_str().to_string(),
+            target_type,
+            target_id,
+            metadata,
+        })
+        .get_result(conn)
+        .inspect_err(|e| tracing::warn!(action = action.as_str(), error = %e, "Failed to write audit log entry"))
+}
+
+/// Record a request made while an admin impersonates another user
+///
+/// The real admin is the actor and the impersonated user the target, so the
+/// entry carries both identities. Does nothing for regular sessions.
+pub fn record_impersonated_request(
+    conn: &mut DbConnection,
+    claims: &Claims,
+    method: &str,
+    path: &str,
+) -> Option<AuditLogEntry> {
+    let impersonator = claims.impersonator.as_deref()?;
+    let target = Uuid::parse_str(&claims.sub).ok().map(AuditTarget::user);
+
+    record(
+        conn,
+        Uuid::parse_str(impersonator).ok(),
+        AuditAction::ImpersonatedRequest,
+        target,
+        json!({ "method": method, "path": path }),
+    )
+    .ok()
+}
+
+/// Filters for querying the audit log; unset fields match everything
+#[derive(Debug, Default, Clone)]
+pub struct AuditFilter {
+    pub actor: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+}
+
+/// Audit log entries matching `filter`, newest first
+pub fn query(
+    conn: &mut DbConnection,
+    filter: &AuditFilter,
+    limit: i64,
+    offset: i64,
+) -> QueryResult<Vec<AuditLogEntry>> {
+    let mut query = audit_log::table.into_boxed();
+
+    if let Some(actor) = filter.actor {
+        query = query.filter(audit_log::actor_uuid.eq(actor));
+    }
+    if let Some(action) = filter.action {
+        query = query.filter(audit_log::action.eq(action.as_str()));
+    }
+    if let Some(from) = filter.from {
+        query = query.filter(audit_log::created_at.ge(from));
+    }
+    if let Some(to) = filter.to {
+        query = query.filter(audit_log::created_at.lt(to));
+    }
+
+    query
+        .order((audit_log::created_at.desc(), audit_log::id.desc()))
+        .limit(limit.clamp(1, MAX_QUERY_LIMIT))
+        .offset(offset.max(0))
+        .select(AuditLogEntry::as_select())
+        .load(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{create_test_claims, setup_test_connection, TestFixtures};
+
+    #[test]
+    fn recorded_event_round_trips_with_metadata() {
+        let mut conn = setup_test_connection();
+        let admin = TestFixtures::create_user(&mut conn, "Audit Admin", UserRole::Admin);
+        let user = TestFixtures::create_user(&mut conn, "Audit Target", UserRole::User);
+
+        let metadata = json!({"from": "user", "to": "technician"});
+        let entry = record(
+            &mut conn,
+            Some(admin.uuid),
+            AuditAction::RoleChanged,
+            Some(AuditTarget::user(user.uuid)),
+            metadata.clone(),
+        )
+        .unwrap();
+        record(&mut conn, Some(user.uuid), AuditAction::LoginSucceeded, None, json!({})).unwrap();
+
+        let filter = AuditFilter {
+            actor: Some(admin.uuid),
+            action: Some(AuditAction::RoleChanged),
+            ..AuditFilter::default()
+        };
+        let found = query(&mut conn, &filter, 50, 0).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, entry.id);
+        assert_eq!(found[0].action, "role_changed");
+        assert_eq!(found[0].target_type.as_deref(), Some("user"));
+        assert_eq!(found[0].target_id, Some(user.uuid.to_string()));
+        assert_eq!(found[0].metadata, metadata);
+
+        let future = AuditFilter {
+            from: Some(entry.created_at + chrono::Duration::hours(1)),
+            ..filter
+        };
+        assert!(query(&mut conn, &future, 50, 0).unwrap().is_empty());
+        assert_eq!(AuditAction::parse("login_succeeded"), Some(AuditAction::LoginSucceeded));
+    }
+
+    #[test]
+    fn impersonated_request_records_the_real_admin() {
+        let mut conn = setup_test_connection();
+        let admin = TestFixtures::create_user(&mut conn, "Impersonating Admin", UserRole::Admin);
+        let user = TestFixtures::create_user(&mut conn, "Impersonated User", UserRole::User);
+
+        let mut claims = create_test_claims(&user);
+        assert!(record_impersonated_request(&mut conn, &claims, "POST", "/api/tickets").is_none());
+
+        claims.impersonator = Some(admin.uuid.to_string());
+        let entry = record_impersonated_request(&mut conn, &claims, "POST", "/api/tickets").unwrap();
+        assert_eq!(entry.actor_uuid, Some(admin.uuid));
+        assert_eq!(entry.action, "impersonated_request");
+        assert_eq!(entry.target_id, Some(user.uuid.to_string()));
+        assert_eq!(entry.metadata, json!({"method": "POST", "path": "/api/tickets"}));
+    }
+}
@@ -0,0 +1,121 @@
+//! Canned response placeholder interpolation
+//!
+//! Template bodies reference ticket fields as `{{name}}` (whitespace inside
+//! the braces is allowed). Supported placeholders:
+//! * `ticket_id`, `ticket_title`, `ticket_status`, `ticket_priority`
+//! * `requester_name`, `requester_first_name`
+//! * `assignee_name`, `assignee_first_name`
+//!
+//! Placeholders without a value (no assignee, unknown name) become empty
+//! text so a template can always be applied.
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use crate::models::{CompleteTicket, TicketPriority, TicketStatus, UserInfoWithAvatar};
+
+static PLACEHOLDER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\{\{\s*([A-Za-z_]+)\s*\}\}").unwrap()
+});
+
+/// Fill in a template's placeholders from a ticket
+pub fn apply(template: &str, ticket: &CompleteTicket) -> String {
+    PLACEHOLDER_RE
+        .replace_all(template, |caps: &Captures| {
+            placeholder_value(&caps[1].to_ascii_lowercase(), ticket).unwrap_or_default()
+        })
+        .into_owned()
+}
+
+fn placeholder_value(name: &str, ticket: &CompleteTicket) -> Option<String> {
+    let requester = ticket.requester_user.as_ref();
+    let assignee = ticket.assignee_user.as_ref();
+
+    match name {
+        "ticket_id" => Some(ticket.ticket.id.to_string()),
+        "ticket_title" => Some(ticket.ticket.title.clone()),
+        "ticket_status" => Some(status_label(ticket.ticket.status).to_string()),
+        "ticket_priority" => Some(priority_label(ticket.ticket.priority).to_string()),
+        "requester_name" => requester.map(|u| u.name.clone()),
+        "requester_first_name" => requester.map(first_name),
+        "assignee_name" => assignee.map(|u| u.name.clone()),
+        "assignee_first_name" => assignee.map(first_name),
+        _ => None,
+    }
+}
+
+fn first_name(user: &UserInfoWithAvatar) -> String {
+    user.name.split_whitespace().next().unwrap_or_default().to_string()
+}
+
+fn status_label(status: TicketStatus) -> &'static str {
+    match status {
+        TicketStatus::Open => "Open",
+        TicketStatus::InProgress => "In Progress",
+        TicketStatus::Closed => "Closed",
+    }
+}
+
+fn priority_label(priority: TicketPriority) -> &'static str {
+    match priority {
+        TicketPriority::Low => "Low",
+        TicketPriority::Medium => "Medium",
+        TicketPriority::High => "High",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::repository;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+    #[test]
+    fn interpolates_ticket_and_requester_fields() {
+        let mut conn = setup_test_connection();
+        let requester = TestFixtures::create_user(&mut conn, "cannedrequester", UserRole::User);
+        let tech = TestFixtures::create_user(&mut conn, "cannedassignee", UserRole::Technician);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Printer jammed", Some(requester.uuid), None);
+        repository::tickets::update_ticket_partial(
+            &mut conn,
+            ticket.id,
+            crate::models::TicketUpdate {
+                assignee_uuid: Some(Some(tech.uuid)),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .unwrap();
+        let complete = repository::get_complete_ticket(&mut conn, ticket.id, true).unwrap();
+
+        let content = apply(
+            "Hi {{requester_first_name}}, re #{{ticket_id}} \"{{ ticket_title }}\" ({{ticket_status}}, {{ticket_priority}}). -- {{assignee_name}}",
+            &complete,
+        );
+        assert_eq!(
+            content,
+            format!(
+                "Hi {}, re #{} \"Printer jammed\" (Open, Medium). -- {}",
+                requester.name, ticket.id, tech.name
+            )
+        );
+    }
+
+    #[test]
+    fn missing_variables_are_left_blank() {
+        let mut conn = setup_test_connection();
+        let requester = TestFixtures::create_user(&mut conn, "cannedblank", UserRole::User);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Unassigned", Some(requester.uuid), None);
+        let complete = repository::get_complete_ticket(&mut conn, ticket.id, true).unwrap();
+
+        // No assignee, and an unknown placeholder
+        assert_eq!(
+            apply("Assigned to [{{assignee_name}}] {{no_such_field}}done", &complete),
+            "Assigned to [] done"
+        );
+        // Text that isn't a placeholder is kept as-is
+        assert_eq!(apply("{ {requester_name} } {{}}", &complete), "{ {requester_name} } {{}}");
+    }
+}
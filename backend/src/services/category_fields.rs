@@ -0,0 +1,260 @@
+//! Category presentation and required ticket fields
+//!
+//! Categories have an icon and color for display, and can list fields a
+//! ticket needs before it can be created in them: a linked device, or a
+//! value for one of the custom fields (e.g. a system name for access
+//! requests). Definitions are checked when a category is saved and
+//! new tickets are checked against them, with an error per field.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::TicketCategory;
+
+/// Most required fields a category can define
+pub const MAX_REQUIRED_FIELDS: usize = 20;
+
+const MAX_KEY_LEN: usize = 50;
+const MAX_LABEL_LEN: usize = 100;
+const MAX_ICON_LEN: usize = 50;
+
+/// What a required field asks of a ticket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequiredFieldKind {
+    /// A value for the custom field with this key (`services::custom_fields`)
+    Text,
+    /// At least one linked device
+    Device,
+}
+
+/// A field tickets in a category must have
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequiredField {
+    /// Lowercase letters, digits and underscores, e.g. `system_name`
+    pub key: String,
+    /// Shown on the ticket form and in errors
+    pub label: String,
+    pub kind: RequiredFieldKind,
+}
+
+/// A problem with one field of a category or a new ticket
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    /// The offending field, e.g. `color` or `required_fields[1].key`
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { field: field.into(), message: message.into() }
+    }
+}
+
+/// Check a category's color (`#rrggbb`) and icon name, when given
+pub fn validate_presentation(color: Option<&str>, icon: Option<&str>) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    if let Some(color) = color {
+        let is_hex = color.len() == 7
+            && color.starts_with('#')
+            && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+        if !is_hex {
+            errors.push(FieldError::new("color", format!("'{color}' is not a hex color like #6366f1")));
+        }
+    }
+
+    if let Some(icon) = icon {
+        if icon.is_empty() || icon.len() > MAX_ICON_LEN {
+            errors.push(FieldError::new("icon", format!("must be 1 to {MAX_ICON_LEN} characters")));
+        } else if !icon.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+            errors.push(FieldError::new("icon", "must be lowercase letters, digits and dashes"));
+        }
+    }
+
+    errors
+}
+
+/// Parse and check a category's required field definitions
+pub fn parse_required_fields(value: &Value) -> Result<Vec<RequiredField>, Vec<FieldError>> {
+    let fields: Vec<RequiredField> = serde_json::from_value(value.clone())
+        .map_err(|e| vec![FieldError::new("required_fields", e.to_string())])?;
+
+    let mut errors = Vec::new();
+    if fields.len() > MAX_REQUIRED_FIELDS {
+        errors.push(FieldError::new(
+            "required_fields",
+            format!("at most {MAX_REQUIRED_FIELDS} fields are allowed"),
+        ));
+    }
+
+    let mut keys = HashSet::new();
+    let mut has_device = false;
+    for (i, field) in fields.iter().enumerate() {
+        let valid_key = !field.key.is_empty()
+            && field.key.len() <= MAX_KEY_LEN
+            && field.key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid_key {
+            errors.push(FieldError::new(
+                format!("required_fields[{i}].key"),
+                format!("must be 1 to {MAX_KEY_LEN} lowercase letters, digits and underscores"),
+            ));
+        } else if !keys.insert(field.key.as_str()) {
+            errors.push(FieldError::new(
+                format!("required_fields[{i}].key"),
+                format!("duplicate field key '{}'", field.key),
+            ));
+        }
+
+        let label = field.label.trim();
+        if label.is_empty() || label.len() > MAX_LABEL_LEN {
+            errors.push(FieldError::new(
+                format!("required_fields[{i}].label"),
+                format!("must be 1 to {MAX_LABEL_LEN} characters"),
+            ));
+        }
+
+        if field.kind == RequiredFieldKind::Device {
+            if has_device {
+                errors.push(FieldError::new(
+                    format!("required_fields[{i}].kind"),
+                    "only one device field is allowed",
+                ));
+            }
+            has_device = true;
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(fields)
+    } else {
+        Err(errors)
+    }
+}
+
+/// The fields tickets in `category` must have
+///
+/// Definitions are checked when saved, so anything unreadable is treated as
+/// no requirements rather than blocking ticket creation.
+pub fn required_fields(category: &TicketCategory) -> Vec<RequiredField> {
+    serde_json::from_value(category.required_fields.clone()).unwrap_or_default()
+}
+
+/// Check a new ticket's custom fields and devices against the required
+/// fields, returning an error per missing or malformed field
+pub fn check_ticket(
+    required: &[RequiredField],
+    custom_fields: Option<&Value>,
+    device_ids: &[i32],
+) -> Result<(), Vec<FieldError>> {
+    let mut errors = Vec::new();
+
+    let values = match custom_fields {
+        None => None,
+        Some(Value::Object(values)) => {
+            for (key, value) in values {
+                if !value.is_string() {
+                    errors.push(FieldError::new(format!("custom_fields.{key}"), "must be text"));
+                }
+            }
+            Some(values)
+        }
+        Some(_) => {
+            errors.push(FieldError::new("custom_fields", "must be an object of text values"));
+            None
+        }
+    };
+
+    for field in required {
+        let present = match field.kind {
+            RequiredFieldKind::Device => !device_ids.is_empty(),
+            RequiredFieldKind::Text => values
+                .and_then(|values| values.get(&field.key))
+                .and_then(Value::as_str)
+                .is_some_and(|value| !value.trim().is_empty()),
+        };
+        if !present {
+            errors.push(FieldError::new(field.key.clone(), format!("{} is required", field.label)));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields() -> Vec<RequiredField> {
+        parse_required_fields(&json!([
+            {"key": "device", "label": "Affected device", "kind": "device"},
+            {"key": "system_name", "label": "System name", "kind": "text"}
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn presentation_accepts_hex_colors_and_icon_names() {
+        assert!(validate_presentation(Some("#6366F1"), Some("laptop-2")).is_empty());
+        assert!(validate_presentation(None, None).is_empty());
+
+        let errors = validate_presentation(Some("blue"), Some("Laptop Icon"));
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["color", "icon"]);
+    }
+
+    #[test]
+    fn invalid_definitions_are_reported_per_field() {
+        assert_eq!(fields().len(), 2);
+
+        let errors = parse_required_fields(&json!([
+            {"key": "System Name", "label": "System name", "kind": "text"},
+            {"key": "serial", "label": " ", "kind": "text"},
+            {"key": "serial", "label": "Serial", "kind": "text"},
+            {"key": "device", "label": "Device", "kind": "device"},
+            {"key": "other_device", "label": "Other device", "kind": "device"}
+        ]))
+        .unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "required_fields[0].key",
+                "required_fields[1].label",
+                "required_fields[2].key",
+                "required_fields[4].kind",
+            ]
+        );
+
+        let errors = parse_required_fields(&json!([{"key": "x", "label": "X", "kind": "date"}])).unwrap_err();
+        assert_eq!(errors[0].field, "required_fields");
+    }
+
+    #[test]
+    fn tickets_missing_required_fields_get_an_error_per_field() {
+        let required = fields();
+        assert!(check_ticket(&required, Some(&json!({"system_name": "SAP"})), &[7]).is_ok());
+
+        let errors = check_ticket(&required, Some(&json!({"system_name": "  "})), &[]).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                FieldError::new("device", "Affected device is required"),
+                FieldError::new("system_name", "System name is required"),
+            ]
+        );
+
+        let errors = check_ticket(&[], Some(&json!({"system_name": 3})), &[]).unwrap_err();
+        assert_eq!(errors, vec![FieldError::new("custom_fields.system_name", "must be text")]);
+        assert!(check_ticket(&[], Some(&json!(["SAP"])), &[]).is_err());
+        assert!(check_ticket(&[], None, &[]).is_ok());
+    }
+}
@@ -0,0 +1,257 @@
+//! Custom ticket fields
+//!
+//! Admins define extra fields for tickets (e.g. "affected system", "cost
+//! center"), optionally limited to one category. Values are stored as text
+//! in a canonical form for their type, so filters can match them exactly:
+//! numbers without trailing zeros, dates as `YYYY-MM-DD`, checkboxes as
+//! `true`/`false`. Text and select values are also indexed for search.
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::models::CustomFieldDefinition;
+use crate::services::category_fields::FieldError;
+
+const MAX_KEY_LEN: usize = 50;
+const MAX_LABEL_LEN: usize = 100;
+const MAX_TEXT_LEN: usize = 1000;
+const MAX_OPTIONS: usize = 100;
+const MAX_OPTION_LEN: usize = 100;
+
+/// Type of a custom field's values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    Date,
+    Select,
+    Checkbox,
+}
+
+impl CustomFieldType {
+    pub const ALL: [CustomFieldType; 5] = [
+        CustomFieldType::Text,
+        CustomFieldType::Number,
+        CustomFieldType::Date,
+        CustomFieldType::Select,
+        CustomFieldType::Checkbox,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Number => "number",
+            CustomFieldType::Date => "date",
+            CustomFieldType::Select => "select",
+            CustomFieldType::Checkbox => "checkbox",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == s)
+    }
+
+    /// Whether values are added to the ticket's search document
+    pub fn is_searchable(self) -> bool {
+        matches!(self, CustomFieldType::Text | CustomFieldType::Select)
+    }
+}
+
+/// A custom field as values are checked against it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSpec {
+    pub field_type: CustomFieldType,
+    /// Choices of a select field
+    pub options: Vec<String>,
+}
+
+impl FieldSpec {
+    /// Spec of a stored definition. Definitions are checked when saved, so
+    /// an unreadable type or options list only happens if edited by hand;
+    /// such a field accepts text.
+    pub fn of(definition: &CustomFieldDefinition) -> Self {
+        Self {
+            field_type: CustomFieldType::parse(&definition.field_type).unwrap_or(CustomFieldType::Text),
+            options: serde_json::from_value(definition.options.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// Canonical form of `raw` for this field, or why it isn't valid
+    pub fn normalize(&self, raw: &str) -> Result<String, String> {
+        let value = raw.trim();
+        match self.field_type {
+            CustomFieldType::Text => {
+                if value.chars().count() > MAX_TEXT_LEN {
+                    Err(format!("must be at most {MAX_TEXT_LEN} characters"))
+                } else {
+                    Ok(value.to_string())
+                }
+            }
+            CustomFieldType::Number => value
+                .parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .map(|n| n.to_string())
+                .ok_or_else(|| format!("'{value}' is not a number")),
+            CustomFieldType::Date => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .map_err(|_| format!("'{value}' is not a date like 2026-03-31")),
+            CustomFieldType::Select => self
+                .options
+                .iter()
+                .find(|option| option.as_str() == value)
+                .cloned()
+                .ok_or_else(|| format!("'{value}' is not one of: {}", self.options.join(", "))),
+            CustomFieldType::Checkbox => match value.to_ascii_lowercase().as_str() {
+                "true" => Ok("true".to_string()),
+                "false" => Ok("false".to_string()),
+                _ => Err(format!("'{value}' is not true or false")),
+            },
+        }
+    }
+}
+
+/// Check a new definition's key, label, type and options
+pub fn validate_definition(
+    key: &str,
+    label: &str,
+    field_type: &str,
+    options: &Value,
+) -> Result<FieldSpec, Vec<FieldError>> {
+    let mut errors = Vec::new();
+
+    let valid_key = !key.is_empty()
+        && key.len() <= MAX_KEY_LEN
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid_key {
+        errors.push(FieldError::new(
+            "key",
+            format!("must be 1 to {MAX_KEY_LEN} lowercase letters, digits and underscores"),
+        ));
+    }
+
+    errors.extend(validate_label(label));
+
+    let Some(field_type) = CustomFieldType::parse(field_type) else {
+        let types: Vec<&str> = CustomFieldType::ALL.iter().map(|t| t.as_str()).collect();
+        errors.push(FieldError::new(
+            "field_type",
+            format!("unknown type '{field_type}' (expected one of: {})", types.join(", ")),
+        ));
+        return Err(errors);
+    };
+
+    match validate_options(field_type, options) {
+        Ok(options) if errors.is_empty() => Ok(FieldSpec { field_type, options }),
+        Ok(_) => Err(errors),
+        Err(option_errors) => {
+            errors.extend(option_errors);
+            Err(errors)
+        }
+    }
+}
+
+/// Check a changed label
+pub fn validate_label(label: &str) -> Vec<FieldError> {
+    let label = label.trim();
+    if label.is_empty() || label.chars().count() > MAX_LABEL_LEN {
+        vec![FieldError::new("label", format!("must be 1 to {MAX_LABEL_LEN} characters"))]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Check the options of a field of type `field_type`: a non-empty list of
+/// distinct choices for select fields, none for the others
+pub fn validate_options(field_type: CustomFieldType, options: &Value) -> Result<Vec<String>, Vec<FieldError>> {
+    let options: Vec<String> = serde_json::from_value(options.clone())
+        .map_err(|_| vec![FieldError::new("options", "must be a list of text choices")])?;
+
+    if field_type != CustomFieldType::Select {
+        return if options.is_empty() {
+            Ok(options)
+        } else {
+            Err(vec![FieldError::new("options", "only select fields have options")])
+        };
+    }
+
+    let mut errors = Vec::new();
+    if options.is_empty() || options.len() > MAX_OPTIONS {
+        errors.push(FieldError::new("options", format!("a select field needs 1 to {MAX_OPTIONS} choices")));
+    }
+    let mut seen = HashSet::new();
+    for (i, option) in options.iter().enumerate() {
+        if option.trim().is_empty() || option.trim() != option || option.chars().count() > MAX_OPTION_LEN {
+            errors.push(FieldError::new(
+                format!("options[{i}]"),
+                format!("must be 1 to {MAX_OPTION_LEN} characters without surrounding spaces"),
+            ));
+        } else if !seen.insert(option.as_str()) {
+            errors.push(FieldError::new(format!("options[{i}]"), format!("duplicate choice '{option}'")));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(options)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(field_type: CustomFieldType, options: &[&str]) -> FieldSpec {
+        FieldSpec { field_type, options: options.iter().map(|o| o.to_string()).collect() }
+    }
+
+    #[test]
+    fn definitions_are_validated() {
+        let select = validate_definition("cost_center", "Cost center", "select", &json!(["4410", "4420"])).unwrap();
+        assert_eq!(select, spec(CustomFieldType::Select, &["4410", "4420"]));
+        assert!(validate_definition("affected_system", "Affected system", "text", &json!([])).is_ok());
+
+        let errors = validate_definition("Cost Center", " ", "select", &json!([])).unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["key", "label", "options"]);
+
+        let errors = validate_definition("due", "Due", "datetime", &json!([])).unwrap_err();
+        assert_eq!(errors[0].field, "field_type");
+
+        let errors = validate_definition("notes", "Notes", "text", &json!(["a"])).unwrap_err();
+        assert_eq!(errors, vec![FieldError::new("options", "only select fields have options")]);
+
+        let errors = validate_definition("tier", "Tier", "select", &json!(["Gold", "Gold", " Silver"])).unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["options[1]", "options[2]"]);
+    }
+
+    #[test]
+    fn values_must_match_the_field_type() {
+        let number = spec(CustomFieldType::Number, &[]);
+        assert_eq!(number.normalize(" 42.50 ").unwrap(), "42.5");
+        assert_eq!(number.normalize("42.0").unwrap(), "42");
+        assert!(number.normalize("forty-two").is_err());
+        assert!(number.normalize("NaN").is_err());
+
+        let date = spec(CustomFieldType::Date, &[]);
+        assert_eq!(date.normalize("2026-03-31").unwrap(), "2026-03-31");
+        assert!(date.normalize("31/03/2026").is_err());
+        assert!(date.normalize("2026-02-30").is_err());
+
+        let select = spec(CustomFieldType::Select, &["4410", "4420"]);
+        assert_eq!(select.normalize("4420").unwrap(), "4420");
+        assert!(select.normalize("9999").is_err());
+
+        let checkbox = spec(CustomFieldType::Checkbox, &[]);
+        assert_eq!(checkbox.normalize("TRUE").unwrap(), "true");
+        assert!(checkbox.normalize("yes").is_err());
+
+        let text = spec(CustomFieldType::Text, &[]);
+        assert_eq!(text.normalize("  SAP ERP ").unwrap(), "SAP ERP");
+        assert!(text.normalize(&"x".repeat(MAX_TEXT_LEN + 1)).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! Microsoft Graph requests for Intune managed devices
+
+use chrono::{DateTime, Utc};
+use tracing::debug;
+
+use crate::handlers::msgraph_integration::MicrosoftGraphDevice;
+
+const MANAGED_DEVICES_URL: &str = "https://graph.microsoft.com/v1.0/deviceManagement/managedDevices";
+
+/// Fields selected from managedDevice, matching `MicrosoftGraphDevice`
+const SELECT_FIELDS: &str = "id,deviceName,operatingSystem,osVersion,manufacturer,model,serialNumber,azureADDeviceId,userPrincipalName,userId,complianceState,lastSyncDateTime,enrolledDateTime,deviceEnrollmentType,managementAgent";
+
+/// One page of a managedDevices listing
+#[derive(Debug)]
+pub struct ManagedDevicePage {
+    pub devices: Vec<MicrosoftGraphDevice>,
+    pub skipped: usize,
+    pub next_link: Option<String>,
+}
+
+/// URL listing managed devices, optionally only those that checked in with
+/// Intune at or after `since`. managedDevices has no delta query, so this
+/// filter is what makes repeat syncs incremental.
+pub fn managed_devices_url(since: Option<DateTime<Utc>>) -> String {
+    let mut url = format!("{MANAGED_DEVICES_URL}?$select={}", urlencoding::encode(SELECT_FIELDS));
+    if let Some(since) = since {
+        let filter = format!("lastSyncDateTime ge {}", since.format("%Y-%m-%dT%H:%M:%SZ"));
+        url.push_str(&format!("&$filter={}", urlencoding::encode(&filter)));
+    }
+    url
+}
+
+/// Parse a managedDevices response page. Entries that don't deserialize are
+/// counted as skipped rather than failing the page.
+pub fn parse_page(response: &serde_json::Value) -> Result<ManagedDevicePage, String> {
+    let values = response
+        .get("value")
+        .and_then(|v| v.as_array())
+        .ok_or("Microsoft Graph managed device response missing 'value' array")?;
+
+    let mut devices = Vec::with_capacity(values.len());
+    let mut skipped = 0;
+    for value in values {
+        match serde_json::from_value::<MicrosoftGraphDevice>(value.clone()) {
+            Ok(device) => devices.push(device),
+            Err(e) => {
+                debug!(error = %e, "Skipping unparseable managed device");
+                skipped += 1;
+            }
+        }
+    }
+
+    Ok(ManagedDevicePage {
+        devices,
+        skipped,
+        next_link: response
+            .get("@odata.nextLink")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+    })
+}
+
+/// Fetch every page starting at `url`, following `@odata.nextLink`
+pub async fn fetch_managed_devices(
+    client: &reqwest::Client,
+    access_token: &str,
+    url: String,
+) -> Result<Vec<MicrosoftGraphDevice>, String> {
+    let mut url = Some(url);
+    let mut devices = Vec::new();
+    let mut page_count = 0;
+
+    while let Some(page_url) = url {
+        page_count += 1;
+
+        let response = client
+            .get(&page_url)
+            .header("Authorization", format!("Bearer {access_token}"))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send managed device request (page {page_count}): {e}"))?;
+
+        let status = response.status();
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse managed device response (page {page_count}): {e}"))?;
+
+        if !status.is_success() {
+            let message = body
+                .get("error")
+                .and_then(|err| err.get("message"))
+                .and_then(|msg| msg.as_str())
+                .unwrap_or("Unknown Microsoft Graph error");
+            return Err(format!("Microsoft Graph API error (page {page_count}, {status}): {message}"));
+        }
+
+        let page = parse_page(&body)?;
+        debug!(page = page_count, devices = page.devices.len(), skipped = page.skipped, "Fetched managed device page");
+        devices.extend(page.devices);
+        url = page.next_link;
+    }
+
+    Ok(devices)
+}
@@ -0,0 +1,275 @@
+//! Mapping Intune managed devices onto local devices
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::db::DbConnection;
+use crate::handlers::msgraph_integration::{parse_microsoft_datetime, MicrosoftGraphDevice};
+use crate::models::{Device, DeviceUpdate, NewDevice};
+use crate::repository;
+use crate::repository::devices as device_repo;
+
+/// Result of syncing a single managed device, named by device name
+#[derive(Debug, PartialEq)]
+pub enum DeviceSyncResult {
+    Created(String),
+    Updated(String),
+    Failed(String, String),
+}
+
+/// Intune reports this for devices that aren't joined to Entra
+const NIL_DEVICE_ID: &str = "00000000-0000-0000-0000-000000000000";
+
+/// Create or update the local device for a managed device and stamp its
+/// `last_sync_time`. Runs in its own transaction.
+pub fn sync_managed_device(
+    conn: &mut DbConnection,
+    managed: &MicrosoftGraphDevice,
+    synced_at: NaiveDateTime,
+) -> DeviceSyncResult {
+    let name = device_name(managed);
+
+    let result = conn.transaction(|conn| {
+        let (device, created) = match find_existing(conn, managed)? {
+            Some(existing) => (device_repo::update_device(conn, existing.id, device_update(managed, synced_at))?, false),
+            None => (device_repo::create_device(conn, new_device(managed, synced_at))?, true),
+        };
+
+        // Primary user comes from the Entra user the device is enrolled to
+        let user_uuid = match managed.user_id.as_deref().filter(|id| !id.is_empty()) {
+            Some(user_id) => repository::user_auth_identities::get_user_uuid_by_external_id_and_provider(
+                user_id, "microsoft", conn,
+            )?,
+            None => None,
+        };
+        if let Some(user_uuid) = user_uuid {
+            device_repo::reassign_primary_user(conn, device.id, user_uuid, None)?;
+        }
+
+        Ok::<_, diesel::result::Error>(created)
+    });
+
+    match result {
+        Ok(true) => DeviceSyncResult::Created(name),
+        Ok(false) => DeviceSyncResult::Updated(name),
+        Err(e) => DeviceSyncResult::Failed(name, e.to_string()),
+    }
+}
+
+/// Match by Intune ID, then by Entra device ID (Intune's `azureADDeviceId`,
+/// stored as `microsoft_device_id` by the Entra sync), then by serial number
+fn find_existing(conn: &mut DbConnection, managed: &MicrosoftGraphDevice) -> QueryResult<Option<Device>> {
+    if let Some(device) = device_repo::get_device_by_intune_id(conn, &managed.id).optional()? {
+        return Ok(Some(device));
+    }
+    if let Some(entra_device_id) = entra_device_id(managed) {
+        if let Some(device) = device_repo::get_device_by_microsoft_id(conn, entra_device_id).optional()? {
+            return Ok(Some(device));
+        }
+    }
+    match serial_number(managed) {
+        Some(serial) => device_repo::get_device_by_serial(conn, serial).optional(),
+        None => Ok(None),
+    }
+}
+
+fn device_name(managed: &MicrosoftGraphDevice) -> String {
+    managed
+        .device_name
+        .clone()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| format!("Device-{}", managed.id))
+}
+
+fn entra_device_id(managed: &MicrosoftGraphDevice) -> Option<&str> {
+    managed
+        .azure_ad_device_id
+        .as_deref()
+        .filter(|id| !id.is_empty() && *id != NIL_DEVICE_ID)
+}
+
+/// Serial number, ignoring the placeholders Intune reports for VMs and
+/// devices that don't expose one
+fn serial_number(managed: &MicrosoftGraphDevice) -> Option<&str> {
+    managed
+        .serial_number
+        .as_deref()
+        .map(str::trim)
+        .filter(|serial| !serial.is_empty() && *serial != "0" && !serial.eq_ignore_ascii_case("unknown"))
+}
+
+fn device_type(managed: &MicrosoftGraphDevice) -> &'static str {
+    match managed.operating_system.as_deref().map(str::to_ascii_lowercase).as_deref() {
+        Some("ios" | "ipados" | "android") => "Mobile",
+        _ => "Computer",
+    }
+}
+
+fn new_device(managed: &MicrosoftGraphDevice, synced_at: NaiveDateTime) -> NewDevice {
+    let name = device_name(managed);
+    NewDevice {
+        hostname: Some(name.clone()),
+        name,
+        device_type: Some(device_type(managed).to_string()),
+        serial_number: serial_number(managed).map(str::to_string),
+        manufacturer: managed.manufacturer.clone(),
+        model: managed.model.clone(),
+        warranty_status: Some("Unknown".to_string()),
+        location: None,
+        notes: None,
+        primary_user_uuid: None, // Set through the assignment history
+        microsoft_device_id: entra_device_id(managed).map(str::to_string),
+        intune_device_id: Some(managed.id.clone()),
+        entra_device_id: None, // Entra object ID comes from the Entra sync
+        compliance_state: managed.compliance_state.clone(),
+        last_sync_time: Some(synced_at),
+        operating_system: managed.operating_system.clone(),
+        os_version: managed.os_version.clone(),
+        is_managed: Some(true),
+        enrollment_date: parse_microsoft_datetime(&managed.enrolled_date_time),
+        warranty_expiry_date: None,
+    }
+}
+
+/// Fields Intune is authoritative for; everything else is left alone
+fn device_update(managed: &MicrosoftGraphDevice, synced_at: NaiveDateTime) -> DeviceUpdate {
+    let name = device_name(managed);
+    DeviceUpdate {
+        hostname: Some(name.clone()),
+        name: Some(name),
+        device_type: None,
+        serial_number: serial_number(managed).map(str::to_string),
+        manufacturer: managed.manufacturer.clone(),
+        model: managed.model.clone(),
+        warranty_status: None,
+        location: None,
+        notes: None,
+        primary_user_uuid: None,
+        microsoft_device_id: entra_device_id(managed).map(str::to_string),
+        intune_device_id: Some(managed.id.clone()),
+        entra_device_id: None,
+        compliance_state: managed.compliance_state.clone(),
+        last_sync_time: Some(synced_at),
+        operating_system: managed.operating_system.clone(),
+        os_version: managed.os_version.clone(),
+        is_managed: Some(true),
+        enrollment_date: parse_microsoft_datetime(&managed.enrolled_date_time),
+        warranty_expiry_date: None,
+        updated_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NewUserAuthIdentity, UserRole};
+    use crate::services::device_sync::graph::parse_page;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+    use serde_json::json;
+
+    fn managed_devices_page(user_id: &str) -> serde_json::Value {
+        json!({
+            "@odata.context": "https://graph.microsoft.com/v1.0/$metadata#deviceManagement/managedDevices",
+            "@odata.nextLink": "https://graph.microsoft.com/v1.0/deviceManagement/managedDevices?$skiptoken=abc",
+            "value": [
+                {
+                    "id": "intune-sync-laptop",
+                    "deviceName": "SYNC-LAPTOP-01",
+                    "operatingSystem": "Windows",
+                    "osVersion": "10.0.22631.3007",
+                    "manufacturer": "Lenovo",
+                    "model": "ThinkPad X1 Carbon",
+                    "serialNumber": "SYNC-SN-LAPTOP",
+                    "azureADDeviceId": "5c1e9f4a-0000-4000-8000-00000000a001",
+                    "userPrincipalName": "syncowner@example.com",
+                    "userId": user_id,
+                    "complianceState": "compliant",
+                    "lastSyncDateTime": "2026-01-15T10:30:00Z",
+                    "enrolledDateTime": "2025-06-01T08:00:00Z",
+                    "deviceEnrollmentType": "windowsAzureADJoin",
+                    "managementAgent": "mdm"
+                },
+                {
+                    "id": "intune-sync-phone",
+                    "deviceName": "Sync iPhone",
+                    "operatingSystem": "iOS",
+                    "osVersion": "17.2",
+                    "serialNumber": "SYNC-SN-EXISTING",
+                    "azureADDeviceId": NIL_DEVICE_ID,
+                    "complianceState": "noncompliant",
+                    "enrolledDateTime": "2025-09-10T12:00:00Z"
+                },
+                { "deviceName": "missing id" }
+            ]
+        })
+    }
+
+    #[test]
+    fn managed_devices_are_created_or_matched_and_mapped() {
+        let mut conn = setup_test_connection();
+        let owner = TestFixtures::create_user(&mut conn, "syncowner", UserRole::User);
+        repository::user_auth_identities::create_identity(
+            NewUserAuthIdentity {
+                user_uuid: owner.uuid,
+                provider_type: "microsoft".to_string(),
+                external_id: "entra-user-syncowner".to_string(),
+                email: None,
+                metadata: None,
+                password_hash: None,
+            },
+            &mut conn,
+        )
+        .unwrap();
+
+        // A device entered by hand before Intune enrollment
+        let (manual, _) = device_repo::upsert_device_by_serial(&mut conn, serde_json::from_value(json!({
+            "name": "Front desk phone",
+            "serial_number": "SYNC-SN-EXISTING",
+            "location": "Reception",
+        })).unwrap()).unwrap();
+
+        let page = parse_page(&managed_devices_page("entra-user-syncowner")).unwrap();
+        assert_eq!(page.devices.len(), 2);
+        assert_eq!(page.skipped, 1);
+        assert!(page.next_link.is_some());
+
+        // Postgres keeps microseconds
+        let synced_at = chrono::SubsecRound::trunc_subsecs(chrono::Utc::now().naive_utc(), 6);
+        let results: Vec<DeviceSyncResult> = page
+            .devices
+            .iter()
+            .map(|managed| sync_managed_device(&mut conn, managed, synced_at))
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                DeviceSyncResult::Created("SYNC-LAPTOP-01".to_string()),
+                DeviceSyncResult::Updated("Sync iPhone".to_string()),
+            ]
+        );
+
+        let laptop = device_repo::get_device_by_intune_id(&mut conn, "intune-sync-laptop").unwrap();
+        assert_eq!(laptop.serial_number.as_deref(), Some("SYNC-SN-LAPTOP"));
+        assert_eq!(laptop.microsoft_device_id.as_deref(), Some("5c1e9f4a-0000-4000-8000-00000000a001"));
+        assert_eq!(laptop.compliance_state.as_deref(), Some("compliant"));
+        assert_eq!(laptop.operating_system.as_deref(), Some("Windows"));
+        assert_eq!(laptop.os_version.as_deref(), Some("10.0.22631.3007"));
+        assert_eq!(laptop.device_type.as_deref(), Some("Computer"));
+        assert_eq!(laptop.is_managed, Some(true));
+        assert_eq!(laptop.last_sync_time, Some(synced_at));
+        assert_eq!(laptop.primary_user_uuid, Some(owner.uuid));
+
+        let phone = device_repo::get_device_by_id(&mut conn, manual.id).unwrap();
+        assert_eq!(phone.intune_device_id.as_deref(), Some("intune-sync-phone"));
+        assert_eq!(phone.name, "Sync iPhone");
+        assert_eq!(phone.microsoft_device_id, None);
+        assert_eq!(phone.compliance_state.as_deref(), Some("noncompliant"));
+        assert_eq!(phone.location.as_deref(), Some("Reception"));
+        assert_eq!(phone.primary_user_uuid, None);
+
+        // A later sync matches by Intune ID and only updates
+        let again = sync_managed_device(&mut conn, &page.devices[0], synced_at);
+        assert_eq!(again, DeviceSyncResult::Updated("SYNC-LAPTOP-01".to_string()));
+        assert_eq!(device_repo::get_device_assignment_history(&mut conn, laptop.id).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,129 @@
+//! Intune Device Sync Service
+//!
+//! Pulls Intune managed devices from Microsoft Graph (client credentials) and
+//! upserts them into the devices table, filling in serial number, compliance
+//! and OS details that the Entra `/devices` sync doesn't provide.
+//!
+//! managedDevices has no delta query, so after the first full pass each run
+//! only fetches devices that checked in since the previous run. The link for
+//! the next run is kept in `sync_delta_tokens` like the Entra delta links.
+//!
+//! Scheduling is opt-in via environment variables:
+//! * `DEVICE_SYNC_INTERVAL_MINUTES` - minutes between syncs (unset or 0 disables)
+
+pub mod graph;
+pub mod mapping;
+
+use chrono::Utc;
+use tracing::{error, info, warn};
+
+use crate::db::Pool;
+use crate::repository::sync_history;
+
+pub use mapping::DeviceSyncResult;
+
+/// Delta token entity type for the managed device sync
+const SYNC_ENTITY: &str = "managed_devices";
+
+/// Scheduled sync settings loaded from the environment
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceSyncConfig {
+    pub interval: Option<std::time::Duration>,
+}
+
+impl DeviceSyncConfig {
+    pub fn from_env() -> Self {
+        let interval = std::env::var("DEVICE_SYNC_INTERVAL_MINUTES")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|minutes| *minutes > 0)
+            .map(|minutes| std::time::Duration::from_secs(minutes * 60));
+
+        Self { interval }
+    }
+}
+
+/// Start the scheduled sync loop, if enabled and Microsoft Graph is configured
+pub fn spawn(pool: Pool, config: DeviceSyncConfig) {
+    let Some(interval) = config.interval else {
+        return;
+    };
+
+    let configured = ["MICROSOFT_CLIENT_ID", "MICROSOFT_CLIENT_SECRET", "MICROSOFT_TENANT_ID"]
+        .iter()
+        .all(|var| std::env::var(var).is_ok());
+    if !configured {
+        warn!("DEVICE_SYNC_INTERVAL_MINUTES is set but Microsoft Graph isn't configured; device sync disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        info!(interval_minutes = interval.as_secs() / 60, "Intune device sync scheduled");
+
+        loop {
+            ticker.tick().await;
+
+            match sync_managed_devices(&pool, true).await {
+                Ok(results) => {
+                    for result in results {
+                        if let DeviceSyncResult::Failed(name, err) = result {
+                            warn!(device = %name, error = %err, "Failed to sync managed device");
+                        }
+                    }
+                }
+                Err(e) => error!(error = %e, "Intune device sync failed"),
+            }
+        }
+    });
+}
+
+/// Sync managed devices from Intune. With `incremental`, only devices that
+/// checked in since the last successful run are fetched; otherwise all are.
+pub async fn sync_managed_devices(pool: &Pool, incremental: bool) -> Result<Vec<DeviceSyncResult>, String> {
+    let started_at = Utc::now();
+
+    let url = {
+        let mut conn = pool.get().map_err(|e| format!("DB error: {e}"))?;
+        let stored = if incremental {
+            sync_history::get_delta_token(&mut conn, "microsoft", SYNC_ENTITY).ok()
+        } else {
+            None
+        };
+        stored
+            .map(|token| token.delta_link)
+            .unwrap_or_else(|| graph::managed_devices_url(None))
+    };
+
+    let (client, access_token) = crate::handlers::msgraph_integration::get_msgraph_client_and_token().await?;
+    let devices = graph::fetch_managed_devices(&client, &access_token, url).await?;
+
+    let mut conn = pool.get().map_err(|e| format!("DB error: {e}"))?;
+    let synced_at = started_at.naive_utc();
+    let results: Vec<DeviceSyncResult> = devices
+        .iter()
+        .map(|managed| mapping::sync_managed_device(&mut conn, managed, synced_at))
+        .collect();
+
+    // Next run picks up devices that checked in from the start of this one
+    if let Err(e) = sync_history::upsert_delta_token(
+        &mut conn,
+        "microsoft",
+        SYNC_ENTITY,
+        &graph::managed_devices_url(Some(started_at)),
+    ) {
+        warn!(error = %e, "Failed to save managed device sync link");
+    }
+
+    // Log summary
+    let created = results.iter().filter(|r| matches!(r, DeviceSyncResult::Created(_))).count();
+    let updated = results.iter().filter(|r| matches!(r, DeviceSyncResult::Updated(_))).count();
+    let failed = results.iter().filter(|r| matches!(r, DeviceSyncResult::Failed(_, _))).count();
+
+    info!(
+        "Intune device sync complete: {} created, {} updated, {} failed",
+        created, updated, failed
+    );
+
+    Ok(results)
+}
@@ -0,0 +1,207 @@
+//! Email Feedback
+//!
+//! Parses bounce and complaint reports from the email provider so addresses
+//! that can't (or shouldn't) receive mail are flagged in `user_emails` and
+//! skipped by the notification email channel. Two formats are accepted:
+//!
+//! - Amazon SES notifications, either wrapped in an SNS envelope or
+//!   delivered raw
+//! - A generic webhook body: `{"event": "hard_bounce" | "soft_bounce" |
+//!   "complaint", "email": "user@example.com"}`
+//!
+//! Soft and transient bounces are ignored; only permanent failures and spam
+//! complaints suppress delivery.
+
+use serde_json::Value;
+
+use crate::db::DbConnection;
+use crate::repository::user_emails;
+
+/// What the provider reported for an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedbackKind {
+    HardBounce,
+    Complaint,
+}
+
+/// One address to flag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedbackEvent {
+    pub kind: FeedbackKind,
+    pub address: String,
+}
+
+/// A parsed feedback request
+#[derive(Debug, PartialEq, Eq)]
+pub enum Feedback {
+    /// Addresses to flag; empty for reports that need no action
+    Events(Vec<FeedbackEvent>),
+    /// SNS asks for the subscription to be confirmed by visiting this URL
+    SubscriptionConfirmation { subscribe_url: String },
+}
+
+/// Parse a feedback body in any supported format
+pub fn parse_feedback(body: &str) -> Result<Feedback, String> {
+    let value: Value = serde_json::from_str(body).map_err(|e| format!("Invalid JSON: {e}"))?;
+
+    // SNS envelope
+    if let Some(message_type) = value.get("Type").and_then(Value::as_str) {
+        return match message_type {
+            "SubscriptionConfirmation" => value
+                .get("SubscribeURL")
+                .and_then(Value::as_str)
+                .map(|url| Feedback::SubscriptionConfirmation { subscribe_url: url.to_string() })
+                .ok_or_else(|| "SubscriptionConfirmation without SubscribeURL".to_string()),
+            "Notification" => {
+                let message = value
+                    .get("Message")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| "SNS notification without Message".to_string())?;
+                let ses: Value =
+                    serde_json::from_str(message).map_err(|e| format!("Invalid SES message: {e}"))?;
+                Ok(Feedback::Events(parse_ses(&ses)))
+            }
+            _ => Ok(Feedback::Events(Vec::new())),
+        };
+    }
+
+    // Raw SES delivery (event publishing uses `eventType`)
+    if value.get("notificationType").is_some() || value.get("eventType").is_some() {
+        return Ok(Feedback::Events(parse_ses(&value)));
+    }
+
+    parse_generic(&value).map(Feedback::Events)
+}
+
+fn parse_ses(message: &Value) -> Vec<FeedbackEvent> {
+    let notification_type = message
+        .get("notificationType")
+        .or_else(|| message.get("eventType"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+
+    let (kind, recipients) = match notification_type {
+        "Bounce" => {
+            let bounce = &message["bounce"];
+            if bounce["bounceType"].as_str() != Some("Permanent") {
+                return Vec::new();
+            }
+            (FeedbackKind::HardBounce, &bounce["bouncedRecipients"])
+        }
+        "Complaint" => (FeedbackKind::Complaint, &message["complaint"]["complainedRecipients"]),
+        _ => return Vec::new(),
+    };
+
+    recipients
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|recipient| recipient["emailAddress"].as_str())
+        .map(|address| FeedbackEvent { kind, address: address.to_string() })
+        .collect()
+}
+
+fn parse_generic(value: &Value) -> Result<Vec<FeedbackEvent>, String> {
+    let event = value
+        .get("event")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Missing 'event'".to_string())?;
+    let address = value
+        .get("email")
+        .and_then(Value::as_str)
+        .filter(|address| !address.trim().is_empty())
+        .ok_or_else(|| "Missing 'email'".to_string())?;
+
+    let kind = match event {
+        "hard_bounce" => FeedbackKind::HardBounce,
+        "complaint" => FeedbackKind::Complaint,
+        "soft_bounce" => return Ok(Vec::new()),
+        other => return Err(format!("Unknown event '{other}'")),
+    };
+    Ok(vec![FeedbackEvent { kind, address: address.to_string() }])
+}
+
+/// Flag each reported address, returning how many rows were newly flagged
+pub fn apply_feedback(
+    conn: &mut DbConnection,
+    events: &[FeedbackEvent],
+) -> Result<usize, diesel::result::Error> {
+    let mut flagged = 0;
+    for event in events {
+        flagged += match event.kind {
+            FeedbackKind::HardBounce => user_emails::mark_bounced(conn, &event.address)?,
+            FeedbackKind::Complaint => user_emails::mark_complained(conn, &event.address)?,
+        };
+    }
+    Ok(flagged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn events(body: Value) -> Vec<FeedbackEvent> {
+        match parse_feedback(&body.to_string()).unwrap() {
+            Feedback::Events(events) => events,
+            other => panic!("expected events, got {other:?}"),
+        }
+    }
+
+    fn sns(message: Value) -> Value {
+        json!({"Type": "Notification", "Message": message.to_string()})
+    }
+
+    #[test]
+    fn sns_permanent_bounce_flags_each_recipient() {
+        let body = sns(json!({
+            "notificationType": "Bounce",
+            "bounce": {
+                "bounceType": "Permanent",
+                "bouncedRecipients": [
+                    {"emailAddress": "gone@example.com"},
+                    {"emailAddress": "typo@exmaple.com"}
+                ]
+            }
+        }));
+        let addresses: Vec<String> = events(body).into_iter().map(|e| e.address).collect();
+        assert_eq!(addresses, vec!["gone@example.com", "typo@exmaple.com"]);
+    }
+
+    #[test]
+    fn transient_and_soft_bounces_are_ignored() {
+        let transient = sns(json!({
+            "notificationType": "Bounce",
+            "bounce": {"bounceType": "Transient", "bouncedRecipients": [{"emailAddress": "full@example.com"}]}
+        }));
+        assert!(events(transient).is_empty());
+        assert!(events(json!({"event": "soft_bounce", "email": "full@example.com"})).is_empty());
+    }
+
+    #[test]
+    fn complaints_are_parsed_from_raw_ses_and_generic_bodies() {
+        let raw = json!({
+            "notificationType": "Complaint",
+            "complaint": {"complainedRecipients": [{"emailAddress": "angry@example.com"}]}
+        });
+        let expected = vec![FeedbackEvent { kind: FeedbackKind::Complaint, address: "angry@example.com".into() }];
+        assert_eq!(events(raw), expected);
+        assert_eq!(events(json!({"event": "complaint", "email": "angry@example.com"})), expected);
+    }
+
+    #[test]
+    fn subscription_confirmation_returns_the_url() {
+        let body = json!({"Type": "SubscriptionConfirmation", "SubscribeURL": "https://sns.example.com/confirm"});
+        assert_eq!(
+            parse_feedback(&body.to_string()).unwrap(),
+            Feedback::SubscriptionConfirmation { subscribe_url: "https://sns.example.com/confirm".into() }
+        );
+    }
+
+    #[test]
+    fn unknown_generic_events_are_rejected() {
+        assert!(parse_feedback(r#"{"event": "opened", "email": "a@example.com"}"#).is_err());
+        assert!(parse_feedback(r#"{"event": "hard_bounce"}"#).is_err());
+        assert!(parse_feedback("not json").is_err());
+    }
+}
@@ -0,0 +1,180 @@
+//! Prometheus metrics
+//!
+//! A process-wide registry with the counters and histograms ops scrapes from
+//! `/metrics`: HTTP requests by route and status, database pool checkouts,
+//! search latency, webhook delivery outcomes and notification deliveries per
+//! channel. Recording functions are cheap and never fail, so hot paths can
+//! call them unconditionally.
+
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+
+use crate::db::Pool;
+
+/// Route label for requests that didn't match a route, so scanners probing
+/// random paths can't blow up label cardinality
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Buckets for request and query latency, in seconds
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Buckets for pool checkout waits, which are normally well under a millisecond
+const CHECKOUT_BUCKETS: &[f64] = &[0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 30.0];
+
+struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    db_pool_checkout_wait: Histogram,
+    db_pool_checkout_timeouts: IntCounter,
+    db_pool_connections: IntGaugeVec,
+    db_pool_max_connections: IntGauge,
+    search_query_duration: Histogram,
+    webhook_deliveries: IntCounterVec,
+    notification_deliveries: IntCounterVec,
+}
+
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some("nosdesk".to_string()), None)?;
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by method, route and status"),
+            &["method", "route", "status"],
+        )?;
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency by method and route")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+            &["method", "route"],
+        )?;
+        let db_pool_checkout_wait = Histogram::with_opts(
+            HistogramOpts::new("db_pool_checkout_wait_seconds", "Time spent waiting for a database connection")
+                .buckets(CHECKOUT_BUCKETS.to_vec()),
+        )?;
+        let db_pool_checkout_timeouts = IntCounter::new(
+            "db_pool_checkout_timeouts_total",
+            "Database connection checkouts that timed out",
+        )?;
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database pool connections by state (idle, in_use)"),
+            &["state"],
+        )?;
+        let db_pool_max_connections = IntGauge::new(
+            "db_pool_max_connections",
+            "Configured maximum size of the database pool",
+        )?;
+        let search_query_duration = Histogram::with_opts(
+            HistogramOpts::new("search_query_duration_seconds", "Full-text search query latency")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+        )?;
+        let webhook_deliveries = IntCounterVec::new(
+            Opts::new("webhook_deliveries_total", "Webhook delivery attempts by outcome"),
+            &["outcome"],
+        )?;
+        let notification_deliveries = IntCounterVec::new(
+            Opts::new("notification_deliveries_total", "Notification deliveries by channel and outcome"),
+            &["channel", "outcome"],
+        )?;
+
+        registry.register(Box::new(http_requests.clone()))?;
+        registry.register(Box::new(http_request_duration.clone()))?;
+        registry.register(Box::new(db_pool_checkout_wait.clone()))?;
+        registry.register(Box::new(db_pool_checkout_timeouts.clone()))?;
+        registry.register(Box::new(db_pool_connections.clone()))?;
+        registry.register(Box::new(db_pool_max_connections.clone()))?;
+        registry.register(Box::new(search_query_duration.clone()))?;
+        registry.register(Box::new(webhook_deliveries.clone()))?;
+        registry.register(Box::new(notification_deliveries.clone()))?;
+
+        Ok(Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            db_pool_checkout_wait,
+            db_pool_checkout_timeouts,
+            db_pool_connections,
+            db_pool_max_connections,
+            search_query_duration,
+            webhook_deliveries,
+            notification_deliveries,
+        })
+    }
+}
+
+static METRICS: Lazy<Metrics> = Lazy::new(|| Metrics::new().expect("metric definitions are valid"));
+
+/// Record a finished HTTP request. `route` is the matched route pattern
+/// (e.g. `/api/tickets/{id}`), never the raw path.
+pub fn record_http_request(method: &str, route: &str, status: u16, elapsed: Duration) {
+    METRICS
+        .http_requests
+        .with_label_values(&[method, route, &status.to_string()])
+        .inc();
+    METRICS
+        .http_request_duration
+        .with_label_values(&[method, route])
+        .observe(elapsed.as_secs_f64());
+}
+
+/// Number of requests recorded for a method, route and status
+#[cfg(test)]
+pub(crate) fn http_request_count(method: &str, route: &str, status: u16) -> u64 {
+    METRICS
+        .http_requests
+        .with_label_values(&[method, route, &status.to_string()])
+        .get()
+}
+
+/// Record how long a database connection checkout waited
+pub fn record_db_checkout(wait: Duration) {
+    METRICS.db_pool_checkout_wait.observe(wait.as_secs_f64());
+}
+
+/// Record a database connection checkout that timed out
+pub fn record_db_checkout_timeout() {
+    METRICS.db_pool_checkout_timeouts.inc();
+}
+
+/// Record the latency of a search query
+pub fn record_search_query(elapsed: Duration) {
+    METRICS.search_query_duration.observe(elapsed.as_secs_f64());
+}
+
+/// Record a webhook delivery attempt (`success`, `http_error` or `network_error`)
+pub fn record_webhook_delivery(outcome: &str) {
+    METRICS.webhook_deliveries.with_label_values(&[outcome]).inc();
+}
+
+/// Record a notification delivery (`delivered`, `rate_limited` or `failed`) on a channel
+pub fn record_notification_delivery(channel: &str, outcome: &str) {
+    METRICS
+        .notification_deliveries
+        .with_label_values(&[channel, outcome])
+        .inc();
+}
+
+/// Render all metrics in the Prometheus text exposition format, sampling
+/// the pool's current connection counts first
+pub fn render(pool: &Pool) -> String {
+    let utilization = crate::db::pool_utilization(pool);
+    METRICS
+        .db_pool_connections
+        .with_label_values(&["idle"])
+        .set(i64::from(utilization.idle));
+    METRICS
+        .db_pool_connections
+        .with_label_values(&["in_use"])
+        .set(i64::from(utilization.in_use));
+    METRICS.db_pool_max_connections.set(i64::from(utilization.max_size));
+
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&METRICS.registry.gather(), &mut buffer) {
+        tracing::error!(error = %e, "Failed to encode metrics");
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
@@ -1,6 +1,23 @@
 pub mod assignment;
+pub mod attachment_retention;
+pub mod audit;
 pub mod backup;
+pub mod canned_responses;
+pub mod category_fields;
+pub mod custom_fields;
+pub mod device_sync;
+pub mod email_feedback;
+pub mod metrics;
 pub mod notifications;
+pub mod outbound_http;
 pub mod plugins;
+pub mod reporting;
 pub mod search;
+pub mod shutdown;
+pub mod sign_in_devices;
+pub mod sla;
+pub mod ticket_auto_close;
+pub mod ticket_json;
+pub mod transcription;
+pub mod warranty;
 pub mod webhooks;
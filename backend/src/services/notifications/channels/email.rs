@@ -11,8 +11,10 @@ use uuid::Uuid;
 
 use super::{ChannelError, ChannelResult, NotificationDeliveryChannel};
 use crate::db::Pool;
+use crate::models::UserEmail;
+use crate::services::notifications::i18n;
 use crate::services::notifications::types::{
-    DeliverableNotification, NotificationChannel, NotificationTypeCode,
+    DeliverableNotification, NotificationChannel, NotificationEntity,
 };
 use crate::utils::email::EmailService;
 
@@ -42,58 +44,42 @@ impl EmailChannel {
         }
     }
 
-    /// Generate email subject based on notification type
+    /// Generate email subject based on notification type, in the
+    /// recipient's locale
     fn generate_subject(&self, notification: &DeliverableNotification) -> String {
         let entity_title = match &notification.payload.entity {
-            crate::services::notifications::types::NotificationEntity::Ticket { title, .. } => {
-                title.clone()
-            }
-            crate::services::notifications::types::NotificationEntity::Comment {
-                ticket_title,
-                ..
-            } => ticket_title.clone(),
+            NotificationEntity::Ticket { title, .. } => title.as_str(),
+            NotificationEntity::Comment { ticket_title, .. } => ticket_title.as_str(),
+            NotificationEntity::Device { name, .. } => name.as_str(),
+            NotificationEntity::Session { device, .. } => device.as_str(),
         };
 
-        match notification.payload.notification_type {
-            NotificationTypeCode::TicketAssigned => {
-                format!("[{}] You've been assigned: {}", self.app_name, entity_title)
-            }
-            NotificationTypeCode::TicketStatusChanged => {
-                format!("[{}] Status changed: {}", self.app_name, entity_title)
-            }
-            NotificationTypeCode::CommentAdded => {
-                format!("[{}] New comment on: {}", self.app_name, entity_title)
-            }
-            NotificationTypeCode::Mentioned => {
-                format!(
-                    "[{}] {} mentioned you",
-                    self.app_name, notification.payload.actor.name
-                )
-            }
-            NotificationTypeCode::TicketCreatedRequester => {
-                format!("[{}] Ticket created: {}", self.app_name, entity_title)
-            }
-        }
+        i18n::translate(
+            notification.locale,
+            &i18n::subject_id(notification.payload.notification_type),
+            &[
+                ("app", &self.app_name),
+                ("title", entity_title),
+                ("actor", &notification.payload.actor.name),
+            ],
+        )
     }
 
-    /// Generate the ticket URL for the email
-    fn generate_ticket_url(&self, notification: &DeliverableNotification) -> String {
-        let ticket_id = notification.payload.entity.ticket_id();
-        format!("{}/tickets/{}", self.base_url, ticket_id)
+    /// Generate the URL of the notification's entity for the email
+    fn generate_entity_url(&self, notification: &DeliverableNotification) -> String {
+        notification.payload.entity.url(&self.base_url)
     }
 
     /// Generate email HTML body
     fn generate_html_body(&self, notification: &DeliverableNotification) -> String {
-        let ticket_url = self.generate_ticket_url(notification);
-        let body_text = notification
-            .payload
-            .body
-            .as_deref()
-            .unwrap_or("You have a new notification.");
+        let entity_url = self.generate_entity_url(notification);
+        let locale = notification.locale;
+        let default_body = i18n::translate(locale, "email.default_body", &[]);
+        let body_text = notification.payload.body.as_deref().unwrap_or(&default_body);
 
         format!(
             r#"<!DOCTYPE html>
-<html>
+<html lang="{}">
 <head>
     <meta charset="utf-8">
     <meta name="viewport" content="width=device-width, initial-scale=1.0">
@@ -107,45 +93,57 @@ impl EmailChannel {
             {}
         </p>
         <p style="color: #6a6a6a; font-size: 14px; margin: 0 0 24px 0;">
-            <strong>From:</strong> {}
+            <strong>{}</strong> {}
         </p>
         <a href="{}" style="display: inline-block; padding: 12px 24px; background-color: #0066cc; color: white; text-decoration: none; border-radius: 6px; font-weight: 500;">
-            View in {}
+            {}
         </a>
     </div>
     <p style="color: #888; font-size: 12px; text-align: center; margin-top: 16px;">
-        You're receiving this because of your notification preferences.
+        {}
     </p>
 </body>
 </html>"#,
+            locale,
             notification.payload.title,
             body_text,
+            i18n::translate(locale, "email.from", &[]),
             notification.payload.actor.name,
-            ticket_url,
-            self.app_name
+            entity_url,
+            i18n::translate(locale, "email.view_in", &[("app", &self.app_name)]),
+            i18n::translate(locale, "email.footer", &[]),
         )
     }
 
-    /// Get the recipient's primary email address
+    /// Get the address to notify: the recipient's primary email if it's
+    /// verified, otherwise their oldest other verified one. Addresses the
+    /// email provider reported as hard-bounced or as a spam complaint are
+    /// skipped.
     async fn get_recipient_email(&self, recipient_uuid: &Uuid) -> ChannelResult<String> {
-        use crate::schema::user_emails::dsl::{user_emails, user_uuid, email, is_primary};
+        use crate::schema::user_emails::dsl::{user_emails, user_uuid, is_primary, is_verified, created_at};
 
         let mut conn = self
             .pool
             .get()
             .map_err(|e| ChannelError::DatabaseError(e.to_string()))?;
 
-        let email_result: Option<String> = user_emails
+        let verified: Vec<UserEmail> = user_emails
             .filter(user_uuid.eq(recipient_uuid))
-            .filter(is_primary.eq(true))
-            .select(email)
-            .first(&mut conn)
-            .optional()
+            .filter(is_verified.eq(true))
+            .order((is_primary.desc(), created_at.asc()))
+            .load(&mut conn)
             .map_err(|e| ChannelError::DatabaseError(e.to_string()))?;
 
-        email_result.ok_or_else(|| {
-            ChannelError::InvalidRecipient(format!("No primary email for user {recipient_uuid}"))
-        })
+        // The user still gets the in-app notification; only email is skipped
+        verified
+            .into_iter()
+            .find(|address| !address.is_suppressed())
+            .map(|address| address.email)
+            .ok_or_else(|| {
+                ChannelError::InvalidRecipient(format!(
+                    "No verified, deliverable email for user {recipient_uuid}"
+                ))
+            })
     }
 
     /// Update rate limit tracking
@@ -230,7 +228,7 @@ impl NotificationDeliveryChannel for EmailChannel {
         let subj = subject.clone();
         let html = body.clone();
 
-        tokio::spawn(async move {
+        crate::services::shutdown::notifications().spawn(async move {
             if let Err(e) = email_service.send_html_email(&email, &subj, &html).await {
                 tracing::error!(error = ?e, "Failed to send notification email");
             }
@@ -301,3 +299,127 @@ impl NotificationDeliveryChannel for EmailChannel {
         recent.is_none()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::repository::user_emails::{clear_delivery_flags, mark_bounced};
+    use crate::repository::users::update_user_locale;
+    use crate::services::notifications::types::{NotificationActor, NotificationPayload, NotificationTypeCode};
+    use crate::test_helpers::{setup_test_pool, TestFixtures};
+    use crate::utils::email::EmailConfig;
+
+    fn channel(pool: Pool) -> EmailChannel {
+        let config = EmailConfig {
+            smtp_host: String::new(),
+            smtp_port: 587,
+            smtp_username: String::new(),
+            smtp_password: String::new(),
+            from_name: String::new(),
+            from_email: String::new(),
+            enabled: false,
+        };
+        EmailChannel::new(
+            Arc::new(EmailService::new(config)),
+            pool,
+            "https://help.example.com".to_string(),
+            "Helpdesk".to_string(),
+        )
+    }
+
+    fn assigned(recipient: Uuid, locale: &'static str) -> DeliverableNotification {
+        DeliverableNotification {
+            id: Some(1),
+            uuid: Uuid::new_v4(),
+            count: 1,
+            locale,
+            payload: NotificationPayload::new(
+                NotificationTypeCode::TicketAssigned,
+                recipient,
+                NotificationActor {
+                    uuid: Uuid::new_v4(),
+                    name: "Sam".to_string(),
+                    avatar_thumb: None,
+                },
+                NotificationEntity::Ticket {
+                    id: 42,
+                    title: "VPN down".to_string(),
+                },
+            ),
+            channels: vec![NotificationChannel::Email],
+        }
+    }
+
+    #[test]
+    fn subject_follows_user_locale_with_english_fallback() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let french = TestFixtures::create_user(&mut conn, "localefr", UserRole::User);
+        let unknown = TestFixtures::create_user(&mut conn, "localexx", UserRole::User);
+        update_user_locale(&mut conn, &french.uuid, Some("fr")).unwrap();
+        update_user_locale(&mut conn, &unknown.uuid, Some("xx")).unwrap();
+
+        let channel = channel(pool);
+        let subject_for = |user: &crate::models::User, conn: &mut crate::db::DbConnection| {
+            let locale = i18n::user_locale(conn, &user.uuid);
+            channel.generate_subject(&assigned(user.uuid, locale))
+        };
+
+        assert_eq!(subject_for(&french, &mut conn), "[Helpdesk] Ticket qui vous est assigné : VPN down");
+        assert_eq!(subject_for(&unknown, &mut conn), "[Helpdesk] You've been assigned: VPN down");
+
+        let body = channel.generate_html_body(&assigned(french.uuid, "fr"));
+        assert!(body.contains(r#"<html lang="fr">"#));
+        assert!(body.contains("Voir dans Helpdesk"));
+    }
+
+    #[tokio::test]
+    async fn bounced_address_is_skipped() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "bounced", UserRole::User);
+        let email = TestFixtures::create_user_email(&mut conn, user.uuid, "bounced@example.com", true);
+        mark_bounced(&mut conn, "bounced@example.com").unwrap();
+
+        let channel = channel(pool);
+        let result = channel.deliver(&assigned(user.uuid, "en")).await;
+        assert!(matches!(result, Err(ChannelError::InvalidRecipient(_))), "got {result:?}");
+
+        clear_delivery_flags(&mut conn, email.id).unwrap();
+        assert_eq!(
+            channel.get_recipient_email(&user.uuid).await.unwrap(),
+            "bounced@example.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn unverified_primary_falls_back_to_a_verified_address() {
+        use crate::models::NewUserEmail;
+
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "unverifiedprimary", UserRole::User);
+        let unverified = |address: &str, primary: bool| NewUserEmail {
+            user_uuid: user.uuid,
+            email: address.to_string(),
+            email_type: "personal".to_string(),
+            is_primary: primary,
+            is_verified: false,
+            source: Some("manual".to_string()),
+        };
+        diesel::insert_into(crate::schema::user_emails::table)
+            .values(&vec![unverified("primary@example.com", true), unverified("typo@example.com", false)])
+            .execute(&mut conn)
+            .unwrap();
+
+        let channel = channel(pool);
+        assert!(matches!(
+            channel.get_recipient_email(&user.uuid).await,
+            Err(ChannelError::InvalidRecipient(_))
+        ));
+
+        TestFixtures::create_user_email(&mut conn, user.uuid, "verified@example.com", false);
+        assert_eq!(channel.get_recipient_email(&user.uuid).await.unwrap(), "verified@example.com");
+    }
+}
@@ -0,0 +1,87 @@
+//! Delivery failure alerts
+//!
+//! When a channel still can't deliver a notification after its retries are
+//! exhausted, the notification service publishes a [`DeliveryFailure`] to a
+//! [`DeliveryFailureSink`]. In production that sink records the failure in
+//! the audit log and fires a `notification.delivery_failed` webhook so
+//! external monitoring picks it up.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::services::audit::{self, AuditAction, AuditTarget};
+use crate::services::webhooks::{WebhookEventType, WebhookService};
+
+use super::types::NotificationChannel;
+
+/// A notification that could not be delivered on one channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryFailure {
+    pub channel: NotificationChannel,
+    pub recipient_uuid: Uuid,
+    pub notification_id: Option<i32>,
+    pub notification_type: String,
+    pub error: String,
+    pub attempts: u32,
+}
+
+/// Destination for delivery failures
+#[async_trait]
+pub trait DeliveryFailureSink: Send + Sync {
+    async fn publish(&self, failure: DeliveryFailure);
+}
+
+/// Records failures in the audit log and forwards them to webhooks
+pub struct AdminAlertSink {
+    pool: Pool,
+    /// None when the webhooks feature is disabled
+    webhooks: Option<Arc<WebhookService>>,
+}
+
+impl AdminAlertSink {
+    pub fn new(pool: Pool, webhooks: Option<Arc<WebhookService>>) -> Self {
+        Self { pool, webhooks }
+    }
+}
+
+#[async_trait]
+impl DeliveryFailureSink for AdminAlertSink {
+    async fn publish(&self, failure: DeliveryFailure) {
+        let data = json!({
+            "channel": failure.channel.as_str(),
+            "recipient_uuid": failure.recipient_uuid,
+            "notification_id": failure.notification_id,
+            "notification_type": failure.notification_type,
+            "error": failure.error,
+            "attempts": failure.attempts,
+        });
+
+        match self.pool.get() {
+            Ok(mut conn) => {
+                let _ = audit::record(
+                    &mut conn,
+                    None,
+                    AuditAction::NotificationDeliveryFailed,
+                    Some(AuditTarget::user(failure.recipient_uuid)),
+                    data.clone(),
+                );
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to audit notification delivery failure");
+            }
+        }
+
+        if let Some(webhooks) = &self.webhooks {
+            if let Err(e) = webhooks
+                .dispatch(WebhookEventType::NotificationDeliveryFailed, data)
+                .await
+            {
+                tracing::error!(error = %e, "Failed to dispatch delivery failure webhook");
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Notification localization
+//!
+//! A small message catalog keyed by locale and message id. Messages use
+//! `{name}` placeholders filled in by [`translate`]; anything missing from a
+//! locale falls back to English, so a partial translation never produces an
+//! empty subject.
+
+use std::collections::HashMap;
+
+use diesel::prelude::*;
+use once_cell::sync::Lazy;
+use uuid::Uuid;
+
+use crate::db::DbConnection;
+
+use super::types::NotificationTypeCode;
+
+/// Locale used when the user has none or it isn't supported
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// (locale, message id, template)
+const MESSAGES: &[(&str, &str, &str)] = &[
+    // English
+    ("en", "subject.ticket_assigned", "[{app}] You've been assigned: {title}"),
+    ("en", "subject.ticket_status_changed", "[{app}] Status changed: {title}"),
+    ("en", "subject.comment_added", "[{app}] New comment on: {title}"),
+    ("en", "subject.mentioned", "[{app}] {actor} mentioned you"),
+    ("en", "subject.ticket_created_requester", "[{app}] Ticket created: {title}"),
+    ("en", "subject.device_warranty_expiring", "[{app}] Warranty expiring: {title}"),
+    ("en", "subject.ticket_merged", "[{app}] Ticket merged into: {title}"),
+    ("en", "subject.new_device_sign_in", "[{app}] New sign-in from {title}"),
+    ("en", "email.default_body", "You have a new notification."),
+    ("en", "email.from", "From:"),
+    ("en", "email.view_in", "View in {app}"),
+    ("en", "email.footer", "You're receiving this because of your notification preferences."),
+    // French
+    ("fr", "subject.ticket_assigned", "[{app}] Ticket qui vous est assigné : {title}"),
+    ("fr", "subject.ticket_status_changed", "[{app}] Statut modifié : {title}"),
+    ("fr", "subject.comment_added", "[{app}] Nouveau commentaire sur : {title}"),
+    ("fr", "subject.mentioned", "[{app}] {actor} vous a mentionné"),
+    ("fr", "subject.ticket_created_requester", "[{app}] Ticket créé : {title}"),
+    ("fr", "subject.device_warranty_expiring", "[{app}] Garantie bientôt expirée : {title}"),
+    ("fr", "subject.ticket_merged", "[{app}] Ticket fusionné dans : {title}"),
+    ("fr", "subject.new_device_sign_in", "[{app}] Nouvelle connexion depuis : {title}"),
+    ("fr", "email.default_body", "Vous avez une nouvelle notification."),
+    ("fr", "email.from", "De :"),
+    ("fr", "email.view_in", "Voir dans {app}"),
+    ("fr", "email.footer", "Vous recevez ce message en raison de vos préférences de notification."),
+    // German
+    ("de", "subject.ticket_assigned", "[{app}] Ihnen zugewiesen: {title}"),
+    ("de", "subject.ticket_status_changed", "[{app}] Status geändert: {title}"),
+    ("de", "subject.comment_added", "[{app}] Neuer Kommentar zu: {title}"),
+    ("de", "subject.mentioned", "[{app}] {actor} hat Sie erwähnt"),
+    ("de", "subject.ticket_created_requester", "[{app}] Ticket erstellt: {title}"),
+    ("de", "subject.device_warranty_expiring", "[{app}] Garantie läuft ab: {title}"),
+    ("de", "subject.ticket_merged", "[{app}] Ticket zusammengeführt mit: {title}"),
+    ("de", "subject.new_device_sign_in", "[{app}] Neue Anmeldung von: {title}"),
+    ("de", "email.default_body", "Sie haben eine neue Benachrichtigung."),
+    ("de", "email.from", "Von:"),
+    ("de", "email.view_in", "In {app} ansehen"),
+    ("de", "email.footer", "Sie erhalten diese Nachricht aufgrund Ihrer Benachrichtigungseinstellungen."),
+    // Spanish
+    ("es", "subject.ticket_assigned", "[{app}] Se le ha asignado: {title}"),
+    ("es", "subject.ticket_status_changed", "[{app}] Estado cambiado: {title}"),
+    ("es", "subject.comment_added", "[{app}] Nuevo comentario en: {title}"),
+    ("es", "subject.mentioned", "[{app}] {actor} le ha mencionado"),
+    ("es", "subject.ticket_created_requester", "[{app}] Ticket creado: {title}"),
+    ("es", "subject.device_warranty_expiring", "[{app}] Garantía por vencer: {title}"),
+    ("es", "subject.ticket_merged", "[{app}] Ticket fusionado en: {title}"),
+    ("es", "subject.new_device_sign_in", "[{app}] Nuevo inicio de sesión desde: {title}"),
+    ("es", "email.default_body", "Tiene una nueva notificación."),
+    ("es", "email.from", "De:"),
+    ("es", "email.view_in", "Ver en {app}"),
+    ("es", "email.footer", "Recibe este mensaje debido a sus preferencias de notificación."),
+];
+
+static CATALOG: Lazy<HashMap<(&'static str, &'static str), &'static str>> = Lazy::new(|| {
+    MESSAGES
+        .iter()
+        .map(|(locale, id, template)| ((*locale, *id), *template))
+        .collect()
+});
+
+/// Locales with a catalog
+pub fn supported_locales() -> Vec<&'static str> {
+    let mut locales: Vec<&str> = MESSAGES.iter().map(|(locale, _, _)| *locale).collect();
+    locales.dedup();
+    locales
+}
+
+/// Map a user's locale (e.g. `fr-CA`, `FR`) to a supported catalog locale,
+/// falling back to English
+pub fn resolve_locale(locale: Option<&str>) -> &'static str {
+    let language = locale
+        .and_then(|l| l.split(['-', '_']).next())
+        .map(|l| l.trim().to_lowercase())
+        .unwrap_or_default();
+
+    supported_locales()
+        .into_iter()
+        .find(|supported| *supported == language)
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// The catalog locale for a user's stored preference
+pub fn user_locale(conn: &mut DbConnection, user_uuid: &Uuid) -> &'static str {
+    use crate::schema::users;
+
+    let locale: Option<String> = users::table
+        .find(user_uuid)
+        .select(users::locale)
+        .first(conn)
+        .optional()
+        .ok()
+        .flatten()
+        .flatten();
+
+    resolve_locale(locale.as_deref())
+}
+
+/// Look up `id` in `locale` (falling back to English) and fill in `{name}`
+/// placeholders from `args`
+pub fn translate(locale: &str, id: &str, args: &[(&str, &str)]) -> String {
+    let template = CATALOG
+        .get(&(resolve_locale(Some(locale)), id))
+        .or_else(|| CATALOG.get(&(DEFAULT_LOCALE, id)))
+        .copied()
+        .unwrap_or(id);
+
+    args.iter().fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+/// Message id of the email subject for a notification type
+pub fn subject_id(notification_type: NotificationTypeCode) -> String {
+    format!("subject.{}", notification_type.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_locale_translates_every_english_message() {
+        let english: Vec<&str> = MESSAGES
+            .iter()
+            .filter(|(locale, _, _)| *locale == DEFAULT_LOCALE)
+            .map(|(_, id, _)| *id)
+            .collect();
+
+        for locale in supported_locales() {
+            for id in &english {
+                assert!(CATALOG.contains_key(&(locale, *id)), "{locale} is missing {id}");
+            }
+        }
+        assert_eq!(resolve_locale(Some("fr-CA")), "fr");
+        assert_eq!(resolve_locale(Some("pt_BR")), DEFAULT_LOCALE);
+        assert_eq!(resolve_locale(None), DEFAULT_LOCALE);
+        assert_eq!(translate("fr", "no.such.message", &[]), "no.such.message");
+    }
+}
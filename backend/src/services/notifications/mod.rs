@@ -43,7 +43,10 @@
 //! ```
 
 pub mod channels;
+pub mod delivery_failures;
+pub mod i18n;
 pub mod preferences;
+pub mod read_sync;
 pub mod service;
 pub mod types;
 
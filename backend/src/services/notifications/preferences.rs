@@ -1,6 +1,11 @@
 //! Notification preferences service
 //!
 //! Manages user notification preferences with caching for performance.
+//!
+//! When a user has no explicit preference for a notification type, the
+//! enabled channels come from a role-specific default (see `ROLE_DEFAULTS`)
+//! or else the type's `default_channels`. Defaults are resolved at read time,
+//! never written as preference rows.
 
 use chrono::Utc;
 use diesel::prelude::*;
@@ -10,10 +15,31 @@ use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::db::Pool;
-use crate::models::{NotificationPreferenceResponse, NotificationType as NotificationTypeModel};
+use crate::models::{NotificationPreferenceResponse, NotificationType as NotificationTypeModel, UserRole};
 
 use super::types::{NotificationChannel, NotificationTypeCode};
 
+/// Default channels that differ by role from a notification type's default
+const ROLE_DEFAULTS: &[(UserRole, NotificationTypeCode, &[NotificationChannel])] = &[
+    (
+        UserRole::Admin,
+        NotificationTypeCode::TicketAssigned,
+        &[NotificationChannel::InApp, NotificationChannel::Email],
+    ),
+    (UserRole::User, NotificationTypeCode::TicketAssigned, &[NotificationChannel::InApp]),
+];
+
+/// Role-specific default channels for a notification type, if any
+fn role_default_channels(role: UserRole, type_code: &str) -> Option<Vec<NotificationChannel>> {
+    ROLE_DEFAULTS
+        .iter()
+        .find(|(r, t, _)| *r == role && t.as_str() == type_code)
+        .map(|(_, _, channels)| channels.to_vec())
+}
+
+/// Channel settings per notification type, as applied by a bulk update
+pub type PreferenceMatrix = Vec<(NotificationTypeCode, Vec<(NotificationChannel, bool)>)>;
+
 /// Manages user notification preferences with caching
 pub struct PreferenceService {
     pool: Pool,
@@ -64,6 +90,29 @@ impl PreferenceService {
         Ok(channels)
     }
 
+    /// Default channels for a notification type: the role's default when one
+    /// is set, otherwise the type's `default_channels`
+    fn default_channels(
+        &self,
+        role: Option<UserRole>,
+        type_code: &str,
+        type_defaults: &serde_json::Value,
+    ) -> Vec<NotificationChannel> {
+        role.and_then(|role| role_default_channels(role, type_code))
+            .unwrap_or_else(|| self.parse_default_channels(type_defaults))
+    }
+
+    /// Look up a user's role (None if the user doesn't exist)
+    fn user_role(conn: &mut PgConnection, user_uuid_val: &Uuid) -> Option<UserRole> {
+        use crate::schema::users;
+
+        users::table
+            .filter(users::uuid.eq(user_uuid_val))
+            .select(users::role)
+            .first(conn)
+            .ok()
+    }
+
     /// Load preferences from database, falling back to defaults
     async fn load_preferences_from_db(
         &self,
@@ -92,9 +141,10 @@ impl PreferenceService {
             .load(&mut conn)
             .unwrap_or_default();
 
-        // If no preferences, use defaults from notification_types table
+        // If no preferences, use the role's or the notification type's defaults
         if prefs.is_empty() {
-            return Ok(self.parse_default_channels(&default_channels));
+            let role = Self::user_role(&mut conn, user_uuid_val);
+            return Ok(self.default_channels(role, type_code, &default_channels));
         }
 
         // Return enabled channels
@@ -164,6 +214,57 @@ impl PreferenceService {
         Ok(())
     }
 
+    /// Update many preferences at once (and invalidate cache)
+    ///
+    /// All changes are written in one transaction, so either every preference
+    /// is updated or none are. Returns the user's full preference set.
+    pub async fn set_preferences_bulk(
+        &self,
+        user_uuid_val: &Uuid,
+        updates: PreferenceMatrix,
+    ) -> Result<Vec<NotificationPreferenceResponse>, String> {
+        use crate::schema::notification_preferences::dsl::*;
+        use crate::schema::notification_types;
+
+        let mut conn = self.pool.get().map_err(|e| format!("Database error: {e}"))?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            for (notification_type, channels) in &updates {
+                let type_id: i32 = notification_types::table
+                    .filter(notification_types::code.eq(notification_type.as_str()))
+                    .select(notification_types::id)
+                    .first(conn)?;
+
+                for &(channel_val, enabled_val) in channels {
+                    let now = Utc::now().naive_utc();
+                    diesel::insert_into(notification_preferences)
+                        .values((
+                            user_uuid.eq(user_uuid_val),
+                            notification_type_id.eq(type_id),
+                            channel.eq(channel_val.as_str()),
+                            enabled.eq(enabled_val),
+                            created_at.eq(now),
+                            updated_at.eq(now),
+                        ))
+                        .on_conflict((user_uuid, notification_type_id, channel))
+                        .do_update()
+                        .set((enabled.eq(enabled_val), updated_at.eq(now)))
+                        .execute(conn)?;
+                }
+            }
+            Ok(())
+        })
+        .map_err(|e| format!("Failed to update preferences: {e}"))?;
+
+        // Invalidate cache for this user
+        {
+            let mut cache = self.cache.write().await;
+            cache.remove(user_uuid_val);
+        }
+
+        self.get_all_preferences(user_uuid_val).await
+    }
+
     /// Get all preferences for a user (for settings UI)
     pub async fn get_all_preferences(
         &self,
@@ -187,13 +288,15 @@ impl PreferenceService {
             .load(&mut conn)
             .unwrap_or_default();
 
+        let role = Self::user_role(&mut conn, user_uuid_val);
+
         // Build response grouped by notification type
         let mut responses = Vec::new();
         for notif_type in types {
             let mut channels_map: HashMap<String, bool> = HashMap::new();
 
-            // Parse default channels
-            let defaults = self.parse_default_channels(&notif_type.default_channels);
+            // Defaults for the user's role
+            let defaults = self.default_channels(role, &notif_type.code, &notif_type.default_channels);
 
             // Set defaults
             for chan in &[NotificationChannel::InApp, NotificationChannel::Email] {
@@ -226,3 +329,87 @@ impl PreferenceService {
         cache.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{setup_test_pool, TestFixtures};
+
+    #[tokio::test]
+    async fn unconfigured_admin_and_user_resolve_role_defaults() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let admin = TestFixtures::create_user(&mut conn, "prefdefaultadmin", UserRole::Admin);
+        let user = TestFixtures::create_user(&mut conn, "prefdefaultuser", UserRole::User);
+        drop(conn);
+
+        let service = PreferenceService::new(pool);
+        let assigned = NotificationTypeCode::TicketAssigned;
+
+        assert_eq!(
+            service.get_enabled_channels(&admin.uuid, &assigned).await.unwrap(),
+            vec![NotificationChannel::InApp, NotificationChannel::Email]
+        );
+        assert_eq!(
+            service.get_enabled_channels(&user.uuid, &assigned).await.unwrap(),
+            vec![NotificationChannel::InApp]
+        );
+
+        // The settings listing shows the same defaults
+        let listed = service.get_all_preferences(&user.uuid).await.unwrap();
+        let assigned_prefs = listed.iter().find(|p| p.notification_type == "ticket_assigned").unwrap();
+        assert_eq!(assigned_prefs.channels.get("email"), Some(&false));
+
+        // An explicit preference replaces the role default
+        service
+            .set_preference(&user.uuid, &assigned, NotificationChannel::Email, true)
+            .await
+            .unwrap();
+        assert_eq!(
+            service.get_enabled_channels(&user.uuid, &assigned).await.unwrap(),
+            vec![NotificationChannel::Email]
+        );
+    }
+
+    #[tokio::test]
+    async fn bulk_update_applies_every_preference() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "prefbulkuser", UserRole::User);
+        drop(conn);
+
+        let service = PreferenceService::new(pool);
+        let assigned = NotificationTypeCode::TicketAssigned;
+
+        // Warm the cache so the update has to invalidate it
+        service.get_enabled_channels(&user.uuid, &assigned).await.unwrap();
+
+        let prefs = service
+            .set_preferences_bulk(
+                &user.uuid,
+                vec![
+                    (
+                        assigned,
+                        vec![(NotificationChannel::InApp, false), (NotificationChannel::Email, true)],
+                    ),
+                    (
+                        NotificationTypeCode::CommentAdded,
+                        vec![(NotificationChannel::Email, false)],
+                    ),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let assigned_prefs = prefs.iter().find(|p| p.notification_type == "ticket_assigned").unwrap();
+        assert_eq!(assigned_prefs.channels.get("in_app"), Some(&false));
+        assert_eq!(assigned_prefs.channels.get("email"), Some(&true));
+        let comment_prefs = prefs.iter().find(|p| p.notification_type == "comment_added").unwrap();
+        assert_eq!(comment_prefs.channels.get("email"), Some(&false));
+
+        assert_eq!(
+            service.get_enabled_channels(&user.uuid, &assigned).await.unwrap(),
+            vec![NotificationChannel::Email]
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! Read-state sync across sessions
+//!
+//! When a user marks notifications read in one browser tab or device, their
+//! other open sessions need to drop the unread badge without refetching. The
+//! notification service publishes each change to a [`ReadStateSink`]; in
+//! production that is the SSE broadcaster.
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::handlers::sse::SseState;
+use crate::utils::sse::SseBroadcaster;
+
+/// A change to a user's notification read state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadStateChange {
+    pub user_uuid: Uuid,
+    /// Notifications that were marked read; None when all of them were
+    pub notification_ids: Option<Vec<i32>>,
+    /// Unread count after the change
+    pub unread_count: i64,
+}
+
+/// Destination for read-state changes
+#[async_trait]
+pub trait ReadStateSink: Send + Sync {
+    async fn publish(&self, change: ReadStateChange);
+}
+
+#[async_trait]
+impl ReadStateSink for SseState {
+    async fn publish(&self, change: ReadStateChange) {
+        SseBroadcaster::broadcast_notifications_read(
+            self,
+            &change.user_uuid,
+            change.notification_ids,
+            change.unread_count,
+        )
+        .await;
+    }
+}
@@ -7,16 +7,31 @@ use chrono::Utc;
 use diesel::prelude::*;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use tokio::sync::RwLock as TokioRwLock;
 use uuid::Uuid;
 
 use crate::db::Pool;
 use crate::models::{NewNotification, Notification, NotificationResponse};
+use crate::services::metrics;
 
 use super::channels::{ChannelError, NotificationDeliveryChannel};
+use super::delivery_failures::{DeliveryFailure, DeliveryFailureSink};
+use super::i18n;
 use super::preferences::PreferenceService;
+use super::read_sync::{ReadStateChange, ReadStateSink};
 use super::types::{DeliverableNotification, NotificationChannel, NotificationPayload};
 
+/// Unread notifications about the same entity within this window are
+/// collapsed into one
+const COLLAPSE_WINDOW_MINUTES: i64 = 60;
+
+/// Attempts per channel before a delivery is reported as failed
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; doubles with each further attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
 /// Central notification service that orchestrates notification creation and delivery
 pub struct NotificationService {
     pool: Pool,
@@ -25,6 +40,11 @@ pub struct NotificationService {
     preference_service: Arc<PreferenceService>,
     /// Cache: notification_type_code -> notification_type_id (uses tokio RwLock for async access)
     type_id_cache: TokioRwLock<HashMap<String, i32>>,
+    /// Where read-state changes are published so other sessions stay in sync
+    read_sink: Option<Arc<dyn ReadStateSink>>,
+    /// Where deliveries that exhausted their retries are reported
+    failure_sink: Option<Arc<dyn DeliveryFailureSink>>,
+    retry_base_delay: Duration,
 }
 
 impl NotificationService {
@@ -35,9 +55,24 @@ impl NotificationService {
             channels: RwLock::new(HashMap::new()),
             preference_service,
             type_id_cache: TokioRwLock::new(HashMap::new()),
+            read_sink: None,
+            failure_sink: None,
+            retry_base_delay: RETRY_BASE_DELAY,
         }
     }
 
+    /// Publish read-state changes to `sink` (the SSE state in production)
+    pub fn with_read_sync(mut self, sink: Arc<dyn ReadStateSink>) -> Self {
+        self.read_sink = Some(sink);
+        self
+    }
+
+    /// Report deliveries that exhausted their retries to `sink`
+    pub fn with_failure_sink(mut self, sink: Arc<dyn DeliveryFailureSink>) -> Self {
+        self.failure_sink = Some(sink);
+        self
+    }
+
     /// Get reference to the preference service
     pub fn preferences(&self) -> &Arc<PreferenceService> {
         &self.preference_service
@@ -58,6 +93,8 @@ impl NotificationService {
     ///
     /// This is the single entry point for all notifications in the system.
     pub async fn notify(&self, payload: NotificationPayload) -> Result<(), String> {
+        payload.validate()?;
+
         // Don't notify the actor themselves
         if payload.recipient_uuid == payload.actor.uuid {
             tracing::debug!(
@@ -132,7 +169,7 @@ impl NotificationService {
         }
 
         // 3. Persist notification to database
-        let notification_id = self
+        let (notification_id, count) = self
             .persist_notification(&payload, &deliverable_channels)
             .await?;
 
@@ -140,6 +177,8 @@ impl NotificationService {
         let deliverable = DeliverableNotification {
             id: Some(notification_id),
             uuid: Uuid::now_v7(),
+            count,
+            locale: self.recipient_locale(&payload.recipient_uuid),
             payload,
             channels: deliverable_channels.clone(),
         };
@@ -157,38 +196,134 @@ impl NotificationService {
         };
 
         for (channel_type, channel) in channels_to_deliver {
-            match channel.deliver(&deliverable).await {
+            if self
+                .deliver_with_retries(channel_type, channel.as_ref(), &deliverable)
+                .await
+            {
+                // Mark channel as delivered
+                if let Err(e) = self
+                    .mark_channel_delivered(notification_id, channel_type)
+                    .await
+                {
+                    tracing::warn!(error = %e, "Failed to mark channel as delivered");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Deliver on one channel, retrying transient failures
+    ///
+    /// Returns whether the notification was delivered. Once the retries are
+    /// exhausted the failure is published to the failure sink, once per
+    /// channel.
+    async fn deliver_with_retries(
+        &self,
+        channel_type: NotificationChannel,
+        channel: &dyn NotificationDeliveryChannel,
+        deliverable: &DeliverableNotification,
+    ) -> bool {
+        let mut attempt = 1;
+        loop {
+            let result = channel.deliver(deliverable).await;
+            metrics::record_notification_delivery(
+                channel_type.as_str(),
+                match &result {
+                    Ok(_) => "delivered",
+                    Err(ChannelError::RateLimited) => "rate_limited",
+                    Err(_) => "failed",
+                },
+            );
+
+            let error = match result {
                 Ok(_) => {
                     tracing::debug!(
                         channel = ?channel_type,
-                        notification_id,
+                        notification_id = deliverable.id,
+                        attempt,
                         "Delivered notification"
                     );
-                    // Mark channel as delivered
-                    if let Err(e) = self
-                        .mark_channel_delivered(notification_id, channel_type)
-                        .await
-                    {
-                        tracing::warn!(error = %e, "Failed to mark channel as delivered");
-                    }
+                    return true;
                 }
                 Err(ChannelError::RateLimited) => {
                     tracing::debug!(
                         channel = ?channel_type,
                         "Rate limited during delivery"
                     );
+                    return false;
                 }
-                Err(e) => {
+                // Retrying won't help; the recipient or channel isn't deliverable
+                Err(e @ (ChannelError::ChannelDisabled | ChannelError::InvalidRecipient(_))) => {
                     tracing::warn!(
                         channel = ?channel_type,
                         error = ?e,
                         "Failed to deliver notification"
                     );
+                    return false;
                 }
+                Err(e) => e,
+            };
+
+            if attempt < MAX_DELIVERY_ATTEMPTS {
+                tracing::debug!(
+                    channel = ?channel_type,
+                    error = ?error,
+                    attempt,
+                    "Notification delivery failed, retrying"
+                );
+                tokio::time::sleep(self.retry_base_delay * 2u32.pow(attempt - 1)).await;
+                attempt += 1;
+                continue;
+            }
+
+            tracing::warn!(
+                channel = ?channel_type,
+                error = ?error,
+                attempts = attempt,
+                "Failed to deliver notification after retries"
+            );
+            if let Some(sink) = &self.failure_sink {
+                sink.publish(DeliveryFailure {
+                    channel: channel_type,
+                    recipient_uuid: deliverable.payload.recipient_uuid,
+                    notification_id: deliverable.id,
+                    notification_type: deliverable.payload.notification_type.as_str().to_string(),
+                    error: error.to_string(),
+                    attempts: attempt,
+                })
+                .await;
             }
+            return false;
         }
+    }
 
-        Ok(())
+    /// Send a notification to each recipient, building the payload per recipient
+    ///
+    /// Recipients are deduplicated and each one's preferences are checked by
+    /// `notify`. Failures are logged and don't stop the remaining deliveries.
+    pub async fn notify_many<F>(&self, recipients: &[Uuid], build: F)
+    where
+        F: Fn(Uuid) -> NotificationPayload,
+    {
+        let mut notified: Vec<Uuid> = Vec::with_capacity(recipients.len());
+        for recipient in recipients {
+            if notified.contains(recipient) {
+                continue;
+            }
+            notified.push(*recipient);
+
+            let payload = build(*recipient);
+            let notification_type = payload.notification_type;
+            if let Err(e) = self.notify(payload).await {
+                tracing::warn!(
+                    error = %e,
+                    recipient = %recipient,
+                    notification_type = ?notification_type,
+                    "Failed to send notification"
+                );
+            }
+        }
     }
 
     /// Persist notification to database
@@ -196,7 +331,7 @@ impl NotificationService {
         &self,
         payload: &NotificationPayload,
         _channels: &[NotificationChannel],
-    ) -> Result<i32, String> {
+    ) -> Result<(i32, i32), String> {
         use crate::schema::notifications;
 
         let mut conn = self
@@ -208,16 +343,18 @@ impl NotificationService {
             .get_notification_type_id(payload.notification_type.as_str())
             .await?;
 
-        // Merge ticket_id into metadata for navigation purposes
-        let mut metadata = payload.metadata.clone();
-        if let serde_json::Value::Object(ref mut map) = metadata {
-            map.insert("ticket_id".to_string(), serde_json::json!(payload.entity.ticket_id()));
-        } else {
-            metadata = serde_json::json!({
-                "ticket_id": payload.entity.ticket_id()
-            });
+        // Merge ticket_id and the entity path into metadata for navigation purposes
+        let mut metadata = match payload.metadata.clone() {
+            serde_json::Value::Object(map) => map,
+            _ => serde_json::Map::new(),
+        };
+        if let Some(ticket_id) = payload.entity.ticket_id() {
+            metadata.insert("ticket_id".to_string(), serde_json::json!(ticket_id));
         }
+        metadata.insert("path".to_string(), serde_json::json!(payload.entity.path()));
+        let metadata = serde_json::Value::Object(metadata);
 
+        let group_key = payload.entity.group_key();
         let new_notification = NewNotification {
             uuid: Uuid::now_v7(),
             user_uuid: payload.recipient_uuid,
@@ -228,14 +365,53 @@ impl NotificationService {
             body: payload.body.clone(),
             metadata: Some(metadata),
             channels_delivered: serde_json::json!([]),
+            group_key: Some(group_key.clone()),
         };
 
-        let notification: Notification = diesel::insert_into(notifications::table)
-            .values(&new_notification)
-            .get_result(&mut conn)
-            .map_err(|e| format!("Failed to persist notification: {e}"))?;
+        // Collapse into a recent unread notification about the same entity
+        // rather than adding another row to the bell
+        let window_start = Utc::now().naive_utc() - chrono::Duration::minutes(COLLAPSE_WINDOW_MINUTES);
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let existing: Option<i32> = notifications::table
+                .filter(notifications::user_uuid.eq(payload.recipient_uuid))
+                .filter(notifications::notification_type_id.eq(type_id))
+                .filter(notifications::group_key.eq(&group_key))
+                .filter(notifications::is_read.eq(false))
+                .filter(notifications::created_at.ge(window_start))
+                .order(notifications::created_at.desc())
+                .select(notifications::id)
+                .for_update()
+                .first(conn)
+                .optional()?;
+
+            match existing {
+                Some(existing_id) => diesel::update(notifications::table.find(existing_id))
+                    .set((
+                        notifications::entity_type.eq(&new_notification.entity_type),
+                        notifications::entity_id.eq(new_notification.entity_id),
+                        notifications::title.eq(&new_notification.title),
+                        notifications::body.eq(&new_notification.body),
+                        notifications::metadata.eq(&new_notification.metadata),
+                        notifications::created_at.eq(Utc::now().naive_utc()),
+                        notifications::collapsed_count.eq(notifications::collapsed_count + 1),
+                    ))
+                    .returning((notifications::id, notifications::collapsed_count))
+                    .get_result(conn),
+                None => diesel::insert_into(notifications::table)
+                    .values(&new_notification)
+                    .returning((notifications::id, notifications::collapsed_count))
+                    .get_result(conn),
+            }
+        })
+        .map_err(|e| format!("Failed to persist notification: {e}"))
+    }
 
-        Ok(notification.id)
+    /// The recipient's notification locale, defaulting to English
+    fn recipient_locale(&self, user_uuid: &Uuid) -> &'static str {
+        match self.pool.get() {
+            Ok(mut conn) => i18n::user_locale(&mut conn, user_uuid),
+            Err(_) => i18n::DEFAULT_LOCALE,
+        }
     }
 
     /// Mark a channel as having delivered the notification
@@ -353,16 +529,22 @@ impl NotificationService {
                 metadata: n.metadata,
                 is_read: n.is_read,
                 created_at: n.created_at,
+                count: n.collapsed_count,
             })
             .collect())
     }
 
     /// Get all notifications for a user (with pagination)
+    ///
+    /// With `auto_read`, the returned notifications are marked read in the
+    /// same transaction. They are returned as they were before marking, so
+    /// the caller can still highlight the ones that were new.
     pub async fn get_all(
         &self,
         user_uuid_val: &Uuid,
         limit: i64,
         offset: i64,
+        auto_read: bool,
     ) -> Result<Vec<NotificationResponse>, String> {
         use crate::schema::notification_types;
         use crate::schema::notifications::dsl::*;
@@ -372,19 +554,40 @@ impl NotificationService {
             .get()
             .map_err(|e| format!("Database error: {e}"))?;
 
-        let results: Vec<(Notification, String)> = notifications
-            .inner_join(notification_types::table)
-            .filter(user_uuid.eq(user_uuid_val))
-            .order(created_at.desc())
-            .limit(limit)
-            .offset(offset)
-            .select((
-                crate::schema::notifications::all_columns,
-                notification_types::code,
-            ))
-            .load(&mut conn)
+        let (results, marked) = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                let results: Vec<(Notification, String)> = notifications
+                    .inner_join(notification_types::table)
+                    .filter(user_uuid.eq(user_uuid_val))
+                    .order(created_at.desc())
+                    .limit(limit)
+                    .offset(offset)
+                    .select((
+                        crate::schema::notifications::all_columns,
+                        notification_types::code,
+                    ))
+                    .load(conn)?;
+
+                let unread_ids: Vec<i32> = results
+                    .iter()
+                    .filter(|(n, _)| auto_read && !n.is_read)
+                    .map(|(n, _)| n.id)
+                    .collect();
+                if !unread_ids.is_empty() {
+                    diesel::update(notifications.filter(id.eq_any(&unread_ids)))
+                        .set((is_read.eq(true), read_at.eq(Some(Utc::now().naive_utc()))))
+                        .execute(conn)?;
+                }
+
+                Ok((results, unread_ids))
+            })
             .map_err(|e| format!("Query failed: {e}"))?;
 
+        if !marked.is_empty() {
+            self.publish_read_state(&mut conn, user_uuid_val, Some(marked))
+                .await;
+        }
+
         Ok(results
             .into_iter()
             .map(|(n, type_code)| NotificationResponse {
@@ -398,25 +601,45 @@ impl NotificationService {
                 metadata: n.metadata,
                 is_read: n.is_read,
                 created_at: n.created_at,
+                count: n.collapsed_count,
             })
             .collect())
     }
 
     /// Get unread notification count for a user
     pub async fn get_unread_count(&self, user_uuid_val: &Uuid) -> Result<i64, String> {
-        use crate::schema::notifications::dsl::*;
-
         let mut conn = self
             .pool
             .get()
             .map_err(|e| format!("Database error: {e}"))?;
 
-        notifications
-            .filter(user_uuid.eq(user_uuid_val))
-            .filter(is_read.eq(false))
-            .count()
-            .get_result(&mut conn)
-            .map_err(|e| format!("Query failed: {e}"))
+        count_unread(&mut conn, user_uuid_val).map_err(|e| format!("Query failed: {e}"))
+    }
+
+    /// Tell the user's other sessions that notifications were marked read
+    async fn publish_read_state(
+        &self,
+        conn: &mut crate::db::DbConnection,
+        user_uuid_val: &Uuid,
+        notification_ids: Option<Vec<i32>>,
+    ) {
+        let Some(sink) = &self.read_sink else {
+            return;
+        };
+
+        match count_unread(conn, user_uuid_val) {
+            Ok(unread_count) => {
+                sink.publish(ReadStateChange {
+                    user_uuid: *user_uuid_val,
+                    notification_ids,
+                    unread_count,
+                })
+                .await;
+            }
+            Err(e) => {
+                tracing::warn!(user = %user_uuid_val, error = %e, "Failed to count unread notifications for read sync");
+            }
+        }
     }
 
     /// Mark notifications as read
@@ -441,6 +664,11 @@ impl NotificationService {
         .execute(&mut conn)
         .map_err(|e| format!("Update failed: {e}"))?;
 
+        if count > 0 {
+            self.publish_read_state(&mut conn, user_uuid_val, Some(notification_ids.to_vec()))
+                .await;
+        }
+
         Ok(count)
     }
 
@@ -462,6 +690,10 @@ impl NotificationService {
         .execute(&mut conn)
         .map_err(|e| format!("Update failed: {e}"))?;
 
+        if count > 0 {
+            self.publish_read_state(&mut conn, user_uuid_val, None).await;
+        }
+
         Ok(count)
     }
 
@@ -514,3 +746,325 @@ impl NotificationService {
         Ok(count)
     }
 }
+
+fn count_unread(conn: &mut PgConnection, user_uuid_val: &Uuid) -> QueryResult<i64> {
+    use crate::schema::notifications::dsl::*;
+
+    notifications
+        .filter(user_uuid.eq(user_uuid_val))
+        .filter(is_read.eq(false))
+        .count()
+        .get_result(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::sse::SseState;
+    use crate::models::UserRole;
+    use crate::repository::ticket_watchers;
+    use crate::services::notifications::channels::in_app::InAppChannel;
+    use crate::services::notifications::channels::ChannelResult;
+    use crate::services::notifications::types::{NotificationActor, NotificationEntity, NotificationTypeCode};
+    use crate::test_helpers::{setup_test_pool, TestFixtures};
+    use tokio::sync::Mutex as TokioMutex;
+
+    /// Collects published read-state changes instead of broadcasting them
+    #[derive(Default)]
+    struct RecordingSink {
+        changes: TokioMutex<Vec<ReadStateChange>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ReadStateSink for RecordingSink {
+        async fn publish(&self, change: ReadStateChange) {
+            self.changes.lock().await.push(change);
+        }
+    }
+
+    /// Collects published delivery failures
+    #[derive(Default)]
+    struct RecordingFailureSink {
+        failures: TokioMutex<Vec<DeliveryFailure>>,
+    }
+
+    #[async_trait::async_trait]
+    impl DeliveryFailureSink for RecordingFailureSink {
+        async fn publish(&self, failure: DeliveryFailure) {
+            self.failures.lock().await.push(failure);
+        }
+    }
+
+    /// A channel whose backend is down, counting delivery attempts
+    #[derive(Default)]
+    struct UnreachableChannel {
+        attempts: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait::async_trait]
+    impl NotificationDeliveryChannel for UnreachableChannel {
+        fn channel_type(&self) -> NotificationChannel {
+            NotificationChannel::Email
+        }
+
+        async fn deliver(&self, _notification: &DeliverableNotification) -> ChannelResult<()> {
+            self.attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Err(ChannelError::DeliveryFailed("SMTP connection refused".to_string()))
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    async fn notify_ticket_assigned(service: &NotificationService, recipient: Uuid, actor: &crate::models::User, ticket_id: i32) {
+        let payload = NotificationPayload::new(
+            NotificationTypeCode::TicketAssigned,
+            recipient,
+            NotificationActor {
+                uuid: actor.uuid,
+                name: actor.name.clone(),
+                avatar_thumb: None,
+            },
+            NotificationEntity::Ticket {
+                id: ticket_id,
+                title: "Laptop won't boot".to_string(),
+            },
+        );
+        service.notify(payload).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_publish_one_delivery_failure() {
+        let sink = Arc::new(RecordingFailureSink::default());
+        let mut service = NotificationService::new(setup_test_pool()).with_failure_sink(sink.clone());
+        service.retry_base_delay = Duration::ZERO;
+        let channel = UnreachableChannel::default();
+
+        let recipient = Uuid::now_v7();
+        let deliverable = DeliverableNotification {
+            id: Some(42),
+            uuid: Uuid::now_v7(),
+            count: 1,
+            locale: "en",
+            payload: NotificationPayload::new(
+                NotificationTypeCode::TicketAssigned,
+                recipient,
+                NotificationActor {
+                    uuid: Uuid::now_v7(),
+                    name: "Dispatcher".to_string(),
+                    avatar_thumb: None,
+                },
+                NotificationEntity::Ticket {
+                    id: 7,
+                    title: "Laptop won't boot".to_string(),
+                },
+            ),
+            channels: vec![NotificationChannel::Email],
+        };
+
+        let delivered = service
+            .deliver_with_retries(NotificationChannel::Email, &channel, &deliverable)
+            .await;
+
+        assert!(!delivered);
+        assert_eq!(
+            channel.attempts.load(std::sync::atomic::Ordering::SeqCst),
+            MAX_DELIVERY_ATTEMPTS
+        );
+        let failures = sink.failures.lock().await;
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].channel, NotificationChannel::Email);
+        assert_eq!(failures[0].recipient_uuid, recipient);
+        assert_eq!(failures[0].notification_id, Some(42));
+        assert_eq!(failures[0].attempts, MAX_DELIVERY_ATTEMPTS);
+        assert!(failures[0].error.contains("SMTP connection refused"));
+    }
+
+    #[tokio::test]
+    async fn watchers_receive_comment_notifications() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let requester = TestFixtures::create_user(&mut conn, "notifywatchrequester", UserRole::User);
+        let tech = TestFixtures::create_user(&mut conn, "notifywatchtech", UserRole::Technician);
+        let watcher = TestFixtures::create_user(&mut conn, "notifywatchmanager", UserRole::User);
+        let bystander = TestFixtures::create_user(&mut conn, "notifywatchbystander", UserRole::User);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Watched Printer", Some(requester.uuid), None);
+        ticket_watchers::add_watcher(&mut conn, ticket.id, watcher.uuid).unwrap();
+        let comment = TestFixtures::create_comment(&mut conn, ticket.id, tech.uuid, "Swapped the toner");
+        let recipients = ticket_watchers::notification_recipients(&mut conn, &ticket, Some(tech.uuid)).unwrap();
+        drop(conn);
+
+        let service = NotificationService::new(pool);
+        service.register_channel(Arc::new(InAppChannel::new(Arc::new(SseState::new()))));
+
+        let actor = NotificationActor {
+            uuid: tech.uuid,
+            name: tech.name.clone(),
+            avatar_thumb: None,
+        };
+        service
+            .notify_many(&recipients, |recipient| {
+                NotificationPayload::new(
+                    NotificationTypeCode::CommentAdded,
+                    recipient,
+                    actor.clone(),
+                    NotificationEntity::Comment {
+                        id: comment.id,
+                        ticket_id: ticket.id,
+                        ticket_title: ticket.title.clone(),
+                    },
+                )
+            })
+            .await;
+
+        for (user, expected) in [(&requester, 1), (&watcher, 1), (&bystander, 0), (&tech, 0)] {
+            let notifications = service.get_all(&user.uuid, 10, 0, false).await.unwrap();
+            let comment_notifications = notifications
+                .iter()
+                .filter(|n| n.notification_type == "comment_added" && n.entity_id == comment.id)
+                .count();
+            assert_eq!(comment_notifications, expected, "notifications for {}", user.name);
+        }
+    }
+
+    #[tokio::test]
+    async fn mark_all_read_returns_count_and_syncs_other_sessions() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "readsyncuser", UserRole::Technician);
+        let actor = TestFixtures::create_user(&mut conn, "readsyncactor", UserRole::Admin);
+        let tickets: Vec<_> = (0..3)
+            .map(|_| TestFixtures::create_ticket(&mut conn, "Laptop won't boot", None, None))
+            .collect();
+        drop(conn);
+
+        let sink = Arc::new(RecordingSink::default());
+        let service = NotificationService::new(pool).with_read_sync(sink.clone());
+        service.register_channel(Arc::new(InAppChannel::new(Arc::new(SseState::new()))));
+        for ticket in &tickets {
+            notify_ticket_assigned(&service, user.uuid, &actor, ticket.id).await;
+        }
+
+        // Viewing with auto-read marks only the returned page
+        let page = service.get_all(&user.uuid, 1, 0, true).await.unwrap();
+        assert_eq!(page.len(), 1);
+        assert!(!page[0].is_read);
+        assert_eq!(service.get_unread_count(&user.uuid).await.unwrap(), 2);
+
+        assert_eq!(service.mark_all_read(&user.uuid).await.unwrap(), 2);
+        // Nothing left to mark, so nothing is broadcast
+        assert_eq!(service.mark_all_read(&user.uuid).await.unwrap(), 0);
+
+        let changes = sink.changes.lock().await;
+        assert_eq!(
+            *changes,
+            vec![
+                ReadStateChange {
+                    user_uuid: user.uuid,
+                    notification_ids: Some(vec![page[0].id]),
+                    unread_count: 2,
+                },
+                ReadStateChange {
+                    user_uuid: user.uuid,
+                    notification_ids: None,
+                    unread_count: 0,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn sse_sink_broadcasts_read_state_to_subscribers() {
+        let sse_state = SseState::new();
+        let mut receiver = sse_state.sender.subscribe();
+        let user_uuid = Uuid::new_v4();
+
+        sse_state
+            .publish(ReadStateChange {
+                user_uuid,
+                notification_ids: Some(vec![7]),
+                unread_count: 4,
+            })
+            .await;
+
+        match receiver.recv().await.unwrap() {
+            crate::handlers::sse::TicketEvent::NotificationsRead {
+                recipient_uuid,
+                notification_ids,
+                unread_count,
+                ..
+            } => {
+                assert_eq!(recipient_uuid, user_uuid.to_string());
+                assert_eq!(notification_ids, Some(vec![7]));
+                assert_eq!(unread_count, 4);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn comment_notifications_on_one_ticket_collapse() {
+        let pool = setup_test_pool();
+        let mut conn = pool.get().unwrap();
+        let requester = TestFixtures::create_user(&mut conn, "collapserequester", UserRole::User);
+        let tech = TestFixtures::create_user(&mut conn, "collapsetech", UserRole::Technician);
+        let busy = TestFixtures::create_ticket(&mut conn, "Busy thread", Some(requester.uuid), None);
+        let quiet = TestFixtures::create_ticket(&mut conn, "Quiet thread", Some(requester.uuid), None);
+        let comments: Vec<_> = ["Looking into it", "Rebooted the switch", "Should be fixed now"]
+            .into_iter()
+            .map(|content| TestFixtures::create_comment(&mut conn, busy.id, tech.uuid, content))
+            .collect();
+        let other = TestFixtures::create_comment(&mut conn, quiet.id, tech.uuid, "Any update?");
+        drop(conn);
+
+        let service = NotificationService::new(pool);
+        service.register_channel(Arc::new(InAppChannel::new(Arc::new(SseState::new()))));
+        let actor = NotificationActor {
+            uuid: tech.uuid,
+            name: tech.name.clone(),
+            avatar_thumb: None,
+        };
+        for (comment, ticket) in comments.iter().map(|c| (c, &busy)).chain([(&other, &quiet)]) {
+            let payload = NotificationPayload::new(
+                NotificationTypeCode::CommentAdded,
+                requester.uuid,
+                actor.clone(),
+                NotificationEntity::Comment {
+                    id: comment.id,
+                    ticket_id: ticket.id,
+                    ticket_title: ticket.title.clone(),
+                },
+            );
+            service.notify(payload).await.unwrap();
+        }
+
+        let notifications = service.get_all(&requester.uuid, 10, 0, false).await.unwrap();
+        assert_eq!(notifications.len(), 2);
+        let collapsed = notifications
+            .iter()
+            .find(|n| n.metadata.as_ref().and_then(|m| m["ticket_id"].as_i64()) == Some(busy.id as i64))
+            .unwrap();
+        assert_eq!(collapsed.count, 3);
+        // The collapsed notification points at the latest comment
+        assert_eq!(collapsed.entity_id, comments[2].id);
+        assert_eq!(service.get_unread_count(&requester.uuid).await.unwrap(), 2);
+
+        // Once read, the next comment starts a fresh notification
+        service.mark_all_read(&requester.uuid).await.unwrap();
+        let payload = NotificationPayload::new(
+            NotificationTypeCode::CommentAdded,
+            requester.uuid,
+            actor,
+            NotificationEntity::Comment {
+                id: comments[0].id,
+                ticket_id: busy.id,
+                ticket_title: busy.title.clone(),
+            },
+        );
+        service.notify(payload).await.unwrap();
+        let unread = service.get_unread(&requester.uuid, 10).await.unwrap();
+        assert_eq!(unread.len(), 1);
+        assert_eq!(unread[0].count, 1);
+    }
+}
@@ -16,6 +16,9 @@ pub enum NotificationTypeCode {
     CommentAdded,
     Mentioned,
     TicketCreatedRequester,
+    DeviceWarrantyExpiring,
+    TicketMerged,
+    NewDeviceSignIn,
 }
 
 impl NotificationTypeCode {
@@ -26,6 +29,9 @@ impl NotificationTypeCode {
             Self::CommentAdded => "comment_added",
             Self::Mentioned => "mentioned",
             Self::TicketCreatedRequester => "ticket_created_requester",
+            Self::DeviceWarrantyExpiring => "device_warranty_expiring",
+            Self::TicketMerged => "ticket_merged",
+            Self::NewDeviceSignIn => "new_device_sign_in",
         }
     }
 
@@ -36,10 +42,27 @@ impl NotificationTypeCode {
             "comment_added" => Some(Self::CommentAdded),
             "mentioned" => Some(Self::Mentioned),
             "ticket_created_requester" => Some(Self::TicketCreatedRequester),
+            "device_warranty_expiring" => Some(Self::DeviceWarrantyExpiring),
+            "ticket_merged" => Some(Self::TicketMerged),
+            "new_device_sign_in" => Some(Self::NewDeviceSignIn),
             _ => None,
         }
     }
 
+    /// Entity types a notification of this type may point at
+    pub fn entity_types(&self) -> &'static [&'static str] {
+        match self {
+            Self::TicketAssigned
+            | Self::TicketStatusChanged
+            | Self::TicketCreatedRequester
+            | Self::TicketMerged => &["ticket"],
+            Self::CommentAdded => &["comment"],
+            Self::Mentioned => &["comment", "ticket"],
+            Self::DeviceWarrantyExpiring => &["device"],
+            Self::NewDeviceSignIn => &["session"],
+        }
+    }
+
     /// Get human-readable title for this notification type
     pub fn title(&self) -> &'static str {
         match self {
@@ -48,6 +71,9 @@ impl NotificationTypeCode {
             Self::CommentAdded => "New Comment",
             Self::Mentioned => "Mentioned",
             Self::TicketCreatedRequester => "Ticket Created",
+            Self::DeviceWarrantyExpiring => "Device Warranty Expiring",
+            Self::TicketMerged => "Ticket Merged",
+            Self::NewDeviceSignIn => "New Device Sign-In",
         }
     }
 }
@@ -86,6 +112,9 @@ impl NotificationChannel {
 pub enum NotificationEntity {
     Ticket { id: i32, title: String },
     Comment { id: i32, ticket_id: i32, ticket_title: String },
+    Device { id: i32, name: String },
+    /// A sign-in session; `device` describes where it signed in from
+    Session { id: i32, device: String },
 }
 
 impl NotificationEntity {
@@ -93,6 +122,8 @@ impl NotificationEntity {
         match self {
             Self::Ticket { .. } => "ticket",
             Self::Comment { .. } => "comment",
+            Self::Device { .. } => "device",
+            Self::Session { .. } => "session",
         }
     }
 
@@ -100,16 +131,43 @@ impl NotificationEntity {
         match self {
             Self::Ticket { id, .. } => *id,
             Self::Comment { id, .. } => *id,
+            Self::Device { id, .. } => *id,
+            Self::Session { id, .. } => *id,
         }
     }
 
-    /// Get the ticket ID (for navigation)
-    pub fn ticket_id(&self) -> i32 {
+    /// Get the ticket ID (for navigation), if the entity belongs to a ticket
+    pub fn ticket_id(&self) -> Option<i32> {
         match self {
-            Self::Ticket { id, .. } => *id,
-            Self::Comment { ticket_id, .. } => *ticket_id,
+            Self::Ticket { id, .. } => Some(*id),
+            Self::Comment { ticket_id, .. } => Some(*ticket_id),
+            Self::Device { .. } | Self::Session { .. } => None,
+        }
+    }
+
+    /// Key that repeated notifications are collapsed by; comments group
+    /// under their ticket so a busy thread yields a single notification
+    pub fn group_key(&self) -> String {
+        match self {
+            Self::Comment { ticket_id, .. } => format!("ticket:{ticket_id}"),
+            _ => format!("{}:{}", self.entity_type(), self.entity_id()),
         }
     }
+
+    /// Frontend path for the entity
+    pub fn path(&self) -> String {
+        match self {
+            Self::Ticket { id, .. } => format!("/tickets/{id}"),
+            Self::Comment { ticket_id, .. } => format!("/tickets/{ticket_id}"),
+            Self::Device { id, .. } => format!("/devices/{id}"),
+            Self::Session { .. } => "/profile/settings/security".to_string(),
+        }
+    }
+
+    /// Absolute link to the entity on the frontend at `base_url`
+    pub fn url(&self, base_url: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), self.path())
+    }
 }
 
 /// Actor who triggered the notification
@@ -166,11 +224,26 @@ impl NotificationPayload {
         self
     }
 
-    #[allow(dead_code)]
     pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
         self.metadata = metadata;
         self
     }
+
+    /// Check the entity matches the notification type, so links from the
+    /// notification lead somewhere meaningful
+    pub fn validate(&self) -> Result<(), String> {
+        let expected = self.notification_type.entity_types();
+        if expected.contains(&self.entity.entity_type()) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} notification requires a {} entity, got {}",
+                self.notification_type.as_str(),
+                expected.join(" or "),
+                self.entity.entity_type()
+            ))
+        }
+    }
 }
 
 /// Notification ready for delivery (after preference checks)
@@ -178,6 +251,10 @@ impl NotificationPayload {
 pub struct DeliverableNotification {
     pub id: Option<i32>,
     pub uuid: Uuid,
+    /// Notifications collapsed into the persisted row, including this one
+    pub count: i32,
+    /// Recipient's catalog locale (see `i18n::resolve_locale`)
+    pub locale: &'static str,
     pub payload: NotificationPayload,
     pub channels: Vec<NotificationChannel>,
 }
@@ -191,7 +268,13 @@ pub struct NotificationEvent {
     pub body: Option<String>,
     pub entity_type: String,
     pub entity_id: i32,
-    pub ticket_id: i32,
+    pub ticket_id: Option<i32>,
+    /// Frontend path to navigate to
+    pub path: String,
+    /// Greater than 1 when this collapsed into an existing unread notification
+    pub count: i32,
+    /// Recipient's locale, for rendering on the client
+    pub locale: String,
     pub actor: NotificationActor,
     #[serde(default)]
     pub metadata: serde_json::Value,
@@ -208,6 +291,9 @@ impl From<&DeliverableNotification> for NotificationEvent {
             entity_type: notification.payload.entity.entity_type().to_string(),
             entity_id: notification.payload.entity.entity_id(),
             ticket_id: notification.payload.entity.ticket_id(),
+            path: notification.payload.entity.path(),
+            count: notification.count,
+            locale: notification.locale.to_string(),
             actor: notification.payload.actor.clone(),
             metadata: notification.payload.metadata.clone(),
             timestamp: notification.payload.created_at,
@@ -227,6 +313,9 @@ mod tests {
             NotificationTypeCode::CommentAdded,
             NotificationTypeCode::Mentioned,
             NotificationTypeCode::TicketCreatedRequester,
+            NotificationTypeCode::DeviceWarrantyExpiring,
+            NotificationTypeCode::TicketMerged,
+            NotificationTypeCode::NewDeviceSignIn,
         ];
         for variant in &variants {
             let s = variant.as_str();
@@ -249,6 +338,9 @@ mod tests {
             NotificationTypeCode::CommentAdded,
             NotificationTypeCode::Mentioned,
             NotificationTypeCode::TicketCreatedRequester,
+            NotificationTypeCode::DeviceWarrantyExpiring,
+            NotificationTypeCode::TicketMerged,
+            NotificationTypeCode::NewDeviceSignIn,
         ];
         for variant in &variants {
             assert!(!variant.title().is_empty(), "{:?} has empty title", variant);
@@ -274,7 +366,7 @@ mod tests {
         let entity = NotificationEntity::Ticket { id: 42, title: "Test".to_string() };
         assert_eq!(entity.entity_type(), "ticket");
         assert_eq!(entity.entity_id(), 42);
-        assert_eq!(entity.ticket_id(), 42);
+        assert_eq!(entity.ticket_id(), Some(42));
     }
 
     #[test]
@@ -286,7 +378,26 @@ mod tests {
         };
         assert_eq!(entity.entity_type(), "comment");
         assert_eq!(entity.entity_id(), 10);
-        assert_eq!(entity.ticket_id(), 42);
+        assert_eq!(entity.ticket_id(), Some(42));
+    }
+
+    #[test]
+    fn notification_entity_device_methods() {
+        let entity = NotificationEntity::Device { id: 7, name: "Laptop".to_string() };
+        assert_eq!(entity.entity_type(), "device");
+        assert_eq!(entity.entity_id(), 7);
+        assert_eq!(entity.ticket_id(), None);
+        assert_eq!(entity.path(), "/devices/7");
+        assert_eq!(entity.url("https://desk.example.com/"), "https://desk.example.com/devices/7");
+    }
+
+    #[test]
+    fn notification_entity_session_methods() {
+        let entity = NotificationEntity::Session { id: 3, device: "Firefox on Linux".to_string() };
+        assert_eq!(entity.entity_type(), "session");
+        assert_eq!(entity.entity_id(), 3);
+        assert_eq!(entity.ticket_id(), None);
+        assert_eq!(entity.path(), "/profile/settings/security");
     }
 
     #[test]
@@ -334,6 +445,8 @@ mod tests {
         let deliverable = DeliverableNotification {
             id: Some(1),
             uuid: notif_uuid,
+            count: 1,
+            locale: "en",
             payload: NotificationPayload::new(
                 NotificationTypeCode::CommentAdded,
                 Uuid::new_v4(),
@@ -348,7 +461,29 @@ mod tests {
         assert_eq!(event.notification_type, "comment_added");
         assert_eq!(event.entity_type, "comment");
         assert_eq!(event.entity_id, 5);
-        assert_eq!(event.ticket_id, 10);
+        assert_eq!(event.ticket_id, Some(10));
+        assert_eq!(event.path, "/tickets/10");
         assert_eq!(event.body.as_deref(), Some("hello"));
     }
+
+    #[test]
+    fn payload_entity_must_match_notification_type() {
+        let actor = NotificationActor {
+            uuid: Uuid::new_v4(),
+            name: "Actor".to_string(),
+            avatar_thumb: None,
+        };
+        let ticket = NotificationEntity::Ticket { id: 1, title: "T".to_string() };
+        let device = NotificationEntity::Device { id: 2, name: "Laptop".to_string() };
+
+        let valid = NotificationPayload::new(NotificationTypeCode::TicketAssigned, Uuid::new_v4(), actor.clone(), ticket)
+            .with_body("Assigned to you");
+        assert!(valid.validate().is_ok());
+
+        let mismatched = NotificationPayload::new(NotificationTypeCode::TicketAssigned, Uuid::new_v4(), actor, device);
+        assert_eq!(
+            mismatched.validate().unwrap_err(),
+            "ticket_assigned notification requires a ticket entity, got device"
+        );
+    }
 }
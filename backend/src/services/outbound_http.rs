@@ -0,0 +1,156 @@
+//! Shared Outbound HTTP
+//!
+//! One connection-pooled client for calls to systems outside Nosdesk
+//! (webhook deliveries, plugin proxy requests and plugin events), with a cap
+//! on how many run at once so a burst of events can't exhaust sockets or
+//! flood the receivers. Registered as app data and handed to the services.
+//!
+//! The client doesn't follow redirects: a webhook answering with a redirect
+//! counts as a failed delivery, and the plugin proxy follows them itself so
+//! each hop is checked against the plugin's permissions.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::{redirect, Client};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Outbound requests in flight at once, across all callers
+const DEFAULT_MAX_CONCURRENT: usize = 32;
+
+/// Time allowed to establish a connection
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Default time allowed for a whole request; callers may set their own
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Idle connections kept per host, and for how long
+const MAX_IDLE_PER_HOST: usize = 8;
+const IDLE_TIMEOUT_SECS: u64 = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutboundHttpConfig {
+    pub max_concurrent: usize,
+}
+
+impl Default for OutboundHttpConfig {
+    fn default() -> Self {
+        Self { max_concurrent: DEFAULT_MAX_CONCURRENT }
+    }
+}
+
+impl OutboundHttpConfig {
+    pub fn from_env() -> Self {
+        let max_concurrent = std::env::var("OUTBOUND_HTTP_MAX_CONCURRENT")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|max| *max > 0)
+            .unwrap_or(DEFAULT_MAX_CONCURRENT);
+
+        Self { max_concurrent }
+    }
+}
+
+/// The shared client and the limit on concurrent requests
+pub struct OutboundHttp {
+    client: Client,
+    permits: Arc<Semaphore>,
+}
+
+impl OutboundHttp {
+    pub fn new(config: OutboundHttpConfig) -> Self {
+        let client = Client::builder()
+            .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .pool_max_idle_per_host(MAX_IDLE_PER_HOST)
+            .pool_idle_timeout(Duration::from_secs(IDLE_TIMEOUT_SECS))
+            .redirect(redirect::Policy::none())
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            client,
+            permits: Arc::new(Semaphore::new(config.max_concurrent)),
+        }
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Wait for a slot to make one request
+    ///
+    /// Hold the permit until the response body has been read; the
+    /// connection is busy until then.
+    pub async fn permit(&self) -> OwnedSemaphorePermit {
+        self.permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("outbound HTTP semaphore is never closed")
+    }
+}
+
+impl Default for OutboundHttp {
+    fn default() -> Self {
+        Self::new(OutboundHttpConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Server that answers every request after `delay`, tracking the most
+    /// requests it had open at once
+    async fn counting_server(delay: Duration) -> (String, Arc<AtomicUsize>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let open = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let peak_seen = peak.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let open = open.clone();
+                let peak = peak.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let _ = socket.read(&mut buf).await;
+                    let now_open = open.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now_open, Ordering::SeqCst);
+                    tokio::time::sleep(delay).await;
+                    open.fetch_sub(1, Ordering::SeqCst);
+                    let _ = socket
+                        .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                        .await;
+                });
+            }
+        });
+        (format!("http://{addr}/hook"), peak_seen)
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_are_bounded() {
+        let http = Arc::new(OutboundHttp::new(OutboundHttpConfig { max_concurrent: 2 }));
+        let (url, peak) = counting_server(Duration::from_millis(100)).await;
+
+        let requests: Vec<_> = (0..6)
+            .map(|_| {
+                let http = http.clone();
+                let url = url.clone();
+                tokio::spawn(async move {
+                    let _permit = http.permit().await;
+                    http.client().post(&url).send().await.unwrap().text().await.unwrap()
+                })
+            })
+            .collect();
+        for request in requests {
+            assert_eq!(request.await.unwrap(), "ok");
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,236 @@
+//! Plugin Event Dispatch
+//!
+//! Delivers Nosdesk events to installed plugins that subscribe to them. A
+//! plugin manifest lists the events in `event_subscriptions` (same names as
+//! webhooks, e.g. "ticket.created") and the endpoint to call in
+//! `event_handler`.
+//!
+//! Unlike webhooks, deliveries are scoped to enabled plugins and go through
+//! the plugin proxy: the handler host must be covered by the plugin's
+//! `external:` permissions, and the payload is signed with the plugin's
+//! stored `event_secret` setting.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::db::{DbConnection, Pool};
+use crate::handlers::sse::{SseState, TicketEvent};
+use crate::models::PluginManifest;
+use crate::repository::plugins as plugin_repo;
+use crate::services::shutdown;
+use crate::services::webhooks::types::{WebhookEventType, WebhookPayload};
+
+use super::proxy::{plugin_secrets, PluginProxyService};
+
+/// An enabled plugin subscribed to an event
+pub struct PluginEventTarget {
+    pub plugin_id: i32,
+    pub plugin_name: String,
+    pub manifest: PluginManifest,
+    pub secrets: HashMap<String, String>,
+}
+
+/// Enabled plugins with an event handler that subscribe to `event_type`
+pub fn subscribed_plugins(
+    conn: &mut DbConnection,
+    event_type: &str,
+) -> Result<Vec<PluginEventTarget>, String> {
+    let plugins = plugin_repo::list_enabled_plugins(conn).map_err(|e| format!("DB error: {e}"))?;
+
+    let mut targets = Vec::new();
+    for plugin in plugins {
+        let manifest = match plugin.parse_manifest() {
+            Ok(m) => m,
+            Err(e) => {
+                warn!(plugin = plugin.name, error = %e, "Skipping plugin with invalid manifest");
+                continue;
+            }
+        };
+
+        if manifest.event_handler.is_none()
+            || !manifest.event_subscriptions.iter().any(|e| e == event_type)
+        {
+            continue;
+        }
+
+        let secrets = plugin_secrets(conn, &plugin, |_, _| {});
+        targets.push(PluginEventTarget {
+            plugin_id: plugin.id,
+            plugin_name: plugin.name,
+            manifest,
+            secrets,
+        });
+    }
+
+    Ok(targets)
+}
+
+/// Start the background task that dispatches SSE events to plugins
+pub fn spawn(pool: Pool, sse_state: Arc<SseState>, proxy: Arc<PluginProxyService>) {
+    let receiver = sse_state.sender.subscribe();
+    tokio::spawn(async move {
+        event_listener(pool, receiver, proxy).await;
+    });
+}
+
+/// Background task that listens to SSE events
+async fn event_listener(
+    pool: Pool,
+    mut receiver: broadcast::Receiver<TicketEvent>,
+    proxy: Arc<PluginProxyService>,
+) {
+    info!("Plugin event dispatcher started");
+
+    loop {
+        match receiver.recv().await {
+            Ok(_) if shutdown::webhooks().is_closed() => {
+                debug!("Shutting down, not dispatching event to plugins");
+            }
+            Ok(event) => {
+                if let Some(event_type) = WebhookEventType::from_sse_event(&event) {
+                    dispatch(&pool, &proxy, event_type, &event);
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(count)) => {
+                warn!(count, "Plugin event dispatcher lagged behind SSE events");
+            }
+            Err(broadcast::error::RecvError::Closed) => {
+                info!("SSE channel closed, plugin event dispatcher stopping");
+                break;
+            }
+        }
+    }
+}
+
+/// Queue delivery of one event to every subscribed plugin
+fn dispatch(
+    pool: &Pool,
+    proxy: &Arc<PluginProxyService>,
+    event_type: WebhookEventType,
+    event: &TicketEvent,
+) {
+    let event_type_str = event_type.as_str();
+
+    let targets = match pool.get() {
+        Ok(mut conn) => match subscribed_plugins(&mut conn, event_type_str) {
+            Ok(t) => t,
+            Err(e) => {
+                warn!(error = %e, "Failed to load plugins for event");
+                return;
+            }
+        },
+        Err(e) => {
+            warn!(error = %e, "Failed to get DB connection for plugin events");
+            return;
+        }
+    };
+
+    if targets.is_empty() {
+        return;
+    }
+
+    let payload = WebhookPayload {
+        id: Uuid::now_v7(),
+        event_type: event_type_str.to_string(),
+        timestamp: Utc::now(),
+        data: serde_json::to_value(event).unwrap_or_default(),
+    };
+
+    for target in targets {
+        let proxy = proxy.clone();
+        let pool = pool.clone();
+        let payload = payload.clone();
+        shutdown::webhooks().spawn(async move {
+            let result = proxy
+                .deliver_event(&target.plugin_name, &target.manifest, &payload, &target.secrets)
+                .await;
+
+            let details = match &result {
+                Ok(status) => serde_json::json!({
+                    "event_type": payload.event_type,
+                    "delivery_id": payload.id,
+                    "status": status,
+                }),
+                Err(e) => {
+                    warn!(plugin = target.plugin_name, error = %e, "Plugin event delivery failed");
+                    serde_json::json!({
+                        "event_type": payload.event_type,
+                        "delivery_id": payload.id,
+                        "error": e,
+                    })
+                }
+            };
+
+            let action = if result.is_ok() { "event_delivered" } else { "event_failed" };
+            if let Ok(mut conn) = pool.get() {
+                let _ = plugin_repo::log_plugin_activity(
+                    &mut conn,
+                    target.plugin_id,
+                    action.to_string(),
+                    Some(details),
+                    None,
+                );
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NewPlugin;
+    use crate::test_helpers::setup_test_connection;
+
+    fn install(conn: &mut DbConnection, name: &str, enabled: bool, subscriptions: &[&str]) {
+        let manifest = serde_json::json!({
+            "name": name,
+            "displayName": name,
+            "version": "1.0.0",
+            "permissions": ["external:hooks.example.com"],
+            "event_subscriptions": subscriptions,
+            "event_handler": "https://hooks.example.com/nosdesk",
+        });
+
+        plugin_repo::create_plugin(
+            conn,
+            NewPlugin {
+                name: name.to_string(),
+                display_name: name.to_string(),
+                version: "1.0.0".to_string(),
+                description: None,
+                manifest,
+                enabled,
+                trust_level: "community".to_string(),
+                installed_by: None,
+                source: "uploaded".to_string(),
+                verified_publisher: None,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn only_enabled_subscribed_plugins_receive_events() {
+        let mut conn = setup_test_connection();
+        install(&mut conn, "ticket-listener", true, &["ticket.created", "ticket.updated"]);
+        install(&mut conn, "comment-listener", true, &["comment.added"]);
+        install(&mut conn, "disabled-listener", false, &["ticket.created"]);
+
+        let names = |conn: &mut DbConnection, event: &str| -> Vec<String> {
+            subscribed_plugins(conn, event)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.plugin_name)
+                .collect()
+        };
+
+        assert_eq!(names(&mut conn, "ticket.created"), vec!["ticket-listener"]);
+        assert_eq!(names(&mut conn, "comment.added"), vec!["comment-listener"]);
+        assert!(names(&mut conn, "ticket.deleted").is_empty());
+    }
+}
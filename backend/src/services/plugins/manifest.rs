@@ -0,0 +1,230 @@
+//! Plugin manifest validation
+//!
+//! Manifests are checked when a plugin is installed so a malformed one is
+//! rejected up front, with a message per offending field, instead of
+//! failing once the plugin runs.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::models::PluginManifest;
+
+/// Setting types the settings UI can render
+pub const SETTING_TYPES: &[&str] = &["string", "number", "boolean", "secret", "select"];
+
+/// Plugin API permissions checked by the frontend runtime
+pub const API_PERMISSIONS: &[&str] = &["tickets:read", "tickets:comment", "devices:read", "storage"];
+
+/// Prefix of permissions allowing proxied requests to a host
+const EXTERNAL_PREFIX: &str = "external:";
+
+/// A problem with one field of a manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ManifestError {
+    /// Path to the field, e.g. `settings[1].type`
+    pub field: String,
+    pub message: String,
+}
+
+impl ManifestError {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { field: field.into(), message: message.into() }
+    }
+}
+
+impl PluginManifest {
+    /// Check required fields, setting definitions, permission formats and
+    /// that the version is semver, returning every problem found
+    pub fn validate(&self) -> Result<(), Vec<ManifestError>> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push(ManifestError::new("name", "is required"));
+        }
+        if self.display_name.trim().is_empty() {
+            errors.push(ManifestError::new("displayName", "is required"));
+        }
+        if self.version.trim().is_empty() {
+            errors.push(ManifestError::new("version", "is required"));
+        } else if !is_semver(&self.version) {
+            errors.push(ManifestError::new(
+                "version",
+                format!("'{}' is not a valid semantic version (e.g. 1.2.0)", self.version),
+            ));
+        }
+
+        let mut keys = HashSet::new();
+        for (i, setting) in self.settings.iter().enumerate() {
+            if setting.key.trim().is_empty() {
+                errors.push(ManifestError::new(format!("settings[{i}].key"), "is required"));
+            } else if !keys.insert(setting.key.as_str()) {
+                errors.push(ManifestError::new(
+                    format!("settings[{i}].key"),
+                    format!("duplicate setting key '{}'", setting.key),
+                ));
+            }
+            if !SETTING_TYPES.contains(&setting.setting_type.as_str()) {
+                errors.push(ManifestError::new(
+                    format!("settings[{i}].type"),
+                    format!(
+                        "unknown setting type '{}' (expected one of: {})",
+                        setting.setting_type,
+                        SETTING_TYPES.join(", ")
+                    ),
+                ));
+            }
+            if setting.label.trim().is_empty() {
+                errors.push(ManifestError::new(format!("settings[{i}].label"), "is required"));
+            }
+        }
+
+        for (i, permission) in self.permissions.iter().enumerate() {
+            let valid = match permission.strip_prefix(EXTERNAL_PREFIX) {
+                Some(host) => is_host_pattern(host),
+                None => API_PERMISSIONS.contains(&permission.as_str()),
+            };
+            if !valid {
+                errors.push(ManifestError::new(
+                    format!("permissions[{i}]"),
+                    format!(
+                        "'{permission}' is not a known permission or an 'external:<host>' / 'external:*.<domain>' entry"
+                    ),
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// MAJOR.MINOR.PATCH with optional `-prerelease` and `+build` parts
+fn is_semver(version: &str) -> bool {
+    let (version, build) = match version.split_once('+') {
+        Some((version, build)) => (version, Some(build)),
+        None => (version, None),
+    };
+    let (core, prerelease) = match version.split_once('-') {
+        Some((core, prerelease)) => (core, Some(prerelease)),
+        None => (version, None),
+    };
+
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts.iter().all(|part| is_numeric_identifier(part))
+        && prerelease.is_none_or(|pre| {
+            pre.split('.').all(|id| {
+                is_identifier(id) && (!id.chars().all(|c| c.is_ascii_digit()) || is_numeric_identifier(id))
+            })
+        })
+        && build.is_none_or(|build| build.split('.').all(is_identifier))
+}
+
+/// Digits without a leading zero
+fn is_numeric_identifier(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()) && (s == "0" || !s.starts_with('0'))
+}
+
+fn is_identifier(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// A hostname, optionally prefixed with `*.` to match its subdomains. No
+/// scheme, port or path.
+fn is_host_pattern(pattern: &str) -> bool {
+    let host = pattern.strip_prefix("*.").unwrap_or(pattern);
+    !host.is_empty()
+        && host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(json: serde_json::Value) -> PluginManifest {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn valid_manifest() -> serde_json::Value {
+        serde_json::json!({
+            "name": "github-integration",
+            "displayName": "GitHub Integration",
+            "version": "1.4.0-beta.2+build.7",
+            "permissions": ["tickets:read", "external:api.github.com", "external:*.githubusercontent.com"],
+            "settings": [
+                {"key": "github_token", "type": "secret", "label": "Token", "required": true},
+                {"key": "default_owner", "type": "string", "label": "Default owner"}
+            ]
+        })
+    }
+
+    fn invalid_fields(json: serde_json::Value) -> Vec<String> {
+        manifest(json)
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.field)
+            .collect()
+    }
+
+    #[test]
+    fn valid_manifest_passes() {
+        assert_eq!(manifest(valid_manifest()).validate(), Ok(()));
+    }
+
+    #[test]
+    fn bad_versions_are_rejected() {
+        for version in ["", "1.0", "v1.0.0", "1.02.0", "1.0.0-", "1.0.0-beta..1", "1.0.0+", "latest"] {
+            let mut json = valid_manifest();
+            json["version"] = version.into();
+            assert_eq!(invalid_fields(json), vec!["version"], "{version:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn unknown_setting_type_is_rejected() {
+        let mut json = valid_manifest();
+        json["settings"][1]["type"] = "colour".into();
+        let errors = manifest(json).validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "settings[1].type");
+        assert!(errors[0].message.contains("colour"));
+    }
+
+    #[test]
+    fn duplicate_setting_key_is_rejected() {
+        let mut json = valid_manifest();
+        json["settings"][1]["key"] = "github_token".into();
+        let errors = manifest(json).validate().unwrap_err();
+        assert_eq!(errors, vec![ManifestError::new("settings[1].key", "duplicate setting key 'github_token'")]);
+    }
+
+    #[test]
+    fn malformed_permissions_and_missing_fields_are_all_reported() {
+        let mut json = valid_manifest();
+        json["displayName"] = "".into();
+        json["settings"][0]["key"] = " ".into();
+        json["permissions"] = serde_json::json!([
+            "tickets:delete",
+            "external:https://api.github.com",
+            "external:api.github.com:443",
+            "external:",
+            "external:10.0.0.5",
+        ]);
+        assert_eq!(
+            invalid_fields(json),
+            vec!["displayName", "settings[0].key", "permissions[0]", "permissions[1]", "permissions[2]", "permissions[3]"]
+        );
+    }
+}
@@ -1,10 +1,14 @@
 //! Plugin Services
 //!
-//! Services for plugin functionality including external request proxying
-//! and provisioning.
+//! Services for plugin functionality including external request proxying,
+//! manifest validation, provisioning, signature verification and server-side
+//! event dispatch.
 
+pub mod events;
+pub mod manifest;
 pub mod provisioning;
 pub mod proxy;
+pub mod signing;
 
 pub use provisioning::provision_plugins;
 pub use proxy::PluginProxyService;
@@ -1,11 +1,12 @@
 //! Plugin Provisioning Service
 //!
-//! Scans the /app/plugins/ directory on startup and syncs plugins to the database.
+//! Scans the plugins directory (`PLUGINS_DIR`, /app/plugins/ by default) on
+//! startup and syncs plugins to the database.
 //! This enables infrastructure-as-code plugin management where plugins can be
 //! provisioned via volume mounts.
 //!
 //! Expected directory structure:
-//! /app/plugins/
+//! plugins/
 //! ├── my-plugin/
 //! │   ├── manifest.json
 //! │   └── bundle.js (optional)
@@ -19,22 +20,26 @@
 //! Example:
 //! PLUGIN_GITHUB_INTEGRATION_GITHUB_TOKEN=ghp_xxxx
 //! PLUGIN_GITHUB_INTEGRATION_DEFAULT_OWNER=myorg
+//!
+//! Plugins are only `official` when signed by a configured publisher (see
+//! [`super::signing`]); unsigned ones are provisioned as `community` and ones
+//! with an invalid signature are skipped.
 
 use std::env;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use chrono::Utc;
 use ring::digest::{Context, SHA256};
 use tracing::{debug, error, info, warn};
 
+use crate::config_utils::PluginPaths;
 use crate::db::DbConnection;
 use crate::models::{NewPlugin, PluginBundleUpdate, PluginManifest};
 use crate::repository::plugins as plugin_repo;
 use crate::utils::encryption;
 
-/// Default plugins directory path
-const PLUGINS_DIR: &str = "/app/plugins";
+use super::signing::{self, PublisherKeys, SignatureCheck};
 
 /// Result of provisioning a single plugin
 #[derive(Debug)]
@@ -47,8 +52,8 @@ pub enum ProvisionResult {
 }
 
 /// Provision all plugins from the plugins directory
-pub fn provision_plugins(conn: &mut DbConnection) -> Vec<ProvisionResult> {
-    let plugins_path = Path::new(PLUGINS_DIR);
+pub fn provision_plugins(conn: &mut DbConnection, paths: &PluginPaths) -> Vec<ProvisionResult> {
+    let plugins_path = paths.plugins_dir.as_path();
 
     if !plugins_path.exists() {
         info!("Plugins directory does not exist, skipping provisioning");
@@ -56,7 +61,7 @@ pub fn provision_plugins(conn: &mut DbConnection) -> Vec<ProvisionResult> {
     }
 
     if !plugins_path.is_dir() {
-        warn!("Plugins path is not a directory: {}", PLUGINS_DIR);
+        warn!("Plugins path is not a directory: {}", plugins_path.display());
         return vec![];
     }
 
@@ -68,12 +73,13 @@ pub fn provision_plugins(conn: &mut DbConnection) -> Vec<ProvisionResult> {
         }
     };
 
+    let keys = PublisherKeys::from_env();
     let mut results = Vec::new();
 
     for entry in entries.flatten() {
         let path = entry.path();
         if path.is_dir() {
-            let result = provision_plugin(conn, &path);
+            let result = provision_plugin(conn, &path, &keys, paths);
             results.push(result);
         }
     }
@@ -93,7 +99,12 @@ pub fn provision_plugins(conn: &mut DbConnection) -> Vec<ProvisionResult> {
 }
 
 /// Provision a single plugin from a directory
-fn provision_plugin(conn: &mut DbConnection, plugin_dir: &Path) -> ProvisionResult {
+fn provision_plugin(
+    conn: &mut DbConnection,
+    plugin_dir: &Path,
+    keys: &PublisherKeys,
+    paths: &PluginPaths,
+) -> ProvisionResult {
     let dir_name = plugin_dir
         .file_name()
         .and_then(|n| n.to_str())
@@ -130,11 +141,44 @@ fn provision_plugin(conn: &mut DbConnection, plugin_dir: &Path) -> ProvisionResu
         }
     };
 
+    // Verify the signature (covers manifest.json and bundle.js)
+    let signature = fs::read_to_string(plugin_dir.join(signing::SIGNATURE_FILE)).ok();
+    let bundle = fs::read(plugin_dir.join("bundle.js")).ok();
+    let check = signing::verify_plugin(
+        keys,
+        manifest_content.as_bytes(),
+        bundle.as_deref(),
+        signature.as_deref(),
+    );
+    if check == SignatureCheck::Invalid {
+        return ProvisionResult::Failed(
+            manifest.name.clone(),
+            "manifest.sig does not match any publisher key".to_string(),
+        );
+    }
+    let (trust_level, verified_publisher) = check.trust();
+
     // Check if plugin already exists
     let existing = plugin_repo::get_plugin_by_name(conn, &manifest.name);
 
     match existing {
         Ok(plugin) => {
+            // Keep the trust level in line with the current signature
+            if plugin.trust_level != trust_level || plugin.verified_publisher != verified_publisher {
+                let update = crate::models::PluginUpdate {
+                    trust_level: Some(trust_level.to_string()),
+                    verified_publisher: Some(verified_publisher.clone()),
+                    ..Default::default()
+                };
+                if let Err(e) = plugin_repo::update_plugin_by_uuid(conn, plugin.uuid, update) {
+                    return ProvisionResult::Failed(
+                        manifest.name.clone(),
+                        format!("Failed to update trust level: {e}"),
+                    );
+                }
+                info!("Plugin {} trust level set to {}", manifest.name, trust_level);
+            }
+
             // Plugin exists - check if it needs updating
             let existing_manifest: PluginManifest = match plugin.parse_manifest() {
                 Ok(m) => m,
@@ -151,7 +195,7 @@ fn provision_plugin(conn: &mut DbConnection, plugin_dir: &Path) -> ProvisionResu
                 // Check if bundle needs updating
                 let bundle_path = plugin_dir.join("bundle.js");
                 if bundle_path.exists() {
-                    if let Some(result) = update_bundle_if_changed(conn, &plugin, &bundle_path) {
+                    if let Some(result) = update_bundle_if_changed(conn, &plugin, &bundle_path, paths) {
                         // Still provision settings even if bundle changed
                         provision_settings_from_env(conn, &plugin, &manifest);
                         return result;
@@ -180,6 +224,7 @@ fn provision_plugin(conn: &mut DbConnection, plugin_dir: &Path) -> ProvisionResu
                 manifest: Some(manifest_json),
                 enabled: None,
                 trust_level: None,
+                verified_publisher: None,
             };
 
             if let Err(e) = plugin_repo::update_plugin_by_uuid(conn, plugin.uuid, update) {
@@ -192,7 +237,7 @@ fn provision_plugin(conn: &mut DbConnection, plugin_dir: &Path) -> ProvisionResu
             // Update bundle if present
             let bundle_path = plugin_dir.join("bundle.js");
             if bundle_path.exists() {
-                let _ = update_bundle(conn, &plugin, &bundle_path);
+                let _ = update_bundle(conn, &plugin, &bundle_path, paths);
             }
 
             // Provision settings from environment variables
@@ -220,9 +265,10 @@ fn provision_plugin(conn: &mut DbConnection, plugin_dir: &Path) -> ProvisionResu
                 description: manifest.description.clone(),
                 manifest: manifest_json,
                 enabled: true,
-                trust_level: "official".to_string(), // Provisioned plugins are trusted
+                trust_level: trust_level.to_string(),
                 installed_by: None,
                 source: "provisioned".to_string(),
+                verified_publisher,
             };
 
             let plugin = match plugin_repo::create_plugin(conn, new_plugin) {
@@ -238,7 +284,7 @@ fn provision_plugin(conn: &mut DbConnection, plugin_dir: &Path) -> ProvisionResu
             // Upload bundle if present
             let bundle_path = plugin_dir.join("bundle.js");
             if bundle_path.exists() {
-                let _ = update_bundle(conn, &plugin, &bundle_path);
+                let _ = update_bundle(conn, &plugin, &bundle_path, paths);
             }
 
             // Provision settings from environment variables
@@ -261,6 +307,7 @@ fn update_bundle(
     conn: &mut DbConnection,
     plugin: &crate::models::Plugin,
     bundle_path: &Path,
+    paths: &PluginPaths,
 ) -> Result<(), String> {
     let content = fs::read(bundle_path).map_err(|e| format!("Failed to read bundle: {e}"))?;
 
@@ -270,7 +317,7 @@ fn update_bundle(
     let hash = hex::encode(context.finish().as_ref());
 
     // Copy bundle to uploads directory
-    let upload_dir = PathBuf::from("/app/uploads/plugins").join(plugin.uuid.to_string());
+    let upload_dir = paths.bundle_dir(plugin.uuid);
     fs::create_dir_all(&upload_dir).map_err(|e| format!("Failed to create upload dir: {e}"))?;
 
     let dest_path = upload_dir.join("bundle.js");
@@ -295,6 +342,7 @@ fn update_bundle_if_changed(
     conn: &mut DbConnection,
     plugin: &crate::models::Plugin,
     bundle_path: &Path,
+    paths: &PluginPaths,
 ) -> Option<ProvisionResult> {
     let content = match fs::read(bundle_path) {
         Ok(c) => c,
@@ -317,7 +365,7 @@ fn update_bundle_if_changed(
     }
 
     // Bundle changed - update it
-    if let Err(e) = update_bundle(conn, plugin, bundle_path) {
+    if let Err(e) = update_bundle(conn, plugin, bundle_path, paths) {
         return Some(ProvisionResult::Failed(plugin.name.clone(), e));
     }
 
@@ -410,3 +458,54 @@ fn provision_settings_from_env(
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::plugins::signing::tests::{key_pair, sign};
+    use crate::test_helpers::setup_test_connection;
+    use ring::signature::KeyPair;
+    use std::path::PathBuf;
+
+    /// Write a plugin directory with an optional signature
+    fn plugin_dir(name: &str, signature: impl FnOnce(&[u8]) -> Option<String>) -> PathBuf {
+        let dir = env::temp_dir().join(format!("nosdesk-plugin-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let manifest = format!(r#"{{"name":"{name}","displayName":"{name}","version":"1.0.0"}}"#);
+        fs::write(dir.join("manifest.json"), &manifest).unwrap();
+        if let Some(signature) = signature(manifest.as_bytes()) {
+            fs::write(dir.join(signing::SIGNATURE_FILE), signature).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn provisioned_trust_follows_signature() {
+        let mut conn = setup_test_connection();
+        let publisher = key_pair();
+        let keys = PublisherKeys::default().with_key("acme", publisher.public_key().as_ref());
+        let paths = PluginPaths::default();
+
+        let signed = plugin_dir("signed-provisioned", |m| Some(sign(&publisher, m, None)));
+        let unsigned = plugin_dir("unsigned-provisioned", |_| None);
+        let forged = plugin_dir("forged-provisioned", |m| Some(sign(&key_pair(), m, None)));
+
+        assert!(matches!(provision_plugin(&mut conn, &signed, &keys, &paths), ProvisionResult::Created(_)));
+        assert!(matches!(provision_plugin(&mut conn, &unsigned, &keys, &paths), ProvisionResult::Created(_)));
+        assert!(matches!(provision_plugin(&mut conn, &forged, &keys, &paths), ProvisionResult::Failed(..)));
+
+        let plugin = plugin_repo::get_plugin_by_name(&mut conn, "signed-provisioned").unwrap();
+        assert_eq!(plugin.trust_level, "official");
+        assert_eq!(plugin.verified_publisher.as_deref(), Some("acme"));
+
+        let plugin = plugin_repo::get_plugin_by_name(&mut conn, "unsigned-provisioned").unwrap();
+        assert_eq!(plugin.trust_level, "community");
+        assert_eq!(plugin.verified_publisher, None);
+        assert!(plugin_repo::get_plugin_by_name(&mut conn, "forged-provisioned").is_err());
+
+        for dir in [signed, unsigned, forged] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
@@ -3,15 +3,194 @@
 //! Proxies external HTTP requests for plugins, providing:
 //! - Permission validation (plugins can only access whitelisted domains)
 //! - Request logging for audit
-//! - Rate limiting (future)
+//! - Per-plugin resource limits (rate, timeout, response size, redirects)
 //! - Response sanitization
 
-use reqwest::{Client, Method};
+use reqwest::{Method, StatusCode};
 use std::collections::HashMap;
-use std::time::Duration;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 
-use crate::models::{PluginManifest, PluginProxyRequest, PluginProxyResponse};
+use crate::db::DbConnection;
+use crate::models::{Plugin, PluginManifest, PluginProxyRequest, PluginProxyResponse};
+use crate::repository::plugins as plugin_repo;
+use crate::services::outbound_http::OutboundHttp;
+use crate::services::webhooks::signature::sign_payload;
+use crate::services::webhooks::types::WebhookPayload;
+use crate::utils::encryption;
+use crate::utils::rate_limit::{get_redis_url, RateLimiter};
+
+/// Proxied requests per plugin per minute
+const DEFAULT_REQUESTS_PER_MINUTE: u32 = 60;
+const MAX_REQUESTS_PER_MINUTE: u32 = 600;
+
+/// Event delivery timeout in seconds
+const EVENT_TIMEOUT_SECS: u64 = 30;
+
+/// Proxied request timeout in seconds
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const MAX_TIMEOUT_SECS: u64 = 120;
+
+/// Largest response body a plugin may receive
+const DEFAULT_MAX_RESPONSE_BYTES: usize = 5 * 1024 * 1024;
+const MAX_RESPONSE_BYTES: usize = 50 * 1024 * 1024;
+
+/// Redirects followed per proxied request
+const DEFAULT_MAX_REDIRECTS: usize = 5;
+const MAX_REDIRECTS: usize = 10;
+
+/// Plugin secret used to sign event deliveries
+pub const EVENT_SECRET_KEY: &str = "event_secret";
+
+/// Decrypt a plugin's secret settings
+///
+/// Secrets that fail to decrypt are left out (fail closed). `on_tampered` is
+/// called with the setting key when a secret fails its integrity check.
+pub fn plugin_secrets(
+    conn: &mut DbConnection,
+    plugin: &Plugin,
+    mut on_tampered: impl FnMut(&mut DbConnection, &str),
+) -> HashMap<String, String> {
+    let settings = match plugin_repo::get_plugin_settings(conn, plugin.id) {
+        Ok(s) => s,
+        Err(e) => {
+            error!("Failed to get plugin settings: {}", e);
+            vec![]
+        }
+    };
+
+    let mut secrets = HashMap::new();
+    for setting in settings {
+        if !setting.is_secret {
+            continue;
+        }
+        let Some(encrypted) = setting.value.as_ref().and_then(|v| v.as_str()) else {
+            continue;
+        };
+        match encryption::decrypt(encrypted) {
+            Ok(decrypted) => {
+                secrets.insert(setting.key, decrypted);
+            }
+            Err(encryption::DecryptError::Tampered) => {
+                error!(
+                    "Plugin secret '{}' for plugin '{}' failed integrity check",
+                    setting.key, plugin.name
+                );
+                on_tampered(conn, &setting.key);
+            }
+            Err(e) => {
+                error!(
+                    "Failed to decrypt secret '{}' for plugin '{}': {}",
+                    setting.key, plugin.name, e
+                );
+            }
+        }
+    }
+
+    secrets
+}
+
+/// Whether a host is a loopback, private or link-local address
+fn is_internal_host(host: &str) -> bool {
+    if host.eq_ignore_ascii_case("localhost") || host.ends_with(".localhost") {
+        return true;
+    }
+
+    match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+        }
+        Ok(IpAddr::V6(ip)) => {
+            let segment = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                // Unique local (fc00::/7) and link-local (fe80::/10)
+                || (segment & 0xfe00) == 0xfc00
+                || (segment & 0xffc0) == 0xfe80
+        }
+        Err(_) => false,
+    }
+}
+
+/// Effective limits for a plugin's proxied requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProxyLimits {
+    pub requests_per_minute: u32,
+    pub timeout: Duration,
+    pub max_response_bytes: usize,
+    pub max_redirects: usize,
+}
+
+impl ProxyLimits {
+    /// Limits declared in the manifest, clamped to the server maximums
+    pub fn for_manifest(manifest: &PluginManifest) -> Self {
+        let limits = &manifest.limits;
+        Self {
+            requests_per_minute: limits
+                .requests_per_minute
+                .unwrap_or(DEFAULT_REQUESTS_PER_MINUTE)
+                .clamp(1, MAX_REQUESTS_PER_MINUTE),
+            timeout: Duration::from_secs(
+                limits.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS).clamp(1, MAX_TIMEOUT_SECS),
+            ),
+            max_response_bytes: limits
+                .max_response_bytes
+                .unwrap_or(DEFAULT_MAX_RESPONSE_BYTES)
+                .clamp(1, MAX_RESPONSE_BYTES),
+            max_redirects: limits.max_redirects.unwrap_or(DEFAULT_MAX_REDIRECTS).min(MAX_REDIRECTS),
+        }
+    }
+}
+
+/// Why a proxied request was refused or failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProxyError {
+    PermissionDenied(String),
+    InvalidRequest(String),
+    RateLimited { requests_per_minute: u32 },
+    Timeout { secs: u64 },
+    ResponseTooLarge { max_bytes: usize },
+    TooManyRedirects { max_redirects: usize },
+    RequestFailed(String),
+}
+
+impl ProxyError {
+    /// Machine-readable error code returned to the plugin
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::PermissionDenied(_) => "permission_denied",
+            Self::InvalidRequest(_) => "invalid_request",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Timeout { .. } => "timeout",
+            Self::ResponseTooLarge { .. } => "response_too_large",
+            Self::TooManyRedirects { .. } => "too_many_redirects",
+            Self::RequestFailed(_) => "request_failed",
+        }
+    }
+}
+
+impl std::fmt::Display for ProxyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PermissionDenied(msg) | Self::InvalidRequest(msg) => write!(f, "{msg}"),
+            Self::RateLimited { requests_per_minute } => {
+                write!(f, "Rate limit of {requests_per_minute} requests per minute exceeded")
+            }
+            Self::Timeout { secs } => write!(f, "Request timed out after {secs}s"),
+            Self::ResponseTooLarge { max_bytes } => {
+                write!(f, "Response exceeds the {max_bytes} byte limit")
+            }
+            Self::TooManyRedirects { max_redirects } => {
+                write!(f, "More than {max_redirects} redirects")
+            }
+            Self::RequestFailed(msg) => write!(f, "Request failed: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ProxyError {}
 
 /// Plugin Proxy Service
 ///
@@ -21,18 +200,13 @@ use crate::models::{PluginManifest, PluginProxyRequest, PluginProxyResponse};
 /// - The request is logged for audit
 /// - Rate limits are enforced
 pub struct PluginProxyService {
-    client: Client,
+    http: Arc<OutboundHttp>,
 }
 
 impl PluginProxyService {
-    /// Create a new proxy service
-    pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
-
-        Self { client }
+    /// Create a new proxy service sending through the shared client
+    pub fn new(http: Arc<OutboundHttp>) -> Self {
+        Self { http }
     }
 
     /// Check if a plugin has permission to access a URL
@@ -68,6 +242,29 @@ impl PluginProxyService {
         false
     }
 
+    /// Check that a plugin's event handler may be called
+    ///
+    /// The handler must be HTTPS, covered by the plugin's `external:`
+    /// permissions and not point at an internal address.
+    fn check_event_handler(&self, manifest: &PluginManifest, url: &str) -> Result<(), String> {
+        let parsed = url::Url::parse(url).map_err(|_| format!("Invalid event handler URL '{url}'"))?;
+
+        if parsed.scheme() != "https" {
+            return Err("Event handler must use HTTPS".to_string());
+        }
+        if parsed.host_str().is_none_or(is_internal_host) {
+            return Err(format!("Event handler '{url}' points at an internal address"));
+        }
+        if !self.has_permission(manifest, url) {
+            return Err(format!(
+                "Plugin '{}' does not have permission to access '{}'",
+                manifest.name, url
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Get the authorization token for a URL based on plugin secrets
     ///
     /// Supports common patterns:
@@ -104,13 +301,45 @@ impl PluginProxyService {
     ///
     /// The `secrets` parameter contains plugin settings marked as secrets,
     /// which are used to inject Authorization headers for known APIs.
+    /// Requests are subject to the plugin's [`ProxyLimits`]; the rate limit
+    /// fails open if Redis is unavailable.
     pub async fn proxy_request(
         &self,
         plugin_name: &str,
         manifest: &PluginManifest,
         request: PluginProxyRequest,
         secrets: &HashMap<String, String>,
-    ) -> Result<PluginProxyResponse, String> {
+    ) -> Result<PluginProxyResponse, ProxyError> {
+        let limits = ProxyLimits::for_manifest(manifest);
+
+        let key = RateLimiter::plugin_proxy_key(plugin_name);
+        match RateLimiter::check_rate_limit(&get_redis_url(), &key, limits.requests_per_minute, 60)
+            .await
+        {
+            Ok(false) => {
+                warn!(plugin = plugin_name, "Plugin proxy rate limit exceeded");
+                return Err(ProxyError::RateLimited {
+                    requests_per_minute: limits.requests_per_minute,
+                });
+            }
+            Err(e) => {
+                warn!(plugin = plugin_name, "Rate limit check failed for plugin proxy: {}", e);
+            }
+            Ok(true) => {}
+        }
+
+        self.execute(plugin_name, manifest, request, secrets, limits).await
+    }
+
+    /// Send a proxied request within the given limits
+    async fn execute(
+        &self,
+        plugin_name: &str,
+        manifest: &PluginManifest,
+        request: PluginProxyRequest,
+        secrets: &HashMap<String, String>,
+        limits: ProxyLimits,
+    ) -> Result<PluginProxyResponse, ProxyError> {
         // Check permission
         if !self.has_permission(manifest, &request.url) {
             warn!(
@@ -118,10 +347,10 @@ impl PluginProxyService {
                 url = request.url,
                 "Plugin denied access to URL - no matching external permission"
             );
-            return Err(format!(
+            return Err(ProxyError::PermissionDenied(format!(
                 "Plugin '{}' does not have permission to access '{}'",
                 plugin_name, request.url
-            ));
+            )));
         }
 
         info!(
@@ -132,7 +361,7 @@ impl PluginProxyService {
         );
 
         // Parse method
-        let method = match request.method.to_uppercase().as_str() {
+        let mut method = match request.method.to_uppercase().as_str() {
             "GET" => Method::GET,
             "POST" => Method::POST,
             "PUT" => Method::PUT,
@@ -140,51 +369,103 @@ impl PluginProxyService {
             "DELETE" => Method::DELETE,
             "HEAD" => Method::HEAD,
             "OPTIONS" => Method::OPTIONS,
-            _ => return Err(format!("Unsupported HTTP method: {}", request.method)),
+            _ => {
+                return Err(ProxyError::InvalidRequest(format!(
+                    "Unsupported HTTP method: {}",
+                    request.method
+                )))
+            }
         };
 
-        // Build the request
-        let mut req = self.client.request(method, &request.url);
-
-        // Add headers from request
-        if let Some(headers) = request.headers {
-            for (key, value) in headers {
-                // Don't allow certain headers to be set by plugins
-                let key_lower = key.to_lowercase();
-                if key_lower == "host" || key_lower == "user-agent" || key_lower == "authorization" {
-                    continue;
+        // Redirects are followed here rather than by the client, so every
+        // hop needs the plugin's permission. The timeout covers all hops.
+        let _permit = self.http.permit().await;
+        let deadline = Instant::now() + limits.timeout;
+        let original_host = url::Url::parse(&request.url).ok().and_then(|u| u.host_str().map(str::to_string));
+        let mut url = request.url.clone();
+        let mut body = request.body;
+        let mut redirects = 0;
+        let mut response = loop {
+            let mut req = self
+                .http
+                .client()
+                .request(method.clone(), &url)
+                .timeout(deadline.saturating_duration_since(Instant::now()));
+
+            // Add headers from request
+            if let Some(headers) = &request.headers {
+                for (key, value) in headers {
+                    // Don't allow certain headers to be set by plugins
+                    let key_lower = key.to_lowercase();
+                    if key_lower == "host" || key_lower == "user-agent" || key_lower == "authorization" {
+                        continue;
+                    }
+                    req = req.header(key, value);
                 }
-                req = req.header(&key, &value);
             }
-        }
 
-        // Inject authorization from secrets if available
-        if let Some(auth) = self.get_auth_for_url(&request.url, secrets) {
-            debug!(plugin = plugin_name, "Injecting authorization header from secrets");
-            req = req.header("Authorization", auth);
-        }
+            // Inject authorization from secrets if available, but never
+            // into a redirect to another host
+            let same_host = url::Url::parse(&url).ok().and_then(|u| u.host_str().map(str::to_string)) == original_host;
+            if let Some(auth) = self.get_auth_for_url(&url, secrets).filter(|_| same_host) {
+                debug!(plugin = plugin_name, "Injecting authorization header from secrets");
+                req = req.header("Authorization", auth);
+            }
 
-        // Add custom User-Agent
-        req = req.header(
-            "User-Agent",
-            format!("Nosdesk-Plugin/{} ({})", manifest.version, plugin_name),
-        );
+            // Add custom User-Agent
+            req = req.header(
+                "User-Agent",
+                format!("Nosdesk-Plugin/{} ({})", manifest.version, plugin_name),
+            );
 
-        // Add body for methods that support it
-        if let Some(body) = request.body {
-            req = req.json(&body);
-        }
+            // Add body for methods that support it
+            if let Some(body) = &body {
+                req = req.json(body);
+            }
 
-        // Execute the request
-        let response = req.send().await.map_err(|e| {
-            error!(
-                plugin = plugin_name,
-                url = request.url,
-                error = %e,
-                "Failed to execute proxied request"
-            );
-            format!("Request failed: {e}")
-        })?;
+            // Execute the request
+            let response = req.send().await.map_err(|e| {
+                error!(
+                    plugin = plugin_name,
+                    url = url,
+                    error = %e,
+                    "Failed to execute proxied request"
+                );
+                request_error(e, &limits)
+            })?;
+
+            let Some(location) = redirect_location(&url, &response) else {
+                break response;
+            };
+            if redirects == limits.max_redirects {
+                return Err(ProxyError::TooManyRedirects {
+                    max_redirects: limits.max_redirects,
+                });
+            }
+            if !self.has_permission(manifest, &location) {
+                warn!(
+                    plugin = plugin_name,
+                    url = location,
+                    "Plugin denied redirect to URL - no matching external permission"
+                );
+                return Err(ProxyError::PermissionDenied(format!(
+                    "Plugin '{plugin_name}' does not have permission to access '{location}'"
+                )));
+            }
+
+            // 301-303 turn the request into a GET without a body, as
+            // browsers do; 307 and 308 repeat it as is
+            if matches!(
+                response.status(),
+                StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND | StatusCode::SEE_OTHER
+            ) && method != Method::HEAD
+            {
+                method = Method::GET;
+                body = None;
+            }
+            url = location;
+            redirects += 1;
+        };
 
         let status = response.status().as_u16();
 
@@ -196,8 +477,28 @@ impl PluginProxyService {
             }
         }
 
+        // Read the body up to the size limit
+        let too_large = ProxyError::ResponseTooLarge {
+            max_bytes: limits.max_response_bytes,
+        };
+        if response
+            .content_length()
+            .is_some_and(|len| len > limits.max_response_bytes as u64)
+        {
+            warn!(plugin = plugin_name, url = request.url, "Proxied response too large");
+            return Err(too_large);
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| request_error(e, &limits))? {
+            if bytes.len() + chunk.len() > limits.max_response_bytes {
+                warn!(plugin = plugin_name, url = request.url, "Proxied response too large");
+                return Err(too_large);
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
         // Get response body
-        let body = response.json::<serde_json::Value>().await.ok();
+        let body = serde_json::from_slice::<serde_json::Value>(&bytes).ok();
 
         debug!(
             plugin = plugin_name,
@@ -214,9 +515,97 @@ impl PluginProxyService {
     }
 }
 
-impl Default for PluginProxyService {
-    fn default() -> Self {
-        Self::new()
+/// Where a redirect response points, resolved against the request URL
+fn redirect_location(url: &str, response: &reqwest::Response) -> Option<String> {
+    if !response.status().is_redirection() {
+        return None;
+    }
+    let location = response.headers().get(reqwest::header::LOCATION)?.to_str().ok()?;
+    url::Url::parse(url).ok()?.join(location).ok().map(String::from)
+}
+
+/// Classify a failed proxied request
+fn request_error(e: reqwest::Error, limits: &ProxyLimits) -> ProxyError {
+    if e.is_timeout() {
+        ProxyError::Timeout {
+            secs: limits.timeout.as_secs(),
+        }
+    } else if e.is_redirect() {
+        ProxyError::TooManyRedirects {
+            max_redirects: limits.max_redirects,
+        }
+    } else {
+        ProxyError::RequestFailed(e.to_string())
+    }
+}
+
+impl PluginProxyService {
+    /// Deliver an event to a plugin's event handler
+    ///
+    /// The JSON payload is signed with the plugin's `event_secret` setting
+    /// (HMAC-SHA256 in `X-Nosdesk-Signature`, same scheme as webhooks).
+    /// Returns the response status.
+    pub async fn deliver_event(
+        &self,
+        plugin_name: &str,
+        manifest: &PluginManifest,
+        payload: &WebhookPayload,
+        secrets: &HashMap<String, String>,
+    ) -> Result<u16, String> {
+        let url = manifest
+            .event_handler
+            .as_deref()
+            .ok_or_else(|| format!("Plugin '{plugin_name}' has no event handler"))?;
+
+        if let Err(e) = self.check_event_handler(manifest, url) {
+            warn!(plugin = plugin_name, url, "Plugin event handler rejected: {}", e);
+            return Err(e);
+        }
+
+        let secret = secrets
+            .get(EVENT_SECRET_KEY)
+            .ok_or_else(|| format!("Plugin '{plugin_name}' has no '{EVENT_SECRET_KEY}' setting"))?;
+
+        let body = serde_json::to_string(payload)
+            .map_err(|e| format!("Failed to serialize payload: {e}"))?;
+        let signature = sign_payload(&body, secret);
+
+        let mut req = self
+            .http
+            .client()
+            .post(url)
+            .timeout(Duration::from_secs(EVENT_TIMEOUT_SECS))
+            .header("Content-Type", "application/json")
+            .header("X-Nosdesk-Signature", signature)
+            .header("X-Nosdesk-Event", &payload.event_type)
+            .header("X-Nosdesk-Delivery", payload.id.to_string())
+            .header(
+                "User-Agent",
+                format!("Nosdesk-Plugin/{} ({})", manifest.version, plugin_name),
+            );
+
+        if let Some(auth) = self.get_auth_for_url(url, secrets) {
+            req = req.header("Authorization", auth);
+        }
+
+        let _permit = self.http.permit().await;
+        let response = req.body(body).send().await.map_err(|e| {
+            error!(plugin = plugin_name, url, error = %e, "Failed to deliver plugin event");
+            format!("Request failed: {e}")
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Event handler returned {status}"));
+        }
+
+        debug!(
+            plugin = plugin_name,
+            event_type = payload.event_type,
+            status = status.as_u16(),
+            "Plugin event delivered"
+        );
+        Ok(status.as_u16())
     }
 }
 
@@ -238,12 +627,54 @@ mod tests {
             components: HashMap::new(),
             events: vec![],
             settings: vec![],
+            event_subscriptions: vec![],
+            event_handler: None,
+            limits: Default::default(),
         }
     }
 
+    /// Serve one canned HTTP response per connection, after `delay`
+    async fn mock_server(response: Vec<u8>, delay: Duration) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let response = response.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let _ = socket.read(&mut buf).await;
+                    tokio::time::sleep(delay).await;
+                    let _ = socket.write_all(&response).await;
+                });
+            }
+        });
+        format!("http://{addr}/data")
+    }
+
+    fn get(url: &str) -> PluginProxyRequest {
+        PluginProxyRequest {
+            url: url.to_string(),
+            method: "GET".to_string(),
+            headers: None,
+            body: None,
+        }
+    }
+
+    fn json_response(body: &str, content_length: bool) -> Vec<u8> {
+        let length = if content_length {
+            format!("Content-Length: {}\r\n", body.len())
+        } else {
+            String::new()
+        };
+        format!("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{length}Connection: close\r\n\r\n{body}")
+            .into_bytes()
+    }
+
     #[test]
     fn test_exact_domain_permission() {
-        let service = PluginProxyService::new();
+        let service = PluginProxyService::new(Arc::new(OutboundHttp::default()));
         let manifest = create_test_manifest(vec!["external:api.example.com".to_string()]);
 
         assert!(service.has_permission(&manifest, "https://api.example.com/v1/data"));
@@ -253,7 +684,7 @@ mod tests {
 
     #[test]
     fn test_wildcard_domain_permission() {
-        let service = PluginProxyService::new();
+        let service = PluginProxyService::new(Arc::new(OutboundHttp::default()));
         let manifest = create_test_manifest(vec!["external:*.example.com".to_string()]);
 
         assert!(service.has_permission(&manifest, "https://api.example.com/v1/data"));
@@ -264,9 +695,118 @@ mod tests {
 
     #[test]
     fn test_no_permission() {
-        let service = PluginProxyService::new();
+        let service = PluginProxyService::new(Arc::new(OutboundHttp::default()));
         let manifest = create_test_manifest(vec!["tickets:read".to_string()]);
 
         assert!(!service.has_permission(&manifest, "https://api.example.com/data"));
     }
+
+    #[test]
+    fn test_event_handler_restrictions() {
+        let service = PluginProxyService::new(Arc::new(OutboundHttp::default()));
+        let manifest = create_test_manifest(vec![
+            "external:hooks.example.com".to_string(),
+            "external:10.0.0.5".to_string(),
+        ]);
+
+        assert!(service
+            .check_event_handler(&manifest, "https://hooks.example.com/nosdesk")
+            .is_ok());
+        // Not HTTPS
+        assert!(service
+            .check_event_handler(&manifest, "http://hooks.example.com/nosdesk")
+            .is_err());
+        // Not covered by the plugin's permissions
+        assert!(service
+            .check_event_handler(&manifest, "https://other.example.com/nosdesk")
+            .is_err());
+        // Internal addresses are refused even when permitted
+        assert!(service.check_event_handler(&manifest, "https://10.0.0.5/nosdesk").is_err());
+        assert!(is_internal_host("127.0.0.1"));
+        assert!(is_internal_host("[::1]"));
+        assert!(is_internal_host("localhost"));
+        assert!(!is_internal_host("hooks.example.com"));
+    }
+
+    #[test]
+    fn test_manifest_limits_are_clamped() {
+        let mut manifest = create_test_manifest(vec![]);
+        assert_eq!(ProxyLimits::for_manifest(&manifest).timeout, Duration::from_secs(DEFAULT_TIMEOUT_SECS));
+
+        manifest.limits.timeout_secs = Some(3600);
+        manifest.limits.requests_per_minute = Some(0);
+        manifest.limits.max_redirects = Some(0);
+        let limits = ProxyLimits::for_manifest(&manifest);
+        assert_eq!(limits.timeout, Duration::from_secs(MAX_TIMEOUT_SECS));
+        assert_eq!(limits.requests_per_minute, 1);
+        assert_eq!(limits.max_redirects, 0);
+    }
+
+    #[tokio::test]
+    async fn test_proxy_request_timeout() {
+        let service = PluginProxyService::new(Arc::new(OutboundHttp::default()));
+        let mut manifest = create_test_manifest(vec!["external:127.0.0.1".to_string()]);
+        manifest.limits.timeout_secs = Some(1);
+        let limits = ProxyLimits::for_manifest(&manifest);
+
+        let url = mock_server(json_response("{}", true), Duration::from_secs(5)).await;
+        let result = service
+            .execute("test-plugin", &manifest, get(&url), &HashMap::new(), limits)
+            .await;
+
+        assert_eq!(result.unwrap_err(), ProxyError::Timeout { secs: 1 });
+    }
+
+    #[tokio::test]
+    async fn test_proxy_response_size_cap() {
+        let service = PluginProxyService::new(Arc::new(OutboundHttp::default()));
+        let mut manifest = create_test_manifest(vec!["external:127.0.0.1".to_string()]);
+        manifest.limits.max_response_bytes = Some(1024);
+        let limits = ProxyLimits::for_manifest(&manifest);
+        let large = format!("{{\"data\":\"{}\"}}", "x".repeat(4096));
+
+        // Rejected up front from Content-Length, and while streaming without it
+        for content_length in [true, false] {
+            let url = mock_server(json_response(&large, content_length), Duration::ZERO).await;
+            let result = service
+                .execute("test-plugin", &manifest, get(&url), &HashMap::new(), limits)
+                .await;
+            assert_eq!(result.unwrap_err(), ProxyError::ResponseTooLarge { max_bytes: 1024 });
+        }
+
+        // Responses within the limit are returned
+        let url = mock_server(json_response(r#"{"ok":true}"#, false), Duration::ZERO).await;
+        let response = service
+            .execute("test-plugin", &manifest, get(&url), &HashMap::new(), limits)
+            .await
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, Some(serde_json::json!({"ok": true})));
+    }
+
+    #[tokio::test]
+    async fn test_proxy_redirects_are_limited_and_checked() {
+        let service = PluginProxyService::new(Arc::new(OutboundHttp::default()));
+        let mut manifest = create_test_manifest(vec!["external:127.0.0.1".to_string()]);
+        manifest.limits.max_redirects = Some(2);
+        let limits = ProxyLimits::for_manifest(&manifest);
+        let redirect_to = |location: &str| {
+            format!("HTTP/1.1 302 Found\r\nLocation: {location}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .into_bytes()
+        };
+
+        // A redirect loop stops at the plugin's limit
+        let url = mock_server(redirect_to("/data"), Duration::ZERO).await;
+        let result = service
+            .execute("test-plugin", &manifest, get(&url), &HashMap::new(), limits)
+            .await;
+        assert_eq!(result.unwrap_err(), ProxyError::TooManyRedirects { max_redirects: 2 });
+
+        // Redirects can't lead outside the plugin's permissions
+        let url = mock_server(redirect_to("http://169.254.169.254/latest/meta-data"), Duration::ZERO).await;
+        let result = service
+            .execute("test-plugin", &manifest, get(&url), &HashMap::new(), limits)
+            .await;
+        assert_eq!(result.unwrap_err().code(), "permission_denied");
+    }
 }
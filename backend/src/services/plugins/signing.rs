@@ -0,0 +1,180 @@
+//! Plugin Signing
+//!
+//! Verifies plugin signatures against configured publisher keys. A signed
+//! plugin ships `manifest.sig` next to `manifest.json`: the base64 Ed25519
+//! signature over the bytes of `manifest.json` followed by the bytes of
+//! `bundle.js` (when present).
+//!
+//! Publisher keys are configured as comma-separated `name:key` pairs, where
+//! the key is the base64 raw 32-byte Ed25519 public key:
+//! PLUGIN_PUBLISHER_KEYS=nosdesk:<base64 key>,acme:<base64 key>
+//!
+//! Only plugins with a valid signature are `official`; unsigned plugins are
+//! installed as `community`.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use tracing::warn;
+
+/// Signature file shipped alongside manifest.json
+pub const SIGNATURE_FILE: &str = "manifest.sig";
+
+/// Trust level for plugins signed by a configured publisher
+pub const TRUST_OFFICIAL: &str = "official";
+
+/// Trust level for unsigned plugins
+pub const TRUST_COMMUNITY: &str = "community";
+
+/// Public keys of trusted plugin publishers
+#[derive(Debug, Clone, Default)]
+pub struct PublisherKeys {
+    keys: Vec<(String, Vec<u8>)>,
+}
+
+impl PublisherKeys {
+    /// Read PLUGIN_PUBLISHER_KEYS
+    pub fn from_env() -> Self {
+        std::env::var("PLUGIN_PUBLISHER_KEYS")
+            .map(|v| Self::parse(&v))
+            .unwrap_or_default()
+    }
+
+    /// Parse `name:base64-key` pairs, skipping malformed entries
+    pub fn parse(value: &str) -> Self {
+        let keys = value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let parsed = entry.split_once(':').and_then(|(name, key)| {
+                    let key = STANDARD.decode(key.trim()).ok()?;
+                    Some((name.trim().to_string(), key))
+                });
+                if parsed.is_none() {
+                    warn!("Ignoring malformed plugin publisher key entry");
+                }
+                parsed
+            })
+            .collect();
+
+        Self { keys }
+    }
+
+    /// Add a publisher key
+    pub fn with_key(mut self, publisher: &str, public_key: &[u8]) -> Self {
+        self.keys.push((publisher.to_string(), public_key.to_vec()));
+        self
+    }
+}
+
+/// Outcome of checking a plugin's signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureCheck {
+    /// Signed by a configured publisher
+    Verified { publisher: String },
+    /// No signature shipped
+    Unsigned,
+    /// A signature was shipped but doesn't match any publisher key
+    Invalid,
+}
+
+impl SignatureCheck {
+    /// Trust level and verified publisher for this outcome
+    pub fn trust(&self) -> (&'static str, Option<String>) {
+        match self {
+            Self::Verified { publisher } => (TRUST_OFFICIAL, Some(publisher.clone())),
+            Self::Unsigned | Self::Invalid => (TRUST_COMMUNITY, None),
+        }
+    }
+}
+
+/// Check a plugin's signature against the publisher keys
+pub fn verify_plugin(
+    keys: &PublisherKeys,
+    manifest: &[u8],
+    bundle: Option<&[u8]>,
+    signature: Option<&str>,
+) -> SignatureCheck {
+    let Some(signature) = signature.map(str::trim).filter(|s| !s.is_empty()) else {
+        return SignatureCheck::Unsigned;
+    };
+    let Ok(signature) = STANDARD.decode(signature) else {
+        return SignatureCheck::Invalid;
+    };
+
+    let mut message = manifest.to_vec();
+    if let Some(bundle) = bundle {
+        message.extend_from_slice(bundle);
+    }
+
+    keys.keys
+        .iter()
+        .find(|(_, key)| UnparsedPublicKey::new(&ED25519, key).verify(&message, &signature).is_ok())
+        .map(|(publisher, _)| SignatureCheck::Verified {
+            publisher: publisher.clone(),
+        })
+        .unwrap_or(SignatureCheck::Invalid)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    /// Generate a publisher key pair
+    pub(crate) fn key_pair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    /// Sign a manifest (and bundle) the way a publisher would
+    pub(crate) fn sign(key_pair: &Ed25519KeyPair, manifest: &[u8], bundle: Option<&[u8]>) -> String {
+        let mut message = manifest.to_vec();
+        message.extend_from_slice(bundle.unwrap_or_default());
+        STANDARD.encode(key_pair.sign(&message).as_ref())
+    }
+
+    #[test]
+    fn signed_plugin_is_official_and_unsigned_is_community() {
+        let publisher = key_pair();
+        let keys = PublisherKeys::default().with_key("acme", publisher.public_key().as_ref());
+        let manifest = br#"{"name":"signed-plugin"}"#;
+        let bundle = b"export default {}";
+
+        let signature = sign(&publisher, manifest, Some(bundle));
+        let check = verify_plugin(&keys, manifest, Some(bundle), Some(&signature));
+        assert_eq!(check.trust(), (TRUST_OFFICIAL, Some("acme".to_string())));
+
+        let check = verify_plugin(&keys, manifest, Some(bundle), None);
+        assert_eq!(check, SignatureCheck::Unsigned);
+        assert_eq!(check.trust(), (TRUST_COMMUNITY, None));
+    }
+
+    #[test]
+    fn tampered_or_foreign_signatures_are_invalid() {
+        let publisher = key_pair();
+        let keys = PublisherKeys::default().with_key("acme", publisher.public_key().as_ref());
+        let manifest = br#"{"name":"signed-plugin"}"#;
+
+        let signature = sign(&publisher, manifest, None);
+        // Bundle added after signing
+        assert_eq!(
+            verify_plugin(&keys, manifest, Some(b"alert(1)"), Some(&signature)),
+            SignatureCheck::Invalid
+        );
+
+        let stranger = key_pair();
+        let signature = sign(&stranger, manifest, None);
+        assert_eq!(verify_plugin(&keys, manifest, None, Some(&signature)), SignatureCheck::Invalid);
+        assert_eq!(verify_plugin(&keys, manifest, None, Some("not base64!")), SignatureCheck::Invalid);
+    }
+
+    #[test]
+    fn publisher_keys_parse() {
+        let key = STANDARD.encode([7u8; 32]);
+        let keys = PublisherKeys::parse(&format!("acme:{key}, broken ,other:{key}"));
+        let names: Vec<&str> = keys.keys.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["acme", "other"]);
+    }
+}
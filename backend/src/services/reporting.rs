@@ -0,0 +1,441 @@
+//! Ticket Reporting
+//!
+//! Aggregate ticket metrics for a date range: tickets created and closed,
+//! resolution times, open tickets by priority, per-assignee load and time
+//! logged in worklogs. The report is served as JSON and, when
+//! `REPORT_SUMMARY_RECIPIENTS` is set, emailed as a weekly summary.
+//!
+//! Resolution time is `closed_at - created_at` over closed tickets only.
+//! Tickets closed by merging into another are duplicates rather than
+//! resolved work, so they are left out of resolution times.
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{Nullable, Timestamptz};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::{DbConnection, Pool};
+use crate::models::{TicketPriority, TicketStatus};
+use crate::schema::{ticket_worklogs, tickets, users};
+use crate::utils::email::EmailService;
+
+/// Default time between summary emails (one week)
+const DEFAULT_SUMMARY_INTERVAL_HOURS: u64 = 24 * 7;
+
+/// Number of tickets created in `[from, to)`
+pub fn tickets_created_between(conn: &mut DbConnection, from: NaiveDateTime, to: NaiveDateTime) -> QueryResult<i64> {
+    tickets::table
+        .filter(tickets::created_at.ge(from))
+        .filter(tickets::created_at.lt(to))
+        .count()
+        .get_result(conn)
+}
+
+/// Number of tickets closed in `[from, to)`
+pub fn tickets_closed_between(conn: &mut DbConnection, from: NaiveDateTime, to: NaiveDateTime) -> QueryResult<i64> {
+    // Boxed because the TicketStatus SQL type has no QueryId
+    tickets::table
+        .into_boxed()
+        .filter(tickets::status.eq(TicketStatus::Closed))
+        .filter(tickets::closed_at.ge(from))
+        .filter(tickets::closed_at.lt(to))
+        .count()
+        .get_result(conn)
+}
+
+#[derive(QueryableByName)]
+struct ResolutionRow {
+    #[diesel(sql_type = Nullable<diesel::sql_types::Double>)]
+    avg_seconds: Option<f64>,
+    #[diesel(sql_type = Nullable<diesel::sql_types::Double>)]
+    median_seconds: Option<f64>,
+}
+
+/// Resolution time statistics, in hours
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ResolutionTime {
+    pub average_hours: Option<f64>,
+    pub median_hours: Option<f64>,
+}
+
+/// Average and median `closed_at - created_at` over tickets closed in
+/// `[from, to)`. `None` when no tickets were closed in the range.
+pub fn resolution_time(conn: &mut DbConnection, from: NaiveDateTime, to: NaiveDateTime) -> QueryResult<ResolutionTime> {
+    let row: ResolutionRow = diesel::sql_query(
+        "SELECT
+            AVG(EXTRACT(EPOCH FROM (closed_at - created_at)))::float8 AS avg_seconds,
+            PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY EXTRACT(EPOCH FROM (closed_at - created_at)))::float8 AS median_seconds
+         FROM tickets
+         WHERE status = 'closed'
+           AND closed_at IS NOT NULL
+           AND merged_into_id IS NULL
+           AND closed_at >= $1 AND closed_at < $2",
+    )
+    .bind::<Timestamptz, _>(from)
+    .bind::<Timestamptz, _>(to)
+    .get_result(conn)?;
+
+    let hours = |seconds: Option<f64>| seconds.map(|s| s / 3600.0);
+    Ok(ResolutionTime {
+        average_hours: hours(row.avg_seconds),
+        median_hours: hours(row.median_seconds),
+    })
+}
+
+/// Average resolution time in hours over tickets closed in `[from, to)`
+pub fn avg_resolution_time(conn: &mut DbConnection, from: NaiveDateTime, to: NaiveDateTime) -> QueryResult<Option<f64>> {
+    resolution_time(conn, from, to).map(|r| r.average_hours)
+}
+
+/// Count of tickets per priority among those not yet closed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct OpenByPriority {
+    pub low: i64,
+    pub medium: i64,
+    pub high: i64,
+}
+
+/// Tickets currently open or in progress, by priority
+pub fn open_by_priority(conn: &mut DbConnection) -> QueryResult<OpenByPriority> {
+    // Boxed because the TicketStatus SQL type has no QueryId
+    let counts: Vec<(TicketPriority, i64)> = tickets::table
+        .filter(tickets::status.ne(TicketStatus::Closed))
+        .group_by(tickets::priority)
+        .select((tickets::priority, diesel::dsl::count_star()))
+        .into_boxed()
+        .load(conn)?;
+
+    let mut result = OpenByPriority::default();
+    for (priority, count) in counts {
+        match priority {
+            TicketPriority::Low => result.low = count,
+            TicketPriority::Medium => result.medium = count,
+            TicketPriority::High => result.high = count,
+        }
+    }
+    Ok(result)
+}
+
+/// Work held by one assignee
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AssigneeLoad {
+    pub assignee_uuid: Uuid,
+    pub name: String,
+    /// Tickets assigned to them that aren't closed
+    pub open: i64,
+    /// Tickets assigned to them that were closed in the range
+    pub closed: i64,
+}
+
+/// Open tickets and tickets closed in `[from, to)` per assignee, busiest first
+pub fn assignee_load(conn: &mut DbConnection, from: NaiveDateTime, to: NaiveDateTime) -> QueryResult<Vec<AssigneeLoad>> {
+    let open: Vec<(Uuid, String, i64)> = tickets::table
+        .inner_join(users::table.on(tickets::assignee_uuid.eq(users::uuid.nullable())))
+        .filter(tickets::status.ne(TicketStatus::Closed))
+        .group_by((users::uuid, users::name))
+        .select((users::uuid, users::name, diesel::dsl::count_star()))
+        .into_boxed()
+        .load(conn)?;
+
+    let closed: Vec<(Uuid, String, i64)> = tickets::table
+        .inner_join(users::table.on(tickets::assignee_uuid.eq(users::uuid.nullable())))
+        .filter(tickets::status.eq(TicketStatus::Closed))
+        .filter(tickets::closed_at.ge(from))
+        .filter(tickets::closed_at.lt(to))
+        .group_by((users::uuid, users::name))
+        .select((users::uuid, users::name, diesel::dsl::count_star()))
+        .into_boxed()
+        .load(conn)?;
+
+    let mut load: Vec<AssigneeLoad> = open
+        .into_iter()
+        .map(|(assignee_uuid, name, open)| AssigneeLoad { assignee_uuid, name, open, closed: 0 })
+        .collect();
+    for (assignee_uuid, name, closed) in closed {
+        match load.iter_mut().find(|l| l.assignee_uuid == assignee_uuid) {
+            Some(entry) => entry.closed = closed,
+            None => load.push(AssigneeLoad { assignee_uuid, name, open: 0, closed }),
+        }
+    }
+
+    load.sort_by(|a, b| b.open.cmp(&a.open).then(b.closed.cmp(&a.closed)).then(a.name.cmp(&b.name)));
+    Ok(load)
+}
+
+/// Time one technician logged in worklogs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TechnicianTime {
+    pub user_uuid: Uuid,
+    pub name: String,
+    pub minutes: i64,
+}
+
+/// Worklog time for work done in `[from, to)`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TimeLogged {
+    pub total_minutes: i64,
+    /// Most time first
+    pub by_technician: Vec<TechnicianTime>,
+}
+
+/// Minutes logged for work done in `[from, to)`, in total and per technician
+pub fn time_logged(conn: &mut DbConnection, from: NaiveDateTime, to: NaiveDateTime) -> QueryResult<TimeLogged> {
+    let rows: Vec<(Uuid, String, Option<i64>)> = ticket_worklogs::table
+        .inner_join(users::table)
+        .filter(ticket_worklogs::logged_at.ge(from))
+        .filter(ticket_worklogs::logged_at.lt(to))
+        .group_by((users::uuid, users::name))
+        .select((users::uuid, users::name, diesel::dsl::sum(ticket_worklogs::minutes)))
+        .load(conn)?;
+
+    let mut by_technician: Vec<TechnicianTime> = rows
+        .into_iter()
+        .map(|(user_uuid, name, minutes)| TechnicianTime { user_uuid, name, minutes: minutes.unwrap_or(0) })
+        .collect();
+    by_technician.sort_by(|a, b| b.minutes.cmp(&a.minutes).then(a.name.cmp(&b.name)));
+
+    Ok(TimeLogged {
+        total_minutes: by_technician.iter().map(|t| t.minutes).sum(),
+        by_technician,
+    })
+}
+
+/// All ticket metrics for a date range
+#[derive(Debug, Clone, Serialize)]
+pub struct TicketReport {
+    pub from: NaiveDateTime,
+    pub to: NaiveDateTime,
+    pub created: i64,
+    pub closed: i64,
+    pub resolution_time: ResolutionTime,
+    /// Current counts, independent of the range
+    pub open_by_priority: OpenByPriority,
+    pub assignee_load: Vec<AssigneeLoad>,
+    pub time_logged: TimeLogged,
+}
+
+/// Build the full report for `[from, to)`
+pub fn ticket_report(conn: &mut DbConnection, from: NaiveDateTime, to: NaiveDateTime) -> QueryResult<TicketReport> {
+    Ok(TicketReport {
+        from,
+        to,
+        created: tickets_created_between(conn, from, to)?,
+        closed: tickets_closed_between(conn, from, to)?,
+        resolution_time: resolution_time(conn, from, to)?,
+        open_by_priority: open_by_priority(conn)?,
+        assignee_load: assignee_load(conn, from, to)?,
+        time_logged: time_logged(conn, from, to)?,
+    })
+}
+
+// ============================================================================
+// Scheduled summary email
+// ============================================================================
+
+/// Summary email settings loaded from the environment
+///
+/// * `REPORT_SUMMARY_RECIPIENTS` - comma-separated addresses; unset disables the summary
+/// * `REPORT_SUMMARY_INTERVAL_HOURS` - time between summaries and the period each covers (default 168)
+#[derive(Debug, Clone)]
+pub struct SummaryConfig {
+    pub recipients: Vec<String>,
+    pub interval: std::time::Duration,
+}
+
+impl SummaryConfig {
+    pub fn from_env() -> Self {
+        let recipients = std::env::var("REPORT_SUMMARY_RECIPIENTS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty())
+            .collect();
+        let hours = std::env::var("REPORT_SUMMARY_INTERVAL_HOURS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|hours| *hours > 0)
+            .unwrap_or(DEFAULT_SUMMARY_INTERVAL_HOURS);
+
+        Self {
+            recipients,
+            interval: std::time::Duration::from_secs(hours * 3600),
+        }
+    }
+}
+
+/// Start the summary email loop, if recipients are configured and email is set up
+pub fn spawn_summary(pool: Pool, config: SummaryConfig) {
+    if config.recipients.is_empty() {
+        return;
+    }
+    let email_service = match EmailService::from_env() {
+        Ok(service) => service,
+        Err(e) => {
+            tracing::warn!(error = %e, "Report summary recipients set but email is not configured");
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.interval);
+        // The first tick fires immediately; skip it so restarts don't resend
+        interval.tick().await;
+
+        tracing::info!(
+            recipients = config.recipients.len(),
+            interval_hours = config.interval.as_secs() / 3600,
+            "Ticket report summary scheduled"
+        );
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = send_summary(&pool, &email_service, &config).await {
+                tracing::error!(error = %e, "Failed to send ticket report summary");
+            }
+        }
+    });
+}
+
+/// Email the report for the period that just ended to every recipient
+pub async fn send_summary(pool: &Pool, email_service: &EmailService, config: &SummaryConfig) -> Result<(), String> {
+    let to: DateTime<Utc> = Utc::now();
+    let from = to - Duration::from_std(config.interval).map_err(|e| e.to_string())?;
+
+    let pool = pool.clone();
+    let (report, branding) = actix_web::web::block(move || {
+        let mut conn = pool.get().map_err(|e| format!("DB error: {e}"))?;
+        let report = ticket_report(&mut conn, from.naive_utc(), to.naive_utc())
+            .map_err(|e| format!("Failed to build report: {e}"))?;
+        let base_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+        let branding = crate::utils::email_branding::get_email_branding(&mut conn, &base_url);
+        Ok::<_, String>((report, branding))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    for recipient in &config.recipients {
+        email_service.send_ticket_report_email(recipient, &report, &branding).await?;
+    }
+    tracing::info!(recipients = config.recipients.len(), "Sent ticket report summary");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+    use chrono::NaiveDate;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2001, 3, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    /// A ticket with fixed timestamps, well before any other test data
+    fn ticket(
+        conn: &mut DbConnection,
+        assignee: Option<Uuid>,
+        priority: TicketPriority,
+        created: NaiveDateTime,
+        closed: Option<NaiveDateTime>,
+    ) -> i32 {
+        let ticket = TestFixtures::create_ticket(conn, "Report ticket", None, None);
+        diesel::update(tickets::table.find(ticket.id))
+            .set((
+                tickets::assignee_uuid.eq(assignee),
+                tickets::priority.eq(priority),
+                tickets::created_at.eq(created),
+                tickets::closed_at.eq(closed),
+                tickets::status.eq(if closed.is_some() { TicketStatus::Closed } else { TicketStatus::Open }),
+            ))
+            .execute(conn)
+            .unwrap();
+        ticket.id
+    }
+
+    #[test]
+    fn aggregates_over_known_timestamps() {
+        let mut conn = setup_test_connection();
+        let alice = TestFixtures::create_user(&mut conn, "Report Alice", UserRole::Technician);
+        let bob = TestFixtures::create_user(&mut conn, "Report Bob", UserRole::Technician);
+
+        // Closed in range after 2h, 4h and 12h
+        ticket(&mut conn, Some(alice.uuid), TicketPriority::High, at(5, 8), Some(at(5, 10)));
+        ticket(&mut conn, Some(alice.uuid), TicketPriority::Low, at(6, 8), Some(at(6, 12)));
+        ticket(&mut conn, Some(bob.uuid), TicketPriority::Medium, at(7, 0), Some(at(7, 12)));
+        // Created before the range, closed in it after 48h
+        ticket(&mut conn, None, TicketPriority::Low, at(2, 0), Some(at(4, 0)));
+        // Created in range, still open
+        ticket(&mut conn, Some(bob.uuid), TicketPriority::High, at(8, 0), None);
+        // Closed after the range
+        ticket(&mut conn, Some(alice.uuid), TicketPriority::Low, at(9, 0), Some(at(20, 0)));
+        // Merged duplicate: counted as closed, left out of resolution times
+        let merged = ticket(&mut conn, None, TicketPriority::Low, at(5, 0), Some(at(5, 1)));
+        let target = ticket(&mut conn, None, TicketPriority::Low, at(1, 0), None);
+        diesel::update(tickets::table.find(merged))
+            .set(tickets::merged_into_id.eq(Some(target)))
+            .execute(&mut conn)
+            .unwrap();
+
+        let (from, to) = (at(4, 0), at(11, 0));
+        assert_eq!(tickets_created_between(&mut conn, from, to).unwrap(), 6);
+        assert_eq!(tickets_closed_between(&mut conn, from, to).unwrap(), 5);
+
+        // 48h, 2h, 4h, 12h
+        let resolution = resolution_time(&mut conn, from, to).unwrap();
+        assert_eq!(resolution.average_hours, Some(16.5));
+        assert_eq!(resolution.median_hours, Some(8.0));
+        assert_eq!(avg_resolution_time(&mut conn, from, to).unwrap(), Some(16.5));
+
+        let load = assignee_load(&mut conn, from, to).unwrap();
+        let of = |uuid: Uuid| load.iter().find(|l| l.assignee_uuid == uuid).cloned().unwrap();
+        assert_eq!((of(bob.uuid).open, of(bob.uuid).closed), (1, 1));
+        assert_eq!((of(alice.uuid).open, of(alice.uuid).closed), (0, 2));
+
+        // Worklogs count by when the work was done
+        let worklogs = [
+            (&alice, 30, at(5, 9)),
+            (&alice, 15, at(6, 9)),
+            (&bob, 90, at(7, 6)),
+            (&bob, 60, at(20, 0)),
+        ];
+        for (user, minutes, logged_at) in worklogs {
+            crate::repository::ticket_worklogs::add_worklog(
+                &mut conn,
+                crate::models::NewTicketWorklog { ticket_id: merged, user_uuid: user.uuid, minutes, note: None, logged_at },
+            )
+            .unwrap();
+        }
+        let logged = time_logged(&mut conn, from, to).unwrap();
+        assert_eq!(logged.total_minutes, 135);
+        let minutes: Vec<(Uuid, i64)> = logged.by_technician.iter().map(|t| (t.user_uuid, t.minutes)).collect();
+        assert_eq!(minutes, vec![(bob.uuid, 90), (alice.uuid, 45)]);
+    }
+
+    #[test]
+    fn empty_range_has_no_resolution_time() {
+        let mut conn = setup_test_connection();
+        let (from, to) = (at(1, 0), at(1, 1));
+        assert_eq!(tickets_created_between(&mut conn, from, to).unwrap(), 0);
+        assert_eq!(
+            resolution_time(&mut conn, from, to).unwrap(),
+            ResolutionTime { average_hours: None, median_hours: None }
+        );
+    }
+
+    #[test]
+    fn open_by_priority_counts_unclosed_tickets() {
+        let mut conn = setup_test_connection();
+        let before = open_by_priority(&mut conn).unwrap();
+
+        ticket(&mut conn, None, TicketPriority::High, at(1, 0), None);
+        ticket(&mut conn, None, TicketPriority::High, at(1, 0), None);
+        ticket(&mut conn, None, TicketPriority::Low, at(1, 0), Some(at(2, 0)));
+
+        let after = open_by_priority(&mut conn).unwrap();
+        assert_eq!(after.high, before.high + 2);
+        assert_eq!(after.low, before.low);
+        assert_eq!(after.medium, before.medium);
+    }
+}
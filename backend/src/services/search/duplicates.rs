@@ -0,0 +1,166 @@
+//! Duplicate ticket suggestions
+//!
+//! Runs a draft ticket's title and description through the search index and
+//! returns existing tickets that look similar, so users can spot duplicates
+//! before submitting.
+
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+
+use crate::db::DbConnection;
+use crate::extractors::AuthContext;
+use crate::models::{Ticket, TicketStatus};
+
+use super::error::SearchError;
+use super::extractors::strip_html;
+use super::types::{EntityType, SearchQuery, SearchResult};
+use super::SearchService;
+
+/// Maximum number of suggestions returned
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Candidates fetched from the index before filtering
+const CANDIDATE_LIMIT: usize = 25;
+
+/// Minimum BM25 score for a ticket to count as similar
+const MIN_SCORE: f32 = 1.0;
+
+/// Closed tickets older than this are not suggested
+const RECENT_DAYS: i64 = 30;
+
+/// Maximum number of keywords sent to the index
+const MAX_TERMS: usize = 32;
+
+/// Words too common to say anything about similarity
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "not", "but", "are", "was", "has", "have", "can", "cannot", "cant",
+    "does", "doesnt", "dont", "this", "that", "from", "when", "what", "into", "our", "your", "any",
+    "all", "its", "get", "got", "been", "will", "please", "help", "issue", "problem",
+];
+
+/// Find existing tickets similar to a draft title/description
+///
+/// Only open tickets, or tickets closed within the last 30 days, that the
+/// user can see are returned, best match first.
+pub fn find_similar_tickets(
+    conn: &mut DbConnection,
+    search_service: &SearchService,
+    auth: &AuthContext,
+    title: &str,
+    description: Option<&str>,
+) -> Result<Vec<SearchResult>, SearchError> {
+    let description = description.map(strip_html).unwrap_or_default();
+    let terms = keywords(&format!("{title} {description}"));
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let query = SearchQuery {
+        q: terms.join(" "),
+        limit: Some(CANDIDATE_LIMIT),
+        types: Some(EntityType::Ticket.as_str().to_string()),
+    };
+    // Not a search anyone typed, so it stays out of the query log
+    let candidates: Vec<SearchResult> = search_service
+        .search_with_limit(&query, CANDIDATE_LIMIT)?
+        .results
+        .into_iter()
+        .filter(|result| result.score >= MIN_SCORE)
+        .collect();
+
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let ids: Vec<i32> = candidates.iter().map(|r| r.entity_id as i32).collect();
+    let tickets: Vec<Ticket> = crate::schema::tickets::table
+        .filter(crate::schema::tickets::id.eq_any(&ids))
+        .load(conn)?;
+
+    let recent_cutoff = Utc::now().naive_utc() - Duration::days(RECENT_DAYS);
+
+    Ok(candidates
+        .into_iter()
+        .filter(|result| {
+            tickets.iter().any(|ticket| {
+                ticket.id as i64 == result.entity_id
+                    && auth.can_view_ticket(ticket.requester_uuid, ticket.assignee_uuid)
+                    && (ticket.status != TicketStatus::Closed || ticket.updated_at >= recent_cutoff)
+            })
+        })
+        .take(MAX_SUGGESTIONS)
+        .collect())
+}
+
+/// Lowercased, de-duplicated keywords with stopwords and short words removed
+fn keywords(text: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let word = word.to_lowercase();
+        if word.chars().count() < 3 || STOPWORDS.contains(&word.as_str()) || terms.contains(&word) {
+            continue;
+        }
+        terms.push(word);
+        if terms.len() == MAX_TERMS {
+            break;
+        }
+    }
+
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{setup_test_connection, setup_test_pool, TestFixtures};
+
+    #[test]
+    fn keywords_drop_noise() {
+        assert_eq!(
+            keywords("The printer is OFFLINE, please help! printer <b>"),
+            vec!["printer".to_string(), "offline".to_string()]
+        );
+    }
+
+    #[test]
+    fn similar_title_surfaces_existing_ticket() {
+        let index_path = std::env::temp_dir().join(format!("nosdesk-duplicates-{}", uuid::Uuid::new_v4()));
+        let search_service = SearchService::new(&index_path, &setup_test_pool()).unwrap();
+
+        let mut conn = setup_test_connection();
+        let tech = TestFixtures::create_user(&mut conn, "duplicatetech", UserRole::Technician);
+        let existing = TestFixtures::create_ticket(
+            &mut conn,
+            "Zephyrline printer offline on third floor",
+            Some(tech.uuid),
+            None,
+        );
+        let unrelated = TestFixtures::create_ticket(&mut conn, "Quarterly budget spreadsheet", Some(tech.uuid), None);
+
+        search_service.index_ticket(&existing, None).unwrap();
+        search_service.index_ticket(&unrelated, None).unwrap();
+        search_service.commit().unwrap();
+        search_service.reader.reload().unwrap();
+
+        let auth = AuthContext::test_context(tech.uuid, UserRole::Technician, vec![]);
+        let suggestions = find_similar_tickets(
+            &mut conn,
+            &search_service,
+            &auth,
+            "Zephyrline printer is offline",
+            Some("<p>Nothing prints on the third floor</p>"),
+        )
+        .unwrap();
+
+        assert_eq!(suggestions.first().map(|s| s.entity_id), Some(existing.id as i64));
+        assert!(suggestions.iter().all(|s| s.entity_id != unrelated.id as i64));
+
+        // A draft unlike any ticket gets no suggestions
+        let suggestions = find_similar_tickets(&mut conn, &search_service, &auth, "Xylophone glockenspiel", None).unwrap();
+        assert!(suggestions.is_empty());
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
+}
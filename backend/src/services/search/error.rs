@@ -0,0 +1,72 @@
+//! Search errors
+
+use std::fmt;
+
+/// Why a search or index operation failed
+#[derive(Debug)]
+pub enum SearchError {
+    /// The query can't be run as given, e.g. it has no search terms
+    ParseError(String),
+    /// A full rebuild is already running
+    IndexBusy,
+    Io(std::io::Error),
+    /// A thread panicked while holding the index writer and it couldn't be
+    /// rolled back
+    LockPoisoned,
+    Tantivy(tantivy::TantivyError),
+    /// Loading records to index or to check visibility failed
+    Database(String),
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ParseError(msg) => write!(f, "Invalid search query: {msg}"),
+            Self::IndexBusy => write!(f, "Index rebuild already in progress"),
+            Self::Io(e) => write!(f, "Search index IO error: {e}"),
+            Self::LockPoisoned => write!(f, "Search index writer lock is poisoned"),
+            Self::Tantivy(e) => write!(f, "Search index error: {e}"),
+            Self::Database(msg) => write!(f, "Database error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Tantivy(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SearchError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<tantivy::TantivyError> for SearchError {
+    fn from(e: tantivy::TantivyError) -> Self {
+        Self::Tantivy(e)
+    }
+}
+
+impl From<tantivy::directory::error::OpenDirectoryError> for SearchError {
+    fn from(e: tantivy::directory::error::OpenDirectoryError) -> Self {
+        Self::Tantivy(e.into())
+    }
+}
+
+impl From<diesel::result::Error> for SearchError {
+    fn from(e: diesel::result::Error) -> Self {
+        Self::Database(e.to_string())
+    }
+}
+
+impl From<diesel::r2d2::PoolError> for SearchError {
+    fn from(e: diesel::r2d2::PoolError) -> Self {
+        Self::Database(e.to_string())
+    }
+}
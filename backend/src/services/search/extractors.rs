@@ -3,7 +3,7 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::panic;
-use tracing::debug;
+use tracing::{debug, info};
 use yrs::{Doc, Transact, ReadTxn, WriteTxn, GetString, Options, updates::decoder::Decode, Update, XmlFragment, XmlOut};
 
 // Pre-compiled regexes for performance
@@ -19,6 +19,42 @@ static MENTION_RE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"@\[[^\]]+\]\([a-f0-9-]+\)").unwrap()
 });
 
+/// Yjs documents larger than this (encoded bytes) only have the start of
+/// their text indexed
+const DEFAULT_LARGE_DOC_BYTES: usize = 1024 * 1024;
+
+/// How much text (in KB) is indexed from a large Yjs document
+const DEFAULT_LARGE_DOC_TEXT_KB: usize = 64;
+
+/// Size guard for indexing Yjs documents
+#[derive(Debug, Clone, Copy)]
+pub struct ExtractionLimits {
+    /// Encoded size above which a document's text is truncated
+    pub large_doc_bytes: usize,
+    /// Text kept from a large document
+    pub max_text_bytes: usize,
+}
+
+impl ExtractionLimits {
+    /// Read SEARCH_LARGE_DOC_BYTES and SEARCH_LARGE_DOC_TEXT_KB
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: usize| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+
+        Self {
+            large_doc_bytes: read("SEARCH_LARGE_DOC_BYTES", DEFAULT_LARGE_DOC_BYTES),
+            max_text_bytes: read("SEARCH_LARGE_DOC_TEXT_KB", DEFAULT_LARGE_DOC_TEXT_KB) * 1024,
+        }
+    }
+}
+
+static EXTRACTION_LIMITS: Lazy<ExtractionLimits> = Lazy::new(ExtractionLimits::from_env);
+
 /// Strip HTML tags from content
 pub fn strip_html(content: &str) -> String {
     let without_html = HTML_TAG_RE.replace_all(content, " ");
@@ -82,6 +118,64 @@ fn extract_text_from_xml_node(node: &XmlOut, txn: &yrs::Transaction) -> String {
 /// Yjs documents in Nosdesk use ProseMirror-style content.
 /// This function decodes the Yjs updates and extracts text content.
 pub fn extract_text_from_yjs(yjs_data: &[u8]) -> Option<String> {
+    let blocks = extract_blocks_from_yjs(yjs_data)?;
+    if blocks.is_empty() {
+        return None;
+    }
+
+    let normalized = blocks.join(" ");
+    debug!(len = normalized.len(), "Extracted text from Yjs document");
+    Some(normalized)
+}
+
+/// Extract the text to index from a Yjs document. Documents over the size
+/// limit only contribute the start of their text, which is enough for
+/// relevance and keeps a giant document from stalling the indexer.
+pub fn extract_index_text_from_yjs(yjs_data: &[u8]) -> Option<String> {
+    extract_index_text_with_limits(yjs_data, *EXTRACTION_LIMITS)
+}
+
+/// [`extract_index_text_from_yjs`] with explicit limits
+pub fn extract_index_text_with_limits(yjs_data: &[u8], limits: ExtractionLimits) -> Option<String> {
+    if yjs_data.len() <= limits.large_doc_bytes {
+        return extract_text_from_yjs(yjs_data);
+    }
+
+    let (blocks, mut truncated) = collect_blocks(yjs_data, Some(limits.max_text_bytes))?;
+    if blocks.is_empty() {
+        return None;
+    }
+
+    let mut text = blocks.join(" ");
+    if text.len() > limits.max_text_bytes {
+        let mut end = limits.max_text_bytes;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        truncated = true;
+    }
+
+    if truncated {
+        info!(
+            doc_bytes = yjs_data.len(),
+            indexed_bytes = text.len(),
+            "Indexing only the start of a large Yjs document"
+        );
+    }
+    Some(text)
+}
+
+/// Extract the plain text of each top-level block (paragraph, heading, list,
+/// ...) of a Yjs document, skipping empty blocks. Returns `None` if the data
+/// can't be decoded.
+pub fn extract_blocks_from_yjs(yjs_data: &[u8]) -> Option<Vec<String>> {
+    collect_blocks(yjs_data, None).map(|(blocks, _)| blocks)
+}
+
+/// Collect block texts, stopping once they add up to `max_text_bytes` (if
+/// set). Also returns whether blocks were left out.
+fn collect_blocks(yjs_data: &[u8], max_text_bytes: Option<usize>) -> Option<(Vec<String>, bool)> {
     if yjs_data.is_empty() {
         return None;
     }
@@ -115,36 +209,29 @@ pub fn extract_text_from_yjs(yjs_data: &[u8]) -> Option<String> {
 
     // Extract text content from the prosemirror fragment by traversing children
     let txn = doc.transact();
-    if let Some(fragment) = txn.get_xml_fragment("prosemirror") {
-        let mut text_parts = Vec::new();
-
-        // Iterate through top-level children (paragraphs, headings, etc.)
-        for child in fragment.children(&txn) {
-            let child_text = extract_text_from_xml_node(&child, &txn);
-            if !child_text.is_empty() {
-                text_parts.push(child_text);
-            }
-        }
+    let fragment = txn.get_xml_fragment("prosemirror")?;
 
-        if text_parts.is_empty() {
-            return None;
+    // Iterate through top-level children (paragraphs, headings, etc.)
+    let mut blocks = Vec::new();
+    let mut text_bytes = 0;
+    for child in fragment.children(&txn) {
+        if max_text_bytes.is_some_and(|max| text_bytes >= max) {
+            return Some((blocks, true));
         }
 
-        let joined = text_parts.join(" ");
+        let child_text = extract_text_from_xml_node(&child, &txn);
         // Strip any remaining XML/HTML tags (e.g., <strong>, <em>, etc.)
-        let clean_text = HTML_TAG_RE.replace_all(&joined, "").to_string();
+        let clean_text = HTML_TAG_RE.replace_all(&child_text, "");
         // Normalize whitespace
         let normalized = WHITESPACE_RE.replace_all(&clean_text, " ").trim().to_string();
-
-        if normalized.is_empty() {
-            None
-        } else {
-            debug!(len = normalized.len(), "Extracted text from Yjs document");
-            Some(normalized)
+        if !normalized.is_empty() {
+            // +1 for the space blocks are joined with
+            text_bytes += normalized.len() + 1;
+            blocks.push(normalized);
         }
-    } else {
-        None
     }
+
+    Some((blocks, false))
 }
 
 /// Create a preview snippet from content (truncated with ellipsis)
@@ -197,4 +284,44 @@ mod tests {
         let result = strip_html_and_mentions(content);
         assert_eq!(result, "Hello , how are you?");
     }
+
+    /// Encode a Yjs document with one paragraph per entry
+    fn yjs_doc(paragraphs: &[String]) -> Vec<u8> {
+        use yrs::{StateVector, XmlElementPrelim, XmlTextPrelim};
+
+        let doc = Doc::new();
+        let mut txn = doc.transact_mut();
+        let fragment = txn.get_or_insert_xml_fragment("prosemirror");
+        for text in paragraphs {
+            let paragraph = fragment.push_back(&mut txn, XmlElementPrelim::empty("paragraph"));
+            paragraph.push_back(&mut txn, XmlTextPrelim::new(text.as_str()));
+        }
+        txn.encode_state_as_update_v1(&StateVector::default())
+    }
+
+    #[test]
+    fn large_yjs_documents_are_truncated_for_indexing() {
+        let limits = ExtractionLimits { large_doc_bytes: 16 * 1024, max_text_bytes: 4 * 1024 };
+
+        let large: Vec<String> = (0..2000)
+            .map(|i| format!("Paragraph {i} about resetting the VPN gateway configuration"))
+            .collect();
+        let large_doc = yjs_doc(&large);
+        assert!(large_doc.len() > limits.large_doc_bytes);
+
+        let text = extract_index_text_with_limits(&large_doc, limits).unwrap();
+        assert!(text.len() <= limits.max_text_bytes);
+        assert!(text.starts_with("Paragraph 0 about"));
+        assert!(!text.contains("Paragraph 1999 "));
+        assert!(extract_text_from_yjs(&large_doc).unwrap().contains("Paragraph 1999 "));
+
+        // Small documents are indexed in full
+        let small = vec!["Reboot the switch".to_string(), "Check the uplink".to_string()];
+        let small_doc = yjs_doc(&small);
+        assert_eq!(
+            extract_index_text_with_limits(&small_doc, limits),
+            Some("Reboot the switch Check the uplink".to_string())
+        );
+        assert_eq!(extract_index_text_with_limits(&small_doc, limits), extract_text_from_yjs(&small_doc));
+    }
 }
@@ -1,25 +1,32 @@
 //! Indexing logic for each entity type
 
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
+use serde::Serialize;
 use tantivy::{doc, IndexWriter, Term};
 use tracing::{info, warn};
 
 use crate::db::DbConnection;
 use crate::models;
 
-use super::extractors::{create_preview, extract_text_from_yjs, strip_html_and_mentions};
+use super::error::SearchError;
+use super::extractors::{create_preview, extract_index_text_from_yjs, strip_html_and_mentions};
 use super::schema::SearchSchema;
 use super::types::{EntityType, IndexDocument};
 
-/// Create an index document from a ticket with its article content
+/// Create an index document from a ticket with its article content and
+/// searchable custom field values
 pub fn index_document_from_ticket(
     ticket: &models::Ticket,
     article_content: Option<&models::ArticleContent>,
+    custom_fields: &[String],
 ) -> IndexDocument {
     // Extract text from Yjs document if available (from associated article_content)
     let content = article_content
         .and_then(|ac| ac.yjs_document.as_ref())
-        .and_then(|data| extract_text_from_yjs(data))
+        .and_then(|data| extract_index_text_from_yjs(data))
         .unwrap_or_default();
 
     let preview = if !content.is_empty() {
@@ -31,7 +38,11 @@ pub fn index_document_from_ticket(
     // Build metadata from ticket fields (status/priority are enums)
     let status_str = format!("{:?}", ticket.status).to_lowercase();
     let priority_str = format!("{:?}", ticket.priority).to_lowercase();
-    let metadata = format!("{} {}", status_str, priority_str);
+    let mut metadata = format!("{} {}", status_str, priority_str);
+    for value in custom_fields {
+        metadata.push(' ');
+        metadata.push_str(value);
+    }
 
     IndexDocument::new(EntityType::Ticket, ticket.id as i64, &ticket.title, content)
         .metadata(metadata)
@@ -60,7 +71,7 @@ pub fn index_document_from_documentation(doc_page: &models::DocumentationPage) -
     let content = doc_page
         .yjs_document
         .as_ref()
-        .and_then(|data| extract_text_from_yjs(data))
+        .and_then(|data| extract_index_text_from_yjs(data))
         .unwrap_or_default();
 
     let preview = if !content.is_empty() {
@@ -212,12 +223,14 @@ pub fn delete_document_from_index(
     Ok(())
 }
 
-/// Rebuild the entire index from the database
+/// Rebuild the entire index from the database, reporting to `progress` as
+/// each entity type and record is processed
 pub fn rebuild_index(
     conn: &mut DbConnection,
     writer: &IndexWriter,
     schema: &SearchSchema,
-) -> Result<IndexStats, Box<dyn std::error::Error + Send + Sync>> {
+    progress: &RebuildProgress,
+) -> Result<IndexStats, SearchError> {
     use crate::schema::{tickets, documentation_pages, devices, users, comments, attachments, article_contents, user_emails};
 
     info!("Starting full index rebuild");
@@ -240,16 +253,20 @@ pub fn rebuild_index(
         .iter()
         .filter_map(|ac| ac.ticket_id.map(|tid| (tid, ac)))
         .collect();
+    let custom_field_map = crate::repository::custom_fields::all_searchable_values(conn)?;
 
     info!(count = all_tickets.len(), "Indexing tickets");
+    progress.start_entity("tickets", all_tickets.len());
     for ticket in &all_tickets {
         let article_content = article_content_map.get(&ticket.id).copied();
-        let doc = index_document_from_ticket(ticket, article_content);
+        let custom_fields = custom_field_map.get(&ticket.id).map(Vec::as_slice).unwrap_or_default();
+        let doc = index_document_from_ticket(ticket, article_content, custom_fields);
         if let Err(e) = add_document_to_index(writer, schema, &doc) {
             warn!(ticket_id = ticket.id, error = ?e, "Failed to index ticket");
         } else {
             stats.tickets += 1;
         }
+        progress.advance(&stats);
     }
 
     // Build a map of ticket IDs to titles for comments
@@ -258,9 +275,12 @@ pub fn rebuild_index(
         .map(|t| (t.id, t.title.clone()))
         .collect();
 
-    // Index all comments
-    let all_comments: Vec<models::Comment> = comments::table.load(conn)?;
+    // Index all comments (internal comments are only visible to technicians)
+    let all_comments: Vec<models::Comment> = comments::table
+        .filter(comments::is_internal.eq(false))
+        .load(conn)?;
     info!(count = all_comments.len(), "Indexing comments");
+    progress.start_entity("comments", all_comments.len());
     for comment in &all_comments {
         let ticket_title = ticket_titles.get(&comment.ticket_id).map(|s| s.as_str()).unwrap_or("Unknown Ticket");
         let doc = index_document_from_comment(comment, ticket_title);
@@ -269,6 +289,7 @@ pub fn rebuild_index(
         } else {
             stats.comments += 1;
         }
+        progress.advance(&stats);
     }
 
     // Index all attachments with transcriptions
@@ -276,10 +297,15 @@ pub fn rebuild_index(
         .filter(attachments::transcription.is_not_null())
         .load(conn)?;
     info!(count = all_attachments.len(), "Indexing attachments with transcriptions");
+    progress.start_entity("attachments", all_attachments.len());
     for attachment in &all_attachments {
         if let Some(comment_id) = attachment.comment_id {
             // Get the ticket_id from the comment
-            if let Ok(comment) = comments::table.find(comment_id).first::<models::Comment>(conn) {
+            if let Ok(comment) = comments::table
+                .find(comment_id)
+                .filter(comments::is_internal.eq(false))
+                .first::<models::Comment>(conn)
+            {
                 let ticket_title = ticket_titles.get(&comment.ticket_id).map(|s| s.as_str()).unwrap_or("Unknown Ticket");
                 let doc = index_document_from_attachment(attachment, comment.ticket_id, ticket_title);
                 if let Err(e) = add_document_to_index(writer, schema, &doc) {
@@ -289,11 +315,13 @@ pub fn rebuild_index(
                 }
             }
         }
+        progress.advance(&stats);
     }
 
     // Index all documentation pages
     let all_docs: Vec<models::DocumentationPage> = documentation_pages::table.load(conn)?;
     info!(count = all_docs.len(), "Indexing documentation pages");
+    progress.start_entity("documentation", all_docs.len());
     for doc_page in &all_docs {
         let doc = index_document_from_documentation(doc_page);
         if let Err(e) = add_document_to_index(writer, schema, &doc) {
@@ -301,11 +329,13 @@ pub fn rebuild_index(
         } else {
             stats.documentation += 1;
         }
+        progress.advance(&stats);
     }
 
     // Index all devices
     let all_devices: Vec<models::Device> = devices::table.load(conn)?;
     info!(count = all_devices.len(), "Indexing devices");
+    progress.start_entity("devices", all_devices.len());
     for device in &all_devices {
         let doc = index_document_from_device(device);
         if let Err(e) = add_document_to_index(writer, schema, &doc) {
@@ -313,6 +343,7 @@ pub fn rebuild_index(
         } else {
             stats.devices += 1;
         }
+        progress.advance(&stats);
     }
 
     // Index all users with their primary emails
@@ -329,6 +360,7 @@ pub fn rebuild_index(
         .collect();
 
     info!(count = all_users.len(), "Indexing users");
+    progress.start_entity("users", all_users.len());
     for user in &all_users {
         let primary_email = email_map.get(&user.uuid).map(|s| s.as_str());
         let doc = index_document_from_user(user, primary_email);
@@ -337,6 +369,7 @@ pub fn rebuild_index(
         } else {
             stats.users += 1;
         }
+        progress.advance(&stats);
     }
 
     // Note: Caller is responsible for committing the writer
@@ -355,7 +388,7 @@ pub fn rebuild_index(
 }
 
 /// Statistics from an index rebuild operation
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct IndexStats {
     pub tickets: usize,
     pub comments: usize,
@@ -370,3 +403,86 @@ impl IndexStats {
         self.tickets + self.comments + self.documentation + self.attachments + self.devices + self.users
     }
 }
+
+/// State of an index rebuild
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RebuildState {
+    #[default]
+    Idle,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Snapshot of index rebuild progress
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RebuildStatus {
+    pub state: RebuildState,
+    /// Entity type being indexed (e.g. "tickets")
+    pub current_entity: Option<&'static str>,
+    /// Records of the current entity type processed so far
+    pub processed: usize,
+    /// Records of the current entity type to process
+    pub total: usize,
+    /// Documents indexed so far, by entity type
+    pub indexed: IndexStats,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+/// Rebuild progress shared between the rebuild and status readers
+#[derive(Debug, Default)]
+pub struct RebuildProgress {
+    status: Mutex<RebuildStatus>,
+}
+
+impl RebuildProgress {
+    fn update(&self, f: impl FnOnce(&mut RebuildStatus)) {
+        let mut status = self.status.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut status);
+    }
+
+    /// Reset for a new rebuild
+    pub fn begin(&self) {
+        self.update(|status| {
+            *status = RebuildStatus {
+                state: RebuildState::Running,
+                started_at: Some(Utc::now()),
+                ..Default::default()
+            };
+        });
+    }
+
+    /// Start indexing `total` records of an entity type
+    pub fn start_entity(&self, entity: &'static str, total: usize) {
+        self.update(|status| {
+            status.current_entity = Some(entity);
+            status.processed = 0;
+            status.total = total;
+        });
+    }
+
+    /// Record one processed record of the current entity type
+    pub fn advance(&self, indexed: &IndexStats) {
+        self.update(|status| {
+            status.processed += 1;
+            status.indexed = indexed.clone();
+        });
+    }
+
+    /// Mark the rebuild finished, successfully or with an error
+    pub fn finish(&self, error: Option<String>) {
+        self.update(|status| {
+            status.state = if error.is_some() { RebuildState::Failed } else { RebuildState::Completed };
+            status.current_entity = None;
+            status.finished_at = Some(Utc::now());
+            status.error = error;
+        });
+    }
+
+    pub fn snapshot(&self) -> RebuildStatus {
+        self.status.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
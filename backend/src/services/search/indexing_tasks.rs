@@ -2,27 +2,75 @@
 //!
 //! These functions spawn background tasks to update the search index
 //! after CRUD operations, ensuring the main request is not blocked.
+//!
+//! Updates are written to the index without committing. The commit task
+//! started by [`spawn_commit_task`] commits them in batches, so a burst of
+//! edits costs one commit instead of one each.
 
 use std::sync::Arc;
-use tracing::{debug, error};
+use std::time::Duration;
+use tracing::{debug, error, info};
 
-use super::SearchService;
+use super::{SearchError, SearchService};
 use crate::models;
 
-/// Spawn a background indexing task that commits after completion.
+/// Default time between commits of pending index updates
+const DEFAULT_COMMIT_INTERVAL_MS: u64 = 1000;
+
+/// Time between commits, from SEARCH_COMMIT_INTERVAL_MS (default 1000)
+pub fn commit_interval_from_env() -> Duration {
+    let ms = std::env::var("SEARCH_COMMIT_INTERVAL_MS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|ms| *ms > 0)
+        .unwrap_or(DEFAULT_COMMIT_INTERVAL_MS);
+    Duration::from_millis(ms)
+}
+
+/// Start the loop that commits pending index updates every `interval`.
+/// Whatever is still pending at shutdown is committed by the shutdown
+/// coordinator.
+pub fn spawn_commit_task(search_service: Arc<SearchService>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        info!(interval_ms = interval.as_millis() as u64, "Search index commit task started");
+
+        loop {
+            ticker.tick().await;
+            let service = search_service.clone();
+            match tokio::task::spawn_blocking(move || service.commit_pending()).await {
+                Ok(Ok(true)) => debug!("Committed pending search index updates"),
+                Ok(Ok(false)) => {}
+                Ok(Err(e)) => error!(error = ?e, "Failed to commit search index"),
+                Err(e) => error!(error = ?e, "Search index commit panicked"),
+            }
+        }
+    });
+}
+
+/// Spawn a background indexing task. The change is committed by the
+/// commit task.
+///
+/// Text extraction and index writes are CPU-bound, so the work runs on the
+/// blocking pool rather than an async worker thread.
 fn spawn_indexing_task(
     search_service: Arc<SearchService>,
     label: &'static str,
-    task: impl FnOnce(&SearchService) -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    task: impl FnOnce(&SearchService) -> Result<(), SearchError> + Send + 'static,
 ) {
-    tokio::spawn(async move {
-        if let Err(e) = task(&search_service) {
-            error!(error = ?e, "Failed to {label}");
-        } else {
-            debug!("{label} completed");
-        }
-        if let Err(e) = search_service.commit() {
-            error!(error = ?e, "Failed to commit search index after {label}");
+    crate::services::shutdown::search_indexing().spawn(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            if let Err(e) = task(&search_service) {
+                error!(error = ?e, "Failed to {label}");
+            } else {
+                debug!("{label} completed");
+            }
+        })
+        .await;
+
+        if let Err(e) = result {
+            error!(error = ?e, "Search indexing task for {label} panicked");
         }
     });
 }
@@ -63,6 +111,18 @@ pub fn spawn_delete_comment(search_service: Arc<SearchService>, comment_id: i32)
     });
 }
 
+/// Index an attachment in the background
+pub fn spawn_index_attachment(
+    search_service: Arc<SearchService>,
+    attachment: models::Attachment,
+    ticket_id: i32,
+    ticket_title: String,
+) {
+    spawn_indexing_task(search_service, "index attachment", move |svc| {
+        svc.index_attachment(&attachment, ticket_id, &ticket_title)
+    });
+}
+
 /// Index a documentation page in the background
 pub fn spawn_index_documentation(
     search_service: Arc<SearchService>,
@@ -0,0 +1,72 @@
+//! Search result limits
+//!
+//! A search without a `limit` gets the default; larger limits than the
+//! maximum are capped so one request can't load an unbounded number of
+//! documents. Both are read from the environment at startup:
+//! - SEARCH_DEFAULT_LIMIT (default 20)
+//! - SEARCH_MAX_LIMIT (default 100)
+
+const DEFAULT_DEFAULT_LIMIT: usize = 20;
+const DEFAULT_MAX_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Results returned when the query has no limit
+    pub default_limit: usize,
+    /// Most results a query can ask for
+    pub max_limit: usize,
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        Self {
+            default_limit: DEFAULT_DEFAULT_LIMIT,
+            max_limit: DEFAULT_MAX_LIMIT,
+        }
+    }
+}
+
+impl SearchLimits {
+    /// Read SEARCH_DEFAULT_LIMIT and SEARCH_MAX_LIMIT. The default is
+    /// lowered to the maximum if it's above it.
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: usize| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+
+        let max_limit = read("SEARCH_MAX_LIMIT", DEFAULT_MAX_LIMIT);
+        Self {
+            default_limit: read("SEARCH_DEFAULT_LIMIT", DEFAULT_DEFAULT_LIMIT).min(max_limit),
+            max_limit,
+        }
+    }
+
+    /// The limit applied for a requested one: the default when unset,
+    /// otherwise capped to between 1 and the maximum
+    pub fn effective(&self, requested: Option<usize>) -> usize {
+        requested.unwrap_or(self.default_limit).clamp(1, self.max_limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_limit_uses_the_default() {
+        let limits = SearchLimits::default();
+        assert_eq!(limits.effective(None), 20);
+    }
+
+    #[test]
+    fn over_large_limit_is_clamped() {
+        let limits = SearchLimits { default_limit: 10, max_limit: 50 };
+        assert_eq!(limits.effective(Some(1_000_000)), 50);
+        assert_eq!(limits.effective(Some(30)), 30);
+        assert_eq!(limits.effective(Some(0)), 1);
+    }
+}
@@ -8,42 +8,75 @@
 //! - Devices (name, hostname, serial number, manufacturer, model)
 //! - Users (name, email, department, title)
 
+pub mod duplicates;
+pub mod error;
 pub mod extractors;
 pub mod indexer;
 pub mod indexing_tasks;
+pub mod limits;
+pub mod query_log;
+pub mod ranking;
+pub mod recent;
 pub mod schema;
 pub mod searcher;
 pub mod types;
+pub mod visibility;
 
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, RwLock, RwLockWriteGuard};
+use std::time::Instant;
 
 use tantivy::{Index, IndexReader, IndexWriter, ReloadPolicy};
 use tracing::{debug, info, warn};
 
 use crate::db::{DbConnection, Pool};
+use crate::extractors::AuthContext;
 use crate::models;
 
-pub use types::{EntityType, IndexDocument, SearchQuery, SearchResponse};
+pub use types::{
+    EntityType, GroupedSearchQuery, GroupedSearchResponse, IndexDocument, SearchGroup, SearchQuery, SearchResponse,
+    SearchSuggestions, SuggestionsQuery,
+};
 use schema::SearchSchema;
+pub use error::SearchError;
 
 /// Memory budget for the index writer (50MB)
 const INDEX_WRITER_MEMORY_BYTES: usize = 50_000_000;
 
+/// Most hits fetched from the index when filtering results by visibility
+const MAX_VISIBILITY_FETCH: usize = 1000;
+
+/// Matches fetched for a grouped search; facet counts cover at most this many
+const GROUPED_FETCH_LIMIT: usize = 200;
+
+/// Most results returned per group in a grouped search
+const MAX_PER_GROUP: usize = 20;
+
+/// Candidate completions considered per suggestion request
+const COMPLETION_CANDIDATES: usize = 20;
+
 /// Search service that manages the Tantivy index
 pub struct SearchService {
     _index: Index,
     schema: SearchSchema,
+    ranking: ranking::RankingConfig,
+    limits: limits::SearchLimits,
+    query_log: query_log::QueryLog,
     reader: IndexReader,
     writer: Arc<RwLock<IndexWriter>>,
     is_rebuilding: AtomicBool,
+    rebuild_progress: indexer::RebuildProgress,
+    /// Set when documents were added or deleted since the last commit
+    has_pending: AtomicBool,
+    /// For looking up data indexed with an entity, like custom field values
+    pool: Pool,
 }
 
 impl SearchService {
     /// Create a new search service with a disk-based index.
     /// Automatically rebuilds the index from the database if empty.
-    pub fn new(index_path: &Path, pool: &Pool) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+    pub fn new(index_path: &Path, pool: &Pool) -> Result<Self, SearchError> {
         // Create index directory if it doesn't exist
         std::fs::create_dir_all(index_path)?;
 
@@ -86,9 +119,15 @@ impl SearchService {
         let service = Self {
             _index: index,
             schema,
+            ranking: ranking::RankingConfig::from_env(),
+            limits: limits::SearchLimits::from_env(),
+            query_log: query_log::QueryLog::new(query_log::QueryLogConfig::from_env()),
             reader,
             writer: Arc::new(RwLock::new(writer)),
             is_rebuilding: AtomicBool::new(false),
+            rebuild_progress: indexer::RebuildProgress::default(),
+            has_pending: AtomicBool::new(false),
+            pool: pool.clone(),
         };
 
         // Auto-populate if the index is empty
@@ -110,7 +149,7 @@ impl SearchService {
     }
 
     /// Delete and recreate the index with a fresh schema
-    fn recreate_index(index_path: &Path) -> Result<(Index, SearchSchema), Box<dyn std::error::Error + Send + Sync>> {
+    fn recreate_index(index_path: &Path) -> Result<(Index, SearchSchema), SearchError> {
         // Delete the old index directory contents
         if index_path.exists() {
             for entry in std::fs::read_dir(index_path)? {
@@ -131,105 +170,361 @@ impl SearchService {
         Ok((index, schema))
     }
 
-    /// Execute a search query
+    /// Searches since startup, for the admin insights endpoint
+    pub fn query_log(&self) -> &query_log::QueryLog {
+        &self.query_log
+    }
+
+    /// Execute a search query. The query's limit is defaulted and capped
+    /// by the configured limits; the response carries the one applied.
     pub fn search(
         &self,
         query: &SearchQuery,
-    ) -> Result<SearchResponse, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<SearchResponse, SearchError> {
+        let started = Instant::now();
+        let response = self.search_with_limit(query, self.limits.effective(query.limit))?;
+        self.query_log.record(&query.q, response.results.len(), started.elapsed());
+        Ok(response)
+    }
+
+    /// Execute a search query for up to `limit` results, ignoring the
+    /// query's own limit
+    fn search_with_limit(
+        &self,
+        query: &SearchQuery,
+        limit: usize,
+    ) -> Result<SearchResponse, SearchError> {
+        if query.q.split_whitespace().next().is_none() {
+            return Err(SearchError::ParseError("query has no search terms".to_string()));
+        }
+
+        // Unknown names in the list are skipped, but a list naming no known
+        // type would otherwise search every type
         let entity_types = query.entity_types();
+        if entity_types.as_ref().is_some_and(|types| types.is_empty())
+            && query.types.as_deref().is_some_and(|types| !types.trim().is_empty())
+        {
+            return Err(SearchError::ParseError(format!(
+                "no known entity type in \"{}\"",
+                query.types.as_deref().unwrap_or_default()
+            )));
+        }
         let entity_types_ref = entity_types.as_deref();
 
         searcher::execute_search(
             &self.reader,
             &self.schema,
+            &self.ranking,
             &query.q,
-            query.limit,
+            limit,
             entity_types_ref,
         )
     }
 
-    /// Index a ticket with its optional article content
+    /// Execute a search query, returning only results the user can see.
+    /// The limit is applied as in [`SearchService::search`].
+    pub fn search_visible(
+        &self,
+        conn: &mut DbConnection,
+        auth: &AuthContext,
+        query: &SearchQuery,
+    ) -> Result<SearchResponse, SearchError> {
+        let started = Instant::now();
+        let response = self.search_visible_with_limit(conn, auth, query, self.limits.effective(query.limit))?;
+        self.query_log.record(&query.q, response.results.len(), started.elapsed());
+        Ok(response)
+    }
+
+    /// Up to `limit` visible results.
+    ///
+    /// Hidden results are dropped after searching, so the index is queried
+    /// again with a larger limit until the page is full or no hits remain.
+    fn search_visible_with_limit(
+        &self,
+        conn: &mut DbConnection,
+        auth: &AuthContext,
+        query: &SearchQuery,
+        limit: usize,
+    ) -> Result<SearchResponse, SearchError> {
+        let mut fetch_limit = limit.max(1);
+
+        loop {
+            let mut response = self.search_with_limit(query, fetch_limit)?;
+            let exhausted = response.results.len() < fetch_limit || fetch_limit >= MAX_VISIBILITY_FETCH;
+
+            let visible = visibility::VisibleEntities::resolve(conn, auth, &response.results)?;
+            visibility::retain_visible(&mut response, &visible, limit);
+
+            if response.results.len() >= limit || exhausted {
+                response.limit = limit;
+                return Ok(response);
+            }
+            fetch_limit = (fetch_limit * 2).min(MAX_VISIBILITY_FETCH);
+        }
+    }
+
+    /// Execute a search and group the visible results by entity type, with
+    /// the number of matches per type and the best few of each.
+    ///
+    /// Groups are ordered by their best match. Counts cover the first
+    /// GROUPED_FETCH_LIMIT visible matches.
+    pub fn search_grouped(
+        &self,
+        conn: &mut DbConnection,
+        auth: &AuthContext,
+        query: &GroupedSearchQuery,
+    ) -> Result<GroupedSearchResponse, SearchError> {
+        let started = Instant::now();
+        let per_group = query.per_group.clamp(1, MAX_PER_GROUP);
+        let search = SearchQuery {
+            q: query.q.clone(),
+            limit: None,
+            types: query.types.clone(),
+        };
+        let response = self.search_visible_with_limit(conn, auth, &search, GROUPED_FETCH_LIMIT)?;
+
+        let mut groups: Vec<SearchGroup> = Vec::new();
+        for result in response.results {
+            let Some(entity_type) = EntityType::from_str(&result.entity_type) else {
+                continue;
+            };
+            let group = match groups.iter().position(|g| g.entity_type == entity_type) {
+                Some(i) => &mut groups[i],
+                None => {
+                    groups.push(SearchGroup {
+                        entity_type,
+                        count: 0,
+                        results: Vec::new(),
+                    });
+                    groups.last_mut().expect("group was just pushed")
+                }
+            };
+            group.count += 1;
+            if group.results.len() < per_group {
+                group.results.push(result);
+            }
+        }
+
+        let total = groups.iter().map(|g| g.count).sum();
+        self.query_log.record(&query.q, total, started.elapsed());
+
+        Ok(GroupedSearchResponse {
+            total,
+            groups,
+            query: response.query,
+            took_ms: response.took_ms,
+        })
+    }
+
+    /// Complete the last word of a query from indexed terms.
+    ///
+    /// Returns whole queries (earlier words kept). Completions that only
+    /// match results the user can't see are skipped, so restricted content
+    /// doesn't leak through suggestions.
+    pub fn suggest_completions(
+        &self,
+        conn: &mut DbConnection,
+        auth: &AuthContext,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<String>, SearchError> {
+        let Some(last_word) = query.split_whitespace().last() else {
+            return Ok(Vec::new());
+        };
+        let head: Vec<&str> = query.split_whitespace().collect();
+        let head = head[..head.len() - 1].join(" ");
+
+        let candidates = searcher::complete_terms(&self.reader, &self.schema, last_word, COMPLETION_CANDIDATES)?;
+
+        let mut completions = Vec::new();
+        for term in candidates {
+            if completions.len() == limit {
+                break;
+            }
+            if !auth.is_admin() {
+                // Probes aren't searches anyone typed, so they skip the query log
+                let probe = SearchQuery {
+                    q: term.clone(),
+                    limit: Some(1),
+                    types: None,
+                };
+                if self.search_visible_with_limit(conn, auth, &probe, 1)?.results.is_empty() {
+                    continue;
+                }
+            }
+            completions.push(if head.is_empty() { term } else { format!("{head} {term}") });
+        }
+
+        Ok(completions)
+    }
+
+    /// Index a ticket with its optional article content and searchable
+    /// custom field values
     pub fn index_ticket(
         &self,
         ticket: &models::Ticket,
         article_content: Option<&models::ArticleContent>,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let doc = indexer::index_document_from_ticket(ticket, article_content);
+    ) -> Result<(), SearchError> {
+        let custom_fields = self
+            .pool
+            .get()
+            .map_err(|e| e.to_string())
+            .and_then(|mut conn| {
+                crate::repository::custom_fields::searchable_values(&mut conn, ticket.id).map_err(|e| e.to_string())
+            })
+            .unwrap_or_else(|e| {
+                warn!(ticket_id = ticket.id, error = %e, "Failed to load custom fields, indexing ticket without them");
+                Vec::new()
+            });
+        let doc = indexer::index_document_from_ticket(ticket, article_content, &custom_fields);
         self.index_document(&doc)
     }
 
-    /// Index a comment
-    pub fn index_comment(&self, comment: &models::Comment, ticket_title: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// Index a comment. Internal comments are skipped since search results
+    /// aren't filtered by role.
+    pub fn index_comment(&self, comment: &models::Comment, ticket_title: &str) -> Result<(), SearchError> {
+        if comment.is_internal {
+            return Ok(());
+        }
         let doc = indexer::index_document_from_comment(comment, ticket_title);
         self.index_document(&doc)
     }
 
+    /// Index an attachment on a ticket
+    pub fn index_attachment(
+        &self,
+        attachment: &models::Attachment,
+        ticket_id: i32,
+        ticket_title: &str,
+    ) -> Result<(), SearchError> {
+        let doc = indexer::index_document_from_attachment(attachment, ticket_id, ticket_title);
+        self.index_document(&doc)
+    }
+
     /// Index a documentation page
-    pub fn index_documentation(&self, doc_page: &models::DocumentationPage) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    pub fn index_documentation(&self, doc_page: &models::DocumentationPage) -> Result<(), SearchError> {
         let doc = indexer::index_document_from_documentation(doc_page);
         self.index_document(&doc)
     }
 
     /// Index a device
-    pub fn index_device(&self, device: &models::Device) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    pub fn index_device(&self, device: &models::Device) -> Result<(), SearchError> {
         let doc = indexer::index_document_from_device(device);
         self.index_document(&doc)
     }
 
     /// Index a user with optional primary email
-    pub fn index_user(&self, user: &models::User, primary_email: Option<&str>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    pub fn index_user(&self, user: &models::User, primary_email: Option<&str>) -> Result<(), SearchError> {
         let doc = indexer::index_document_from_user(user, primary_email);
         self.index_document(&doc)
     }
 
     /// Delete a ticket from the index
-    pub fn delete_ticket(&self, ticket_id: i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    pub fn delete_ticket(&self, ticket_id: i32) -> Result<(), SearchError> {
         self.delete_by_key(EntityType::Ticket, &ticket_id.to_string())
     }
 
     /// Delete a comment from the index
-    pub fn delete_comment(&self, comment_id: i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    pub fn delete_comment(&self, comment_id: i32) -> Result<(), SearchError> {
         self.delete_by_key(EntityType::Comment, &comment_id.to_string())
     }
 
     /// Delete a documentation page from the index
-    pub fn delete_documentation(&self, doc_id: i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    pub fn delete_documentation(&self, doc_id: i32) -> Result<(), SearchError> {
         self.delete_by_key(EntityType::Documentation, &doc_id.to_string())
     }
 
     /// Delete a device from the index
-    pub fn delete_device(&self, device_id: i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    pub fn delete_device(&self, device_id: i32) -> Result<(), SearchError> {
         self.delete_by_key(EntityType::Device, &device_id.to_string())
     }
 
     /// Delete a user from the index
-    pub fn delete_user(&self, user_uuid: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    pub fn delete_user(&self, user_uuid: &str) -> Result<(), SearchError> {
         self.delete_by_key(EntityType::User, user_uuid)
     }
 
     /// Rebuild the entire index from the database
-    pub fn rebuild_index(&self, conn: &mut DbConnection) -> Result<indexer::IndexStats, Box<dyn std::error::Error + Send + Sync>> {
-        if self.is_rebuilding.swap(true, Ordering::SeqCst) {
-            return Err("Index rebuild already in progress".into());
+    pub fn rebuild_index(&self, conn: &mut DbConnection) -> Result<indexer::IndexStats, SearchError> {
+        if !self.claim_rebuild() {
+            return Err(SearchError::IndexBusy);
         }
 
-        let result = {
-            let mut writer = self.writer.write().map_err(|e| format!("Lock error: {}", e))?;
+        let result = self.rebuild_claimed(conn);
+        self.release_rebuild(result.as_ref().err().map(|e| e.to_string()));
+        result
+    }
+
+    /// Start rebuilding the index on a background task.
+    /// Returns false if a rebuild is already in progress.
+    pub fn start_rebuild(self: &Arc<Self>, pool: Pool) -> bool {
+        if !self.claim_rebuild() {
+            return false;
+        }
 
-            // Delete all existing documents
-            writer.delete_all_documents()?;
+        let service = self.clone();
+        crate::services::shutdown::search_indexing().spawn(async move {
+            let worker = service.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let mut conn = pool.get()?;
+                worker.rebuild_claimed(&mut conn)
+            })
+            .await;
+
+            let error = match result {
+                Ok(Ok(stats)) => {
+                    info!(total = stats.total(), "Background index rebuild complete");
+                    None
+                }
+                Ok(Err(e)) => {
+                    warn!(error = ?e, "Background index rebuild failed");
+                    Some(e.to_string())
+                }
+                Err(e) => {
+                    warn!(error = ?e, "Background index rebuild panicked");
+                    Some("Index rebuild panicked".to_string())
+                }
+            };
+            service.release_rebuild(error);
+        });
 
-            // Rebuild from database
-            let stats = indexer::rebuild_index(conn, &writer, &self.schema)?;
+        true
+    }
 
-            // Commit changes
-            writer.commit()?;
+    /// Progress of the current or most recent rebuild
+    pub fn rebuild_status(&self) -> indexer::RebuildStatus {
+        self.rebuild_progress.snapshot()
+    }
 
-            Ok(stats)
-        };
+    /// Mark a rebuild as started, unless one is already running
+    fn claim_rebuild(&self) -> bool {
+        if self.is_rebuilding.swap(true, Ordering::SeqCst) {
+            return false;
+        }
+        self.rebuild_progress.begin();
+        true
+    }
 
+    /// Mark the claimed rebuild as finished
+    fn release_rebuild(&self, error: Option<String>) {
+        self.rebuild_progress.finish(error);
         self.is_rebuilding.store(false, Ordering::SeqCst);
-        result
+    }
+
+    /// Rebuild the index; the caller must hold the rebuild claim
+    fn rebuild_claimed(&self, conn: &mut DbConnection) -> Result<indexer::IndexStats, SearchError> {
+        let mut writer = self.lock_writer()?;
+
+        // Delete all existing documents
+        writer.delete_all_documents()?;
+
+        // Rebuild from database
+        let stats = indexer::rebuild_index(conn, &writer, &self.schema, &self.rebuild_progress)?;
+
+        // Commit changes
+        writer.commit()?;
+
+        Ok(stats)
     }
 
     /// Check if the index is currently being rebuilt
@@ -238,14 +533,38 @@ impl SearchService {
     }
 
     /// Commit pending changes to the index
-    pub fn commit(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let mut writer = self.writer.write().map_err(|e| format!("Lock error: {}", e))?;
-        writer.commit()?;
+    pub fn commit(&self) -> Result<(), SearchError> {
+        let mut writer = self.lock_writer()?;
+        self.has_pending.store(false, Ordering::SeqCst);
+        if let Err(e) = writer.commit() {
+            self.has_pending.store(true, Ordering::SeqCst);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Commit if anything was indexed or deleted since the last commit.
+    /// Returns whether a commit was made.
+    pub fn commit_pending(&self) -> Result<bool, SearchError> {
+        if !self.has_pending.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+        self.commit()?;
+        Ok(true)
+    }
+
+    /// Check that the index is usable: the writer lock isn't poisoned and
+    /// the reader can open a searcher
+    pub fn check_health(&self) -> Result<(), String> {
+        if self.writer.is_poisoned() {
+            return Err("Index writer lock is poisoned".to_string());
+        }
+        let _ = self.reader.searcher().num_docs();
         Ok(())
     }
 
     /// Get index statistics
-    pub fn get_stats(&self) -> Result<types::IndexStats, Box<dyn std::error::Error + Send + Sync>> {
+    pub fn get_stats(&self) -> Result<types::IndexStats, SearchError> {
         let searcher = self.reader.searcher();
         let total_documents = searcher.num_docs();
 
@@ -263,21 +582,263 @@ impl SearchService {
 
     // Internal helper methods
 
-    fn index_document(&self, doc: &IndexDocument) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let writer = self.writer.write().map_err(|e| format!("Lock error: {}", e))?;
+    /// Lock the index writer, recovering it if a thread panicked while
+    /// holding the lock.
+    ///
+    /// The panicking operation may have left uncommitted changes behind
+    /// (e.g. a rebuild that deleted every document), so they're rolled
+    /// back; edits indexed since the last commit are lost with them.
+    fn lock_writer(&self) -> Result<RwLockWriteGuard<'_, IndexWriter>, SearchError> {
+        match self.writer.write() {
+            Ok(writer) => Ok(writer),
+            Err(poisoned) => {
+                warn!("Search index writer lock was poisoned, rolling back uncommitted changes");
+                let mut writer = poisoned.into_inner();
+                if let Err(e) = writer.rollback() {
+                    warn!(error = ?e, "Failed to roll back search index writer");
+                    return Err(SearchError::LockPoisoned);
+                }
+                self.writer.clear_poison();
+                self.has_pending.store(false, Ordering::SeqCst);
+                Ok(writer)
+            }
+        }
+    }
+
+    fn index_document(&self, doc: &IndexDocument) -> Result<(), SearchError> {
+        let writer = self.lock_writer()?;
         indexer::add_document_to_index(&writer, &self.schema, doc)?;
-        // Note: We don't commit here for performance. The caller should commit periodically
-        // or use a background commit task.
+        // Not committed here; the background commit task batches commits
+        self.has_pending.store(true, Ordering::SeqCst);
         Ok(())
     }
 
-    fn delete_by_key(&self, entity_type: EntityType, key: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let writer = self.writer.write().map_err(|e| format!("Lock error: {}", e))?;
+    fn delete_by_key(&self, entity_type: EntityType, key: &str) -> Result<(), SearchError> {
+        let writer = self.lock_writer()?;
         indexer::delete_document_from_index(&writer, &self.schema, entity_type, key)?;
+        self.has_pending.store(true, Ordering::SeqCst);
         Ok(())
     }
 }
 
-// Make SearchService thread-safe
-unsafe impl Send for SearchService {}
-unsafe impl Sync for SearchService {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{setup_test_connection, setup_test_pool, TestFixtures};
+    use indexer::RebuildState;
+
+    #[test]
+    fn search_service_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SearchService>();
+    }
+
+    #[tokio::test]
+    async fn background_rebuild_reports_progress_until_complete() {
+        let index_path = std::env::temp_dir().join(format!("nosdesk-reindex-{}", uuid::Uuid::new_v4()));
+        let pool = setup_test_pool();
+        let search_service = Arc::new(SearchService::new(&index_path, &pool).unwrap());
+
+        assert!(search_service.start_rebuild(pool.clone()));
+        let status = search_service.rebuild_status();
+        assert_eq!(status.state, RebuildState::Running);
+        assert!(status.started_at.is_some());
+        assert!(!search_service.start_rebuild(pool.clone()), "second rebuild should be rejected");
+
+        let mut status = search_service.rebuild_status();
+        for _ in 0..600 {
+            if status.state != RebuildState::Running {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            status = search_service.rebuild_status();
+        }
+
+        assert_eq!(status.state, RebuildState::Completed, "rebuild error: {:?}", status.error);
+        assert!(status.current_entity.is_none());
+        assert!(status.finished_at.is_some());
+        assert!(!search_service.is_rebuilding());
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
+
+    #[test]
+    fn index_updates_wait_for_a_commit() {
+        let index_path = std::env::temp_dir().join(format!("nosdesk-pending-{}", uuid::Uuid::new_v4()));
+        let search_service = SearchService::new(&index_path, &setup_test_pool()).unwrap();
+        search_service.commit().unwrap();
+        assert!(!search_service.commit_pending().unwrap(), "nothing to commit");
+
+        let mut conn = setup_test_connection();
+        let ticket = TestFixtures::create_ticket(&mut conn, "Zindleworth badge reader", None, None);
+        search_service.index_ticket(&ticket, None).unwrap();
+
+        let query = SearchQuery { q: "zindleworth".to_string(), limit: Some(10), types: None };
+        assert!(search_service.search(&query).unwrap().results.is_empty());
+
+        assert!(search_service.commit_pending().unwrap());
+        assert!(!search_service.commit_pending().unwrap());
+        search_service.reader.reload().unwrap();
+        assert_eq!(search_service.search(&query).unwrap().results.len(), 1);
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
+
+    #[test]
+    fn response_reports_the_limit_applied() {
+        let index_path = std::env::temp_dir().join(format!("nosdesk-limits-{}", uuid::Uuid::new_v4()));
+        let mut search_service = SearchService::new(&index_path, &setup_test_pool()).unwrap();
+        search_service.limits = limits::SearchLimits { default_limit: 5, max_limit: 8 };
+
+        let mut conn = setup_test_connection();
+        for i in 0..12 {
+            let ticket = TestFixtures::create_ticket(&mut conn, &format!("Plumbrook monitor {i}"), None, None);
+            search_service.index_ticket(&ticket, None).unwrap();
+        }
+        search_service.commit().unwrap();
+        search_service.reader.reload().unwrap();
+
+        let mut query = SearchQuery { q: "plumbrook".to_string(), limit: Some(1_000_000), types: None };
+        let response = search_service.search(&query).unwrap();
+        assert_eq!(response.limit, 8);
+        assert_eq!(response.results.len(), 8);
+
+        query.limit = None;
+        let response = search_service.search(&query).unwrap();
+        assert_eq!(response.limit, 5);
+        assert_eq!(response.results.len(), 5);
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
+
+    #[test]
+    fn indexing_recovers_after_a_panic_poisons_the_writer() {
+        let index_path = std::env::temp_dir().join(format!("nosdesk-poison-{}", uuid::Uuid::new_v4()));
+        let search_service = Arc::new(SearchService::new(&index_path, &setup_test_pool()).unwrap());
+
+        let poisoner = search_service.clone();
+        let panicked = std::thread::spawn(move || {
+            let _writer = poisoner.writer.write().unwrap();
+            panic!("indexing panicked");
+        })
+        .join();
+        assert!(panicked.is_err());
+        assert!(search_service.writer.is_poisoned());
+
+        let doc = IndexDocument::new(EntityType::Ticket, 1, "Quorvane projector", "Lamp is flickering");
+        search_service.index_document(&doc).unwrap();
+        search_service.commit().unwrap();
+        assert!(!search_service.writer.is_poisoned());
+        assert!(search_service.check_health().is_ok());
+
+        search_service.reader.reload().unwrap();
+        let query = SearchQuery { q: "quorvane".to_string(), limit: None, types: None };
+        assert_eq!(search_service.search(&query).unwrap().results.len(), 1);
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
+
+    #[test]
+    fn malformed_query_is_a_parse_error() {
+        let index_path = std::env::temp_dir().join(format!("nosdesk-parse-{}", uuid::Uuid::new_v4()));
+        let search_service = SearchService::new(&index_path, &setup_test_pool()).unwrap();
+
+        let no_terms = SearchQuery { q: "   ".to_string(), limit: None, types: None };
+        assert!(matches!(search_service.search(&no_terms), Err(SearchError::ParseError(_))));
+
+        let unknown_types = SearchQuery { q: "printer".to_string(), limit: None, types: Some("bogus".to_string()) };
+        assert!(matches!(search_service.search(&unknown_types), Err(SearchError::ParseError(_))));
+
+        // One known type is enough
+        let some_known = SearchQuery { q: "printer".to_string(), limit: None, types: Some("bogus,ticket".to_string()) };
+        assert!(search_service.search(&some_known).is_ok());
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
+
+    #[test]
+    fn grouped_search_returns_capped_groups_with_counts() {
+        let index_path = std::env::temp_dir().join(format!("nosdesk-grouped-{}", uuid::Uuid::new_v4()));
+        let search_service = SearchService::new(&index_path, &setup_test_pool()).unwrap();
+
+        let mut conn = setup_test_connection();
+        let admin = TestFixtures::create_user(&mut conn, "groupedadmin", UserRole::Admin);
+        for title in ["Vexamorph printer offline", "Vexamorph scanner jam", "Vexamorph toner low"] {
+            let ticket = TestFixtures::create_ticket(&mut conn, title, Some(admin.uuid), None);
+            search_service.index_ticket(&ticket, None).unwrap();
+        }
+        let device = IndexDocument::new(EntityType::Device, i32::MAX as i64, "Vexamorph copier", "");
+        search_service.index_document(&device).unwrap();
+        search_service.commit().unwrap();
+        search_service.reader.reload().unwrap();
+
+        let auth = AuthContext::test_context(admin.uuid, UserRole::Admin, vec![]);
+        let query = GroupedSearchQuery {
+            q: "vexamorph".to_string(),
+            per_group: 2,
+            types: None,
+        };
+        let response = search_service.search_grouped(&mut conn, &auth, &query).unwrap();
+
+        let shape: Vec<(EntityType, usize, usize)> = response
+            .groups
+            .iter()
+            .map(|g| (g.entity_type, g.count, g.results.len()))
+            .collect();
+        assert_eq!(shape.len(), 2);
+        assert!(shape.contains(&(EntityType::Ticket, 3, 2)));
+        assert!(shape.contains(&(EntityType::Device, 1, 1)));
+        assert_eq!(response.total, 4);
+        assert!(response
+            .groups
+            .iter()
+            .all(|g| g.results.iter().all(|r| r.entity_type == g.entity_type.as_str())));
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
+
+    #[test]
+    fn grouped_search_leaves_out_non_matching_documents() {
+        let index_path = std::env::temp_dir().join(format!("nosdesk-grouped-miss-{}", uuid::Uuid::new_v4()));
+        let search_service = SearchService::new(&index_path, &setup_test_pool()).unwrap();
+
+        let mut conn = setup_test_connection();
+        let admin = TestFixtures::create_user(&mut conn, "groupedmissadmin", UserRole::Admin);
+        let matching = TestFixtures::create_ticket(&mut conn, "Quorvath badge reader broken", Some(admin.uuid), None);
+        let other = TestFixtures::create_ticket(&mut conn, "Badge reader broken", Some(admin.uuid), None);
+        search_service.index_ticket(&matching, None).unwrap();
+        search_service.index_ticket(&other, None).unwrap();
+
+        // One document of every other type, none mentioning the search term
+        let others = [
+            EntityType::Comment,
+            EntityType::Documentation,
+            EntityType::Attachment,
+            EntityType::Device,
+            EntityType::User,
+        ];
+        for (i, entity_type) in others.into_iter().enumerate() {
+            let doc = IndexDocument::new(entity_type, i32::MAX as i64 - i as i64, "Badge reader", "broken badge reader");
+            search_service.index_document(&doc).unwrap();
+        }
+        search_service.commit().unwrap();
+        search_service.reader.reload().unwrap();
+
+        let auth = AuthContext::test_context(admin.uuid, UserRole::Admin, vec![]);
+        let query = GroupedSearchQuery {
+            q: "quorvath".to_string(),
+            per_group: 5,
+            types: None,
+        };
+        let response = search_service.search_grouped(&mut conn, &auth, &query).unwrap();
+
+        assert_eq!(response.groups.len(), 1);
+        assert_eq!(response.groups[0].entity_type, EntityType::Ticket);
+        let ids: Vec<i64> = response.groups[0].results.iter().map(|r| r.entity_id).collect();
+        assert_eq!(ids, vec![matching.id as i64]);
+        assert_eq!(response.total, 1);
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
+}
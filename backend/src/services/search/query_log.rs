@@ -0,0 +1,258 @@
+//! Search query logging
+//!
+//! Every search is logged at debug level with its result count and latency;
+//! searches slower than the threshold are logged as warnings. Queries that
+//! find nothing are tallied so admins can see what people look for and
+//! don't find. The tally is kept in memory since startup, for at most
+//! `MAX_TRACKED_QUERIES` distinct queries. Read from the environment:
+//! - SEARCH_SLOW_QUERY_MS (default 500)
+//! - SEARCH_QUERY_LOGGING: `plain` (default), `hashed` to log and tally a
+//!   hash of the query instead of its text, or `off`
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{debug, warn};
+
+const DEFAULT_SLOW_QUERY_MS: u64 = 500;
+
+/// Distinct zero-result queries remembered; the least searched make room
+const MAX_TRACKED_QUERIES: usize = 1000;
+
+/// Hex characters of the SHA-256 kept for a hashed query
+const HASH_PREFIX_LEN: usize = 16;
+
+/// How query text appears in logs and tallies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryText {
+    Plain,
+    Hashed,
+    Off,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryLogConfig {
+    pub text: QueryText,
+    /// Searches taking longer than this are logged as slow
+    pub slow_threshold: Duration,
+}
+
+impl Default for QueryLogConfig {
+    fn default() -> Self {
+        Self {
+            text: QueryText::Plain,
+            slow_threshold: Duration::from_millis(DEFAULT_SLOW_QUERY_MS),
+        }
+    }
+}
+
+impl QueryLogConfig {
+    pub fn from_env() -> Self {
+        let slow_ms = std::env::var("SEARCH_SLOW_QUERY_MS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(DEFAULT_SLOW_QUERY_MS);
+        let text = match std::env::var("SEARCH_QUERY_LOGGING").map(|v| v.trim().to_ascii_lowercase()) {
+            Ok(mode) if mode == "hashed" => QueryText::Hashed,
+            Ok(mode) if mode == "off" => QueryText::Off,
+            _ => QueryText::Plain,
+        };
+
+        Self {
+            text,
+            slow_threshold: Duration::from_millis(slow_ms),
+        }
+    }
+}
+
+/// A query that found nothing, and how often
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ZeroResultQuery {
+    /// Normalized query, or its hash when queries are hashed
+    pub query: String,
+    pub count: u64,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Search activity since startup
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchInsights {
+    pub query_text: QueryText,
+    pub slow_threshold_ms: u64,
+    pub searches: u64,
+    pub slow_searches: u64,
+    /// Most frequent zero-result queries, most searched first
+    pub zero_result_queries: Vec<ZeroResultQuery>,
+}
+
+pub struct QueryLog {
+    config: QueryLogConfig,
+    searches: AtomicU64,
+    slow_searches: AtomicU64,
+    zero_results: Mutex<HashMap<String, ZeroResultQuery>>,
+}
+
+impl QueryLog {
+    pub fn new(config: QueryLogConfig) -> Self {
+        Self {
+            config,
+            searches: AtomicU64::new(0),
+            slow_searches: AtomicU64::new(0),
+            zero_results: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a completed search. Returns whether it was slow.
+    pub fn record(&self, query: &str, result_count: usize, took: Duration) -> bool {
+        self.searches.fetch_add(1, Ordering::Relaxed);
+        let slow = took > self.config.slow_threshold;
+        let key = self.key(query);
+        let logged_query = key.as_deref().unwrap_or("-");
+        let took_ms = took.as_millis() as u64;
+
+        if slow {
+            self.slow_searches.fetch_add(1, Ordering::Relaxed);
+            warn!(query = logged_query, results = result_count, took_ms, "Slow search");
+        } else {
+            debug!(query = logged_query, results = result_count, took_ms, "Search");
+        }
+
+        if let Some(key) = key.filter(|_| result_count == 0) {
+            self.count_zero_result(key);
+        }
+        slow
+    }
+
+    /// Search activity, with up to `limit` zero-result queries
+    pub fn insights(&self, limit: usize) -> SearchInsights {
+        let mut zero_result_queries: Vec<ZeroResultQuery> = self
+            .zero_results
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        zero_result_queries.sort_by(|a, b| b.count.cmp(&a.count).then(b.last_seen.cmp(&a.last_seen)));
+        zero_result_queries.truncate(limit);
+
+        SearchInsights {
+            query_text: self.config.text,
+            slow_threshold_ms: self.config.slow_threshold.as_millis() as u64,
+            searches: self.searches.load(Ordering::Relaxed),
+            slow_searches: self.slow_searches.load(Ordering::Relaxed),
+            zero_result_queries,
+        }
+    }
+
+    /// How `query` is logged: collapsed whitespace and lowercase, hashed if
+    /// configured. None when query logging is off or the query is blank.
+    fn key(&self, query: &str) -> Option<String> {
+        let normalized = query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        if normalized.is_empty() {
+            return None;
+        }
+        match self.config.text {
+            QueryText::Plain => Some(normalized),
+            QueryText::Hashed => {
+                let digest = ring::digest::digest(&ring::digest::SHA256, normalized.as_bytes());
+                let mut hash = hex::encode(digest.as_ref());
+                hash.truncate(HASH_PREFIX_LEN);
+                Some(hash)
+            }
+            QueryText::Off => None,
+        }
+    }
+
+    fn count_zero_result(&self, key: String) {
+        let now = Utc::now();
+        let mut zero_results = self.zero_results.lock().unwrap_or_else(|e| e.into_inner());
+        if !zero_results.contains_key(&key) && zero_results.len() >= MAX_TRACKED_QUERIES {
+            let least_searched = zero_results
+                .values()
+                .min_by(|a, b| a.count.cmp(&b.count).then(a.last_seen.cmp(&b.last_seen)))
+                .map(|entry| entry.query.clone());
+            if let Some(least_searched) = least_searched {
+                zero_results.remove(&least_searched);
+            }
+        }
+
+        let entry = zero_results.entry(key.clone()).or_insert(ZeroResultQuery {
+            query: key,
+            count: 0,
+            last_seen: now,
+        });
+        entry.count += 1;
+        entry.last_seen = now;
+    }
+}
+
+impl Default for QueryLog {
+    fn default() -> Self {
+        Self::new(QueryLogConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(text: QueryText) -> QueryLog {
+        QueryLog::new(QueryLogConfig { text, slow_threshold: Duration::from_millis(200) })
+    }
+
+    #[test]
+    fn slow_searches_are_flagged() {
+        let log = log(QueryText::Plain);
+        assert!(!log.record("vpn", 3, Duration::from_millis(200)));
+        assert!(log.record("vpn", 3, Duration::from_millis(201)));
+
+        let insights = log.insights(10);
+        assert_eq!(insights.searches, 2);
+        assert_eq!(insights.slow_searches, 1);
+        assert_eq!(insights.slow_threshold_ms, 200);
+    }
+
+    #[test]
+    fn zero_result_queries_are_counted() {
+        let log = log(QueryText::Plain);
+        log.record("Printer  offline", 0, Duration::ZERO);
+        log.record("printer offline", 0, Duration::ZERO);
+        log.record("badge reader", 0, Duration::ZERO);
+        log.record("vpn", 4, Duration::ZERO);
+
+        let queries: Vec<(String, u64)> = log
+            .insights(10)
+            .zero_result_queries
+            .into_iter()
+            .map(|q| (q.query, q.count))
+            .collect();
+        assert_eq!(
+            queries,
+            vec![("printer offline".to_string(), 2), ("badge reader".to_string(), 1)]
+        );
+        assert_eq!(log.insights(1).zero_result_queries.len(), 1);
+    }
+
+    #[test]
+    fn hashed_queries_keep_their_text_out_of_the_tally() {
+        let log = log(QueryText::Hashed);
+        log.record("jane's salary", 0, Duration::ZERO);
+        log.record("Jane's  salary", 0, Duration::ZERO);
+
+        let queries = log.insights(10).zero_result_queries;
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].count, 2);
+        assert_eq!(queries[0].query.len(), HASH_PREFIX_LEN);
+        assert!(!queries[0].query.contains("salary"));
+
+        let off = self::log(QueryText::Off);
+        off.record("jane's salary", 0, Duration::ZERO);
+        assert!(off.insights(10).zero_result_queries.is_empty());
+        assert_eq!(off.insights(10).searches, 1);
+    }
+}
@@ -0,0 +1,142 @@
+//! Search relevance tuning
+//!
+//! BM25 scores are weighted per field (a title match counts more than a
+//! content match), then multiplied by a per-entity-type boost and an optional
+//! recency boost that decays with a document's age.
+//!
+//! All weights are read from the environment at startup:
+//! - SEARCH_BOOST_TITLE, SEARCH_BOOST_CONTENT, SEARCH_BOOST_METADATA
+//! - SEARCH_BOOST_TYPES, e.g. `ticket:1.5,device:0.5`
+//! - SEARCH_RECENCY_BOOST: extra weight for brand new documents (0 disables)
+//! - SEARCH_RECENCY_HALF_LIFE_DAYS: age at which the recency boost halves
+
+use std::collections::HashMap;
+
+use tracing::warn;
+
+use super::types::EntityType;
+
+const DEFAULT_TITLE_BOOST: f32 = 3.0;
+const DEFAULT_CONTENT_BOOST: f32 = 1.0;
+const DEFAULT_METADATA_BOOST: f32 = 0.8;
+const DEFAULT_RECENCY_HALF_LIFE_DAYS: f32 = 30.0;
+
+const SECONDS_PER_DAY: f32 = 86_400.0;
+
+/// Relevance weights applied when searching
+#[derive(Debug, Clone)]
+pub struct RankingConfig {
+    pub title_boost: f32,
+    pub content_boost: f32,
+    pub metadata_boost: f32,
+    /// Score multiplier per entity type (1.0 when absent)
+    pub entity_type_boosts: HashMap<EntityType, f32>,
+    /// Extra score weight for brand new documents; 0 disables recency boosting
+    pub recency_boost: f32,
+    pub recency_half_life_days: f32,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            title_boost: DEFAULT_TITLE_BOOST,
+            content_boost: DEFAULT_CONTENT_BOOST,
+            metadata_boost: DEFAULT_METADATA_BOOST,
+            entity_type_boosts: HashMap::new(),
+            recency_boost: 0.0,
+            recency_half_life_days: DEFAULT_RECENCY_HALF_LIFE_DAYS,
+        }
+    }
+}
+
+impl RankingConfig {
+    /// Read the SEARCH_BOOST_* and SEARCH_RECENCY_* settings
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: f32| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<f32>().ok())
+                .filter(|v| v.is_finite() && *v >= 0.0)
+                .unwrap_or(default)
+        };
+
+        let defaults = Self::default();
+        Self {
+            title_boost: read("SEARCH_BOOST_TITLE", defaults.title_boost),
+            content_boost: read("SEARCH_BOOST_CONTENT", defaults.content_boost),
+            metadata_boost: read("SEARCH_BOOST_METADATA", defaults.metadata_boost),
+            entity_type_boosts: std::env::var("SEARCH_BOOST_TYPES")
+                .map(|v| parse_type_boosts(&v))
+                .unwrap_or_default(),
+            recency_boost: read("SEARCH_RECENCY_BOOST", defaults.recency_boost),
+            recency_half_life_days: Some(read("SEARCH_RECENCY_HALF_LIFE_DAYS", defaults.recency_half_life_days))
+                .filter(|days| *days > 0.0)
+                .unwrap_or(defaults.recency_half_life_days),
+        }
+    }
+
+    /// Score multiplier for an entity type name as stored in the index
+    pub fn entity_type_boost(&self, entity_type: &str) -> f32 {
+        EntityType::from_str(entity_type)
+            .and_then(|t| self.entity_type_boosts.get(&t).copied())
+            .unwrap_or(1.0)
+    }
+
+    /// Score multiplier for a document last updated at `updated_at` (Unix
+    /// seconds), given the current time `now`
+    pub fn recency_multiplier(&self, updated_at: i64, now: i64) -> f32 {
+        if self.recency_boost <= 0.0 {
+            return 1.0;
+        }
+        let age_days = (now - updated_at).max(0) as f32 / SECONDS_PER_DAY;
+        1.0 + self.recency_boost * 0.5f32.powf(age_days / self.recency_half_life_days)
+    }
+
+    /// Whether scores need adjusting after BM25
+    pub fn adjusts_scores(&self) -> bool {
+        self.recency_boost > 0.0 || self.entity_type_boosts.values().any(|b| *b != 1.0)
+    }
+}
+
+/// Parse `type:weight` pairs, skipping malformed entries
+fn parse_type_boosts(value: &str) -> HashMap<EntityType, f32> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let parsed = entry.split_once(':').and_then(|(name, weight)| {
+                let entity_type = EntityType::from_str(name.trim())?;
+                let weight = weight.trim().parse::<f32>().ok().filter(|w| w.is_finite() && *w >= 0.0)?;
+                Some((entity_type, weight))
+            });
+            if parsed.is_none() {
+                warn!(entry, "Ignoring malformed SEARCH_BOOST_TYPES entry");
+            }
+            parsed
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_boosts_parse_and_recency_decays() {
+        let boosts = parse_type_boosts("ticket:1.5, device:0.5, gadget:2, user:lots");
+        assert_eq!(boosts.len(), 2);
+        assert_eq!(boosts[&EntityType::Ticket], 1.5);
+        assert_eq!(boosts[&EntityType::Device], 0.5);
+
+        let config = RankingConfig {
+            recency_boost: 1.0,
+            recency_half_life_days: 10.0,
+            ..Default::default()
+        };
+        let now = 1_000 * SECONDS_PER_DAY as i64;
+        assert_eq!(config.recency_multiplier(now, now), 2.0);
+        assert!((config.recency_multiplier(now - 10 * SECONDS_PER_DAY as i64, now) - 1.5).abs() < 1e-4);
+        assert_eq!(RankingConfig::default().recency_multiplier(0, now), 1.0);
+    }
+}
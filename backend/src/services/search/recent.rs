@@ -0,0 +1,135 @@
+//! Recent searches
+//!
+//! Keeps each user's last few search terms in a Redis list, newest first, so
+//! they can be offered as suggestions. History is best-effort: if Redis is
+//! unavailable, searches still work and nothing is recorded.
+
+use redis::AsyncCommands;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::utils::rate_limit::get_redis_url;
+
+/// Number of recent searches kept per user
+pub const MAX_RECENT_SEARCHES: usize = 10;
+
+/// Longest search term stored in history (characters)
+const MAX_TERM_CHARS: usize = 100;
+
+/// History expires after 30 days without a search
+const RECENT_TTL_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+fn recent_key(user_uuid: &Uuid) -> String {
+    format!("search:recent:{user_uuid}")
+}
+
+/// Collapse whitespace and cap length; empty terms are not stored
+fn normalize(term: &str) -> Option<String> {
+    let term: String = term
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(MAX_TERM_CHARS)
+        .collect();
+    (!term.is_empty()).then_some(term)
+}
+
+/// Put `term` at the front of `history`, dropping earlier copies (ignoring
+/// case) and anything past `cap`
+fn push_recent(history: Vec<String>, term: String, cap: usize) -> Vec<String> {
+    let lowered = term.to_lowercase();
+    std::iter::once(term)
+        .chain(history.into_iter().filter(|t| t.to_lowercase() != lowered))
+        .take(cap)
+        .collect()
+}
+
+/// Recent searches starting with `prefix` (ignoring case), newest first
+pub fn matching(history: &[String], prefix: &str, limit: usize) -> Vec<String> {
+    let prefix = prefix.trim().to_lowercase();
+    history
+        .iter()
+        .filter(|t| t.to_lowercase().starts_with(&prefix))
+        .take(limit)
+        .cloned()
+        .collect()
+}
+
+async fn connection() -> redis::RedisResult<redis::aio::MultiplexedConnection> {
+    redis::Client::open(get_redis_url().as_str())?
+        .get_multiplexed_async_connection()
+        .await
+}
+
+/// Record an executed search in the user's history
+pub async fn record(user_uuid: Uuid, term: String) {
+    let Some(term) = normalize(&term) else {
+        return;
+    };
+
+    let result: redis::RedisResult<()> = async {
+        let mut con = connection().await?;
+        let key = recent_key(&user_uuid);
+        let history: Vec<String> = con.lrange(&key, 0, -1).await?;
+        let history = push_recent(history, term, MAX_RECENT_SEARCHES);
+
+        redis::pipe()
+            .atomic()
+            .del(&key)
+            .rpush(&key, history)
+            .expire(&key, RECENT_TTL_SECONDS)
+            .query_async(&mut con)
+            .await
+    }
+    .await;
+
+    if let Err(e) = result {
+        warn!(user = %user_uuid, error = %e, "Failed to record recent search");
+    }
+}
+
+/// The user's recent searches, newest first
+pub async fn list(user_uuid: &Uuid) -> Vec<String> {
+    let result: redis::RedisResult<Vec<String>> = async {
+        let mut con = connection().await?;
+        con.lrange(recent_key(user_uuid), 0, MAX_RECENT_SEARCHES as isize - 1).await
+    }
+    .await;
+
+    result.unwrap_or_else(|e| {
+        warn!(user = %user_uuid, error = %e, "Failed to load recent searches");
+        Vec::new()
+    })
+}
+
+/// Clear the user's recent searches
+pub async fn clear(user_uuid: &Uuid) -> redis::RedisResult<()> {
+    let mut con = connection().await?;
+    con.del(recent_key(user_uuid)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_searches_are_deduplicated_and_capped() {
+        let mut history = Vec::new();
+        for term in ["vpn", "printer offline", "VPN", "laptop"] {
+            history = push_recent(history, normalize(term).unwrap(), 3);
+        }
+        // Re-searching moves the term to the front instead of repeating it
+        assert_eq!(history, vec!["laptop", "VPN", "printer offline"]);
+
+        history = push_recent(history, "email".to_string(), 3);
+        assert_eq!(history, vec!["email", "laptop", "VPN"]);
+
+        assert_eq!(normalize("  printer   offline "), Some("printer offline".to_string()));
+        assert_eq!(normalize("   "), None);
+        assert_eq!(normalize(&"x".repeat(500)).map(|t| t.len()), Some(MAX_TERM_CHARS));
+
+        assert_eq!(matching(&history, "e", 5), vec!["email"]);
+        assert_eq!(matching(&history, "", 2), vec!["email", "laptop"]);
+    }
+}
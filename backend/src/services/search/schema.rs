@@ -1,7 +1,7 @@
 //! Tantivy index schema definition
 
 use tantivy::schema::{
-    Field, Schema, SchemaBuilder, STORED, STRING, TextFieldIndexing, TextOptions,
+    Field, Schema, SchemaBuilder, FAST, STORED, STRING, TextFieldIndexing, TextOptions,
     IndexRecordOption, NumericOptions,
 };
 use tantivy::Index;
@@ -41,16 +41,17 @@ impl SearchSchema {
 
         // STRING fields - stored but not tokenized (exact match only)
         let id = builder.add_text_field(fields::ID, STRING | STORED);
-        let entity_type = builder.add_text_field(fields::ENTITY_TYPE, STRING | STORED);
+        // Entity type is also a fast field, read when boosting scores by type
+        let entity_type = builder.add_text_field(fields::ENTITY_TYPE, STRING | STORED | FAST);
         let url = builder.add_text_field(fields::URL, STRING | STORED);
 
         // Stored-only fields (not searchable)
         let preview = builder.add_text_field(fields::PREVIEW, STORED);
 
-        // Numeric fields
+        // Numeric fields (updated_at is fast, read when boosting by recency)
         let numeric_options = NumericOptions::default().set_stored();
         let entity_id = builder.add_i64_field(fields::ENTITY_ID, numeric_options.clone());
-        let updated_at = builder.add_i64_field(fields::UPDATED_AT, numeric_options);
+        let updated_at = builder.add_i64_field(fields::UPDATED_AT, numeric_options.set_fast());
 
         // TEXT fields - tokenized for full-text search
         // Title field with higher weight (configured at query time via boost)
@@ -105,12 +106,9 @@ impl SearchSchema {
     ];
 
     /// Create a SearchSchema from an existing index by looking up field handles
-    pub fn from_index(index: &Index) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+    pub fn from_index(index: &Index) -> tantivy::Result<Self> {
         let schema = index.schema();
-
-        let get = |name: &str| -> Result<Field, Box<dyn std::error::Error + Send + Sync>> {
-            schema.get_field(name).map_err(|_| format!("Missing field: {name}").into())
-        };
+        let get = |name: &str| schema.get_field(name);
 
         Ok(Self {
             id: get(fields::ID)?,
@@ -126,10 +124,18 @@ impl SearchSchema {
         })
     }
 
+    /// Fields that must be fast fields for score boosting
+    const FAST_FIELD_NAMES: &'static [&'static str] = &[fields::ENTITY_TYPE, fields::UPDATED_AT];
+
     /// Check if an index has the expected schema fields
     pub fn is_compatible_with_index(index: &Index) -> bool {
         let schema = index.schema();
         Self::FIELD_NAMES.iter().all(|name| schema.get_field(name).is_ok())
+            && Self::FAST_FIELD_NAMES.iter().all(|name| {
+                schema
+                    .get_field(name)
+                    .is_ok_and(|field| schema.get_field_entry(field).is_fast())
+            })
     }
 }
 
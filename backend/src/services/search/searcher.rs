@@ -1,31 +1,41 @@
 //! Search query execution
 
+use std::collections::HashMap;
+
 use tantivy::collector::TopDocs;
 use tantivy::query::{BooleanQuery, BoostQuery, Occur, Query, TermQuery};
 use tantivy::schema::IndexRecordOption;
-use tantivy::{IndexReader, TantivyDocument};
+use tantivy::{DocId, IndexReader, Score, SegmentReader, TantivyDocument};
 use tracing::{debug, warn};
 
-use super::schema::SearchSchema;
+use super::error::SearchError;
+use super::ranking::RankingConfig;
+use super::schema::{fields, SearchSchema};
 use super::types::{EntityType, SearchResult, SearchResponse};
 
 /// Execute a search query against the index
 pub fn execute_search(
     reader: &IndexReader,
     schema: &SearchSchema,
+    ranking: &RankingConfig,
     query_str: &str,
     limit: usize,
     entity_types: Option<&[EntityType]>,
-) -> Result<SearchResponse, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SearchResponse, SearchError> {
     let start_time = std::time::Instant::now();
 
     let searcher = reader.searcher();
 
     // Build the query
-    let query = build_search_query(schema, query_str, entity_types);
+    let query = build_search_query(schema, ranking, query_str, entity_types);
 
     // Execute the search
-    let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+    let collector = TopDocs::with_limit(limit);
+    let top_docs = if ranking.adjusts_scores() {
+        searcher.search(&query, &collector.tweak_score(score_tweaker(ranking.clone())))?
+    } else {
+        searcher.search(&query, &collector)?
+    };
 
     let total = top_docs.len();
 
@@ -43,10 +53,11 @@ pub fn execute_search(
         })
         .collect();
 
-    let took_ms = start_time.elapsed().as_millis() as u64;
+    let elapsed = start_time.elapsed();
+    crate::services::metrics::record_search_query(elapsed);
+    let took_ms = elapsed.as_millis() as u64;
 
     debug!(
-        query = query_str,
         results = results.len(),
         total = total,
         took_ms = took_ms,
@@ -57,39 +68,115 @@ pub fn execute_search(
         results,
         total,
         query: query_str.to_string(),
+        limit,
         took_ms,
     })
 }
 
+/// Multiply BM25 scores by the entity type and recency boosts, read from
+/// the `entity_type` and `updated_at` fast fields
+fn score_tweaker(ranking: RankingConfig) -> impl Fn(&SegmentReader) -> Box<dyn FnMut(DocId, Score) -> Score> + Sync {
+    let now = chrono::Utc::now().timestamp();
+
+    move |segment: &SegmentReader| {
+        let ranking = ranking.clone();
+        let updated_at = segment.fast_fields().i64(fields::UPDATED_AT).ok();
+        let entity_types = segment.fast_fields().str(fields::ENTITY_TYPE).ok().flatten();
+
+        // Boost per entity type term ordinal in this segment
+        let type_boosts: Vec<f32> = entity_types
+            .as_ref()
+            .map(|column| {
+                (0..column.num_terms() as u64)
+                    .map(|ord| {
+                        let mut name = String::new();
+                        let _ = column.ord_to_str(ord, &mut name);
+                        ranking.entity_type_boost(&name)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Box::new(move |doc: DocId, score: Score| {
+            let mut score = score;
+            if let Some(ord) = entity_types.as_ref().and_then(|c| c.term_ords(doc).next()) {
+                score *= type_boosts.get(ord as usize).copied().unwrap_or(1.0);
+            }
+            if let Some(timestamp) = updated_at.as_ref().and_then(|c| c.first(doc)) {
+                score *= ranking.recency_multiplier(timestamp, now);
+            }
+            score
+        })
+    }
+}
+
+/// Complete a word prefix from terms indexed in titles, most common first
+pub fn complete_terms(
+    reader: &IndexReader,
+    schema: &SearchSchema,
+    prefix: &str,
+    limit: usize,
+) -> Result<Vec<String>, SearchError> {
+    let prefix = prefix.trim().to_lowercase();
+    if prefix.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let searcher = reader.searcher();
+    let mut doc_freqs: HashMap<String, u32> = HashMap::new();
+
+    for segment in searcher.segment_readers() {
+        let inverted_index = segment.inverted_index(schema.title)?;
+        let mut stream = inverted_index.terms().range().ge(prefix.as_bytes()).into_stream()?;
+        while stream.advance() {
+            if !stream.key().starts_with(prefix.as_bytes()) {
+                break;
+            }
+            if let Ok(term) = std::str::from_utf8(stream.key()) {
+                *doc_freqs.entry(term.to_string()).or_default() += stream.value().doc_freq;
+            }
+        }
+    }
+
+    let mut terms: Vec<(String, u32)> = doc_freqs.into_iter().filter(|(term, _)| *term != prefix).collect();
+    terms.sort_by(|(a, a_freq), (b, b_freq)| b_freq.cmp(a_freq).then_with(|| a.cmp(b)));
+
+    Ok(terms.into_iter().take(limit).map(|(term, _)| term).collect())
+}
+
 /// Build a Tantivy query from a search string
 /// Uses term queries with field boosts for BM25 ranking
 fn build_search_query(
     schema: &SearchSchema,
+    ranking: &RankingConfig,
     query_str: &str,
     entity_types: Option<&[EntityType]>,
 ) -> Box<dyn Query> {
-    // Apply field boosts using BooleanQuery
-    // Title gets 3x boost, content 1x, metadata 0.8x
+    // Apply configured field boosts using BooleanQuery
+    // (title 3x, content 1x, metadata 0.8x by default)
     let title_query: Box<dyn Query> = Box::new(BoostQuery::new(
         Box::new(build_field_query(schema.title, query_str)),
-        3.0,
+        ranking.title_boost,
     ));
 
     let content_query: Box<dyn Query> = Box::new(BoostQuery::new(
         Box::new(build_field_query(schema.content, query_str)),
-        1.0,
+        ranking.content_boost,
     ));
 
     let metadata_query: Box<dyn Query> = Box::new(BoostQuery::new(
         Box::new(build_field_query(schema.metadata, query_str)),
-        0.8,
+        ranking.metadata_boost,
     ));
 
-    let mut subqueries: Vec<(Occur, Box<dyn Query>)> = vec![
+    // The text must match in at least one field; grouped on its own so a
+    // type filter next to it can't make the text optional
+    let text_query = BooleanQuery::new(vec![
         (Occur::Should, title_query),
         (Occur::Should, content_query),
         (Occur::Should, metadata_query),
-    ];
+    ]);
+    let mut subqueries: Vec<(Occur, Box<dyn Query>)> = vec![(Occur::Must, Box::new(text_query))];
 
     // Add entity type filter if specified
     if let Some(types) = entity_types {
@@ -175,3 +262,106 @@ fn document_to_result(doc: &TantivyDocument, schema: &SearchSchema, score: f32)
         updated_at: updated_at_str,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::search::indexer::add_document_to_index;
+    use crate::services::search::types::IndexDocument;
+    use tantivy::Index;
+
+    /// Index documents in memory and return a reader over them
+    fn index(schema: &SearchSchema, docs: &[IndexDocument]) -> IndexReader {
+        let index = Index::create_in_ram(schema.schema.clone());
+        let mut writer = index.writer(15_000_000).unwrap();
+        for doc in docs {
+            add_document_to_index(&writer, schema, doc).unwrap();
+        }
+        writer.commit().unwrap();
+        index.reader().unwrap()
+    }
+
+    fn ranked_ids(reader: &IndexReader, schema: &SearchSchema, ranking: &RankingConfig, query: &str) -> Vec<String> {
+        execute_search(reader, schema, ranking, query, 10, None)
+            .unwrap()
+            .results
+            .into_iter()
+            .map(|r| r.id)
+            .collect()
+    }
+
+    #[test]
+    fn title_match_outranks_content_only_match() {
+        let schema = SearchSchema::new();
+        let reader = index(
+            &schema,
+            &[
+                IndexDocument::new(EntityType::Device, 1, "Reception desk PC", "printer driver printer queue"),
+                IndexDocument::new(EntityType::Ticket, 2, "Printer jammed", "Paper stuck in tray two"),
+            ],
+        );
+
+        let ranking = RankingConfig::default();
+        assert_eq!(ranked_ids(&reader, &schema, &ranking, "printer"), vec!["ticket-2", "device-1"]);
+
+        // With titles weighted no higher than content, repeated content wins
+        let flat = RankingConfig {
+            title_boost: 0.1,
+            ..Default::default()
+        };
+        assert_eq!(ranked_ids(&reader, &schema, &flat, "printer"), vec!["device-1", "ticket-2"]);
+    }
+
+    #[test]
+    fn entity_type_and_recency_boosts_reorder_equal_matches() {
+        let now = chrono::Utc::now().timestamp();
+        let schema = SearchSchema::new();
+        let reader = index(
+            &schema,
+            &[
+                IndexDocument::new(EntityType::Device, 1, "VPN gateway", "").updated_at(now - 365 * 86_400),
+                IndexDocument::new(EntityType::Ticket, 2, "VPN gateway", "").updated_at(now),
+            ],
+        );
+
+        let by_type = RankingConfig {
+            entity_type_boosts: HashMap::from([(EntityType::Device, 2.0)]),
+            ..Default::default()
+        };
+        assert_eq!(ranked_ids(&reader, &schema, &by_type, "vpn"), vec!["device-1", "ticket-2"]);
+
+        let by_recency = RankingConfig {
+            recency_boost: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(ranked_ids(&reader, &schema, &by_recency, "vpn"), vec!["ticket-2", "device-1"]);
+    }
+
+    #[test]
+    fn type_filter_still_requires_the_text_to_match() {
+        let schema = SearchSchema::new();
+        let reader = index(
+            &schema,
+            &[
+                IndexDocument::new(EntityType::Ticket, 1, "Printer jammed", "Paper stuck in tray two"),
+                IndexDocument::new(EntityType::Ticket, 2, "VPN drops hourly", "Office Wi-Fi only"),
+                IndexDocument::new(EntityType::Device, 3, "Printer on floor 2", ""),
+            ],
+        );
+
+        let ids: Vec<String> = execute_search(
+            &reader,
+            &schema,
+            &RankingConfig::default(),
+            "printer",
+            10,
+            Some(&[EntityType::Ticket]),
+        )
+        .unwrap()
+        .results
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+        assert_eq!(ids, vec!["ticket-1"]);
+    }
+}
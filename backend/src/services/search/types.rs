@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Entity types that can be indexed and searched
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum EntityType {
     Ticket,
@@ -152,18 +152,15 @@ pub struct SearchResult {
 pub struct SearchQuery {
     /// Search query string
     pub q: String,
-    /// Maximum number of results to return
-    #[serde(default = "default_limit")]
-    pub limit: usize,
+    /// Maximum number of results to return; the configured default when
+    /// unset, capped at the configured maximum
+    #[serde(default)]
+    pub limit: Option<usize>,
     /// Entity types to search (comma-separated)
     #[serde(default)]
     pub types: Option<String>,
 }
 
-fn default_limit() -> usize {
-    20
-}
-
 impl SearchQuery {
     pub fn entity_types(&self) -> Option<Vec<EntityType>> {
         self.types.as_ref().map(|types_str| {
@@ -184,10 +181,68 @@ pub struct SearchResponse {
     pub total: usize,
     /// Original query
     pub query: String,
+    /// Limit applied, after the default and maximum
+    pub limit: usize,
+    /// Search duration in milliseconds
+    pub took_ms: u64,
+}
+
+/// Grouped search query parameters
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupedSearchQuery {
+    /// Search query string
+    pub q: String,
+    /// Maximum number of results returned per entity type
+    #[serde(default = "default_per_group")]
+    pub per_group: usize,
+    /// Entity types to search (comma-separated)
+    #[serde(default)]
+    pub types: Option<String>,
+}
+
+fn default_per_group() -> usize {
+    5
+}
+
+/// Results of one entity type in a grouped search
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchGroup {
+    pub entity_type: EntityType,
+    /// Number of visible matches of this type (facet count)
+    pub count: usize,
+    /// Best matches of this type, capped at `per_group`
+    pub results: Vec<SearchResult>,
+}
+
+/// Search results grouped by entity type, best group first
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupedSearchResponse {
+    pub groups: Vec<SearchGroup>,
+    /// Total visible matches across all groups
+    pub total: usize,
+    /// Original query
+    pub query: String,
     /// Search duration in milliseconds
     pub took_ms: u64,
 }
 
+/// Search suggestion query parameters
+#[derive(Debug, Clone, Deserialize)]
+pub struct SuggestionsQuery {
+    /// Text typed so far
+    #[serde(default)]
+    pub q: String,
+}
+
+/// Search suggestions for a partially typed query
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchSuggestions {
+    /// The user's recent searches starting with the query, newest first
+    pub recent: Vec<String>,
+    /// Queries completing the last word from indexed terms
+    pub completions: Vec<String>,
+}
+
 /// Index statistics
 #[derive(Debug, Clone, Serialize)]
 pub struct IndexStats {
@@ -273,7 +328,7 @@ mod tests {
     fn search_query_entity_types_parse() {
         let query = SearchQuery {
             q: "test".to_string(),
-            limit: 20,
+            limit: None,
             types: Some("ticket,comment".to_string()),
         };
         let types = query.entity_types().unwrap();
@@ -284,7 +339,7 @@ mod tests {
     fn search_query_entity_types_none() {
         let query = SearchQuery {
             q: "test".to_string(),
-            limit: 20,
+            limit: None,
             types: None,
         };
         assert!(query.entity_types().is_none());
@@ -294,7 +349,7 @@ mod tests {
     fn search_query_entity_types_ignores_invalid() {
         let query = SearchQuery {
             q: "test".to_string(),
-            limit: 20,
+            limit: None,
             types: Some("ticket,invalid,user".to_string()),
         };
         let types = query.entity_types().unwrap();
@@ -0,0 +1,213 @@
+//! Search result visibility
+//!
+//! The index holds every entity regardless of who may see it, so results are
+//! filtered per user after searching:
+//! - tickets follow the same rules as the ticket list (`TicketQuery`)
+//! - comments and attachments follow the ticket they belong to
+//! - documentation pages follow their group visibility
+//! - regular users only see devices they are the primary user of
+
+use std::collections::HashSet;
+
+use diesel::prelude::*;
+
+use crate::db::DbConnection;
+use crate::extractors::AuthContext;
+use crate::repository::ticket_query::TicketQuery;
+
+use super::types::{EntityType, SearchResponse, SearchResult};
+
+/// Entity IDs the user may see, per entity type
+#[derive(Debug, Default)]
+pub struct VisibleEntities {
+    tickets: HashSet<i32>,
+    comments: HashSet<i32>,
+    attachments: HashSet<i32>,
+    hidden_docs: HashSet<i32>,
+    devices: Option<HashSet<i32>>,
+}
+
+impl VisibleEntities {
+    /// Resolve which of the given results the user may see
+    pub fn resolve(
+        conn: &mut DbConnection,
+        auth: &AuthContext,
+        results: &[SearchResult],
+    ) -> Result<Self, diesel::result::Error> {
+        use crate::schema::{attachments, comments, devices};
+
+        let ids_of = |entity_type: EntityType| -> Vec<i32> {
+            results
+                .iter()
+                .filter(|r| r.entity_type == entity_type.as_str())
+                .filter_map(|r| i32::try_from(r.entity_id).ok())
+                .collect()
+        };
+
+        let comment_tickets: Vec<(i32, i32)> = comments::table
+            .filter(comments::id.eq_any(ids_of(EntityType::Comment)))
+            .select((comments::id, comments::ticket_id))
+            .load(conn)?;
+        let attachment_tickets: Vec<(i32, i32)> = attachments::table
+            .inner_join(comments::table)
+            .filter(attachments::id.eq_any(ids_of(EntityType::Attachment)))
+            .select((attachments::id, comments::ticket_id))
+            .load(conn)?;
+
+        let mut ticket_ids = ids_of(EntityType::Ticket);
+        ticket_ids.extend(comment_tickets.iter().map(|(_, ticket_id)| *ticket_id));
+        ticket_ids.extend(attachment_tickets.iter().map(|(_, ticket_id)| *ticket_id));
+        let tickets = TicketQuery::new().visible_to(auth).matching_ids(conn, &ticket_ids)?;
+
+        let visible_children = |pairs: Vec<(i32, i32)>| -> HashSet<i32> {
+            pairs
+                .into_iter()
+                .filter(|(_, ticket_id)| tickets.contains(ticket_id))
+                .map(|(id, _)| id)
+                .collect()
+        };
+        let comments = visible_children(comment_tickets);
+        let attachments = visible_children(attachment_tickets);
+
+        let hidden_docs =
+            crate::repository::get_hidden_doc_ids(conn, &auth.user_uuid, auth.is_admin())?;
+
+        let devices = if auth.is_technician_or_admin() {
+            None
+        } else {
+            let owned: Vec<i32> = devices::table
+                .filter(devices::id.eq_any(ids_of(EntityType::Device)))
+                .filter(devices::primary_user_uuid.eq(auth.user_uuid))
+                .select(devices::id)
+                .load(conn)?;
+            Some(owned.into_iter().collect())
+        };
+
+        Ok(Self {
+            tickets,
+            comments,
+            attachments,
+            hidden_docs,
+            devices,
+        })
+    }
+
+    /// Whether the user may see a result
+    pub fn contains(&self, result: &SearchResult) -> bool {
+        let id = i32::try_from(result.entity_id).ok();
+        let visible_in = |set: &HashSet<i32>| id.is_some_and(|id| set.contains(&id));
+
+        match EntityType::from_str(&result.entity_type) {
+            Some(EntityType::Ticket) => visible_in(&self.tickets),
+            Some(EntityType::Comment) => visible_in(&self.comments),
+            Some(EntityType::Attachment) => visible_in(&self.attachments),
+            Some(EntityType::Documentation) => !visible_in(&self.hidden_docs),
+            Some(EntityType::Device) => self.devices.as_ref().is_none_or(visible_in),
+            Some(EntityType::User) => true,
+            None => false,
+        }
+    }
+}
+
+/// Remove results the user can't see and trim back to `limit`
+pub fn retain_visible(response: &mut SearchResponse, visible: &VisibleEntities, limit: usize) {
+    let before = response.results.len();
+    response.results.retain(|result| visible.contains(result));
+    response.total = response.total.saturating_sub(before - response.results.len());
+    response.results.truncate(limit);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::services::search::{SearchQuery, SearchService};
+    use crate::test_helpers::{setup_test_connection, setup_test_pool, TestFixtures};
+
+    fn result(entity_type: &str, entity_id: i64) -> SearchResult {
+        SearchResult {
+            id: format!("{entity_type}-{entity_id}"),
+            entity_type: entity_type.to_string(),
+            entity_id,
+            title: String::new(),
+            preview: String::new(),
+            url: String::new(),
+            score: 1.0,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn hidden_documentation_results_are_dropped() {
+        let mut response = SearchResponse {
+            results: vec![
+                result("documentation", 1),
+                result("ticket", 2),
+                result("documentation", 2),
+                result("documentation", 3),
+            ],
+            total: 10,
+            query: "vpn".to_string(),
+            limit: 4,
+            took_ms: 0,
+        };
+        let visible = VisibleEntities {
+            tickets: HashSet::from([2]),
+            hidden_docs: HashSet::from([2]),
+            ..Default::default()
+        };
+
+        retain_visible(&mut response, &visible, 2);
+
+        let kept: Vec<&str> = response.results.iter().map(|r| r.id.as_str()).collect();
+        // The ticket with the same numeric ID as the hidden page is kept
+        assert_eq!(kept, vec!["documentation-1", "ticket-2"]);
+        assert_eq!(response.total, 9);
+    }
+
+    #[test]
+    fn restricted_ticket_is_hidden_from_non_owning_user() {
+        let index_path = std::env::temp_dir().join(format!("nosdesk-visibility-{}", uuid::Uuid::new_v4()));
+        let search_service = SearchService::new(&index_path, &setup_test_pool()).unwrap();
+
+        let mut conn = setup_test_connection();
+        let owner = TestFixtures::create_user(&mut conn, "visibilityowner", UserRole::User);
+        let outsider = TestFixtures::create_user(&mut conn, "visibilityoutsider", UserRole::User);
+        let admin = TestFixtures::create_user(&mut conn, "visibilityadmin", UserRole::Admin);
+        let group = TestFixtures::create_group(&mut conn, "Payroll");
+        let category = TestFixtures::create_category(&mut conn, "Payroll");
+        TestFixtures::set_category_visibility(&mut conn, category.id, &[group.id]);
+
+        let ticket = TestFixtures::create_ticket(
+            &mut conn,
+            "Quillfeather salary adjustment",
+            Some(owner.uuid),
+            Some(category.id),
+        );
+        let comment = TestFixtures::create_comment(&mut conn, ticket.id, owner.uuid, "Quillfeather details");
+        search_service.index_ticket(&ticket, None).unwrap();
+        search_service.index_comment(&comment, &ticket.title).unwrap();
+        search_service.commit().unwrap();
+        search_service.reader.reload().unwrap();
+
+        let query = SearchQuery {
+            q: "quillfeather".to_string(),
+            limit: Some(10),
+            types: None,
+        };
+        let mut found = |user: &crate::models::User| -> Vec<String> {
+            let auth = AuthContext::test_context(user.uuid, user.role, vec![]);
+            let response = search_service.search_visible(&mut conn, &auth, &query).unwrap();
+            let mut ids: Vec<String> = response.results.into_iter().map(|r| r.id).collect();
+            ids.sort();
+            ids
+        };
+
+        let expected = vec![format!("comment-{}", comment.id), format!("ticket-{}", ticket.id)];
+        assert!(found(&outsider).is_empty());
+        assert_eq!(found(&owner), expected);
+        assert_eq!(found(&admin), expected);
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
+}
@@ -0,0 +1,248 @@
+//! Graceful shutdown
+//!
+//! Background work that must not be lost on SIGTERM (notification and email
+//! delivery, search indexing, webhook deliveries) is spawned through a
+//! `TaskTracker`. Once the HTTP server has stopped, `ShutdownCoordinator`
+//! closes the trackers so no new work is accepted, waits for what is in
+//! flight up to a deadline and commits the search index writer.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use tracing::Instrument;
+
+use crate::services::search::SearchService;
+
+/// Default time allowed for draining in-flight work
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+static NOTIFICATIONS: Lazy<TaskTracker> = Lazy::new(TaskTracker::new);
+static SEARCH_INDEXING: Lazy<TaskTracker> = Lazy::new(TaskTracker::new);
+static WEBHOOKS: Lazy<TaskTracker> = Lazy::new(TaskTracker::new);
+
+/// Tracker for notification and email delivery tasks
+pub fn notifications() -> &'static TaskTracker {
+    &NOTIFICATIONS
+}
+
+/// Tracker for background search indexing tasks
+pub fn search_indexing() -> &'static TaskTracker {
+    &SEARCH_INDEXING
+}
+
+/// Tracker for webhook and plugin event deliveries
+pub fn webhooks() -> &'static TaskTracker {
+    &WEBHOOKS
+}
+
+/// Result of waiting for a tracker to drain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrainOutcome {
+    Completed,
+    TimedOut { remaining: usize },
+}
+
+#[derive(Default)]
+struct TrackerInner {
+    in_flight: AtomicUsize,
+    closed: AtomicBool,
+    idle: Notify,
+}
+
+/// Counts in-flight background tasks so shutdown can wait for them
+#[derive(Clone, Default)]
+pub struct TaskTracker {
+    inner: Arc<TrackerInner>,
+}
+
+/// Keeps a task counted as in flight until dropped
+pub struct TaskGuard {
+    inner: Arc<TrackerInner>,
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        if self.inner.in_flight.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.inner.idle.notify_waiters();
+        }
+    }
+}
+
+impl TaskTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count work done inline (not in its own task) as in flight
+    pub fn enter(&self) -> TaskGuard {
+        self.inner.in_flight.fetch_add(1, Ordering::AcqRel);
+        TaskGuard { inner: self.inner.clone() }
+    }
+
+    /// Spawn a task that shutdown waits for. Work spawned after `close()`
+    /// still runs, since dropping it would lose it; producers that can hold
+    /// off should check `is_closed()` first. The task runs in the caller's
+    /// tracing span, so its logs keep the request id.
+    pub fn spawn<F>(&self, future: F) -> tokio::task::JoinHandle<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let guard = self.enter();
+        tokio::spawn(
+            async move {
+                let _guard = guard;
+                future.await;
+            }
+            .instrument(tracing::Span::current()),
+        )
+    }
+
+    /// Mark the tracker as no longer accepting new work
+    pub fn close(&self) {
+        self.inner.closed.store(true, Ordering::Release);
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.inner.closed.load(Ordering::Acquire)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.inner.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Wait until nothing is in flight or `deadline` passes
+    pub async fn drain_until(&self, deadline: Instant) -> DrainOutcome {
+        loop {
+            // Register for the wakeup before checking the count so a task
+            // finishing in between isn't missed
+            let idle = self.inner.idle.notified();
+            tokio::pin!(idle);
+            idle.as_mut().enable();
+
+            if self.in_flight() == 0 {
+                return DrainOutcome::Completed;
+            }
+            if tokio::time::timeout_at(deadline, idle).await.is_err() {
+                return match self.in_flight() {
+                    0 => DrainOutcome::Completed,
+                    remaining => DrainOutcome::TimedOut { remaining },
+                };
+            }
+        }
+    }
+
+    /// Wait until nothing is in flight, giving up after `timeout`
+    pub async fn drain(&self, timeout: Duration) -> DrainOutcome {
+        self.drain_until(Instant::now() + timeout).await
+    }
+}
+
+/// Drains background work once the HTTP server has stopped
+pub struct ShutdownCoordinator {
+    timeout: Duration,
+    search: Option<Arc<SearchService>>,
+}
+
+impl ShutdownCoordinator {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout, search: None }
+    }
+
+    /// Read the drain timeout from SHUTDOWN_TIMEOUT_SECS (default 30)
+    pub fn from_env() -> Self {
+        let timeout = std::env::var("SHUTDOWN_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT);
+        Self::new(timeout)
+    }
+
+    pub fn with_search(mut self, search: Arc<SearchService>) -> Self {
+        self.search = Some(search);
+        self
+    }
+
+    /// Stop accepting background work, wait for in-flight work and commit
+    /// the search index. Everything shares one deadline.
+    pub async fn shutdown(&self) {
+        let trackers = [
+            ("webhooks", webhooks()),
+            ("notifications", notifications()),
+            ("search_indexing", search_indexing()),
+        ];
+        for (_, tracker) in &trackers {
+            tracker.close();
+        }
+
+        tracing::info!(timeout_secs = self.timeout.as_secs(), "Draining background work before exit");
+        let deadline = Instant::now() + self.timeout;
+        let outcomes = futures::future::join_all(trackers.iter().map(|(_, t)| t.drain_until(deadline))).await;
+
+        for ((name, _), outcome) in trackers.iter().zip(outcomes) {
+            match outcome {
+                DrainOutcome::Completed => tracing::info!(work = %name, "Background work drained"),
+                DrainOutcome::TimedOut { remaining } => tracing::warn!(
+                    work = %name,
+                    remaining,
+                    "Timed out waiting for background work, abandoning it"
+                ),
+            }
+        }
+
+        if let Some(search) = &self.search {
+            let search = search.clone();
+            match tokio::task::spawn_blocking(move || search.commit()).await {
+                Ok(Ok(())) => tracing::info!("Search index committed"),
+                Ok(Err(e)) => tracing::error!(error = ?e, "Failed to commit search index on shutdown"),
+                Err(e) => tracing::error!(error = %e, "Search index commit task panicked"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn drain_completes_when_work_finishes() {
+        let tracker = TaskTracker::new();
+        for delay in [10, 30] {
+            tracker.spawn(async move { tokio::time::sleep(Duration::from_millis(delay)).await });
+        }
+        assert_eq!(tracker.in_flight(), 2);
+
+        tracker.close();
+        assert_eq!(tracker.drain(Duration::from_secs(5)).await, DrainOutcome::Completed);
+        assert_eq!(tracker.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn drain_times_out_when_work_is_stuck() {
+        let tracker = TaskTracker::new();
+        tracker.spawn(async { tokio::time::sleep(Duration::from_millis(10)).await });
+        let stuck = tracker.spawn(std::future::pending());
+        let inline = tracker.enter();
+
+        let started = std::time::Instant::now();
+        let outcome = tracker.drain(Duration::from_millis(100)).await;
+        assert_eq!(outcome, DrainOutcome::TimedOut { remaining: 2 });
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        stuck.abort();
+        drop(inline);
+        assert_eq!(tracker.drain(Duration::from_secs(5)).await, DrainOutcome::Completed);
+    }
+
+    #[tokio::test]
+    async fn drain_with_nothing_in_flight_returns_immediately() {
+        let tracker = TaskTracker::new();
+        assert_eq!(tracker.drain(Duration::ZERO).await, DrainOutcome::Completed);
+    }
+}
@@ -0,0 +1,248 @@
+//! New-device sign-in detection
+//!
+//! Every sign-in gets a coarse fingerprint: browser family and OS from the
+//! user agent, plus country and ASN when a [`GeoLookup`] can place the client.
+//! Fingerprints are remembered per user (`repository::known_devices`). A
+//! sign-in from a fingerprint the user hasn't used before is audited and the
+//! user is notified; their very first sign-in only records it.
+
+use actix_web::HttpRequest;
+use diesel::QueryResult;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::config_utils::GeoHeaders;
+use crate::db::DbConnection;
+use crate::models::ActiveSession;
+use crate::repository::known_devices::{self, Sighting};
+use crate::services::audit::{self, AuditAction, AuditTarget};
+use crate::services::notifications::types::{NotificationActor, NotificationEntity, NotificationPayload};
+use crate::services::notifications::{NotificationService, NotificationTypeCode};
+
+/// Where a request came from, as far as a [`GeoLookup`] can tell
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GeoLocation {
+    /// ISO country code
+    pub country: Option<String>,
+    /// Autonomous system, e.g. `AS13335`
+    pub asn: Option<String>,
+}
+
+impl GeoLocation {
+    /// Human-readable location, e.g. `US, AS13335`
+    pub fn label(&self) -> Option<String> {
+        let parts: Vec<&str> = [self.country.as_deref(), self.asn.as_deref()].into_iter().flatten().collect();
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+/// Places a request geographically
+///
+/// Registered as `web::Data<dyn GeoLookup>` app data. Without one, sign-ins
+/// are told apart by browser and OS only.
+pub trait GeoLookup: Send + Sync {
+    fn locate(&self, request: &HttpRequest) -> Option<GeoLocation>;
+}
+
+/// Reads the location from headers set by a CDN or reverse proxy
+pub struct HeaderGeoLookup {
+    headers: GeoHeaders,
+}
+
+impl HeaderGeoLookup {
+    pub fn new(headers: GeoHeaders) -> Self {
+        Self { headers }
+    }
+}
+
+impl GeoLookup for HeaderGeoLookup {
+    fn locate(&self, request: &HttpRequest) -> Option<GeoLocation> {
+        let header = |name: &Option<String>| {
+            name.as_deref()
+                .and_then(|name| request.headers().get(name))
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let location = GeoLocation {
+            country: header(&self.headers.country),
+            asn: header(&self.headers.asn),
+        };
+        location.label().map(|_| location)
+    }
+}
+
+/// Device name shown for a session, from its user agent
+pub fn device_name(user_agent: &str) -> &'static str {
+    if user_agent.contains("iPhone") {
+        "iPhone"
+    } else if user_agent.contains("iPad") {
+        "iPad"
+    } else if user_agent.contains("Android") {
+        "Android Device"
+    } else if user_agent.contains("Macintosh") || user_agent.contains("Mac OS") {
+        "Mac"
+    } else if user_agent.contains("Windows") {
+        "Windows PC"
+    } else if user_agent.contains("Linux") {
+        "Linux"
+    } else {
+        "Unknown Device"
+    }
+}
+
+/// Browser family from a user agent; the checks run in this order because
+/// most browsers also claim to be Chrome and Safari
+pub fn browser_family(user_agent: &str) -> &'static str {
+    if user_agent.contains("Edg/") || user_agent.contains("EdgA/") || user_agent.contains("EdgiOS/") {
+        "Edge"
+    } else if user_agent.contains("OPR/") || user_agent.contains("Opera") {
+        "Opera"
+    } else if user_agent.contains("Firefox/") || user_agent.contains("FxiOS/") {
+        "Firefox"
+    } else if user_agent.contains("Chrome/") || user_agent.contains("CriOS/") {
+        "Chrome"
+    } else if user_agent.contains("Safari/") {
+        "Safari"
+    } else {
+        "Unknown browser"
+    }
+}
+
+/// Browser and device, e.g. `Firefox on Linux`
+pub fn describe_user_agent(user_agent: Option<&str>) -> String {
+    match user_agent {
+        Some(ua) => format!("{} on {}", browser_family(ua), device_name(ua)),
+        None => "Unknown browser on Unknown Device".to_string(),
+    }
+}
+
+/// Coarse fingerprint of a sign-in
+///
+/// Versions are left out so browser updates don't look like new devices.
+pub fn fingerprint(user_agent: Option<&str>, location: Option<&GeoLocation>) -> String {
+    let mut fingerprint = describe_user_agent(user_agent);
+    if let Some(location) = location {
+        fingerprint.push_str(&format!(
+            " | {} | {}",
+            location.country.as_deref().unwrap_or("-"),
+            location.asn.as_deref().unwrap_or("-")
+        ));
+    }
+    fingerprint
+}
+
+/// Remember the fingerprint a session signed in from, and flag it if it's new
+///
+/// A new fingerprint on an account with other known devices is audited and
+/// the user notified through `notifications`, when given. Notification
+/// failures are logged; they don't fail the sign-in.
+pub async fn check_sign_in(
+    conn: &mut DbConnection,
+    session: &ActiveSession,
+    fingerprint: &str,
+    device: &str,
+    notifications: Option<&NotificationService>,
+) -> QueryResult<Sighting> {
+    let user_uuid = session.user_uuid;
+    let sighting = known_devices::remember(conn, &user_uuid, fingerprint, chrono::Utc::now().naive_utc())?;
+    if sighting != (Sighting::New { first_device: false }) {
+        return Ok(sighting);
+    }
+
+    let ip_address = session.ip_address.map(|ip| ip.ip().to_string());
+    let details = json!({
+        "session_id": session.id,
+        "fingerprint": fingerprint,
+        "ip_address": ip_address,
+        "location": session.location,
+    });
+    let _ = audit::record(
+        conn,
+        Some(user_uuid),
+        AuditAction::NewDeviceSignIn,
+        Some(AuditTarget::user(user_uuid)),
+        details.clone(),
+    );
+
+    if let Some(notifications) = notifications {
+        let origin = match (&session.location, &ip_address) {
+            (Some(location), Some(ip)) => format!(" in {location} ({ip})"),
+            (Some(location), None) => format!(" in {location}"),
+            (None, Some(ip)) => format!(" ({ip})"),
+            (None, None) => String::new(),
+        };
+        let payload = NotificationPayload::new(
+            NotificationTypeCode::NewDeviceSignIn,
+            user_uuid,
+            NotificationActor {
+                uuid: Uuid::nil(), // System actor
+                name: "System".to_string(),
+                avatar_thumb: None,
+            },
+            NotificationEntity::Session {
+                id: session.id,
+                device: device.to_string(),
+            },
+        )
+        .with_body(format!(
+            "Your account signed in from {device}{origin}. If this wasn't you, revoke the session and change your password."
+        ))
+        .with_metadata(details);
+
+        if let Err(e) = notifications.notify(payload).await {
+            tracing::warn!(user = %user_uuid, error = %e, "Failed to send new device sign-in notification");
+        }
+    }
+
+    Ok(sighting)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    const FIREFOX_LINUX: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+    const EDGE_WINDOWS: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36 Edg/126.0.0.0";
+    const SAFARI_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1";
+
+    #[test]
+    fn user_agents_reduce_to_browser_and_device() {
+        assert_eq!(describe_user_agent(Some(FIREFOX_LINUX)), "Firefox on Linux");
+        assert_eq!(describe_user_agent(Some(EDGE_WINDOWS)), "Edge on Windows PC");
+        assert_eq!(describe_user_agent(Some(SAFARI_IPHONE)), "Safari on iPhone");
+        assert_eq!(describe_user_agent(None), "Unknown browser on Unknown Device");
+
+        // A browser update keeps the fingerprint
+        let updated = FIREFOX_LINUX.replace("128.0", "129.0");
+        assert_eq!(fingerprint(Some(&updated), None), fingerprint(Some(FIREFOX_LINUX), None));
+    }
+
+    #[test]
+    fn fingerprint_includes_the_location_when_known() {
+        let location = GeoLocation { country: Some("NZ".to_string()), asn: None };
+        assert_eq!(fingerprint(Some(FIREFOX_LINUX), Some(&location)), "Firefox on Linux | NZ | -");
+        assert_eq!(location.label().as_deref(), Some("NZ"));
+    }
+
+    #[test]
+    fn header_lookup_reads_configured_headers() {
+        let lookup = HeaderGeoLookup::new(GeoHeaders {
+            country: Some("CF-IPCountry".to_string()),
+            asn: Some("X-Client-ASN".to_string()),
+        });
+        let request = TestRequest::default()
+            .insert_header(("CF-IPCountry", "DE"))
+            .insert_header(("X-Client-ASN", "AS3320"))
+            .to_http_request();
+        assert_eq!(
+            lookup.locate(&request),
+            Some(GeoLocation { country: Some("DE".to_string()), asn: Some("AS3320".to_string()) })
+        );
+
+        assert_eq!(lookup.locate(&TestRequest::default().to_http_request()), None);
+        let unconfigured = HeaderGeoLookup::new(GeoHeaders::default());
+        assert_eq!(unconfigured.locate(&request), None);
+    }
+}
@@ -0,0 +1,178 @@
+//! SLA Breach Monitor
+//!
+//! Background task that periodically evaluates unclosed tickets against the
+//! SLA targets for their priority. Each breach (ticket + metric) is recorded
+//! once; a new breach runs the assignment rules with the `sla_breached`
+//! trigger so a matching rule can escalate the ticket to someone else.
+
+use actix_web::web;
+use uuid::Uuid;
+
+use crate::db::{DbConnection, Pool};
+use crate::models::{AssignmentTrigger, Ticket, TicketSla, TicketUpdate};
+use crate::repository;
+use crate::services::assignment::AssignmentEngine;
+use crate::services::notifications::{
+    types::{NotificationActor, NotificationEntity, NotificationPayload, NotificationTypeCode},
+    NotificationService,
+};
+
+/// Default time between checks
+const DEFAULT_CHECK_INTERVAL_MINUTES: u64 = 5;
+
+/// Monitor settings loaded from the environment
+///
+/// * `SLA_CHECK_INTERVAL_MINUTES` - time between checks (default 5)
+#[derive(Debug, Clone, Copy)]
+pub struct SlaMonitorConfig {
+    pub check_interval: std::time::Duration,
+}
+
+impl SlaMonitorConfig {
+    pub fn from_env() -> Self {
+        let minutes = std::env::var("SLA_CHECK_INTERVAL_MINUTES")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|minutes| *minutes > 0)
+            .unwrap_or(DEFAULT_CHECK_INTERVAL_MINUTES);
+
+        Self {
+            check_interval: std::time::Duration::from_secs(minutes * 60),
+        }
+    }
+}
+
+/// A ticket reassigned because it breached its SLA
+struct Escalation {
+    ticket_id: i32,
+    ticket_title: String,
+    assignee_uuid: Uuid,
+    rule_name: String,
+}
+
+/// Start the background check loop
+pub fn spawn(pool: Pool, notification_service: web::Data<NotificationService>, config: SlaMonitorConfig) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.check_interval);
+
+        tracing::info!(
+            interval_minutes = config.check_interval.as_secs() / 60,
+            "SLA monitor started"
+        );
+
+        loop {
+            interval.tick().await;
+
+            let check_pool = pool.clone();
+            let escalations = match web::block(move || check_breaches(&check_pool)).await {
+                Ok(Ok(escalations)) => escalations,
+                Ok(Err(e)) => {
+                    tracing::error!(error = %e, "SLA breach check failed");
+                    continue;
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "SLA breach check task failed");
+                    continue;
+                }
+            };
+
+            for escalation in escalations {
+                notify_escalation(&notification_service, escalation).await;
+            }
+        }
+    });
+}
+
+/// Record new breaches and run the breach assignment rules for them.
+/// Returns the tickets that were reassigned.
+fn check_breaches(pool: &Pool) -> Result<Vec<Escalation>, String> {
+    let mut conn = pool.get().map_err(|e| format!("DB error: {e}"))?;
+    let now = chrono::Utc::now().naive_utc();
+
+    let tickets = repository::sla::open_tickets_with_targets(&mut conn)
+        .map_err(|e| format!("Failed to load tickets with SLA targets: {e}"))?;
+
+    let mut escalations = Vec::new();
+    for (ticket, target) in tickets {
+        let sla = TicketSla::evaluate(&ticket, &target, now);
+
+        let mut newly_breached = false;
+        for metric in sla.breached() {
+            match repository::sla::record_breach(&mut conn, ticket.id, metric) {
+                Ok(true) => {
+                    tracing::warn!(ticket_id = ticket.id, metric = metric.as_str(), "Ticket breached its SLA");
+                    newly_breached = true;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::warn!(ticket_id = ticket.id, error = %e, "Failed to record SLA breach");
+                }
+            }
+        }
+
+        if newly_breached {
+            if let Some(escalation) = escalate(&mut conn, &ticket) {
+                escalations.push(escalation);
+            }
+        }
+    }
+
+    Ok(escalations)
+}
+
+/// Reassign a breached ticket if a `sla_breached` rule picks someone new
+fn escalate(conn: &mut DbConnection, ticket: &Ticket) -> Option<Escalation> {
+    let result = AssignmentEngine::evaluate_rules(conn, ticket, AssignmentTrigger::SlaBreached)?;
+    let assignee_uuid = result.assigned_user_uuid?;
+    if ticket.assignee_uuid == Some(assignee_uuid) {
+        return None;
+    }
+
+    let update = TicketUpdate {
+        assignee_uuid: Some(Some(assignee_uuid)),
+        updated_at: Some(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    };
+    if let Err(e) = repository::update_ticket_partial(conn, ticket.id, update, None, None) {
+        tracing::warn!(ticket_id = ticket.id, error = ?e, "Failed to reassign ticket after SLA breach");
+        return None;
+    }
+
+    tracing::info!(
+        ticket_id = ticket.id,
+        assignee = %assignee_uuid,
+        rule = %result.rule_name,
+        "Escalated ticket after SLA breach"
+    );
+
+    Some(Escalation {
+        ticket_id: ticket.id,
+        ticket_title: ticket.title.clone(),
+        assignee_uuid,
+        rule_name: result.rule_name,
+    })
+}
+
+async fn notify_escalation(notification_service: &NotificationService, escalation: Escalation) {
+    let payload = NotificationPayload::new(
+        NotificationTypeCode::TicketAssigned,
+        escalation.assignee_uuid,
+        NotificationActor {
+            uuid: Uuid::nil(), // System actor
+            name: "System".to_string(),
+            avatar_thumb: None,
+        },
+        NotificationEntity::Ticket {
+            id: escalation.ticket_id,
+            title: escalation.ticket_title,
+        },
+    )
+    .with_body(format!(
+        "Ticket #{} breached its SLA and was escalated to you (Rule: {})",
+        escalation.ticket_id, escalation.rule_name
+    ));
+
+    if let Err(e) = notification_service.notify(payload).await {
+        tracing::warn!(ticket_id = escalation.ticket_id, error = %e, "Failed to send SLA escalation notification");
+    }
+}
@@ -0,0 +1,331 @@
+//! Ticket Auto-Close
+//!
+//! Background task that closes tickets left waiting on the requester. A
+//! ticket in the configured status with no activity (its `updated_at`, which
+//! comments also bump) for the configured number of days gets a system
+//! comment, is closed by the system user, and its requester is notified with
+//! a link that reopens it. Auto-close is off unless a window is configured.
+
+use actix_web::web;
+use chrono::{Duration, Utc};
+use diesel::Connection;
+use uuid::Uuid;
+
+use crate::db::{DbConnection, Pool};
+use crate::models::{NewComment, Ticket, TicketStatus, TicketUpdate};
+use crate::repository;
+use crate::services::notifications::{
+    types::{NotificationActor, NotificationEntity, NotificationPayload, NotificationTypeCode},
+    NotificationService,
+};
+
+/// Default time between sweeps
+const DEFAULT_SWEEP_INTERVAL_MINUTES: u64 = 60;
+
+/// Status tickets are auto-closed from when none is configured
+const DEFAULT_STATUS: TicketStatus = TicketStatus::InProgress;
+
+/// Tickets closed per query, so one sweep doesn't load everything at once
+const CLOSE_BATCH_SIZE: i64 = 100;
+
+/// Auto-close settings loaded from the environment
+///
+/// * `TICKET_AUTO_CLOSE_DAYS` - days without activity before a ticket is
+///   closed (unset or 0 disables auto-close)
+/// * `TICKET_AUTO_CLOSE_STATUS` - status tickets must be in, `open` or
+///   `in-progress` (default `in-progress`)
+/// * `TICKET_AUTO_CLOSE_INTERVAL_MINUTES` - time between sweeps (default 60)
+#[derive(Debug, Clone, Copy)]
+pub struct AutoCloseConfig {
+    pub idle_days: Option<i64>,
+    pub status: TicketStatus,
+    pub sweep_interval: std::time::Duration,
+}
+
+impl AutoCloseConfig {
+    pub fn from_env() -> Self {
+        let idle_days = std::env::var("TICKET_AUTO_CLOSE_DAYS")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|days| *days > 0);
+        let status = match std::env::var("TICKET_AUTO_CLOSE_STATUS").ok().as_deref().map(str::trim) {
+            None | Some("") => DEFAULT_STATUS,
+            Some("open") => TicketStatus::Open,
+            Some("in-progress") => TicketStatus::InProgress,
+            Some(other) => {
+                tracing::warn!(status = other, "Invalid TICKET_AUTO_CLOSE_STATUS, using in-progress");
+                DEFAULT_STATUS
+            }
+        };
+        let minutes = std::env::var("TICKET_AUTO_CLOSE_INTERVAL_MINUTES")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|minutes| *minutes > 0)
+            .unwrap_or(DEFAULT_SWEEP_INTERVAL_MINUTES);
+
+        Self {
+            idle_days,
+            status,
+            sweep_interval: std::time::Duration::from_secs(minutes * 60),
+        }
+    }
+}
+
+/// A ticket closed for inactivity
+struct AutoClosed {
+    ticket_id: i32,
+    ticket_title: String,
+    requester_uuid: Option<Uuid>,
+}
+
+/// Start the background sweep loop, if an idle window is configured
+pub fn spawn(pool: Pool, notification_service: web::Data<NotificationService>, config: AutoCloseConfig) {
+    let Some(idle_days) = config.idle_days else {
+        tracing::debug!("Ticket auto-close disabled");
+        return;
+    };
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.sweep_interval);
+
+        tracing::info!(
+            idle_days,
+            status = ?config.status,
+            interval_minutes = config.sweep_interval.as_secs() / 60,
+            "Ticket auto-close started"
+        );
+
+        loop {
+            interval.tick().await;
+
+            let sweep_pool = pool.clone();
+            let status = config.status;
+            let closed = match web::block(move || close_idle_tickets(&sweep_pool, status, idle_days)).await {
+                Ok(Ok(closed)) => closed,
+                Ok(Err(e)) => {
+                    tracing::error!(error = %e, "Ticket auto-close sweep failed");
+                    continue;
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Ticket auto-close task failed");
+                    continue;
+                }
+            };
+
+            if !closed.is_empty() {
+                tracing::info!(tickets = closed.len(), "Auto-closed idle tickets");
+            }
+            for ticket in closed {
+                notify_requester(&notification_service, ticket, idle_days).await;
+            }
+        }
+    });
+}
+
+/// Close tickets in `status` idle for more than `idle_days`.
+/// Returns the tickets that were closed.
+fn close_idle_tickets(pool: &Pool, status: TicketStatus, idle_days: i64) -> Result<Vec<AutoClosed>, String> {
+    let mut conn = pool.get().map_err(|e| format!("DB error: {e}"))?;
+    let idle_since = Utc::now().naive_utc() - Duration::days(idle_days);
+
+    let mut closed = Vec::new();
+    loop {
+        let batch = repository::tickets::get_idle_tickets(&mut conn, status, idle_since, CLOSE_BATCH_SIZE)
+            .map_err(|e| format!("Failed to load idle tickets: {e}"))?;
+        let batch_len = batch.len();
+        let mut batch_closed = 0;
+
+        for ticket in batch {
+            match auto_close(&mut conn, &ticket, idle_days) {
+                Ok(()) => {
+                    batch_closed += 1;
+                    closed.push(AutoClosed {
+                        ticket_id: ticket.id,
+                        ticket_title: ticket.title,
+                        requester_uuid: ticket.requester_uuid,
+                    });
+                }
+                Err(e) => tracing::warn!(ticket_id = ticket.id, error = %e, "Failed to auto-close ticket"),
+            }
+        }
+
+        // A short batch is the last one; a batch with nothing closed would repeat forever
+        if batch_len < CLOSE_BATCH_SIZE as usize || batch_closed == 0 {
+            break;
+        }
+    }
+
+    Ok(closed)
+}
+
+/// Post the auto-close comment and close the ticket as the system user.
+/// Fails without closing if the ticket changed since it was loaded.
+fn auto_close(conn: &mut DbConnection, ticket: &Ticket, idle_days: i64) -> Result<(), String> {
+    conn.transaction(|conn| {
+        let system = repository::users::ensure_system_user(conn)?;
+
+        let update = TicketUpdate {
+            status: Some(TicketStatus::Closed),
+            updated_at: Some(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        repository::update_ticket_partial(conn, ticket.id, update, Some(ticket.version), Some(system.uuid))?;
+
+        repository::create_comment(
+            conn,
+            NewComment {
+                content: format!(
+                    "This ticket was closed automatically after {idle_days} days without activity. \
+                     Reopen it if you still need help."
+                ),
+                ticket_id: ticket.id,
+                user_uuid: system.uuid,
+                is_internal: false,
+            },
+        )?;
+        Ok::<_, repository::TicketUpdateError>(())
+    })
+    .map_err(|e| e.to_string())
+}
+
+async fn notify_requester(notification_service: &NotificationService, ticket: AutoClosed, idle_days: i64) {
+    let ticket_id = ticket.ticket_id;
+    let base_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+    let Some(payload) = requester_notification(ticket, idle_days, &base_url) else {
+        return;
+    };
+
+    if let Err(e) = notification_service.notify(payload).await {
+        tracing::warn!(ticket_id, error = %e, "Failed to send auto-close notification");
+    }
+}
+
+/// Notification telling the requester their ticket was closed, with a link to
+/// the frontend page that reopens it (`POST /tickets/{id}/reopen`)
+fn requester_notification(ticket: AutoClosed, idle_days: i64, base_url: &str) -> Option<NotificationPayload> {
+    let requester_uuid = ticket.requester_uuid?;
+
+    let entity = NotificationEntity::Ticket {
+        id: ticket.ticket_id,
+        title: ticket.ticket_title,
+    };
+    let reopen_url = format!("{}/reopen", entity.url(base_url));
+
+    let payload = NotificationPayload::new(
+        NotificationTypeCode::TicketStatusChanged,
+        requester_uuid,
+        NotificationActor {
+            uuid: Uuid::nil(), // System actor
+            name: "System".to_string(),
+            avatar_thumb: None,
+        },
+        entity,
+    )
+    .with_body(format!(
+        "Ticket #{} was closed after {idle_days} days without activity. Reopen it at {reopen_url} if you still need help.",
+        ticket.ticket_id
+    ))
+    .with_metadata(serde_json::json!({ "reopen_url": reopen_url }));
+
+    Some(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::schema::tickets;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+    use diesel::prelude::*;
+
+    fn backdate(conn: &mut DbConnection, ticket_id: i32, status: TicketStatus, days: i64) {
+        diesel::update(tickets::table.find(ticket_id))
+            .set((
+                tickets::status.eq(status),
+                tickets::updated_at.eq(Utc::now().naive_utc() - Duration::days(days)),
+            ))
+            .execute(conn)
+            .unwrap();
+    }
+
+    fn idle_ids(conn: &mut DbConnection, status: TicketStatus, idle_days: i64) -> Vec<i32> {
+        let idle_since = Utc::now().naive_utc() - Duration::days(idle_days);
+        repository::tickets::get_idle_tickets(conn, status, idle_since, 1000)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect()
+    }
+
+    #[test]
+    fn only_idle_tickets_in_the_configured_status_are_eligible() {
+        let mut conn = setup_test_connection();
+        let idle = TestFixtures::create_ticket(&mut conn, "Waiting on requester", None, None);
+        let recent = TestFixtures::create_ticket(&mut conn, "Just updated", None, None);
+        let other_status = TestFixtures::create_ticket(&mut conn, "Still open", None, None);
+        backdate(&mut conn, idle.id, TicketStatus::InProgress, 10);
+        backdate(&mut conn, recent.id, TicketStatus::InProgress, 2);
+        backdate(&mut conn, other_status.id, TicketStatus::Open, 10);
+
+        let eligible = idle_ids(&mut conn, TicketStatus::InProgress, 7);
+        assert!(eligible.contains(&idle.id));
+        assert!(!eligible.contains(&recent.id));
+        assert!(!eligible.contains(&other_status.id));
+    }
+
+    #[test]
+    fn activity_resets_the_clock_and_closing_is_attributed_to_system() {
+        let mut conn = setup_test_connection();
+        let requester = TestFixtures::create_user(&mut conn, "autoclosereq", UserRole::User);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Printer queue stuck", Some(requester.uuid), None);
+        backdate(&mut conn, ticket.id, TicketStatus::InProgress, 10);
+        assert!(idle_ids(&mut conn, TicketStatus::InProgress, 7).contains(&ticket.id));
+
+        // A reply from the requester makes the ticket active again
+        repository::create_comment(
+            &mut conn,
+            NewComment {
+                content: "Still broken".to_string(),
+                ticket_id: ticket.id,
+                user_uuid: requester.uuid,
+                is_internal: false,
+            },
+        )
+        .unwrap();
+        assert!(!idle_ids(&mut conn, TicketStatus::InProgress, 7).contains(&ticket.id));
+
+        backdate(&mut conn, ticket.id, TicketStatus::InProgress, 10);
+        let ticket = repository::get_ticket_by_id(&mut conn, ticket.id).unwrap();
+        auto_close(&mut conn, &ticket, 7).unwrap();
+
+        let closed = repository::get_ticket_by_id(&mut conn, ticket.id).unwrap();
+        assert_eq!(closed.status, TicketStatus::Closed);
+        assert!(closed.closed_at.is_some());
+        assert_eq!(closed.closed_by, Some(repository::users::SYSTEM_USER_UUID));
+
+        let comments = repository::get_comments_by_ticket_id(&mut conn, ticket.id, false).unwrap();
+        let system_comment = comments.last().unwrap();
+        assert_eq!(system_comment.user_uuid, repository::users::SYSTEM_USER_UUID);
+        assert!(system_comment.content.contains("closed automatically"));
+
+        // A ticket that changed since it was loaded is left alone
+        assert!(auto_close(&mut conn, &ticket, 7).is_err());
+    }
+
+    #[test]
+    fn requester_is_sent_a_reopen_link() {
+        let requester_uuid = Uuid::new_v4();
+        let closed = |requester_uuid| AutoClosed {
+            ticket_id: 42,
+            ticket_title: "Printer queue stuck".to_string(),
+            requester_uuid,
+        };
+
+        let payload = requester_notification(closed(Some(requester_uuid)), 7, "https://help.example.com/").unwrap();
+        assert_eq!(payload.recipient_uuid, requester_uuid);
+        assert_eq!(payload.metadata["reopen_url"], "https://help.example.com/tickets/42/reopen");
+        assert!(payload.body.unwrap().contains("https://help.example.com/tickets/42/reopen"));
+
+        assert!(requester_notification(closed(None), 7, "https://help.example.com").is_none());
+    }
+}
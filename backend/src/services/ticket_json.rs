@@ -0,0 +1,144 @@
+//! Ticket import/export format
+//!
+//! Tickets are exchanged as `{"tickets": [...]}` documents described by
+//! `ticket_json.schema.json`. Imports are checked against the schema before
+//! anything is written, with an error per offending field (e.g.
+//! `tickets[2].status`); user references are checked as each ticket is
+//! imported (`repository::tickets::import_ticket_from_json`).
+
+use chrono::{DateTime, NaiveDateTime};
+use jsonschema::paths::{JSONPointer, PathChunk};
+use jsonschema::error::ValidationErrorKind;
+use jsonschema::JSONSchema;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+
+use crate::models::TicketsJson;
+use crate::services::category_fields::FieldError;
+
+/// JSON Schema (draft 7) of the format
+pub const SCHEMA: &str = include_str!("ticket_json.schema.json");
+
+static COMPILED_SCHEMA: Lazy<JSONSchema> = Lazy::new(|| {
+    let schema: Value = serde_json::from_str(SCHEMA).expect("ticket JSON schema is valid JSON");
+    JSONSchema::compile(&schema).expect("ticket JSON schema compiles")
+});
+
+/// Field path of a JSON pointer, e.g. `/tickets/0/requester` becomes
+/// `tickets[0].requester`; `property` is appended for missing properties
+fn field_path(pointer: &JSONPointer, property: Option<&str>) -> String {
+    let mut path = String::new();
+    let properties = pointer.iter().filter_map(|chunk| match chunk {
+        PathChunk::Property(name) => Some(Err(name.as_ref())),
+        PathChunk::Index(i) => Some(Ok(*i)),
+        PathChunk::Keyword(_) => None,
+    });
+    for chunk in properties.chain(property.map(Err)) {
+        match chunk {
+            Ok(i) => path.push_str(&format!("[{i}]")),
+            Err(name) if path.is_empty() => path.push_str(name),
+            Err(name) => path.push_str(&format!(".{name}")),
+        }
+    }
+    if path.is_empty() {
+        path.push_str("document");
+    }
+    path
+}
+
+/// Check a document against the schema and read its tickets
+pub fn parse(document: &Value) -> Result<TicketsJson, Vec<FieldError>> {
+    if let Err(errors) = COMPILED_SCHEMA.validate(document) {
+        return Err(errors
+            .map(|e| match &e.kind {
+                ValidationErrorKind::Required { property } => {
+                    FieldError::new(field_path(&e.instance_path, property.as_str()), "is required")
+                }
+                _ => FieldError::new(field_path(&e.instance_path, None), e.to_string()),
+            })
+            .collect());
+    }
+
+    serde_json::from_value(document.clone()).map_err(|e| vec![FieldError::new("document", e.to_string())])
+}
+
+/// Read an RFC 3339 timestamp as UTC
+pub fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(value).ok().map(|dt| dt.naive_utc())
+}
+
+/// Write a UTC timestamp as RFC 3339
+pub fn format_timestamp(value: NaiveDateTime) -> String {
+    value.and_utc().to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ticket() -> Value {
+        json!({
+            "id": 7,
+            "title": "Printer jams on tray 2",
+            "status": "in-progress",
+            "priority": "high",
+            "created": "2026-02-03T09:15:00Z",
+            "modified": "2026-02-04T16:40:00+01:00",
+            "closed_at": null,
+            "assignee": "",
+            "requester": "0190a4c6-5f1e-7c3a-9d2b-5a6e1f0c8d41",
+            "comments": [{
+                "id": 1,
+                "content": "Tried a new roller",
+                "user_uuid": "0190a4c6-5f1e-7c3a-9d2b-5a6e1f0c8d41",
+                "createdAt": "2026-02-03T10:00:00Z",
+                "attachments": []
+            }]
+        })
+    }
+
+    fn fields(errors: &[FieldError]) -> Vec<&str> {
+        errors.iter().map(|e| e.field.as_str()).collect()
+    }
+
+    #[test]
+    fn valid_documents_are_read() {
+        let tickets = parse(&json!({"tickets": [ticket()]})).unwrap();
+        assert_eq!(tickets.tickets.len(), 1);
+        assert_eq!(tickets.tickets[0].title, "Printer jams on tray 2");
+        assert!(!tickets.tickets[0].comments.as_ref().unwrap()[0].is_internal);
+    }
+
+    #[test]
+    fn schema_errors_name_the_field() {
+        let mut bad = ticket();
+        bad["status"] = json!("pending");
+        bad["created"] = json!("yesterday");
+        bad.as_object_mut().unwrap().remove("requester");
+        bad["comments"][0]["attachments"] = json!([{"url": "/uploads/tickets/a.png"}]);
+
+        let errors = parse(&json!({"tickets": [ticket(), bad]})).unwrap_err();
+        let mut paths = fields(&errors);
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                "tickets[1].comments",
+                "tickets[1].created",
+                "tickets[1].requester",
+                "tickets[1].status",
+            ]
+        );
+        assert!(errors.iter().any(|e| e.field == "tickets[1].requester" && e.message == "is required"));
+
+        assert_eq!(fields(&parse(&json!({"items": []})).unwrap_err()), vec!["tickets"]);
+    }
+
+    #[test]
+    fn timestamps_are_read_as_utc() {
+        let modified = parse_timestamp("2026-02-04T16:40:00+01:00").unwrap();
+        assert_eq!(format_timestamp(modified), "2026-02-04T15:40:00+00:00");
+        assert!(parse_timestamp("2026-02-04 15:40").is_none());
+    }
+}
@@ -0,0 +1,70 @@
+//! HTTP transcription backend
+//!
+//! POSTs the raw file to the configured endpoint with its content type and
+//! `?kind=speech` or `?kind=ocr`, and expects `{"text": "..."}` back.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use super::{Transcriber, TranscriptionKind};
+
+/// Speech-to-text on long recordings can take a while
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionResponse {
+    text: Option<String>,
+}
+
+/// Transcriber backed by an external HTTP endpoint
+pub struct HttpTranscriber {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+}
+
+impl HttpTranscriber {
+    pub fn new(url: String, api_key: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self { client, url, api_key }
+    }
+}
+
+#[async_trait]
+impl Transcriber for HttpTranscriber {
+    async fn transcribe(
+        &self,
+        kind: TranscriptionKind,
+        mime_type: &str,
+        data: Vec<u8>,
+    ) -> Result<Option<String>, String> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .query(&[("kind", kind.as_str())])
+            .header(reqwest::header::CONTENT_TYPE, mime_type)
+            .body(data);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Transcription request failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("Transcription endpoint returned {}", response.status()));
+        }
+
+        let body: TranscriptionResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid transcription response: {e}"))?;
+        Ok(body.text)
+    }
+}
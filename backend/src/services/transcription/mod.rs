@@ -0,0 +1,336 @@
+//! Attachment Transcription
+//!
+//! Extracts searchable text from uploaded audio (speech-to-text) and images
+//! (OCR) through an external endpoint. Uploads enqueue a job; a single worker
+//! drains the queue at a limited rate, stores the text in
+//! `attachments.transcription` and re-indexes the attachment in search.
+//!
+//! Transcription is best-effort: a full queue, an endpoint error or an empty
+//! result leaves the transcription null and the upload unaffected.
+
+pub mod http;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use tokio::sync::mpsc;
+
+use crate::db::Pool;
+use crate::models::Comment;
+use crate::repository;
+use crate::schema::comments;
+use crate::services::search::SearchService;
+
+/// Jobs waiting for the worker; uploads past this are not transcribed
+const QUEUE_SIZE: usize = 32;
+
+/// Default transcription requests per minute
+const DEFAULT_RATE_PER_MINUTE: u32 = 10;
+
+/// Default largest file sent for transcription
+const DEFAULT_MAX_BYTES: usize = 25 * 1024 * 1024;
+
+/// What to extract from a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptionKind {
+    Speech,
+    Ocr,
+}
+
+impl TranscriptionKind {
+    /// Audio is transcribed and images are OCR'd; other types are skipped
+    pub fn for_mime_type(mime_type: &str) -> Option<Self> {
+        if mime_type.starts_with("audio/") {
+            Some(Self::Speech)
+        } else if mime_type.starts_with("image/") {
+            Some(Self::Ocr)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Speech => "speech",
+            Self::Ocr => "ocr",
+        }
+    }
+}
+
+/// Turns file contents into text
+#[async_trait]
+pub trait Transcriber: Send + Sync {
+    /// Returns the extracted text, or None when there was nothing to extract
+    async fn transcribe(
+        &self,
+        kind: TranscriptionKind,
+        mime_type: &str,
+        data: Vec<u8>,
+    ) -> Result<Option<String>, String>;
+}
+
+/// Transcription settings loaded from the environment
+///
+/// * `TRANSCRIPTION_URL` - endpoint files are POSTed to (unset disables transcription)
+/// * `TRANSCRIPTION_API_KEY` - optional bearer token for the endpoint
+/// * `TRANSCRIPTION_RATE_PER_MINUTE` - requests per minute (default 10)
+/// * `TRANSCRIPTION_MAX_BYTES` - larger files are skipped (default 25 MB)
+#[derive(Debug, Clone)]
+pub struct TranscriptionConfig {
+    pub url: Option<String>,
+    pub api_key: Option<String>,
+    pub rate_per_minute: u32,
+    pub max_bytes: usize,
+}
+
+impl TranscriptionConfig {
+    pub fn from_env() -> Self {
+        let non_empty = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let rate_per_minute = std::env::var("TRANSCRIPTION_RATE_PER_MINUTE")
+            .ok()
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .filter(|rate| *rate > 0)
+            .unwrap_or(DEFAULT_RATE_PER_MINUTE);
+        let max_bytes = std::env::var("TRANSCRIPTION_MAX_BYTES")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|bytes| *bytes > 0)
+            .unwrap_or(DEFAULT_MAX_BYTES);
+
+        Self {
+            url: non_empty("TRANSCRIPTION_URL"),
+            api_key: non_empty("TRANSCRIPTION_API_KEY"),
+            rate_per_minute,
+            max_bytes,
+        }
+    }
+}
+
+/// A file waiting to be transcribed
+struct TranscriptionJob {
+    attachment_id: i32,
+    kind: TranscriptionKind,
+    mime_type: String,
+    data: Vec<u8>,
+}
+
+/// Queues uploaded files for transcription
+pub struct TranscriptionService {
+    queue: mpsc::Sender<TranscriptionJob>,
+    max_bytes: usize,
+}
+
+impl TranscriptionService {
+    /// Build from the environment, or None when no endpoint is configured
+    pub fn from_env(pool: Pool, search_service: Arc<SearchService>) -> Option<Self> {
+        let config = TranscriptionConfig::from_env();
+        let url = config.url.clone()?;
+        let transcriber = Arc::new(http::HttpTranscriber::new(url, config.api_key.clone()));
+        tracing::info!(rate_per_minute = config.rate_per_minute, "Attachment transcription enabled");
+        Some(Self::new(pool, search_service, transcriber, &config))
+    }
+
+    /// Create the service and start its worker
+    pub fn new(
+        pool: Pool,
+        search_service: Arc<SearchService>,
+        transcriber: Arc<dyn Transcriber>,
+        config: &TranscriptionConfig,
+    ) -> Self {
+        let (queue, jobs) = mpsc::channel(QUEUE_SIZE);
+        let min_interval = Duration::from_secs(60) / config.rate_per_minute;
+        tokio::spawn(worker(pool, search_service, transcriber, jobs, min_interval));
+
+        Self {
+            queue,
+            max_bytes: config.max_bytes,
+        }
+    }
+
+    /// Queue an uploaded file for transcription if it's audio or an image.
+    /// Returns whether a job was queued.
+    pub fn enqueue(&self, attachment_id: i32, mime_type: &str, data: &[u8]) -> bool {
+        let Some(kind) = TranscriptionKind::for_mime_type(mime_type) else {
+            return false;
+        };
+        if data.len() > self.max_bytes {
+            tracing::debug!(attachment_id, bytes = data.len(), "Attachment too large to transcribe");
+            return false;
+        }
+
+        let job = TranscriptionJob {
+            attachment_id,
+            kind,
+            mime_type: mime_type.to_string(),
+            data: data.to_vec(),
+        };
+        match self.queue.try_send(job) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(attachment_id, error = %e, "Transcription queue unavailable, skipping");
+                false
+            }
+        }
+    }
+}
+
+/// Drain the queue, starting at most one job per `min_interval`
+async fn worker(
+    pool: Pool,
+    search_service: Arc<SearchService>,
+    transcriber: Arc<dyn Transcriber>,
+    mut jobs: mpsc::Receiver<TranscriptionJob>,
+    min_interval: Duration,
+) {
+    let mut rate_limit = tokio::time::interval(min_interval);
+    rate_limit.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    while let Some(job) = jobs.recv().await {
+        rate_limit.tick().await;
+        let attachment_id = job.attachment_id;
+        if let Err(e) = process(&pool, &search_service, transcriber.as_ref(), job).await {
+            tracing::warn!(attachment_id, error = %e, "Attachment transcription failed");
+        }
+    }
+}
+
+/// Transcribe one file, store the text and re-index the attachment
+async fn process(
+    pool: &Pool,
+    search_service: &Arc<SearchService>,
+    transcriber: &dyn Transcriber,
+    job: TranscriptionJob,
+) -> Result<(), String> {
+    let text = transcriber
+        .transcribe(job.kind, &job.mime_type, job.data)
+        .await?
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty());
+    let Some(text) = text else {
+        tracing::debug!(attachment_id = job.attachment_id, "Nothing transcribed from attachment");
+        return Ok(());
+    };
+
+    let mut conn = pool.get().map_err(|e| format!("DB error: {e}"))?;
+    let attachment = repository::comments::set_attachment_transcription(&mut conn, job.attachment_id, &text)
+        .map_err(|e| format!("Failed to store transcription: {e}"))?;
+    tracing::debug!(attachment_id = attachment.id, kind = job.kind.as_str(), "Stored attachment transcription");
+
+    // Attachments not yet linked to a comment are indexed when the comment is posted
+    let Some(comment_id) = attachment.comment_id else {
+        return Ok(());
+    };
+    let comment: Option<Comment> = comments::table
+        .find(comment_id)
+        .filter(comments::is_internal.eq(false))
+        .first(&mut conn)
+        .optional()
+        .map_err(|e| format!("Failed to load comment: {e}"))?;
+    let Some(comment) = comment else {
+        return Ok(());
+    };
+    let ticket_title = repository::get_ticket_by_id(&mut conn, comment.ticket_id)
+        .map(|ticket| ticket.title)
+        .unwrap_or_else(|_| format!("Ticket #{}", comment.ticket_id));
+    drop(conn);
+
+    let search_service = search_service.clone();
+    tokio::task::spawn_blocking(move || {
+        search_service.index_attachment(&attachment, comment.ticket_id, &ticket_title)?;
+        search_service.commit()
+    })
+    .await
+    .map_err(|e| format!("Indexing task panicked: {e}"))?
+    .map_err(|e| format!("Failed to index attachment: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::services::search::SearchQuery;
+    use crate::test_helpers::{setup_test_pool, TestFixtures};
+    use tokio::sync::Mutex;
+
+    /// Returns fixed text and records what it was asked to transcribe
+    #[derive(Default)]
+    struct MockTranscriber {
+        calls: Mutex<Vec<(TranscriptionKind, String)>>,
+    }
+
+    #[async_trait]
+    impl Transcriber for MockTranscriber {
+        async fn transcribe(
+            &self,
+            kind: TranscriptionKind,
+            mime_type: &str,
+            _data: Vec<u8>,
+        ) -> Result<Option<String>, String> {
+            self.calls.lock().await.push((kind, mime_type.to_string()));
+            Ok(Some("the zorbulon printer keeps jamming on tray two".to_string()))
+        }
+    }
+
+    fn test_config() -> TranscriptionConfig {
+        TranscriptionConfig {
+            url: None,
+            api_key: None,
+            rate_per_minute: 600,
+            max_bytes: 1024,
+        }
+    }
+
+    #[test]
+    fn kind_follows_mime_type() {
+        assert_eq!(TranscriptionKind::for_mime_type("audio/webm"), Some(TranscriptionKind::Speech));
+        assert_eq!(TranscriptionKind::for_mime_type("image/png"), Some(TranscriptionKind::Ocr));
+        assert_eq!(TranscriptionKind::for_mime_type("application/pdf"), None);
+    }
+
+    #[tokio::test]
+    async fn enqueued_upload_is_transcribed_and_indexed() {
+        let pool = setup_test_pool();
+        let index_path = std::env::temp_dir().join(format!("nosdesk-transcribe-{}", uuid::Uuid::new_v4()));
+        let search_service = Arc::new(SearchService::new(&index_path, &pool).unwrap());
+
+        let mut conn = pool.get().unwrap();
+        let user = TestFixtures::create_user(&mut conn, "transcribeuser", UserRole::User);
+        let ticket = TestFixtures::create_ticket(&mut conn, "Printer voice note", Some(user.uuid), None);
+        let comment = TestFixtures::create_comment(&mut conn, ticket.id, user.uuid, "See the recording");
+        let attachment = TestFixtures::create_attachment(&mut conn, comment.id, "note.webm");
+        drop(conn);
+
+        let transcriber = Arc::new(MockTranscriber::default());
+        let service = TranscriptionService::new(pool.clone(), search_service.clone(), transcriber.clone(), &test_config());
+
+        assert!(!service.enqueue(attachment.id, "application/pdf", b"%PDF"));
+        assert!(!service.enqueue(attachment.id, "audio/webm", &[0; 2048]), "over max_bytes");
+        assert!(service.enqueue(attachment.id, "audio/webm", b"fake audio"));
+
+        let query = SearchQuery {
+            q: "zorbulon".to_string(),
+            limit: Some(10),
+            types: Some("attachment".to_string()),
+        };
+        let mut hits = Vec::new();
+        for _ in 0..100 {
+            hits = search_service.search(&query).unwrap().results;
+            if !hits.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entity_id, attachment.id as i64);
+        assert_eq!(*transcriber.calls.lock().await, vec![(TranscriptionKind::Speech, "audio/webm".to_string())]);
+
+        let mut conn = pool.get().unwrap();
+        let stored = repository::comments::get_attachment_by_id(&mut conn, attachment.id).unwrap();
+        assert_eq!(stored.transcription.as_deref(), Some("the zorbulon printer keeps jamming on tray two"));
+
+        let _ = std::fs::remove_dir_all(&index_path);
+    }
+}
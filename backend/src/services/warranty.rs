@@ -0,0 +1,162 @@
+//! Device Warranty Monitor
+//!
+//! Background task that periodically looks for devices whose warranty expires
+//! soon and notifies the device's primary user and all admins. Each
+//! device/expiry date pair is only notified once, so the daily scan doesn't
+//! repeat itself; a renewed warranty (new expiry date) notifies again.
+
+use actix_web::web;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::db::Pool;
+use crate::models::{Device, UserRole};
+use crate::repository;
+use crate::schema::users;
+use crate::services::notifications::{
+    types::{NotificationActor, NotificationEntity, NotificationPayload, NotificationTypeCode},
+    NotificationService,
+};
+
+/// Default number of days ahead to look for expiring warranties
+const DEFAULT_WINDOW_DAYS: i64 = 30;
+
+/// Default time between scans
+const DEFAULT_SCAN_INTERVAL_HOURS: u64 = 24;
+
+/// Scan settings loaded from the environment
+///
+/// * `WARRANTY_EXPIRY_WINDOW_DAYS` - days ahead to warn about (default 30)
+/// * `WARRANTY_SCAN_INTERVAL_HOURS` - time between scans (default 24)
+#[derive(Debug, Clone, Copy)]
+pub struct WarrantyMonitorConfig {
+    pub window_days: i64,
+    pub scan_interval: std::time::Duration,
+}
+
+impl WarrantyMonitorConfig {
+    pub fn from_env() -> Self {
+        let window_days = std::env::var("WARRANTY_EXPIRY_WINDOW_DAYS")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|days| *days >= 0)
+            .unwrap_or(DEFAULT_WINDOW_DAYS);
+        let hours = std::env::var("WARRANTY_SCAN_INTERVAL_HOURS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|hours| *hours > 0)
+            .unwrap_or(DEFAULT_SCAN_INTERVAL_HOURS);
+
+        Self {
+            window_days,
+            scan_interval: std::time::Duration::from_secs(hours * 3600),
+        }
+    }
+}
+
+/// Start the background scan loop
+pub fn spawn(pool: Pool, notification_service: web::Data<NotificationService>, config: WarrantyMonitorConfig) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.scan_interval);
+
+        tracing::info!(
+            window_days = config.window_days,
+            interval_hours = config.scan_interval.as_secs() / 3600,
+            "Warranty monitor started"
+        );
+
+        loop {
+            interval.tick().await;
+
+            match scan_and_notify(&pool, &notification_service, config.window_days).await {
+                Ok(0) => tracing::debug!("No new expiring warranties"),
+                Ok(count) => tracing::info!(devices = count, "Sent warranty expiry notifications"),
+                Err(e) => tracing::error!(error = %e, "Warranty expiry scan failed"),
+            }
+        }
+    });
+}
+
+/// Notify about devices expiring within the window that haven't been
+/// notified yet. Returns the number of devices notified.
+pub async fn scan_and_notify(
+    pool: &Pool,
+    notification_service: &NotificationService,
+    window_days: i64,
+) -> Result<usize, String> {
+    let mut conn = pool.get().map_err(|e| format!("DB error: {e}"))?;
+
+    let devices = repository::devices::get_devices_expiring_within(&mut conn, window_days)
+        .map_err(|e| format!("Failed to load expiring devices: {e}"))?;
+    if devices.is_empty() {
+        return Ok(0);
+    }
+
+    // Boxed because the UserRole SQL type has no QueryId
+    let admins: Vec<Uuid> = users::table
+        .into_boxed()
+        .filter(users::role.eq(UserRole::Admin))
+        .select(users::uuid)
+        .load(&mut conn)
+        .map_err(|e| format!("Failed to load admins: {e}"))?;
+
+    let mut notified = 0;
+    for device in devices {
+        let Some(expiry) = device.warranty_expiry_date else {
+            continue;
+        };
+
+        // Claim before sending so concurrent scans can't double-notify
+        match repository::devices::mark_warranty_notified(&mut conn, device.id, expiry) {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                tracing::warn!(device_id = device.id, error = %e, "Failed to record warranty notification");
+                continue;
+            }
+        }
+
+        for recipient in recipients(&device, &admins) {
+            if let Err(e) = notification_service.notify(payload_for(&device, recipient)).await {
+                tracing::warn!(device_id = device.id, error = %e, "Failed to send warranty notification");
+            }
+        }
+        notified += 1;
+    }
+
+    Ok(notified)
+}
+
+/// The device's primary user plus every admin, without duplicates
+fn recipients(device: &Device, admins: &[Uuid]) -> Vec<Uuid> {
+    let mut recipients: Vec<Uuid> = device.primary_user_uuid.into_iter().collect();
+    for admin in admins {
+        if !recipients.contains(admin) {
+            recipients.push(*admin);
+        }
+    }
+    recipients
+}
+
+fn payload_for(device: &Device, recipient: Uuid) -> NotificationPayload {
+    let expiry = device
+        .warranty_expiry_date
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+
+    NotificationPayload::new(
+        NotificationTypeCode::DeviceWarrantyExpiring,
+        recipient,
+        NotificationActor {
+            uuid: Uuid::nil(), // System actor
+            name: "System".to_string(),
+            avatar_thumb: None,
+        },
+        NotificationEntity::Device {
+            id: device.id,
+            name: device.name.clone(),
+        },
+    )
+    .with_body(format!("The warranty for {} expires on {}", device.name, expiry))
+    .with_metadata(serde_json::json!({ "warranty_expiry_date": expiry }))
+}
@@ -0,0 +1,152 @@
+//! Webhook Circuit Breaker
+//!
+//! Protects the delivery worker from consumers that have stopped answering.
+//! After `TIMEOUT_THRESHOLD` consecutive timeouts the circuit opens and
+//! deliveries fail fast without a request. Once `COOLDOWN_SECS` have passed
+//! it half-opens: the next delivery goes out as a test, closing the circuit
+//! if it gets an answer and reopening it if it times out again.
+//!
+//! Unlike auto-disable (see `delivery`), an open circuit recovers on its own.
+//! The state lives on the webhook row, so it survives restarts.
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::models::Webhook;
+
+/// Consecutive timeouts that open the circuit
+pub const TIMEOUT_THRESHOLD: i32 = 3;
+
+/// How long the circuit stays open before a test delivery, in seconds
+pub const COOLDOWN_SECS: i64 = 60;
+
+/// State of a webhook's circuit at some moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Deliveries go out as usual
+    Closed,
+    /// Deliveries short-circuit until the given time
+    Open { until: NaiveDateTime },
+    /// The next delivery tests whether the consumer has recovered
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// State at `now` from the stored time the circuit opened
+    pub fn at(opened_at: Option<NaiveDateTime>, now: NaiveDateTime) -> Self {
+        match opened_at {
+            None => Self::Closed,
+            Some(opened_at) => {
+                let until = opened_at + Duration::seconds(COOLDOWN_SECS);
+                if now < until {
+                    Self::Open { until }
+                } else {
+                    Self::HalfOpen
+                }
+            }
+        }
+    }
+
+    /// State of `webhook`'s circuit at `now`
+    pub fn of(webhook: &Webhook, now: NaiveDateTime) -> Self {
+        Self::at(webhook.circuit_opened_at, now)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::Open { .. } => "open",
+            Self::HalfOpen => "half_open",
+        }
+    }
+
+    /// When an open circuit lets a test delivery through
+    pub fn retry_at(&self) -> Option<NaiveDateTime> {
+        match self {
+            Self::Open { until } => Some(*until),
+            Self::Closed | Self::HalfOpen => None,
+        }
+    }
+}
+
+/// Stored breaker fields after a delivery at `now`
+///
+/// Only timeouts count: any response, even an error status, shows the
+/// consumer is answering and closes the circuit. Returns the new
+/// `(consecutive_timeouts, circuit_opened_at)`.
+pub fn record_outcome(
+    consecutive_timeouts: i32,
+    opened_at: Option<NaiveDateTime>,
+    timed_out: bool,
+    now: NaiveDateTime,
+) -> (i32, Option<NaiveDateTime>) {
+    if !timed_out {
+        return (0, None);
+    }
+
+    let timeouts = consecutive_timeouts + 1;
+    let opened_at = match CircuitState::at(opened_at, now) {
+        // A failed test reopens the circuit for another cooldown
+        CircuitState::HalfOpen => Some(now),
+        CircuitState::Open { .. } => opened_at,
+        CircuitState::Closed if timeouts >= TIMEOUT_THRESHOLD => Some(now),
+        CircuitState::Closed => None,
+    };
+    (timeouts, opened_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn time_out_times(count: usize, now: NaiveDateTime) -> (i32, Option<NaiveDateTime>) {
+        (0..count).fold((0, None), |(timeouts, opened_at), _| record_outcome(timeouts, opened_at, true, now))
+    }
+
+    #[test]
+    fn consecutive_timeouts_open_the_circuit() {
+        let now = Utc::now().naive_utc();
+        let (timeouts, opened_at) = time_out_times(TIMEOUT_THRESHOLD as usize - 1, now);
+        assert_eq!(CircuitState::at(opened_at, now), CircuitState::Closed);
+
+        let (_, opened_at) = record_outcome(timeouts, opened_at, true, now);
+        let until = now + Duration::seconds(COOLDOWN_SECS);
+        assert_eq!(CircuitState::at(opened_at, now), CircuitState::Open { until });
+        assert_eq!(CircuitState::at(opened_at, now).retry_at(), Some(until));
+    }
+
+    #[test]
+    fn an_answer_resets_the_count() {
+        let now = Utc::now().naive_utc();
+        let (timeouts, opened_at) = time_out_times(TIMEOUT_THRESHOLD as usize - 1, now);
+        assert_eq!(record_outcome(timeouts, opened_at, false, now), (0, None));
+    }
+
+    #[test]
+    fn open_circuit_half_opens_after_the_cooldown() {
+        let now = Utc::now().naive_utc();
+        let (_, opened_at) = time_out_times(TIMEOUT_THRESHOLD as usize, now);
+        let later = now + Duration::seconds(COOLDOWN_SECS);
+        assert_eq!(CircuitState::at(opened_at, later), CircuitState::HalfOpen);
+        assert_eq!(CircuitState::HalfOpen.as_str(), "half_open");
+    }
+
+    #[test]
+    fn half_open_test_closes_or_reopens_the_circuit() {
+        let now = Utc::now().naive_utc();
+        let (timeouts, opened_at) = time_out_times(TIMEOUT_THRESHOLD as usize, now);
+        let later = now + Duration::seconds(COOLDOWN_SECS + 1);
+
+        // The consumer answers the test delivery
+        let (timeouts_after, opened_after) = record_outcome(timeouts, opened_at, false, later);
+        assert_eq!(CircuitState::at(opened_after, later), CircuitState::Closed);
+        assert_eq!(timeouts_after, 0);
+
+        // The test delivery times out again: open for another cooldown
+        let (_, reopened_at) = record_outcome(timeouts, opened_at, true, later);
+        assert_eq!(
+            CircuitState::at(reopened_at, later),
+            CircuitState::Open { until: later + Duration::seconds(COOLDOWN_SECS) }
+        );
+    }
+}
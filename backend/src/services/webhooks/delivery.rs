@@ -2,16 +2,20 @@
 //!
 //! Handles HTTP delivery of webhook payloads with retry logic.
 
+use std::sync::Arc;
 use std::time::Duration;
 
 use chrono::Utc;
-use reqwest::Client;
 use tokio::sync::mpsc;
 
 use crate::db::Pool;
 use crate::models::{NewWebhookDelivery, WebhookDeliveryUpdate, WebhookUpdate};
 use crate::repository::webhooks as webhook_repo;
+use crate::services::outbound_http::OutboundHttp;
+use crate::services::{metrics, shutdown};
+use crate::utils::encryption::open_secret;
 
+use super::circuit_breaker::{self, CircuitState};
 use super::signature::sign_payload;
 use super::types::WebhookPayload;
 
@@ -34,6 +38,7 @@ const AUTO_DISABLE_THRESHOLD: i32 = 10;
 pub struct DeliveryTask {
     pub webhook_id: i32,
     pub webhook_url: String,
+    /// Secret as stored (encrypted); decrypted only to sign
     pub webhook_secret: String,
     pub webhook_headers: Option<serde_json::Value>,
     pub payload: WebhookPayload,
@@ -44,30 +49,33 @@ pub struct DeliveryTask {
 pub struct WebhookDeliveryWorker {
     pool: Pool,
     receiver: mpsc::Receiver<DeliveryTask>,
-    client: Client,
+    http: Arc<OutboundHttp>,
 }
 
 impl WebhookDeliveryWorker {
-    /// Create a new delivery worker
-    pub fn new(pool: Pool, receiver: mpsc::Receiver<DeliveryTask>) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
-            .user_agent("Nosdesk-Webhook/1.0")
-            .build()
-            .expect("Failed to build HTTP client");
-
+    /// Create a new delivery worker sending through the shared client
+    pub fn new(pool: Pool, receiver: mpsc::Receiver<DeliveryTask>, http: Arc<OutboundHttp>) -> Self {
         Self {
             pool,
             receiver,
-            client,
+            http,
         }
     }
 
     /// Run the delivery worker (processes tasks from the channel)
     pub async fn run(mut self) {
-        while let Some(task) = self.receiver.recv().await {
-            if let Err(e) = self.deliver(task).await {
-                tracing::error!(error = %e, "Webhook delivery failed");
+        while let Some(mut task) = self.receiver.recv().await {
+            // Hold the shutdown guard until the queue is empty, so shutdown
+            // waits for queued deliveries as well as the current one
+            let _in_flight = shutdown::webhooks().enter();
+            loop {
+                if let Err(e) = self.deliver(task).await {
+                    tracing::error!(error = %e, "Webhook delivery failed");
+                }
+                match self.receiver.try_recv() {
+                    Ok(next) => task = next,
+                    Err(_) => break,
+                }
             }
         }
         tracing::info!("Webhook delivery worker shutting down");
@@ -75,16 +83,30 @@ impl WebhookDeliveryWorker {
 
     /// Deliver a single webhook
     async fn deliver(&self, task: DeliveryTask) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| format!("DB error: {e}"))?;
+
+        // Don't wait on a consumer that keeps timing out
+        let webhook = webhook_repo::get_webhook_by_id(&mut conn, task.webhook_id)?;
+        if let CircuitState::Open { until } = CircuitState::of(&webhook, Utc::now().naive_utc()) {
+            metrics::record_webhook_delivery("circuit_open");
+            return self.handle_short_circuit(&mut conn, &task, until);
+        }
+
         let payload_json = serde_json::to_string(&task.payload)
             .map_err(|e| format!("Failed to serialize payload: {e}"))?;
 
         // Generate signature
-        let signature = sign_payload(&payload_json, &task.webhook_secret);
+        let secret = open_secret(&task.webhook_secret)
+            .map_err(|e| format!("Failed to decrypt secret for webhook {}: {e}", task.webhook_id))?;
+        let signature = sign_payload(&payload_json, &secret);
 
         // Build request
         let mut request = self
-            .client
+            .http
+            .client()
             .post(&task.webhook_url)
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .header("User-Agent", "Nosdesk-Webhook/1.0")
             .header("Content-Type", "application/json")
             .header("X-Nosdesk-Signature", &signature)
             .header("X-Nosdesk-Event", &task.payload.event_type)
@@ -101,25 +123,14 @@ impl WebhookDeliveryWorker {
             }
         }
 
+        // Wait for an outbound slot before the clock starts
+        let _permit = self.http.permit().await;
+
         // Record start time
         let start = std::time::Instant::now();
 
         // Create delivery record
-        let mut conn = self.pool.get().map_err(|e| format!("DB error: {e}"))?;
-        let delivery = webhook_repo::create_delivery(
-            &mut conn,
-            NewWebhookDelivery {
-                webhook_id: task.webhook_id,
-                event_type: task.payload.event_type.clone(),
-                payload: serde_json::to_value(&task.payload).unwrap_or_default(),
-                request_headers: Some(serde_json::json!({
-                    "X-Nosdesk-Signature": "sha256=***",
-                    "X-Nosdesk-Event": &task.payload.event_type,
-                    "X-Nosdesk-Delivery": task.payload.id.to_string(),
-                })),
-                attempt_number: task.attempt,
-            },
-        )?;
+        let delivery = Self::create_delivery(&mut conn, &task)?;
 
         // Send request
         let result = request.body(payload_json).send().await;
@@ -132,9 +143,11 @@ impl WebhookDeliveryWorker {
 
                 if (200..300).contains(&status) {
                     // Success
+                    metrics::record_webhook_delivery("success");
                     self.handle_success(&mut conn, &task, delivery.id, status, response_body, duration_ms)?;
                 } else {
                     // HTTP error - schedule retry
+                    metrics::record_webhook_delivery("http_error");
                     self.handle_failure(
                         &mut conn,
                         &task,
@@ -143,11 +156,13 @@ impl WebhookDeliveryWorker {
                         response_body,
                         duration_ms,
                         None,
+                        false,
                     )?;
                 }
             }
             Err(e) => {
                 // Network error - schedule retry
+                metrics::record_webhook_delivery("network_error");
                 self.handle_failure(
                     &mut conn,
                     &task,
@@ -156,6 +171,7 @@ impl WebhookDeliveryWorker {
                     None,
                     duration_ms,
                     Some(e.to_string()),
+                    e.is_timeout(),
                 )?;
             }
         }
@@ -163,6 +179,77 @@ impl WebhookDeliveryWorker {
         Ok(())
     }
 
+    /// Record a delivery attempt for `task`
+    fn create_delivery(
+        conn: &mut crate::db::DbConnection,
+        task: &DeliveryTask,
+    ) -> Result<crate::models::WebhookDelivery, String> {
+        webhook_repo::create_delivery(
+            conn,
+            NewWebhookDelivery {
+                webhook_id: task.webhook_id,
+                event_type: task.payload.event_type.clone(),
+                payload: serde_json::to_value(&task.payload).unwrap_or_default(),
+                request_headers: Some(serde_json::json!({
+                    "X-Nosdesk-Signature": "sha256=***",
+                    "X-Nosdesk-Event": &task.payload.event_type,
+                    "X-Nosdesk-Delivery": task.payload.id.to_string(),
+                })),
+                attempt_number: task.attempt,
+            },
+        )
+    }
+
+    /// When to retry a failed attempt, if any attempts are left
+    /// (exponential backoff with jitter)
+    fn next_retry_at(attempt: i32) -> Option<chrono::NaiveDateTime> {
+        if attempt < MAX_RETRIES {
+            let base_delay = INITIAL_RETRY_DELAY_SECS * 2u64.pow(attempt as u32 - 1);
+            let jitter = rand::random::<u64>() % (base_delay / 2 + 1);
+            let delay = std::cmp::min(base_delay + jitter, MAX_RETRY_DELAY_SECS);
+            Some(Utc::now().naive_utc() + chrono::Duration::seconds(delay as i64))
+        } else {
+            None
+        }
+    }
+
+    /// Record a delivery skipped because the circuit is open
+    ///
+    /// The retry waits for the circuit to half-open. The webhook's failure
+    /// count is left alone, so an open circuit doesn't lead to auto-disable.
+    fn handle_short_circuit(
+        &self,
+        conn: &mut crate::db::DbConnection,
+        task: &DeliveryTask,
+        until: chrono::NaiveDateTime,
+    ) -> Result<(), String> {
+        let delivery = Self::create_delivery(conn, task)?;
+        let next_retry = Self::next_retry_at(task.attempt).map(|at| at.max(until));
+
+        webhook_repo::update_delivery(
+            conn,
+            delivery.id,
+            WebhookDeliveryUpdate {
+                response_status: Some(0),
+                duration_ms: Some(0),
+                error_message: Some(format!(
+                    "Circuit open after repeated timeouts; deliveries resume at {until}"
+                )),
+                next_retry_at: Some(next_retry),
+                ..Default::default()
+            },
+        )?;
+
+        tracing::debug!(
+            webhook_id = task.webhook_id,
+            attempt = task.attempt,
+            next_retry = ?next_retry,
+            "Webhook circuit open, delivery skipped"
+        );
+
+        Ok(())
+    }
+
     /// Handle successful delivery
     fn handle_success(
         &self,
@@ -195,6 +282,8 @@ impl WebhookDeliveryWorker {
                 last_triggered_at: Some(Utc::now().naive_utc()),
                 failure_count: Some(0),
                 disabled_reason: Some(None),
+                consecutive_timeouts: Some(0),
+                circuit_opened_at: Some(None),
                 ..Default::default()
             },
         )?;
@@ -219,16 +308,9 @@ impl WebhookDeliveryWorker {
         response_body: Option<String>,
         duration_ms: i32,
         error_message: Option<String>,
+        timed_out: bool,
     ) -> Result<(), String> {
-        // Calculate next retry time (exponential backoff with jitter)
-        let next_retry = if task.attempt < MAX_RETRIES {
-            let base_delay = INITIAL_RETRY_DELAY_SECS * 2u64.pow(task.attempt as u32 - 1);
-            let jitter = rand::random::<u64>() % (base_delay / 2 + 1);
-            let delay = std::cmp::min(base_delay + jitter, MAX_RETRY_DELAY_SECS);
-            Some(Utc::now().naive_utc() + chrono::Duration::seconds(delay as i64))
-        } else {
-            None
-        };
+        let next_retry = Self::next_retry_at(task.attempt);
 
         // Update delivery record
         webhook_repo::update_delivery(
@@ -247,9 +329,24 @@ impl WebhookDeliveryWorker {
         // Increment failure count
         let webhook = webhook_repo::get_webhook_by_id(conn, task.webhook_id)?;
         let new_failure_count = webhook.failure_count + 1;
+        let (consecutive_timeouts, circuit_opened_at) = circuit_breaker::record_outcome(
+            webhook.consecutive_timeouts,
+            webhook.circuit_opened_at,
+            timed_out,
+            Utc::now().naive_utc(),
+        );
+        if circuit_opened_at.is_some() && circuit_opened_at != webhook.circuit_opened_at {
+            tracing::warn!(
+                webhook_id = task.webhook_id,
+                timeouts = consecutive_timeouts,
+                "Webhook circuit opened after consecutive timeouts"
+            );
+        }
 
         let mut update = WebhookUpdate {
             failure_count: Some(new_failure_count),
+            consecutive_timeouts: Some(consecutive_timeouts),
+            circuit_opened_at: Some(circuit_opened_at),
             ..Default::default()
         };
 
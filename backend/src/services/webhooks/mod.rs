@@ -2,6 +2,7 @@
 //!
 //! Provides webhook functionality for external integrations.
 
+pub mod circuit_breaker;
 pub mod delivery;
 pub mod service;
 pub mod signature;
@@ -11,6 +11,8 @@ use uuid::Uuid;
 use crate::db::Pool;
 use crate::handlers::sse::{SseState, TicketEvent};
 use crate::repository::webhooks as webhook_repo;
+use crate::services::outbound_http::OutboundHttp;
+use crate::services::shutdown;
 
 use super::delivery::{DeliveryTask, WebhookDeliveryWorker};
 use super::types::{WebhookEventType, WebhookPayload};
@@ -26,12 +28,12 @@ pub struct WebhookService {
 
 impl WebhookService {
     /// Create a new WebhookService and start background workers
-    pub fn new(pool: Pool, sse_state: Arc<SseState>) -> Self {
+    pub fn new(pool: Pool, sse_state: Arc<SseState>, http: Arc<OutboundHttp>) -> Self {
         // Create bounded channel for delivery tasks
         let (delivery_tx, delivery_rx) = mpsc::channel::<DeliveryTask>(DELIVERY_QUEUE_SIZE);
 
         // Start delivery worker
-        let worker = WebhookDeliveryWorker::new(pool.clone(), delivery_rx);
+        let worker = WebhookDeliveryWorker::new(pool.clone(), delivery_rx, http);
         tokio::spawn(async move {
             worker.run().await;
         });
@@ -67,11 +69,15 @@ impl WebhookService {
 
         loop {
             match receiver.recv().await {
+                Ok(_) if shutdown::webhooks().is_closed() => {
+                    tracing::debug!("Shutting down, not queueing webhooks for event");
+                }
                 Ok(event) => {
                     // Map SSE event to webhook event type
                     if let Some(event_type) = WebhookEventType::from_sse_event(&event) {
+                        let data = serde_json::to_value(&event).unwrap_or_default();
                         if let Err(e) =
-                            Self::process_event(&pool, &delivery_tx, event_type, &event).await
+                            Self::process_event(&pool, &delivery_tx, event_type, data).await
                         {
                             tracing::error!(error = %e, "Failed to process webhook event");
                         }
@@ -93,7 +99,7 @@ impl WebhookService {
         pool: &Pool,
         delivery_tx: &mpsc::Sender<DeliveryTask>,
         event_type: WebhookEventType,
-        event: &TicketEvent,
+        data: serde_json::Value,
     ) -> Result<(), String> {
         let event_type_str = event_type.as_str();
 
@@ -110,7 +116,7 @@ impl WebhookService {
             id: Uuid::now_v7(),
             event_type: event_type_str.to_string(),
             timestamp: Utc::now(),
-            data: serde_json::to_value(event).unwrap_or_default(),
+            data,
         };
 
         tracing::debug!(
@@ -151,6 +157,11 @@ impl WebhookService {
         loop {
             interval.tick().await;
 
+            // Pending retries stay in the database for the next start
+            if shutdown::webhooks().is_closed() {
+                continue;
+            }
+
             if let Err(e) = Self::process_retries(&pool, &delivery_tx).await {
                 tracing::error!(error = %e, "Failed to process webhook retries");
             }
@@ -218,6 +229,18 @@ impl WebhookService {
         Ok(())
     }
 
+    /// Queue deliveries for an event that doesn't come from the SSE stream
+    pub async fn dispatch(
+        &self,
+        event_type: WebhookEventType,
+        data: serde_json::Value,
+    ) -> Result<(), String> {
+        if shutdown::webhooks().is_closed() {
+            return Ok(());
+        }
+        Self::process_event(&self.pool, &self.delivery_tx, event_type, data).await
+    }
+
     /// Send a test event to a webhook
     pub async fn send_test_event(&self, webhook_id: i32) -> Result<(), String> {
         let mut conn = self.pool.get().map_err(|e| format!("DB error: {e}"))?;
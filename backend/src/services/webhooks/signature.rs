@@ -1,9 +1,13 @@
 //! Webhook Signature
 //!
-//! HMAC-SHA256 signature generation for webhook payloads.
+//! HMAC-SHA256 signature generation for webhook payloads. Secrets are stored
+//! encrypted with `utils::encryption` and only decrypted to sign a delivery or
+//! for an audited reveal.
 
 use ring::hmac;
 
+use crate::utils::encryption::{self, WEBHOOK_SECRET_PREFIX};
+
 /// Generate HMAC-SHA256 signature for a payload
 pub fn sign_payload(payload: &str, secret: &str) -> String {
     let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
@@ -12,7 +16,6 @@ pub fn sign_payload(payload: &str, secret: &str) -> String {
 }
 
 /// Verify HMAC-SHA256 signature (constant-time comparison)
-#[allow(dead_code)]
 #[allow(deprecated)]
 pub fn verify_signature(payload: &str, secret: &str, signature: &str) -> bool {
     let expected = sign_payload(payload, secret);
@@ -24,12 +27,19 @@ pub fn generate_secret() -> String {
     use rand::Rng;
     let mut rng = rand::thread_rng();
     let bytes: [u8; 32] = rng.gen();
-    format!("whsec_{}", hex::encode(bytes))
+    format!("{WEBHOOK_SECRET_PREFIX}{}", hex::encode(bytes))
+}
+
+/// Encrypt a secret for storage
+pub fn seal_secret(secret: &str) -> anyhow::Result<String> {
+    encryption::encrypt(secret)
 }
 
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::encryption::{is_plaintext_secret, open_secret};
 
     #[test]
     fn test_sign_and_verify() {
@@ -47,4 +57,20 @@ mod tests {
         assert!(secret.starts_with("whsec_"));
         assert_eq!(secret.len(), 70); // "whsec_" (6) + 64 hex chars
     }
+
+    #[test]
+    fn sealed_secrets_open_and_legacy_plaintext_passes_through() {
+        std::env::set_var(
+            "ENCRYPTION_KEY",
+            "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        );
+        let secret = generate_secret();
+
+        let sealed = seal_secret(&secret).unwrap();
+        assert!(!is_plaintext_secret(&sealed));
+        assert_eq!(open_secret(&sealed).unwrap(), secret);
+
+        assert!(is_plaintext_secret(&secret));
+        assert_eq!(open_secret(&secret).unwrap(), secret);
+    }
 }
@@ -45,6 +45,9 @@ pub enum WebhookEventType {
     UserCreated,
     UserUpdated,
     UserDeleted,
+
+    // System events
+    NotificationDeliveryFailed,
 }
 
 impl WebhookEventType {
@@ -71,6 +74,7 @@ impl WebhookEventType {
             Self::UserCreated => "user.created",
             Self::UserUpdated => "user.updated",
             Self::UserDeleted => "user.deleted",
+            Self::NotificationDeliveryFailed => "notification.delivery_failed",
         }
     }
 
@@ -98,6 +102,7 @@ impl WebhookEventType {
             "user.created" => Some(Self::UserCreated),
             "user.updated" => Some(Self::UserUpdated),
             "user.deleted" => Some(Self::UserDeleted),
+            "notification.delivery_failed" => Some(Self::NotificationDeliveryFailed),
             _ => None,
         }
     }
@@ -125,10 +130,14 @@ impl WebhookEventType {
             "user.created",
             "user.updated",
             "user.deleted",
+            "notification.delivery_failed",
         ]
     }
 
     /// Map from SSE TicketEvent to WebhookEventType
+    ///
+    /// System events aren't broadcast over SSE; they're dispatched directly
+    /// through `WebhookService::dispatch`.
     pub fn from_sse_event(event: &TicketEvent) -> Option<Self> {
         match event {
             TicketEvent::TicketCreated { .. } => Some(Self::TicketCreated),
@@ -154,7 +163,11 @@ impl WebhookEventType {
             // Internal events not exposed to webhooks
             TicketEvent::Heartbeat { .. } => None,
             TicketEvent::ViewerCountChanged { .. } => None,
+            TicketEvent::EditorJoined { .. } => None,
+            TicketEvent::EditorLeft { .. } => None,
+            TicketEvent::EditorCursorMoved { .. } => None,
             TicketEvent::NotificationReceived { .. } => None,
+            TicketEvent::NotificationsRead { .. } => None,
         }
     }
 }
@@ -195,6 +208,7 @@ mod tests {
             WebhookEventType::UserCreated,
             WebhookEventType::UserUpdated,
             WebhookEventType::UserDeleted,
+            WebhookEventType::NotificationDeliveryFailed,
         ];
         for variant in &variants {
             let s = variant.as_str();
@@ -214,7 +228,7 @@ mod tests {
 
     #[test]
     fn all_returns_correct_count() {
-        assert_eq!(WebhookEventType::all().len(), 20);
+        assert_eq!(WebhookEventType::all().len(), 21);
     }
 
     #[test]
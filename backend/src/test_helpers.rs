@@ -104,6 +104,9 @@ impl TestFixtures {
             display_order: 0,
             is_active: true,
             created_by: None,
+            default_assignee_uuid: None,
+            default_group_id: None,
+            required_fields: serde_json::json!([]),
         };
 
         diesel::insert_into(ticket_categories::table)
@@ -156,6 +159,7 @@ impl TestFixtures {
             content: content.to_string(),
             ticket_id,
             user_uuid,
+            is_internal: false,
         };
 
         diesel::insert_into(comments::table)
@@ -226,6 +230,12 @@ impl TestFixtures {
 /// Unlike `setup_test_connection`, this returns a Pool that can be used with `web::Data`.
 /// Note: Tests using this pool share the same database state.
 pub fn setup_test_pool() -> crate::db::Pool {
+    setup_test_pool_with_size(2)
+}
+
+/// Test pool with room for `max_size` connections, for code that holds a
+/// connection while the services it calls take more from the pool
+pub fn setup_test_pool_with_size(max_size: u32) -> crate::db::Pool {
     dotenv::dotenv().ok();
 
     let database_url = std::env::var("TEST_DATABASE_URL")
@@ -234,7 +244,7 @@ pub fn setup_test_pool() -> crate::db::Pool {
 
     let manager = ConnectionManager::<PgConnection>::new(database_url);
     r2d2::Pool::builder()
-        .max_size(2)
+        .max_size(max_size)
         .build(manager)
         .expect("Failed to create test pool")
 }
@@ -259,5 +269,6 @@ pub fn create_test_claims(user: &User) -> crate::models::Claims {
         scope: "full".to_string(),
         exp: (chrono::Utc::now() + chrono::Duration::hours(24)).timestamp() as usize,
         iat: chrono::Utc::now().timestamp() as usize,
+        impersonator: None,
     }
 }
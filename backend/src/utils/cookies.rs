@@ -4,14 +4,16 @@ use actix_web::cookie::{Cookie, SameSite};
 pub const ACCESS_TOKEN_COOKIE: &str = "access_token";
 pub const REFRESH_TOKEN_COOKIE: &str = "refresh_token";
 pub const CSRF_TOKEN_COOKIE: &str = "csrf_token";
+pub const IMPERSONATOR_TOKEN_COOKIE: &str = "impersonator_token";
+pub const IMPERSONATING_COOKIE: &str = "impersonating";
 
 /// Create an httpOnly cookie for the access token (24 hours)
 pub fn create_access_token_cookie(token: &str) -> Cookie<'static> {
     Cookie::build(ACCESS_TOKEN_COOKIE, token.to_string())
         .path("/")
         .http_only(true)
-        .secure(is_production()) // HTTPS only in production
-        .same_site(SameSite::Strict)
+        .secure(is_secure()) // HTTPS only in production
+        .same_site(same_site())
         .max_age(actix_web::cookie::time::Duration::hours(24))
         .finish()
 }
@@ -21,8 +23,8 @@ pub fn create_refresh_token_cookie(token: &str) -> Cookie<'static> {
     Cookie::build(REFRESH_TOKEN_COOKIE, token.to_string())
         .path("/")
         .http_only(true)
-        .secure(is_production())
-        .same_site(SameSite::Strict)
+        .secure(is_secure())
+        .same_site(same_site())
         .max_age(actix_web::cookie::time::Duration::days(7))
         .finish()
 }
@@ -32,19 +34,44 @@ pub fn create_csrf_token_cookie(token: &str) -> Cookie<'static> {
     Cookie::build(CSRF_TOKEN_COOKIE, token.to_string())
         .path("/")
         .http_only(false) // JavaScript needs to read this
-        .secure(is_production())
-        .same_site(SameSite::Strict)
+        .secure(is_secure())
+        .same_site(same_site())
         .max_age(actix_web::cookie::time::Duration::hours(24))
         .finish()
 }
 
+/// Create an httpOnly cookie holding the admin's own access token while they
+/// impersonate someone, so stopping can restore it
+pub fn create_impersonator_token_cookie(token: &str) -> Cookie<'static> {
+    Cookie::build(IMPERSONATOR_TOKEN_COOKIE, token.to_string())
+        .path("/")
+        .http_only(true)
+        .secure(is_secure())
+        .same_site(same_site())
+        .max_age(actix_web::cookie::time::Duration::hours(24))
+        .finish()
+}
+
+/// Create a cookie flagging an impersonation session (NOT httpOnly - the
+/// frontend reads it to show the impersonation banner). Holds the
+/// impersonated user's UUID.
+pub fn create_impersonating_cookie(user_uuid: &str, max_age_seconds: i64) -> Cookie<'static> {
+    Cookie::build(IMPERSONATING_COOKIE, user_uuid.to_string())
+        .path("/")
+        .http_only(false)
+        .secure(is_secure())
+        .same_site(same_site())
+        .max_age(actix_web::cookie::time::Duration::seconds(max_age_seconds))
+        .finish()
+}
+
 /// Create a cookie to delete the access token
 pub fn delete_access_token_cookie() -> Cookie<'static> {
     Cookie::build(ACCESS_TOKEN_COOKIE, "")
         .path("/")
         .http_only(true)
-        .secure(is_production())
-        .same_site(SameSite::Strict)
+        .secure(is_secure())
+        .same_site(same_site())
         .max_age(actix_web::cookie::time::Duration::seconds(0))
         .finish()
 }
@@ -54,8 +81,8 @@ pub fn delete_refresh_token_cookie() -> Cookie<'static> {
     Cookie::build(REFRESH_TOKEN_COOKIE, "")
         .path("/")
         .http_only(true)
-        .secure(is_production())
-        .same_site(SameSite::Strict)
+        .secure(is_secure())
+        .same_site(same_site())
         .max_age(actix_web::cookie::time::Duration::seconds(0))
         .finish()
 }
@@ -65,12 +92,61 @@ pub fn delete_csrf_token_cookie() -> Cookie<'static> {
     Cookie::build(CSRF_TOKEN_COOKIE, "")
         .path("/")
         .http_only(false)
-        .secure(is_production())
-        .same_site(SameSite::Strict)
+        .secure(is_secure())
+        .same_site(same_site())
+        .max_age(actix_web::cookie::time::Duration::seconds(0))
+        .finish()
+}
+
+/// Create a cookie to delete the stashed impersonator token
+pub fn delete_impersonator_token_cookie() -> Cookie<'static> {
+    Cookie::build(IMPERSONATOR_TOKEN_COOKIE, "")
+        .path("/")
+        .http_only(true)
+        .secure(is_secure())
+        .same_site(same_site())
+        .max_age(actix_web::cookie::time::Duration::seconds(0))
+        .finish()
+}
+
+/// Create a cookie to delete the impersonation banner flag
+pub fn delete_impersonating_cookie() -> Cookie<'static> {
+    Cookie::build(IMPERSONATING_COOKIE, "")
+        .path("/")
+        .http_only(false)
+        .secure(is_secure())
+        .same_site(same_site())
         .max_age(actix_web::cookie::time::Duration::seconds(0))
         .finish()
 }
 
+/// SameSite policy for auth cookies (COOKIE_SAME_SITE=strict|lax|none, default strict)
+///
+/// Deployments serving the SPA from a different site than the API need `none`
+/// (cross-site requests never carry Strict/Lax cookies); CSRF is still enforced
+/// by the double-submit check and the CORS origin allowlist.
+pub fn same_site() -> SameSite {
+    std::env::var("COOKIE_SAME_SITE")
+        .ok()
+        .and_then(|v| parse_same_site(&v))
+        .unwrap_or(SameSite::Strict)
+}
+
+/// Parse a SameSite setting, ignoring case
+fn parse_same_site(value: &str) -> Option<SameSite> {
+    match value.trim().to_lowercase().as_str() {
+        "strict" => Some(SameSite::Strict),
+        "lax" => Some(SameSite::Lax),
+        "none" => Some(SameSite::None),
+        _ => None,
+    }
+}
+
+/// Browsers reject `SameSite=None` cookies that aren't `Secure`
+fn is_secure() -> bool {
+    is_production() || same_site() == SameSite::None
+}
+
 /// Check if running in production mode
 fn is_production() -> bool {
     std::env::var("ENVIRONMENT")
@@ -137,5 +213,12 @@ mod tests {
         let cookie = create_refresh_token_cookie("t");
         assert_eq!(cookie.max_age(), Some(actix_web::cookie::time::Duration::days(7)));
     }
-}
 
+    #[test]
+    fn same_site_parsing() {
+        assert_eq!(parse_same_site("Strict"), Some(SameSite::Strict));
+        assert_eq!(parse_same_site(" lax "), Some(SameSite::Lax));
+        assert_eq!(parse_same_site("NONE"), Some(SameSite::None));
+        assert_eq!(parse_same_site("sometimes"), None);
+    }
+}
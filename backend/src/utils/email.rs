@@ -254,7 +254,7 @@ impl<'a> EmailTemplate<'a> {
         let (bg_color, border_color): (&str, &str) = match notice_type {
             NoticeType::Warning => ("#fef3c7", "#f59e0b"),
             NoticeType::Critical => ("#fee2e2", "#dc2626"),
-            NoticeType::Info => (&light_color, &self.branding.primary_color),
+            NoticeType::Info | NoticeType::Summary => (&light_color, &self.branding.primary_color),
             NoticeType::Success => ("#ecfdf5", "#059669"),
         };
 
@@ -263,6 +263,7 @@ impl<'a> EmailTemplate<'a> {
             NoticeType::Critical => "Critical Security Notice",
             NoticeType::Info => "Getting Started",
             NoticeType::Success => "Success",
+            NoticeType::Summary => "At a Glance",
         };
 
         let items_html: String = items
@@ -297,6 +298,7 @@ enum NoticeType {
     Info,
     #[allow(dead_code)]
     Success,
+    Summary,
 }
 
 /// Email configuration loaded from environment variables
@@ -650,6 +652,125 @@ impl EmailService {
         let subject = format!("You've Been Invited to {} - Set Up Your Account", branding.app_name);
         self.send_html_email(to, &subject, &html_body).await
     }
+
+    /// Send a link confirming the user owns an email address they added
+    pub async fn send_email_verification_email(
+        &self,
+        to: &str,
+        user_name: &str,
+        verification_token: &str,
+        branding: &EmailBranding,
+    ) -> Result<(), String> {
+        if !self.config.is_configured() {
+            return Err("Email is not configured".to_string());
+        }
+
+        let verify_link = format!("{}/verify-email?token={}", branding.base_url, verification_token);
+        let template = EmailTemplate::new(branding);
+
+        let content = format!(
+            r#"<p style="margin: 0 0 16px 0; color: #374151; font-size: 16px; line-height: 1.6;">
+                Hello <strong>{}</strong>,
+            </p>
+            <p style="margin: 0 0 16px 0; color: #374151; font-size: 16px; line-height: 1.6;">
+                <strong>{}</strong> was added to your {} account. Until it's verified, it won't receive notifications and can't be made your primary address.
+            </p>
+            <p style="margin: 0 0 8px 0; color: #374151; font-size: 16px; line-height: 1.6;">
+                To verify this address, click the button below:
+            </p>"#,
+            escape_html(user_name),
+            escape_html(to),
+            escape_html(&branding.app_name)
+        );
+
+        let html_body = template.build(
+            "Verify Your Email Address",
+            &branding.primary_color,
+            &content,
+            "Verify Email",
+            &verify_link,
+            &branding.primary_color,
+            NoticeType::Info,
+            &[
+                "This link will expire in <strong>24 hours</strong>",
+                "This link can only be used <strong>once</strong>",
+                "If you didn't add this address, you can safely ignore this email",
+            ],
+            "If you have any questions, please contact your system administrator.",
+        );
+
+        let subject = format!("Verify Your Email Address - {}", branding.app_name);
+        self.send_html_email(to, &subject, &html_body).await
+    }
+
+    /// Send the periodic ticket report summary with branding
+    pub async fn send_ticket_report_email(
+        &self,
+        to: &str,
+        report: &crate::services::reporting::TicketReport,
+        branding: &EmailBranding,
+    ) -> Result<(), String> {
+        if !self.config.is_configured() {
+            return Err("Email is not configured".to_string());
+        }
+
+        let template = EmailTemplate::new(branding);
+        let period = format!(
+            "{} to {}",
+            report.from.format("%b %-d, %Y"),
+            report.to.format("%b %-d, %Y")
+        );
+        let hours = |h: Option<f64>| h.map(|h| format!("{h:.1} hours")).unwrap_or_else(|| "n/a".to_string());
+
+        let assignee_rows: String = report
+            .assignee_load
+            .iter()
+            .take(10)
+            .map(|load| format!(
+                r#"<tr><td style="padding: 4px 0; color: #374151; font-size: 14px;">{}</td><td style="padding: 4px 0; color: #374151; font-size: 14px; text-align: right;">{} open / {} closed</td></tr>"#,
+                escape_html(&load.name),
+                load.open,
+                load.closed
+            ))
+            .collect();
+
+        let content = format!(
+            r#"<p style="margin: 0 0 16px 0; color: #374151; font-size: 16px; line-height: 1.6;">
+                Ticket activity for <strong>{period}</strong>:
+            </p>
+            <p style="margin: 0 0 16px 0; color: #374151; font-size: 16px; line-height: 1.6;">
+                <strong>{created}</strong> created, <strong>{closed}</strong> closed.<br>
+                Median time to close: <strong>{median}</strong> (average {average}).<br>
+                Time logged: <strong>{logged}</strong>.
+            </p>
+            <table role="presentation" cellspacing="0" cellpadding="0" border="0" width="100%" style="margin: 0 0 8px 0;">{assignee_rows}</table>"#,
+            created = report.created,
+            closed = report.closed,
+            median = hours(report.resolution_time.median_hours),
+            average = hours(report.resolution_time.average_hours),
+            logged = hours(Some(report.time_logged.total_minutes as f64 / 60.0)),
+        );
+
+        let open = &report.open_by_priority;
+        let open_high = format!("<strong>{}</strong> open high priority tickets", open.high);
+        let open_medium = format!("<strong>{}</strong> open medium priority tickets", open.medium);
+        let open_low = format!("<strong>{}</strong> open low priority tickets", open.low);
+
+        let html_body = template.build(
+            "Ticket Summary",
+            &branding.primary_color,
+            &content,
+            "View Tickets",
+            &format!("{}/tickets", branding.base_url),
+            &branding.primary_color,
+            NoticeType::Summary,
+            &[&open_high, &open_medium, &open_low],
+            "You receive this summary because your address is listed in the report settings.",
+        );
+
+        let subject = format!("Ticket Summary {period} - {}", branding.app_name);
+        self.send_html_email(to, &subject, &html_body).await
+    }
 }
 
 #[cfg(test)]
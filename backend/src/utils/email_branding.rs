@@ -1,6 +1,7 @@
 use crate::db::DbConnection;
 use crate::repository::site_settings;
 use crate::utils::email::EmailBranding;
+use crate::utils::pdf::PdfBranding;
 
 /// Get email branding from site settings, with fallbacks
 pub fn get_email_branding(conn: &mut DbConnection, base_url: &str) -> EmailBranding {
@@ -22,3 +23,8 @@ pub fn get_email_branding(conn: &mut DbConnection, base_url: &str) -> EmailBrand
         }
     }
 }
+
+/// Get PDF letterhead branding from the same site settings used for emails
+pub fn get_pdf_branding(conn: &mut DbConnection, base_url: &str) -> PdfBranding {
+    PdfBranding::from_email_branding(&get_email_branding(conn, base_url))
+}
@@ -1,19 +1,109 @@
 //! Encryption utilities for sensitive data at rest
 //!
-//! Uses AES-256-GCM for authenticated encryption.
+//! Uses AES-256-GCM for authenticated encryption. Every ciphertext carries a
+//! format version byte and a random nonce; a failed auth tag check surfaces as
+//! [`DecryptError::Tampered`] so callers can fail closed and raise a security event.
 //! Requires ENCRYPTION_KEY or MFA_ENCRYPTION_KEY environment variable (64 hex chars = 32 bytes).
+//!
+//! Key rotation: ciphertext is prefixed with the id of the key that produced it
+//! (`<key_id>:<hex>`). `ENCRYPTION_KEY_ID` names the current key (default `k1`) and
+//! `ENCRYPTION_RETIRED_KEYS` holds old keys as a comma list of `<key_id>:<hex>` pairs.
+//! Unprefixed ciphertext from before rotation support is tried against every key.
+//!
+//! The same keys sign short messages (e.g. shareable links) with HMAC-SHA256.
+//! Signatures are made with the current key and verified against every key,
+//! so links survive a rotation until the retired key is removed.
 
 use anyhow::{anyhow, Result};
 use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
+use ring::hmac;
 use ring::rand::{SecureRandom, SystemRandom};
+use tracing::{info, warn};
 
-/// Get encryption key from environment (must be 32 bytes for AES-256-GCM)
-/// Checks ENCRYPTION_KEY first, falls back to MFA_ENCRYPTION_KEY for compatibility
-fn get_encryption_key() -> Result<[u8; 32]> {
-    let key_hex = std::env::var("ENCRYPTION_KEY")
-        .or_else(|_| std::env::var("MFA_ENCRYPTION_KEY"))
-        .map_err(|_| anyhow!("ENCRYPTION_KEY or MFA_ENCRYPTION_KEY environment variable not set"))?;
+use crate::config_utils::EnvLookup;
+use crate::db::DbConnection;
+use crate::repository::plugins as plugin_repo;
+use crate::repository::users as user_repo;
+use crate::repository::webhooks as webhook_repo;
+
+/// Key id used for ENCRYPTION_KEY when ENCRYPTION_KEY_ID is not set
+const DEFAULT_KEY_ID: &str = "k1";
+
+/// Separator between the key id prefix and the hex payload
+const KEY_ID_SEPARATOR: char = ':';
+
+/// Ciphertext format version: AES-256-GCM, 12-byte random nonce, header bound as AAD
+const FORMAT_VERSION_AES_GCM: u8 = 1;
+
+/// AES-GCM nonce length in bytes
+const NONCE_LEN: usize = 12;
+
+/// AES-GCM authentication tag length in bytes
+const TAG_LEN: usize = 16;
+
+/// Prefix of generated webhook secrets. A stored value with it predates
+/// encryption at rest and is still plaintext.
+pub const WEBHOOK_SECRET_PREFIX: &str = "whsec_";
+
+/// Errors returned by [`decrypt`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecryptError {
+    /// No usable encryption key is configured
+    KeyUnavailable(String),
+    /// Ciphertext names a key id that isn't configured
+    UnknownKey(String),
+    /// Ciphertext isn't in a recognised format (bad hex, truncated, ...)
+    Malformed(String),
+    /// Authentication tag check failed - the data was modified or the wrong key was used
+    Tampered,
+    /// Decrypted bytes are not valid UTF-8
+    InvalidUtf8,
+}
+
+impl std::fmt::Display for DecryptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeyUnavailable(s) => write!(f, "Encryption key unavailable: {s}"),
+            Self::UnknownKey(id) => write!(f, "Unknown encryption key id '{id}'"),
+            Self::Malformed(s) => write!(f, "Malformed encrypted data: {s}"),
+            Self::Tampered => write!(f, "Decryption failed - data was tampered with or the wrong key was used"),
+            Self::InvalidUtf8 => write!(f, "Invalid UTF-8 in decrypted data"),
+        }
+    }
+}
+
+impl std::error::Error for DecryptError {}
+
+/// A named AES-256 key
+#[derive(Clone)]
+struct EncryptionKey {
+    id: String,
+    bytes: [u8; 32],
+}
+
+/// The current encryption key plus any retired keys still accepted for decryption
+#[derive(Clone)]
+struct Keyring {
+    current: EncryptionKey,
+    retired: Vec<EncryptionKey>,
+}
+
+impl Keyring {
+    /// Find a key by id (current or retired)
+    fn find(&self, key_id: &str) -> Option<&EncryptionKey> {
+        std::iter::once(&self.current)
+            .chain(self.retired.iter())
+            .find(|k| k.id == key_id)
+    }
+
+    /// All keys, current first
+    fn all(&self) -> impl Iterator<Item = &EncryptionKey> {
+        std::iter::once(&self.current).chain(self.retired.iter())
+    }
+}
 
+/// Parse a 64-character hex key into 32 bytes
+fn parse_key_hex(key_hex: &str) -> Result<[u8; 32]> {
     if key_hex.len() != 64 {
         return Err(anyhow!(
             "Encryption key must be exactly 64 hex characters (32 bytes)"
@@ -21,77 +111,413 @@ fn get_encryption_key() -> Result<[u8; 32]> {
     }
 
     let mut key = [0u8; 32];
-    hex::decode_to_slice(&key_hex, &mut key)
+    hex::decode_to_slice(key_hex, &mut key)
         .map_err(|_| anyhow!("Encryption key must be valid hexadecimal"))?;
 
     Ok(key)
 }
 
+/// Validate a key id (used as a ciphertext prefix)
+fn validate_key_id(key_id: &str) -> Result<()> {
+    if key_id.is_empty()
+        || key_id.len() > 32
+        || !key_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "Encryption key id '{key_id}' must be 1-32 characters of [A-Za-z0-9_-]"
+        ));
+    }
+    Ok(())
+}
+
+/// Get encryption key from environment (must be 32 bytes for AES-256-GCM)
+/// Checks ENCRYPTION_KEY first, falls back to MFA_ENCRYPTION_KEY for compatibility
+fn get_encryption_key(lookup: EnvLookup) -> Result<EncryptionKey> {
+    let key_hex = lookup("ENCRYPTION_KEY")
+        .or_else(|| lookup("MFA_ENCRYPTION_KEY"))
+        .ok_or_else(|| anyhow!("ENCRYPTION_KEY or MFA_ENCRYPTION_KEY environment variable not set"))?;
+
+    let id = lookup("ENCRYPTION_KEY_ID").unwrap_or_else(|| DEFAULT_KEY_ID.to_string());
+    validate_key_id(&id)?;
+
+    Ok(EncryptionKey {
+        id,
+        bytes: parse_key_hex(&key_hex)?,
+    })
+}
+
+/// Parse retired keys from a `<key_id>:<hex>,<key_id>:<hex>` list
+fn parse_retired_keys(value: &str) -> Result<Vec<EncryptionKey>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (id, key_hex) = entry
+                .split_once(KEY_ID_SEPARATOR)
+                .ok_or_else(|| anyhow!("Retired encryption keys must be formatted as <key_id>:<hex>"))?;
+            validate_key_id(id)?;
+            Ok(EncryptionKey {
+                id: id.to_string(),
+                bytes: parse_key_hex(key_hex)?,
+            })
+        })
+        .collect()
+}
+
+/// Load the keyring (current + retired keys) from the environment
+fn get_keyring() -> Result<Keyring> {
+    keyring_from(&|name| std::env::var(name).ok())
+}
+
+/// Load the keyring from `lookup`
+fn keyring_from(lookup: EnvLookup) -> Result<Keyring> {
+    let current = get_encryption_key(lookup)?;
+    let retired = match lookup("ENCRYPTION_RETIRED_KEYS") {
+        Some(value) => parse_retired_keys(&value)?,
+        None => Vec::new(),
+    };
+
+    if retired.iter().any(|k| k.id == current.id) {
+        return Err(anyhow!(
+            "Retired encryption key id '{}' collides with the current key id",
+            current.id
+        ));
+    }
+
+    Ok(Keyring { current, retired })
+}
+
+/// Check that the encryption settings in `lookup` form a usable keyring
+pub fn validate_key_config(lookup: EnvLookup) -> Result<()> {
+    keyring_from(lookup).map(|_| ())
+}
+
 /// Check if encryption is available (key is configured)
 #[allow(dead_code)]
 pub fn is_encryption_available() -> bool {
-    get_encryption_key().is_ok()
+    get_keyring().is_ok()
 }
 
-/// Encrypt a string using AES-256-GCM
+/// Encrypt a string using AES-256-GCM with the current key
 ///
-/// Returns hex-encoded ciphertext with prepended nonce.
-/// Format: <12-byte nonce><ciphertext><16-byte auth tag>
+/// Returns the key id and hex-encoded ciphertext with prepended nonce.
+/// Format: <key_id>:<hex(<12-byte nonce><ciphertext><16-byte auth tag>)>
 pub fn encrypt(plaintext: &str) -> Result<String> {
-    let key_bytes = get_encryption_key()?;
+    encrypt_with(&get_keyring()?, plaintext)
+}
+
+fn encrypt_with(keyring: &Keyring, plaintext: &str) -> Result<String> {
+    let key = &keyring.current;
     let unbound_key =
-        UnboundKey::new(&AES_256_GCM, &key_bytes).map_err(|_| anyhow!("Failed to create encryption key"))?;
+        UnboundKey::new(&AES_256_GCM, &key.bytes).map_err(|_| anyhow!("Failed to create encryption key"))?;
     let sealing_key = LessSafeKey::new(unbound_key);
 
     // Generate random 12-byte nonce
     let rng = SystemRandom::new();
-    let mut nonce_bytes = [0u8; 12];
+    let mut nonce_bytes = [0u8; NONCE_LEN];
     rng.fill(&mut nonce_bytes)
         .map_err(|_| anyhow!("Failed to generate nonce"))?;
     let nonce = Nonce::assume_unique_for_key(nonce_bytes);
 
-    // Encrypt the plaintext
+    // Encrypt the plaintext, binding the version and key id as associated data
     let mut in_out = plaintext.as_bytes().to_vec();
     sealing_key
-        .seal_in_place_append_tag(nonce, Aad::empty(), &mut in_out)
+        .seal_in_place_append_tag(
+            nonce,
+            Aad::from(associated_data(FORMAT_VERSION_AES_GCM, &key.id)),
+            &mut in_out,
+        )
         .map_err(|_| anyhow!("Encryption failed"))?;
 
-    // Combine nonce + ciphertext and encode as hex
-    let mut result = nonce_bytes.to_vec();
+    // Combine version + nonce + ciphertext and encode as hex, prefixed with the key id
+    let mut result = Vec::with_capacity(1 + NONCE_LEN + in_out.len());
+    result.push(FORMAT_VERSION_AES_GCM);
+    result.extend_from_slice(&nonce_bytes);
     result.extend_from_slice(&in_out);
-    Ok(hex::encode(result))
+    Ok(format!("{}{}{}", key.id, KEY_ID_SEPARATOR, hex::encode(result)))
+}
+
+/// Associated data authenticated alongside the ciphertext: version byte + key id
+fn associated_data(version: u8, key_id: &str) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(1 + key_id.len());
+    aad.push(version);
+    aad.extend_from_slice(key_id.as_bytes());
+    aad
 }
 
-/// Decrypt a hex-encoded ciphertext using AES-256-GCM
+/// Decrypt a ciphertext produced by [`encrypt`]
 ///
-/// Expects format: <12-byte nonce><ciphertext><16-byte auth tag>
-pub fn decrypt(encrypted_hex: &str) -> Result<String> {
-    let key_bytes = get_encryption_key()?;
-    let unbound_key =
-        UnboundKey::new(&AES_256_GCM, &key_bytes).map_err(|_| anyhow!("Failed to create decryption key"))?;
-    let opening_key = LessSafeKey::new(unbound_key);
+/// Selects the key by the `<key_id>:` prefix. Legacy unprefixed ciphertext
+/// (format: <12-byte nonce><ciphertext><16-byte auth tag>) is tried against
+/// the current key, then each retired key.
+///
+/// Returns [`DecryptError::Tampered`] when the authentication tag doesn't verify.
+pub fn decrypt(encrypted: &str) -> Result<String, DecryptError> {
+    let keyring = get_keyring().map_err(|e| DecryptError::KeyUnavailable(e.to_string()))?;
+    decrypt_with(&keyring, encrypted)
+}
+
+fn decrypt_with(keyring: &Keyring, encrypted: &str) -> Result<String, DecryptError> {
+    let plaintext = match encrypted.split_once(KEY_ID_SEPARATOR) {
+        Some((key_id, encrypted_hex)) => {
+            let key = keyring
+                .find(key_id)
+                .ok_or_else(|| DecryptError::UnknownKey(key_id.to_string()))?;
+            let data = decode_ciphertext(encrypted_hex)?;
+
+            // Versioned format first; fall back to the unversioned layout written
+            // before version bytes were introduced. A flipped version byte fails both.
+            match data.split_first() {
+                Some((&FORMAT_VERSION_AES_GCM, rest)) if rest.len() >= NONCE_LEN + TAG_LEN => {
+                    let aad = associated_data(FORMAT_VERSION_AES_GCM, key_id);
+                    open(&key.bytes, rest, &aad).or_else(|_| open(&key.bytes, &data, &[]))?
+                }
+                _ => open(&key.bytes, &data, &[])?,
+            }
+        }
+        None => {
+            let data = decode_ciphertext(encrypted)?;
+            keyring
+                .all()
+                .find_map(|key| open(&key.bytes, &data, &[]).ok())
+                .ok_or(DecryptError::Tampered)?
+        }
+    };
+
+    String::from_utf8(plaintext).map_err(|_| DecryptError::InvalidUtf8)
+}
+
+/// Hex-decode a ciphertext payload and check the minimum length
+fn decode_ciphertext(encrypted_hex: &str) -> Result<Vec<u8>, DecryptError> {
+    let data = hex::decode(encrypted_hex)
+        .map_err(|_| DecryptError::Malformed("invalid hex encoding".to_string()))?;
+
+    if data.len() < NONCE_LEN + TAG_LEN {
+        return Err(DecryptError::Malformed("encrypted data too short".to_string()));
+    }
 
-    // Decode from hex
-    let encrypted_data =
-        hex::decode(encrypted_hex).map_err(|_| anyhow!("Invalid encrypted data format"))?;
+    Ok(data)
+}
 
-    if encrypted_data.len() < 12 + 16 {
-        // nonce + minimum auth tag
-        return Err(anyhow!("Encrypted data too short"));
+/// Open `<12-byte nonce><ciphertext><16-byte auth tag>` with AES-256-GCM
+fn open(key_bytes: &[u8; 32], data: &[u8], aad: &[u8]) -> Result<Vec<u8>, DecryptError> {
+    if data.len() < NONCE_LEN + TAG_LEN {
+        return Err(DecryptError::Malformed("encrypted data too short".to_string()));
     }
 
+    let unbound_key = UnboundKey::new(&AES_256_GCM, key_bytes)
+        .map_err(|_| DecryptError::KeyUnavailable("failed to create decryption key".to_string()))?;
+    let opening_key = LessSafeKey::new(unbound_key);
+
     // Split nonce and ciphertext
-    let (nonce_bytes, ciphertext) = encrypted_data.split_at(12);
-    let nonce =
-        Nonce::try_assume_unique_for_key(nonce_bytes).map_err(|_| anyhow!("Invalid nonce"))?;
+    let (nonce_bytes, ciphertext) = data.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce_bytes)
+        .map_err(|_| DecryptError::Malformed("invalid nonce".to_string()))?;
 
-    // Decrypt
+    // Decrypt and verify the auth tag
     let mut in_out = ciphertext.to_vec();
     let plaintext = opening_key
-        .open_in_place(nonce, Aad::empty(), &mut in_out)
-        .map_err(|_| anyhow!("Decryption failed - invalid key or corrupted data"))?;
+        .open_in_place(nonce, Aad::from(aad), &mut in_out)
+        .map_err(|_| DecryptError::Tampered)?;
+
+    Ok(plaintext.to_vec())
+}
+
+/// HMAC key for signing, derived from an encryption key so the two uses never
+/// share key material directly
+fn signing_key(key: &EncryptionKey) -> hmac::Key {
+    let derived = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, &key.bytes), b"nosdesk-signing-v1");
+    hmac::Key::new(hmac::HMAC_SHA256, derived.as_ref())
+}
+
+/// The signed bytes: `context` separates purposes that might sign equal messages
+fn signing_input(context: &str, message: &str) -> Vec<u8> {
+    let mut input = Vec::with_capacity(context.len() + 1 + message.len());
+    input.extend_from_slice(context.as_bytes());
+    input.push(0);
+    input.extend_from_slice(message.as_bytes());
+    input
+}
+
+/// Sign `message` for `context` with the current key, returning a hex HMAC-SHA256
+pub fn sign(context: &str, message: &str) -> Result<String> {
+    Ok(sign_with(&get_keyring()?, context, message))
+}
+
+fn sign_with(keyring: &Keyring, context: &str, message: &str) -> String {
+    let tag = hmac::sign(&signing_key(&keyring.current), &signing_input(context, message));
+    hex::encode(tag.as_ref())
+}
+
+/// Check a signature made by [`sign`] with the current or a retired key
+pub fn verify_signature(context: &str, message: &str, signature: &str) -> bool {
+    get_keyring().is_ok_and(|keyring| verify_signature_with(&keyring, context, message, signature))
+}
+
+fn verify_signature_with(keyring: &Keyring, context: &str, message: &str, signature: &str) -> bool {
+    let Ok(tag) = hex::decode(signature) else {
+        return false;
+    };
+    let input = signing_input(context, message);
+    keyring
+        .all()
+        .any(|key| hmac::verify(&signing_key(key), &input, &tag).is_ok())
+}
+
+/// Recover a stored webhook secret, accepting plaintext secrets saved before
+/// encryption at rest
+pub fn open_secret(stored: &str) -> Result<String, DecryptError> {
+    if is_plaintext_secret(stored) {
+        return Ok(stored.to_string());
+    }
+    decrypt(stored)
+}
 
-    String::from_utf8(plaintext.to_vec()).map_err(|_| anyhow!("Invalid UTF-8 in decrypted data"))
+fn open_secret_with(keyring: &Keyring, stored: &str) -> Result<String, DecryptError> {
+    if is_plaintext_secret(stored) {
+        return Ok(stored.to_string());
+    }
+    decrypt_with(keyring, stored)
+}
+
+/// Whether a stored webhook secret predates encryption at rest
+pub fn is_plaintext_secret(stored: &str) -> bool {
+    stored.starts_with(WEBHOOK_SECRET_PREFIX)
+}
+
+/// Check whether a ciphertext was produced by a key other than the current one
+fn needs_reencryption_with(keyring: &Keyring, encrypted: &str) -> bool {
+    match encrypted.split_once(KEY_ID_SEPARATOR) {
+        Some((key_id, _)) => key_id != keyring.current.id,
+        None => true,
+    }
+}
+
+/// Summary of a [`reencrypt_all_secrets`] run
+#[derive(Debug, Default, Clone, serde::Serialize)]
+pub struct ReencryptionSummary {
+    /// Secrets re-encrypted with the current key
+    pub reencrypted: usize,
+    /// Secrets already encrypted with the current key
+    pub skipped: usize,
+    /// Secrets that could not be decrypted with any configured key
+    pub failed: usize,
+}
+
+/// Re-encrypt every stored plugin secret, webhook secret and user MFA secret
+/// with the current key
+///
+/// Run after rotating ENCRYPTION_KEY (with the old key listed in
+/// ENCRYPTION_RETIRED_KEYS). Once `failed` is zero the retired key can be removed.
+/// Webhook secrets saved as plaintext before encryption at rest are encrypted.
+pub fn reencrypt_all_secrets(conn: &mut DbConnection) -> Result<ReencryptionSummary> {
+    reencrypt_all_secrets_with(conn, &get_keyring()?)
+}
+
+fn reencrypt_all_secrets_with(conn: &mut DbConnection, keyring: &Keyring) -> Result<ReencryptionSummary> {
+    let secrets = plugin_repo::get_all_secret_data(conn)
+        .map_err(|e| anyhow!("Failed to load plugin secrets: {e}"))?;
+
+    let mut summary = ReencryptionSummary::default();
+
+    for entry in secrets {
+        let Some(encrypted) = entry.value.as_ref().and_then(|v| v.as_str()) else {
+            summary.skipped += 1;
+            continue;
+        };
+
+        if !needs_reencryption_with(keyring, encrypted) {
+            summary.skipped += 1;
+            continue;
+        }
+
+        let reencrypted = match decrypt_with(keyring, encrypted)
+            .map_err(anyhow::Error::from)
+            .and_then(|plaintext| encrypt_with(keyring, &plaintext))
+        {
+            Ok(value) => value,
+            Err(e) => {
+                warn!(plugin_id = entry.plugin_id, key = %entry.key, error = %e, "Failed to re-encrypt plugin secret");
+                summary.failed += 1;
+                continue;
+            }
+        };
+
+        plugin_repo::update_plugin_data_value(
+            conn,
+            entry.id,
+            Some(serde_json::Value::String(reencrypted)),
+        )
+        .map_err(|e| anyhow!("Failed to store re-encrypted plugin secret: {e}"))?;
+        summary.reencrypted += 1;
+    }
+
+    let webhooks = webhook_repo::list_all_webhooks(conn)
+        .map_err(|e| anyhow!("Failed to load webhooks: {e}"))?;
+
+    for webhook in webhooks {
+        if !is_plaintext_secret(&webhook.secret) && !needs_reencryption_with(keyring, &webhook.secret) {
+            summary.skipped += 1;
+            continue;
+        }
+
+        let reencrypted = match open_secret_with(keyring, &webhook.secret)
+            .map_err(anyhow::Error::from)
+            .and_then(|plaintext| encrypt_with(keyring, &plaintext))
+        {
+            Ok(value) => value,
+            Err(e) => {
+                warn!(webhook = %webhook.uuid, error = %e, "Failed to re-encrypt webhook secret");
+                summary.failed += 1;
+                continue;
+            }
+        };
+
+        let update = crate::models::WebhookUpdate {
+            secret: Some(reencrypted),
+            ..Default::default()
+        };
+        webhook_repo::update_webhook_by_uuid(conn, webhook.uuid, update)
+            .map_err(|e| anyhow!("Failed to store re-encrypted webhook secret: {e}"))?;
+        summary.reencrypted += 1;
+    }
+
+    let mfa_secrets = user_repo::get_mfa_secrets(conn)
+        .map_err(|e| anyhow!("Failed to load MFA secrets: {e}"))?;
+
+    for (user_uuid, encrypted) in mfa_secrets {
+        if !needs_reencryption_with(keyring, &encrypted) {
+            summary.skipped += 1;
+            continue;
+        }
+
+        let reencrypted = match decrypt_with(keyring, &encrypted)
+            .map_err(anyhow::Error::from)
+            .and_then(|plaintext| encrypt_with(keyring, &plaintext))
+        {
+            Ok(value) => value,
+            Err(e) => {
+                warn!(user = %user_uuid, error = %e, "Failed to re-encrypt MFA secret");
+                summary.failed += 1;
+                continue;
+            }
+        };
+
+        user_repo::update_mfa_secret(conn, &user_uuid, &reencrypted)
+            .map_err(|e| anyhow!("Failed to store re-encrypted MFA secret: {e}"))?;
+        summary.reencrypted += 1;
+    }
+
+    info!(
+        reencrypted = summary.reencrypted,
+        skipped = summary.skipped,
+        failed = summary.failed,
+        key_id = %keyring.current.id,
+        "Secret re-encryption complete"
+    );
+
+    Ok(summary)
 }
 
 #[cfg(test)]
@@ -112,8 +538,10 @@ mod tests {
         // Encrypted should be different from original
         assert_ne!(encrypted, original);
 
-        // Should be hex-encoded
-        assert!(encrypted.chars().all(|c| c.is_ascii_hexdigit()));
+        // Should be the key id followed by hex
+        let (key_id, encrypted_hex) = encrypted.split_once(':').expect("key id prefix");
+        assert!(!key_id.is_empty());
+        assert!(encrypted_hex.chars().all(|c| c.is_ascii_hexdigit()));
 
         // Decrypt should return original
         let decrypted = decrypt(&encrypted).expect("Decryption failed");
@@ -140,4 +568,227 @@ mod tests {
             decrypt(&encrypted2).unwrap()
         );
     }
+
+    fn test_key(id: &str, byte: u8) -> EncryptionKey {
+        EncryptionKey {
+            id: id.to_string(),
+            bytes: [byte; 32],
+        }
+    }
+
+    /// Encrypt in the pre-rotation format: unprefixed hex of <nonce><ciphertext><tag>
+    fn encrypt_legacy(key: &EncryptionKey, plaintext: &str) -> String {
+        let sealing_key = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &key.bytes).unwrap());
+        let nonce_bytes = [7u8; NONCE_LEN];
+        let mut in_out = plaintext.as_bytes().to_vec();
+        sealing_key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce_bytes), Aad::empty(), &mut in_out)
+            .unwrap();
+        let mut result = nonce_bytes.to_vec();
+        result.extend_from_slice(&in_out);
+        hex::encode(result)
+    }
+
+    /// Flip one bit of the byte at `index` within the hex payload
+    fn flip_byte(encrypted: &str, index: usize) -> String {
+        let (key_id, encrypted_hex) = encrypted.split_once(':').unwrap();
+        let mut data = hex::decode(encrypted_hex).unwrap();
+        data[index] ^= 0x01;
+        format!("{key_id}:{}", hex::encode(data))
+    }
+
+    #[test]
+    fn test_decrypts_legacy_format_with_current_or_retired_key() {
+        let old = test_key("k1", 1);
+        let new = test_key("k2", 2);
+        let legacy = encrypt_legacy(&old, "legacy-secret");
+
+        let rotated = Keyring {
+            current: new.clone(),
+            retired: vec![old.clone()],
+        };
+        assert_eq!(decrypt_with(&rotated, &legacy).unwrap(), "legacy-secret");
+
+        let unrotated = Keyring {
+            current: old,
+            retired: vec![],
+        };
+        assert_eq!(decrypt_with(&unrotated, &legacy).unwrap(), "legacy-secret");
+
+        let without_old = Keyring {
+            current: new,
+            retired: vec![],
+        };
+        assert!(decrypt_with(&without_old, &legacy).is_err());
+    }
+
+    #[test]
+    fn test_decrypts_prefixed_format_by_key_id() {
+        let old = test_key("k1", 1);
+        let new = test_key("k2", 2);
+        let before = Keyring {
+            current: old.clone(),
+            retired: vec![],
+        };
+        let encrypted = encrypt_with(&before, "rotating-secret").unwrap();
+        assert!(encrypted.starts_with("k1:"));
+
+        let after = Keyring {
+            current: new,
+            retired: vec![old],
+        };
+        assert!(needs_reencryption_with(&after, &encrypted));
+        assert_eq!(decrypt_with(&after, &encrypted).unwrap(), "rotating-secret");
+
+        let reencrypted = encrypt_with(&after, "rotating-secret").unwrap();
+        assert!(reencrypted.starts_with("k2:"));
+        assert!(!needs_reencryption_with(&after, &reencrypted));
+        assert_eq!(decrypt_with(&after, &reencrypted).unwrap(), "rotating-secret");
+    }
+
+    #[test]
+    fn test_unknown_key_id_is_rejected() {
+        let keyring = Keyring {
+            current: test_key("k2", 2),
+            retired: vec![],
+        };
+        let encrypted = encrypt_with(
+            &Keyring {
+                current: test_key("k9", 9),
+                retired: vec![],
+            },
+            "secret",
+        )
+        .unwrap();
+        assert_eq!(
+            decrypt_with(&keyring, &encrypted),
+            Err(DecryptError::UnknownKey("k9".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_ciphertext_carries_version_byte() {
+        let keyring = Keyring {
+            current: test_key("k1", 1),
+            retired: vec![],
+        };
+        let encrypted = encrypt_with(&keyring, "secret").unwrap();
+        let data = hex::decode(encrypted.split_once(':').unwrap().1).unwrap();
+        assert_eq!(data[0], FORMAT_VERSION_AES_GCM);
+        assert_eq!(data.len(), 1 + NONCE_LEN + "secret".len() + TAG_LEN);
+    }
+
+    #[test]
+    fn test_flipped_ciphertext_byte_is_tampered() {
+        let keyring = Keyring {
+            current: test_key("k1", 1),
+            retired: vec![],
+        };
+        let encrypted = encrypt_with(&keyring, "api-token-value").unwrap();
+        let data_len = hex::decode(encrypted.split_once(':').unwrap().1).unwrap().len();
+
+        // Version byte, nonce, ciphertext body and auth tag must all be covered
+        for index in [0, 1, NONCE_LEN + 1, data_len - 1] {
+            let tampered = flip_byte(&encrypted, index);
+            assert_eq!(decrypt_with(&keyring, &tampered), Err(DecryptError::Tampered));
+        }
+    }
+
+    #[test]
+    fn test_swapped_key_id_is_tampered() {
+        // Same key material under a different id must not verify (key id is bound as AAD)
+        let keyring = Keyring {
+            current: test_key("k1", 1),
+            retired: vec![test_key("k0", 1)],
+        };
+        let encrypted = encrypt_with(&keyring, "secret").unwrap();
+        let relabelled = encrypted.replacen("k1:", "k0:", 1);
+        assert_eq!(decrypt_with(&keyring, &relabelled), Err(DecryptError::Tampered));
+    }
+
+    #[test]
+    fn test_flipped_legacy_byte_is_tampered() {
+        let key = test_key("k1", 1);
+        let mut data = hex::decode(encrypt_legacy(&key, "legacy-secret")).unwrap();
+        data[NONCE_LEN] ^= 0x01;
+        let keyring = Keyring {
+            current: key,
+            retired: vec![],
+        };
+        assert_eq!(decrypt_with(&keyring, &hex::encode(data)), Err(DecryptError::Tampered));
+    }
+
+    #[test]
+    fn test_malformed_ciphertext() {
+        let keyring = Keyring {
+            current: test_key("k1", 1),
+            retired: vec![],
+        };
+        assert!(matches!(decrypt_with(&keyring, "k1:zz"), Err(DecryptError::Malformed(_))));
+        assert!(matches!(decrypt_with(&keyring, "k1:00ff"), Err(DecryptError::Malformed(_))));
+    }
+
+    #[test]
+    fn test_parse_retired_keys() {
+        let key_hex = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+        let keys = parse_retired_keys(&format!("k0:{key_hex}, old-1:{key_hex}")).unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[1].id, "old-1");
+
+        assert!(parse_retired_keys(key_hex).is_err());
+        assert!(parse_retired_keys("k0:not-hex").is_err());
+        assert!(parse_retired_keys(&format!("bad id:{key_hex}")).is_err());
+        assert!(parse_retired_keys("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rotation_reencrypts_user_mfa_secrets() {
+        use crate::models::UserRole;
+        use crate::schema::users;
+        use crate::test_helpers::{setup_test_connection, TestFixtures};
+        use diesel::prelude::*;
+
+        let old = test_key("k1", 1);
+        let new = test_key("k2", 2);
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "rotatedmfauser", UserRole::User);
+        let before = Keyring { current: old.clone(), retired: vec![] };
+        diesel::update(users::table.find(user.uuid))
+            .set(users::mfa_secret.eq(Some(encrypt_with(&before, "JBSWY3DPEHPK3PXP").unwrap())))
+            .execute(&mut conn)
+            .unwrap();
+
+        let after = Keyring { current: new.clone(), retired: vec![old] };
+        let summary = reencrypt_all_secrets_with(&mut conn, &after).unwrap();
+        assert!(summary.reencrypted >= 1);
+
+        let stored: Option<String> = users::table
+            .find(user.uuid)
+            .select(users::mfa_secret)
+            .first(&mut conn)
+            .unwrap();
+        let stored = stored.unwrap();
+        assert!(stored.starts_with("k2:"));
+        // Still readable once the retired key is dropped
+        let dropped = Keyring { current: new, retired: vec![] };
+        assert_eq!(decrypt_with(&dropped, &stored).unwrap(), "JBSWY3DPEHPK3PXP");
+    }
+
+    #[test]
+    fn test_signatures_verify_across_rotation_and_reject_tampering() {
+        let old = test_key("k1", 1);
+        let new = test_key("k2", 2);
+        let before = Keyring { current: old.clone(), retired: vec![] };
+        let after = Keyring { current: new.clone(), retired: vec![old] };
+
+        let signature = sign_with(&before, "ticket-status", "42.1700000000");
+        assert!(verify_signature_with(&before, "ticket-status", "42.1700000000", &signature));
+        assert!(verify_signature_with(&after, "ticket-status", "42.1700000000", &signature));
+
+        assert!(!verify_signature_with(&before, "ticket-status", "43.1700000000", &signature));
+        assert!(!verify_signature_with(&before, "other-purpose", "42.1700000000", &signature));
+        assert!(!verify_signature_with(&before, "ticket-status", "42.1700000000", "not-hex"));
+        let dropped = Keyring { current: new, retired: vec![] };
+        assert!(!verify_signature_with(&dropped, "ticket-status", "42.1700000000", &signature));
+    }
 }
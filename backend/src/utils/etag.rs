@@ -0,0 +1,122 @@
+//! ETag / conditional GET helpers
+//!
+//! List endpoints polled by the frontend hash their serialized response into a
+//! weak ETag and answer `304 Not Modified` when the client already has it.
+
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse};
+use ring::digest;
+use serde::Serialize;
+use tracing::error;
+
+/// Compute a weak ETag (`W/"…"`) from serialized bytes
+pub fn weak_etag(bytes: &[u8]) -> String {
+    let hash = digest::digest(&digest::SHA256, bytes);
+    format!("W/\"{}\"", hex::encode(&hash.as_ref()[..16]))
+}
+
+/// Check whether the request's If-None-Match header matches an ETag
+///
+/// Uses weak comparison (the `W/` prefix is ignored) and honours `*` and
+/// comma separated lists.
+pub fn if_none_match(req: &HttpRequest, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let expected = opaque(etag);
+
+    req.headers()
+        .get_all(header::IF_NONE_MATCH)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque(tag) == expected)
+}
+
+/// Respond with JSON plus a weak ETag, or `304 Not Modified` if unchanged
+pub fn json_with_etag<T: Serialize>(req: &HttpRequest, value: &T) -> HttpResponse {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => body,
+        Err(e) => {
+            error!("Failed to serialize response: {}", e);
+            return HttpResponse::InternalServerError().json("Failed to serialize response");
+        }
+    };
+
+    let etag = weak_etag(&body);
+
+    // Clients must revalidate, but can reuse their copy on a 304
+    if if_none_match(req, &etag) {
+        return HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .insert_header((header::CACHE_CONTROL, "private, no-cache"))
+            .finish();
+    }
+
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header((header::ETAG, etag))
+        .insert_header((header::CACHE_CONTROL, "private, no-cache"))
+        .body(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::test::TestRequest;
+    use serde_json::json;
+
+    fn etag_of(resp: &HttpResponse) -> String {
+        resp.headers()
+            .get(header::ETAG)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn unchanged_data_returns_not_modified() {
+        let data = json!([{ "id": 1, "is_read": false }]);
+
+        let first = json_with_etag(&TestRequest::default().to_http_request(), &data);
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = etag_of(&first);
+        assert!(etag.starts_with("W/\""));
+
+        let req = TestRequest::default()
+            .insert_header((header::IF_NONE_MATCH, etag.as_str()))
+            .to_http_request();
+        let second = json_with_etag(&req, &data);
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(etag_of(&second), etag);
+    }
+
+    #[test]
+    fn changed_data_returns_new_etag() {
+        let before = json!([{ "id": 1, "is_read": false }]);
+        let after = json!([{ "id": 1, "is_read": true }]);
+
+        let etag = etag_of(&json_with_etag(&TestRequest::default().to_http_request(), &before));
+
+        let req = TestRequest::default()
+            .insert_header((header::IF_NONE_MATCH, etag.as_str()))
+            .to_http_request();
+        let resp = json_with_etag(&req, &after);
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_ne!(etag_of(&resp), etag);
+    }
+
+    #[test]
+    fn if_none_match_handles_lists_and_strong_tags() {
+        let etag = weak_etag(b"payload");
+        let strong = etag.trim_start_matches("W/").to_string();
+        let req = TestRequest::default()
+            .insert_header((header::IF_NONE_MATCH, format!("\"other\", {strong}")))
+            .to_http_request();
+        assert!(if_none_match(&req, &etag));
+
+        let req = TestRequest::default()
+            .insert_header((header::IF_NONE_MATCH, "\"other\""))
+            .to_http_request();
+        assert!(!if_none_match(&req, &etag));
+    }
+}
@@ -1,4 +1,8 @@
 use std::path::Path;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tracing::{debug, info, warn};
 
 /// Maximum file size in bytes (configurable via environment)
 /// Default: 50MB matches MAX_FILE_SIZE_MB in main.rs
@@ -55,19 +59,33 @@ pub enum FileValidationError {
     BlockedMimeType { detected: String },
     BlockedExtension { extension: String },
     InvalidFilename(String),
+    MalwareDetected { signature: String },
+    ScanFailed(String),
+    MimeMismatch { declared: String, detected: String },
+    NotAllowed { context: &'static str, value: String },
 }
 
 impl std::fmt::Display for FileValidationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::FileTooLarge { size, max_size } => {
-                write!(
-                    f,
-                    "File too large: {} bytes exceeds maximum of {} bytes ({} MB)",
-                    size,
-                    max_size,
-                    max_size / (1024 * 1024)
-                )
+                if *max_size >= 1024 * 1024 {
+                    write!(
+                        f,
+                        "File too large: {} bytes exceeds maximum of {} bytes ({} MB)",
+                        size,
+                        max_size,
+                        max_size / (1024 * 1024)
+                    )
+                } else {
+                    write!(
+                        f,
+                        "File too large: {} bytes exceeds maximum of {} bytes ({} KB)",
+                        size,
+                        max_size,
+                        max_size / 1024
+                    )
+                }
             }
             Self::BlockedMimeType { detected } => {
                 write!(
@@ -82,6 +100,19 @@ impl std::fmt::Display for FileValidationError {
                 )
             }
             Self::InvalidFilename(msg) => write!(f, "Invalid filename: {msg}"),
+            Self::MalwareDetected { signature } => {
+                write!(f, "File rejected: malware detected ({signature})")
+            }
+            Self::ScanFailed(msg) => write!(f, "Malware scan failed: {msg}"),
+            Self::MimeMismatch { declared, detected } => {
+                write!(
+                    f,
+                    "File content ({detected}) does not match its declared type ({declared})"
+                )
+            }
+            Self::NotAllowed { context, value } => {
+                write!(f, "'{value}' is not an allowed file type for {context} uploads")
+            }
         }
     }
 }
@@ -104,6 +135,18 @@ impl From<FileValidationError> for actix_web::Error {
             FileValidationError::InvalidFilename(_) => {
                 actix_web::error::ErrorBadRequest(error.to_string())
             }
+            FileValidationError::MalwareDetected { .. } => {
+                actix_web::error::ErrorBadRequest(error.to_string())
+            }
+            FileValidationError::ScanFailed(_) => {
+                actix_web::error::ErrorServiceUnavailable(error.to_string())
+            }
+            FileValidationError::MimeMismatch { .. } => {
+                actix_web::error::ErrorBadRequest(error.to_string())
+            }
+            FileValidationError::NotAllowed { .. } => {
+                actix_web::error::ErrorBadRequest(error.to_string())
+            }
         }
     }
 }
@@ -252,6 +295,443 @@ impl FileValidator {
 
 }
 
+// ============================================================================
+// Per-context upload policy
+// ============================================================================
+
+/// Named upload contexts, each with its own size/type policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadContext {
+    /// Ticket/comment file attachments
+    Attachment,
+    /// Images pasted into ticket notes
+    NoteImage,
+    /// User avatar and banner images
+    Avatar,
+    /// Plugin JavaScript bundles
+    PluginBundle,
+    /// Plugin zip packages
+    PluginZip,
+}
+
+impl UploadContext {
+    /// Human readable name used in error messages
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Attachment => "attachment",
+            Self::NoteImage => "note image",
+            Self::Avatar => "avatar",
+            Self::PluginBundle => "plugin bundle",
+            Self::PluginZip => "plugin zip",
+        }
+    }
+
+    /// Prefix for the environment variables that override this context's policy
+    fn env_prefix(&self) -> &'static str {
+        match self {
+            Self::Attachment => "UPLOAD_ATTACHMENT",
+            Self::NoteImage => "UPLOAD_NOTE_IMAGE",
+            Self::Avatar => "UPLOAD_AVATAR",
+            Self::PluginBundle => "UPLOAD_PLUGIN_BUNDLE",
+            Self::PluginZip => "UPLOAD_PLUGIN_ZIP",
+        }
+    }
+
+    /// Built-in policy used when nothing is configured
+    pub fn default_policy(&self) -> UploadPolicy {
+        match self {
+            // Attachments keep the blocklist-only behaviour and the global size limit
+            Self::Attachment => UploadPolicy::new(*self, get_max_file_size(), &[], &[]),
+            Self::NoteImage => UploadPolicy::new(*self, 10 * 1024 * 1024, &["image/*"], &[]),
+            Self::Avatar => UploadPolicy::new(
+                *self,
+                10 * 1024 * 1024,
+                &["image/jpeg", "image/png", "image/gif", "image/webp"],
+                &[],
+            ),
+            Self::PluginBundle => UploadPolicy::new(
+                *self,
+                500 * 1024,
+                &["application/javascript", "text/javascript", "application/octet-stream"],
+                &["js", "mjs"],
+            ),
+            Self::PluginZip => UploadPolicy::new(
+                *self,
+                2 * 1024 * 1024,
+                &["application/zip", "application/x-zip-compressed", "application/octet-stream"],
+                &["zip"],
+            ),
+        }
+    }
+
+    /// Effective policy: the defaults with any environment overrides applied
+    ///
+    /// `UPLOAD_<CONTEXT>_MAX_SIZE_KB`, `UPLOAD_<CONTEXT>_ALLOWED_MIMES` and
+    /// `UPLOAD_<CONTEXT>_ALLOWED_EXTENSIONS` (comma separated) are read, e.g.
+    /// `UPLOAD_PLUGIN_ZIP_MAX_SIZE_KB=4096`.
+    pub fn policy(&self) -> UploadPolicy {
+        let prefix = self.env_prefix();
+        let mut policy = self.default_policy();
+
+        if let Some(kb) = std::env::var(format!("{prefix}_MAX_SIZE_KB"))
+            .ok()
+            .and_then(|s| s.trim().parse::<usize>().ok())
+        {
+            policy.max_size = kb * 1024;
+        }
+        if let Ok(mimes) = std::env::var(format!("{prefix}_ALLOWED_MIMES")) {
+            policy.allowed_mimes = parse_policy_list(&mimes);
+        }
+        if let Ok(extensions) = std::env::var(format!("{prefix}_ALLOWED_EXTENSIONS")) {
+            policy.allowed_extensions = parse_policy_list(&extensions)
+                .into_iter()
+                .map(|e| e.trim_start_matches('.').to_string())
+                .collect();
+        }
+
+        policy
+    }
+}
+
+/// Split a comma separated config value into normalised entries
+fn parse_policy_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Size and type limits for one upload context
+///
+/// Empty `allowed_mimes` / `allowed_extensions` mean "anything not on the
+/// security blocklist". Entries listed explicitly are trusted even if they
+/// appear on the blocklist (plugin bundles are `.js`, for example).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadPolicy {
+    pub context: UploadContext,
+    pub max_size: usize,
+    pub allowed_mimes: Vec<String>,
+    pub allowed_extensions: Vec<String>,
+}
+
+impl UploadPolicy {
+    pub fn new(
+        context: UploadContext,
+        max_size: usize,
+        allowed_mimes: &[&str],
+        allowed_extensions: &[&str],
+    ) -> Self {
+        Self {
+            context,
+            max_size,
+            allowed_mimes: allowed_mimes.iter().map(|s| s.to_string()).collect(),
+            allowed_extensions: allowed_extensions.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Check a (possibly partial) size against the limit
+    ///
+    /// Call this as chunks arrive so oversized uploads are cut off early.
+    pub fn check_size(&self, size: usize) -> Result<(), FileValidationError> {
+        if size > self.max_size {
+            return Err(FileValidationError::FileTooLarge {
+                size,
+                max_size: self.max_size,
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether a declared MIME type is accepted (`image/*` wildcards supported)
+    pub fn allows_mime(&self, declared: Option<&str>) -> bool {
+        let mime = declared
+            .map(|d| d.split(';').next().unwrap_or(d).trim().to_lowercase())
+            .unwrap_or_default();
+
+        if self.allowed_mimes.is_empty() {
+            return !BLOCKED_MIME_TYPES.contains(&mime.as_str());
+        }
+
+        self.allowed_mimes.iter().any(|allowed| match allowed.strip_suffix("/*") {
+            Some(category) => mime_category(&mime) == category && mime.contains('/'),
+            None => *allowed == mime,
+        })
+    }
+
+    /// Whether a filename's extension is accepted
+    pub fn allows_extension(&self, filename: &str) -> bool {
+        let extension = Path::new(filename)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+
+        if self.allowed_extensions.is_empty() {
+            return extension.is_none_or(|ext| !BLOCKED_EXTENSIONS.contains(&ext.as_str()));
+        }
+
+        extension.is_some_and(|ext| self.allowed_extensions.contains(&ext))
+    }
+
+    /// Validate upload metadata against this policy
+    pub fn validate(
+        &self,
+        filename: Option<&str>,
+        declared_mime: Option<&str>,
+        size: usize,
+    ) -> Result<(), FileValidationError> {
+        self.check_size(size)?;
+
+        if let Some(name) = filename {
+            if !self.allows_extension(name) {
+                let extension = Path::new(name)
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .map(|e| format!(".{}", e.to_lowercase()))
+                    .unwrap_or_else(|| "(no extension)".to_string());
+                return Err(FileValidationError::NotAllowed {
+                    context: self.context.as_str(),
+                    value: extension,
+                });
+            }
+        }
+
+        if !self.allows_mime(declared_mime) {
+            return Err(FileValidationError::NotAllowed {
+                context: self.context.as_str(),
+                value: declared_mime.unwrap_or("(no content type)").to_string(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Content-type sniffing
+// ============================================================================
+
+/// Declared top-level types that make a claim about the content and must be
+/// backed by the bytes (a sniffed type in another category is rejected)
+const VERIFIED_MIME_CATEGORIES: &[&str] = &["image", "audio", "video", "text", "font"];
+
+/// Detect the real MIME type of a file from its magic bytes
+///
+/// Returns `None` when the content has no recognisable signature (plain text,
+/// CSV, JSON, JavaScript, ...).
+pub fn detect_true_mime(data: &[u8]) -> Option<String> {
+    infer::get(data).map(|kind| kind.mime_type().to_string())
+}
+
+/// Top-level category of a MIME type ("image/png" -> "image")
+fn mime_category(mime: &str) -> &str {
+    mime.split('/').next().unwrap_or(mime).trim()
+}
+
+/// Cross-check a client-declared Content-Type against the sniffed content
+///
+/// Rejects content whose sniffed type is blocked regardless of the label, and
+/// rejects content that claims an image/audio/video/text/font/PDF type but
+/// sniffs as something in a different category (e.g. an executable labelled
+/// `image/png`). Returns the sniffed type, if any.
+pub fn verify_declared_mime(
+    data: &[u8],
+    declared: Option<&str>,
+) -> Result<Option<String>, FileValidationError> {
+    let detected = detect_true_mime(data);
+    let declared = declared
+        .map(|d| d.split(';').next().unwrap_or(d).trim().to_lowercase())
+        .filter(|d| !d.is_empty() && d != "application/octet-stream");
+
+    if let Some(ref detected) = detected {
+        if BLOCKED_MIME_TYPES.contains(&detected.as_str()) {
+            return Err(FileValidationError::BlockedMimeType {
+                detected: detected.clone(),
+            });
+        }
+    }
+
+    let (Some(declared), Some(detected_mime)) = (declared, detected.as_deref()) else {
+        return Ok(detected);
+    };
+
+    let mismatch = if declared == "application/pdf" {
+        detected_mime != "application/pdf"
+    } else if VERIFIED_MIME_CATEGORIES.contains(&mime_category(&declared)) {
+        mime_category(&declared) != mime_category(detected_mime)
+    } else {
+        false
+    };
+
+    if mismatch {
+        return Err(FileValidationError::MimeMismatch {
+            declared,
+            detected: detected_mime.to_string(),
+        });
+    }
+
+    Ok(detected)
+}
+
+// ============================================================================
+// Malware scanning (ClamAV clamd)
+// ============================================================================
+
+/// Chunk size used when streaming data to clamd
+const CLAMD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Timeout for a complete clamd scan (connect + stream + reply)
+const CLAMD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Outcome of a malware scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanResult {
+    /// clamd scanned the data and found nothing
+    Clean,
+    /// clamd matched a signature
+    Infected { signature: String },
+    /// Scanning is not configured (CLAMD_ADDRESS unset)
+    Skipped,
+}
+
+/// Where to reach the clamd daemon
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ClamdAddress {
+    Tcp(String),
+    Unix(String),
+}
+
+/// Read the clamd address from CLAMD_ADDRESS
+///
+/// Accepts `tcp://host:port`, `unix:///path/to/clamd.sock`, a bare `host:port`,
+/// or a bare absolute socket path.
+fn get_clamd_address() -> Option<ClamdAddress> {
+    let value = std::env::var("CLAMD_ADDRESS").ok()?;
+    parse_clamd_address(&value)
+}
+
+fn parse_clamd_address(value: &str) -> Option<ClamdAddress> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    if let Some(addr) = value.strip_prefix("tcp://") {
+        Some(ClamdAddress::Tcp(addr.to_string()))
+    } else if let Some(path) = value.strip_prefix("unix://") {
+        Some(ClamdAddress::Unix(path.to_string()))
+    } else if value.starts_with('/') {
+        Some(ClamdAddress::Unix(value.to_string()))
+    } else {
+        Some(ClamdAddress::Tcp(value.to_string()))
+    }
+}
+
+/// Check whether malware scanning is configured
+pub fn is_malware_scanning_enabled() -> bool {
+    get_clamd_address().is_some()
+}
+
+/// Log the malware scanning configuration (called once at startup)
+pub fn log_malware_scanning_status() {
+    match get_clamd_address() {
+        Some(ClamdAddress::Tcp(addr)) => info!(address = %addr, "Malware scanning enabled via clamd (TCP)"),
+        Some(ClamdAddress::Unix(path)) => info!(socket = %path, "Malware scanning enabled via clamd (Unix socket)"),
+        None => warn!("CLAMD_ADDRESS not set - uploaded files will not be scanned for malware"),
+    }
+}
+
+/// Scan data for malware by streaming it to the configured clamd daemon
+///
+/// Returns `ScanResult::Skipped` when CLAMD_ADDRESS isn't configured. When it is
+/// configured but clamd can't be reached, returns `ScanFailed` so uploads fail closed.
+pub async fn scan_for_malware(data: &[u8]) -> Result<ScanResult, FileValidationError> {
+    let Some(address) = get_clamd_address() else {
+        return Ok(ScanResult::Skipped);
+    };
+
+    let scan = async {
+        match &address {
+            ClamdAddress::Tcp(addr) => {
+                let stream = tokio::net::TcpStream::connect(addr)
+                    .await
+                    .map_err(|e| FileValidationError::ScanFailed(format!("cannot connect to clamd: {e}")))?;
+                clamd_instream(stream, data).await
+            }
+            #[cfg(unix)]
+            ClamdAddress::Unix(path) => {
+                let stream = tokio::net::UnixStream::connect(path)
+                    .await
+                    .map_err(|e| FileValidationError::ScanFailed(format!("cannot connect to clamd: {e}")))?;
+                clamd_instream(stream, data).await
+            }
+            #[cfg(not(unix))]
+            ClamdAddress::Unix(_) => Err(FileValidationError::ScanFailed(
+                "Unix sockets are not supported on this platform".to_string(),
+            )),
+        }
+    };
+
+    let result = tokio::time::timeout(CLAMD_TIMEOUT, scan)
+        .await
+        .map_err(|_| FileValidationError::ScanFailed("clamd scan timed out".to_string()))??;
+
+    debug!(result = ?result, bytes = data.len(), "Malware scan complete");
+    Ok(result)
+}
+
+/// Scan data and convert an infected result into a validation error
+pub async fn ensure_not_malware(data: &[u8], filename: &str) -> Result<(), FileValidationError> {
+    match scan_for_malware(data).await? {
+        ScanResult::Infected { signature } => {
+            warn!(filename = %filename, signature = %signature, "Rejected infected upload");
+            Err(FileValidationError::MalwareDetected { signature })
+        }
+        ScanResult::Clean | ScanResult::Skipped => Ok(()),
+    }
+}
+
+/// Send data using the clamd INSTREAM protocol and parse the reply
+async fn clamd_instream<S>(mut stream: S, data: &[u8]) -> Result<ScanResult, FileValidationError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let io_err = |e: std::io::Error| FileValidationError::ScanFailed(format!("clamd I/O error: {e}"));
+
+    stream.write_all(b"zINSTREAM\0").await.map_err(io_err)?;
+    for chunk in data.chunks(CLAMD_CHUNK_SIZE) {
+        stream
+            .write_all(&(chunk.len() as u32).to_be_bytes())
+            .await
+            .map_err(io_err)?;
+        stream.write_all(chunk).await.map_err(io_err)?;
+    }
+    // Zero-length chunk terminates the stream
+    stream.write_all(&0u32.to_be_bytes()).await.map_err(io_err)?;
+    stream.flush().await.map_err(io_err)?;
+
+    let mut reply = Vec::new();
+    stream.read_to_end(&mut reply).await.map_err(io_err)?;
+
+    parse_clamd_reply(&String::from_utf8_lossy(&reply))
+}
+
+/// Parse a clamd reply such as `stream: OK` or `stream: Eicar-Signature FOUND`
+fn parse_clamd_reply(reply: &str) -> Result<ScanResult, FileValidationError> {
+    let reply = reply.trim_end_matches(['\0', '\n', '\r']).trim();
+    let body = reply.strip_prefix("stream:").map(str::trim).unwrap_or(reply);
+
+    if body == "OK" {
+        Ok(ScanResult::Clean)
+    } else if let Some(signature) = body.strip_suffix(" FOUND") {
+        Ok(ScanResult::Infected {
+            signature: signature.trim().to_string(),
+        })
+    } else {
+        Err(FileValidationError::ScanFailed(format!("unexpected clamd reply: {reply}")))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -317,4 +797,204 @@ mod tests {
         assert!(FileValidator::validate_file(plain_text, Some("config.json")).is_ok());
     }
 
+    #[test]
+    fn test_parse_clamd_address() {
+        assert_eq!(
+            parse_clamd_address("tcp://clamav:3310"),
+            Some(ClamdAddress::Tcp("clamav:3310".to_string()))
+        );
+        assert_eq!(
+            parse_clamd_address("unix:///run/clamav/clamd.ctl"),
+            Some(ClamdAddress::Unix("/run/clamav/clamd.ctl".to_string()))
+        );
+        assert_eq!(
+            parse_clamd_address("/run/clamav/clamd.ctl"),
+            Some(ClamdAddress::Unix("/run/clamav/clamd.ctl".to_string()))
+        );
+        assert_eq!(
+            parse_clamd_address("localhost:3310"),
+            Some(ClamdAddress::Tcp("localhost:3310".to_string()))
+        );
+        assert_eq!(parse_clamd_address("  "), None);
+    }
+
+    #[test]
+    fn test_parse_clamd_reply() {
+        assert_eq!(parse_clamd_reply("stream: OK\0").unwrap(), ScanResult::Clean);
+        assert_eq!(
+            parse_clamd_reply("stream: Win.Test.EICAR_HDB-1 FOUND\0").unwrap(),
+            ScanResult::Infected {
+                signature: "Win.Test.EICAR_HDB-1".to_string()
+            }
+        );
+        assert!(parse_clamd_reply("INSTREAM size limit exceeded. ERROR\0").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_clamd_instream_protocol() {
+        let (client, mut server) = tokio::io::duplex(1024);
+
+        let daemon = tokio::spawn(async move {
+            let mut command = [0u8; 10];
+            server.read_exact(&mut command).await.unwrap();
+            assert_eq!(&command, b"zINSTREAM\0");
+
+            let mut received = Vec::new();
+            loop {
+                let mut len = [0u8; 4];
+                server.read_exact(&mut len).await.unwrap();
+                let len = u32::from_be_bytes(len) as usize;
+                if len == 0 {
+                    break;
+                }
+                let mut chunk = vec![0u8; len];
+                server.read_exact(&mut chunk).await.unwrap();
+                received.extend_from_slice(&chunk);
+            }
+            server.write_all(b"stream: OK\0").await.unwrap();
+            received
+        });
+
+        let result = clamd_instream(client, b"hello clamd").await.unwrap();
+        assert_eq!(result, ScanResult::Clean);
+        assert_eq!(daemon.await.unwrap(), b"hello clamd");
+    }
+
+    /// Requires a running clamd: `CLAMD_ADDRESS=tcp://localhost:3310 cargo test --features clamav-tests`
+    #[cfg(feature = "clamav-tests")]
+    #[tokio::test]
+    async fn test_eicar_is_detected_by_clamd() {
+        assert!(is_malware_scanning_enabled(), "CLAMD_ADDRESS must be set");
+
+        let eicar = br"X5O!P%@AP[4\PZX54(P^)7CC)7}$EICAR-STANDARD-ANTIVIRUS-TEST-FILE!$H+H*";
+        assert!(matches!(
+            scan_for_malware(eicar).await.unwrap(),
+            ScanResult::Infected { .. }
+        ));
+        assert_eq!(scan_for_malware(b"harmless text").await.unwrap(), ScanResult::Clean);
+    }
+
+    const PNG_BYTES: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\0\x01\0\0\0\x01\x08\x06\0\0\0";
+    const EXE_BYTES: &[u8] = b"MZ\x90\0\x03\0\0\0\x04\0\0\0\xff\xff\0\0";
+    const ZIP_BYTES: &[u8] = b"PK\x03\x04\x14\0\0\0\x08\0";
+
+    #[test]
+    fn test_detect_true_mime() {
+        assert_eq!(detect_true_mime(PNG_BYTES).as_deref(), Some("image/png"));
+        assert_eq!(detect_true_mime(ZIP_BYTES).as_deref(), Some("application/zip"));
+        assert!(detect_true_mime(b"export default {}").is_none());
+    }
+
+    #[test]
+    fn test_png_matches_declared_type() {
+        assert_eq!(
+            verify_declared_mime(PNG_BYTES, Some("image/png")).unwrap().as_deref(),
+            Some("image/png")
+        );
+        // Same category with a different subtype is tolerated (e.g. mislabelled jpg/png)
+        assert!(verify_declared_mime(PNG_BYTES, Some("image/jpeg")).is_ok());
+    }
+
+    #[test]
+    fn test_disguised_executable_is_rejected() {
+        assert!(matches!(
+            verify_declared_mime(EXE_BYTES, Some("image/png")),
+            Err(FileValidationError::BlockedMimeType { .. })
+        ));
+        assert!(verify_declared_mime(EXE_BYTES, None).is_err());
+    }
+
+    #[test]
+    fn test_zip_declared_type_handling() {
+        assert!(verify_declared_mime(ZIP_BYTES, Some("application/zip")).is_ok());
+        assert!(verify_declared_mime(ZIP_BYTES, Some("application/octet-stream")).is_ok());
+        assert!(matches!(
+            verify_declared_mime(ZIP_BYTES, Some("image/png")),
+            Err(FileValidationError::MimeMismatch { .. })
+        ));
+        assert!(matches!(
+            verify_declared_mime(ZIP_BYTES, Some("application/pdf")),
+            Err(FileValidationError::MimeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unrecognised_content_passes_through() {
+        assert_eq!(verify_declared_mime(b"plain text", Some("text/plain")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_attachment_policy() {
+        let policy = UploadContext::Attachment.default_policy();
+        assert!(policy.validate(Some("report.pdf"), Some("application/pdf"), 1024).is_ok());
+        assert!(policy.validate(Some("data.csv"), None, 1024).is_ok());
+        assert!(matches!(
+            policy.validate(Some("setup.exe"), Some("application/octet-stream"), 1024),
+            Err(FileValidationError::NotAllowed { .. })
+        ));
+        assert!(policy
+            .validate(Some("x.bin"), Some("application/x-msdownload"), 1024)
+            .is_err());
+        assert!(matches!(
+            policy.validate(Some("big.pdf"), None, policy.max_size + 1),
+            Err(FileValidationError::FileTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_note_image_policy() {
+        let policy = UploadContext::NoteImage.default_policy();
+        assert!(policy.validate(Some("paste.png"), Some("image/png"), 2048).is_ok());
+        assert!(policy.validate(Some("paste.heic"), Some("image/heic"), 2048).is_ok());
+        assert!(policy.validate(Some("doc.pdf"), Some("application/pdf"), 2048).is_err());
+        assert!(policy.validate(Some("paste.png"), Some("image/png"), 11 * 1024 * 1024).is_err());
+    }
+
+    #[test]
+    fn test_avatar_policy() {
+        let policy = UploadContext::Avatar.default_policy();
+        assert!(policy.validate(None, Some("image/webp"), 0).is_ok());
+        assert!(policy.validate(None, Some("image/jpeg; charset=binary"), 0).is_ok());
+        assert!(policy.validate(None, Some("image/svg+xml"), 0).is_err());
+        assert!(policy.validate(None, Some("application/octet-stream"), 0).is_err());
+        assert!(policy.validate(None, None, 0).is_err());
+    }
+
+    #[test]
+    fn test_plugin_bundle_policy() {
+        let policy = UploadContext::PluginBundle.default_policy();
+        // .js is on the global blocklist but explicitly allowed for bundles
+        assert!(policy
+            .validate(Some("bundle.js"), Some("application/javascript"), 400 * 1024)
+            .is_ok());
+        assert!(policy.validate(None, Some("text/javascript"), 10).is_ok());
+        assert!(policy.validate(Some("bundle.zip"), Some("application/javascript"), 10).is_err());
+        assert!(policy.validate(Some("bundle.js"), Some("text/html"), 10).is_err());
+        assert!(policy.validate(Some("bundle.js"), None, 10).is_err());
+        assert!(matches!(
+            policy.validate(Some("bundle.js"), Some("text/javascript"), 501 * 1024),
+            Err(FileValidationError::FileTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_plugin_zip_policy() {
+        let policy = UploadContext::PluginZip.default_policy();
+        assert!(policy.validate(Some("plugin.zip"), Some("application/zip"), 1024).is_ok());
+        assert!(policy
+            .validate(Some("PLUGIN.ZIP"), Some("application/x-zip-compressed"), 1024)
+            .is_ok());
+        assert!(policy.validate(Some("plugin.tar"), Some("application/zip"), 1024).is_err());
+        assert!(policy.validate(Some("plugin"), Some("application/zip"), 1024).is_err());
+        assert!(policy.validate(Some("plugin.zip"), Some("text/plain"), 1024).is_err());
+        assert!(policy.validate(Some("plugin.zip"), None, 3 * 1024 * 1024).is_err());
+    }
+
+    #[test]
+    fn test_policy_list_parsing() {
+        assert_eq!(
+            parse_policy_list(" Image/PNG, ,image/jpeg ,"),
+            vec!["image/png".to_string(), "image/jpeg".to_string()]
+        );
+    }
 }
@@ -2,7 +2,7 @@ use actix_web::{HttpResponse, Error as ActixError};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation, errors::ErrorKind};
 use serde_json::json;
 use std::time::{SystemTime, UNIX_EPOCH};
-// Removed unused import: use uuid::Uuid;
+use uuid::Uuid;
 
 use crate::db::DbConnection;
 use crate::models::{Claims, User};
@@ -15,6 +15,9 @@ lazy_static::lazy_static! {
         std::env::var("JWT_SECRET").expect("JWT_SECRET environment variable must be set");
 }
 
+/// Lifetime of an impersonation token (30 minutes)
+pub const IMPERSONATION_TTL_SECONDS: usize = 30 * 60;
+
 /// JWT token creation and validation utilities
 pub struct JwtUtils;
 
@@ -44,6 +47,34 @@ impl JwtUtils {
             scope: scope.to_string(),
             exp: now + expiry_seconds,
             iat: now,
+            impersonator: None,
+        };
+
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+        )
+        .map_err(JwtError::EncodingError)
+    }
+
+    /// Create a short-lived token that lets an admin act as `user`
+    /// The real admin's UUID travels in the `impersonator` claim.
+    pub fn create_impersonation_token(user: &User, admin_uuid: &Uuid) -> Result<String, JwtError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| JwtError::SystemTime)?
+            .as_secs() as usize;
+
+        let claims = Claims {
+            sub: uuid_to_string(&user.uuid),
+            name: user.name.clone(),
+            email: String::new(),
+            role: role_to_string(&user.role),
+            scope: "full".to_string(),
+            exp: now + IMPERSONATION_TTL_SECONDS,
+            iat: now,
+            impersonator: Some(uuid_to_string(admin_uuid)),
         };
 
         encode(
@@ -71,6 +102,7 @@ impl JwtUtils {
             scope: "sse".to_string(), // SSE-specific scope
             exp: now + 3600, // 1 hour from now (in seconds)
             iat: now,
+            impersonator: None,
         };
 
         encode(
@@ -111,6 +143,9 @@ impl JwtUtils {
         // Get user from database to ensure they still exist and are active
         let user = repository::get_user_by_uuid(&user_uuid, conn)
             .map_err(|_| JwtError::UserNotFound)?;
+        if user.deactivated_at.is_some() {
+            return Err(JwtError::UserNotFound);
+        }
 
         // Verify role hasn't changed since token was issued
         let current_role = role_to_string(&user.role);
@@ -121,6 +156,19 @@ impl JwtUtils {
             });
         }
 
+        // An impersonation token is only valid while the real admin still is one
+        if let Some(ref impersonator) = claims.impersonator {
+            let admin_uuid = parse_uuid(impersonator).map_err(|_| JwtError::InvalidUserUuid)?;
+            let admin = repository::get_user_by_uuid(&admin_uuid, conn)
+                .map_err(|_| JwtError::UserNotFound)?;
+            if admin.role != crate::models::UserRole::Admin {
+                return Err(JwtError::InsufficientPermissions {
+                    required: "admin".to_string(),
+                    actual: role_to_string(&admin.role),
+                });
+            }
+        }
+
         // Skip session validation for SSE tokens (they're short-lived and not stored in active_sessions)
         // SSE tokens are identified by having "SSE_TOKEN" in the name field
         let is_sse_token = claims.name == "SSE_TOKEN";
@@ -382,6 +430,7 @@ pub mod helpers {
             token_hash: refresh_token_hash,
             user_uuid: user.uuid,
             expires_at: refresh_expires,
+            session_id: None,
         };
 
         if let Err(e) = crate::repository::refresh_tokens::create_refresh_token(conn, new_refresh_token) {
@@ -474,6 +523,7 @@ pub mod helpers {
             token_hash: refresh_token_hash,
             user_uuid: user.uuid,
             expires_at: refresh_expires,
+            session_id: None,
         };
 
         if let Err(e) = crate::repository::refresh_tokens::create_refresh_token(conn, new_refresh_token) {
@@ -572,6 +622,9 @@ mod tests {
             mfa_enabled: false,
             mfa_backup_codes: None,
             passkey_credentials: None,
+            locale: None,
+            deactivated_at: None,
+            timezone: None,
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: chrono::Utc::now().naive_utc(),
             password_changed_at: None,
@@ -585,6 +638,48 @@ mod tests {
         assert_eq!(claims.scope, "full");
     }
 
+    #[test]
+    fn impersonation_token_carries_both_identities() {
+        unsafe { std::env::set_var("JWT_SECRET", "test-secret-key-for-testing-only"); }
+        let _ = &*JWT_SECRET;
+
+        let admin_uuid = uuid::Uuid::new_v4();
+        let user = crate::models::User {
+            uuid: uuid::Uuid::new_v4(),
+            name: "Impersonated User".to_string(),
+            role: crate::models::UserRole::User,
+            pronouns: None,
+            avatar_url: None,
+            banner_url: None,
+            avatar_thumb: None,
+            theme: None,
+            microsoft_uuid: None,
+            mfa_secret: None,
+            mfa_enabled: false,
+            mfa_backup_codes: None,
+            passkey_credentials: None,
+            locale: None,
+            deactivated_at: None,
+            timezone: None,
+            created_at: chrono::Utc::now().naive_utc(),
+            updated_at: chrono::Utc::now().naive_utc(),
+            password_changed_at: None,
+        };
+
+        let token = JwtUtils::create_impersonation_token(&user, &admin_uuid).expect("Failed to create token");
+        let claims = JwtUtils::validate_token(&token).expect("Failed to validate token");
+
+        assert_eq!(claims.sub, user.uuid.to_string());
+        assert_eq!(claims.role, "user");
+        assert_eq!(claims.impersonator, Some(admin_uuid.to_string()));
+        assert!(claims.is_impersonating());
+        assert!(claims.exp - claims.iat <= IMPERSONATION_TTL_SECONDS);
+
+        // Regular tokens don't carry the claim at all
+        let regular = JwtUtils::validate_token(&JwtUtils::create_token(&user).unwrap()).unwrap();
+        assert!(!regular.is_impersonating());
+    }
+
     #[test]
     fn create_sse_token_has_sse_scope() {
         unsafe { std::env::set_var("JWT_SECRET", "test-secret-key-for-testing-only"); }
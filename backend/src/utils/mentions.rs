@@ -0,0 +1,108 @@
+//! @mention parsing for comment content
+//!
+//! Comments store mentions as `@[Display Name](user-uuid)`, the format
+//! inserted by the frontend's MentionInput.
+
+use diesel::prelude::*;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use uuid::Uuid;
+
+use crate::db::DbConnection;
+
+static MENTION_UUID_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"@\[[^\]]+\]\(([0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12})\)").unwrap()
+});
+
+/// UUIDs mentioned in `content`, in order of first appearance, without duplicates
+pub fn extract_mentions(content: &str) -> Vec<Uuid> {
+    let mut mentions: Vec<Uuid> = Vec::new();
+    for uuid in MENTION_UUID_RE
+        .captures_iter(content)
+        .filter_map(|cap| Uuid::parse_str(&cap[1]).ok())
+    {
+        if !mentions.contains(&uuid) {
+            mentions.push(uuid);
+        }
+    }
+    mentions
+}
+
+/// Keep only mentions that refer to existing users, preserving order
+pub fn resolve_mentions(conn: &mut DbConnection, mentions: &[Uuid]) -> QueryResult<Vec<Uuid>> {
+    use crate::schema::users;
+
+    if mentions.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let existing: Vec<Uuid> = users::table
+        .filter(users::uuid.eq_any(mentions))
+        .select(users::uuid)
+        .load(conn)?;
+
+    Ok(mentions.iter().copied().filter(|uuid| existing.contains(uuid)).collect())
+}
+
+/// Users to send a mention notification to: everyone mentioned except the
+/// author and anyone already being notified about the comment another way
+pub fn mention_recipients(mentions: &[Uuid], author: Uuid, already_notified: &[Uuid]) -> Vec<Uuid> {
+    mentions
+        .iter()
+        .copied()
+        .filter(|uuid| *uuid != author && !already_notified.contains(uuid))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{setup_test_connection, TestFixtures};
+
+    const ALICE: &str = "0b6f5a2e-1c1d-4c8e-9a61-3f2d6f1e7a01";
+    const BOB: &str = "7d3c2b1a-9e8f-4a6b-8c5d-1e2f3a4b5c02";
+
+    #[test]
+    fn extracts_mentions_in_order_without_duplicates() {
+        let content = format!(
+            "<p>@[Bob]({BOB}) can you check? cc @[Alice]({ALICE}) and @[Bob again]({BOB})</p>"
+        );
+        assert_eq!(
+            extract_mentions(&content),
+            vec![Uuid::parse_str(BOB).unwrap(), Uuid::parse_str(ALICE).unwrap()]
+        );
+    }
+
+    #[test]
+    fn ignores_malformed_mentions() {
+        let content = format!(
+            "email me@example.com, @[NoUuid](not-a-uuid), @Alice, [Alice]({ALICE}), @[Upper]({})",
+            BOB.to_uppercase()
+        );
+        assert_eq!(extract_mentions(&content), vec![Uuid::parse_str(BOB).unwrap()]);
+    }
+
+    #[test]
+    fn recipients_skip_author_and_already_notified() {
+        let author = Uuid::new_v4();
+        let requester = Uuid::new_v4();
+        let mentioned = Uuid::new_v4();
+
+        assert_eq!(
+            mention_recipients(&[author, requester, mentioned], author, &[requester]),
+            vec![mentioned]
+        );
+    }
+
+    #[test]
+    fn resolve_drops_unknown_users() {
+        let mut conn = setup_test_connection();
+        let user = TestFixtures::create_user(&mut conn, "mentioneduser", UserRole::User);
+        let ghost = Uuid::new_v4();
+
+        let content = format!("@[Ghost]({ghost}) @[User]({}) @[User]({})", user.uuid, user.uuid);
+        let resolved = resolve_mentions(&mut conn, &extract_mentions(&content)).unwrap();
+        assert_eq!(resolved, vec![user.uuid]);
+    }
+}
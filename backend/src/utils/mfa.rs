@@ -466,6 +466,9 @@ mod tests {
             mfa_enabled: false,
             mfa_backup_codes: None,
             passkey_credentials: None,
+            locale: None,
+            deactivated_at: None,
+            timezone: None,
         };
 
         assert!(!user_has_mfa_enabled(&base_user));
@@ -2,6 +2,7 @@ pub mod auth;
 pub mod user;
 pub mod image;
 pub mod jwt;
+pub mod mentions;
 pub mod sse;
 pub mod mfa;
 pub mod storage;
@@ -11,11 +12,16 @@ pub mod reset_tokens;
 pub mod csrf;
 pub mod cookies;
 pub mod encryption;
+pub mod etag;
 pub mod file_validation;
 pub mod rate_limit;
 pub mod redis_yjs_cache;
 pub mod rbac;
+pub mod status_links;
+pub mod permissions;
 pub mod pdf;
+pub mod text_diff;
+pub mod timezone;
 pub mod webauthn;
 
 use uuid::Uuid;
@@ -1,9 +1,15 @@
+use chrono::{DateTime, Utc};
 use image::ImageFormat;
-use pdfium_render::prelude::{Pdfium, PdfRenderConfig, PdfPageRenderRotation};
+use pdfium_render::prelude::{
+    PdfColor, PdfDocument, PdfPageObjectCommon, PdfPageObjectsCommon, PdfPagePaperSize,
+    PdfPageRenderRotation, PdfPoints, PdfRenderConfig, Pdfium,
+};
 use std::sync::OnceLock;
 use tokio::fs;
 use tracing::{debug, error, info, warn};
 
+use crate::utils::email::EmailBranding;
+
 /// Track whether pdfium is available (checked once at startup)
 static PDFIUM_AVAILABLE: OnceLock<bool> = OnceLock::new();
 
@@ -224,3 +230,311 @@ pub async fn generate_and_store_pdf_thumbnail(
         None => Ok(None),
     }
 }
+
+// ============================================================================
+// Branded PDF generation
+// ============================================================================
+
+/// Page margin used for the branded header and footer (in points)
+const BRANDING_MARGIN: f32 = 36.0;
+/// Height reserved for the header logo (in points)
+const BRANDING_LOGO_HEIGHT: f32 = 24.0;
+/// Font size for the header company name
+const BRANDING_HEADER_FONT_SIZE: f32 = 12.0;
+/// Font size for footer text (page numbers and timestamp)
+const BRANDING_FOOTER_FONT_SIZE: f32 = 8.0;
+/// Font size for body text in rendered documents
+const BODY_FONT_SIZE: f32 = 10.0;
+/// Line height for body text in rendered documents
+const BODY_LINE_HEIGHT: f32 = 14.0;
+
+/// Letterhead stamped onto generated PDFs.
+///
+/// Built from the same site settings as [`EmailBranding`] so emails and
+/// PDFs stay visually consistent.
+#[derive(Debug, Clone)]
+pub struct PdfBranding {
+    pub company_name: String,
+    pub logo: Option<image::DynamicImage>,
+    pub primary_color: String,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl PdfBranding {
+    /// Create branding without a logo
+    pub fn new(company_name: impl Into<String>, primary_color: impl Into<String>) -> Self {
+        Self {
+            company_name: company_name.into(),
+            logo: None,
+            primary_color: primary_color.into(),
+            generated_at: Utc::now(),
+        }
+    }
+
+    /// Build PDF branding from email branding, loading the logo from local uploads
+    pub fn from_email_branding(branding: &EmailBranding) -> Self {
+        let logo = branding.logo_url.as_deref().and_then(load_branding_logo);
+        Self {
+            company_name: branding.app_name.clone(),
+            logo,
+            primary_color: branding.primary_color.clone(),
+            generated_at: Utc::now(),
+        }
+    }
+}
+
+/// Load a branding logo from its `/uploads/...` URL
+fn load_branding_logo(logo_url: &str) -> Option<image::DynamicImage> {
+    let relative = logo_url.strip_prefix("/uploads/")?;
+    // Branding images are always stored directly under uploads/branding/
+    if !relative.starts_with("branding/") || relative.contains("..") {
+        warn!(logo_url = %logo_url, "Ignoring branding logo outside the branding directory");
+        return None;
+    }
+
+    let path = format!("uploads/{relative}");
+    match image::open(&path) {
+        Ok(img) => Some(img),
+        Err(e) => {
+            // SVG logos and missing files simply render without a logo
+            debug!(path = %path, error = %e, "Branding logo could not be loaded for PDF");
+            None
+        }
+    }
+}
+
+/// Parse a `#rrggbb` color into a PdfColor, falling back to the default blue
+fn parse_pdf_color(hex: &str) -> PdfColor {
+    let parsed = hex.strip_prefix('#').filter(|h| h.len() == 6).and_then(|h| {
+        Some((
+            u8::from_str_radix(&h[0..2], 16).ok()?,
+            u8::from_str_radix(&h[2..4], 16).ok()?,
+            u8::from_str_radix(&h[4..6], 16).ok()?,
+        ))
+    });
+
+    match parsed {
+        Some((r, g, b)) => PdfColor::new(r, g, b, 255),
+        None => PdfColor::new(0x25, 0x63, 0xeb, 255),
+    }
+}
+
+/// Stamp a branded header and footer onto every page of a document
+///
+/// The header contains the logo (if available) and the company name; the
+/// footer contains "Page N of M" and the generated-on timestamp.
+pub fn apply_branding(document: &mut PdfDocument, branding: &PdfBranding) -> Result<(), String> {
+    let font = document.fonts_mut().helvetica();
+    let header_color = parse_pdf_color(&branding.primary_color);
+    let footer_color = PdfColor::new(0x6b, 0x72, 0x80, 255);
+    let generated_on = format!(
+        "Generated {}",
+        branding.generated_at.format("%Y-%m-%d %H:%M UTC")
+    );
+
+    let page_count = document.pages().len();
+
+    for (index, mut page) in document.pages().iter().enumerate() {
+        let page_width = page.width().value;
+        let page_height = page.height().value;
+        let header_y = page_height - BRANDING_MARGIN - BRANDING_LOGO_HEIGHT;
+        let mut name_x = BRANDING_MARGIN;
+
+        if let Some(ref logo) = branding.logo {
+            page.objects_mut()
+                .create_image_object(
+                    PdfPoints::new(BRANDING_MARGIN),
+                    PdfPoints::new(header_y),
+                    logo,
+                    None,
+                    Some(PdfPoints::new(BRANDING_LOGO_HEIGHT)),
+                )
+                .map_err(|e| format!("Failed to add logo to PDF header: {e}"))?;
+
+            let aspect = logo.width() as f32 / logo.height().max(1) as f32;
+            name_x += BRANDING_LOGO_HEIGHT * aspect + 8.0;
+        }
+
+        let mut header = page
+            .objects_mut()
+            .create_text_object(
+                PdfPoints::new(name_x),
+                PdfPoints::new(header_y + 6.0),
+                &branding.company_name,
+                font,
+                PdfPoints::new(BRANDING_HEADER_FONT_SIZE),
+            )
+            .map_err(|e| format!("Failed to add PDF header text: {e}"))?;
+        header
+            .set_fill_color(header_color)
+            .map_err(|e| format!("Failed to color PDF header text: {e}"))?;
+
+        let mut page_label = page
+            .objects_mut()
+            .create_text_object(
+                PdfPoints::new(page_width - BRANDING_MARGIN - 60.0),
+                PdfPoints::new(BRANDING_MARGIN / 2.0),
+                format!("Page {} of {}", index + 1, page_count),
+                font,
+                PdfPoints::new(BRANDING_FOOTER_FONT_SIZE),
+            )
+            .map_err(|e| format!("Failed to add PDF page number: {e}"))?;
+        page_label
+            .set_fill_color(footer_color)
+            .map_err(|e| format!("Failed to color PDF footer text: {e}"))?;
+
+        let mut timestamp = page
+            .objects_mut()
+            .create_text_object(
+                PdfPoints::new(BRANDING_MARGIN),
+                PdfPoints::new(BRANDING_MARGIN / 2.0),
+                &generated_on,
+                font,
+                PdfPoints::new(BRANDING_FOOTER_FONT_SIZE),
+            )
+            .map_err(|e| format!("Failed to add PDF timestamp: {e}"))?;
+        timestamp
+            .set_fill_color(footer_color)
+            .map_err(|e| format!("Failed to color PDF footer text: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Render a plain-text document (e.g. a ticket export or report) to PDF bytes
+///
+/// # Returns
+/// * `Ok(Some(bytes))` - PDF rendered successfully
+/// * `Ok(None)` - PDF rendering not available on this system
+/// * `Err(e)` - An error occurred
+pub async fn render_text_pdf(
+    title: &str,
+    body: &str,
+    branding: Option<PdfBranding>,
+) -> Result<Option<Vec<u8>>, String> {
+    if !check_pdfium_available() {
+        debug!("Pdfium not available, skipping PDF rendering");
+        return Ok(None);
+    }
+
+    let title = title.to_string();
+    let body = body.to_string();
+
+    tokio::task::spawn_blocking(move || render_text_pdf_sync(&title, &body, branding.as_ref()))
+        .await
+        .map_err(|e| format!("PDF render task panicked: {e}"))?
+}
+
+/// Synchronous text rendering (runs in blocking task)
+fn render_text_pdf_sync(
+    title: &str,
+    body: &str,
+    branding: Option<&PdfBranding>,
+) -> Result<Option<Vec<u8>>, String> {
+    let pdfium = match create_pdfium() {
+        Some(p) => p,
+        None => return Ok(None),
+    };
+
+    let mut document = pdfium
+        .create_new_pdf()
+        .map_err(|e| format!("Failed to create PDF: {e}"))?;
+    let font = document.fonts_mut().helvetica();
+    let bold = document.fonts_mut().helvetica_bold();
+
+    let paper = PdfPagePaperSize::a4();
+    let page_height = paper.height().value;
+    // Leave room for the branded header/footer even when unbranded so layouts match
+    let top = page_height - BRANDING_MARGIN * 2.0 - BRANDING_LOGO_HEIGHT;
+    let bottom = BRANDING_MARGIN * 1.5;
+
+    let mut page = document
+        .pages_mut()
+        .create_page_at_end(paper)
+        .map_err(|e| format!("Failed to add PDF page: {e}"))?;
+    page.objects_mut()
+        .create_text_object(
+            PdfPoints::new(BRANDING_MARGIN),
+            PdfPoints::new(top),
+            title,
+            bold,
+            PdfPoints::new(BRANDING_HEADER_FONT_SIZE + 4.0),
+        )
+        .map_err(|e| format!("Failed to add PDF title: {e}"))?;
+
+    let mut y = top - BODY_LINE_HEIGHT * 2.0;
+    for line in body.lines() {
+        if y < bottom {
+            page = document
+                .pages_mut()
+                .create_page_at_end(paper)
+                .map_err(|e| format!("Failed to add PDF page: {e}"))?;
+            y = top;
+        }
+
+        if !line.trim().is_empty() {
+            page.objects_mut()
+                .create_text_object(
+                    PdfPoints::new(BRANDING_MARGIN),
+                    PdfPoints::new(y),
+                    line,
+                    font,
+                    PdfPoints::new(BODY_FONT_SIZE),
+                )
+                .map_err(|e| format!("Failed to add PDF text: {e}"))?;
+        }
+        y -= BODY_LINE_HEIGHT;
+    }
+    drop(page);
+
+    if let Some(branding) = branding {
+        apply_branding(&mut document, branding)?;
+    }
+
+    let bytes = document
+        .save_to_bytes()
+        .map_err(|e| format!("Failed to serialize PDF: {e}"))?;
+
+    debug!(size_bytes = bytes.len(), "Rendered text PDF");
+    Ok(Some(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pdf_color_valid_and_fallback() {
+        let color = parse_pdf_color("#ff8000");
+        assert_eq!((color.red(), color.green(), color.blue()), (0xff, 0x80, 0x00));
+
+        let fallback = parse_pdf_color("not-a-color");
+        assert_eq!((fallback.red(), fallback.green(), fallback.blue()), (0x25, 0x63, 0xeb));
+    }
+
+    #[test]
+    fn branding_logo_outside_branding_dir_is_ignored() {
+        assert!(load_branding_logo("/uploads/tickets/1/secret.png").is_none());
+        assert!(load_branding_logo("/uploads/branding/../tickets/x.png").is_none());
+        assert!(load_branding_logo("https://example.com/logo.png").is_none());
+    }
+
+    /// Requires the pdfium shared library: `cargo test --features pdfium-tests`
+    #[cfg(feature = "pdfium-tests")]
+    #[tokio::test]
+    async fn branded_render_embeds_company_name() {
+        let branding = PdfBranding::new("Acme Helpdesk", "#2563eb");
+        let bytes = render_text_pdf("Ticket #42", "Printer is on fire", Some(branding))
+            .await
+            .expect("render failed")
+            .expect("pdfium available");
+
+        let pdfium = create_pdfium().unwrap();
+        let document = pdfium.load_pdf_from_byte_slice(&bytes, None).unwrap();
+        let page = document.pages().get(0).unwrap();
+        let text = page.text().unwrap().all();
+
+        assert!(text.contains("Acme Helpdesk"));
+        assert!(text.contains("Page 1 of 1"));
+    }
+}
@@ -0,0 +1,85 @@
+//! Granular permissions
+//!
+//! Each of the three roles carries a default bundle of permissions, so
+//! existing deployments behave exactly as before. Admins can grant extra
+//! permissions to individual users or whole groups (e.g. a "read-only
+//! auditor" group with `view_audit_log`); grants only ever add to a role's
+//! bundle. Checked per request with `rbac::require_permission`.
+
+use serde::{Deserialize, Serialize};
+
+/// A capability that can be required by a handler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ManageWebhooks,
+    ManagePlugins,
+    ManageAssignmentRules,
+    ManageSlaTargets,
+    ManagePermissions,
+    ViewAuditLog,
+    ViewReports,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 7] = [
+        Permission::ManageWebhooks,
+        Permission::ManagePlugins,
+        Permission::ManageAssignmentRules,
+        Permission::ManageSlaTargets,
+        Permission::ManagePermissions,
+        Permission::ViewAuditLog,
+        Permission::ViewReports,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Permission::ManageWebhooks => "manage_webhooks",
+            Permission::ManagePlugins => "manage_plugins",
+            Permission::ManageAssignmentRules => "manage_assignment_rules",
+            Permission::ManageSlaTargets => "manage_sla_targets",
+            Permission::ManagePermissions => "manage_permissions",
+            Permission::ViewAuditLog => "view_audit_log",
+            Permission::ViewReports => "view_reports",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.as_str() == s)
+    }
+}
+
+/// Permissions a technician has without any grants
+const TECHNICIAN_PERMISSIONS: &[Permission] = &[Permission::ViewReports];
+
+/// Default permission bundle for a role (as it appears in JWT claims)
+pub fn role_permissions(role: &str) -> &'static [Permission] {
+    match role {
+        "admin" => &Permission::ALL,
+        "technician" => TECHNICIAN_PERMISSIONS,
+        _ => &[],
+    }
+}
+
+/// Whether a role's default bundle includes `permission`
+pub fn role_has_permission(role: &str, permission: Permission) -> bool {
+    role_permissions(role).contains(&permission)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_keep_their_previous_access() {
+        assert!(role_has_permission("admin", Permission::ManageWebhooks));
+        assert!(!role_has_permission("technician", Permission::ManageWebhooks));
+        assert!(role_has_permission("technician", Permission::ViewReports));
+        assert!(!role_has_permission("user", Permission::ViewReports));
+
+        for permission in Permission::ALL {
+            assert_eq!(Permission::parse(permission.as_str()), Some(permission));
+            assert_eq!(serde_json::to_value(permission).unwrap(), permission.as_str());
+        }
+    }
+}
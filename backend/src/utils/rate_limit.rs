@@ -145,6 +145,16 @@ impl RateLimiter {
         format!("login_attempts:{}", email.to_lowercase())
     }
 
+    /// Generate a standardized rate limit key for password reset requests (by email)
+    pub fn password_reset_key(email: &str) -> String {
+        format!("password_reset_requests:{}", email.trim().to_lowercase())
+    }
+
+    /// Generate a standardized rate limit key for plugin proxy requests
+    pub fn plugin_proxy_key(plugin_name: &str) -> String {
+        format!("plugin_proxy:{plugin_name}")
+    }
+
     /// Clear all attempts for a key (used on successful login)
     pub async fn clear_attempts(redis_url: &str, key: &str) -> Result<(), RateLimitError> {
         use redis::AsyncCommands;
@@ -246,5 +256,13 @@ mod tests {
         assert_eq!(key, "mfa_attempts:550e8400-e29b-41d4-a716-446655440000");
     }
 
+    #[test]
+    fn test_password_reset_key_ignores_case_and_whitespace() {
+        assert_eq!(
+            RateLimiter::password_reset_key(" Jo@Example.com "),
+            RateLimiter::password_reset_key("jo@example.com")
+        );
+    }
+
     // Note: Integration tests requiring Redis would go in tests/ directory
 }
@@ -3,10 +3,15 @@
 //! This module provides centralised role checking functions and response helpers
 //! for implementing consistent authorization across all API handlers.
 
-use actix_web::{HttpMessage, HttpRequest, HttpResponse};
+use actix_web::http::Method;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
 use serde_json::json;
+use uuid::Uuid;
 
+use crate::db::Pool;
 use crate::models::Claims;
+use crate::repository::permission_grants;
+use crate::utils::permissions::{role_has_permission, Permission};
 
 /// Check if user has technician or admin role
 pub fn is_technician_or_admin(claims: &Claims) -> bool {
@@ -62,6 +67,165 @@ pub fn require_admin(req: &HttpRequest) -> Result<Claims, HttpResponse> {
     Ok(claims)
 }
 
+/// Extract claims and verify the user holds a permission
+/// The role's default bundle is checked first; otherwise the user's own and
+/// group grants are looked up. Returns Err(HttpResponse) with 401/403 if not held.
+pub fn require_permission(req: &HttpRequest, permission: Permission) -> Result<Claims, HttpResponse> {
+    let claims = require_auth(req)?;
+
+    if role_has_permission(&claims.role, permission) || has_granted_permission(req, &claims, permission) {
+        return Ok(claims);
+    }
+
+    Err(HttpResponse::Forbidden().json(json!({
+        "error": "Forbidden",
+        "message": format!("This action requires the '{}' permission", permission.as_str())
+    })))
+}
+
+/// Look up a grant for the requesting user; any lookup failure counts as not granted
+fn has_granted_permission(req: &HttpRequest, claims: &Claims, permission: Permission) -> bool {
+    let Ok(user_uuid) = Uuid::parse_str(&claims.sub) else {
+        return false;
+    };
+    let Some(mut conn) = req.app_data::<web::Data<Pool>>().and_then(|pool| pool.get().ok()) else {
+        return false;
+    };
+
+    permission_grants::user_has_grant(&mut conn, user_uuid, permission.as_str()).unwrap_or_else(|e| {
+        tracing::error!(error = %e, "Failed to check permission grants");
+        false
+    })
+}
+
+// =============================================================================
+// API token scopes
+// =============================================================================
+
+/// Scope granting every permission (session auth and tokens created without scopes)
+pub const FULL_SCOPE: &str = "full";
+
+/// Resources API token scopes can be granted for, as `<resource>:read` or `<resource>:write`
+pub const SCOPE_RESOURCES: &[&str] = &[
+    "tickets",
+    "comments",
+    "devices",
+    "users",
+    "projects",
+    "documentation",
+    "webhooks",
+    "plugins",
+    "notifications",
+    "audit",
+];
+
+/// Scopes granted to the current request, stored in request extensions by
+/// `dual_auth_middleware`. Session auth gets `full`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenScopes(Vec<String>);
+
+impl TokenScopes {
+    /// Unrestricted scopes (cookie sessions)
+    pub fn all() -> Self {
+        Self(vec![FULL_SCOPE.to_string()])
+    }
+
+    /// Scopes from an API token; tokens without scopes keep full access
+    pub fn new(scopes: Vec<String>) -> Self {
+        if scopes.is_empty() {
+            Self::all()
+        } else {
+            Self(scopes)
+        }
+    }
+
+    /// Check whether a scope is granted (`x:write` implies `x:read`)
+    pub fn allows(&self, scope: &str) -> bool {
+        let read_implied_by = scope
+            .strip_suffix(":read")
+            .map(|resource| format!("{resource}:write"));
+
+        self.0.iter().any(|granted| {
+            granted == FULL_SCOPE || granted == scope || Some(granted) == read_implied_by.as_ref()
+        })
+    }
+}
+
+/// Check whether a scope name is one tokens can be granted
+pub fn is_valid_scope(scope: &str) -> bool {
+    if scope == FULL_SCOPE {
+        return true;
+    }
+    match scope.split_once(':') {
+        Some((resource, action)) => {
+            SCOPE_RESOURCES.contains(&resource) && matches!(action, "read" | "write")
+        }
+        None => false,
+    }
+}
+
+/// Resource an `/api` route belongs to for token scoping, by its path prefix
+fn scope_resource(segments: &[&str]) -> Option<&'static str> {
+    match segments {
+        ["tickets", _, "comments", ..] | ["comments", ..] | ["attachments", ..] => Some("comments"),
+        ["tickets", _, "documentation", ..] => Some("documentation"),
+        ["tickets", ..]
+        | ["import", ..]
+        | ["categories", ..]
+        | ["custom-fields", ..]
+        | ["canned-responses", ..]
+        | ["reports", ..]
+        | ["uploads", "tickets", ..] => Some("tickets"),
+        ["users", _, "devices"] | ["devices", ..] => Some("devices"),
+        ["users", ..] | ["groups", ..] | ["admin", "permissions", ..] => Some("users"),
+        ["projects", ..] => Some("projects"),
+        ["documentation", ..] | ["documents", ..] => Some("documentation"),
+        ["admin", "webhooks", ..] => Some("webhooks"),
+        ["plugins", ..] | ["admin", "plugins", ..] => Some("plugins"),
+        ["notifications", ..] => Some("notifications"),
+        ["admin", "audit-log", ..] => Some("audit"),
+        _ => None,
+    }
+}
+
+/// Scope an API token needs for a request under `/api`
+/// Safe methods need `<resource>:read` (as do a few POST lookups), anything else
+/// `<resource>:write`. Routes outside every scope resource need `full`.
+pub fn required_scope(method: &Method, path: &str) -> String {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let Some(resource) = scope_resource(&segments) else {
+        return FULL_SCOPE.to_string();
+    };
+
+    let reads = method.is_safe()
+        || matches!(
+            segments.as_slice(),
+            ["tickets", "suggest-duplicates"] | ["tickets", _, "status-link"] | ["users", "batch"]
+        );
+    format!("{resource}:{}", if reads { "read" } else { "write" })
+}
+
+/// Verify the request's credentials carry a scope
+/// Returns Err(HttpResponse) with 403 if the API token lacks it. Requests without
+/// scope information (no token auth) are treated as full-scope sessions.
+pub fn require_scope(req: &HttpRequest, scope: &str) -> Result<(), HttpResponse> {
+    let allowed = req
+        .extensions()
+        .get::<TokenScopes>()
+        .is_none_or(|scopes| scopes.allows(scope));
+
+    if !allowed {
+        return Err(HttpResponse::Forbidden().json(json!({
+            "error": "Forbidden",
+            "message": format!("This API token is missing the required scope '{scope}'")
+        })));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,6 +239,7 @@ mod tests {
             scope: "full".to_string(),
             exp: 0,
             iat: 0,
+            impersonator: None,
         }
     }
 
@@ -91,4 +256,67 @@ mod tests {
         assert!(is_technician_or_admin(&create_test_claims("technician")));
         assert!(!is_technician_or_admin(&create_test_claims("user")));
     }
+
+    #[actix_web::test]
+    async fn test_require_permission_uses_role_bundle() {
+        let req = actix_web::test::TestRequest::default().to_http_request();
+        req.extensions_mut().insert(create_test_claims("admin"));
+        assert!(require_permission(&req, Permission::ManageWebhooks).is_ok());
+
+        // No pool registered, so only the role bundle applies
+        let req = actix_web::test::TestRequest::default().to_http_request();
+        req.extensions_mut().insert(create_test_claims("technician"));
+        let resp = require_permission(&req, Permission::ManageWebhooks).unwrap_err();
+        assert_eq!(resp.status(), actix_web::http::StatusCode::FORBIDDEN);
+        assert!(require_permission(&req, Permission::ViewReports).is_ok());
+    }
+
+    #[test]
+    fn test_token_scopes() {
+        let scopes = TokenScopes::new(vec!["tickets:read".to_string(), "webhooks:write".to_string()]);
+        assert!(scopes.allows("tickets:read"));
+        assert!(!scopes.allows("tickets:write"));
+        assert!(scopes.allows("webhooks:read"));
+        assert!(scopes.allows("webhooks:write"));
+        assert!(!scopes.allows("users:read"));
+
+        assert!(TokenScopes::all().allows("users:write"));
+        assert_eq!(TokenScopes::new(vec![]), TokenScopes::all());
+    }
+
+    #[test]
+    fn test_scope_validation() {
+        assert!(is_valid_scope("full"));
+        assert!(is_valid_scope("tickets:write"));
+        assert!(!is_valid_scope("tickets:admin"));
+        assert!(!is_valid_scope("billing:read"));
+        assert!(!is_valid_scope("tickets"));
+    }
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(required_scope(&Method::GET, "/api/tickets/12"), "tickets:read");
+        assert_eq!(required_scope(&Method::PUT, "/api/tickets/12"), "tickets:write");
+        assert_eq!(required_scope(&Method::POST, "/api/tickets/suggest-duplicates"), "tickets:read");
+        assert_eq!(required_scope(&Method::POST, "/api/tickets/12/comments"), "comments:write");
+        assert_eq!(required_scope(&Method::GET, "/api/users/abc/devices"), "devices:read");
+        assert_eq!(required_scope(&Method::DELETE, "/api/admin/webhooks/abc"), "webhooks:write");
+        assert_eq!(required_scope(&Method::GET, "/api/admin/audit-log"), "audit:read");
+
+        // Routes outside every scope resource are for full-scope tokens only
+        assert_eq!(required_scope(&Method::GET, "/api/admin/system/info"), FULL_SCOPE);
+        assert_eq!(required_scope(&Method::POST, "/api/search/rebuild"), FULL_SCOPE);
+    }
+
+    #[actix_web::test]
+    async fn test_require_scope() {
+        let req = actix_web::test::TestRequest::default().to_http_request();
+        assert!(require_scope(&req, "tickets:write").is_ok());
+
+        req.extensions_mut()
+            .insert(TokenScopes::new(vec!["tickets:read".to_string()]));
+        assert!(require_scope(&req, "tickets:read").is_ok());
+        let resp = require_scope(&req, "tickets:write").unwrap_err();
+        assert_eq!(resp.status(), actix_web::http::StatusCode::FORBIDDEN);
+    }
 }
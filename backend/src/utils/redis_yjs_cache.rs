@@ -3,9 +3,21 @@
 /// This module provides a caching layer for Yjs documents to survive backend restarts
 /// and prevent state vector mismatches. Documents are stored with a TTL and fall back
 /// to PostgreSQL if Redis is unavailable.
+///
+/// Each document is a snapshot plus a log of the incremental updates received since.
+/// Once the log grows past the compaction thresholds it is merged into a new snapshot
+/// (see [`compact_updates`]).
+///
+/// Editors present on a document are tracked alongside it (see [`PresenceSet`]).
 use redis::{AsyncCommands, RedisError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+use yrs::updates::decoder::Decode;
+use yrs::updates::encoder::Encode;
+use yrs::{Doc, Options, ReadTxn, StateVector, Transact, Update};
 
 /// TTL for cached documents (1 hour = 3600 seconds)
 const DOCUMENT_TTL: usize = 3600;
@@ -13,16 +25,211 @@ const DOCUMENT_TTL: usize = 3600;
 /// Redis key prefix for Yjs documents
 const KEY_PREFIX: &str = "yjs:doc";
 
+/// Redis key prefix for per-document update logs
+const LOG_KEY_PREFIX: &str = "yjs:log";
+
+/// Redis key prefix for per-document presence
+const PRESENCE_KEY_PREFIX: &str = "yjs:presence";
+
+/// Seconds an editor stays present without a heartbeat
+pub const PRESENCE_TTL_SECS: i64 = 60;
+
+/// Default number of logged updates that triggers compaction
+const DEFAULT_COMPACT_MAX_UPDATES: usize = 200;
+
+/// Default logged update size (bytes) that triggers compaction
+const DEFAULT_COMPACT_MAX_BYTES: usize = 512 * 1024;
+
+/// When a document's update log gets compacted
+#[derive(Debug, Clone, Copy)]
+pub struct CompactionConfig {
+    pub max_updates: usize,
+    pub max_bytes: usize,
+}
+
+impl CompactionConfig {
+    /// Read YJS_COMPACT_MAX_UPDATES and YJS_COMPACT_MAX_BYTES
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: usize| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+
+        Self {
+            max_updates: read("YJS_COMPACT_MAX_UPDATES", DEFAULT_COMPACT_MAX_UPDATES),
+            max_bytes: read("YJS_COMPACT_MAX_BYTES", DEFAULT_COMPACT_MAX_BYTES),
+        }
+    }
+
+    /// Whether a log of this size should be compacted
+    pub fn exceeded(&self, updates: usize, bytes: usize) -> bool {
+        updates >= self.max_updates || bytes >= self.max_bytes
+    }
+}
+
+/// A document reduced to a single update plus its state vector
+#[derive(Debug, Clone)]
+pub struct CompactedDocument {
+    pub document: Vec<u8>,
+    pub state_vector: Vec<u8>,
+}
+
+/// Merge a snapshot and the updates logged after it into a single update.
+/// The updates are applied to a document with garbage collection enabled, so
+/// deleted content is dropped rather than carried along as tombstones.
+pub fn compact_updates(snapshot: Option<&[u8]>, updates: &[Vec<u8>]) -> Result<CompactedDocument, yrs::encoding::read::Error> {
+    let doc = Doc::with_options(Options {
+        skip_gc: false,
+        ..Options::default()
+    });
+
+    for update in snapshot.into_iter().chain(updates.iter().map(Vec::as_slice)) {
+        if update.is_empty() {
+            continue;
+        }
+        let update = Update::decode_v1(update)?;
+        let mut txn = doc.transact_mut();
+        if let Err(e) = txn.apply_update(update) {
+            warn!(error = ?e, "Skipping Yjs update that failed to apply during compaction");
+        }
+    }
+
+    let txn = doc.transact();
+    Ok(CompactedDocument {
+        document: txn.encode_state_as_update_v1(&StateVector::default()),
+        state_vector: txn.state_vector().encode_v1(),
+    })
+}
+
+/// One editing session on a document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresenceEntry {
+    pub session_id: String,
+    pub user_uuid: Uuid,
+    pub name: String,
+    /// Cursor/selection from the editor's awareness state
+    pub cursor: Option<serde_json::Value>,
+    /// Unix timestamp (seconds) of the last heartbeat
+    pub last_seen: i64,
+}
+
+/// Editors present on a document, keyed by session. A user with several
+/// sessions (e.g. two tabs) joins with the first and leaves with the last.
+#[derive(Debug, Default)]
+pub struct PresenceSet {
+    entries: HashMap<String, PresenceEntry>,
+}
+
+impl PresenceSet {
+    pub fn from_entries(entries: impl IntoIterator<Item = PresenceEntry>) -> Self {
+        Self {
+            entries: entries.into_iter().map(|e| (e.session_id.clone(), e)).collect(),
+        }
+    }
+
+    fn has_user(&self, user_uuid: &Uuid) -> bool {
+        self.entries.values().any(|e| &e.user_uuid == user_uuid)
+    }
+
+    /// Record a heartbeat for a session, keeping its last cursor. Returns true
+    /// if the user wasn't present before.
+    pub fn heartbeat(&mut self, session_id: &str, user_uuid: Uuid, name: &str, now: i64) -> bool {
+        if let Some(entry) = self.entries.get_mut(session_id) {
+            entry.last_seen = now;
+            return false;
+        }
+
+        let joined = !self.has_user(&user_uuid);
+        self.entries.insert(session_id.to_string(), PresenceEntry {
+            session_id: session_id.to_string(),
+            user_uuid,
+            name: name.to_string(),
+            cursor: None,
+            last_seen: now,
+        });
+        joined
+    }
+
+    /// Update a session's cursor (counts as a heartbeat). Returns false if the
+    /// session isn't present.
+    pub fn set_cursor(&mut self, session_id: &str, cursor: serde_json::Value, now: i64) -> bool {
+        match self.entries.get_mut(session_id) {
+            Some(entry) => {
+                entry.cursor = Some(cursor);
+                entry.last_seen = now;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a session. Returns the user if that was their last session.
+    pub fn remove(&mut self, session_id: &str) -> Option<Uuid> {
+        let entry = self.entries.remove(session_id)?;
+        (!self.has_user(&entry.user_uuid)).then_some(entry.user_uuid)
+    }
+
+    /// Drop sessions whose last heartbeat is older than [`PRESENCE_TTL_SECS`].
+    /// Returns the expired session IDs and the users who left as a result.
+    pub fn expire(&mut self, now: i64) -> (Vec<String>, Vec<Uuid>) {
+        let expired: Vec<String> = self
+            .entries
+            .values()
+            .filter(|e| now - e.last_seen >= PRESENCE_TTL_SECS)
+            .map(|e| e.session_id.clone())
+            .collect();
+
+        let mut left = Vec::new();
+        for session_id in &expired {
+            if let Some(user_uuid) = self.remove(session_id) {
+                left.push(user_uuid);
+            }
+        }
+        (expired, left)
+    }
+
+    pub fn get(&self, session_id: &str) -> Option<&PresenceEntry> {
+        self.entries.get(session_id)
+    }
+
+    /// One entry per present user (their most recently active session),
+    /// ordered by name
+    pub fn editors(&self) -> Vec<PresenceEntry> {
+        let mut by_user: HashMap<Uuid, &PresenceEntry> = HashMap::new();
+        for entry in self.entries.values() {
+            let current = by_user.entry(entry.user_uuid).or_insert(entry);
+            if entry.last_seen > current.last_seen {
+                *current = entry;
+            }
+        }
+
+        let mut editors: Vec<PresenceEntry> = by_user.into_values().cloned().collect();
+        editors.sort_by(|a, b| a.name.cmp(&b.name).then(a.user_uuid.cmp(&b.user_uuid)));
+        editors
+    }
+}
+
+/// Users who joined or left a document as a result of a presence change
+#[derive(Debug, Default)]
+pub struct PresenceChanges {
+    pub joined: bool,
+    pub left: Vec<Uuid>,
+}
+
 /// Redis cache for Yjs document state
 pub struct RedisYjsCache {
     client: redis::Client,
+    compaction: CompactionConfig,
 }
 
 impl RedisYjsCache {
     /// Create a new Redis cache instance
     pub fn new(redis_url: &str) -> Result<Self, RedisError> {
         let client = redis::Client::open(redis_url)?;
-        Ok(Self { client })
+        Ok(Self { client, compaction: CompactionConfig::from_env() })
     }
 
     /// Generate Redis key for a document
@@ -30,17 +237,41 @@ impl RedisYjsCache {
         format!("{KEY_PREFIX}:{doc_id}")
     }
 
-    /// Get document state from Redis
-    /// Returns None if document not found or Redis unavailable
+    /// Generate Redis key for a document's update log
+    fn log_key(doc_id: &str) -> String {
+        format!("{LOG_KEY_PREFIX}:{doc_id}")
+    }
+
+    /// Generate Redis key for the byte size of a document's update log
+    fn log_bytes_key(doc_id: &str) -> String {
+        format!("{LOG_KEY_PREFIX}:{doc_id}:bytes")
+    }
+
+    /// Get document state from Redis, including any updates logged since the
+    /// last snapshot. Returns None if there is no snapshot or Redis is unavailable.
     pub async fn get_document(&self, doc_id: &str) -> Option<Vec<u8>> {
         let key = Self::document_key(doc_id);
 
         match self.client.get_multiplexed_async_connection().await {
             Ok(mut conn) => {
-                match conn.get::<_, Vec<u8>>(&key).await {
-                    Ok(data) => {
+                match conn.get::<_, Option<Vec<u8>>>(&key).await {
+                    Ok(Some(data)) => {
                         debug!(doc_id = %doc_id, bytes = data.len(), "Redis cache HIT for document");
-                        Some(data)
+                        let updates: Vec<Vec<u8>> = conn.lrange(Self::log_key(doc_id), 0, -1).await.unwrap_or_default();
+                        if updates.is_empty() {
+                            return Some(data);
+                        }
+                        match compact_updates(Some(&data), &updates) {
+                            Ok(merged) => Some(merged.document),
+                            Err(e) => {
+                                warn!(doc_id = %doc_id, error = ?e, "Failed to merge Redis update log - using snapshot only");
+                                Some(data)
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        debug!(doc_id = %doc_id, "Redis cache MISS for document");
+                        None
                     }
                     Err(e) => {
                         debug!(doc_id = %doc_id, error = ?e, "Redis cache MISS for document");
@@ -81,13 +312,13 @@ impl RedisYjsCache {
         }
     }
 
-    /// Delete document from Redis
+    /// Delete document (and its update log) from Redis
     pub async fn delete_document(&self, doc_id: &str) {
-        let key = Self::document_key(doc_id);
+        let keys = [Self::document_key(doc_id), Self::log_key(doc_id), Self::log_bytes_key(doc_id)];
 
         match self.client.get_multiplexed_async_connection().await {
             Ok(mut conn) => {
-                match conn.del::<_, ()>(&key).await {
+                match conn.del::<_, ()>(&keys).await {
                     Ok(_) => {
                         debug!(doc_id = %doc_id, "Deleted document from Redis cache");
                     }
@@ -127,6 +358,235 @@ impl RedisYjsCache {
             }
         }
     }
+
+    /// Append an incremental update to a document's log. Returns true once the
+    /// log is large enough that it should be compacted.
+    pub async fn append_update(&self, doc_id: &str, update: &[u8]) -> bool {
+        let log_key = Self::log_key(doc_id);
+        let bytes_key = Self::log_bytes_key(doc_id);
+
+        let mut conn = match self.client.get_multiplexed_async_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!(doc_id = %doc_id, error = ?e, "Redis connection failed when logging update");
+                return false;
+            }
+        };
+
+        let result: Result<(usize, usize), RedisError> = redis::pipe()
+            .atomic()
+            .rpush(&log_key, update)
+            .incr(&bytes_key, update.len())
+            .expire(&log_key, DOCUMENT_TTL as i64).ignore()
+            .expire(&bytes_key, DOCUMENT_TTL as i64).ignore()
+            .query_async(&mut conn)
+            .await;
+
+        match result {
+            Ok((updates, bytes)) => {
+                debug!(doc_id = %doc_id, updates, bytes, "Logged document update");
+                self.compaction.exceeded(updates, bytes)
+            }
+            Err(e) => {
+                warn!(doc_id = %doc_id, error = ?e, "Failed to log document update");
+                false
+            }
+        }
+    }
+
+    /// Drop a document's update log, e.g. after its content was replaced
+    /// wholesale and the logged updates no longer apply
+    pub async fn clear_update_log(&self, doc_id: &str) {
+        let keys = [Self::log_key(doc_id), Self::log_bytes_key(doc_id)];
+
+        match self.client.get_multiplexed_async_connection().await {
+            Ok(mut conn) => {
+                if let Err(e) = conn.del::<_, ()>(&keys).await {
+                    warn!(doc_id = %doc_id, error = ?e, "Failed to clear document update log");
+                }
+            }
+            Err(e) => {
+                warn!(doc_id = %doc_id, error = ?e, "Redis connection failed when clearing update log");
+            }
+        }
+    }
+
+    /// Merge a document's update log into its snapshot and trim the log.
+    /// Updates logged while compacting are kept for the next round. Returns
+    /// the compacted document, or None if there was nothing to compact.
+    pub async fn compact_document(&self, doc_id: &str) -> Option<CompactedDocument> {
+        let key = Self::document_key(doc_id);
+        let log_key = Self::log_key(doc_id);
+        let bytes_key = Self::log_bytes_key(doc_id);
+
+        let mut conn = match self.client.get_multiplexed_async_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!(doc_id = %doc_id, error = ?e, "Redis connection failed when compacting document");
+                return None;
+            }
+        };
+
+        let snapshot: Option<Vec<u8>> = conn.get(&key).await.ok().flatten();
+        let updates: Vec<Vec<u8>> = conn.lrange(&log_key, 0, -1).await.unwrap_or_default();
+        if updates.is_empty() {
+            return None;
+        }
+
+        // Without a snapshot the log is only part of the document; merging it
+        // would lose everything before it
+        let Some(snapshot) = snapshot else {
+            warn!(doc_id = %doc_id, "Dropping update log with no snapshot to compact into");
+            self.clear_update_log(doc_id).await;
+            return None;
+        };
+
+        let logged_bytes: usize = updates.iter().map(Vec::len).sum();
+        let compacted = match compact_updates(Some(&snapshot), &updates) {
+            Ok(compacted) => compacted,
+            Err(e) => {
+                warn!(doc_id = %doc_id, error = ?e, "Failed to compact document");
+                return None;
+            }
+        };
+
+        let result: Result<(), RedisError> = redis::pipe()
+            .atomic()
+            .set_ex(&key, &compacted.document, DOCUMENT_TTL as u64).ignore()
+            .ltrim(&log_key, updates.len() as isize, -1).ignore()
+            .decr(&bytes_key, logged_bytes).ignore()
+            .query_async(&mut conn)
+            .await;
+
+        if let Err(e) = result {
+            warn!(doc_id = %doc_id, error = ?e, "Failed to store compacted document");
+            return None;
+        }
+
+        info!(
+            doc_id = %doc_id,
+            updates = updates.len(),
+            before_bytes = snapshot.len() + logged_bytes,
+            after_bytes = compacted.document.len(),
+            "Compacted document"
+        );
+        Some(compacted)
+    }
+
+    /// Generate Redis key for a document's presence
+    fn presence_key(doc_id: &str) -> String {
+        format!("{PRESENCE_KEY_PREFIX}:{doc_id}")
+    }
+
+    /// Load a document's presence, apply `change` and write back what changed.
+    /// Stale sessions are expired first; their users are reported as left.
+    async fn update_presence<T>(
+        &self,
+        doc_id: &str,
+        change: impl FnOnce(&mut PresenceSet, i64) -> (T, Vec<String>),
+    ) -> Option<(T, PresenceSet, Vec<Uuid>)> {
+        let key = Self::presence_key(doc_id);
+        let mut conn = match self.client.get_multiplexed_async_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!(doc_id = %doc_id, error = ?e, "Redis connection failed when updating presence");
+                return None;
+            }
+        };
+
+        let stored: HashMap<String, String> = match conn.hgetall(&key).await {
+            Ok(stored) => stored,
+            Err(e) => {
+                warn!(doc_id = %doc_id, error = ?e, "Failed to load presence");
+                return None;
+            }
+        };
+        let mut presence = PresenceSet::from_entries(
+            stored.values().filter_map(|json| serde_json::from_str::<PresenceEntry>(json).ok()),
+        );
+
+        let now = chrono::Utc::now().timestamp();
+        let (_, left) = presence.expire(now);
+        let (result, touched) = change(&mut presence, now);
+
+        // Expired entries, and any that failed to parse
+        let removed: Vec<&String> = stored
+            .keys()
+            .filter(|id| presence.get(id).is_none() && !touched.contains(id))
+            .collect();
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for session_id in &touched {
+            match presence.get(session_id) {
+                Some(entry) => {
+                    pipe.hset(&key, session_id, serde_json::to_string(entry).unwrap_or_default()).ignore();
+                }
+                None => {
+                    pipe.hdel(&key, session_id).ignore();
+                }
+            }
+        }
+        if !removed.is_empty() {
+            pipe.hdel(&key, &removed).ignore();
+        }
+        pipe.expire(&key, PRESENCE_TTL_SECS).ignore();
+
+        if let Err(e) = pipe.query_async::<_, ()>(&mut conn).await {
+            warn!(doc_id = %doc_id, error = ?e, "Failed to store presence");
+            return None;
+        }
+
+        Some((result, presence, left))
+    }
+
+    /// Record a heartbeat from an editing session
+    pub async fn presence_heartbeat(&self, doc_id: &str, session_id: &str, user_uuid: Uuid, name: &str) -> PresenceChanges {
+        self.update_presence(doc_id, |presence, now| {
+            (presence.heartbeat(session_id, user_uuid, name, now), vec![session_id.to_string()])
+        })
+        .await
+        .map(|(joined, _, left)| PresenceChanges { joined, left })
+        .unwrap_or_default()
+    }
+
+    /// Record an editing session's cursor. Returns false if the session isn't present.
+    pub async fn presence_cursor(&self, doc_id: &str, session_id: &str, cursor: serde_json::Value) -> bool {
+        self.update_presence(doc_id, |presence, now| {
+            (presence.set_cursor(session_id, cursor, now), vec![session_id.to_string()])
+        })
+        .await
+        .is_some_and(|(updated, _, _)| updated)
+    }
+
+    /// Remove an editing session. Returns the users who left.
+    pub async fn presence_leave(&self, doc_id: &str, session_id: &str) -> Vec<Uuid> {
+        self.update_presence(doc_id, |presence, _| {
+            (presence.remove(session_id), vec![session_id.to_string()])
+        })
+        .await
+        .map(|(removed, _, mut left)| {
+            left.extend(removed);
+            left
+        })
+        .unwrap_or_default()
+    }
+
+    /// Editors currently present on a document, and users whose presence
+    /// just expired
+    pub async fn presence(&self, doc_id: &str) -> (Vec<PresenceEntry>, Vec<Uuid>) {
+        self.update_presence(doc_id, |_, _| ((), Vec::new()))
+            .await
+            .map(|(_, presence, left)| (presence.editors(), left))
+            .unwrap_or_default()
+    }
+
+    /// Round-trip a PING to check that Redis is reachable
+    pub async fn ping(&self) -> Result<(), RedisError> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        redis::cmd("PING").query_async::<_, String>(&mut conn).await?;
+        Ok(())
+    }
 }
 
 /// Convenience function to create an Arc-wrapped cache instance
@@ -138,8 +598,152 @@ pub fn create_redis_cache(redis_url: &str) -> Result<Arc<RedisYjsCache>, RedisEr
 mod tests {
     use super::*;
 
+    use crate::services::search::extractors::extract_text_from_yjs;
+    use yrs::{Text, WriteTxn, XmlElementPrelim, XmlFragment, XmlTextPrelim};
+
     #[tokio::test]
     async fn test_redis_key_format() {
         assert_eq!(RedisYjsCache::document_key("ticket-123"), "yjs:doc:ticket-123");
+        assert_eq!(RedisYjsCache::log_key("ticket-123"), "yjs:log:ticket-123");
+    }
+
+    /// Simulate an editing session: a snapshot with one paragraph, then many
+    /// small edits that type and delete text. Returns the snapshot, the logged
+    /// updates and the final full state.
+    fn editing_session() -> (Vec<u8>, Vec<Vec<u8>>, Vec<u8>) {
+        let doc = Doc::new();
+        let text = {
+            let mut txn = doc.transact_mut();
+            let fragment = txn.get_or_insert_xml_fragment("prosemirror");
+            let paragraph = fragment.push_back(&mut txn, XmlElementPrelim::empty("paragraph"));
+            paragraph.push_back(&mut txn, XmlTextPrelim::new("Restart the print spooler"))
+        };
+        let snapshot = doc.transact().encode_state_as_update_v1(&StateVector::default());
+
+        let mut updates = Vec::new();
+        for i in 0..100 {
+            let before = doc.transact().state_vector();
+            {
+                let mut txn = doc.transact_mut();
+                let len = text.len(&txn);
+                if i % 2 == 0 {
+                    text.insert(&mut txn, len, " draft note that gets removed");
+                } else {
+                    text.remove_range(&mut txn, len - 29, 29);
+                }
+            }
+            updates.push(doc.transact().encode_state_as_update_v1(&before));
+        }
+        {
+            let before = doc.transact().state_vector();
+            let mut txn = doc.transact_mut();
+            let len = text.len(&txn);
+            text.insert(&mut txn, len, " and clear the queue");
+            drop(txn);
+            updates.push(doc.transact().encode_state_as_update_v1(&before));
+        }
+
+        let full = doc.transact().encode_state_as_update_v1(&StateVector::default());
+        (snapshot, updates, full)
+    }
+
+    #[test]
+    fn compaction_preserves_text_and_shrinks_storage() {
+        let (snapshot, updates, full) = editing_session();
+        let compacted = compact_updates(Some(&snapshot), &updates).unwrap();
+
+        assert_eq!(
+            extract_text_from_yjs(&compacted.document).as_deref(),
+            Some("Restart the print spooler and clear the queue")
+        );
+        assert_eq!(extract_text_from_yjs(&compacted.document), extract_text_from_yjs(&full));
+
+        let stored: usize = snapshot.len() + updates.iter().map(Vec::len).sum::<usize>();
+        assert!(
+            compacted.document.len() * 4 < stored,
+            "compacted {} bytes vs {} stored",
+            compacted.document.len(),
+            stored
+        );
+
+        // The state vector covers every logged update
+        let state_vector = StateVector::decode_v1(&compacted.state_vector).unwrap();
+        let full_doc = Doc::new();
+        full_doc.transact_mut().apply_update(Update::decode_v1(&full).unwrap()).unwrap();
+        assert_eq!(state_vector, full_doc.transact().state_vector());
+
+        // Compacting again changes nothing
+        let again = compact_updates(Some(&compacted.document), &[]).unwrap();
+        assert_eq!(extract_text_from_yjs(&again.document), extract_text_from_yjs(&compacted.document));
+    }
+
+    #[test]
+    fn compaction_rejects_corrupt_updates_and_thresholds_trigger() {
+        assert!(compact_updates(None, &[vec![0xff, 0xff, 0xff]]).is_err());
+
+        let config = CompactionConfig { max_updates: 10, max_bytes: 1024 };
+        assert!(!config.exceeded(9, 1023));
+        assert!(config.exceeded(10, 0));
+        assert!(config.exceeded(1, 1024));
+    }
+
+    #[test]
+    fn presence_join_leave_tracks_users_not_sessions() {
+        let alice = Uuid::new_v4();
+        let bob = Uuid::new_v4();
+        let mut presence = PresenceSet::default();
+
+        assert!(presence.heartbeat("s1", alice, "Alice", 100));
+        // Second tab for the same user isn't a new join
+        assert!(!presence.heartbeat("s2", alice, "Alice", 105));
+        assert!(presence.heartbeat("s3", bob, "Bob", 110));
+        // Repeat heartbeats just refresh
+        assert!(!presence.heartbeat("s3", bob, "Bob", 120));
+
+        assert!(presence.set_cursor("s3", serde_json::json!({"anchor": 4, "head": 9}), 121));
+        assert!(!presence.set_cursor("unknown", serde_json::json!({}), 121));
+        // A heartbeat keeps the last cursor
+        presence.heartbeat("s3", bob, "Bob", 130);
+        assert_eq!(presence.get("s3").unwrap().cursor, Some(serde_json::json!({"anchor": 4, "head": 9})));
+
+        let editors = presence.editors();
+        assert_eq!(editors.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), vec!["Alice", "Bob"]);
+        assert_eq!(editors[0].session_id, "s2");
+
+        // Closing one of two tabs doesn't make Alice leave; closing both does
+        assert_eq!(presence.remove("s1"), None);
+        assert_eq!(presence.remove("s2"), Some(alice));
+        assert_eq!(presence.remove("s2"), None);
+    }
+
+    #[test]
+    fn presence_expires_without_heartbeats() {
+        let alice = Uuid::new_v4();
+        let bob = Uuid::new_v4();
+        let mut presence = PresenceSet::default();
+        presence.heartbeat("s1", alice, "Alice", 0);
+        presence.heartbeat("s2", bob, "Bob", 0);
+        presence.heartbeat("s3", bob, "Bob", 30);
+
+        // Nobody has been silent for the full TTL yet
+        let (expired, left) = presence.expire(PRESENCE_TTL_SECS - 1);
+        assert!(expired.is_empty() && left.is_empty());
+
+        // Alice's only session and Bob's older one expire; Bob is still present
+        let (mut expired, left) = presence.expire(PRESENCE_TTL_SECS);
+        expired.sort();
+        assert_eq!(expired, vec!["s1".to_string(), "s2".to_string()]);
+        assert_eq!(left, vec![alice]);
+        assert_eq!(presence.editors().len(), 1);
+
+        let (_, left) = presence.expire(30 + PRESENCE_TTL_SECS);
+        assert_eq!(left, vec![bob]);
+        assert!(presence.editors().is_empty());
+
+        // Entries round-trip through their stored JSON form
+        presence.heartbeat("s4", alice, "Alice", 200);
+        let stored = serde_json::to_string(presence.get("s4").unwrap()).unwrap();
+        let restored = PresenceSet::from_entries([serde_json::from_str::<PresenceEntry>(&stored).unwrap()]);
+        assert_eq!(restored.editors(), presence.editors());
     }
 }
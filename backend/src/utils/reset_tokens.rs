@@ -9,6 +9,7 @@ pub enum TokenType {
     PasswordReset,
     MfaReset,
     Invitation,
+    EmailVerification,
 }
 
 impl TokenType {
@@ -17,6 +18,7 @@ impl TokenType {
             TokenType::PasswordReset => "password_reset",
             TokenType::MfaReset => "mfa_reset",
             TokenType::Invitation => "invitation",
+            TokenType::EmailVerification => "email_verification",
         }
     }
 
@@ -26,6 +28,7 @@ impl TokenType {
             TokenType::PasswordReset => Duration::hours(1),  // 1 hour for password resets
             TokenType::MfaReset => Duration::minutes(15),    // 15 minutes for MFA resets
             TokenType::Invitation => Duration::days(7),      // 7 days for user invitations
+            TokenType::EmailVerification => Duration::hours(24), // 24 hours to confirm an added email
         }
     }
 }
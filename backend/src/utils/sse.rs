@@ -2,6 +2,7 @@ use crate::handlers::sse::{SseState, TicketEvent};
 use actix_web::web;
 use chrono::Utc;
 use tracing::debug;
+use uuid::Uuid;
 
 /// SSE broadcasting utilities for real-time ticket updates
 pub struct SseBroadcaster;
@@ -274,6 +275,55 @@ impl SseBroadcaster {
         }).await;
     }
 
+    /// Broadcast that a user started editing a collaborative document
+    pub async fn broadcast_editor_joined(
+        state: &web::Data<SseState>,
+        document_id: &str,
+        user_uuid: Uuid,
+        name: &str,
+    ) {
+        Self::broadcast_generic_event(state, |timestamp| {
+            TicketEvent::EditorJoined {
+                document_id: document_id.to_string(),
+                user_uuid: user_uuid.to_string(),
+                name: name.to_string(),
+                timestamp,
+            }
+        }).await;
+    }
+
+    /// Broadcast that a user stopped editing a collaborative document
+    pub async fn broadcast_editor_left(
+        state: &web::Data<SseState>,
+        document_id: &str,
+        user_uuid: Uuid,
+    ) {
+        Self::broadcast_generic_event(state, |timestamp| {
+            TicketEvent::EditorLeft {
+                document_id: document_id.to_string(),
+                user_uuid: user_uuid.to_string(),
+                timestamp,
+            }
+        }).await;
+    }
+
+    /// Broadcast an editor's cursor position on a collaborative document
+    pub async fn broadcast_editor_cursor(
+        state: &web::Data<SseState>,
+        document_id: &str,
+        user_uuid: Uuid,
+        cursor: serde_json::Value,
+    ) {
+        Self::broadcast_generic_event(state, |timestamp| {
+            TicketEvent::EditorCursorMoved {
+                document_id: document_id.to_string(),
+                user_uuid: user_uuid.to_string(),
+                cursor,
+                timestamp,
+            }
+        }).await;
+    }
+
     /// Broadcast a user field update to all connected clients
     pub async fn broadcast_user_updated(
         state: &web::Data<SseState>,
@@ -320,6 +370,24 @@ impl SseBroadcaster {
             }
         }).await;
     }
+
+    /// Broadcast a notification read-state change to the user's sessions
+    pub async fn broadcast_notifications_read(
+        state: &SseState,
+        recipient_uuid: &Uuid,
+        notification_ids: Option<Vec<i32>>,
+        unread_count: i64,
+    ) {
+        debug!(recipient = %recipient_uuid, unread_count, "SSE: Broadcasting notification read state");
+        state
+            .broadcast_event(TicketEvent::NotificationsRead {
+                recipient_uuid: recipient_uuid.to_string(),
+                notification_ids,
+                unread_count,
+                timestamp: Utc::now(),
+            })
+            .await;
+    }
 }
 
 /// Macro for easy SSE broadcasting with error handling
@@ -1,12 +1,23 @@
 use async_trait::async_trait;
+use bytes::Bytes;
+use futures::Stream;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::io;
 use std::path::Path;
 use uuid::Uuid;
 use actix_web::{HttpResponse, HttpRequest};
-use actix_web::http::header::{CONTENT_TYPE, CACHE_CONTROL, ACCEPT_RANGES};
+use actix_web::body::SizedStream;
+use actix_web::http::header::{CONTENT_TYPE, CACHE_CONTROL, ACCEPT_RANGES, CONTENT_RANGE, RANGE};
+use actix_web::http::StatusCode;
 use tracing::error;
 
+/// Bytes read per chunk when streaming a file
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// A file's contents streamed in chunks
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send>>;
+
 /// Storage configuration for different backends
 #[derive(Debug, Clone)]
 pub enum StorageConfig {
@@ -70,6 +81,12 @@ pub trait Storage: Send + Sync {
     /// Retrieve a file by path
     async fn get_file(&self, path: &str) -> Result<Vec<u8>, StorageError>;
 
+    /// Size of a file in bytes
+    async fn file_size(&self, path: &str) -> Result<u64, StorageError>;
+
+    /// Stream the bytes `start..=end` of a file without loading it into memory
+    async fn stream_range(&self, path: &str, start: u64, end: u64) -> Result<ByteStream, StorageError>;
+
     /// Delete a file by path
     async fn delete_file(&self, path: &str) -> Result<(), StorageError>;
 
@@ -150,6 +167,50 @@ impl Storage for LocalStorage {
         }
     }
 
+    async fn file_size(&self, path: &str) -> Result<u64, StorageError> {
+        let full_path = self.get_full_path(path);
+        match tokio::fs::metadata(&full_path).await {
+            Ok(metadata) if metadata.is_file() => Ok(metadata.len()),
+            Ok(_) => Err(StorageError::NotFound(format!("File not found: {path}"))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                Err(StorageError::NotFound(format!("File not found: {path}")))
+            }
+            Err(e) => Err(StorageError::Io(e)),
+        }
+    }
+
+    async fn stream_range(&self, path: &str, start: u64, end: u64) -> Result<ByteStream, StorageError> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let full_path = self.get_full_path(path);
+        let mut file = match tokio::fs::File::open(&full_path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(StorageError::NotFound(format!("File not found: {path}")));
+            }
+            Err(e) => return Err(StorageError::Io(e)),
+        };
+        file.seek(io::SeekFrom::Start(start)).await?;
+
+        let remaining = end.saturating_sub(start) + 1;
+        let stream = futures::stream::unfold((file, remaining), |(mut file, remaining)| async move {
+            if remaining == 0 {
+                return None;
+            }
+            let mut buf = vec![0u8; STREAM_CHUNK_SIZE.min(remaining as usize)];
+            match file.read(&mut buf).await {
+                // The file shrank while streaming
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    Some((Ok(Bytes::from(buf)), (file, remaining - n as u64)))
+                }
+                Err(e) => Some((Err(e), (file, 0))),
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+
     async fn delete_file(&self, path: &str) -> Result<(), StorageError> {
         let full_path = self.get_full_path(path);
         match std::fs::remove_file(&full_path) {
@@ -228,6 +289,16 @@ impl Storage for S3Storage {
         Err(StorageError::ConfigError("S3 storage not implemented yet".to_string()))
     }
 
+    async fn file_size(&self, _path: &str) -> Result<u64, StorageError> {
+        // TODO: Implement with HeadObject (Content-Length)
+        Err(StorageError::ConfigError("S3 storage not implemented yet".to_string()))
+    }
+
+    async fn stream_range(&self, _path: &str, _start: u64, _end: u64) -> Result<ByteStream, StorageError> {
+        // TODO: Implement as a ranged GetObject (Range: bytes=start-end), streaming the body
+        Err(StorageError::ConfigError("S3 storage not implemented yet".to_string()))
+    }
+
     async fn delete_file(&self, _path: &str) -> Result<(), StorageError> {
         // TODO: Implement S3 delete
         Err(StorageError::ConfigError("S3 storage not implemented yet".to_string()))
@@ -276,7 +347,61 @@ pub fn get_storage_config() -> StorageConfig {
     }
 }
 
+/// Why a `Range` header can't be served
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeNotSatisfiable;
+
+/// Parse a `Range` header against a file of `size` bytes
+///
+/// Returns the inclusive byte range to serve, or None to serve the whole
+/// file. Headers we don't support (other units, multiple ranges) or can't
+/// parse are ignored, as RFC 9110 allows; a well-formed range that lies
+/// outside the file is not satisfiable.
+pub fn parse_range(header: &str, size: u64) -> Result<Option<(u64, u64)>, RangeNotSatisfiable> {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return Ok(None);
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    // "bytes=-500": the last 500 bytes
+    if start.is_empty() {
+        let Ok(suffix) = end.parse::<u64>() else {
+            return Ok(None);
+        };
+        if suffix == 0 || size == 0 {
+            return Err(RangeNotSatisfiable);
+        }
+        return Ok(Some((size.saturating_sub(suffix), size - 1)));
+    }
+
+    // "bytes=500-999" or "bytes=500-"
+    let Ok(start) = start.parse::<u64>() else {
+        return Ok(None);
+    };
+    let end = match end {
+        "" => None,
+        end => match end.parse::<u64>() {
+            Ok(end) if end >= start => Some(end),
+            _ => return Ok(None),
+        },
+    };
+    if start >= size {
+        return Err(RangeNotSatisfiable);
+    }
+    Ok(Some((start, end.map_or(size - 1, |end| end.min(size - 1)))))
+}
+
 /// Centralized file serving function that works with any storage backend
+///
+/// Files are streamed rather than buffered, and `Range` requests get a
+/// `206 Partial Content` (or `416` when the range lies outside the file) so
+/// large downloads can resume and video can seek.
 pub async fn serve_file_from_storage(
     storage: Arc<dyn Storage>,
     path: &str,
@@ -284,19 +409,18 @@ pub async fn serve_file_from_storage(
 ) -> Result<HttpResponse, actix_web::Error> {
     // Extract filename from path for content type detection
     let filename = path.split('/').next_back().unwrap_or("file");
-    
-    // Get file data from storage
-    let file_data = storage.get_file(path).await.map_err(|e| {
+
+    let size = storage.file_size(path).await.map_err(|e| {
         error!("Failed to get file from storage: {:?}", e);
         actix_web::error::ErrorNotFound("File not found")
     })?;
-    
+
     // Determine content type based on file extension
     let content_type = get_content_type(filename);
-    
+
     // Build response with proper headers
     let mut response_builder = HttpResponse::Ok();
-    
+
     response_builder
         .insert_header((CONTENT_TYPE, content_type))
         .insert_header((ACCEPT_RANGES, "bytes"))
@@ -305,43 +429,38 @@ pub async fn serve_file_from_storage(
         .insert_header(("Access-Control-Allow-Methods", "GET, HEAD, OPTIONS"))
         .insert_header(("Access-Control-Allow-Headers", "Range, Content-Type, Authorization"))
         .insert_header(("Access-Control-Expose-Headers", "Content-Range, Content-Length, Accept-Ranges"));
-    
-    // Handle range requests for PDF.js and other file types
-    let range_header = req.headers().get("Range");
-            if let Some(range_value) = range_header {
-            if let Ok(range_str) = range_value.to_str() {
-            if let Some(range_spec) = range_str.strip_prefix("bytes=") {
-                // Remove "bytes="
-                
-                // Parse range like "0-1023" or "1024-"
-                if let Some((start_str, end_str)) = range_spec.split_once('-') {
-                    let start = start_str.parse::<usize>().unwrap_or(0);
-                    let end = if end_str.is_empty() {
-                        file_data.len() - 1
-                    } else {
-                        end_str.parse::<usize>().unwrap_or(file_data.len() - 1).min(file_data.len() - 1)
-                    };
-                    
-                    if start <= end && start < file_data.len() {
-                        let content_length = end - start + 1;
-                        let range_data = file_data[start..=end].to_vec();
-                        
-                        // Return partial content response
-                        return Ok(response_builder
-                            .status(actix_web::http::StatusCode::PARTIAL_CONTENT)
-                            .insert_header(("Content-Length", content_length.to_string()))
-                            .insert_header(("Content-Range", format!("bytes {}-{}/{}", start, end, file_data.len())))
-                            .body(range_data));
-                    }
-                }
+
+    let range = match req.headers().get(RANGE).and_then(|value| value.to_str().ok()) {
+        Some(header) => match parse_range(header, size) {
+            Ok(range) => range,
+            Err(RangeNotSatisfiable) => {
+                return Ok(response_builder
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .insert_header((CONTENT_RANGE, format!("bytes */{size}")))
+                    .finish());
             }
-        }
+        },
+        None => None,
+    };
+
+    // Empty files have no byte range to stream
+    if size == 0 {
+        return Ok(response_builder.body(Bytes::new()));
+    }
+
+    let (start, end) = range.unwrap_or((0, size - 1));
+    let stream = storage.stream_range(path, start, end).await.map_err(|e| {
+        error!("Failed to get file from storage: {:?}", e);
+        actix_web::error::ErrorNotFound("File not found")
+    })?;
+    let body = SizedStream::new(end - start + 1, stream);
+
+    if range.is_some() {
+        response_builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .insert_header((CONTENT_RANGE, format!("bytes {start}-{end}/{size}")));
     }
-    
-    // Full file response (no range request or invalid range)
-    Ok(response_builder
-        .insert_header(("Content-Length", file_data.len().to_string()))
-        .body(file_data))
+    Ok(response_builder.body(body))
 }
 
 /// Helper function to determine content type based on file extension
@@ -423,4 +542,96 @@ mod tests {
         let storage = LocalStorage::new("/app/uploads".into(), "/uploads/".into());
         assert_eq!(storage.get_public_url("/tickets/file.pdf"), "/uploads/tickets/file.pdf");
     }
+
+    // ── Range requests ───────────────────────────────────────────
+
+    #[test]
+    fn parse_range_forms() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Ok(Some((0, 99))));
+        assert_eq!(parse_range("bytes=900-", 1000), Ok(Some((900, 999))));
+        assert_eq!(parse_range("bytes=-100", 1000), Ok(Some((900, 999))));
+        assert_eq!(parse_range("bytes=-5000", 1000), Ok(Some((0, 999))));
+        assert_eq!(parse_range("bytes=500-5000", 1000), Ok(Some((500, 999))));
+    }
+
+    #[test]
+    fn parse_range_ignores_unsupported_or_malformed() {
+        assert_eq!(parse_range("items=0-9", 1000), Ok(None));
+        assert_eq!(parse_range("bytes=0-9,20-29", 1000), Ok(None));
+        assert_eq!(parse_range("bytes=abc", 1000), Ok(None));
+        assert_eq!(parse_range("bytes=50-10", 1000), Ok(None));
+    }
+
+    #[test]
+    fn parse_range_outside_file_is_unsatisfiable() {
+        assert_eq!(parse_range("bytes=1000-", 1000), Err(RangeNotSatisfiable));
+        assert_eq!(parse_range("bytes=-0", 1000), Err(RangeNotSatisfiable));
+        assert_eq!(parse_range("bytes=0-", 0), Err(RangeNotSatisfiable));
+    }
+
+    /// Local storage in a fresh temp dir holding `tickets/video.mp4`
+    fn storage_with_file(contents: &[u8]) -> (Arc<dyn Storage>, std::path::PathBuf) {
+        let base = std::env::temp_dir().join(format!("nosdesk-storage-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(base.join("tickets")).unwrap();
+        std::fs::write(base.join("tickets/video.mp4"), contents).unwrap();
+        let storage = LocalStorage::new(base.to_string_lossy().into_owned(), "/uploads".into());
+        (Arc::new(storage), base)
+    }
+
+    async fn serve(storage: &Arc<dyn Storage>, range: Option<&str>) -> (StatusCode, actix_web::http::header::HeaderMap, Bytes) {
+        let mut req = actix_web::test::TestRequest::get();
+        if let Some(range) = range {
+            req = req.insert_header((RANGE, range));
+        }
+        let resp = serve_file_from_storage(storage.clone(), "tickets/video.mp4", &req.to_http_request())
+            .await
+            .unwrap();
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let body = actix_web::body::to_bytes(resp.into_body()).await.unwrap();
+        (status, headers, body)
+    }
+
+    #[actix_web::test]
+    async fn full_get_streams_whole_file() {
+        let contents: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let (storage, base) = storage_with_file(&contents);
+
+        let (status, headers, body) = serve(&storage, None).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(headers.get(ACCEPT_RANGES).unwrap(), "bytes");
+        assert_eq!(headers.get(CONTENT_TYPE).unwrap(), "video/mp4");
+        assert!(headers.get(CONTENT_RANGE).is_none());
+        assert_eq!(body.as_ref(), contents.as_slice());
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[actix_web::test]
+    async fn ranged_get_returns_partial_content() {
+        let contents: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let (storage, base) = storage_with_file(&contents);
+
+        let (status, headers, body) = serve(&storage, Some("bytes=70000-139999")).await;
+
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(headers.get(CONTENT_RANGE).unwrap(), "bytes 70000-139999/200000");
+        assert_eq!(body.as_ref(), &contents[70000..140000]);
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[actix_web::test]
+    async fn invalid_range_is_not_satisfiable() {
+        let (storage, base) = storage_with_file(b"short file");
+
+        let (status, headers, body) = serve(&storage, Some("bytes=500-600")).await;
+
+        assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(headers.get(CONTENT_RANGE).unwrap(), "bytes */10");
+        assert!(body.is_empty());
+
+        let _ = std::fs::remove_dir_all(base);
+    }
 }
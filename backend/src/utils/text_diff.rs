@@ -0,0 +1,90 @@
+//! Line-based diff for comparing document revisions
+//!
+//! Documents are compared block by block (one line per paragraph, heading,
+//! list, ...) using a longest-common-subsequence table. Documentation pages
+//! are small enough that the quadratic table is not a concern.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// One line of a diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// Diff `old` against `new`. Deletions are listed before insertions where a
+/// line was replaced.
+pub fn diff_lines(old: &[String], new: &[String]) -> Vec<DiffLine> {
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let line = |op, text: &String| DiffLine { op, text: text.clone() };
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(line(DiffOp::Equal, &old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(line(DiffOp::Delete, &old[i]));
+            i += 1;
+        } else {
+            diff.push(line(DiffOp::Insert, &new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|text| line(DiffOp::Delete, text)));
+    diff.extend(new[j..].iter().map(|text| line(DiffOp::Insert, text)));
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn diff_marks_replaced_added_and_removed_lines() {
+        let old = lines("Intro\nStep one\nStep two\nOutro");
+        let new = lines("Intro\nStep 1\nStep two\nOutro\nSee also");
+
+        let diff = diff_lines(&old, &new);
+        let ops: Vec<(DiffOp, &str)> = diff.iter().map(|l| (l.op, l.text.as_str())).collect();
+        assert_eq!(
+            ops,
+            vec![
+                (DiffOp::Equal, "Intro"),
+                (DiffOp::Delete, "Step one"),
+                (DiffOp::Insert, "Step 1"),
+                (DiffOp::Equal, "Step two"),
+                (DiffOp::Equal, "Outro"),
+                (DiffOp::Insert, "See also"),
+            ]
+        );
+
+        assert!(diff_lines(&old, &old).iter().all(|l| l.op == DiffOp::Equal));
+        assert!(diff_lines(&[], &new).iter().all(|l| l.op == DiffOp::Insert));
+    }
+}
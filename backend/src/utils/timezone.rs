@@ -0,0 +1,105 @@
+//! Timezones
+//!
+//! Date-only filters ("created on 2026-03-04") name a calendar day, which
+//! starts and ends at different UTC instants depending on where the user is.
+//! The zone used is the request's `timezone` parameter, then the user's saved
+//! preference, then `DEFAULT_TIMEZONE` (UTC when unset).
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use once_cell::sync::Lazy;
+
+/// Zone used when neither the request nor the user names one, from
+/// DEFAULT_TIMEZONE (default UTC)
+static DEFAULT_TIMEZONE: Lazy<Tz> = Lazy::new(|| match std::env::var("DEFAULT_TIMEZONE") {
+    Ok(name) if !name.trim().is_empty() => parse(&name).unwrap_or_else(|| {
+        tracing::warn!(timezone = %name, "Invalid DEFAULT_TIMEZONE, using UTC");
+        Tz::UTC
+    }),
+    _ => Tz::UTC,
+});
+
+pub fn default_timezone() -> Tz {
+    *DEFAULT_TIMEZONE
+}
+
+/// Parse an IANA zone name such as `Europe/Berlin`
+pub fn parse(name: &str) -> Option<Tz> {
+    name.trim().parse().ok()
+}
+
+/// The first of `request`, then `preference`, that names a valid zone,
+/// otherwise the default
+pub fn resolve(request: Option<&str>, preference: Option<&str>) -> Tz {
+    request
+        .and_then(parse)
+        .or_else(|| preference.and_then(parse))
+        .unwrap_or_else(default_timezone)
+}
+
+/// UTC time of the first instant of `date` in `tz`
+///
+/// Where a DST change skips local midnight, the day starts at the first
+/// local time that exists.
+pub fn start_of_day_utc(date: NaiveDate, tz: Tz) -> NaiveDateTime {
+    let mut local = date.and_time(chrono::NaiveTime::MIN);
+    // Gaps are at most a few hours; step through them
+    for _ in 0..24 {
+        if let Some(start) = tz.from_local_datetime(&local).earliest() {
+            return start.naive_utc();
+        }
+        local += Duration::hours(1);
+    }
+    date.and_time(chrono::NaiveTime::MIN)
+}
+
+/// UTC time of the last second of `date` in `tz`
+pub fn end_of_day_utc(date: NaiveDate, tz: Tz) -> NaiveDateTime {
+    match date.succ_opt() {
+        Some(next) => start_of_day_utc(next, tz) - Duration::seconds(1),
+        None => NaiveDateTime::MAX,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn same_date_has_different_utc_bounds_per_zone() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+
+        assert_eq!(start_of_day_utc(date, Tz::UTC), at(4, 0));
+        assert_eq!(end_of_day_utc(date, Tz::UTC), at(4, 23) + Duration::minutes(59) + Duration::seconds(59));
+
+        // UTC-5 in winter: the day runs 05:00 to 05:00 UTC
+        let new_york = parse("America/New_York").unwrap();
+        assert_eq!(start_of_day_utc(date, new_york), at(4, 5));
+        assert_eq!(end_of_day_utc(date, new_york), at(5, 5) - Duration::seconds(1));
+
+        // UTC+9: the day starts the evening before in UTC
+        let tokyo = parse("Asia/Tokyo").unwrap();
+        assert_eq!(start_of_day_utc(date, tokyo), at(3, 15));
+        assert_eq!(end_of_day_utc(date, tokyo), at(4, 15) - Duration::seconds(1));
+    }
+
+    #[test]
+    fn dst_change_shortens_the_day() {
+        // Clocks go forward on 2026-03-08 in New York: a 23 hour day
+        let new_york = parse("America/New_York").unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 3, 8).unwrap();
+        assert_eq!(start_of_day_utc(date, new_york), at(8, 5));
+        assert_eq!(end_of_day_utc(date, new_york), at(9, 4) - Duration::seconds(1));
+    }
+
+    #[test]
+    fn request_zone_wins_over_preference() {
+        assert_eq!(resolve(Some("Asia/Tokyo"), Some("Europe/Berlin")), chrono_tz::Asia::Tokyo);
+        assert_eq!(resolve(Some("Not/AZone"), Some("Europe/Berlin")), chrono_tz::Europe::Berlin);
+        assert_eq!(resolve(None, None), default_timezone());
+    }
+}
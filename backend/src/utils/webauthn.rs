@@ -13,6 +13,7 @@ use uuid::Uuid;
 use base64::Engine;
 use webauthn_rs::prelude::*;
 
+use crate::config_utils::EnvLookup;
 use crate::db::DbConnection;
 use crate::models::User;
 use crate::repository;
@@ -35,22 +36,27 @@ impl WebAuthnConfig {
     /// In production, all WEBAUTHN_* variables are required.
     /// In development, defaults to localhost values.
     pub fn from_env() -> Result<Self> {
-        let environment = env::var("ENVIRONMENT")
-            .unwrap_or_else(|_| "development".to_string())
+        Self::from_lookup(&|name| env::var(name).ok())
+    }
+
+    /// Load WebAuthn configuration from `lookup` (see [`Self::from_env`])
+    pub fn from_lookup(lookup: EnvLookup) -> Result<Self> {
+        let environment = lookup("ENVIRONMENT")
+            .unwrap_or_else(|| "development".to_string())
             .to_lowercase();
 
         let is_production = environment == "production";
 
         // In production, require explicit configuration
-        let rp_id = match env::var("WEBAUTHN_RP_ID") {
-            Ok(id) => id,
-            Err(_) if is_production => {
+        let rp_id = match lookup("WEBAUTHN_RP_ID") {
+            Some(id) => id,
+            None if is_production => {
                 return Err(anyhow!(
                     "WEBAUTHN_RP_ID environment variable is required in production. \
                     Set it to your domain (e.g., 'example.com')"
                 ));
             }
-            Err(_) => {
+            None => {
                 tracing::warn!(
                     "WEBAUTHN_RP_ID not set, defaulting to 'localhost'. \
                     This is insecure for production use."
@@ -59,18 +65,18 @@ impl WebAuthnConfig {
             }
         };
 
-        let rp_name = env::var("WEBAUTHN_RP_NAME")
-            .unwrap_or_else(|_| "Nosdesk".to_string());
+        let rp_name = lookup("WEBAUTHN_RP_NAME")
+            .unwrap_or_else(|| "Nosdesk".to_string());
 
-        let rp_origin_str = match env::var("WEBAUTHN_RP_ORIGIN") {
-            Ok(origin) => origin,
-            Err(_) if is_production => {
+        let rp_origin_str = match lookup("WEBAUTHN_RP_ORIGIN") {
+            Some(origin) => origin,
+            None if is_production => {
                 return Err(anyhow!(
                     "WEBAUTHN_RP_ORIGIN environment variable is required in production. \
                     Set it to your full origin URL (e.g., 'https://example.com')"
                 ));
             }
-            Err(_) => {
+            None => {
                 tracing::warn!(
                     "WEBAUTHN_RP_ORIGIN not set, defaulting to 'http://localhost:5173'. \
                     This is insecure for production use."
@@ -9,7 +9,7 @@ import pluginService from '@/services/pluginService';
 import { getTicketById, getTickets, addCommentToTicket } from '@/services/ticketService';
 import { getDeviceById, getDevices } from '@/services/deviceService';
 import { logger } from '@/utils/logger';
-import type { Plugin, PluginProxyRequest, PluginEvent } from '@/types/plugin';
+import type { Plugin, PluginProxyRequest, PluginEvent, PluginStorageScope } from '@/types/plugin';
 import type { Ticket } from '@/types/ticket';
 import type { Device } from '@/types/device';
 
@@ -160,39 +160,39 @@ export function createPluginAPI(plugin: Plugin): PluginAPI {
 
     // === STORE: Plugin data ===
     storage: {
-      async get<T>(key: string): Promise<T | null> {
+      async get<T>(key: string, scope: PluginStorageScope = 'global'): Promise<T | null> {
         if (!hasPermission('storage')) {
           logger.warn(`Plugin ${plugin.name} denied storage permission`);
           return null;
         }
         try {
-          const result = await pluginService.getStorage(plugin.uuid, key);
+          const result = await pluginService.getStorage(plugin.uuid, key, scope);
           return result.value as T;
         } catch (error) {
           logger.error(`Plugin ${plugin.name} failed to get storage`, { key, error });
           return null;
         }
       },
-      async set<T>(key: string, value: T): Promise<boolean> {
+      async set<T>(key: string, value: T, scope: PluginStorageScope = 'global'): Promise<boolean> {
         if (!hasPermission('storage')) {
           logger.warn(`Plugin ${plugin.name} denied storage permission`);
           return false;
         }
         try {
-          await pluginService.setStorage(plugin.uuid, { key, value });
+          await pluginService.setStorage(plugin.uuid, { key, value, scope });
           return true;
         } catch (error) {
           logger.error(`Plugin ${plugin.name} failed to set storage`, { key, error });
           return false;
         }
       },
-      async delete(key: string): Promise<boolean> {
+      async delete(key: string, scope: PluginStorageScope = 'global'): Promise<boolean> {
         if (!hasPermission('storage')) {
           logger.warn(`Plugin ${plugin.name} denied storage permission`);
           return false;
         }
         try {
-          await pluginService.deleteStorage(plugin.uuid, key);
+          await pluginService.deleteStorage(plugin.uuid, key, scope);
           return true;
         } catch (error) {
           logger.error(`Plugin ${plugin.name} failed to delete storage`, { key, error });
@@ -278,11 +278,11 @@ export interface PluginAPI {
   // External requests
   fetch(url: string, options?: RequestInit): Promise<Response | null>;
 
-  // Plugin storage
+  // Plugin storage ('user' scope is private to the current user)
   storage: {
-    get<T>(key: string): Promise<T | null>;
-    set<T>(key: string, value: T): Promise<boolean>;
-    delete(key: string): Promise<boolean>;
+    get<T>(key: string, scope?: PluginStorageScope): Promise<T | null>;
+    set<T>(key: string, value: T, scope?: PluginStorageScope): Promise<boolean>;
+    delete(key: string, scope?: PluginStorageScope): Promise<boolean>;
   };
 
   // Event subscription
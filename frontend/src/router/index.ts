@@ -71,6 +71,16 @@ const router = createRouter({
         title: 'MFA Setup Required'
       }
     },
+    {
+      path: '/verify-email',
+      name: 'verify-email',
+      component: () => import('@/views/VerifyEmailView.vue'),
+      meta: {
+        layout: 'blank',
+        requiresAuth: false,
+        title: 'Verify Email'
+      }
+    },
     {
       path: '/accept-invitation',
       name: 'accept-invitation',
@@ -131,6 +141,17 @@ const router = createRouter({
         to.meta.key = to.params.id
       }
     },
+    {
+      // Linked from the auto-close notification
+      path: '/tickets/:id/reopen',
+      name: 'ticket-reopen',
+      component: () => import('@/views/ReopenTicketView.vue'),
+      meta: {
+        layout: 'blank',
+        requiresAuth: true,
+        title: 'Reopen Ticket'
+      }
+    },
     {
       path: '/users/:uuid',
       name: 'user-profile',
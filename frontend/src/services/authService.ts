@@ -530,6 +530,19 @@ class AuthService {
     }
   }
 
+  /**
+   * Confirm an email address from a verification link
+   */
+  async verifyEmail(token: string): Promise<{ status: string; message: string; email?: string }> {
+    try {
+      const response = await apiClient.post('/auth/email-verification/confirm', { token });
+      return response.data;
+    } catch (error) {
+      logger.error('Failed to verify email', { error });
+      throw error;
+    }
+  }
+
   /**
    * Request MFA reset
    */
@@ -0,0 +1,68 @@
+import apiClient from './apiConfig';
+import { logger } from '@/utils/logger';
+import type {
+  CannedResponse,
+  CannedResponseRequest,
+  AppliedCannedResponse
+} from '@/types/cannedResponse';
+
+export const cannedResponseService = {
+  // Get shared and personal canned responses, optionally for a ticket category
+  async getCannedResponses(categoryId?: number | null): Promise<CannedResponse[]> {
+    try {
+      const params = categoryId ? { category_id: categoryId } : undefined;
+      const response = await apiClient.get<CannedResponse[]>('/canned-responses', { params });
+      return response.data;
+    } catch (error) {
+      logger.error('Error fetching canned responses:', error);
+      throw error;
+    }
+  },
+
+  // Create a canned response
+  async createCannedResponse(request: CannedResponseRequest): Promise<CannedResponse> {
+    try {
+      const response = await apiClient.post<CannedResponse>('/canned-responses', request);
+      return response.data;
+    } catch (error) {
+      logger.error('Error creating canned response:', error);
+      throw error;
+    }
+  },
+
+  // Replace a canned response
+  async updateCannedResponse(id: number, request: CannedResponseRequest): Promise<CannedResponse> {
+    try {
+      const response = await apiClient.put<CannedResponse>(`/canned-responses/${id}`, request);
+      return response.data;
+    } catch (error) {
+      logger.error(`Error updating canned response ${id}:`, error);
+      throw error;
+    }
+  },
+
+  // Delete a canned response
+  async deleteCannedResponse(id: number): Promise<void> {
+    try {
+      await apiClient.delete(`/canned-responses/${id}`);
+    } catch (error) {
+      logger.error(`Error deleting canned response ${id}:`, error);
+      throw error;
+    }
+  },
+
+  // Render a canned response for a ticket with its placeholders filled in
+  async applyCannedResponse(id: number, ticketId: number): Promise<string> {
+    try {
+      const response = await apiClient.post<AppliedCannedResponse>(`/canned-responses/${id}/apply`, {
+        ticket_id: ticketId
+      });
+      return response.data.content;
+    } catch (error) {
+      logger.error(`Error applying canned response ${id}:`, error);
+      throw error;
+    }
+  }
+};
+
+export default cannedResponseService;
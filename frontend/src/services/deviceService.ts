@@ -1,5 +1,5 @@
 import apiClient from './apiConfig';
-import type { Device, DeviceFormData } from '@/types/device';
+import type { Device, DeviceAssignment, DeviceFormData } from '@/types/device';
 import type { PaginationParams, PaginatedResponse } from '@/types/pagination';
 import { logger } from '@/utils/logger';
 import { RequestManager } from '@/utils/requestManager';
@@ -233,6 +233,52 @@ export const unmanageDevice = async (id: number): Promise<Device> => {
   }
 };
 
+// Per-row outcome of a device import
+export interface DeviceImportResult {
+  row: number;
+  serial_number?: string;
+  status: 'created' | 'updated' | 'failed';
+  device_id?: number;
+  reason?: string;
+}
+
+export interface DeviceImportResponse {
+  created: number;
+  updated: number;
+  failed: number;
+  results: DeviceImportResult[];
+}
+
+/**
+ * Import devices in bulk, creating or updating each by serial number
+ * @param devices - Device rows, e.g. parsed from a CSV/JSON export
+ * @returns Promise<DeviceImportResponse> - Counts and per-row results
+ */
+export const importDevices = async (devices: Partial<DeviceFormData>[]): Promise<DeviceImportResponse> => {
+  try {
+    const response = await apiClient.post(`/devices/import`, devices);
+    return response.data;
+  } catch (error) {
+    logger.error('Failed to import devices', { error, count: devices.length });
+    throw error;
+  }
+};
+
+/**
+ * Get a device's primary user history, most recent first
+ * @param id - The ID of the device
+ * @returns Promise<DeviceAssignment[]> - The device's assignment history
+ */
+export const getDeviceAssignmentHistory = async (id: number): Promise<DeviceAssignment[]> => {
+  try {
+    const response = await apiClient.get(`/devices/${id}/assignment-history`);
+    return response.data;
+  } catch (error) {
+    logger.error('Failed to fetch device assignment history', { error, deviceId: id });
+    throw error;
+  }
+};
+
 /**
  * Helper function to determine device type based on model
  * @param model - The device model
@@ -1,6 +1,7 @@
 import { logger } from '@/utils/logger';
 import apiClient from './apiConfig';
 import type { UserInfo } from '@/types/user';
+import type { Group } from '@/types/group';
 
 // Note: apiClient already has baseURL set to '/api', so routes need no prefix
 
@@ -913,6 +914,64 @@ export const getOrderedTopLevelPages = async (): Promise<Page[]> => {
   }
 };
 
+/**
+ * A page in the documentation table of contents
+ */
+export interface DocumentationTreeNode {
+  id: number;
+  title: string;
+  slug: string | null;
+  icon: string | null;
+  status: string;
+  display_order: number | null;
+  children: DocumentationTreeNode[];
+}
+
+/**
+ * Get the page tree the current user can see (archived pages excluded) for the sidebar
+ */
+export const getDocumentationTree = async (): Promise<DocumentationTreeNode[]> => {
+  try {
+    const response = await apiClient.get(`/documentation/tree`);
+    return response.data;
+  } catch (error) {
+    logger.error('Error fetching documentation tree:', error);
+    return [];
+  }
+};
+
+export interface DocumentationVisibility {
+  page_id: number;
+  /** Groups that can see the page; empty means visible to everyone */
+  visible_to_groups: Group[];
+}
+
+/**
+ * Get the groups a page is restricted to (admin only)
+ */
+export const getDocumentationVisibility = async (id: number): Promise<DocumentationVisibility> => {
+  try {
+    const response = await apiClient.get(`/documentation/pages/${id}/visibility`);
+    return response.data;
+  } catch (error) {
+    logger.error(`Error fetching visibility for page ${id}:`, error);
+    throw error;
+  }
+};
+
+/**
+ * Restrict a page to the given groups; an empty list makes it public (admin only)
+ */
+export const setDocumentationVisibility = async (id: number, groupIds: number[]): Promise<DocumentationVisibility> => {
+  try {
+    const response = await apiClient.put(`/documentation/pages/${id}/visibility`, { group_ids: groupIds });
+    return response.data;
+  } catch (error) {
+    logger.error(`Error setting visibility for page ${id}:`, error);
+    throw error;
+  }
+};
+
 /**
  * Get page with ordered children
  */
@@ -12,6 +12,15 @@ export interface NotificationPreference {
   enabled: boolean;
 }
 
+/** One notification type's channels, as returned by the bulk update */
+export interface NotificationPreferenceMatrixEntry {
+  notification_type: string;
+  notification_name?: string;
+  description?: string | null;
+  category?: string;
+  channels: Record<string, boolean>;
+}
+
 export interface NotificationPreferencesResponse {
   preferences: NotificationPreference[];
   notification_types: NotificationType[];
@@ -34,10 +43,13 @@ export interface Notification {
   entity_id: number;
   is_read: boolean;
   created_at: string;
+  count: number; // Notifications collapsed into this one
   metadata?: {
     ticket_id?: number;
+    path?: string; // Frontend route of the entity
     preview?: string;
     rule_name?: string;
+    reopen_url?: string; // Set when the ticket was closed automatically
     [key: string]: unknown;
   } | null;
 }
@@ -124,6 +136,27 @@ export async function updateNotificationPreference(
   });
 }
 
+/**
+ * Update many notification preferences at once; returns the full preference set
+ */
+export async function updateNotificationPreferencesBulk(
+  preferences: Pick<NotificationPreferenceMatrixEntry, 'notification_type' | 'channels'>[]
+): Promise<NotificationPreferenceMatrixEntry[]> {
+  const response = await apiClient.put<NotificationPreferenceMatrixEntry[]>(
+    '/notifications/preferences/bulk',
+    { preferences }
+  );
+  return response.data;
+}
+
+/**
+ * Set the language notifications are sent in (null resets to English)
+ */
+export async function updateNotificationLocale(locale: string | null): Promise<string | null> {
+  const response = await apiClient.put<{ locale: string | null }>('/notifications/locale', { locale });
+  return response.data.locale;
+}
+
 /**
  * Get user's notifications
  */
@@ -131,6 +164,7 @@ export async function getNotifications(params?: {
   limit?: number;
   offset?: number;
   unread_only?: boolean;
+  auto_read_on_fetch?: boolean;
 }): Promise<Notification[]> {
   const response = await apiClient.get<Notification[]>('/notifications', { params });
   return response.data;
@@ -168,6 +202,7 @@ export async function deleteNotifications(notificationIds: number[]): Promise<vo
 export default {
   getNotificationPreferences,
   updateNotificationPreference,
+  updateNotificationLocale,
   deleteNotifications,
   getNotifications,
   getUnreadCount,
@@ -4,6 +4,7 @@ import type {
   Plugin,
   PluginSetting,
   PluginStorage,
+  PluginStorageScope,
   InstallPluginRequest,
   UpdatePluginRequest,
   SetPluginSettingRequest,
@@ -191,9 +192,15 @@ const pluginService = {
   /**
    * Get a storage value for a plugin
    */
-  async getStorage(pluginUuid: string, key: string): Promise<PluginStorage> {
+  async getStorage(
+    pluginUuid: string,
+    key: string,
+    scope: PluginStorageScope = 'global'
+  ): Promise<PluginStorage> {
     try {
-      const response = await apiClient.get(`/plugins/${pluginUuid}/storage/${key}`);
+      const response = await apiClient.get(`/plugins/${pluginUuid}/storage/${key}`, {
+        params: { scope },
+      });
       return response.data;
     } catch (error) {
       logger.error('Failed to get plugin storage', { error, pluginUuid, key });
@@ -217,9 +224,13 @@ const pluginService = {
   /**
    * Delete a storage value for a plugin
    */
-  async deleteStorage(pluginUuid: string, key: string): Promise<void> {
+  async deleteStorage(
+    pluginUuid: string,
+    key: string,
+    scope: PluginStorageScope = 'global'
+  ): Promise<void> {
     try {
-      await apiClient.delete(`/plugins/${pluginUuid}/storage/${key}`);
+      await apiClient.delete(`/plugins/${pluginUuid}/storage/${key}`, { params: { scope } });
     } catch (error) {
       logger.error('Failed to delete plugin storage', { error, pluginUuid, key });
       throw error;
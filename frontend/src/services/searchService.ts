@@ -1,9 +1,14 @@
 import apiClient from './apiConfig';
 import type {
+  GroupedSearchParams,
+  GroupedSearchResponse,
   SearchParams,
   SearchResponse,
   IndexStats,
   RebuildResponse,
+  ReindexStatus,
+  SearchInsights,
+  SearchSuggestions,
 } from '@/types/search';
 
 /**
@@ -33,6 +38,48 @@ export const searchService = {
     return response.data;
   },
 
+  /**
+   * Execute a search with results grouped by entity type
+   * @param params Grouped search parameters
+   * @returns Groups with per-type counts and top results
+   */
+  async searchGrouped(params: GroupedSearchParams): Promise<GroupedSearchResponse> {
+    const queryParams = new URLSearchParams();
+    queryParams.set('q', params.q);
+
+    if (params.per_group !== undefined) {
+      queryParams.set('per_group', params.per_group.toString());
+    }
+
+    if (params.types) {
+      queryParams.set('types', params.types);
+    }
+
+    const response = await apiClient.get<GroupedSearchResponse>(
+      `/search/grouped?${queryParams.toString()}`
+    );
+    return response.data;
+  },
+
+  /**
+   * Get recent searches and completions for a partially typed query
+   * @param q Text typed so far
+   * @returns Search suggestions
+   */
+  async getSuggestions(q: string): Promise<SearchSuggestions> {
+    const response = await apiClient.get<SearchSuggestions>(
+      `/search/suggestions?q=${encodeURIComponent(q)}`
+    );
+    return response.data;
+  },
+
+  /**
+   * Clear the current user's recent searches
+   */
+  async clearRecentSearches(): Promise<void> {
+    await apiClient.delete('/search/recent');
+  },
+
   /**
    * Rebuild the search index (admin only)
    * @returns Rebuild statistics
@@ -42,6 +89,36 @@ export const searchService = {
     return response.data;
   },
 
+  /**
+   * Start rebuilding the search index in the background (admin only)
+   * @returns Initial reindex status
+   */
+  async startReindex(): Promise<ReindexStatus> {
+    const response = await apiClient.post<ReindexStatus>('/admin/search/reindex');
+    return response.data;
+  },
+
+  /**
+   * Get progress of the current or most recent reindex (admin only)
+   * @returns Reindex status
+   */
+  async getReindexStatus(): Promise<ReindexStatus> {
+    const response = await apiClient.get<ReindexStatus>('/admin/search/reindex/status');
+    return response.data;
+  },
+
+  /**
+   * Get search activity and the most frequent zero-result queries (admin only)
+   * @param limit Zero-result queries to return
+   * @returns Search insights
+   */
+  async getInsights(limit?: number): Promise<SearchInsights> {
+    const response = await apiClient.get<SearchInsights>('/admin/search/insights', {
+      params: limit ? { limit } : undefined,
+    });
+    return response.data;
+  },
+
   /**
    * Get search index statistics (admin only)
    * @returns Index statistics
@@ -43,10 +43,14 @@ export type SSEEventType =
   | "project-unassigned"
   | "documentation-created"
   | "documentation-updated"
+  | "editor-joined"
+  | "editor-left"
+  | "editor-cursor-moved"
   | "user-updated"
   | "user-created"
   | "user-deleted"
   | "notification-received"
+  | "notifications-read"
   | "heartbeat"
   | "reconnect";
 
@@ -165,10 +169,14 @@ class SSEService {
       "project-unassigned",
       "documentation-created",
       "documentation-updated",
+      "editor-joined",
+      "editor-left",
+      "editor-cursor-moved",
       "user-updated",
       "user-created",
       "user-deleted",
       "notification-received",
+      "notifications-read",
       "heartbeat",
       "reconnect",
     ];
@@ -1,7 +1,7 @@
 import apiClient from './apiConfig';
 import { logger } from '@/utils/logger';
 import { RequestManager } from '@/utils/requestManager';
-import type { Ticket, Comment, Attachment, Device, Project } from '@/types/ticket';
+import type { Ticket, Comment, Attachment, Device, Project, TicketWorklog, MyWork } from '@/types/ticket';
 import type { UserInfo } from '@/types/user';
 import type { PaginatedResponse } from '@/types/pagination';
 import type { CommentWithAttachments } from '@/types/comment';
@@ -71,6 +71,23 @@ export const getPaginatedTickets = async (params: TicketPaginationParams, reques
   }
 };
 
+// Download every ticket matching the list filters (pagination is ignored)
+export const exportTickets = async (
+  params: Omit<TicketPaginationParams, 'page' | 'pageSize' | 'sortField' | 'sortDirection'>,
+  format: 'csv' | 'json' | 'pdf' = 'csv'
+): Promise<Blob> => {
+  try {
+    const response = await apiClient.get('/tickets/export', {
+      params: { ...params, format },
+      responseType: 'blob'
+    });
+    return response.data;
+  } catch (error) {
+    logger.error('Failed to export tickets', { error, params, format });
+    throw error;
+  }
+};
+
 export const getTicketById = async (id: number): Promise<Ticket> => {
   try {
     const response = await apiClient.get(`/tickets/${id}`);
@@ -143,17 +160,113 @@ export const unlinkTicket = async (ticketId: number, linkedTicketId: number): Pr
   }
 };
 
+// Merge a duplicate ticket into another; returns the updated target ticket
+export const mergeTicket = async (sourceId: number, targetId: number): Promise<Ticket> => {
+  try {
+    const response = await apiClient.post(`/tickets/${sourceId}/merge`, { target_id: targetId });
+    return response.data;
+  } catch (error) {
+    logger.error('Failed to merge tickets', { error, sourceId, targetId });
+    throw error;
+  }
+};
+
+// Reopen a closed ticket; returns the reopened ticket
+export const reopenTicket = async (id: number): Promise<Ticket> => {
+  try {
+    const response = await apiClient.post(`/tickets/${id}/reopen`);
+    return response.data;
+  } catch (error) {
+    logger.error('Failed to reopen ticket', { error, id });
+    throw error;
+  }
+};
+
+// Users watching a ticket
+export const getTicketWatchers = async (
+  ticketId: number
+): Promise<Pick<UserInfo, 'uuid' | 'name' | 'avatar_url' | 'avatar_thumb'>[]> => {
+  try {
+    const response = await apiClient.get(`/tickets/${ticketId}/watchers`);
+    return response.data;
+  } catch (error) {
+    logger.error('Failed to get ticket watchers', { error, ticketId });
+    throw error;
+  }
+};
+
+// Watch a ticket; technicians can pass another user's UUID
+export const watchTicket = async (ticketId: number, userUuid?: string): Promise<void> => {
+  try {
+    await apiClient.post(`/tickets/${ticketId}/watchers`, { user_uuid: userUuid ?? null });
+  } catch (error) {
+    logger.error('Failed to watch ticket', { error, ticketId, userUuid });
+    throw error;
+  }
+};
+
+// Stop a user watching a ticket
+export const unwatchTicket = async (ticketId: number, userUuid: string): Promise<void> => {
+  try {
+    await apiClient.delete(`/tickets/${ticketId}/watchers/${userUuid}`);
+  } catch (error) {
+    logger.error('Failed to unwatch ticket', { error, ticketId, userUuid });
+    throw error;
+  }
+};
+
+// Time logged against a ticket (technicians only)
+export const getTicketWorklogs = async (
+  ticketId: number
+): Promise<{ worklogs: TicketWorklog[]; total_time_spent: number }> => {
+  try {
+    const response = await apiClient.get(`/tickets/${ticketId}/worklogs`);
+    return response.data;
+  } catch (error) {
+    logger.error('Failed to get ticket worklogs', { error, ticketId });
+    throw error;
+  }
+};
+
+// Log time spent on a ticket
+export const addTicketWorklog = async (
+  ticketId: number,
+  entry: { minutes: number; note?: string; logged_at?: string }
+): Promise<TicketWorklog> => {
+  try {
+    const response = await apiClient.post(`/tickets/${ticketId}/worklogs`, entry);
+    return response.data;
+  } catch (error) {
+    logger.error('Failed to add ticket worklog', { error, ticketId });
+    throw error;
+  }
+};
+
+// Delete a worklog entry
+export const deleteTicketWorklog = async (ticketId: number, worklogId: number): Promise<void> => {
+  try {
+    await apiClient.delete(`/tickets/${ticketId}/worklogs/${worklogId}`);
+  } catch (error) {
+    logger.error('Failed to delete ticket worklog', { error, ticketId, worklogId });
+    throw error;
+  }
+};
+
 // Add a comment to a ticket
 export const addCommentToTicket = async (
   ticketId: number,
   content: string,
-  attachments: { url: string; name: string }[] = []
+  attachments: { url: string; name: string }[] = [],
+  isInternal = false,
+  watch = true
 ): Promise<Comment> => {
   try {
     const response = await apiClient.post(`/tickets/${ticketId}/comments`, {
       content,
       // user information is extracted from JWT token on backend for security
-      attachments
+      attachments,
+      is_internal: isInternal,
+      watch
     });
     return response.data;
   } catch (error) {
@@ -238,12 +351,24 @@ export const getRecentTickets = async () => {
   return response.data;
 };
 
+// Dashboard summary of the authenticated user's own work
+export const getMyWork = async (): Promise<MyWork> => {
+  const response = await apiClient.get('/tickets/my-work');
+  return response.data;
+};
+
 // Record a ticket view
 export const recordTicketView = async (ticketId: number) => {
   const response = await apiClient.post(`/tickets/${ticketId}/view`);
   return response.data;
 };
 
+// Suggest existing tickets that may duplicate a draft
+export const suggestDuplicates = async (title: string, description?: string) => {
+  const response = await apiClient.post('/tickets/suggest-duplicates', { title, description });
+  return response.data;
+};
+
 // Bulk operations
 export interface BulkActionRequest {
   action: 'delete' | 'set-status' | 'set-priority' | 'assign';
@@ -251,7 +376,15 @@ export interface BulkActionRequest {
   value?: string;
 }
 
-export const bulkAction = async (request: BulkActionRequest): Promise<{ affected: number }> => {
+export interface BulkItemResult {
+  id: number;
+  status: 'updated' | 'skipped';
+  reason?: 'not_found' | 'forbidden';
+}
+
+export const bulkAction = async (
+  request: BulkActionRequest
+): Promise<{ affected: number; results?: BulkItemResult[] }> => {
   const response = await apiClient.post('/tickets/bulk', request);
   return response.data;
 };
@@ -260,6 +393,7 @@ export const bulkAction = async (request: BulkActionRequest): Promise<{ affected
 export default {
   getTickets,
   getPaginatedTickets,
+  exportTickets,
   getTicketById,
   createTicket,
   updateTicket,
@@ -267,6 +401,9 @@ export default {
   createEmptyTicket,
   linkTicket,
   unlinkTicket,
+  getTicketWatchers,
+  watchTicket,
+  unwatchTicket,
   addCommentToTicket,
   addAttachmentToComment,
   deleteComment,
@@ -277,5 +414,6 @@ export default {
   getRecentTickets,
   recordTicketView,
   bulkAction,
+  suggestDuplicates,
   cancelAllRequests
 }; 
\ No newline at end of file
@@ -32,10 +32,14 @@ export interface UserEmail {
   email: string;
   email_type: string;
   is_primary: boolean;
-  verified: boolean;
+  is_verified: boolean;
   source?: string | null;
   created_at: string;
   updated_at: string;
+  // Set when the email provider reports a hard bounce or spam complaint;
+  // notification email to the address is skipped until cleared
+  bounced_at?: string | null;
+  complained_at?: string | null;
 }
 
 // Request cancellation manager instance
@@ -145,6 +149,38 @@ const userService = {
     }
   },
 
+  // Send (or resend) the verification link for an unverified email
+  async resendEmailVerification(uuid: string, emailId: number): Promise<void> {
+    try {
+      await apiClient.post(`/users/${uuid}/emails/${emailId}/resend-verification`);
+    } catch (error) {
+      logger.error('Failed to send email verification link', { error, uuid, emailId });
+      throw error;
+    }
+  },
+
+  // Clear bounce/complaint flags so notification email resumes (admin only)
+  async clearEmailDeliveryFlags(uuid: string, emailId: number): Promise<UserEmail | null> {
+    try {
+      const response = await apiClient.post(`/users/${uuid}/emails/${emailId}/clear-delivery-flags`);
+      return response.data.email || null;
+    } catch (error) {
+      logger.error('Failed to clear email delivery flags', { error, uuid, emailId });
+      throw error;
+    }
+  },
+
+  // Unlock an account locked by failed password logins (admin only)
+  async unlockAccount(uuid: string): Promise<boolean> {
+    try {
+      const response = await apiClient.post(`/users/${uuid}/unlock`);
+      return Boolean(response.data.was_locked);
+    } catch (error) {
+      logger.error('Failed to unlock account', { error, uuid });
+      throw error;
+    }
+  },
+
   // Delete an email address
   async deleteUserEmail(uuid: string, emailId: number): Promise<void> {
     try {
@@ -18,6 +18,20 @@ export interface ArticleRevisionDetail extends ArticleRevision {
 export interface RestoreRevisionResponse {
   success: boolean;
   message: string;
+  /** Revision recording the restore (documentation pages only) */
+  revision_number?: number;
+}
+
+export interface RevisionDiffLine {
+  op: 'equal' | 'insert' | 'delete';
+  text: string;
+}
+
+export interface RevisionDiff {
+  page_id: number;
+  revision_number: number;
+  against: number | null;
+  lines: RevisionDiffLine[];
 }
 
 // Version history service
@@ -53,6 +67,31 @@ export const versionHistoryService = {
     const response = await apiClient.post(`/collaboration/tickets/${ticketId}/restore/${revisionNumber}`);
     return response.data;
   },
+
+  /**
+   * Diff a documentation page revision against another revision
+   * @param docId - The documentation page ID
+   * @param revisionNumber - The revision to diff from
+   * @param against - Revision to diff to (defaults to the current content)
+   * @returns Block-level text diff
+   */
+  async getDocRevisionDiff(docId: number, revisionNumber: number, against?: number): Promise<RevisionDiff> {
+    const response = await apiClient.get(`/collaboration/docs/${docId}/revisions/${revisionNumber}/diff`, {
+      params: against !== undefined ? { against } : undefined,
+    });
+    return response.data;
+  },
+
+  /**
+   * Restore a documentation page to a specific revision
+   * @param docId - The documentation page ID
+   * @param revisionNumber - The revision number to restore
+   * @returns Success response with the revision recording the restore
+   */
+  async restoreDocRevision(docId: number, revisionNumber: number): Promise<RestoreRevisionResponse> {
+    const response = await apiClient.post(`/collaboration/docs/${docId}/restore/${revisionNumber}`);
+    return response.data;
+  },
 };
 
 export default versionHistoryService;
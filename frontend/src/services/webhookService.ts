@@ -7,6 +7,7 @@ import type {
   UpdateWebhookRequest,
   WebhookDelivery,
 } from '@/types/webhook';
+import type { PaginatedResponse } from '@/types/pagination';
 
 /**
  * Webhook Service
@@ -94,12 +95,16 @@ const webhookService = {
   /**
    * Get delivery history for a webhook
    */
-  async getDeliveries(uuid: string, limit = 50, offset = 0): Promise<WebhookDelivery[]> {
+  async getDeliveries(
+    uuid: string,
+    limit = 50,
+    offset = 0,
+  ): Promise<PaginatedResponse<WebhookDelivery>> {
     try {
       const response = await apiClient.get(`/admin/webhooks/${uuid}/deliveries`, {
         params: { limit, offset },
       });
-      return response.data || [];
+      return response.data;
     } catch (error) {
       logger.error('Failed to get webhook deliveries', { error, uuid });
       throw error;
@@ -117,6 +122,19 @@ const webhookService = {
       throw error;
     }
   },
+
+  /**
+   * Reveal a webhook's signing secret (audited)
+   */
+  async revealSecret(uuid: string): Promise<string> {
+    try {
+      const response = await apiClient.post(`/admin/webhooks/${uuid}/secret/reveal`);
+      return response.data.secret;
+    } catch (error) {
+      logger.error('Failed to reveal webhook secret', { error, uuid });
+      throw error;
+    }
+  },
 };
 
 export default webhookService;
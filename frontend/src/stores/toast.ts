@@ -21,7 +21,7 @@ export interface Toast {
   notification?: {
     entityType: string;
     entityId: number;
-    ticketId: number;
+    ticketId: number | null;
     actorName?: string;
     actorAvatar?: string;
   };
@@ -138,7 +138,7 @@ export const useToastStore = defineStore('toast', () => {
     message: string | undefined,
     entityType: string,
     entityId: number,
-    ticketId: number,
+    ticketId: number | null,
     actorName?: string,
     actorAvatar?: string
   ): string {
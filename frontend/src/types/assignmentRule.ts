@@ -21,6 +21,7 @@ export interface AssignmentRule {
   target_group_id: number | null
   trigger_on_create: boolean
   trigger_on_category_change: boolean
+  trigger_on_sla_breach: boolean
   category_id: number | null
   conditions: Record<string, unknown> | null
   created_at: string
@@ -56,6 +57,7 @@ export interface CreateAssignmentRuleRequest {
   target_group_id?: number
   trigger_on_create?: boolean
   trigger_on_category_change?: boolean
+  trigger_on_sla_breach?: boolean
   category_id?: number
   conditions?: Record<string, unknown>
 }
@@ -71,6 +73,7 @@ export interface UpdateAssignmentRuleRequest {
   target_group_id?: number | null
   trigger_on_create?: boolean
   trigger_on_category_change?: boolean
+  trigger_on_sla_breach?: boolean
   category_id?: number | null
   conditions?: Record<string, unknown>
 }
@@ -0,0 +1,26 @@
+/**
+ * Canned Response Type Definitions
+ * Reusable reply templates with {{placeholder}} variables
+ */
+
+export interface CannedResponse {
+  id: number
+  title: string
+  body: string
+  category_id?: number | null
+  is_shared: boolean
+  created_by?: string | null
+  created_at: string
+  updated_at: string
+}
+
+export interface CannedResponseRequest {
+  title: string
+  body: string
+  category_id?: number | null
+  is_shared?: boolean
+}
+
+export interface AppliedCannedResponse {
+  content: string
+}
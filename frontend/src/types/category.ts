@@ -5,6 +5,16 @@
 
 import type { Group } from './group'
 
+/**
+ * A field tickets in a category must have: a linked device, or a text
+ * value under `key` in the ticket's custom_fields
+ */
+export interface RequiredField {
+  key: string
+  label: string
+  kind: 'text' | 'device'
+}
+
 export interface TicketCategory {
   id: number
   uuid: string
@@ -17,6 +27,9 @@ export interface TicketCategory {
   created_at: string
   updated_at: string
   created_by?: string | null
+  default_assignee_uuid?: string | null
+  default_group_id?: number | null
+  required_fields: RequiredField[]
 }
 
 export interface CategoryWithVisibility extends TicketCategory {
@@ -30,6 +43,9 @@ export interface CreateCategoryRequest {
   color?: string
   icon?: string
   visible_to_group_ids?: number[]
+  default_assignee_uuid?: string | null
+  default_group_id?: number | null
+  required_fields?: RequiredField[]
 }
 
 export interface UpdateCategoryRequest {
@@ -39,6 +55,9 @@ export interface UpdateCategoryRequest {
   icon?: string
   is_active?: boolean
   visible_to_group_ids?: number[]
+  default_assignee_uuid?: string | null
+  default_group_id?: number | null
+  required_fields?: RequiredField[]
 }
 
 export interface CategoryOrder {
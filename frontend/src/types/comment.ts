@@ -20,6 +20,8 @@ export interface Comment {
   user_uuid: string
   created_at: string
   ticket_id: number
+  /** Only visible to technicians and admins */
+  is_internal?: boolean
   attachments?: Attachment[]
   user?: UserInfo
 }
@@ -0,0 +1,30 @@
+/**
+ * Custom Field Type Definitions
+ * Admin-defined ticket fields, optionally limited to one category
+ */
+
+export type CustomFieldType = 'text' | 'number' | 'date' | 'select' | 'checkbox'
+
+export interface CustomFieldDefinition {
+  id: number
+  key: string
+  label: string
+  field_type: CustomFieldType
+  /** Choices of a select field */
+  options: string[]
+  /** Every category when null */
+  category_id: number | null
+  display_order: number
+  created_at: string
+  updated_at: string
+  created_by?: string | null
+}
+
+/** A ticket's value for a custom field, in canonical form (dates as YYYY-MM-DD) */
+export interface CustomFieldValue {
+  field_id: number
+  key: string
+  label: string
+  field_type: CustomFieldType
+  value: string
+}
@@ -43,6 +43,24 @@ export interface Device {
   assignedTo?: string | null;
 }
 
+export interface DeviceAssignmentUser {
+  uuid: string;
+  name: string;
+  email: string;
+  role: string;
+  avatar_url?: string | null;
+  avatar_thumb?: string | null;
+}
+
+/** One entry in a device's primary user history (unassigned_at is null for the current user) */
+export interface DeviceAssignment {
+  id: number;
+  user: DeviceAssignmentUser | null;
+  assigned_by: DeviceAssignmentUser | null;
+  assigned_at: string;
+  unassigned_at: string | null;
+}
+
 export interface DeviceFormData {
   name: string;
   hostname: string;
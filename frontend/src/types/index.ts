@@ -10,3 +10,5 @@ export * from './category';
 export * from './microsoft-graph';
 export * from './webhook';
 export * from './plugin';
+export * from './cannedResponse';
+export * from './customField';
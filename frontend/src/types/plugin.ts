@@ -20,6 +20,17 @@ export interface PluginManifest {
   components: Record<string, PluginComponentConfig>;
   events: string[];
   settings: PluginSettingDefinition[];
+  event_subscriptions?: string[]; // Server-side events delivered to event_handler (e.g. 'ticket.created')
+  event_handler?: string; // HTTPS endpoint, signed with the plugin's event_secret setting
+  limits?: PluginLimits;
+}
+
+// Limits on proxied requests (server defaults apply when unset)
+export interface PluginLimits {
+  requests_per_minute?: number;
+  timeout_secs?: number;
+  max_response_bytes?: number;
+  max_redirects?: number;
 }
 
 export interface PluginComponentConfig {
@@ -56,6 +67,7 @@ export interface Plugin {
   manifest: PluginManifest;
   enabled: boolean;
   trust_level: PluginTrustLevel;
+  verified_publisher: string | null; // Publisher whose signature verified an official plugin
   source: PluginSource;
   installed_by: string | null;
   installed_at: string;
@@ -107,7 +119,13 @@ export interface UpdatePluginRequest {
 
 // Use SetPluginDataRequest for both settings and storage (consolidated backend)
 export type SetPluginSettingRequest = SetPluginDataRequest;
-export type SetPluginStorageRequest = SetPluginDataRequest;
+
+// 'global' storage is shared by all users, 'user' is private to the current user
+export type PluginStorageScope = 'global' | 'user';
+
+export interface SetPluginStorageRequest extends SetPluginDataRequest {
+  scope?: PluginStorageScope;
+}
 
 export interface PluginProxyRequest {
   url: string;
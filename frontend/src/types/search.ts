@@ -29,6 +29,8 @@ export interface SearchResult {
 export interface SearchResponse {
   results: SearchResult[];
   total: number;
+  /** Limit applied; lower than requested when it was over the server maximum */
+  limit: number;
   query: string;
   took_ms: number;
 }
@@ -42,6 +44,34 @@ export interface SearchParams {
   types?: string;
 }
 
+/**
+ * Results of one entity type in a grouped search
+ */
+export interface SearchGroup {
+  entity_type: SearchEntityType;
+  count: number; // Visible matches of this type
+  results: SearchResult[]; // Best matches, capped at per_group
+}
+
+/**
+ * Grouped search API response (groups ordered by best match)
+ */
+export interface GroupedSearchResponse {
+  groups: SearchGroup[];
+  total: number;
+  query: string;
+  took_ms: number;
+}
+
+/**
+ * Grouped search query parameters
+ */
+export interface GroupedSearchParams {
+  q: string;
+  per_group?: number;
+  types?: string;
+}
+
 /**
  * Index statistics (admin only)
  */
@@ -69,6 +99,55 @@ export interface RebuildResponse {
   };
 }
 
+/**
+ * Search suggestions for a partially typed query
+ */
+export interface SearchSuggestions {
+  recent: string[]; // User's recent searches matching the query, newest first
+  completions: string[]; // Query with the last word completed from indexed terms
+}
+
+/**
+ * Background reindex progress
+ */
+export interface ReindexStatus {
+  state: 'idle' | 'running' | 'completed' | 'failed';
+  current_entity: string | null; // Entity type being indexed, e.g. 'tickets'
+  processed: number; // Records of current_entity processed so far
+  total: number; // Records of current_entity to process
+  indexed: {
+    tickets: number;
+    comments: number;
+    documentation: number;
+    attachments: number;
+    devices: number;
+    users: number;
+  };
+  started_at: string | null;
+  finished_at: string | null;
+  error: string | null;
+}
+
+/**
+ * A search that found nothing, and how often it was made
+ */
+export interface ZeroResultQuery {
+  query: string; // Normalized query, or a hash of it when queries are hashed
+  count: number;
+  last_seen: string;
+}
+
+/**
+ * Search activity since the server started
+ */
+export interface SearchInsights {
+  query_text: 'plain' | 'hashed' | 'off';
+  slow_threshold_ms: number;
+  searches: number;
+  slow_searches: number;
+  zero_result_queries: ZeroResultQuery[]; // Most searched first
+}
+
 /**
  * Results grouped by entity type for display
  */
@@ -105,6 +105,19 @@ export interface ViewerCountEventData {
   count: number
 }
 
+/**
+ * editor-joined / editor-left / editor-cursor-moved event data.
+ * document_id is the collaboration document ("ticket-N" or "doc-N").
+ */
+export interface EditorPresenceEventData {
+  document_id: string
+  user_uuid: string
+  /** editor-joined only */
+  name?: string
+  /** editor-cursor-moved only: cursor from the editor's awareness state */
+  cursor?: unknown
+}
+
 /**
  * Actor who triggered a notification
  */
@@ -126,13 +139,25 @@ export interface NotificationReceivedEventData {
     body?: string
     entity_type: string
     entity_id: number
-    ticket_id: number
+    ticket_id: number | null
+    path: string // Frontend route of the entity
+    count: number // > 1 when collapsed into an existing unread notification
+    locale: string // Recipient's notification locale
     actor: NotificationActor
     metadata?: Record<string, unknown>
     timestamp: string
   }
 }
 
+/**
+ * notifications-read event data
+ */
+export interface NotificationsReadEventData {
+  recipient_uuid: string
+  notification_ids: number[] | null // null when all notifications were marked read
+  unread_count: number
+}
+
 /**
  * Union type of all SSE event data types (for generic handling)
  */
@@ -145,7 +170,9 @@ export type SSEEventData =
   | TicketLinkEventData
   | ProjectEventData
   | ViewerCountEventData
+  | EditorPresenceEventData
   | NotificationReceivedEventData
+  | NotificationsReadEventData
 
 /**
  * SSE event handler function type
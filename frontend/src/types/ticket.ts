@@ -8,10 +8,25 @@ import type { Device } from './device'
 import type { Comment, Attachment } from './comment'
 import type { Project } from './project'
 import type { UserInfo } from './user'
+import type { CustomFieldValue } from './customField'
 
 // Re-export for convenience
 export type { Device, Comment, Attachment, Project }
 
+export type SlaState = 'on_track' | 'met' | 'breached'
+
+export interface SlaMetricStatus {
+  state: SlaState
+  target_minutes: number
+  due_at: string
+  completed_at: string | null
+}
+
+export interface TicketSla {
+  first_response: SlaMetricStatus | null
+  resolution: SlaMetricStatus | null
+}
+
 export interface Ticket {
   id: number
   title: string
@@ -25,10 +40,54 @@ export interface Ticket {
   assignee_user?: UserInfo | null
   category_id?: number | null
   closed_at?: string
+  first_response_at?: string | null
+  /** Incremented on every update; send it back to detect concurrent edits */
+  version?: number
+  /** Set when this ticket was merged into another ticket */
+  merged_into_id?: number | null
   devices?: Device[]
   comments?: Comment[]
   article_content?: string
   linkedTickets?: number[]
   linked_tickets?: number[]
   projects?: Project[]
+  /** Absent when the ticket's priority has no SLA targets */
+  sla?: TicketSla | null
+  /** Minutes logged against the ticket in worklogs */
+  total_time_spent?: number
+  custom_fields?: CustomFieldValue[]
+}
+
+/** Time a technician logged against a ticket */
+export interface TicketWorklog {
+  id: number
+  ticket_id: number
+  user_uuid: string
+  minutes: number
+  note: string | null
+  /** When the work was done */
+  logged_at: string
+  created_at: string
+  user?: Pick<UserInfo, 'uuid' | 'name'>
+}
+
+/** Dashboard summary of the current user's own queue */
+export interface MyWork {
+  open_assigned: number
+  /** SLA deadlines due within the next few hours, soonest first */
+  sla_due_soon: {
+    ticket_id: number
+    title: string
+    priority: TicketPriority
+    metric: 'first_response' | 'resolution'
+    due_at: string
+  }[]
+  unread_notifications: number
+  watched_tickets: {
+    id: number
+    title: string
+    status: TicketStatus
+    priority: TicketPriority
+    updated_at: string
+  }[]
 }
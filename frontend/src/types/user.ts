@@ -15,8 +15,11 @@ export interface User {
   banner_url?: string | null;
   avatar_thumb?: string | null;
   theme?: string | null;
+  locale?: string | null; // Notification language; null uses English
   created_at: string;
   updated_at: string;
+  deactivated_at?: string | null; // Set for deactivated accounts kept for history
+  timezone?: string | null; // IANA zone for date filters; server default when unset
 }
 
 /**
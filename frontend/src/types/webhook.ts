@@ -16,8 +16,13 @@ export interface Webhook {
   last_triggered_at: string | null;
   failure_count: number;
   disabled_reason: string | null;
+  circuit_state: WebhookCircuitState;
+  circuit_retry_at: string | null;
 }
 
+/** Circuit breaker state; an open circuit skips deliveries until it half-opens */
+export type WebhookCircuitState = 'closed' | 'open' | 'half_open';
+
 export interface WebhookCreated {
   uuid: string;
   name: string;
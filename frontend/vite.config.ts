@@ -6,6 +6,11 @@ import vue from "@vitejs/plugin-vue";
 // https://vite.dev/config/
 export default defineConfig({
   plugins: [vue()],
+  // Tag built <script>/<style> tags with a placeholder the backend swaps for the
+  // per-response CSP nonce when serving index.html
+  html: {
+    cspNonce: "__CSP_NONCE__",
+  },
   resolve: {
     alias: {
       "@": fileURLToPath(new URL("./src", import.meta.url)),